@@ -0,0 +1,123 @@
+//! Criterion benchmarks for the expensive code paths.
+//!
+//! Run with `cargo bench`. The workloads live in `thatch::utils::bench` so
+//! `thatch --bench-world` can time the same work in a release build.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use thatch::{
+    bench_astar, bench_crowd, bench_crowd_astar, bench_crowd_dijkstra, bench_crowd_game,
+    bench_dijkstra, bench_fov_game, bench_fov_step, bench_generate_floor, bench_generate_world,
+    BENCH_SEEDS, CROWD_SIZES, CROWD_TURN_SIZE,
+};
+
+fn world_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_generation");
+    group.sample_size(10);
+    for seed in BENCH_SEEDS {
+        group.bench_with_input(BenchmarkId::from_parameter(seed), &seed, |b, &seed| {
+            b.iter(|| bench_generate_world(black_box(seed)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn floor_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("progressive_wall_placement");
+    group.sample_size(20);
+    for seed in BENCH_SEEDS {
+        group.bench_with_input(BenchmarkId::from_parameter(seed), &seed, |b, &seed| {
+            b.iter(|| bench_generate_floor(black_box(seed)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn pathfinding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pathfinding");
+    for seed in BENCH_SEEDS {
+        let level = bench_generate_floor(seed).unwrap();
+        group.bench_with_input(BenchmarkId::new("astar", seed), &level, |b, level| {
+            b.iter(|| bench_astar(black_box(level)))
+        });
+        group.bench_with_input(BenchmarkId::new("dijkstra", seed), &level, |b, level| {
+            b.iter(|| bench_dijkstra(black_box(level)))
+        });
+    }
+    group.finish();
+}
+
+fn field_of_view(c: &mut Criterion) {
+    let mut group = c.benchmark_group("field_of_view");
+    for seed in BENCH_SEEDS {
+        let mut game_state = bench_fov_game(seed).unwrap();
+        let level = game_state.world.current_level().unwrap();
+        let (spawn, step) = (level.player_spawn, bench_fov_step(level));
+        group.bench_function(BenchmarkId::new("step_and_back", seed), |b| {
+            b.iter(|| {
+                game_state
+                    .update_player_visibility(black_box(step))
+                    .unwrap();
+                game_state
+                    .update_player_visibility(black_box(spawn))
+                    .unwrap();
+            })
+        });
+        group.bench_function(BenchmarkId::new("wait", seed), |b| {
+            b.iter(|| {
+                game_state
+                    .update_player_visibility(black_box(spawn))
+                    .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("full_recompute", seed), |b| {
+            b.iter(|| {
+                game_state.field_of_view = None;
+                game_state
+                    .update_player_visibility(black_box(spawn))
+                    .unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn crowd_movement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("crowd_movement");
+    let level = bench_generate_floor(BENCH_SEEDS[0]).unwrap();
+    for size in CROWD_SIZES {
+        let crowd = bench_crowd(&level, size);
+        group.bench_with_input(
+            BenchmarkId::new("dijkstra_map", size),
+            &crowd,
+            |b, crowd| b.iter(|| bench_crowd_dijkstra(black_box(&level), crowd)),
+        );
+        group.bench_with_input(BenchmarkId::new("astar_each", size), &crowd, |b, crowd| {
+            b.iter(|| bench_crowd_astar(black_box(&level), crowd))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("crowd_turn");
+    group.sample_size(20);
+    for seed in BENCH_SEEDS {
+        let game_state = bench_crowd_game(seed, CROWD_TURN_SIZE).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(seed), |b| {
+            b.iter_batched(
+                || game_state.clone(),
+                |mut game_state| game_state.advance_turn().unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    world_generation,
+    floor_generation,
+    pathfinding,
+    field_of_view,
+    crowd_movement
+);
+criterion_main!(benches);
@@ -0,0 +1,236 @@
+//! # Accessibility
+//!
+//! Plain-text descriptions of the player's surroundings for screen readers.
+//!
+//! With narration turned on, the game describes what lies next to the player
+//! and what is in sight after every turn, in short phrases such as "Wall
+//! north, open corridor east, goblin two tiles south." The text goes to the
+//! message log, and can also be written to standard output where a screen
+//! reader or terminal can pick it up.
+
+use crate::{GameState, Position, TileType};
+use serde::{Deserialize, Serialize};
+
+/// Farthest an entity can be, in steps, and still be narrated.
+pub const NARRATION_RADIUS: i32 = 8;
+
+/// Where turn-by-turn descriptions of the surroundings are sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NarrationMode {
+    /// No descriptions
+    #[default]
+    Off,
+    /// Descriptions go to the message log
+    MessageLog,
+    /// Descriptions go to the message log and standard output
+    MessageLogAndStdout,
+}
+
+impl NarrationMode {
+    /// Every mode, in the order the options step through them
+    pub const ALL: [NarrationMode; 3] = [
+        NarrationMode::Off,
+        NarrationMode::MessageLog,
+        NarrationMode::MessageLogAndStdout,
+    ];
+
+    /// Returns a short human-readable name for the mode.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            NarrationMode::Off => "Off",
+            NarrationMode::MessageLog => "Message log",
+            NarrationMode::MessageLogAndStdout => "Message log and stdout",
+        }
+    }
+
+    /// Returns true if descriptions are made at all.
+    pub fn is_enabled(self) -> bool {
+        self != NarrationMode::Off
+    }
+
+    /// Returns true if descriptions are also written to standard output.
+    pub fn to_stdout(self) -> bool {
+        self == NarrationMode::MessageLogAndStdout
+    }
+}
+
+/// Describes the tiles next to the player and the entities in sight.
+///
+/// Neighbouring tiles are listed north, east, south and west, then visible
+/// entities on the player's level within [`NARRATION_RADIUS`] steps, nearest
+/// first. Returns None if there
+/// is no player.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{describe_surroundings, GameState, Position, Tile};
+///
+/// let mut game_state = GameState::new(12345);
+/// let level = game_state.world.current_level_mut().unwrap();
+/// for (x, y, mut tile) in [(5, 4, Tile::wall()), (5, 5, Tile::floor()), (6, 5, Tile::floor())] {
+///     tile.visible = true;
+///     level.set_tile(Position::new(x, y), tile).unwrap();
+/// }
+/// game_state
+///     .initialize_player("Listener".to_string(), Position::new(5, 5))
+///     .unwrap();
+///
+/// let text = describe_surroundings(&game_state).unwrap();
+/// assert!(text.starts_with("Wall north, open corridor east"));
+/// ```
+pub fn describe_surroundings(game_state: &GameState) -> Option<String> {
+    let player_id = game_state.player_id?;
+    let origin = game_state.get_entity_position(player_id)?;
+    let level = game_state.world.current_level()?;
+
+    let mut phrases = Vec::new();
+    for (name, delta) in [
+        ("north", Position::new(0, -1)),
+        ("east", Position::new(1, 0)),
+        ("south", Position::new(0, 1)),
+        ("west", Position::new(-1, 0)),
+    ] {
+        let position = Position::new(origin.x + delta.x, origin.y + delta.y);
+        let what = match level.get_tile(position) {
+            Some(tile) if !tile.is_explored() && !tile.is_visible() => "darkness".to_string(),
+            Some(tile) if tile.tile_type == TileType::Floor => {
+                let in_room = level.rooms.iter().any(|room| room.contains(position));
+                if in_room {
+                    "open floor"
+                } else {
+                    "open corridor"
+                }
+                .to_string()
+            }
+            Some(tile) => tile.tile_type.display_name().to_lowercase(),
+            None => "wall".to_string(),
+        };
+        phrases.push(format!("{} {}", what, name));
+    }
+
+    let mut in_sight: Vec<(i32, String)> = level
+        .entities
+        .iter()
+        .filter(|&&entity_id| Some(entity_id) != game_state.player_id)
+        .filter_map(|entity_id| {
+            let entity = game_state.entities.get(entity_id)?.as_entity();
+            let position = entity.position();
+            let (dx, dy) = (position.x - origin.x, position.y - origin.y);
+            // Movement is cardinal, so distance is counted in steps along the axes
+            let distance = origin.manhattan_distance(position) as i32;
+            let visible = level
+                .get_tile(position)
+                .is_some_and(|tile| tile.is_visible());
+            if !visible || distance > NARRATION_RADIUS {
+                return None;
+            }
+            let phrase = format!(
+                "{} {} {}",
+                entity.name().to_lowercase(),
+                tiles_away(distance),
+                compass_direction(dx, dy)
+            );
+            Some((distance, phrase))
+        })
+        .collect();
+    in_sight.sort();
+    phrases.extend(in_sight.into_iter().map(|(_, phrase)| phrase));
+
+    let mut text = phrases.join(", ");
+    if let Some(first) = text.get(..1) {
+        text.replace_range(..1, &first.to_uppercase());
+    }
+    text.push('.');
+    Some(text)
+}
+
+/// Says how many tiles away something is, in words for short distances.
+fn tiles_away(distance: i32) -> String {
+    const WORDS: [&str; 10] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let count = WORDS
+        .get(distance as usize)
+        .map_or_else(|| distance.to_string(), |word| word.to_string());
+    let unit = if distance == 1 { "tile" } else { "tiles" };
+    format!("{} {}", count, unit)
+}
+
+/// Names the nearest of the eight compass directions for an offset.
+fn compass_direction(dx: i32, dy: i32) -> String {
+    // Offsets more than twice as long along one axis count as straight
+    let vertical = if dy.abs() * 2 < dx.abs() {
+        ""
+    } else if dy < 0 {
+        "north"
+    } else if dy > 0 {
+        "south"
+    } else {
+        ""
+    };
+    let horizontal = if dx.abs() * 2 < dy.abs() {
+        ""
+    } else if dx > 0 {
+        "east"
+    } else if dx < 0 {
+        "west"
+    } else {
+        ""
+    };
+    match (vertical, horizontal) {
+        ("", "") => "here".to_string(),
+        ("", direction) | (direction, "") => direction.to_string(),
+        (vertical, horizontal) => format!("{}-{}", vertical, horizontal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Monster, MonsterType, Room, RoomType, Tile};
+
+    #[test]
+    fn test_surroundings_name_neighbours_and_creatures_in_sight() {
+        let mut game_state = GameState::new(12345);
+        let level = game_state.world.current_level_mut().unwrap();
+        for y in 4..10 {
+            for x in 4..8 {
+                let mut tile = Tile::floor();
+                tile.visible = true;
+                level.set_tile(Position::new(x, y), tile).unwrap();
+            }
+        }
+        let mut wall = Tile::wall();
+        wall.visible = true;
+        level.set_tile(Position::new(5, 4), wall).unwrap();
+        level
+            .rooms
+            .push(Room::new(0, Position::new(4, 5), 1, 5, RoomType::Normal));
+        game_state
+            .initialize_player("Listener".to_string(), Position::new(5, 5))
+            .unwrap();
+        for monster in [
+            Monster::new(MonsterType::Goblin, Position::new(5, 7)),
+            Monster::new(MonsterType::Skeleton, Position::new(7, 9)),
+        ] {
+            let monster_id = game_state.add_entity(monster.into()).unwrap();
+            game_state
+                .world
+                .current_level_mut()
+                .unwrap()
+                .add_entity(monster_id);
+        }
+        // A monster on another floor is not in sight, whatever its coordinates
+        let elsewhere = Monster::new(MonsterType::Orc, Position::new(6, 5));
+        game_state.add_entity(elsewhere.into()).unwrap();
+
+        assert_eq!(
+            describe_surroundings(&game_state).unwrap(),
+            "Wall north, open corridor east, open corridor south, open floor west, \
+             goblin two tiles south, skeleton six tiles south-east."
+        );
+        assert_eq!(compass_direction(1, -5), "north");
+        assert_eq!(tiles_away(12), "12 tiles");
+    }
+}
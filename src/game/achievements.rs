@@ -0,0 +1,241 @@
+//! # Achievements Module
+//!
+//! Milestones the player earns across runs.
+//!
+//! Every event the game state processes is shown to the run's
+//! [`AchievementTracker`] along with the updated [`GameStatistics`]. Newly
+//! earned achievements come back out as [`GameEvent::AchievementUnlocked`]
+//! events, which the scene records in the [`MetaProgress`] file so unlocks
+//! outlive the run that earned them.
+
+use crate::{EntityId, GameEvent, GameStatistics, ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Monsters to defeat in one run for [`Achievement::Slayer`].
+pub const SLAYER_KILLS: u32 = 50;
+
+/// Dungeon level, counting from 1, to reach for [`Achievement::DeepDelver`].
+pub const DEEP_DELVER_FLOOR: u32 = 10;
+
+/// Something the player can earn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Kill a monster
+    FirstBlood,
+    /// Kill [`SLAYER_KILLS`] monsters in one run
+    Slayer,
+    /// Reach dungeon level [`DEEP_DELVER_FLOOR`]
+    DeepDelver,
+    /// Leave a floor by the stairs down without taking damage on it
+    Untouchable,
+    /// Escape the dungeon with the Amulet
+    Champion,
+}
+
+impl Achievement {
+    /// Every achievement, in display order
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstBlood,
+        Achievement::Slayer,
+        Achievement::DeepDelver,
+        Achievement::Untouchable,
+        Achievement::Champion,
+    ];
+
+    /// Returns the name shown when the achievement is earned.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "First Blood",
+            Achievement::Slayer => "Slayer",
+            Achievement::DeepDelver => "Deep Delver",
+            Achievement::Untouchable => "Untouchable",
+            Achievement::Champion => "Champion",
+        }
+    }
+
+    /// Describes how the achievement is earned.
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "Kill a monster",
+            Achievement::Slayer => "Kill 50 monsters in one run",
+            Achievement::DeepDelver => "Reach dungeon level 10",
+            Achievement::Untouchable => "Descend from a floor without taking damage on it",
+            Achievement::Champion => "Escape the dungeon with the Amulet",
+        }
+    }
+}
+
+/// The achievements earned during one run.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{new_entity_id, Achievement, AchievementTracker, GameEvent, GameStatistics};
+///
+/// let player_id = new_entity_id();
+/// let mut stats = GameStatistics::new();
+/// stats.enemies_defeated = 1;
+/// let kill = GameEvent::EntityDied {
+///     entity_id: new_entity_id(),
+///     killer: Some(player_id),
+/// };
+///
+/// let mut tracker = AchievementTracker::new();
+/// assert_eq!(tracker.observe(&kill, &stats, Some(player_id)), [Achievement::FirstBlood]);
+/// assert!(tracker.observe(&kill, &stats, Some(player_id)).is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementTracker {
+    /// Achievements earned so far this run
+    unlocked: BTreeSet<Achievement>,
+    /// Damage the player had taken on arriving on the current floor
+    floor_damage: u64,
+}
+
+impl AchievementTracker {
+    /// Creates a tracker with nothing earned.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks an event and the statistics it updated for new achievements.
+    ///
+    /// Returns the achievements this event earned; each is earned once a run.
+    pub fn observe(
+        &mut self,
+        event: &GameEvent,
+        stats: &GameStatistics,
+        player_id: Option<EntityId>,
+    ) -> Vec<Achievement> {
+        let mut earned = Vec::new();
+        match event {
+            GameEvent::EntityDied { killer, .. } if killer.is_some() && *killer == player_id => {
+                earned.push(Achievement::FirstBlood);
+                if stats.enemies_defeated >= SLAYER_KILLS {
+                    earned.push(Achievement::Slayer);
+                }
+            }
+            GameEvent::PlayerChangedLevel {
+                old_level,
+                new_level,
+                ..
+            } => {
+                if new_level > old_level && stats.damage_taken == self.floor_damage {
+                    earned.push(Achievement::Untouchable);
+                }
+                if new_level + 1 >= DEEP_DELVER_FLOOR {
+                    earned.push(Achievement::DeepDelver);
+                }
+                self.floor_damage = stats.damage_taken;
+            }
+            GameEvent::GameEnded { ending_type, .. } if ending_type == "victory" => {
+                earned.push(Achievement::Champion);
+            }
+            _ => {}
+        }
+        earned.retain(|&achievement| self.unlocked.insert(achievement));
+        earned
+    }
+
+    /// Returns true if the achievement was earned this run.
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+}
+
+/// Progress kept between runs, stored as JSON in
+/// [`config::PROGRESS_FILE`](crate::config::PROGRESS_FILE).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetaProgress {
+    /// Every achievement earned in any run
+    pub achievements: BTreeSet<Achievement>,
+    /// Whether the player has finished the tutorial
+    pub tutorial_completed: bool,
+}
+
+impl MetaProgress {
+    /// Loads progress from a JSON file.
+    pub fn load(path: &Path) -> ThatchResult<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(ThatchError::from)
+    }
+
+    /// Loads progress from a JSON file, starting afresh if it is missing or unreadable.
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    /// Writes progress to a JSON file.
+    pub fn save(&self, path: &Path) -> ThatchResult<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Returns true if the player has played before, so new games can skip
+    /// the tutorial.
+    pub fn is_returning_player(&self) -> bool {
+        self.tutorial_completed || !self.achievements.is_empty()
+    }
+
+    /// Records an achievement, returning true if it was not earned before.
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        self.achievements.insert(achievement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Position, StairDirection};
+
+    #[test]
+    fn test_achievements_unlock_from_events_and_persist() {
+        let mut game_state = GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Hero".to_string(), Position::new(5, 5))
+            .unwrap();
+        let descend = |old_level, new_level| GameEvent::PlayerChangedLevel {
+            player_id,
+            old_level,
+            new_level,
+            direction: StairDirection::Down,
+        };
+        let unlocked = |events: Vec<GameEvent>| -> Vec<Achievement> {
+            events
+                .into_iter()
+                .filter_map(|event| match event {
+                    GameEvent::AchievementUnlocked { achievement } => Some(achievement),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // A clean descent is untouchable; one after getting hurt is not
+        let events = game_state.process_event(&descend(0, 1)).unwrap();
+        assert_eq!(unlocked(events), [Achievement::Untouchable]);
+        let hurt = GameEvent::EntityDamaged {
+            entity_id: player_id,
+            damage: 5,
+            source: None,
+        };
+        game_state.process_event(&hurt).unwrap();
+        assert_eq!(game_state.statistics.damage_taken, 5);
+        assert!(unlocked(game_state.process_event(&descend(1, 2)).unwrap()).is_empty());
+
+        let events = game_state.process_event(&descend(8, 9)).unwrap();
+        assert_eq!(unlocked(events), [Achievement::DeepDelver]);
+        assert!(game_state.achievements.is_unlocked(Achievement::DeepDelver));
+
+        // Unlocks survive a round trip through the progress file
+        let path = std::env::temp_dir().join(format!("thatch_progress_{}.json", player_id));
+        let mut progress = MetaProgress::default();
+        assert!(progress.unlock(Achievement::DeepDelver));
+        assert!(!progress.unlock(Achievement::DeepDelver));
+        progress.save(&path).unwrap();
+        assert_eq!(MetaProgress::load_or_default(&path), progress);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -6,7 +6,9 @@
 //! and AI decisions. All actions are serializable for MCP integration,
 //! save/load functionality, and replay systems.
 
-use crate::{Direction, Entity, EntityId, GameEvent, Position, ThatchError, ThatchResult};
+use crate::{
+    BlockedBy, Direction, Entity, EntityId, GameEvent, Position, ThatchError, ThatchResult,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -46,6 +48,12 @@ pub trait Action: Send + Sync {
 pub enum ActionType {
     /// Movement actions
     Move(Direction),
+    /// Being pushed along by an attack or spell
+    ForcedMove {
+        target: EntityId,
+        direction: Direction,
+        distance: u32,
+    },
     /// Combat actions
     Attack {
         target: EntityId,
@@ -84,11 +92,56 @@ pub enum ActionType {
     },
     /// Wait/rest action
     Wait,
+    /// Shout, drawing monsters from far away
+    Shout,
+    /// Rest one turn to recover health and mana
+    Rest,
+    /// Search adjacent tiles for secrets
+    Search,
+    /// Use the actor's class ability
+    UseAbility(crate::ClassAbility),
+    /// Cast a spell by name
+    CastSpell {
+        spell: String,
+    },
+    /// Throw a carried item toward a position
+    Throw {
+        item_id: EntityId,
+        target: Position,
+    },
+    /// Shoot from the quiver toward a position
+    Fire {
+        target: Position,
+    },
+    /// Open a chest or other container
+    OpenContainer {
+        container_id: EntityId,
+    },
+    /// Take an item out of an open container
+    TakeFromContainer {
+        container_id: EntityId,
+        item_id: EntityId,
+    },
+    /// Butcher a corpse into food
+    Butcher {
+        corpse_id: EntityId,
+    },
+    /// Talk to a friendly NPC
+    Talk {
+        npc_id: EntityId,
+    },
+    /// Pray at an altar within reach
+    Pray,
+    /// Examine a position without spending a turn
+    Look {
+        position: Position,
+    },
     /// Development and debugging actions
     Debug(DebugAction),
     /// LLDM-generated custom actions
     Custom {
         action_name: String,
+        #[serde(serialize_with = "crate::sorted_map")]
         parameters: HashMap<String, String>,
     },
 }
@@ -136,6 +189,7 @@ pub struct ActionResult {
     /// Time cost of the action (for turn management)
     pub time_cost: u32,
     /// Action-specific result data
+    #[serde(serialize_with = "crate::sorted_map")]
     pub result_data: HashMap<String, String>,
 }
 
@@ -177,6 +231,7 @@ impl ActionResult {
 pub struct MoveAction {
     pub actor: EntityId,
     pub direction: Direction,
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
 }
 
@@ -208,33 +263,42 @@ impl Action for MoveAction {
             .current_level()
             .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
 
+        let blocked = |by| ThatchError::MoveBlocked {
+            position: new_pos,
+            by,
+        };
         if !current_level.is_valid_position(new_pos) {
-            return Err(ThatchError::InvalidAction(
-                "Position out of bounds".to_string(),
-            ));
+            return Err(blocked(BlockedBy::Bounds));
         }
 
         if !current_level.is_passable(new_pos) {
-            return Err(ThatchError::InvalidAction(
-                "Position is blocked".to_string(),
-            ));
+            return Err(blocked(BlockedBy::Terrain));
         }
 
         // Check for other entities at the target position
-        if let Some(_blocking_entity) = game_state.get_entity_at_position(new_pos) {
-            return Err(ThatchError::InvalidAction(
-                "Position occupied by another entity".to_string(),
-            ));
+        if let Some(blocking_entity) = game_state.get_entity_at_position(new_pos) {
+            return Err(blocked(BlockedBy::Entity(blocking_entity)));
         }
 
         // Execute the movement
         game_state.set_entity_position(self.actor, new_pos)?;
 
-        Ok(vec![GameEvent::EntityMoved {
-            entity_id: self.actor,
-            from: current_pos,
-            to: new_pos,
-        }])
+        let mut events = vec![
+            GameEvent::EntityMoved {
+                entity_id: self.actor,
+                from: current_pos,
+                to: new_pos,
+            },
+            GameEvent::NoiseMade {
+                source: self.actor,
+                position: new_pos,
+                volume: crate::NOISE_WALK,
+            },
+        ];
+
+        // Spring whatever is scripted on the tile stepped onto
+        events.extend(game_state.trigger_tile_effect(self.actor, new_pos)?);
+        Ok(events)
     }
 
     fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
@@ -274,11 +338,161 @@ impl Action for MoveAction {
     }
 }
 
+/// Damage dealt to a pushed entity for each tile of the push a wall cuts short.
+pub const SLAM_DAMAGE_PER_TILE: u32 = 4;
+
+/// Tiles a blow from a mace knocks its target back.
+pub const MACE_KNOCKBACK: u32 = 1;
+
+/// Forced movement, pushing an entity up to `distance` tiles in a direction.
+///
+/// Each tile of the push is an ordinary [`MoveAction`] by the pushed entity,
+/// so closed doors stop it, traps spring and teleporters carry it off. The
+/// push ends early on running into another entity or landing in water, and
+/// running into a wall or closed door slams the entity against it for
+/// [`SLAM_DAMAGE_PER_TILE`] damage per tile of the push left. Attacks and
+/// spells resolve it as part of their own turn, so it costs no time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForcedMoveAction {
+    /// Entity doing the pushing, credited with any slam damage
+    pub source: EntityId,
+    /// Entity being pushed
+    pub target: EntityId,
+    pub direction: Direction,
+    /// Most tiles the target is pushed
+    pub distance: u32,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl ForcedMoveAction {
+    /// Creates a new forced movement action.
+    pub fn new(source: EntityId, target: EntityId, direction: Direction, distance: u32) -> Self {
+        Self {
+            source,
+            target,
+            direction,
+            distance,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Creates a push driving `target` straight away from `source`, or None
+    /// if they share a tile or either has no position.
+    pub fn away_from(
+        game_state: &crate::GameState,
+        source: EntityId,
+        target: EntityId,
+        distance: u32,
+    ) -> Option<Self> {
+        let from = game_state.get_entity_position(source)?;
+        let to = game_state.get_entity_position(target)?;
+        let direction = Direction::toward(from, to)?;
+        Some(Self::new(source, target, direction, distance))
+    }
+
+    /// Describes the target hitting a wall.
+    fn slam_message(&self, game_state: &crate::GameState) -> String {
+        if Some(self.target) == game_state.player_id {
+            return "You slam into the wall!".to_string();
+        }
+        let name = game_state
+            .entities
+            .get(&self.target)
+            .map_or("creature", |entity| entity.name());
+        format!("The {} slams into the wall!", name)
+    }
+}
+
+impl Action for ForcedMoveAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let mut events = Vec::new();
+        for pushed in 0..self.distance {
+            let Some(from) = game_state.get_entity_position(self.target) else {
+                break;
+            };
+            let to = from + self.direction.to_delta();
+            let level = game_state
+                .world
+                .current_level()
+                .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+            if !level.is_passable(to) {
+                events.push(GameEvent::Message {
+                    text: self.slam_message(game_state),
+                    importance: crate::MessageImportance::Normal,
+                    channel: crate::MessageChannel::Combat,
+                });
+                events.push(GameEvent::EntityDamaged {
+                    entity_id: self.target,
+                    damage: SLAM_DAMAGE_PER_TILE * (self.distance - pushed),
+                    source: Some(self.source),
+                });
+                break;
+            }
+            if game_state.get_entity_at_position(to).is_some() {
+                break;
+            }
+
+            events.extend(MoveAction::new(self.target, self.direction).execute(game_state)?);
+            let landed = game_state.get_entity_position(self.target);
+            let in_water = landed
+                .and_then(|pos| game_state.world.current_level()?.get_tile(pos))
+                .is_some_and(|tile| tile.tile_type == crate::TileType::Water);
+            // A teleporter carries the target off, and water drags it to a halt
+            if landed != Some(to) || in_water {
+                break;
+            }
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        if !game_state.entity_exists(self.target) {
+            return Err(ThatchError::InvalidAction(
+                "Target entity does not exist".to_string(),
+            ));
+        }
+        if !game_state.is_entity_alive(self.target) {
+            return Err(ThatchError::InvalidAction(
+                "Target is not alive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.source
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::ForcedMove {
+            target: self.target,
+            direction: self.direction,
+            distance: self.distance,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        0 // Part of the attack or spell that caused it
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
 /// Attack action implementation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttackAction {
     pub attacker: EntityId,
     pub target: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
 }
 
@@ -291,6 +505,21 @@ impl AttackAction {
             metadata: HashMap::new(),
         }
     }
+
+    /// Gets how many tiles the blow knocks the target back, from the
+    /// attacker's weapon.
+    fn knockback(&self, game_state: &crate::GameState) -> u32 {
+        let weapon = game_state
+            .entities
+            .get(&self.attacker)
+            .and_then(crate::ConcreteEntity::as_player)
+            .and_then(|player| player.get_equipped_item("weapon"))
+            .and_then(|id| game_state.carried_items.get(id));
+        match weapon.map(|weapon| &weapon.item_type) {
+            Some(crate::ItemType::Weapon(crate::WeaponType::Mace)) => MACE_KNOCKBACK,
+            _ => 0,
+        }
+    }
 }
 
 impl Action for AttackAction {
@@ -328,14 +557,35 @@ impl Action for AttackAction {
             .ok_or_else(|| ThatchError::InvalidState("Attacker stats not found".to_string()))?;
 
         let base_damage = attacker_stats.attack;
-        let actual_damage = base_damage + rand::random::<u32>() % 10; // Add some randomness
+        // Add some randomness, drawn from the seeded combat stream so runs replay exactly
+        let roll = game_state
+            .rng
+            .gen_range(crate::RngStream::Combat, 0..crate::ATTACK_DAMAGE_SPREAD);
+        let actual_damage = base_damage + roll;
 
         // Apply damage to target
-        let events = vec![GameEvent::EntityDamaged {
-            entity_id: self.target,
-            damage: actual_damage,
-            source: Some(self.attacker),
-        }];
+        let mut events = vec![
+            GameEvent::EntityDamaged {
+                entity_id: self.target,
+                damage: actual_damage,
+                source: Some(self.attacker),
+            },
+            GameEvent::NoiseMade {
+                source: self.attacker,
+                position: attacker_pos,
+                volume: crate::NOISE_FIGHT,
+            },
+        ];
+
+        // Heavy blows drive the target back
+        let knockback = self.knockback(game_state);
+        if knockback > 0 {
+            let push =
+                ForcedMoveAction::away_from(game_state, self.attacker, self.target, knockback);
+            if let Some(push) = push {
+                events.extend(push.execute(game_state)?);
+            }
+        }
 
         Ok(events)
     }
@@ -381,6 +631,7 @@ impl Action for AttackAction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WaitAction {
     pub actor: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
 }
 
@@ -430,6 +681,146 @@ impl Action for WaitAction {
     }
 }
 
+/// Action for shouting, a deliberate noise that carries far.
+///
+/// Useful for luring monsters out of hiding; see [`NOISE_SHOUT`](crate::NOISE_SHOUT).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoutAction {
+    pub actor: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl ShoutAction {
+    /// Creates a new shout action.
+    pub fn new(actor: EntityId) -> Self {
+        Self {
+            actor,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for ShoutAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let position = game_state
+            .get_entity_position(self.actor)
+            .ok_or_else(|| ThatchError::InvalidState("Actor position not found".to_string()))?;
+        Ok(vec![
+            GameEvent::Message {
+                text: "You shout!".to_string(),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            },
+            GameEvent::NoiseMade {
+                source: self.actor,
+                position,
+                volume: crate::NOISE_SHOUT,
+            },
+        ])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        if !game_state.is_entity_alive(self.actor) {
+            return Err(ThatchError::InvalidAction("Actor is not alive".to_string()));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Shout
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Health recovered by each turn of rest.
+pub const REST_HEALTH_PER_TURN: u32 = 2;
+
+/// Mana recovered by each turn of rest.
+pub const REST_MANA_PER_TURN: u32 = 1;
+
+/// Action for resting one turn to recover health and mana.
+///
+/// Resting repeats this action each turn; see [`crate::RestState`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestAction {
+    pub actor: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl RestAction {
+    /// Creates a new rest action.
+    pub fn new(actor: EntityId) -> Self {
+        Self {
+            actor,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for RestAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        if let Some(stats) = game_state
+            .entities
+            .get_mut(&self.actor)
+            .and_then(|entity| entity.as_entity_mut().stats_mut())
+        {
+            stats.heal(REST_HEALTH_PER_TURN);
+            stats.restore_mana(REST_MANA_PER_TURN);
+        }
+        Ok(vec![])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        if !game_state.entity_exists(self.actor) {
+            return Err(ThatchError::InvalidAction(
+                "Actor entity does not exist".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Rest
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
 /// Action for using stairs to change levels.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UseStairsAction {
@@ -438,6 +829,7 @@ pub struct UseStairsAction {
     /// Direction of stair travel
     pub direction: StairDirection,
     /// Action metadata
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
 }
 
@@ -482,9 +874,11 @@ impl Action for UseStairsAction {
         if level_changed {
             events.push(GameEvent::PlayerChangedLevel {
                 player_id: self.actor,
-                old_level: game_state.world.current_level_id.saturating_sub(
-                    u32::from(self.direction == StairDirection::Down),
-                ) + u32::from(self.direction == StairDirection::Up),
+                old_level: game_state
+                    .world
+                    .current_level_id
+                    .saturating_sub(u32::from(self.direction == StairDirection::Down))
+                    + u32::from(self.direction == StairDirection::Up),
                 new_level: game_state.world.current_level_id,
                 direction: self.direction.clone(),
             });
@@ -497,12 +891,13 @@ impl Action for UseStairsAction {
                         message: "You escaped the dungeon with your life, but left much treasure behind...".to_string(),
                     });
                 }
-                crate::GameCompletionState::CompletedDungeon => {
+                crate::GameCompletionState::EscapedWithArtifact => {
                     events.push(GameEvent::GameEnded {
                         ending_type: "victory".to_string(),
-                        message:
-                            "Congratulations! You've conquered the deepest depths of the dungeon!"
-                                .to_string(),
+                        message: format!(
+                            "Congratulations! You climbed out of the dungeon with the {}!",
+                            crate::ARTIFACT_NAME
+                        ),
                     });
                 }
                 _ => {}
@@ -553,58 +948,2486 @@ impl Action for UseStairsAction {
     }
 }
 
-/// Concrete action types for serialization and queue management.
+/// Chance of finding an adjacent secret with zero perception.
+pub const SEARCH_BASE_CHANCE: f64 = 0.25;
+
+/// Extra chance of finding a secret per point of perception.
+pub const SEARCH_CHANCE_PER_PERCEPTION: f64 = 0.025;
+
+/// Highest chance a single search can have of finding a secret.
+pub const SEARCH_MAX_CHANCE: f64 = 0.95;
+
+/// Action for searching the tiles around the actor for secret doors and
+/// hidden traps.
 ///
-/// This enum represents all concrete action implementations that can be
-/// stored in the action queue and serialized for save/load and MCP.
+/// Each adjacent secret is found independently with a chance based on the
+/// actor's perception; found secret doors become closed doors and found traps
+/// show.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ConcreteAction {
-    Move(MoveAction),
-    Attack(AttackAction),
-    Wait(WaitAction),
-    UseStairs(UseStairsAction),
+pub struct SearchAction {
+    pub actor: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
 }
 
-impl ConcreteAction {
-    /// Executes the concrete action.
-    pub fn execute(
-        &self,
-        game_state: &mut crate::GameState,
-    ) -> crate::ThatchResult<Vec<crate::GameEvent>> {
-        match self {
-            Self::Move(action) => action.execute(game_state),
-            Self::Attack(action) => action.execute(game_state),
-            Self::Wait(action) => action.execute(game_state),
-            Self::UseStairs(action) => action.execute(game_state),
+impl SearchAction {
+    /// Creates a new search action.
+    pub fn new(actor: EntityId) -> Self {
+        Self {
+            actor,
+            metadata: HashMap::new(),
         }
     }
 
-    /// Gets the action type.
-    #[must_use]
-    pub fn action_type(&self) -> ActionType {
-        match self {
-            Self::Move(action) => action.action_type(),
-            Self::Attack(action) => action.action_type(),
-            Self::Wait(action) => action.action_type(),
-            Self::UseStairs(action) => action.action_type(),
-        }
+    /// Returns the chance of finding each adjacent secret for a given perception.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::SearchAction;
+    ///
+    /// assert!(SearchAction::find_chance(20) > SearchAction::find_chance(5));
+    /// assert!(SearchAction::find_chance(1000) <= 0.95);
+    /// ```
+    pub fn find_chance(perception: u32) -> f64 {
+        (SEARCH_BASE_CHANCE + perception as f64 * SEARCH_CHANCE_PER_PERCEPTION)
+            .min(SEARCH_MAX_CHANCE)
     }
+}
 
-    /// Gets the entity ID that performs this action.
-    #[must_use]
-    pub fn actor(&self) -> EntityId {
-        match self {
-            Self::Move(action) => action.actor(),
-            Self::Attack(action) => action.actor(),
-            Self::Wait(action) => action.actor(),
-            Self::UseStairs(action) => action.actor(),
+impl Action for SearchAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let actor = game_state
+            .entities
+            .get(&self.actor)
+            .ok_or_else(|| ThatchError::InvalidState("Actor entity not found".to_string()))?;
+        let position = actor.position();
+        let perception = actor
+            .stats()
+            .map_or(crate::config::DEFAULT_PERCEPTION, |stats| stats.perception);
+        let chance = Self::find_chance(perception);
+
+        let mut events = Vec::new();
+        for neighbor in position.adjacent_positions() {
+            let is_secret = game_state
+                .world
+                .current_level()
+                .is_some_and(|level| level.is_secret(neighbor));
+            if !is_secret || !game_state.rng.gen_bool(crate::RngStream::Search, chance) {
+                continue;
+            }
+
+            if let Some(level) = game_state.world.current_level_mut() {
+                level.reveal_secret(neighbor);
+            }
+            events.push(GameEvent::SecretFound {
+                finder_id: self.actor,
+                position: neighbor,
+            });
+        }
+
+        if events.is_empty() {
+            events.push(GameEvent::Message {
+                text: "You search but find nothing.".to_string(),
+                importance: crate::MessageImportance::Info,
+                channel: crate::MessageChannel::Exploration,
+            });
         }
+
+        Ok(events)
     }
-}
 
-/// Action queue for managing turn order and action execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionQueue {
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        if !game_state.entity_exists(self.actor) {
+            return Err(ThatchError::InvalidAction(
+                "Actor entity does not exist".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Search
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for opening or closing a door next to the actor.
+///
+/// A door can only be closed while nothing stands or lies in the doorway. One
+/// opened with [`DoorAction::open_and_enter`] is stepped through in the same
+/// turn, unless something has moved into the doorway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoorAction {
+    pub actor: EntityId,
+    pub position: Position,
+    /// Whether the door is being opened rather than closed
+    pub open: bool,
+    /// Whether the actor steps into the doorway once the door is open
+    #[serde(default)]
+    pub enter: bool,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl DoorAction {
+    /// Creates an action opening the door at `position`.
+    pub fn open(actor: EntityId, position: Position) -> Self {
+        Self {
+            actor,
+            position,
+            open: true,
+            enter: false,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Creates an action opening the door at `position` and stepping through.
+    pub fn open_and_enter(actor: EntityId, position: Position) -> Self {
+        Self {
+            enter: true,
+            ..Self::open(actor, position)
+        }
+    }
+
+    /// Creates an action closing the door at `position`.
+    pub fn close(actor: EntityId, position: Position) -> Self {
+        Self {
+            open: false,
+            ..Self::open(actor, position)
+        }
+    }
+}
+
+impl Action for DoorAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        if let Some(tile) = game_state
+            .world
+            .current_level_mut()
+            .and_then(|level| level.get_tile_mut(self.position))
+        {
+            tile.tile_type = crate::TileType::Door { is_open: self.open };
+        }
+
+        // A door swinging open or shut changes what the player can see
+        if game_state.player_id == Some(self.actor) {
+            game_state.field_of_view = None;
+            if let Some(position) = game_state.get_entity_position(self.actor) {
+                game_state.update_player_visibility(position)?;
+            }
+        }
+
+        let text = if self.open {
+            "You open the door."
+        } else {
+            "You close the door."
+        };
+        let mut events = vec![GameEvent::Message {
+            text: text.to_string(),
+            importance: crate::MessageImportance::Info,
+            channel: crate::MessageChannel::Exploration,
+        }];
+
+        let direction = game_state
+            .get_entity_position(self.actor)
+            .and_then(|from| Direction::from_delta(self.position - from));
+        if let Some(direction) = direction.filter(|_| self.open && self.enter) {
+            // Whatever now stands in the doorway keeps the actor on this side
+            if let Ok(moved) = MoveAction::new(self.actor, direction).execute(game_state) {
+                events.extend(moved);
+            }
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let position = game_state
+            .get_entity_position(self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Actor entity does not exist".to_string()))?;
+        if !position.adjacent_positions().contains(&self.position) {
+            return Err(ThatchError::InvalidAction(
+                "That door is out of reach".to_string(),
+            ));
+        }
+
+        let level = game_state
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        match level.get_tile(self.position).map(|tile| &tile.tile_type) {
+            Some(crate::TileType::Door { is_open }) if *is_open != self.open => {}
+            Some(crate::TileType::Door { .. }) if self.open => {
+                return Err(ThatchError::InvalidAction(
+                    "That door is already open".to_string(),
+                ));
+            }
+            Some(crate::TileType::Door { .. }) => {
+                return Err(ThatchError::InvalidAction(
+                    "That door is already closed".to_string(),
+                ));
+            }
+            _ => {
+                return Err(ThatchError::InvalidAction(
+                    "There is no door there".to_string(),
+                ))
+            }
+        }
+
+        if !self.open
+            && (!game_state
+                .get_entities_at_position(self.position)
+                .is_empty()
+                || level.ground_items.top_at(self.position).is_some())
+        {
+            return Err(ThatchError::InvalidAction(
+                "Something is blocking the doorway".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        if self.open {
+            ActionType::OpenDoor {
+                position: self.position,
+            }
+        } else {
+            ActionType::CloseDoor {
+                position: self.position,
+            }
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Share of maximum health, in percent, restored by Second Wind.
+pub const SECOND_WIND_HEAL_PERCENT: u32 = 30;
+
+/// Distance within which Keen Eye uncovers secret doors and hidden traps.
+pub const KEEN_EYE_RADIUS: i32 = 5;
+
+/// Farthest a visible hostile can be and still be hit by Arcane Bolt.
+pub const ARCANE_BOLT_RANGE: u32 = 8;
+
+/// Damage dealt by Arcane Bolt before the caster's level bonus.
+pub const ARCANE_BOLT_BASE_DAMAGE: u32 = 15;
+
+/// Damage Arcane Bolt gains for each of the caster's levels.
+pub const ARCANE_BOLT_DAMAGE_PER_LEVEL: u32 = 2;
+
+/// Action for using the player's class ability.
+///
+/// Abilities cost mana and start a cooldown; see [`crate::ClassAbility`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbilityAction {
+    pub actor: EntityId,
+    pub ability: crate::ClassAbility,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl AbilityAction {
+    /// Creates a new ability action.
+    pub fn new(actor: EntityId, ability: crate::ClassAbility) -> Self {
+        Self {
+            actor,
+            ability,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Finds the nearest visible hostile within Arcane Bolt range.
+    fn arcane_bolt_target(&self, game_state: &crate::GameState) -> Option<EntityId> {
+        let origin = game_state.get_entity_position(self.actor)?;
+        game_state.nearest_visible_hostile(origin, ARCANE_BOLT_RANGE)
+    }
+
+    /// Uncovers every secret door and hidden trap near `center`.
+    fn reveal_secrets(
+        &self,
+        game_state: &mut crate::GameState,
+        center: Position,
+    ) -> Vec<GameEvent> {
+        let Some(level) = game_state.world.current_level_mut() else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        for y in (center.y - KEEN_EYE_RADIUS)..=(center.y + KEEN_EYE_RADIUS) {
+            for x in (center.x - KEEN_EYE_RADIUS)..=(center.x + KEEN_EYE_RADIUS) {
+                let position = Position::new(x, y);
+                if level.reveal_secret(position) {
+                    events.push(GameEvent::SecretFound {
+                        finder_id: self.actor,
+                        position,
+                    });
+                }
+            }
+        }
+        events
+    }
+}
+
+impl Action for AbilityAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let player = game_state
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let position = player.position();
+        let stats = player.stats.clone();
+
+        let events = match self.ability {
+            crate::ClassAbility::SecondWind => vec![
+                GameEvent::Message {
+                    text: "You catch your second wind.".to_string(),
+                    importance: crate::MessageImportance::Normal,
+                    channel: crate::MessageChannel::Combat,
+                },
+                GameEvent::EntityHealed {
+                    entity_id: self.actor,
+                    amount: stats.max_health * SECOND_WIND_HEAL_PERCENT / 100,
+                    source: Some(self.actor),
+                },
+            ],
+            crate::ClassAbility::KeenEye => {
+                let events = self.reveal_secrets(game_state, position);
+                if events.is_empty() {
+                    vec![GameEvent::Message {
+                        text: "You study your surroundings but find no secrets.".to_string(),
+                        importance: crate::MessageImportance::Info,
+                        channel: crate::MessageChannel::Exploration,
+                    }]
+                } else {
+                    events
+                }
+            }
+            crate::ClassAbility::ArcaneBolt => {
+                let target = self.arcane_bolt_target(game_state).ok_or_else(|| {
+                    ThatchError::InvalidAction("There is nothing in range to target".to_string())
+                })?;
+                let target_name = game_state
+                    .entities
+                    .get(&target)
+                    .map_or("target", |entity| entity.name())
+                    .to_string();
+                let damage = ARCANE_BOLT_BASE_DAMAGE + stats.level * ARCANE_BOLT_DAMAGE_PER_LEVEL;
+                vec![
+                    GameEvent::Message {
+                        text: format!("Your arcane bolt strikes the {}.", target_name),
+                        importance: crate::MessageImportance::Normal,
+                        channel: crate::MessageChannel::Combat,
+                    },
+                    GameEvent::EntityDamaged {
+                        entity_id: target,
+                        damage,
+                        source: Some(self.actor),
+                    },
+                ]
+            }
+        };
+
+        let turn = game_state.turn_number;
+        if let Some(player) = game_state.get_player_mut() {
+            player.stats.mana -= self.ability.mana_cost();
+            player.ability_ready_turn = turn + self.ability.cooldown_turns();
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player has a class ability".to_string())
+            })?;
+
+        let name = self.ability.display_name();
+        if player.class.ability() != self.ability {
+            return Err(ThatchError::InvalidAction(format!(
+                "A {} cannot use {}",
+                player.class.display_name(),
+                name
+            )));
+        }
+        if player.ability_ready_turn > game_state.turn_number {
+            return Err(ThatchError::InvalidAction(format!(
+                "{} is not ready for {} more turns",
+                name,
+                player.ability_ready_turn - game_state.turn_number
+            )));
+        }
+        if player.stats.mana < self.ability.mana_cost() {
+            return Err(ThatchError::InvalidAction(format!(
+                "Not enough mana for {}",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::UseAbility(self.ability)
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for casting one of the caster's known spells.
+///
+/// The spell's damage, healing and status effect are emitted as ordinary
+/// events, so they resolve like any other attack or effect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastSpellAction {
+    pub caster: EntityId,
+    pub spell: String,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl CastSpellAction {
+    /// Creates a new spell casting action.
+    pub fn new(caster: EntityId, spell: String) -> Self {
+        Self {
+            caster,
+            spell,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Looks up the spell being cast.
+    fn spell(&self) -> ThatchResult<&'static crate::Spell> {
+        crate::Spell::by_name(&self.spell)
+            .ok_or_else(|| ThatchError::InvalidAction(format!("Unknown spell: {}", self.spell)))
+    }
+
+    /// Finds the entity the spell will affect.
+    fn target(&self, spell: &crate::Spell, game_state: &crate::GameState) -> Option<EntityId> {
+        match spell.target {
+            crate::SpellTarget::Caster => Some(self.caster),
+            crate::SpellTarget::NearestHostile => {
+                let origin = game_state.get_entity_position(self.caster)?;
+                game_state.nearest_visible_hostile(origin, spell.range)
+            }
+            crate::SpellTarget::NearestCorpse => {
+                let origin = game_state.get_entity_position(self.caster)?;
+                game_state.nearest_visible_corpse(origin, spell.range)
+            }
+        }
+    }
+
+    /// Works out the events of a spell landing on its target.
+    ///
+    /// Shared with wands, which cast spells without the caster knowing them.
+    fn effects(
+        &self,
+        spell: &crate::Spell,
+        target: EntityId,
+        game_state: &mut crate::GameState,
+    ) -> ThatchResult<Vec<GameEvent>> {
+        let mut events = Vec::new();
+        if spell.damage > 0 {
+            events.push(GameEvent::EntityDamaged {
+                entity_id: target,
+                damage: spell.damage,
+                source: Some(self.caster),
+            });
+        }
+        if spell.healing > 0 {
+            events.push(GameEvent::EntityHealed {
+                entity_id: target,
+                amount: spell.healing,
+                source: Some(self.caster),
+            });
+        }
+        if let Some((kind, turns)) = spell.status {
+            events.push(GameEvent::StatusApplied {
+                entity_id: target,
+                kind,
+                turns,
+            });
+        }
+        if spell.target == crate::SpellTarget::NearestCorpse {
+            events.extend(game_state.raise_corpse(target)?);
+        }
+        if spell.knockback > 0 {
+            let push =
+                ForcedMoveAction::away_from(game_state, self.caster, target, spell.knockback);
+            if let Some(push) = push {
+                events.extend(push.execute(game_state)?);
+            }
+        }
+        Ok(events)
+    }
+}
+
+impl Action for CastSpellAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let spell = self.spell()?;
+        let target = self.target(spell, game_state).ok_or_else(|| {
+            ThatchError::InvalidAction("There is nothing in range to target".to_string())
+        })?;
+
+        let mut events = vec![GameEvent::Message {
+            text: format!("You cast {}.", spell.name),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Combat,
+        }];
+        events.extend(self.effects(spell, target, game_state)?);
+
+        if let Some(caster) = game_state.entities.get_mut(&self.caster) {
+            if let Some(stats) = caster.as_entity_mut().stats_mut() {
+                stats.mana -= spell.mana_cost;
+            }
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let spell = self.spell()?;
+        let caster = game_state
+            .entities
+            .get(&self.caster)
+            .ok_or_else(|| ThatchError::InvalidAction("Caster does not exist".to_string()))?;
+
+        if let Some(player) = caster.as_player() {
+            if !player.knows_spell(spell.name) {
+                return Err(ThatchError::InvalidAction(format!(
+                    "You do not know {}",
+                    spell.name
+                )));
+            }
+        }
+        if caster
+            .as_entity()
+            .stats()
+            .is_none_or(|stats| stats.mana < spell.mana_cost)
+        {
+            return Err(ThatchError::InvalidAction(format!(
+                "Not enough mana for {}",
+                spell.name
+            )));
+        }
+        if self.target(spell, game_state).is_none() {
+            return Err(ThatchError::InvalidAction(
+                "There is nothing in range to target".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.caster
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::CastSpell {
+            spell: self.spell.clone(),
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Farthest a thrown item can be aimed, in tiles walked.
+pub const THROW_RANGE: u32 = 6;
+
+/// Damage a thrown weapon deals to whatever it hits.
+pub const THROWN_WEAPON_DAMAGE: u32 = 8;
+
+/// Damage any other thrown item deals to whatever it hits.
+pub const THROWN_ITEM_DAMAGE: u32 = 2;
+
+/// Action for picking up the item on top of the pile under the actor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickUpAction {
+    pub actor: EntityId,
+    pub item_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl PickUpAction {
+    /// Creates a new pick up action.
+    pub fn new(actor: EntityId, item_id: EntityId) -> Self {
+        Self {
+            actor,
+            item_id,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl PickUpAction {
+    /// Puts a picked up stack of ammunition in the player's quiver.
+    fn quiver(
+        &self,
+        game_state: &mut crate::GameState,
+        stack: crate::ItemStack,
+    ) -> ThatchResult<Vec<GameEvent>> {
+        let item_id = stack.item.id;
+        let label = stack.label(&game_state.item_display_name(&stack.item));
+        let displaced = game_state.quiver_ammo(stack)?;
+
+        let mut events = vec![
+            GameEvent::ItemPickedUp {
+                item_id,
+                picker_id: self.actor,
+            },
+            GameEvent::Message {
+                text: format!("You put the {} in your quiver.", label),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            },
+        ];
+        if let Some(displaced) = displaced {
+            let label = displaced.label(&game_state.item_display_name(&displaced.item));
+            events.push(GameEvent::Message {
+                text: format!("You drop the {} that were in it.", label),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            });
+        }
+        Ok(events)
+    }
+}
+
+impl Action for PickUpAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let level = game_state
+            .world
+            .current_level_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        let is_ammo = level
+            .ground_items
+            .iter()
+            .any(|stack| stack.item.id == self.item_id && stack.item.item_type.is_ammo());
+        if is_ammo {
+            // Arrows and bolts are gathered up all at once, into the quiver
+            let stack = level
+                .ground_items
+                .take_stack(self.item_id)
+                .ok_or_else(|| ThatchError::InvalidAction("That item is not here".to_string()))?;
+            return self.quiver(game_state, stack);
+        }
+
+        let item = level
+            .ground_items
+            .take(self.item_id)
+            .ok_or_else(|| ThatchError::InvalidAction("That item is not here".to_string()))?;
+        let text = format!("You pick up the {}.", game_state.item_display_name(&item));
+        let item_id = game_state.give_item_to_player(item, false)?;
+
+        Ok(vec![
+            GameEvent::ItemPickedUp {
+                item_id,
+                picker_id: self.actor,
+            },
+            GameEvent::Message {
+                text,
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            },
+        ])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can pick up items".to_string())
+            })?;
+        let on_top = game_state
+            .world
+            .current_level()
+            .and_then(|level| level.ground_items.top_at(player.position()))
+            .filter(|stack| stack.item.id == self.item_id)
+            .ok_or_else(|| ThatchError::InvalidAction("That item is not here".to_string()))?;
+        if !on_top.item.item_type.is_ammo() && !player.can_pick_up_item() {
+            return Err(ThatchError::InvalidAction("Your pack is full".to_string()));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::PickUpItem {
+            item_id: self.item_id,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for butchering a corpse under the actor into food.
+///
+/// The corpse is replaced by [`BUTCHER_YIELD`](crate::BUTCHER_YIELD) chunks of
+/// meat on the same tile, ready to be picked up and eaten.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ButcherAction {
+    pub actor: EntityId,
+    pub corpse_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl ButcherAction {
+    /// Creates a new butcher action.
+    pub fn new(actor: EntityId, corpse_id: EntityId) -> Self {
+        Self {
+            actor,
+            corpse_id,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Finds the topmost corpse under the player, if there is one.
+    pub fn corpse_under_player(game_state: &crate::GameState) -> Option<EntityId> {
+        let position = game_state.get_player()?.position();
+        game_state
+            .world
+            .current_level()?
+            .ground_items
+            .stacks_at(position)
+            .filter(|stack| matches!(stack.item.item_type, crate::ItemType::Corpse(_)))
+            .last()
+            .map(|stack| stack.item.id)
+    }
+}
+
+impl Action for ButcherAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let level = game_state
+            .world
+            .current_level_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        let corpse = level
+            .ground_items
+            .take(self.corpse_id)
+            .ok_or_else(|| ThatchError::InvalidAction("There is no corpse here".to_string()))?;
+
+        let mut events = vec![GameEvent::Message {
+            text: format!("You butcher the {}.", corpse.name),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Exploration,
+        }];
+        for meat in crate::butcher_yield(corpse.position) {
+            events.push(GameEvent::ItemDropped {
+                item_id: meat.id,
+                dropper_id: self.actor,
+                position: corpse.position,
+            });
+            level.ground_items.drop_item(meat);
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can butcher corpses".to_string())
+            })?;
+
+        let here = game_state.world.current_level().is_some_and(|level| {
+            level
+                .ground_items
+                .stacks_at(player.position())
+                .any(|stack| {
+                    stack.item.id == self.corpse_id
+                        && matches!(stack.item.item_type, crate::ItemType::Corpse(_))
+                })
+        });
+        if !here {
+            return Err(ThatchError::InvalidAction(
+                "There is no corpse here".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Butcher {
+            corpse_id: self.corpse_id,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for talking to a friendly NPC within reach.
+///
+/// The NPC's opening line goes to the message log; the scene shows the rest
+/// of what it has to say from [`GameState::npc_dialogue`](crate::GameState::npc_dialogue).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TalkAction {
+    pub actor: EntityId,
+    pub npc_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl TalkAction {
+    /// Creates a new talk action.
+    pub fn new(actor: EntityId, npc_id: EntityId) -> Self {
+        Self {
+            actor,
+            npc_id,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Finds an NPC standing next to the player, if there is one.
+    pub fn npc_within_reach(game_state: &crate::GameState) -> Option<EntityId> {
+        let position = game_state.get_player()?.position();
+        position
+            .adjacent_positions()
+            .into_iter()
+            .flat_map(|next| game_state.get_entities_at_position(next))
+            .find(|id| {
+                let entity = game_state.entities.get(id);
+                entity.is_some_and(|entity| entity.as_npc().is_some())
+            })
+    }
+}
+
+impl Action for TalkAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let lines = game_state.npc_dialogue(self.npc_id)?;
+        let name = game_state
+            .entities
+            .get(&self.npc_id)
+            .map_or("Someone", |npc| npc.name())
+            .to_string();
+        let text = match lines.first() {
+            Some(line) => format!("{} says: \"{}\"", name, line),
+            None => format!("{} has nothing to say.", name),
+        };
+        // NPCs share their role's name, so each is told apart by its level
+        let subject = format!(
+            "the {} of level {}",
+            name,
+            game_state.world.current_level_id + 1
+        );
+        let fact = format!("Spoke with {}.", subject);
+        game_state.remember(crate::MemoryKind::Npc, subject, fact);
+        Ok(vec![GameEvent::Message {
+            text,
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Narrative,
+        }])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Only the player can talk".to_string()))?;
+
+        let npc = game_state
+            .entities
+            .get(&self.npc_id)
+            .and_then(crate::ConcreteEntity::as_npc)
+            .ok_or_else(|| ThatchError::InvalidAction("There is nobody to talk to".to_string()))?;
+        if npc.position.manhattan_distance(player.position()) > 1 {
+            return Err(ThatchError::InvalidAction(format!(
+                "The {} is too far away to talk to",
+                npc.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Talk {
+            npc_id: self.npc_id,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for praying at an altar under or next to the player.
+///
+/// The floor's deity grants one [`crate::PrayerBoon`] and then ignores further
+/// prayers for [`crate::PRAYER_TIMEOUT_TURNS`] turns. Praying before then
+/// still takes a turn, but nothing answers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrayAction {
+    pub actor: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl PrayAction {
+    /// Creates a new pray action.
+    pub fn new(actor: EntityId) -> Self {
+        Self {
+            actor,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Picks the boon the player needs most, if there is one to give.
+    fn boon(&self, game_state: &crate::GameState) -> Option<crate::PrayerBoon> {
+        let player = game_state.get_player()?;
+        if player.stats.health * 2 < player.stats.max_health {
+            return Some(crate::PrayerBoon::Healing);
+        }
+        if game_state.carried_items.values().any(|item| item.cursed) {
+            return Some(crate::PrayerBoon::Purification);
+        }
+        player
+            .get_equipped_item("weapon")
+            .and_then(|id| game_state.carried_items.get(id))
+            .filter(|weapon| weapon.enchantment < crate::MAX_BLESSING)
+            .map(|_| crate::PrayerBoon::Blessing)
+    }
+}
+
+impl Action for PrayAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+        let deity = game_state.floor_deity();
+        let message = |text: String, importance| GameEvent::Message {
+            text,
+            importance,
+            channel: crate::MessageChannel::Narrative,
+        };
+        let mut events = vec![message(
+            format!("You kneel and pray to {}.", deity.title()),
+            crate::MessageImportance::Normal,
+        )];
+
+        let player = game_state
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        if player.prayer_ready_turn > game_state.turn_number {
+            events.push(message(
+                format!("{} does not answer.", deity.name),
+                crate::MessageImportance::Info,
+            ));
+            return Ok(events);
+        }
+
+        let Some(boon) = self.boon(game_state) else {
+            events.push(message(
+                format!("You feel that {} is well pleased with you.", deity.name),
+                crate::MessageImportance::Normal,
+            ));
+            return Ok(events);
+        };
+        events.push(message(
+            boon.message(&deity.name),
+            crate::MessageImportance::Important,
+        ));
+        match boon {
+            crate::PrayerBoon::Healing => {
+                let amount = player.stats.max_health - player.stats.health;
+                events.push(GameEvent::EntityHealed {
+                    entity_id: self.actor,
+                    amount,
+                    source: None,
+                });
+            }
+            crate::PrayerBoon::Purification => {
+                game_state.remove_curses();
+            }
+            crate::PrayerBoon::Blessing => {
+                let weapon = player.get_equipped_item("weapon").copied();
+                if let Some(weapon) = weapon {
+                    game_state.apply_enchantment(weapon, false);
+                    if let Some(item) = game_state.carried_items.get_mut(&weapon) {
+                        item.enchantment += 1;
+                        item.appraised = true;
+                    }
+                    game_state.apply_enchantment(weapon, true);
+                }
+            }
+        }
+
+        let turn = game_state.turn_number;
+        if let Some(player) = game_state.get_player_mut() {
+            player.prayer_ready_turn = turn + crate::PRAYER_TIMEOUT_TURNS;
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Only the player can pray".to_string()))?;
+        game_state
+            .world
+            .current_level()
+            .and_then(|level| crate::altar_within_reach(level, player.position()))
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("There is no altar here to pray at".to_string())
+            })?;
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Pray
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for throwing an item from the player's pack toward a position.
+///
+/// The item flies in a straight line and stops at the first wall or creature
+/// in its way, hurting the creature, then lands on the floor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThrowAction {
+    pub actor: EntityId,
+    pub item_id: EntityId,
+    pub target: Position,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl ThrowAction {
+    /// Creates a new throw action.
+    pub fn new(actor: EntityId, item_id: EntityId, target: Position) -> Self {
+        Self {
+            actor,
+            item_id,
+            target,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Aims the first weapon in the player's pack at the nearest visible hostile.
+    pub fn at_nearest_hostile(game_state: &crate::GameState) -> ThatchResult<Self> {
+        let player = game_state
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let item_id = player
+            .inventory
+            .iter()
+            .copied()
+            .find(|id| {
+                game_state
+                    .carried_items
+                    .get(id)
+                    .is_some_and(|item| matches!(item.item_type, crate::ItemType::Weapon(_)))
+            })
+            .ok_or_else(|| ThatchError::InvalidAction("You have nothing to throw".to_string()))?;
+        Self::item_at_nearest_hostile(game_state, item_id)
+    }
+
+    /// Aims an item from the player's pack at the nearest visible hostile.
+    pub fn item_at_nearest_hostile(
+        game_state: &crate::GameState,
+        item_id: EntityId,
+    ) -> ThatchResult<Self> {
+        let player = game_state
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let target = game_state
+            .nearest_visible_hostile(player.position(), THROW_RANGE)
+            .and_then(|id| game_state.get_entity_position(id))
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("There is nothing in range to throw at".to_string())
+            })?;
+
+        Ok(Self::new(player.id(), item_id, target))
+    }
+}
+
+/// Follows something thrown or shot from `origin` toward `target`, returning
+/// where it lands and the creature it hits, if any.
+fn projectile_flight(
+    game_state: &crate::GameState,
+    origin: Position,
+    target: Position,
+) -> (Position, Option<EntityId>) {
+    let Some(level) = game_state.world.current_level() else {
+        return (origin, None);
+    };
+
+    let mut landing = origin;
+    for position in origin.line_to(target).into_iter().skip(1) {
+        if !level.is_passable(position) {
+            break;
+        }
+        landing = position;
+        if let Some(hit) = game_state
+            .get_entity_at_position(position)
+            .filter(|&id| game_state.is_entity_alive(id))
+        {
+            return (landing, Some(hit));
+        }
+    }
+    (landing, None)
+}
+
+impl Action for ThrowAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let origin = game_state
+            .get_entity_position(self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Actor has no position".to_string()))?;
+        let (landing, hit) = projectile_flight(game_state, origin, self.target);
+        let mut item = game_state.take_item_from_player(self.item_id)?;
+
+        let mut events = vec![GameEvent::Message {
+            text: format!("You throw the {}.", game_state.item_display_name(&item)),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Combat,
+        }];
+        if let Some(target) = hit {
+            let damage = match item.item_type {
+                crate::ItemType::Weapon(_) => THROWN_WEAPON_DAMAGE,
+                _ => THROWN_ITEM_DAMAGE,
+            };
+            events.push(GameEvent::EntityDamaged {
+                entity_id: target,
+                damage,
+                source: Some(self.actor),
+            });
+        }
+        events.push(GameEvent::ItemDropped {
+            item_id: self.item_id,
+            dropper_id: self.actor,
+            position: landing,
+        });
+
+        item.position = landing;
+        game_state.drop_item_on_ground(item)?;
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can throw items".to_string())
+            })?;
+        if !player.inventory.contains(&self.item_id) {
+            return Err(ThatchError::InvalidAction(
+                "That item is not in your pack".to_string(),
+            ));
+        }
+
+        let distance = player.position().manhattan_distance(self.target);
+        if distance == 0 || distance > THROW_RANGE {
+            return Err(ThatchError::InvalidAction(
+                "You cannot throw that far".to_string(),
+            ));
+        }
+        let visible = game_state
+            .world
+            .current_level()
+            .and_then(|level| level.get_tile(self.target))
+            .is_some_and(|tile| tile.is_visible());
+        if !visible {
+            return Err(ThatchError::InvalidAction(
+                "You cannot see where to throw".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Throw {
+            item_id: self.item_id,
+            target: self.target,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for dropping an item from the player's pack on the floor at their feet.
+///
+/// Worn items must be taken off first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropItemAction {
+    pub actor: EntityId,
+    pub item_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl DropItemAction {
+    /// Creates a new drop item action.
+    pub fn new(actor: EntityId, item_id: EntityId) -> Self {
+        Self {
+            actor,
+            item_id,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for DropItemAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let position = game_state
+            .get_entity_position(self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Actor has no position".to_string()))?;
+        let mut item = game_state.take_item_from_player(self.item_id)?;
+        let text = format!("You drop the {}.", game_state.item_display_name(&item));
+        item.position = position;
+        game_state.drop_item_on_ground(item)?;
+
+        Ok(vec![
+            GameEvent::ItemDropped {
+                item_id: self.item_id,
+                dropper_id: self.actor,
+                position,
+            },
+            GameEvent::Message {
+                text,
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            },
+        ])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can drop items".to_string())
+            })?;
+        if !player.inventory.contains(&self.item_id) {
+            return Err(ThatchError::InvalidAction(
+                "That item is not in your pack".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::DropItem {
+            item_id: self.item_id,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Farthest a launcher can be aimed, in tiles walked.
+pub const FIRE_RANGE: u32 = 8;
+
+/// Chance a shot arrow or bolt breaks rather than landing where it stopped.
+pub const AMMO_BREAK_CHANCE: f64 = 0.3;
+
+/// Action for shooting the ammunition in the player's quiver from the
+/// launcher they have ready.
+///
+/// Each shot uses up one arrow or bolt. It flies like a thrown item, then
+/// lands on the floor to be picked up again, unless it breaks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FireAction {
+    pub actor: EntityId,
+    pub target: Position,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl FireAction {
+    /// Creates a new fire action.
+    pub fn new(actor: EntityId, target: Position) -> Self {
+        Self {
+            actor,
+            target,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Aims at the nearest visible hostile.
+    pub fn at_nearest_hostile(game_state: &crate::GameState) -> ThatchResult<Self> {
+        let player = game_state
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let target = game_state
+            .nearest_visible_hostile(player.position(), FIRE_RANGE)
+            .and_then(|id| game_state.get_entity_position(id))
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("There is nothing in range to shoot at".to_string())
+            })?;
+
+        Ok(Self::new(player.id(), target))
+    }
+}
+
+impl Action for FireAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let origin = game_state
+            .get_entity_position(self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Actor has no position".to_string()))?;
+        let (landing, hit) = projectile_flight(game_state, origin, self.target);
+        let mut shot = game_state
+            .get_player_mut()
+            .and_then(crate::PlayerCharacter::take_from_quiver)
+            .ok_or_else(|| ThatchError::InvalidAction("Your quiver is empty".to_string()))?;
+        let name = game_state.item_display_name(&shot);
+
+        let mut events = vec![GameEvent::Message {
+            text: format!("You shoot the {}.", name),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Combat,
+        }];
+        if let (Some(target), crate::ItemType::Ammo(ammo)) = (hit, &shot.item_type) {
+            events.push(GameEvent::EntityDamaged {
+                entity_id: target,
+                damage: ammo.damage(),
+                source: Some(self.actor),
+            });
+        }
+
+        if game_state
+            .rng
+            .gen_bool(crate::RngStream::Combat, AMMO_BREAK_CHANCE)
+        {
+            events.push(GameEvent::Message {
+                text: format!("The {} breaks.", name),
+                importance: crate::MessageImportance::Info,
+                channel: crate::MessageChannel::Combat,
+            });
+        } else {
+            events.push(GameEvent::ItemDropped {
+                item_id: shot.id,
+                dropper_id: self.actor,
+                position: landing,
+            });
+            shot.position = landing;
+            game_state.drop_item_on_ground(shot)?;
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| ThatchError::InvalidAction("Only the player can shoot".to_string()))?;
+        let (_, ammo) = game_state.player_launcher().ok_or_else(|| {
+            ThatchError::InvalidAction("You have nothing to shoot with".to_string())
+        })?;
+        if game_state.shots_ready() == 0 {
+            return Err(ThatchError::InvalidAction(format!(
+                "You have no {} to shoot",
+                ammo.plural_name()
+            )));
+        }
+
+        let distance = player.position().manhattan_distance(self.target);
+        if distance == 0 || distance > FIRE_RANGE {
+            return Err(ThatchError::InvalidAction(
+                "You cannot shoot that far".to_string(),
+            ));
+        }
+        let visible = game_state
+            .world
+            .current_level()
+            .and_then(|level| level.get_tile(self.target))
+            .is_some_and(|tile| tile.is_visible());
+        if !visible {
+            return Err(ThatchError::InvalidAction(
+                "You cannot see where to shoot".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Fire {
+            target: self.target,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Health restored by drinking a potion of healing.
+pub const POTION_HEALING: u32 = 30;
+
+/// Mana restored by drinking a potion of mana.
+pub const POTION_MANA: u32 = 30;
+
+/// Action for drinking, reading, eating or zapping a consumable from the
+/// player's pack.
+///
+/// Using an item uses it up and identifies its kind. Wands instead spend a
+/// charge casting their spell, and are only used up with the last charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UseItemAction {
+    pub actor: EntityId,
+    pub item_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl UseItemAction {
+    /// Creates a new use item action.
+    pub fn new(actor: EntityId, item_id: EntityId) -> Self {
+        Self {
+            actor,
+            item_id,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Identifies the first unknown item in the player's pack, or failing
+    /// that appraises the first piece of gear whose curse and enchantment the
+    /// player does not know yet.
+    fn identify_from_pack(&self, game_state: &mut crate::GameState) -> String {
+        let unknown = game_state.get_player().and_then(|player| {
+            player
+                .inventory
+                .iter()
+                .filter_map(|id| game_state.carried_items.get(id))
+                .find(|item| !game_state.identification.is_identified(&item.name))
+                .cloned()
+        });
+
+        if let Some(item) = unknown {
+            let appearance = game_state.item_display_name(&item);
+            game_state.identification.identify(&item.name);
+            return format!("The {} is a {}.", appearance, item.name);
+        }
+
+        let unappraised = game_state.get_player().and_then(|player| {
+            player
+                .inventory
+                .iter()
+                .chain(player.equipment.values())
+                .copied()
+                .find(|id| {
+                    game_state.carried_items.get(id).is_some_and(|item| {
+                        !item.appraised && item.item_type.equip_slot().is_some()
+                    })
+                })
+        });
+        let Some(item) = unappraised.and_then(|id| game_state.carried_items.get_mut(&id)) else {
+            return "You have nothing left to identify.".to_string();
+        };
+        item.appraised = true;
+        let item = item.clone();
+        let name = game_state.item_display_name(&item);
+        if item.cursed {
+            format!("Your {} radiates malice. It is cursed!", name)
+        } else {
+            format!("Your {} is free of curses.", name)
+        }
+    }
+
+    /// Zaps a wand from the pack at the target of its spell, spending a charge.
+    fn zap(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        let item = game_state
+            .carried_items
+            .get(&self.item_id)
+            .cloned()
+            .ok_or_else(|| ThatchError::InvalidAction("You cannot use that".to_string()))?;
+        let message = |text: String| GameEvent::Message {
+            text,
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Combat,
+        };
+        let mut events = vec![message(format!("You zap the {}.", item.name))];
+
+        match crate::Wand::by_name(&item.name).and_then(crate::Wand::spell) {
+            Some(spell) => {
+                let cast = CastSpellAction::new(self.actor, spell.name.to_string());
+                let target = cast.target(spell, game_state).ok_or_else(|| {
+                    ThatchError::InvalidAction("There is nothing in range to target".to_string())
+                })?;
+                events.extend(cast.effects(spell, target, game_state)?);
+            }
+            None => events.push(message("Nothing happens.".to_string())),
+        }
+
+        let charges = game_state
+            .carried_items
+            .get_mut(&self.item_id)
+            .and_then(|item| item.charges.as_mut())
+            .map(|charges| {
+                *charges = charges.saturating_sub(1);
+                *charges
+            });
+        if charges.unwrap_or(0) == 0 {
+            game_state.take_item_from_player(self.item_id)?;
+            events.push(message(format!("The {} crumbles to dust.", item.name)));
+        }
+        Ok(events)
+    }
+}
+
+impl Action for UseItemAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+        let is_wand = game_state
+            .carried_items
+            .get(&self.item_id)
+            .is_some_and(|item| {
+                item.item_type == crate::ItemType::Consumable(crate::ConsumableType::Wand)
+            });
+        if is_wand {
+            return self.zap(game_state);
+        }
+
+        let item = game_state.take_item_from_player(self.item_id)?;
+        let crate::ItemType::Consumable(kind) = item.item_type.clone() else {
+            return Err(ThatchError::InvalidAction(
+                "You cannot use that".to_string(),
+            ));
+        };
+        let verb = match kind {
+            crate::ConsumableType::HealthPotion | crate::ConsumableType::ManaPotion => "drink",
+            crate::ConsumableType::Scroll => "read",
+            crate::ConsumableType::Food => "eat",
+            crate::ConsumableType::Wand | crate::ConsumableType::Custom(_) => "use",
+        };
+
+        let message = |text: String| GameEvent::Message {
+            text,
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Exploration,
+        };
+        let mut events = vec![message(format!(
+            "You {} the {}.",
+            verb,
+            game_state.item_display_name(&item)
+        ))];
+        if game_state.identification.identify(&item.name) {
+            events.push(message(format!("It was a {}.", item.name)));
+        }
+
+        match kind {
+            crate::ConsumableType::HealthPotion => events.push(GameEvent::EntityHealed {
+                entity_id: self.actor,
+                amount: POTION_HEALING,
+                source: Some(self.actor),
+            }),
+            crate::ConsumableType::ManaPotion => {
+                if let Some(player) = game_state.get_player_mut() {
+                    player.stats.restore_mana(POTION_MANA);
+                }
+                events.push(message("You feel your magic return.".to_string()));
+            }
+            crate::ConsumableType::Scroll if item.name == crate::IDENTIFY_SCROLL => {
+                events.push(message(self.identify_from_pack(game_state)));
+            }
+            crate::ConsumableType::Scroll if item.name == crate::PORTAL_SCROLL => {
+                events.extend(game_state.open_portal(self.actor)?);
+            }
+            crate::ConsumableType::Scroll if item.name == crate::TELEPORT_SCROLL => {
+                events.extend(game_state.teleport_entity(self.actor)?);
+            }
+            crate::ConsumableType::Scroll if item.name == crate::MAGIC_MAPPING_SCROLL => {
+                if let Some(level) = game_state.world.current_level_mut() {
+                    level.reveal_map();
+                }
+                // Survey the newly mapped floor next time the player looks around
+                game_state.field_of_view = None;
+                events.push(message(
+                    "A map of the floor coalesces in your mind!".to_string(),
+                ));
+            }
+            crate::ConsumableType::Scroll if item.name == crate::REMOVE_CURSE_SCROLL => {
+                let text = if game_state.remove_curses() > 0 {
+                    "You feel a malevolent aura lift from your belongings."
+                } else {
+                    "You feel as if someone is watching over you."
+                };
+                events.push(message(text.to_string()));
+            }
+            crate::ConsumableType::Food => {
+                events.push(message("That hit the spot.".to_string()));
+            }
+            _ => events.push(message("Nothing happens.".to_string())),
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can use items".to_string())
+            })?;
+        if !player.inventory.contains(&self.item_id) {
+            return Err(ThatchError::InvalidAction(
+                "That item is not in your pack".to_string(),
+            ));
+        }
+
+        let usable = game_state
+            .carried_items
+            .get(&self.item_id)
+            .is_some_and(|item| matches!(item.item_type, crate::ItemType::Consumable(_)));
+        if !usable {
+            return Err(ThatchError::InvalidAction(
+                "You cannot use that".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::UseItem {
+            item_id: self.item_id,
+            target: None,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for putting on a weapon or piece of armor from the player's pack.
+///
+/// Whatever was worn in the same slot goes back in the pack, unless it is
+/// cursed, in which case it will not come off. Putting on a cursed item
+/// reveals the curse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquipItemAction {
+    pub actor: EntityId,
+    pub item_id: EntityId,
+    pub slot: String,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl EquipItemAction {
+    /// Creates a new equip item action, wearing the item in `slot`.
+    pub fn new(actor: EntityId, item_id: EntityId, slot: String) -> Self {
+        Self {
+            actor,
+            item_id,
+            slot,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for EquipItemAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+        let mut item = game_state.take_item_from_player(self.item_id)?;
+        if item.cursed {
+            item.appraised = true;
+        }
+        let name = game_state.item_display_name(&item);
+        let cursed = item.cursed;
+        game_state.give_item_to_player(item, true)?;
+
+        let message = |text: String, importance| GameEvent::Message {
+            text,
+            importance,
+            channel: crate::MessageChannel::Exploration,
+        };
+        let mut events = vec![message(
+            format!("You put on the {}.", name),
+            crate::MessageImportance::Normal,
+        )];
+        if cursed {
+            events.push(message(
+                "It is cursed! It clings to you.".to_string(),
+                crate::MessageImportance::Important,
+            ));
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can equip items".to_string())
+            })?;
+        if !player.inventory.contains(&self.item_id) {
+            return Err(ThatchError::InvalidAction(
+                "That item is not in your pack".to_string(),
+            ));
+        }
+        let fits = game_state
+            .carried_items
+            .get(&self.item_id)
+            .and_then(|item| item.item_type.equip_slot())
+            == Some(self.slot.as_str());
+        if !fits {
+            return Err(ThatchError::InvalidAction(
+                "You cannot wear that there".to_string(),
+            ));
+        }
+
+        let worn = player
+            .get_equipped_item(&self.slot)
+            .and_then(|id| game_state.carried_items.get(id))
+            .filter(|item| item.cursed);
+        if let Some(worn) = worn {
+            return Err(ThatchError::InvalidAction(format!(
+                "Your {} is cursed and will not come off!",
+                worn.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::EquipItem {
+            item_id: self.item_id,
+            slot: self.slot.clone(),
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for taking off whatever the player wears in an equipment slot and
+/// putting it in the pack.
+///
+/// Cursed items will not come off until the curse is lifted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnequipItemAction {
+    pub actor: EntityId,
+    pub slot: String,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl UnequipItemAction {
+    /// Creates a new unequip item action.
+    pub fn new(actor: EntityId, slot: String) -> Self {
+        Self {
+            actor,
+            slot,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for UnequipItemAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+        let player = game_state
+            .get_player_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let item_id = player
+            .unequip_item(&self.slot)
+            .ok_or_else(|| ThatchError::InvalidAction("You are not wearing that".to_string()))?;
+        player.add_to_inventory(item_id)?;
+        game_state.apply_enchantment(item_id, false);
+
+        let name = game_state
+            .carried_items
+            .get(&item_id)
+            .map(|item| game_state.item_display_name(item))
+            .unwrap_or_default();
+        Ok(vec![GameEvent::Message {
+            text: format!("You take off the {}.", name),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Exploration,
+        }])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can unequip items".to_string())
+            })?;
+        let item = player
+            .get_equipped_item(&self.slot)
+            .and_then(|id| game_state.carried_items.get(id))
+            .ok_or_else(|| ThatchError::InvalidAction("You are not wearing that".to_string()))?;
+        if item.cursed {
+            return Err(ThatchError::InvalidAction(format!(
+                "Your {} is cursed and will not come off!",
+                item.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::UnequipItem {
+            slot: self.slot.clone(),
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for opening a chest next to or under the player.
+///
+/// A locked chest uses up a key from the pack if the player has one, and
+/// otherwise takes a lockpicking roll; a failed roll still spends the turn. Any
+/// trap on the chest goes off when it opens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenContainerAction {
+    pub actor: EntityId,
+    pub container_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl OpenContainerAction {
+    /// Creates a new open container action.
+    pub fn new(actor: EntityId, container_id: EntityId) -> Self {
+        Self {
+            actor,
+            container_id,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Finds a chest key in the player's pack.
+    fn find_key(game_state: &crate::GameState) -> Option<EntityId> {
+        game_state
+            .get_player()?
+            .inventory
+            .iter()
+            .copied()
+            .find(|id| {
+                game_state
+                    .carried_items
+                    .get(id)
+                    .is_some_and(|item| item.name == crate::CHEST_KEY)
+            })
+    }
+
+    /// Gets the chance of the player picking a lock without a key.
+    fn lockpick_chance(game_state: &crate::GameState) -> f64 {
+        match game_state.get_player().map(|player| player.class) {
+            Some(crate::CharacterClass::Rogue) => crate::ROGUE_LOCKPICK_CHANCE,
+            _ => crate::LOCKPICK_CHANCE,
+        }
+    }
+}
+
+impl Action for OpenContainerAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let message = |text: &str| GameEvent::Message {
+            text: text.to_string(),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Exploration,
+        };
+        let level = game_state
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        let container = level
+            .containers
+            .iter()
+            .find(|container| container.id == self.container_id)
+            .ok_or_else(|| ThatchError::InvalidAction("There is no chest there".to_string()))?;
+
+        let mut events = Vec::new();
+        if container.locked {
+            if let Some(key_id) = Self::find_key(game_state) {
+                game_state.take_item_from_player(key_id)?;
+                events.push(message("You unlock the chest with your key."));
+            } else {
+                let chance = Self::lockpick_chance(game_state);
+                if !game_state.rng.gen_bool(crate::RngStream::Loot, chance) {
+                    return Ok(vec![message("You fail to pick the lock.")]);
+                }
+                events.push(message("You pick the lock."));
+            }
+        }
+
+        let container = game_state
+            .world
+            .current_level_mut()
+            .and_then(|level| level.container_mut(self.container_id))
+            .ok_or_else(|| ThatchError::InvalidAction("There is no chest there".to_string()))?;
+        container.locked = false;
+        container.opened = true;
+        match container.trap.take() {
+            Some(crate::ChestTrap::Needle) => {
+                events.push(message("A needle springs from the lock!"));
+                events.push(GameEvent::EntityDamaged {
+                    entity_id: self.actor,
+                    damage: crate::NEEDLE_TRAP_DAMAGE,
+                    source: None,
+                });
+            }
+            Some(crate::ChestTrap::Flame) => {
+                events.push(message("Flames burst from the chest!"));
+                events.push(GameEvent::StatusApplied {
+                    entity_id: self.actor,
+                    kind: crate::StatusKind::Burning,
+                    turns: crate::FLAME_TRAP_TURNS,
+                });
+            }
+            None => {}
+        }
+        if container.contents.is_empty() {
+            events.push(message("The chest is empty."));
+        }
+        Ok(events)
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can open chests".to_string())
+            })?;
+
+        let container = game_state
+            .world
+            .current_level()
+            .and_then(|level| {
+                level
+                    .containers
+                    .iter()
+                    .find(|container| container.id == self.container_id)
+            })
+            .filter(|container| {
+                container.position.manhattan_distance(player.position()) <= crate::CONTAINER_REACH
+            })
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("There is no chest within reach".to_string())
+            })?;
+        if container.opened {
+            return Err(ThatchError::InvalidAction(
+                "The chest is already open".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::OpenContainer {
+            container_id: self.container_id,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for taking an item out of an open container within reach.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeFromContainerAction {
+    pub actor: EntityId,
+    pub container_id: EntityId,
+    pub item_id: EntityId,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl TakeFromContainerAction {
+    /// Creates a new take from container action.
+    pub fn new(actor: EntityId, container_id: EntityId, item_id: EntityId) -> Self {
+        Self {
+            actor,
+            container_id,
+            item_id,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for TakeFromContainerAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        let item = game_state
+            .world
+            .current_level_mut()
+            .and_then(|level| level.container_mut(self.container_id))
+            .and_then(|container| container.take(self.item_id))
+            .ok_or_else(|| ThatchError::InvalidAction("That item is not there".to_string()))?;
+        let text = format!("You take the {}.", game_state.item_display_name(&item));
+        let item_id = game_state.give_item_to_player(item, false)?;
+
+        Ok(vec![
+            GameEvent::ItemPickedUp {
+                item_id,
+                picker_id: self.actor,
+            },
+            GameEvent::Message {
+                text,
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            },
+        ])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        let player = game_state
+            .get_player()
+            .filter(|player| player.id() == self.actor)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("Only the player can take items".to_string())
+            })?;
+        if !player.can_pick_up_item() {
+            return Err(ThatchError::InvalidAction("Your pack is full".to_string()));
+        }
+
+        let reachable = game_state
+            .container_within_reach(player.position())
+            .filter(|container| container.id == self.container_id && container.opened)
+            .is_some_and(|container| container.contents.iter().any(|i| i.id == self.item_id));
+        if !reachable {
+            return Err(ThatchError::InvalidAction(
+                "That item is not there".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::TakeFromContainer {
+            container_id: self.container_id,
+            item_id: self.item_id,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        100 // Standard time cost
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Action for examining a position without spending any game time.
+///
+/// Produces a single message describing the tile, anything standing on it,
+/// and any room flavor text, so it can be shown through the normal message log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookAction {
+    pub actor: EntityId,
+    pub position: Position,
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl LookAction {
+    /// Creates a new look action.
+    pub fn new(actor: EntityId, position: Position) -> Self {
+        Self {
+            actor,
+            position,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Action for LookAction {
+    fn execute(&self, game_state: &mut crate::GameState) -> ThatchResult<Vec<GameEvent>> {
+        self.validate(game_state)?;
+
+        Ok(vec![GameEvent::Message {
+            text: game_state.describe_position(self.position),
+            importance: crate::MessageImportance::Info,
+            channel: crate::MessageChannel::Exploration,
+        }])
+    }
+
+    fn validate(&self, game_state: &crate::GameState) -> ThatchResult<()> {
+        if !game_state.entity_exists(self.actor) {
+            return Err(ThatchError::InvalidAction(
+                "Actor entity does not exist".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn actor(&self) -> EntityId {
+        self.actor
+    }
+
+    fn action_type(&self) -> ActionType {
+        ActionType::Look {
+            position: self.position,
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn time_cost(&self) -> u32 {
+        0 // Looking around is free
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+/// Concrete action types for serialization and queue management.
+///
+/// This enum represents all concrete action implementations that can be
+/// stored in the action queue and serialized for save/load and MCP.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConcreteAction {
+    Move(MoveAction),
+    ForcedMove(ForcedMoveAction),
+    Attack(AttackAction),
+    Wait(WaitAction),
+    Shout(ShoutAction),
+    UseStairs(UseStairsAction),
+    Search(SearchAction),
+    Door(DoorAction),
+    Rest(RestAction),
+    Ability(AbilityAction),
+    CastSpell(CastSpellAction),
+    PickUp(PickUpAction),
+    Butcher(ButcherAction),
+    Talk(TalkAction),
+    Pray(PrayAction),
+    Throw(ThrowAction),
+    Drop(DropItemAction),
+    Fire(FireAction),
+    UseItem(UseItemAction),
+    Equip(EquipItemAction),
+    Unequip(UnequipItemAction),
+    OpenContainer(OpenContainerAction),
+    TakeFromContainer(TakeFromContainerAction),
+}
+
+impl ConcreteAction {
+    /// Executes the concrete action.
+    pub fn execute(
+        &self,
+        game_state: &mut crate::GameState,
+    ) -> crate::ThatchResult<Vec<crate::GameEvent>> {
+        match self {
+            Self::Move(action) => action.execute(game_state),
+            Self::ForcedMove(action) => action.execute(game_state),
+            Self::Attack(action) => action.execute(game_state),
+            Self::Wait(action) => action.execute(game_state),
+            Self::Shout(action) => action.execute(game_state),
+            Self::UseStairs(action) => action.execute(game_state),
+            Self::Search(action) => action.execute(game_state),
+            Self::Door(action) => action.execute(game_state),
+            Self::Rest(action) => action.execute(game_state),
+            Self::Ability(action) => action.execute(game_state),
+            Self::CastSpell(action) => action.execute(game_state),
+            Self::PickUp(action) => action.execute(game_state),
+            Self::Butcher(action) => action.execute(game_state),
+            Self::Talk(action) => action.execute(game_state),
+            Self::Pray(action) => action.execute(game_state),
+            Self::Throw(action) => action.execute(game_state),
+            Self::Drop(action) => action.execute(game_state),
+            Self::Fire(action) => action.execute(game_state),
+            Self::UseItem(action) => action.execute(game_state),
+            Self::Equip(action) => action.execute(game_state),
+            Self::Unequip(action) => action.execute(game_state),
+            Self::OpenContainer(action) => action.execute(game_state),
+            Self::TakeFromContainer(action) => action.execute(game_state),
+        }
+    }
+
+    /// Gets the action type.
+    #[must_use]
+    pub fn action_type(&self) -> ActionType {
+        match self {
+            Self::Move(action) => action.action_type(),
+            Self::ForcedMove(action) => action.action_type(),
+            Self::Attack(action) => action.action_type(),
+            Self::Wait(action) => action.action_type(),
+            Self::Shout(action) => action.action_type(),
+            Self::UseStairs(action) => action.action_type(),
+            Self::Search(action) => action.action_type(),
+            Self::Door(action) => action.action_type(),
+            Self::Rest(action) => action.action_type(),
+            Self::Ability(action) => action.action_type(),
+            Self::CastSpell(action) => action.action_type(),
+            Self::PickUp(action) => action.action_type(),
+            Self::Butcher(action) => action.action_type(),
+            Self::Talk(action) => action.action_type(),
+            Self::Pray(action) => action.action_type(),
+            Self::Throw(action) => action.action_type(),
+            Self::Drop(action) => action.action_type(),
+            Self::Fire(action) => action.action_type(),
+            Self::UseItem(action) => action.action_type(),
+            Self::Equip(action) => action.action_type(),
+            Self::Unequip(action) => action.action_type(),
+            Self::OpenContainer(action) => action.action_type(),
+            Self::TakeFromContainer(action) => action.action_type(),
+        }
+    }
+
+    /// Gets the entity ID that performs this action.
+    #[must_use]
+    pub fn actor(&self) -> EntityId {
+        match self {
+            Self::Move(action) => action.actor(),
+            Self::ForcedMove(action) => action.actor(),
+            Self::Attack(action) => action.actor(),
+            Self::Wait(action) => action.actor(),
+            Self::Shout(action) => action.actor(),
+            Self::UseStairs(action) => action.actor(),
+            Self::Search(action) => action.actor(),
+            Self::Door(action) => action.actor(),
+            Self::Rest(action) => action.actor(),
+            Self::Ability(action) => action.actor(),
+            Self::CastSpell(action) => action.actor(),
+            Self::PickUp(action) => action.actor(),
+            Self::Butcher(action) => action.actor(),
+            Self::Talk(action) => action.actor(),
+            Self::Pray(action) => action.actor(),
+            Self::Throw(action) => action.actor(),
+            Self::Drop(action) => action.actor(),
+            Self::Fire(action) => action.actor(),
+            Self::UseItem(action) => action.actor(),
+            Self::Equip(action) => action.actor(),
+            Self::Unequip(action) => action.actor(),
+            Self::OpenContainer(action) => action.actor(),
+            Self::TakeFromContainer(action) => action.actor(),
+        }
+    }
+}
+
+/// Action queue for managing turn order and action execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionQueue {
     /// Queued actions awaiting execution
     pending_actions: Vec<ConcreteAction>,
     /// Actions currently being processed
@@ -716,6 +3539,33 @@ mod tests {
         assert_eq!(action.time_cost(), 150);
     }
 
+    #[test]
+    fn test_attack_damage_replays_with_same_seed() {
+        let damage_rolls = |seed| {
+            let mut game_state = crate::GameState::new(seed);
+            let attacker = game_state
+                .initialize_player("Attacker".to_string(), Position::new(5, 5))
+                .unwrap();
+            let dummy = crate::PlayerCharacter::new("Dummy".to_string(), Position::new(6, 5));
+            let target = game_state
+                .add_entity(crate::ConcreteEntity::Player(dummy))
+                .unwrap();
+
+            let attack = AttackAction::new(attacker, target);
+            (0..10)
+                .flat_map(|_| attack.execute(&mut game_state).unwrap())
+                .filter_map(|event| match event {
+                    GameEvent::EntityDamaged { damage, .. } => Some(damage),
+                    _ => None,
+                })
+                .collect::<Vec<u32>>()
+        };
+
+        let rolls = damage_rolls(77);
+        assert!(!rolls.is_empty());
+        assert_eq!(rolls, damage_rolls(77));
+    }
+
     #[test]
     fn test_wait_action_creation() {
         let actor = new_entity_id();
@@ -726,11 +3576,570 @@ mod tests {
         assert_eq!(action.time_cost(), 100);
     }
 
+    #[test]
+    fn test_look_action_creation() {
+        let actor = new_entity_id();
+        let position = Position::new(3, 4);
+        let action = LookAction::new(actor, position);
+
+        assert_eq!(action.actor(), actor);
+        assert_eq!(action.action_type(), ActionType::Look { position });
+        assert_eq!(action.time_cost(), 0);
+    }
+
+    #[test]
+    fn test_search_action_creation() {
+        let actor = new_entity_id();
+        let action = SearchAction::new(actor);
+
+        assert_eq!(action.actor(), actor);
+        assert_eq!(action.action_type(), ActionType::Search);
+        assert_eq!(action.time_cost(), 100);
+        assert!(SearchAction::find_chance(0) < SearchAction::find_chance(10));
+        assert!(SearchAction::find_chance(u32::MAX) <= SEARCH_MAX_CHANCE);
+    }
+
+    #[test]
+    fn test_searching_uncovers_hidden_traps() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Wary".to_string(), Position::new(5, 5))
+            .unwrap();
+        let trap = Position::new(6, 5);
+        let hidden = crate::TileEffect::Trap {
+            damage: 4,
+            found: false,
+        };
+        let level = game_state.world.current_level_mut().unwrap();
+        level.set_tile(trap, hidden.into_tile()).unwrap();
+        assert!(level.is_secret(trap));
+
+        let search = SearchAction::new(player_id);
+        let mut messages = Vec::new();
+        while game_state.world.current_level().unwrap().is_secret(trap) {
+            for event in search.execute(&mut game_state).unwrap() {
+                messages.extend(game_state.process_event(&event).unwrap());
+            }
+        }
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(
+            level.get_tile(trap).unwrap().tile_type.display_name(),
+            "Trap"
+        );
+        assert!(messages.iter().any(|event| matches!(
+            event,
+            GameEvent::Message { text, .. } if text == "You find a trap!"
+        )));
+    }
+
+    #[test]
+    fn test_rest_action_recovers() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Sleepy".to_string(), Position::new(5, 5))
+            .unwrap();
+        game_state.get_player_mut().unwrap().stats.health = 10;
+
+        let action = RestAction::new(player_id);
+        assert_eq!(action.action_type(), ActionType::Rest);
+        action.execute(&mut game_state).unwrap();
+
+        assert_eq!(
+            game_state.get_player().unwrap().stats.health,
+            10 + REST_HEALTH_PER_TURN
+        );
+    }
+
+    #[test]
+    fn test_ability_action_cooldown_and_mana() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Tired".to_string(), Position::new(5, 5))
+            .unwrap();
+        game_state.get_player_mut().unwrap().stats.health = 10;
+
+        let second_wind = AbilityAction::new(player_id, crate::ClassAbility::SecondWind);
+        for event in second_wind.execute(&mut game_state).unwrap() {
+            game_state.process_event(&event).unwrap();
+        }
+        assert!(game_state.get_player().unwrap().stats.health > 10);
+        assert!(second_wind.validate(&game_state).is_err());
+
+        game_state.turn_number += crate::ClassAbility::SecondWind.cooldown_turns();
+        assert!(second_wind.validate(&game_state).is_ok());
+
+        // A warrior cannot cast a mage's bolt
+        let bolt = AbilityAction::new(player_id, crate::ClassAbility::ArcaneBolt);
+        assert!(bolt.validate(&game_state).is_err());
+    }
+
+    #[test]
+    fn test_thrown_item_lands_and_can_be_picked_up() {
+        let mut game_state = crate::GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 3..=9 {
+                let mut tile = crate::Tile::floor();
+                tile.set_visible(true);
+                level.set_tile(Position::new(x, 5), tile).unwrap();
+            }
+        }
+        let player_id = game_state
+            .initialize_player("Thrower".to_string(), Position::new(3, 5))
+            .unwrap();
+        let dagger = crate::Item::new(
+            "Dagger".to_string(),
+            crate::ItemType::Weapon(crate::WeaponType::Dagger),
+            Position::new(3, 5),
+        );
+        let dagger_id = game_state.give_item_to_player(dagger, false).unwrap();
+
+        // Out of range, then across the corridor
+        assert!(ThrowAction::new(player_id, dagger_id, Position::new(12, 5))
+            .validate(&game_state)
+            .is_err());
+        let throw = ThrowAction::new(player_id, dagger_id, Position::new(9, 5));
+        throw.execute(&mut game_state).unwrap();
+        assert!(!game_state.carried_items.contains_key(&dagger_id));
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(
+            level
+                .ground_items
+                .top_at(Position::new(9, 5))
+                .unwrap()
+                .item
+                .id,
+            dagger_id
+        );
+
+        let landing = Position::new(9, 5);
+        game_state.set_entity_position(player_id, landing).unwrap();
+        let events = game_state
+            .process_event(&GameEvent::EntityMoved {
+                entity_id: player_id,
+                from: Position::new(3, 5),
+                to: landing,
+            })
+            .unwrap();
+        assert!(events.contains(&GameEvent::Message {
+            text: "You see here: Dagger.".to_string(),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Exploration,
+        }));
+
+        PickUpAction::new(player_id, dagger_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(game_state
+            .get_player()
+            .unwrap()
+            .inventory
+            .contains(&dagger_id));
+        assert!(PickUpAction::new(player_id, dagger_id)
+            .validate(&game_state)
+            .is_err());
+    }
+
+    #[test]
+    fn test_shots_use_up_the_quiver_and_can_be_recovered() {
+        let mut game_state = crate::GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 3..=12 {
+                let mut tile = crate::Tile::floor();
+                tile.set_visible(true);
+                level.set_tile(Position::new(x, 5), tile).unwrap();
+            }
+        }
+        let player_id = game_state
+            .initialize_player_with_class(
+                "Archer".to_string(),
+                Position::new(3, 5),
+                crate::CharacterClass::Rogue,
+            )
+            .unwrap();
+        assert_eq!(
+            game_state.player_launcher().unwrap().1,
+            crate::AmmoType::Arrow
+        );
+        assert_eq!(game_state.shots_ready(), 20);
+
+        // Out of range, then ten shots down the corridor
+        let target = Position::new(9, 5);
+        assert!(FireAction::new(player_id, Position::new(12, 5))
+            .validate(&game_state)
+            .is_err());
+        for _ in 0..10 {
+            FireAction::new(player_id, target)
+                .execute(&mut game_state)
+                .unwrap();
+        }
+        assert_eq!(game_state.shots_ready(), 10);
+        let level = game_state.world.current_level().unwrap();
+        let landed = level
+            .ground_items
+            .top_at(target)
+            .map_or(0, |stack| stack.count);
+        assert!(landed < 10, "some of the shots should have broken");
+
+        // Spent arrows are gathered up in one go, into the quiver
+        game_state.set_entity_position(player_id, target).unwrap();
+        if let Some(stack) = game_state
+            .world
+            .current_level()
+            .unwrap()
+            .ground_items
+            .top_at(target)
+        {
+            PickUpAction::new(player_id, stack.item.id)
+                .execute(&mut game_state)
+                .unwrap();
+        }
+        assert_eq!(game_state.shots_ready(), 10 + landed);
+
+        // Bolts push the arrows out of the quiver, and a bow cannot shoot them
+        let bolt = crate::Item::new(
+            "Bolt".to_string(),
+            crate::ItemType::Ammo(crate::AmmoType::Bolt),
+            target,
+        );
+        game_state.give_item_to_player(bolt, false).unwrap();
+        assert_eq!(game_state.get_player().unwrap().quiver_count(), 1);
+        assert_eq!(game_state.shots_ready(), 0);
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(
+            level.ground_items.top_at(target).unwrap().count,
+            10 + landed
+        );
+        assert!(FireAction::new(player_id, Position::new(12, 5))
+            .validate(&game_state)
+            .is_err());
+    }
+
+    #[test]
+    fn test_reading_identify_reveals_pack_item() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Reader".to_string(), Position::new(5, 5))
+            .unwrap();
+        let give = |game_state: &mut crate::GameState, name: &str, kind| {
+            let item = crate::Item::new(
+                name.to_string(),
+                crate::ItemType::Consumable(kind),
+                Position::new(5, 5),
+            );
+            game_state.give_item_to_player(item, false).unwrap()
+        };
+        let scroll_id = give(
+            &mut game_state,
+            crate::IDENTIFY_SCROLL,
+            crate::ConsumableType::Scroll,
+        );
+        let potion_id = give(
+            &mut game_state,
+            "Potion of mana",
+            crate::ConsumableType::ManaPotion,
+        );
+        assert!(!game_state.identification.is_identified("Potion of mana"));
+        let appearance = game_state.item_display_name(&game_state.carried_items[&potion_id]);
+        assert_ne!(appearance, "Potion of mana");
+
+        let events = UseItemAction::new(player_id, scroll_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(events.contains(&GameEvent::Message {
+            text: format!("The {} is a Potion of mana.", appearance),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Exploration,
+        }));
+        assert!(game_state
+            .identification
+            .is_identified(crate::IDENTIFY_SCROLL));
+        assert!(!game_state.carried_items.contains_key(&scroll_id));
+
+        game_state.get_player_mut().unwrap().stats.mana = 0;
+        UseItemAction::new(player_id, potion_id)
+            .execute(&mut game_state)
+            .unwrap();
+        let stats = &game_state.get_player().unwrap().stats;
+        assert_eq!(stats.mana, POTION_MANA.min(stats.max_mana));
+    }
+
+    #[test]
+    fn test_cast_spell_spends_mana_and_applies_status() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player_with_class(
+                "Merlin".to_string(),
+                Position::new(5, 5),
+                crate::CharacterClass::Mage,
+            )
+            .unwrap();
+        let mana = game_state.get_player().unwrap().stats.mana;
+
+        let douse = CastSpellAction::new(player_id, "Douse".to_string());
+        for event in douse.execute(&mut game_state).unwrap() {
+            game_state.process_event(&event).unwrap();
+        }
+        let player = game_state.get_player().unwrap();
+        assert!(player.statuses.has(crate::StatusKind::Soaked));
+        assert_eq!(player.stats.mana, mana - 4);
+
+        game_state.advance_turn().unwrap();
+        let regenerated = game_state.get_player().unwrap().stats.mana;
+        assert_eq!(regenerated, mana - 4 + crate::config::MANA_REGEN_PER_TURN);
+
+        // Nothing to aim at, and no mana left for anything
+        let missile = CastSpellAction::new(player_id, "Magic Missile".to_string());
+        assert!(missile.validate(&game_state).is_err());
+        game_state.get_player_mut().unwrap().stats.mana = 0;
+        assert!(douse.validate(&game_state).is_err());
+    }
+
+    #[test]
+    fn test_knockback_slams_into_walls_and_stops_in_water() {
+        let mut game_state = crate::GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 5..=9 {
+                level
+                    .set_tile(Position::new(x, 5), crate::Tile::floor())
+                    .unwrap();
+            }
+        }
+        let player_id = game_state
+            .initialize_player("Brute".to_string(), Position::new(5, 5))
+            .unwrap();
+        let mace = crate::Item::new(
+            "Mace".to_string(),
+            crate::ItemType::Weapon(crate::WeaponType::Mace),
+            Position::new(5, 5),
+        );
+        game_state.give_item_to_player(mace, true).unwrap();
+        let goblin = crate::Monster::new(crate::MonsterType::Goblin, Position::new(6, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+
+        // A mace knocks the goblin back a tile
+        AttackAction::new(player_id, goblin_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(7, 5))
+        );
+
+        // The wall at the end of the corridor cuts a long push short
+        let push = ForcedMoveAction::away_from(&game_state, player_id, goblin_id, 4).unwrap();
+        assert_eq!(push.direction, Direction::East);
+        assert_eq!(push.time_cost(), 0);
+        let events = push.execute(&mut game_state).unwrap();
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(9, 5))
+        );
+        assert!(events.contains(&GameEvent::EntityDamaged {
+            entity_id: goblin_id,
+            damage: SLAM_DAMAGE_PER_TILE * 2,
+            source: Some(player_id),
+        }));
+
+        // Water drags a push to a halt, and another entity blocks it outright
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .set_tile(
+                Position::new(8, 5),
+                crate::Tile::new(crate::TileType::Water),
+            )
+            .unwrap();
+        let back = ForcedMoveAction::new(player_id, goblin_id, Direction::West, 3);
+        let events = back.execute(&mut game_state).unwrap();
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(8, 5))
+        );
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, GameEvent::EntityDamaged { .. })));
+        back.execute(&mut game_state).unwrap();
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(6, 5))
+        );
+        assert!(back.execute(&mut game_state).unwrap().is_empty());
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(6, 5))
+        );
+    }
+
+    #[test]
+    fn test_raise_dead_turns_a_corpse_into_an_ally() {
+        let mut game_state = crate::GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 5..=9 {
+                let mut tile = crate::Tile::floor();
+                tile.set_visible(true);
+                level.set_tile(Position::new(x, 5), tile).unwrap();
+            }
+        }
+        let player_id = game_state
+            .initialize_player_with_class(
+                "Morgana".to_string(),
+                Position::new(5, 5),
+                crate::CharacterClass::Mage,
+            )
+            .unwrap();
+        let raise = CastSpellAction::new(player_id, "Raise Dead".to_string());
+        assert!(raise.validate(&game_state).is_err());
+
+        let orc = crate::Monster::new(crate::MonsterType::Orc, Position::new(8, 5));
+        let corpse = crate::corpse_of(&orc, orc.position);
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .ground_items
+            .drop_item(corpse);
+        let mana = game_state.get_player().unwrap().stats.mana;
+
+        let events = raise.execute(&mut game_state).unwrap();
+        let Some(GameEvent::EntityCreated { entity_id, .. }) = events
+            .iter()
+            .find(|event| matches!(event, GameEvent::EntityCreated { .. }))
+        else {
+            panic!("Raise Dead created nothing");
+        };
+        let ally = game_state.entities.get(entity_id).unwrap().as_entity();
+        assert_eq!(ally.name(), "Raised Orc");
+        assert!(!ally.is_hostile());
+        assert_eq!(ally.position(), Position::new(8, 5));
+        assert!(game_state
+            .world
+            .current_level()
+            .unwrap()
+            .ground_items
+            .is_empty());
+        assert_eq!(game_state.get_player().unwrap().stats.mana, mana - 15);
+    }
+
+    #[test]
+    fn test_locked_trapped_chest_opens_with_key() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Looter".to_string(), Position::new(5, 5))
+            .unwrap();
+        let ring = crate::Item::new(
+            "Silver ring".to_string(),
+            crate::ItemType::Armor(crate::ArmorType::Ring),
+            Position::new(6, 5),
+        );
+        let ring_id = ring.id;
+        let mut chest = crate::Container::chest(Position::new(6, 5), vec![ring]);
+        chest.locked = true;
+        chest.trap = Some(crate::ChestTrap::Needle);
+        let chest_id = chest.id;
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .containers
+            .push(chest);
+        let key = crate::Item::new(
+            crate::CHEST_KEY.to_string(),
+            crate::ItemType::QuestItem,
+            Position::new(5, 5),
+        );
+        let key_id = game_state.give_item_to_player(key, false).unwrap();
+
+        // Nothing can be taken before the chest is open
+        let take = TakeFromContainerAction::new(player_id, chest_id, ring_id);
+        assert!(take.validate(&game_state).is_err());
+
+        let events = OpenContainerAction::new(player_id, chest_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(events.contains(&GameEvent::EntityDamaged {
+            entity_id: player_id,
+            damage: crate::NEEDLE_TRAP_DAMAGE,
+            source: None,
+        }));
+        assert!(!game_state.carried_items.contains_key(&key_id));
+        let chest = game_state
+            .container_within_reach(Position::new(5, 5))
+            .unwrap();
+        assert!(chest.opened && !chest.locked && chest.trap.is_none());
+        assert!(OpenContainerAction::new(player_id, chest_id)
+            .validate(&game_state)
+            .is_err());
+
+        take.execute(&mut game_state).unwrap();
+        assert!(game_state
+            .get_player()
+            .unwrap()
+            .inventory
+            .contains(&ring_id));
+        assert!(take.validate(&game_state).is_err());
+    }
+
+    #[test]
+    fn test_cursed_helm_sticks_until_the_curse_is_lifted() {
+        let mut game_state = crate::GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Wearer".to_string(), Position::new(5, 5))
+            .unwrap();
+        let defense = game_state.get_player().unwrap().stats.defense;
+        let mut helm = crate::Item::new(
+            "Iron helm".to_string(),
+            crate::ItemType::Armor(crate::ArmorType::Helmet),
+            Position::new(5, 5),
+        );
+        helm.cursed = true;
+        helm.enchantment = -2;
+        let helm_id = game_state.give_item_to_player(helm, false).unwrap();
+        assert_eq!(
+            game_state.item_display_name(&game_state.carried_items[&helm_id]),
+            "Iron helm"
+        );
+
+        // Putting it on reveals the curse and the penalty
+        let equip = EquipItemAction::new(player_id, helm_id, "head".to_string());
+        let events = equip.execute(&mut game_state).unwrap();
+        assert_eq!(events.len(), 2);
+        let helm = &game_state.carried_items[&helm_id];
+        assert_eq!(game_state.item_display_name(helm), "cursed Iron helm (-2)");
+        assert_eq!(game_state.get_player().unwrap().stats.defense, defense - 2);
+        let unequip = UnequipItemAction::new(player_id, "head".to_string());
+        assert!(unequip.execute(&mut game_state).is_err());
+
+        // A scroll of remove curse lifts it, and the helm comes off
+        let scroll = crate::Item::new(
+            crate::REMOVE_CURSE_SCROLL.to_string(),
+            crate::ItemType::Consumable(crate::ConsumableType::Scroll),
+            Position::new(5, 5),
+        );
+        let scroll_id = game_state.give_item_to_player(scroll, false).unwrap();
+        UseItemAction::new(player_id, scroll_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(!game_state.carried_items[&helm_id].cursed);
+        assert_eq!(game_state.get_player().unwrap().stats.defense, defense);
+        unequip.execute(&mut game_state).unwrap();
+        assert!(game_state
+            .get_player()
+            .unwrap()
+            .inventory
+            .contains(&helm_id));
+    }
+
     #[test]
     fn test_action_result_creation() {
         let events = vec![GameEvent::Message {
             text: "Test event".to_string(),
             importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::System,
         }];
 
         let result = ActionResult::success(events.clone(), 100);
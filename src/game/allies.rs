@@ -0,0 +1,174 @@
+//! # Allies
+//!
+//! Monsters fighting on the player's side.
+//!
+//! A monster raised or summoned by the player becomes an ally, carrying an
+//! [`AllyCommand`] in place of hostility. Allies take their turns right after
+//! the player's: a following ally keeps within [`ALLY_FOLLOW_DISTANCE`] of the
+//! player and strikes any hostile that comes next to it, a staying ally holds
+//! its ground, and an ally told to attack hunts down its target before falling
+//! back in. Following allies within [`ALLY_STAIRS_RANGE`] take the stairs with
+//! the player, and whatever an ally kills counts as the player's kill.
+
+use crate::{EntityId, Level, Position};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Farthest, in tiles walked, a following ally lets the player get before
+/// catching up.
+pub const ALLY_FOLLOW_DISTANCE: u32 = 2;
+
+/// Farthest, in tiles walked, a following ally can be from the player and
+/// still take the stairs with them.
+pub const ALLY_STAIRS_RANGE: u32 = 6;
+
+/// What the player has told an ally to do.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::AllyCommand;
+///
+/// let command = AllyCommand::default();
+/// assert_eq!(command, AllyCommand::Follow);
+/// assert!(command.follows());
+/// assert_eq!(command.next(None), AllyCommand::Stay);
+/// assert_eq!(AllyCommand::Stay.next(None), AllyCommand::Follow);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AllyCommand {
+    /// Stay close to the player and follow them between floors
+    #[default]
+    Follow,
+    /// Hold position until told otherwise
+    Stay,
+    /// Go after a monster, then follow again once it is dead
+    Attack(EntityId),
+}
+
+impl AllyCommand {
+    /// Returns true if the ally keeps close to the player.
+    ///
+    /// Allies sent to attack fall back in once their target dies, so they
+    /// follow too.
+    pub fn follows(self) -> bool {
+        !matches!(self, AllyCommand::Stay)
+    }
+
+    /// Gets the command after this one, as the player cycles through them.
+    ///
+    /// Following allies are told to stay, and staying allies are sent after
+    /// `target` if there is one or told to follow if not.
+    pub fn next(self, target: Option<EntityId>) -> Self {
+        match (self, target) {
+            (AllyCommand::Follow, _) => AllyCommand::Stay,
+            (AllyCommand::Stay, Some(target)) => AllyCommand::Attack(target),
+            _ => AllyCommand::Follow,
+        }
+    }
+
+    /// Gets the message shown when the command is given.
+    pub fn message(self) -> &'static str {
+        match self {
+            AllyCommand::Follow => "You call your allies to your side.",
+            AllyCommand::Stay => "You tell your allies to stay where they are.",
+            AllyCommand::Attack(_) => "You point out a foe to your allies.",
+        }
+    }
+}
+
+/// Finds the passable tile closest to `origin` in tiles walked, other than
+/// `origin` itself, for which `taken` returns false.
+///
+/// Used to set allies down around the player when they arrive on a floor.
+/// Only tiles within [`ALLY_STAIRS_RANGE`] are considered.
+pub fn ally_spot(
+    level: &Level,
+    origin: Position,
+    taken: impl Fn(Position) -> bool,
+) -> Option<Position> {
+    let mut visited = HashSet::from([origin]);
+    let mut queue = VecDeque::from([origin]);
+    while let Some(position) = queue.pop_front() {
+        if position != origin && !taken(position) {
+            return Some(position);
+        }
+        for neighbor in position.cardinal_adjacent_positions() {
+            if level.is_passable(neighbor)
+                && origin.manhattan_distance(neighbor) <= ALLY_STAIRS_RANGE
+                && visited.insert(neighbor)
+            {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Monster, MonsterType, StairDirection};
+
+    #[test]
+    fn test_allies_follow_downstairs_and_kill_for_the_player() {
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        game_state
+            .initialize_player("Necromancer".to_string(), spawn)
+            .unwrap();
+
+        let add_ally = |game_state: &mut GameState, command| {
+            let level = game_state.world.current_level().unwrap();
+            let spot = ally_spot(level, spawn, |p| {
+                game_state.get_entity_at_position(p).is_some()
+            });
+            let mut skeleton = Monster::new(MonsterType::Skeleton, spot.unwrap());
+            skeleton.hostile = false;
+            skeleton.ally = Some(command);
+            let skeleton_id = game_state.add_entity(skeleton.into()).unwrap();
+            game_state
+                .world
+                .current_level_mut()
+                .unwrap()
+                .add_entity(skeleton_id);
+            skeleton_id
+        };
+        let follower = add_ally(&mut game_state, AllyCommand::Follow);
+        let stayer = add_ally(&mut game_state, AllyCommand::Stay);
+
+        // Only the follower takes the stairs
+        game_state.use_stairs(StairDirection::Down).unwrap();
+        let level = game_state.world.current_level().unwrap();
+        assert!(level.entities.contains(&follower));
+        assert!(!level.entities.contains(&stayer));
+        assert!(game_state.world.levels[&0].entities.contains(&stayer));
+        let arrival = game_state.get_player().unwrap().position;
+        let beside = game_state.get_entity_position(follower).unwrap();
+        assert!(beside.manhattan_distance(arrival) <= ALLY_STAIRS_RANGE);
+
+        // A hostile next to the follower is struck down, and the kill is the player's
+        let spot = ally_spot(level, beside, |p| {
+            p.manhattan_distance(beside) != 1 || game_state.get_entity_at_position(p).is_some()
+        });
+        let mut goblin = Monster::new(MonsterType::Goblin, spot.unwrap());
+        goblin.stats.health = 1;
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+
+        let mut events = game_state.advance_turn().unwrap();
+        while !events.is_empty() {
+            let event = events.remove(0);
+            events.extend(game_state.process_event(&event).unwrap());
+        }
+        assert!(!game_state.is_entity_alive(goblin_id));
+        assert_eq!(game_state.statistics.kill_list, vec!["Goblin".to_string()]);
+        assert!(game_state.credits_player(Some(follower)));
+        let follower = game_state.entities[&follower].as_monster().unwrap();
+        assert_eq!(follower.ally, Some(AllyCommand::Follow));
+    }
+}
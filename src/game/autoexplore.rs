@@ -1,13 +1,16 @@
 //! # Autoexplore Module
 //!
 //! Debug functionality for automatically exploring dungeons and navigating between levels.
+//!
+//! Autoexplore pauses itself when something needs the player's attention: a
+//! new hostile or item comes into view, health drops below
+//! [`config::AUTOEXPLORE_HEALTH_THRESHOLD`], or a critical message is shown.
 
 use crate::{
-    ConcreteAction, Direction, Entity, GameState, MoveAction, Position, StairDirection,
-    ThatchError, ThatchResult, TileType, UseStairsAction,
+    config, ConcreteAction, Direction, Entity, GameState, Interruption, InterruptionWatch,
+    PathfindingUtils, Position, StairDirection, ThatchError, ThatchResult, TileType,
+    UseStairsAction,
 };
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
 
 /// Autoexplore state and functionality for debug mode.
 #[derive(Debug, Clone)]
@@ -22,37 +25,62 @@ pub struct AutoexploreState {
     pub last_action_time: Option<std::time::Instant>,
     /// Delay between actions in milliseconds
     pub action_delay_ms: u64,
+    /// What autoexplore has already seen, and what should pause it
+    pub watch: InterruptionWatch,
 }
 
 impl AutoexploreState {
     /// Creates a new autoexplore state.
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             enabled: false,
             current_path: Vec::new(),
             target: None,
             last_action_time: None,
             action_delay_ms: 50, // 50ms between actions = 20 actions per second (10x faster)
+            watch: InterruptionWatch::new(Some(config::AUTOEXPLORE_HEALTH_THRESHOLD)),
         }
     }
 
     /// Toggles autoexplore on/off.
     pub fn toggle(&mut self) -> bool {
-        self.enabled = !self.enabled;
-        if !self.enabled {
-            // Clear state when disabling
-            self.current_path.clear();
-            self.target = None;
-            self.last_action_time = None;
+        if self.enabled {
+            self.pause();
+        } else {
+            self.enabled = true;
+            self.watch.clear();
         }
         self.enabled
     }
 
+    /// Disables autoexplore and clears its progress.
+    pub fn pause(&mut self) {
+        self.enabled = false;
+        self.current_path.clear();
+        self.target = None;
+        self.last_action_time = None;
+        self.watch.clear();
+    }
+
+    /// Checks whether autoexplore should stop for the player's attention.
+    #[must_use]
+    pub fn find_interruption(&self, game_state: &GameState) -> Option<Interruption> {
+        if !self.enabled {
+            return None;
+        }
+        self.watch.check(game_state)
+    }
+
+    /// Returns the message shown when autoexplore pauses for `interruption`.
+    pub fn pause_message(interruption: Interruption) -> String {
+        format!("Autoexplore paused: {}.", interruption.reason())
+    }
+
     /// Checks if enough time has passed for the next action.
     #[must_use]
     pub fn can_perform_action(&self) -> bool {
-        self.last_action_time.map_or(true, |last_time| {
+        self.last_action_time.is_none_or(|last_time| {
             last_time.elapsed().as_millis() >= u128::from(self.action_delay_ms)
         })
     }
@@ -62,6 +90,18 @@ impl AutoexploreState {
         self.last_action_time = Some(std::time::Instant::now());
     }
 
+    /// Gets the next position on the current path from `from`.
+    ///
+    /// A step stays on the path until the player stands on it, so one that
+    /// left them where they were, like a door opened into a blocked doorway,
+    /// is taken again.
+    pub fn next_step(&mut self, from: Position) -> Option<Position> {
+        if self.current_path.first() == Some(&from) {
+            self.current_path.remove(0);
+        }
+        self.current_path.first().copied()
+    }
+
     /// Gets the next autoexplore action to perform.
     pub fn get_next_action(
         &mut self,
@@ -75,6 +115,11 @@ impl AutoexploreState {
             return Ok(None);
         }
 
+        if let Some(interruption) = self.find_interruption(game_state) {
+            self.pause();
+            return Err(ThatchError::InvalidState(Self::pause_message(interruption)));
+        }
+
         let player = game_state
             .get_player()
             .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
@@ -87,7 +132,7 @@ impl AutoexploreState {
                 if tile.tile_type == TileType::StairsDown {
                     // Safety check: ensure the next level exists before using stairs
                     let current_level_id = game_state.world.current_level_id;
-                    if current_level_id < 25
+                    if game_state.world.shape.has_floor_below(current_level_id)
                         && game_state.world.get_level(current_level_id + 1).is_some()
                     {
                         // We're on stairs down and next level exists, use them
@@ -107,15 +152,13 @@ impl AutoexploreState {
         }
 
         // If we have a current path, follow it
-        if !self.current_path.is_empty() {
-            let next_pos = self.current_path.remove(0);
-            if let Some(direction) = self.get_direction_to_position(player_pos, next_pos) {
+        if let Some(next_pos) = self.next_step(player_pos) {
+            let step = self
+                .get_direction_to_position(player_pos, next_pos)
+                .and_then(|direction| game_state.route_step(player_id, player_pos, direction));
+            if let Some(action) = step {
                 self.mark_action_performed();
-                return Ok(Some(ConcreteAction::Move(MoveAction {
-                    actor: player_id,
-                    direction,
-                    metadata: HashMap::new(),
-                })));
+                return Ok(Some(action));
             }
             // Path is invalid, clear it
             self.current_path.clear();
@@ -135,15 +178,15 @@ impl AutoexploreState {
                 self.target = Some(stairs_down_pos);
 
                 // Return the first move in the path
-                if !self.current_path.is_empty() {
-                    let next_pos = self.current_path.remove(0);
-                    if let Some(direction) = self.get_direction_to_position(player_pos, next_pos) {
+                if let Some(next_pos) = self.next_step(player_pos) {
+                    let step = self
+                        .get_direction_to_position(player_pos, next_pos)
+                        .and_then(|direction| {
+                            game_state.route_step(player_id, player_pos, direction)
+                        });
+                    if let Some(action) = step {
                         self.mark_action_performed();
-                        return Ok(Some(ConcreteAction::Move(MoveAction {
-                            actor: player_id,
-                            direction,
-                            metadata: HashMap::new(),
-                        })));
+                        return Ok(Some(action));
                     }
                 }
             } else {
@@ -177,7 +220,8 @@ impl AutoexploreState {
         Direction::from_delta(delta)
     }
 
-    /// Uses A* pathfinding to find a path between two positions.
+    /// Finds a path between two positions for the player, going around
+    /// hazards where it can; see [`PathfindingUtils::find_route`].
     pub fn find_path(
         &self,
         game_state: &GameState,
@@ -188,72 +232,11 @@ impl AutoexploreState {
             .world
             .current_level()
             .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
-
-        // A* algorithm implementation
-        let mut open_set = BinaryHeap::new();
-        let mut came_from = HashMap::new();
-        let mut g_score = HashMap::new();
-        let mut f_score = HashMap::new();
-
-        g_score.insert(start, 0.0);
-        f_score.insert(start, start.euclidean_distance(goal));
-        open_set.push(AStarNode {
-            position: start,
-            f_score: start.euclidean_distance(goal),
-        });
-
-        while let Some(current_node) = open_set.pop() {
-            let current = current_node.position;
-
-            if current == goal {
-                // Reconstruct path
-                let mut path = Vec::new();
-                let mut current_pos = goal;
-
-                while let Some(&prev) = came_from.get(&current_pos) {
-                    path.push(current_pos);
-                    current_pos = prev;
-                }
-
-                path.reverse();
-                return Ok(Some(path));
-            }
-
-            // Check all adjacent positions
-            for neighbor in current.adjacent_positions() {
-                if !level.is_valid_position(neighbor) {
-                    continue;
-                }
-
-                // Check if tile is passable
-                let tile = level.get_tile(neighbor).unwrap();
-                if !tile.tile_type.is_passable() {
-                    continue;
-                }
-
-                // Check if there's an entity blocking the path (except at goal)
-                if neighbor != goal && game_state.get_entity_at_position(neighbor).is_some() {
-                    continue;
-                }
-
-                let tentative_g_score = g_score.get(&current).unwrap_or(&f64::INFINITY) + 1.0;
-
-                if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&f64::INFINITY) {
-                    came_from.insert(neighbor, current);
-                    g_score.insert(neighbor, tentative_g_score);
-                    let f = tentative_g_score + neighbor.euclidean_distance(goal);
-                    f_score.insert(neighbor, f);
-
-                    // Add to open set if not already there with a better score
-                    open_set.push(AStarNode {
-                        position: neighbor,
-                        f_score: f,
-                    });
-                }
-            }
-        }
-
-        Ok(None) // No path found
+        Ok(
+            PathfindingUtils::new().find_route(level, start, goal, |position| {
+                game_state.get_entity_at_position(position).is_some()
+            }),
+        )
     }
 }
 
@@ -263,37 +246,6 @@ impl Default for AutoexploreState {
     }
 }
 
-/// Node for A* pathfinding algorithm.
-#[derive(Debug, Clone)]
-pub struct AStarNode {
-    pub position: Position,
-    pub f_score: f64,
-}
-
-impl PartialEq for AStarNode {
-    fn eq(&self, other: &Self) -> bool {
-        self.f_score == other.f_score
-    }
-}
-
-impl Eq for AStarNode {}
-
-impl PartialOrd for AStarNode {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for AStarNode {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Reverse ordering for min-heap behavior in BinaryHeap
-        other
-            .f_score
-            .partial_cmp(&self.f_score)
-            .unwrap_or(Ordering::Equal)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -320,6 +272,37 @@ mod tests {
         assert!(!autoexplore.enabled);
     }
 
+    #[test]
+    fn test_autoexplore_interruptions() {
+        let mut game_state = GameState::new(12345);
+        game_state
+            .initialize_player("Explorer".to_string(), Position::new(5, 5))
+            .unwrap();
+        let mut autoexplore = AutoexploreState::new();
+        autoexplore.toggle();
+        assert_eq!(autoexplore.find_interruption(&game_state), None);
+
+        autoexplore.watch.note_critical_message();
+        assert_eq!(
+            autoexplore.find_interruption(&game_state),
+            Some(Interruption::CriticalMessage)
+        );
+        assert!(autoexplore.get_next_action(&game_state).is_err());
+        assert!(!autoexplore.enabled);
+
+        autoexplore.toggle();
+        let player = game_state.get_player_mut().unwrap();
+        player.stats.health = player.stats.max_health * 2 / 5;
+        assert_eq!(autoexplore.find_interruption(&game_state), None);
+
+        let player = game_state.get_player_mut().unwrap();
+        player.stats.health = player.stats.max_health / 10;
+        assert_eq!(
+            autoexplore.find_interruption(&game_state),
+            Some(Interruption::LowHealth)
+        );
+    }
+
     #[test]
     fn test_direction_calculation() {
         let autoexplore = AutoexploreState::new();
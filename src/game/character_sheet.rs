@@ -0,0 +1,210 @@
+//! # Character Sheet
+//!
+//! Everything about the player character on one screen.
+//!
+//! A [`CharacterSheet`] is built afresh from the game state each time it is
+//! shown. It reads the same stats, gear and statuses that combat does, and its
+//! derived figures come from the helpers the rules themselves call, such as
+//! [`EntityStats::damage_range`] and [`StatusEffects::resisted_by`], so the sheet
+//! never drifts from how the game actually plays.
+//!
+//! [`EntityStats::damage_range`]: crate::EntityStats::damage_range
+//! [`StatusEffects::resisted_by`]: crate::StatusEffects::resisted_by
+
+use crate::{Entity, GameState, Spell, StatusKind};
+
+/// A titled group of lines on the character sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetSection {
+    /// Heading of the section
+    pub title: &'static str,
+    /// Lines listed under the heading
+    pub lines: Vec<String>,
+}
+
+impl SheetSection {
+    fn new(title: &'static str, lines: Vec<String>) -> Self {
+        Self { title, lines }
+    }
+
+    /// Creates a section that says "None" when it has nothing to list.
+    fn or_none(title: &'static str, lines: Vec<String>) -> Self {
+        if lines.is_empty() {
+            Self::new(title, vec!["None".to_string()])
+        } else {
+            Self::new(title, lines)
+        }
+    }
+}
+
+/// The player character's stats, gear, statuses and class, ready to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterSheet {
+    /// Name, class and level of the character
+    pub title: String,
+    /// Sections in the order they are shown
+    pub sections: Vec<SheetSection>,
+}
+
+impl CharacterSheet {
+    /// Builds the sheet for the player, or `None` if there is no player.
+    pub fn new(game_state: &GameState) -> Option<Self> {
+        let player = game_state.get_player()?;
+        let stats = &player.stats;
+        let class = player.class;
+
+        let vitals = SheetSection::new(
+            "Vitals",
+            vec![
+                format!("Health: {}/{}", stats.health, stats.max_health),
+                format!("Mana: {}/{}", stats.mana, stats.max_mana),
+                format!("Experience: {}", stats.experience),
+            ],
+        );
+
+        let (least, most) = stats.damage_range();
+        let mut combat = vec![
+            format!("Attack: {} (blows deal {}-{})", stats.attack, least, most),
+            format!(
+                "Defense: {} (blocks {} damage a hit)",
+                stats.defense,
+                stats.damage_blocked()
+            ),
+            format!("Perception: {}", stats.perception),
+            format!("Sight: {} tiles", player.sight_radius),
+        ];
+        if let Some(quiver) = &player.quiver {
+            let name = game_state.item_display_name(&quiver.item);
+            combat.push(format!("Quiver: {}", quiver.label(&name)));
+        }
+        let combat = SheetSection::new("Combat", combat);
+
+        let mut resistances: Vec<String> = StatusKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let cause = player.statuses.resisted_by(kind)?;
+                Some(format!(
+                    "{} (while {})",
+                    kind.display_name(),
+                    cause.display_name().to_lowercase()
+                ))
+            })
+            .collect();
+        if game_state.player_can_breathe_water() {
+            resistances.push("Drowning (water breathing)".to_string());
+        }
+        let resistances = SheetSection::or_none("Resistances", resistances);
+
+        let mut worn: Vec<(&String, _)> = player.equipment.iter().collect();
+        worn.sort();
+        let equipment = worn
+            .into_iter()
+            .filter_map(|(slot, id)| {
+                let item = game_state.carried_items.get(id)?;
+                Some(format!("{}: {}", slot, game_state.item_display_name(item)))
+            })
+            .collect();
+        let equipment = SheetSection::or_none("Equipment", equipment);
+
+        let statuses = player
+            .statuses
+            .effects
+            .iter()
+            .map(|effect| {
+                format!(
+                    "{} ({} turns left)",
+                    effect.kind.display_name(),
+                    effect.turns_remaining
+                )
+            })
+            .collect();
+        let statuses = SheetSection::or_none("Status", statuses);
+
+        let ability = class.ability();
+        let mut class_lines = vec![
+            class.description().to_string(),
+            format!("{}: {}", ability.display_name(), ability.description()),
+        ];
+        let readiness = match player
+            .ability_ready_turn
+            .checked_sub(game_state.turn_number)
+        {
+            Some(turns) if turns > 0 => format!("Ready in {} turns", turns),
+            _ => "Ready".to_string(),
+        };
+        class_lines.push(match ability.mana_cost() {
+            0 => readiness,
+            mana => format!("{}, costs {} mana", readiness, mana),
+        });
+        let spells: Vec<String> = player
+            .known_spells
+            .iter()
+            .filter_map(|name| Spell::by_name(name))
+            .map(|spell| format!("{} ({} mana)", spell.name, spell.mana_cost))
+            .collect();
+        if !spells.is_empty() {
+            class_lines.push(format!("Spells: {}", spells.join(", ")));
+        }
+        let class_section = SheetSection::new("Class", class_lines);
+
+        Some(Self {
+            title: format!(
+                "{}, level {} {}",
+                player.name(),
+                stats.level,
+                class.display_name()
+            ),
+            sections: vec![
+                vitals,
+                combat,
+                resistances,
+                equipment,
+                statuses,
+                class_section,
+            ],
+        })
+    }
+
+    /// Gets the lines of the section with the given title.
+    pub fn section(&self, title: &str) -> Option<&[String]> {
+        self.sections
+            .iter()
+            .find(|section| section.title == title)
+            .map(|section| section.lines.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, SOAKED_DURATION};
+
+    #[test]
+    fn test_character_sheet_reads_the_numbers_combat_uses() {
+        let mut game_state = GameState::new(12345);
+        game_state
+            .initialize_player("Sheeted".to_string(), Position::new(5, 5))
+            .unwrap();
+        let stats = game_state.get_player().unwrap().stats.clone();
+
+        let sheet = CharacterSheet::new(&game_state).unwrap();
+        assert!(sheet.title.starts_with("Sheeted, level 1"));
+        let combat = sheet.section("Combat").unwrap();
+        let (least, most) = stats.damage_range();
+        assert!(combat[0].contains(&format!("{}-{}", least, most)));
+        let mut target = stats.clone();
+        assert_eq!(target.take_damage(most), most - stats.damage_blocked());
+        assert_eq!(sheet.section("Resistances").unwrap(), ["None"]);
+
+        // A soaking shows up as a status and as a resistance to fire
+        let player = game_state.get_player_mut().unwrap();
+        player.statuses.apply(StatusKind::Soaked, SOAKED_DURATION);
+        let sheet = CharacterSheet::new(&game_state).unwrap();
+        assert_eq!(
+            sheet.section("Resistances").unwrap(),
+            ["Burning (while soaked)"]
+        );
+        let status = &sheet.section("Status").unwrap()[0];
+        assert!(status.starts_with("Soaked"));
+    }
+}
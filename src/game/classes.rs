@@ -0,0 +1,290 @@
+//! # Classes Module
+//!
+//! Character classes chosen when a new game starts.
+//!
+//! A class decides the player's starting stats, the items they start with, and
+//! one unique ability used through [`AbilityAction`]. The class is stored on
+//! [`PlayerCharacter`] so saves and morgue files record it.
+//!
+//! [`AbilityAction`]: crate::AbilityAction
+//! [`PlayerCharacter`]: crate::PlayerCharacter
+
+use crate::{AmmoType, ArmorType, ConsumableType, EntityStats, ItemType, LightKind, WeaponType};
+use serde::{Deserialize, Serialize};
+
+/// Player character classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum CharacterClass {
+    /// Sturdy melee fighter
+    #[default]
+    Warrior,
+    /// Quick, perceptive skirmisher
+    Rogue,
+    /// Fragile caster with a large mana pool
+    Mage,
+}
+
+impl CharacterClass {
+    /// Every class, in the order offered at character creation
+    pub const ALL: [CharacterClass; 3] = [
+        CharacterClass::Warrior,
+        CharacterClass::Rogue,
+        CharacterClass::Mage,
+    ];
+
+    /// Returns the class name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            CharacterClass::Warrior => "Warrior",
+            CharacterClass::Rogue => "Rogue",
+            CharacterClass::Mage => "Mage",
+        }
+    }
+
+    /// Returns a one-line description for the class selection screen.
+    pub fn description(self) -> &'static str {
+        match self {
+            CharacterClass::Warrior => "Heavy armor and a long sword. Catches a second wind.",
+            CharacterClass::Rogue => "Light on their feet with a sharp eye for secrets.",
+            CharacterClass::Mage => "Frail, but hurls arcane bolts at distant foes.",
+        }
+    }
+
+    /// Returns the stats a new character of this class starts with.
+    pub fn starting_stats(self) -> EntityStats {
+        let base = EntityStats::new();
+        match self {
+            CharacterClass::Warrior => EntityStats {
+                health: 120,
+                max_health: 120,
+                mana: 20,
+                max_mana: 20,
+                attack: 12,
+                defense: 7,
+                ..base
+            },
+            CharacterClass::Rogue => EntityStats {
+                health: 90,
+                max_health: 90,
+                mana: 30,
+                max_mana: 30,
+                attack: 11,
+                defense: 4,
+                speed: 120,
+                perception: 16,
+                ..base
+            },
+            CharacterClass::Mage => EntityStats {
+                health: 70,
+                max_health: 70,
+                mana: 100,
+                max_mana: 100,
+                attack: 6,
+                defense: 3,
+                ..base
+            },
+        }
+    }
+
+    /// Returns the items a new character of this class starts with.
+    pub fn starting_items(self) -> Vec<StartingItem> {
+        let mut items = match self {
+            CharacterClass::Warrior => vec![
+                StartingItem::equipped("Longsword", ItemType::Weapon(WeaponType::Sword)),
+                StartingItem::equipped("Chain mail", ItemType::Armor(ArmorType::ChestArmor)),
+                StartingItem::equipped("Wooden shield", ItemType::Armor(ArmorType::Shield)),
+                StartingItem::carried(
+                    "Potion of healing",
+                    ItemType::Consumable(ConsumableType::HealthPotion),
+                ),
+            ],
+            CharacterClass::Rogue => vec![
+                StartingItem::equipped("Dagger", ItemType::Weapon(WeaponType::Dagger)),
+                StartingItem::equipped("Soft boots", ItemType::Armor(ArmorType::Boots)),
+                StartingItem::equipped("Shortbow", ItemType::Weapon(WeaponType::Bow)),
+                StartingItem::bundle("Arrow", ItemType::Ammo(AmmoType::Arrow), 20),
+                StartingItem::carried(
+                    "Potion of healing",
+                    ItemType::Consumable(ConsumableType::HealthPotion),
+                ),
+            ],
+            CharacterClass::Mage => vec![
+                StartingItem::equipped("Oak staff", ItemType::Weapon(WeaponType::Staff)),
+                StartingItem::equipped("Copper ring", ItemType::Armor(ArmorType::Ring)),
+                StartingItem::carried(
+                    "Potion of mana",
+                    ItemType::Consumable(ConsumableType::ManaPotion),
+                ),
+                StartingItem::carried(
+                    "Potion of mana",
+                    ItemType::Consumable(ConsumableType::ManaPotion),
+                ),
+                StartingItem::carried("Blank scroll", ItemType::Consumable(ConsumableType::Scroll)),
+            ],
+        };
+        // Every class sets out with a lantern to see by
+        items.push(StartingItem::equipped(
+            "Lantern",
+            ItemType::Light(LightKind::Lantern),
+        ));
+        items
+    }
+
+    /// Returns the names of the spells a new character of this class knows.
+    pub fn starting_spells(self) -> &'static [&'static str] {
+        match self {
+            CharacterClass::Warrior => &["Mend"],
+            CharacterClass::Rogue => &["Magic Missile", "Befuddle", "Douse"],
+            CharacterClass::Mage => &[
+                "Magic Missile",
+                "Fire Bolt",
+                "Force Blast",
+                "Mend",
+                "Douse",
+                "Raise Dead",
+            ],
+        }
+    }
+
+    /// Returns the unique ability of this class.
+    pub fn ability(self) -> ClassAbility {
+        match self {
+            CharacterClass::Warrior => ClassAbility::SecondWind,
+            CharacterClass::Rogue => ClassAbility::KeenEye,
+            CharacterClass::Mage => ClassAbility::ArcaneBolt,
+        }
+    }
+}
+
+/// An item a class starts the game with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartingItem {
+    /// Item name
+    pub name: &'static str,
+    /// What kind of item it is
+    pub item_type: ItemType,
+    /// Whether it starts equipped rather than in the pack
+    pub equipped: bool,
+    /// How many of the item the class starts with
+    pub count: u32,
+}
+
+impl StartingItem {
+    /// Describes an item that starts equipped.
+    fn equipped(name: &'static str, item_type: ItemType) -> Self {
+        Self {
+            name,
+            item_type,
+            equipped: true,
+            count: 1,
+        }
+    }
+
+    /// Describes an item that starts in the pack.
+    fn carried(name: &'static str, item_type: ItemType) -> Self {
+        Self {
+            name,
+            item_type,
+            equipped: false,
+            count: 1,
+        }
+    }
+
+    /// Describes several of an item that start in the pack, such as a quiver
+    /// of arrows.
+    fn bundle(name: &'static str, item_type: ItemType, count: u32) -> Self {
+        Self {
+            count,
+            ..Self::carried(name, item_type)
+        }
+    }
+}
+
+/// Unique class abilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClassAbility {
+    /// Warrior: recover a share of maximum health
+    SecondWind,
+    /// Rogue: uncover every secret door nearby
+    KeenEye,
+    /// Mage: strike the nearest visible hostile from range
+    ArcaneBolt,
+}
+
+impl ClassAbility {
+    /// Returns the ability name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ClassAbility::SecondWind => "Second Wind",
+            ClassAbility::KeenEye => "Keen Eye",
+            ClassAbility::ArcaneBolt => "Arcane Bolt",
+        }
+    }
+
+    /// Returns what the ability does.
+    pub fn description(self) -> String {
+        match self {
+            ClassAbility::SecondWind => format!(
+                "Restores {}% of your maximum health.",
+                crate::SECOND_WIND_HEAL_PERCENT
+            ),
+            ClassAbility::KeenEye => format!(
+                "Uncovers every secret door within {} tiles.",
+                crate::KEEN_EYE_RADIUS
+            ),
+            ClassAbility::ArcaneBolt => format!(
+                "Strikes the nearest visible hostile within {} tiles for {} damage, \
+                 plus {} a level.",
+                crate::ARCANE_BOLT_RANGE,
+                crate::ARCANE_BOLT_BASE_DAMAGE,
+                crate::ARCANE_BOLT_DAMAGE_PER_LEVEL
+            ),
+        }
+    }
+
+    /// Returns the number of turns before the ability can be used again.
+    pub fn cooldown_turns(self) -> u64 {
+        match self {
+            ClassAbility::SecondWind => 50,
+            ClassAbility::KeenEye => 20,
+            ClassAbility::ArcaneBolt => 0,
+        }
+    }
+
+    /// Returns the mana the ability costs.
+    pub fn mana_cost(self) -> u32 {
+        match self {
+            ClassAbility::ArcaneBolt => 10,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classes_are_distinct() {
+        for (index, class) in CharacterClass::ALL.iter().enumerate() {
+            for other in &CharacterClass::ALL[index + 1..] {
+                assert_ne!(class.ability(), other.ability());
+                assert_ne!(class.starting_items(), other.starting_items());
+            }
+            assert!(class.starting_items().iter().any(|item| item.equipped));
+            assert!(class
+                .starting_spells()
+                .iter()
+                .all(|name| crate::Spell::by_name(name).is_some()));
+            assert!(class
+                .starting_items()
+                .iter()
+                .all(|item| { !item.equipped || item.item_type.equip_slot().is_some() }));
+        }
+
+        let warrior = CharacterClass::Warrior.starting_stats();
+        let mage = CharacterClass::Mage.starting_stats();
+        assert!(warrior.max_health > mage.max_health);
+        assert!(mage.max_mana > warrior.max_mana);
+    }
+}
@@ -0,0 +1,94 @@
+//! # Containers Module
+//!
+//! Chests and other containers lying in the dungeon.
+//!
+//! A container sits on a floor tile and holds items until it is opened. Chests
+//! can be locked, needing a key or a successful lockpicking check, and can be
+//! trapped; the trap goes off the first time the chest is opened. The generator
+//! places chests in treasure rooms and stores them on their
+//! [`Level`](crate::Level).
+
+use crate::{new_entity_id, EntityId, Item, Position};
+use serde::{Deserialize, Serialize};
+
+/// Name of the key that opens any locked chest.
+pub const CHEST_KEY: &str = "Iron key";
+
+/// Farthest a container can be from the opener, in tiles walked.
+pub const CONTAINER_REACH: u32 = 1;
+
+/// Chance of picking a chest's lock without a key.
+pub const LOCKPICK_CHANCE: f64 = 0.25;
+
+/// Chance of a rogue picking a chest's lock without a key.
+pub const ROGUE_LOCKPICK_CHANCE: f64 = 0.6;
+
+/// Damage dealt by a chest's poison needle.
+pub const NEEDLE_TRAP_DAMAGE: u32 = 6;
+
+/// Turns a chest's flame trap sets the opener burning for.
+pub const FLAME_TRAP_TURNS: u32 = 3;
+
+/// Traps that can be set on a chest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChestTrap {
+    /// A poisoned needle in the lock
+    Needle,
+    /// A burst of flame from under the lid
+    Flame,
+}
+
+/// A chest or other container holding items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    /// Unique ID
+    pub id: EntityId,
+    /// Tile the container sits on
+    pub position: Position,
+    /// Container name
+    pub name: String,
+    /// Items inside
+    pub contents: Vec<Item>,
+    /// Whether a key or lockpicking is needed to open it
+    pub locked: bool,
+    /// Trap set off the first time it is opened
+    pub trap: Option<ChestTrap>,
+    /// Whether it has been opened
+    pub opened: bool,
+}
+
+impl Container {
+    /// Creates an unlocked, untrapped chest holding `contents`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Container, Position};
+    ///
+    /// let chest = Container::chest(Position::new(4, 4), Vec::new());
+    /// assert_eq!(chest.name, "chest");
+    /// assert!(!chest.locked && !chest.opened);
+    /// ```
+    pub fn chest(position: Position, contents: Vec<Item>) -> Self {
+        Self {
+            id: new_entity_id(),
+            position,
+            name: "chest".to_string(),
+            contents,
+            locked: false,
+            trap: None,
+            opened: false,
+        }
+    }
+
+    /// Returns the character used to draw the container.
+    pub fn display_char(&self) -> char {
+        '&'
+    }
+
+    /// Takes an item out of the container.
+    pub fn take(&mut self, item_id: EntityId) -> Option<Item> {
+        let index = self.contents.iter().position(|item| item.id == item_id)?;
+        Some(self.contents.remove(index))
+    }
+}
@@ -0,0 +1,283 @@
+//! # Crash Reports
+//!
+//! What the game writes down when it panics.
+//!
+//! The interactive game tells the [`crash_recorder`] about every action it
+//! takes and the state it reaches at the end of each turn. Copying the whole
+//! game is costly, so the recorder only keeps a copy every
+//! [`CRASH_STATE_INTERVAL`] turns; the actions taken since then are still in
+//! the log. Once [`install_crash_handler`] has run, a panic writes a
+//! [`CrashReport`] with that state, the last [`CRASH_ACTION_LIMIT`] actions,
+//! the seed and the build
+//! into [`config::CRASH_DIRECTORY`](crate::config::CRASH_DIRECTORY) and tells
+//! the player where to find it, so a crash can be attached to an issue and
+//! replayed.
+
+use crate::{config, ConcreteAction, GameState, ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Most recent actions a crash report keeps.
+pub const CRASH_ACTION_LIMIT: usize = 100;
+
+/// Turns between the copies of the game a crash report keeps, fewer than
+/// [`CRASH_ACTION_LIMIT`] so every action since the copy is still known.
+pub const CRASH_STATE_INTERVAL: u64 = 50;
+
+/// The recorder the crash handler reports from.
+static CRASH_RECORDER: Mutex<CrashRecorder> = Mutex::new(CrashRecorder::new());
+
+/// The build that crashed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Crate version
+    pub version: String,
+    /// Operating system the build targets
+    pub os: String,
+    /// CPU architecture the build targets
+    pub arch: String,
+    /// "debug" or "release"
+    pub profile: String,
+    /// Optional features compiled in
+    pub features: Vec<String>,
+}
+
+impl BuildInfo {
+    /// Describes the running build.
+    pub fn current() -> Self {
+        let features = [
+            ("dev-tools", cfg!(feature = "dev-tools")),
+            ("ai-player", cfg!(feature = "ai-player")),
+            ("mcp-server", cfg!(feature = "mcp-server")),
+            ("ws-server", cfg!(feature = "ws-server")),
+        ];
+        Self {
+            version: crate::VERSION.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+            features: features
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Everything known about the game when it crashed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// The panic message
+    pub message: String,
+    /// Source file and line that panicked, if known
+    pub location: Option<String>,
+    /// Seconds since the Unix epoch when the crash happened
+    pub created_at: u64,
+    /// The build that crashed
+    pub build: BuildInfo,
+    /// World seed of the game, if one was being played
+    pub seed: Option<u64>,
+    /// Last actions taken, oldest first, with the turn each was taken on
+    pub recent_actions: Vec<(u64, ConcreteAction)>,
+    /// The game as last copied, at most [`CRASH_STATE_INTERVAL`] turns
+    /// before the crash, if one was recorded
+    pub game_state: Option<GameState>,
+}
+
+impl CrashReport {
+    /// Serializes the report to pretty-printed JSON.
+    pub fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string_pretty(self).map_err(ThatchError::from)
+    }
+
+    /// Writes the report into `directory`, creating it if needed, and returns
+    /// the path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the directory or file cannot be written.
+    pub fn write_to_directory(&self, directory: &Path) -> ThatchResult<PathBuf> {
+        std::fs::create_dir_all(directory)?;
+        let seed = self
+            .seed
+            .map_or_else(|| "none".to_string(), |seed| seed.to_string());
+        let path = directory.join(format!("crash-{}-{}.json", seed, self.created_at));
+        std::fs::write(&path, self.to_json()?)?;
+        Ok(path)
+    }
+}
+
+/// The recent history of the game being played, kept for crash reports.
+#[derive(Debug, Clone, Default)]
+pub struct CrashRecorder {
+    /// Last actions taken, with the turn each was taken on
+    actions: VecDeque<(u64, ConcreteAction)>,
+    /// Copy of the game taken at most [`CRASH_STATE_INTERVAL`] turns ago
+    game_state: Option<GameState>,
+}
+
+impl CrashRecorder {
+    /// Creates a recorder that has seen nothing yet.
+    pub const fn new() -> Self {
+        Self {
+            actions: VecDeque::new(),
+            game_state: None,
+        }
+    }
+
+    /// Remembers an action taken on `turn`, forgetting the oldest beyond
+    /// [`CRASH_ACTION_LIMIT`].
+    pub fn record_action(&mut self, turn: u64, action: &ConcreteAction) {
+        if self.actions.len() == CRASH_ACTION_LIMIT {
+            self.actions.pop_front();
+        }
+        self.actions.push_back((turn, action.clone()));
+    }
+
+    /// Remembers the game as it is at the end of a turn, if the last copy is
+    /// [`CRASH_STATE_INTERVAL`] turns old or belongs to another game.
+    pub fn record_state(&mut self, game_state: &GameState) {
+        let is_due = self.game_state.as_ref().is_none_or(|saved| {
+            saved.rng_seed != game_state.rng_seed
+                || game_state.turn_number < saved.turn_number
+                || game_state.turn_number >= saved.turn_number + CRASH_STATE_INTERVAL
+        });
+        if is_due {
+            self.game_state = Some(game_state.clone());
+        }
+    }
+
+    /// Builds a report of a crash with `message` at `location`.
+    pub fn report(&self, message: String, location: Option<String>) -> CrashReport {
+        CrashReport {
+            message,
+            location,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            build: BuildInfo::current(),
+            seed: self
+                .game_state
+                .as_ref()
+                .map(|game_state| game_state.rng_seed),
+            recent_actions: self.actions.iter().cloned().collect(),
+            game_state: self.game_state.clone(),
+        }
+    }
+}
+
+/// Locks the recorder the crash handler reports from.
+pub fn crash_recorder() -> MutexGuard<'static, CrashRecorder> {
+    CRASH_RECORDER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Makes panics write a crash report before the usual panic output.
+///
+/// If the recorder is locked by the panicking code itself, the report goes
+/// without the game's history rather than waiting forever.
+pub fn install_crash_handler() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        write_crash_report(info);
+        default_hook(info);
+    }));
+}
+
+/// Writes the report for a panic and tells the player where it went.
+fn write_crash_report(info: &PanicHookInfo<'_>) {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    let location = info
+        .location()
+        .map(|location| format!("{}:{}", location.file(), location.line()));
+    let report = match CRASH_RECORDER.try_lock() {
+        Ok(recorder) => recorder.report(message, location),
+        Err(std::sync::TryLockError::Poisoned(poisoned)) => {
+            poisoned.into_inner().report(message, location)
+        }
+        Err(std::sync::TryLockError::WouldBlock) => CrashRecorder::new().report(message, location),
+    };
+
+    match report.write_to_directory(Path::new(config::CRASH_DIRECTORY)) {
+        Ok(path) => eprintln!(
+            "Thatch crashed, sorry! A crash report was saved to {}. \
+             Please attach it to an issue so the crash can be reproduced.",
+            path.display()
+        ),
+        Err(e) => eprintln!(
+            "Thatch crashed, sorry! The crash report could not be saved: {}",
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, WaitAction};
+
+    #[test]
+    fn test_crash_reports_keep_recent_history_and_round_trip() {
+        let mut game_state = GameState::new(77);
+        let player_id = game_state
+            .initialize_player("Crashed".to_string(), Position::new(5, 5))
+            .unwrap();
+        let mut recorder = CrashRecorder::new();
+        assert!(recorder.report("boom".to_string(), None).seed.is_none());
+
+        let wait = ConcreteAction::Wait(WaitAction::new(player_id));
+        for turn in 0..CRASH_ACTION_LIMIT as u64 + 5 {
+            recorder.record_action(turn, &wait);
+        }
+        recorder.record_state(&game_state);
+        let report = recorder.report("boom".to_string(), Some("src/lib.rs:1".to_string()));
+        assert_eq!(report.recent_actions.len(), CRASH_ACTION_LIMIT);
+        assert_eq!(report.recent_actions[0].0, 5);
+        assert_eq!(report.seed, Some(77));
+        assert_eq!(report.build, BuildInfo::current());
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = report.write_to_directory(directory.path()).unwrap();
+        let saved: CrashReport =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(saved.message, "boom");
+        assert_eq!(saved.game_state.unwrap().player_id, Some(player_id));
+    }
+
+    #[test]
+    fn test_game_is_only_copied_every_few_turns() {
+        let mut recorder = CrashRecorder::new();
+        let mut game_state = GameState::new(77);
+        recorder.record_state(&game_state);
+
+        game_state.turn_number = CRASH_STATE_INTERVAL - 1;
+        recorder.record_state(&game_state);
+        assert_eq!(recorder.game_state.as_ref().unwrap().turn_number, 0);
+
+        game_state.turn_number = CRASH_STATE_INTERVAL;
+        recorder.record_state(&game_state);
+        assert_eq!(
+            recorder.game_state.as_ref().unwrap().turn_number,
+            CRASH_STATE_INTERVAL
+        );
+
+        // A new game replaces the copy straight away
+        recorder.record_state(&GameState::new(78));
+        assert_eq!(recorder.game_state.as_ref().unwrap().rng_seed, 78);
+    }
+}
@@ -0,0 +1,116 @@
+//! # Danger Overlay
+//!
+//! Where visible monsters can strike next turn.
+//!
+//! Each visible hostile takes one step a turn and then attacks anything
+//! within its reach, so the tiles it threatens are those within
+//! [`MONSTER_ATTACK_RANGE`] of anywhere it can step to. A
+//! [`DangerMap`] gathers those tiles for every visible hostile, counting how
+//! many threaten each one, and the display tints them over the map while the
+//! danger overlay is on. Other monsters standing in the way are ignored, as
+//! they may well move first, so the map errs on the side of caution.
+
+use crate::{GameState, PathfindingUtils, Position};
+use std::collections::HashMap;
+
+/// How far, in tiles, a monster can attack from where it stands.
+pub const MONSTER_ATTACK_RANGE: u32 = 1;
+
+/// Tiles that visible hostiles can attack next turn.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DangerMap {
+    /// Number of hostiles threatening each tile
+    threats: HashMap<Position, u32>,
+}
+
+impl DangerMap {
+    /// Works out the tiles threatened by the hostiles the player can see on
+    /// the current level.
+    pub fn compute(game_state: &GameState) -> Self {
+        let mut threats = HashMap::new();
+        let Some(level) = game_state.world.current_level() else {
+            return Self { threats };
+        };
+
+        let pathfinding = PathfindingUtils::new();
+        for id in game_state.visible_hostiles() {
+            let Some(position) = game_state.get_entity_position(id) else {
+                continue;
+            };
+
+            // A step, then a blow
+            for tile in pathfinding
+                .reachable_within(level, position, 1 + MONSTER_ATTACK_RANGE)
+                .into_keys()
+            {
+                if tile != position {
+                    *threats.entry(tile).or_insert(0) += 1;
+                }
+            }
+        }
+        Self { threats }
+    }
+
+    /// Gets how many hostiles can attack the given tile next turn.
+    pub fn threat_at(&self, position: Position) -> u32 {
+        self.threats.get(&position).copied().unwrap_or(0)
+    }
+
+    /// Returns true if any hostile can attack the given tile next turn.
+    pub fn is_threatened(&self, position: Position) -> bool {
+        self.threat_at(position) > 0
+    }
+
+    /// Iterates over the threatened tiles and how many hostiles threaten each.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, u32)> + '_ {
+        self.threats
+            .iter()
+            .map(|(&position, &count)| (position, count))
+    }
+
+    /// Returns true if no tile is threatened.
+    pub fn is_empty(&self) -> bool {
+        self.threats.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Monster, MonsterType, Tile};
+
+    #[test]
+    fn test_danger_map_covers_a_step_and_a_blow_from_visible_hostiles() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 2..=14 {
+                level.set_tile(Position::new(x, 5), Tile::floor()).unwrap();
+            }
+        }
+        game_state
+            .initialize_player("Scout".to_string(), Position::new(2, 5))
+            .unwrap();
+        for x in [8, 10] {
+            let goblin = Monster::new(MonsterType::Goblin, Position::new(x, 5));
+            let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+            game_state
+                .world
+                .current_level_mut()
+                .unwrap()
+                .add_entity(goblin_id);
+        }
+        game_state
+            .update_player_visibility(Position::new(2, 5))
+            .unwrap();
+
+        let danger = DangerMap::compute(&game_state);
+        assert!(!danger.is_threatened(Position::new(5, 5)));
+        assert_eq!(danger.threat_at(Position::new(6, 5)), 1);
+        assert_eq!(danger.threat_at(Position::new(9, 5)), 2);
+        assert_eq!(danger.threat_at(Position::new(12, 5)), 1);
+        assert!(!danger.is_threatened(Position::new(13, 5)));
+        // Walls are never threatened
+        assert!(!danger.is_threatened(Position::new(8, 4)));
+    }
+}
@@ -0,0 +1,159 @@
+//! # Dialogue Module
+//!
+//! Friendly characters and what they have to say.
+//!
+//! Shops and sanctuaries are kept by an [`Npc`](crate::Npc) the player can
+//! talk to with a [`TalkAction`](crate::TalkAction). By default an NPC speaks
+//! the lines of its role's script. When the LLDM is enabled its lines are
+//! instead written by [`LldmClient::generate_dialogue`](crate::LldmClient::generate_dialogue)
+//! from the conversation context, and cached so the NPC keeps saying the same
+//! thing.
+
+use crate::{EntityId, RoomType};
+use serde::{Deserialize, Serialize};
+
+/// What a friendly NPC does in the dungeon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NpcRole {
+    /// Keeps a shop
+    Shopkeeper,
+    /// Tends a sanctuary
+    Priest,
+    /// Anyone else with something to say
+    Townsfolk,
+}
+
+impl NpcRole {
+    /// Returns the name shown for NPCs with this role.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            NpcRole::Shopkeeper => "Shopkeeper",
+            NpcRole::Priest => "Priest",
+            NpcRole::Townsfolk => "Villager",
+        }
+    }
+
+    /// Gets the role of the NPC who keeps a room of this type, if anyone does.
+    pub fn for_room(room_type: &RoomType) -> Option<Self> {
+        match room_type {
+            RoomType::Shop => Some(NpcRole::Shopkeeper),
+            RoomType::Sanctuary => Some(NpcRole::Priest),
+            _ => None,
+        }
+    }
+
+    /// Gets the scripted lines NPCs with this role speak, in order.
+    pub fn script(self) -> &'static [&'static str] {
+        match self {
+            NpcRole::Shopkeeper => &[
+                "Welcome, welcome! Mind the blood on the floor, it isn't mine.",
+                "Everything here was found on adventurers who went deeper than you.",
+                "Come back alive and I may even have something worth buying.",
+            ],
+            NpcRole::Priest => &[
+                "Rest here, traveller. Nothing that walks below may enter.",
+                "The Amulet has called many down these stairs. Few returned.",
+                "Go with care, and pray the dark does not notice you.",
+            ],
+            NpcRole::Townsfolk => &[
+                "Another one heading down? You look braver than the last.",
+                "They say the deeper floors shift when nobody is watching.",
+            ],
+        }
+    }
+}
+
+/// A conversation the player is reading through, one line at a time.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{new_entity_id, Conversation};
+///
+/// let lines = vec!["Hello.".to_string(), "Goodbye.".to_string()];
+/// let mut conversation = Conversation::new(new_entity_id(), "Priest".to_string(), lines);
+///
+/// assert_eq!(conversation.current_line(), Some("Hello."));
+/// assert!(conversation.advance());
+/// assert_eq!(conversation.current_line(), Some("Goodbye."));
+/// assert!(!conversation.advance());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conversation {
+    /// NPC being talked to
+    pub npc_id: EntityId,
+    /// Name shown above the NPC's lines
+    pub speaker: String,
+    /// Everything the NPC says
+    pub lines: Vec<String>,
+    /// Index of the line being shown
+    pub line: usize,
+}
+
+impl Conversation {
+    /// Starts a conversation at its first line.
+    pub fn new(npc_id: EntityId, speaker: String, lines: Vec<String>) -> Self {
+        Self {
+            npc_id,
+            speaker,
+            lines,
+            line: 0,
+        }
+    }
+
+    /// Gets the line being shown, or `None` once the conversation is over.
+    pub fn current_line(&self) -> Option<&str> {
+        self.lines.get(self.line).map(String::as_str)
+    }
+
+    /// Moves on to the next line.
+    ///
+    /// Returns false once there are no lines left.
+    pub fn advance(&mut self) -> bool {
+        self.line += 1;
+        self.line < self.lines.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, GameEvent, GameState, Npc, Position, TalkAction};
+
+    #[test]
+    fn test_npcs_speak_their_script_or_cached_lldm_lines() {
+        let mut game_state = GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Pilgrim".to_string(), Position::new(5, 5))
+            .unwrap();
+        let priest = Npc::new(NpcRole::Priest, Position::new(6, 5));
+        let priest_id = game_state.add_entity(priest.into()).unwrap();
+        let shopkeeper = Npc::new(NpcRole::Shopkeeper, Position::new(9, 9));
+        let shopkeeper_id = game_state.add_entity(shopkeeper.into()).unwrap();
+
+        // Talking needs the NPC within reach
+        assert_eq!(TalkAction::npc_within_reach(&game_state), Some(priest_id));
+        assert!(TalkAction::new(player_id, shopkeeper_id)
+            .validate(&game_state)
+            .is_err());
+
+        let events = TalkAction::new(player_id, priest_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(matches!(&events[0], GameEvent::Message { text, .. }
+            if text.contains(NpcRole::Priest.script()[0])));
+        assert_eq!(
+            game_state.npc_dialogue(priest_id).unwrap(),
+            NpcRole::Priest.script()
+        );
+
+        // With the LLDM on, lines are generated once and then reused
+        game_state.lldm_state.enabled = true;
+        let generated = game_state.npc_dialogue(priest_id).unwrap();
+        assert_ne!(generated, NpcRole::Priest.script());
+        assert!(generated[0].contains("Pilgrim"));
+        assert_eq!(game_state.lldm_state.content_cache.len(), 1);
+        assert_eq!(game_state.npc_dialogue(priest_id).unwrap(), generated);
+        assert_eq!(game_state.lldm_state.content_cache.len(), 1);
+    }
+}
@@ -6,7 +6,10 @@
 //! for creating unique creatures, items, and interactive objects. All entities are
 //! serializable for save/load functionality and MCP integration.
 
-use crate::{config, new_entity_id, EntityId, Position, ThatchError, ThatchResult};
+use crate::{
+    config, new_entity_id, Achievement, CharacterClass, EntityId, ItemStack, LightKind, LootDrop,
+    NpcRole, Position, StatusEffects, StatusKind, ThatchError, ThatchResult,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -50,6 +53,36 @@ pub trait Entity {
 
     /// Sets metadata for LLDM integration.
     fn set_metadata(&mut self, key: String, value: String);
+
+    /// Gets the combat stats of this entity, if it has any.
+    fn stats(&self) -> Option<&EntityStats> {
+        None
+    }
+
+    /// Gets the combat stats of this entity mutably, if it has any.
+    fn stats_mut(&mut self) -> Option<&mut EntityStats> {
+        None
+    }
+
+    /// Gets the active status effects of this entity, if it can have any.
+    fn statuses(&self) -> Option<&StatusEffects> {
+        None
+    }
+
+    /// Gets mutable access to the status effects of this entity, if it can have any.
+    fn statuses_mut(&mut self) -> Option<&mut StatusEffects> {
+        None
+    }
+
+    /// Gets the items carried by this entity, if it can carry any.
+    fn inventory(&self) -> Option<&[EntityId]> {
+        None
+    }
+
+    /// Returns true if this entity is hostile to the player.
+    fn is_hostile(&self) -> bool {
+        false
+    }
 }
 
 /// Enumeration of different entity types in the game.
@@ -89,6 +122,34 @@ pub enum MonsterType {
     Custom(String),
 }
 
+impl MonsterType {
+    /// Returns the name shown for monsters of this type.
+    pub fn display_name(&self) -> &str {
+        match self {
+            MonsterType::Goblin => "Goblin",
+            MonsterType::Orc => "Orc",
+            MonsterType::Wizard => "Wizard",
+            MonsterType::Skeleton => "Skeleton",
+            MonsterType::Troll => "Troll",
+            MonsterType::Dragon => "Dragon",
+            MonsterType::Custom(name) => name,
+        }
+    }
+
+    /// Returns the character used to draw monsters of this type.
+    pub fn display_char(&self) -> char {
+        match self {
+            MonsterType::Goblin => 'g',
+            MonsterType::Orc => 'o',
+            MonsterType::Wizard => 'W',
+            MonsterType::Skeleton => 's',
+            MonsterType::Troll => 'T',
+            MonsterType::Dragon => 'D',
+            MonsterType::Custom(_) => 'M',
+        }
+    }
+}
+
 /// Different types of items in the game.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ItemType {
@@ -98,10 +159,16 @@ pub enum ItemType {
     Armor(ArmorType),
     /// Consumable items
     Consumable(ConsumableType),
+    /// Arrows and bolts shot from a launcher
+    Ammo(AmmoType),
     /// Quest or story items
     QuestItem,
     /// Treasure and valuables
     Treasure,
+    /// The body of a dead monster of the given type
+    Corpse(MonsterType),
+    /// A light held while exploring, lighting the tiles around its bearer
+    Light(LightKind),
     /// LLDM can create custom item types
     Custom(String),
 }
@@ -112,11 +179,48 @@ pub enum WeaponType {
     Sword,
     Dagger,
     Bow,
+    Crossbow,
     Staff,
     Mace,
     Custom(String),
 }
 
+impl WeaponType {
+    /// Returns the ammunition this weapon shoots, if it is a launcher.
+    pub fn ammo(&self) -> Option<AmmoType> {
+        match self {
+            WeaponType::Bow => Some(AmmoType::Arrow),
+            WeaponType::Crossbow => Some(AmmoType::Bolt),
+            _ => None,
+        }
+    }
+}
+
+/// Ammunition subtypes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AmmoType {
+    Arrow,
+    Bolt,
+}
+
+impl AmmoType {
+    /// Returns the plural name used when counting ammunition, such as "12 arrows".
+    pub fn plural_name(self) -> &'static str {
+        match self {
+            AmmoType::Arrow => "arrows",
+            AmmoType::Bolt => "bolts",
+        }
+    }
+
+    /// Returns the damage a shot of this ammunition deals to whatever it hits.
+    pub fn damage(self) -> u32 {
+        match self {
+            AmmoType::Arrow => 9,
+            AmmoType::Bolt => 12,
+        }
+    }
+}
+
 /// Armor subtypes.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ArmorType {
@@ -135,9 +239,51 @@ pub enum ConsumableType {
     ManaPotion,
     Food,
     Scroll,
+    Wand,
     Custom(String),
 }
 
+impl ItemType {
+    /// Returns the character used to draw items of this type.
+    pub fn display_char(&self) -> char {
+        match self {
+            ItemType::Weapon(_) => ')',
+            ItemType::Armor(ArmorType::Ring) => '=',
+            ItemType::Armor(_) => '[',
+            ItemType::Consumable(ConsumableType::Scroll) => '?',
+            ItemType::Consumable(ConsumableType::Food) => '%',
+            ItemType::Consumable(ConsumableType::Wand) => '/',
+            ItemType::Consumable(_) => '!',
+            ItemType::Ammo(_) | ItemType::Light(_) => '(',
+            ItemType::QuestItem => '"',
+            ItemType::Treasure => '$',
+            ItemType::Corpse(_) => '%',
+            ItemType::Custom(_) => '*',
+        }
+    }
+
+    /// Returns true for arrows, bolts and other ammunition.
+    pub fn is_ammo(&self) -> bool {
+        matches!(self, ItemType::Ammo(_))
+    }
+
+    /// Returns the equipment slot items of this type are worn in, if any.
+    pub fn equip_slot(&self) -> Option<&'static str> {
+        match self {
+            ItemType::Weapon(weapon) if weapon.ammo().is_some() => Some("ranged"),
+            ItemType::Weapon(_) => Some("weapon"),
+            ItemType::Armor(ArmorType::Helmet) => Some("head"),
+            ItemType::Armor(ArmorType::ChestArmor) => Some("body"),
+            ItemType::Armor(ArmorType::Boots) => Some("feet"),
+            ItemType::Armor(ArmorType::Shield) => Some("offhand"),
+            ItemType::Armor(ArmorType::Ring) => Some("ring"),
+            ItemType::Armor(ArmorType::Custom(_)) => Some("body"),
+            ItemType::Light(_) => Some("light"),
+            _ => None,
+        }
+    }
+}
+
 /// Events that can occur in the game world.
 ///
 /// These events are used for communication between entities and systems,
@@ -162,6 +308,12 @@ pub enum GameEvent {
         amount: u32,
         source: Option<EntityId>,
     },
+    /// A status effect was applied to an entity
+    StatusApplied {
+        entity_id: EntityId,
+        kind: StatusKind,
+        turns: u32,
+    },
     /// An entity died
     EntityDied {
         entity_id: EntityId,
@@ -184,14 +336,29 @@ pub enum GameEvent {
         dropper_id: EntityId,
         position: Position,
     },
+    /// An entity made a noise that monsters may hear
+    NoiseMade {
+        source: EntityId,
+        position: Position,
+        volume: u32,
+    },
+    /// A secret door or hidden trap was uncovered by searching
+    SecretFound {
+        finder_id: EntityId,
+        position: Position,
+    },
     /// A message should be displayed to the player
     Message {
         text: String,
         importance: MessageImportance,
+        /// What part of the game the message is about
+        #[serde(default)]
+        channel: MessageChannel,
     },
     /// LLDM-generated event with custom data
     LldmEvent {
         event_type: String,
+        #[serde(serialize_with = "crate::sorted_map")]
         data: HashMap<String, String>,
     },
     /// Player changed levels
@@ -201,6 +368,8 @@ pub enum GameEvent {
         new_level: u32,
         direction: crate::StairDirection,
     },
+    /// The player earned an achievement
+    AchievementUnlocked { achievement: Achievement },
     /// Game ended with a specific outcome
     GameEnded {
         ending_type: String,
@@ -209,7 +378,9 @@ pub enum GameEvent {
 }
 
 /// Importance levels for game messages.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Critical messages are shown even on a muted [`MessageChannel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MessageImportance {
     /// Low priority, can be ignored
     Info,
@@ -221,6 +392,44 @@ pub enum MessageImportance {
     Critical,
 }
 
+/// What part of the game a message is about, so the log can be filtered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MessageChannel {
+    /// Attacks, damage, deaths and status effects
+    Combat,
+    /// Moving around, items, searching and the dungeon itself
+    Exploration,
+    /// Game flow, achievements and problems outside the game
+    #[default]
+    System,
+    /// Quests, conversations and the story of the run
+    Narrative,
+}
+
+impl MessageChannel {
+    /// Every channel, in the order the log's filter lists them
+    pub const ALL: [MessageChannel; 4] = [
+        MessageChannel::Combat,
+        MessageChannel::Exploration,
+        MessageChannel::System,
+        MessageChannel::Narrative,
+    ];
+
+    /// Returns the channel name.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            MessageChannel::Combat => "Combat",
+            MessageChannel::Exploration => "Exploration",
+            MessageChannel::System => "System",
+            MessageChannel::Narrative => "Narrative",
+        }
+    }
+}
+
+/// How much a melee blow can deal on top of the attacker's attack stat; each
+/// blow adds a roll below this.
+pub const ATTACK_DAMAGE_SPREAD: u32 = 10;
+
 /// Basic stats that most entities have.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityStats {
@@ -242,6 +451,13 @@ pub struct EntityStats {
     pub experience: u32,
     /// Character level
     pub level: u32,
+    /// Awareness used when searching for secrets
+    #[serde(default = "default_perception")]
+    pub perception: u32,
+}
+
+fn default_perception() -> u32 {
+    config::DEFAULT_PERCEPTION
 }
 
 impl EntityStats {
@@ -257,6 +473,7 @@ impl EntityStats {
             speed: 100,
             experience: 0,
             level: 1,
+            perception: config::DEFAULT_PERCEPTION,
         }
     }
 
@@ -273,6 +490,7 @@ impl EntityStats {
                 speed: 110,
                 experience: 0,
                 level: 1,
+                perception: 8,
             },
             MonsterType::Orc => Self {
                 health: 40,
@@ -284,6 +502,7 @@ impl EntityStats {
                 speed: 80,
                 experience: 0,
                 level: 2,
+                perception: 6,
             },
             MonsterType::Dragon => Self {
                 health: 500,
@@ -295,6 +514,7 @@ impl EntityStats {
                 speed: 60,
                 experience: 0,
                 level: 20,
+                perception: 18,
             },
             _ => Self::new(), // Default for other types
         }
@@ -305,9 +525,29 @@ impl EntityStats {
         self.health > 0
     }
 
+    /// Gets the least and most damage a melee blow from these stats deals,
+    /// before the target's defense.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::EntityStats;
+    ///
+    /// let stats = EntityStats::new();
+    /// assert_eq!(stats.damage_range(), (stats.attack, stats.attack + 9));
+    /// ```
+    pub fn damage_range(&self) -> (u32, u32) {
+        (self.attack, self.attack + ATTACK_DAMAGE_SPREAD - 1)
+    }
+
+    /// Gets how much of every hit these stats shrug off.
+    pub fn damage_blocked(&self) -> u32 {
+        self.defense / 2
+    }
+
     /// Applies damage, returns actual damage dealt.
     pub fn take_damage(&mut self, damage: u32) -> u32 {
-        let actual_damage = damage.saturating_sub(self.defense / 2);
+        let actual_damage = damage.saturating_sub(self.damage_blocked());
         self.health = self.health.saturating_sub(actual_damage);
         actual_damage
     }
@@ -319,6 +559,11 @@ impl EntityStats {
         self.health - old_health
     }
 
+    /// Checks if health and mana are both full.
+    pub fn is_fully_recovered(&self) -> bool {
+        self.health >= self.max_health && self.mana >= self.max_mana
+    }
+
     /// Restores mana, returns actual mana restored.
     pub fn restore_mana(&mut self, amount: u32) -> u32 {
         let old_mana = self.mana;
@@ -348,6 +593,7 @@ pub struct PlayerCharacter {
     /// Character stats
     pub stats: EntityStats,
     /// Equipment slots
+    #[serde(serialize_with = "crate::sorted_map")]
     pub equipment: HashMap<String, EntityId>,
     /// Inventory items
     pub inventory: Vec<EntityId>,
@@ -355,10 +601,38 @@ pub struct PlayerCharacter {
     pub inventory_capacity: usize,
     /// Field of view radius
     pub sight_radius: u32,
+    /// Active status effects such as soaked or burning
+    #[serde(default)]
+    pub statuses: StatusEffects,
+    /// Consecutive turns spent in water
+    #[serde(default)]
+    pub turns_in_water: u32,
+    /// Class chosen when the character was created
+    #[serde(default)]
+    pub class: CharacterClass,
+    /// Turn on which the class ability can next be used
+    #[serde(default)]
+    pub ability_ready_turn: u64,
+    /// Turn on which the gods next listen to a prayer
+    #[serde(default)]
+    pub prayer_ready_turn: u64,
+    /// Names of the spells the player can cast
+    #[serde(default)]
+    pub known_spells: Vec<String>,
+    /// Arrows or bolts ready to shoot, kept apart from the pack
+    #[serde(default)]
+    pub quiver: Option<ItemStack>,
     /// LLDM integration metadata
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
 }
 
+/// Player metadata key the LLDM can set to let the player breathe underwater.
+pub const WATER_BREATHING_METADATA_KEY: &str = "water_breathing";
+
+/// Name of the ring that lets its wearer breathe underwater.
+pub const WATER_BREATHING_RING: &str = "Ring of water breathing";
+
 impl PlayerCharacter {
     /// Creates a new player character.
     ///
@@ -373,19 +647,63 @@ impl PlayerCharacter {
     /// assert!(player.is_alive());
     /// ```
     pub fn new(name: String, position: Position) -> Self {
+        Self::with_class(name, position, CharacterClass::default())
+    }
+
+    /// Creates a new player character with the starting stats of `class`.
+    ///
+    /// The class's starting items are handed out separately by
+    /// [`GameState::initialize_player_with_class`](crate::GameState::initialize_player_with_class).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{CharacterClass, PlayerCharacter, Position};
+    ///
+    /// let position = Position::new(1, 1);
+    /// let mage = PlayerCharacter::with_class("Merlin".to_string(), position, CharacterClass::Mage);
+    /// assert_eq!(mage.class, CharacterClass::Mage);
+    /// assert_eq!(mage.stats.max_mana, CharacterClass::Mage.starting_stats().max_mana);
+    /// ```
+    pub fn with_class(name: String, position: Position, class: CharacterClass) -> Self {
         Self {
             id: new_entity_id(),
             position,
             name,
-            stats: EntityStats::new(),
+            stats: class.starting_stats(),
             equipment: HashMap::new(),
             inventory: Vec::new(),
             inventory_capacity: 20,
             sight_radius: 8,
+            statuses: StatusEffects::new(),
+            turns_in_water: 0,
+            class,
+            ability_ready_turn: 0,
+            prayer_ready_turn: 0,
+            known_spells: class
+                .starting_spells()
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            quiver: None,
             metadata: HashMap::new(),
         }
     }
 
+    /// Checks if the player knows the spell called `name`.
+    pub fn knows_spell(&self, name: &str) -> bool {
+        self.known_spells.iter().any(|known| known == name)
+    }
+
+    /// Checks if the player has been granted water breathing through their
+    /// metadata; worn gear is checked by
+    /// [`GameState::player_can_breathe_water`](crate::GameState::player_can_breathe_water).
+    pub fn can_breathe_water(&self) -> bool {
+        self.metadata
+            .get(WATER_BREATHING_METADATA_KEY)
+            .is_some_and(|value| value == "true")
+    }
+
     /// Checks if the player can pick up an item (inventory not full).
     pub fn can_pick_up_item(&self) -> bool {
         self.inventory.len() < self.inventory_capacity
@@ -424,6 +742,62 @@ impl PlayerCharacter {
     pub fn get_equipped_item(&self, slot: &str) -> Option<&EntityId> {
         self.equipment.get(slot)
     }
+
+    /// Gets the number of shots left in the quiver.
+    pub fn quiver_count(&self) -> u32 {
+        self.quiver.as_ref().map_or(0, |stack| stack.count)
+    }
+
+    /// Puts ammunition in the quiver.
+    ///
+    /// Ammunition of the same kind and name joins what is already there;
+    /// anything else takes its place, and the ammunition it replaces is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{AmmoType, Item, ItemStack, ItemType, PlayerCharacter, Position};
+    ///
+    /// let position = Position::new(1, 1);
+    /// let arrow = Item::new("Arrow".to_string(), ItemType::Ammo(AmmoType::Arrow), position);
+    /// let mut player = PlayerCharacter::new("Archer".to_string(), position);
+    ///
+    /// assert!(player.fill_quiver(ItemStack { item: arrow.clone(), count: 5 }).is_none());
+    /// assert!(player.fill_quiver(ItemStack::new(arrow)).is_none());
+    /// assert_eq!(player.quiver_count(), 6);
+    ///
+    /// let shot = player.take_from_quiver().unwrap();
+    /// assert_eq!(shot.name, "Arrow");
+    /// assert_eq!(player.quiver_count(), 5);
+    /// ```
+    pub fn fill_quiver(&mut self, stack: ItemStack) -> Option<ItemStack> {
+        match &mut self.quiver {
+            Some(quivered)
+                if quivered.item.item_type == stack.item.item_type
+                    && quivered.item.name == stack.item.name =>
+            {
+                quivered.count += stack.count;
+                None
+            }
+            _ => self.quiver.replace(stack),
+        }
+    }
+
+    /// Takes one shot out of the quiver.
+    ///
+    /// The rest of the quiver keeps going under a fresh item id.
+    pub fn take_from_quiver(&mut self) -> Option<Item> {
+        let stack = self.quiver.as_mut()?;
+        if stack.count > 1 {
+            stack.count -= 1;
+            let taken = stack.item.clone();
+            stack.item.id = new_entity_id();
+            Some(taken)
+        } else {
+            self.quiver.take().map(|stack| stack.item)
+        }
+    }
 }
 
 impl Entity for PlayerCharacter {
@@ -486,6 +860,7 @@ impl Entity for PlayerCharacter {
                     events.push(GameEvent::Message {
                         text: "You have died!".to_string(),
                         importance: MessageImportance::Critical,
+                        channel: MessageChannel::Combat,
                     });
                 } else if actual_damage > 0 {
                     #[cfg(feature = "dev-tools")]
@@ -495,6 +870,7 @@ impl Entity for PlayerCharacter {
                     events.push(GameEvent::Message {
                         text: format!("You take {} damage!", actual_damage),
                         importance: MessageImportance::Important,
+                        channel: MessageChannel::Combat,
                     });
                 }
 
@@ -508,6 +884,22 @@ impl Entity for PlayerCharacter {
                     Ok(vec![GameEvent::Message {
                         text: format!("You are healed for {} health!", actual_healing),
                         importance: MessageImportance::Normal,
+                        channel: MessageChannel::Combat,
+                    }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            GameEvent::StatusApplied {
+                entity_id,
+                kind,
+                turns,
+            } if *entity_id == self.id => {
+                if self.statuses.apply(*kind, *turns) {
+                    Ok(vec![GameEvent::Message {
+                        text: format!("You are {}.", kind.display_name().to_lowercase()),
+                        importance: MessageImportance::Normal,
+                        channel: MessageChannel::Combat,
                     }])
                 } else {
                     Ok(vec![])
@@ -528,39 +920,561 @@ impl Entity for PlayerCharacter {
     fn set_metadata(&mut self, key: String, value: String) {
         self.metadata.insert(key, value);
     }
+
+    fn stats(&self) -> Option<&EntityStats> {
+        Some(&self.stats)
+    }
+
+    fn stats_mut(&mut self) -> Option<&mut EntityStats> {
+        Some(&mut self.stats)
+    }
+
+    fn statuses(&self) -> Option<&StatusEffects> {
+        Some(&self.statuses)
+    }
+
+    fn statuses_mut(&mut self) -> Option<&mut StatusEffects> {
+        Some(&mut self.statuses)
+    }
+
+    fn inventory(&self) -> Option<&[EntityId]> {
+        Some(&self.inventory)
+    }
+}
+
+/// A monster roaming the dungeon.
+///
+/// Monsters share the player's stats and status components and are hostile
+/// by default. The LLDM can create unique monsters with [`MonsterType::Custom`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Monster {
+    /// Unique entity ID
+    pub id: EntityId,
+    /// Current position in the world
+    pub position: Position,
+    /// What kind of monster this is
+    pub monster_type: MonsterType,
+    /// Monster name
+    pub name: String,
+    /// Monster stats
+    pub stats: EntityStats,
+    /// Active status effects such as soaked or burning
+    #[serde(default)]
+    pub statuses: StatusEffects,
+    /// Whether the monster attacks the player
+    pub hostile: bool,
+    /// Items the monster may drop when it dies
+    #[serde(default)]
+    pub loot: Vec<LootDrop>,
+    /// Where the monster is headed to look for the player, if anywhere
+    #[serde(default)]
+    pub investigating: Option<Position>,
+    /// What the player has told the monster to do, if it is their ally
+    #[serde(default)]
+    pub ally: Option<crate::AllyCommand>,
+    /// LLDM integration metadata
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl Monster {
+    /// Creates a new hostile monster with the standard stats for its type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Entity, Monster, MonsterType, Position};
+    ///
+    /// let goblin = Monster::new(MonsterType::Goblin, Position::new(3, 4));
+    /// assert_eq!(goblin.name(), "Goblin");
+    /// assert_eq!(goblin.display_char(), 'g');
+    /// assert!(goblin.is_hostile());
+    /// ```
+    pub fn new(monster_type: MonsterType, position: Position) -> Self {
+        Self {
+            id: new_entity_id(),
+            position,
+            name: monster_type.display_name().to_string(),
+            stats: EntityStats::for_monster(&monster_type),
+            monster_type,
+            statuses: StatusEffects::new(),
+            hostile: true,
+            loot: Vec::new(),
+            investigating: None,
+            ally: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Returns true if the monster fights on the player's side.
+    pub fn is_ally(&self) -> bool {
+        self.ally.is_some()
+    }
+}
+
+impl Entity for Monster {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn display_char(&self) -> char {
+        self.monster_type.display_char()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn entity_type(&self) -> EntityType {
+        EntityType::Monster(self.monster_type.clone())
+    }
+
+    fn is_alive(&self) -> bool {
+        self.stats.is_alive()
+    }
+
+    fn update(&mut self) -> ThatchResult<Vec<GameEvent>> {
+        // Monster AI is driven by the game state
+        Ok(Vec::new())
+    }
+
+    fn handle_event(&mut self, event: &GameEvent) -> ThatchResult<Vec<GameEvent>> {
+        match event {
+            GameEvent::EntityDamaged {
+                entity_id,
+                damage,
+                source,
+            } if *entity_id == self.id => {
+                let actual_damage = self.stats.take_damage(*damage);
+                if !self.is_alive() {
+                    Ok(vec![
+                        GameEvent::EntityDied {
+                            entity_id: self.id,
+                            killer: *source,
+                        },
+                        GameEvent::Message {
+                            text: format!("The {} dies!", self.name),
+                            importance: MessageImportance::Important,
+                            channel: MessageChannel::Combat,
+                        },
+                    ])
+                } else if actual_damage > 0 {
+                    Ok(vec![GameEvent::Message {
+                        text: format!("The {} takes {} damage.", self.name, actual_damage),
+                        importance: MessageImportance::Normal,
+                        channel: MessageChannel::Combat,
+                    }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            GameEvent::EntityHealed {
+                entity_id, amount, ..
+            } if *entity_id == self.id => {
+                self.stats.heal(*amount);
+                Ok(vec![])
+            }
+            GameEvent::StatusApplied {
+                entity_id,
+                kind,
+                turns,
+            } if *entity_id == self.id => {
+                if self.statuses.apply(*kind, *turns) {
+                    Ok(vec![GameEvent::Message {
+                        text: format!(
+                            "The {} is {}.",
+                            self.name,
+                            kind.display_name().to_lowercase()
+                        ),
+                        importance: MessageImportance::Normal,
+                        channel: MessageChannel::Combat,
+                    }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    fn set_metadata(&mut self, key: String, value: String) {
+        self.metadata.insert(key, value);
+    }
+
+    fn stats(&self) -> Option<&EntityStats> {
+        Some(&self.stats)
+    }
+
+    fn stats_mut(&mut self) -> Option<&mut EntityStats> {
+        Some(&mut self.stats)
+    }
+
+    fn statuses(&self) -> Option<&StatusEffects> {
+        Some(&self.statuses)
+    }
+
+    fn statuses_mut(&mut self) -> Option<&mut StatusEffects> {
+        Some(&mut self.statuses)
+    }
+
+    fn is_hostile(&self) -> bool {
+        self.hostile
+    }
+}
+
+/// An item lying in the dungeon or carried by the player.
+///
+/// Items on the floor lie in their level's [`GroundItems`](crate::GroundItems).
+/// Carried items are kept off the map in
+/// [`GameState::carried_items`](crate::GameState::carried_items).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    /// Unique entity ID
+    pub id: EntityId,
+    /// Current position in the world
+    pub position: Position,
+    /// What kind of item this is
+    pub item_type: ItemType,
+    /// Item name
+    pub name: String,
+    /// Uses left before the item is spent, for wands and other charged items
+    #[serde(default)]
+    pub charges: Option<u32>,
+    /// Whether the item clings to its wearer, who cannot take it off
+    #[serde(default)]
+    pub cursed: bool,
+    /// Bonus to attack for weapons or to defense for armor; cursed gear has a penalty
+    #[serde(default)]
+    pub enchantment: i32,
+    /// Whether the player knows the item's curse and enchantment
+    #[serde(default)]
+    pub appraised: bool,
+    /// LLDM integration metadata
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl Item {
+    /// Creates a new item.
+    ///
+    /// Wands start with [`WAND_CHARGES`](crate::WAND_CHARGES) charges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Entity, Item, ItemType, Position, WeaponType};
+    ///
+    /// let item_type = ItemType::Weapon(WeaponType::Sword);
+    /// let sword = Item::new("Longsword".to_string(), item_type, Position::new(2, 3));
+    /// assert_eq!(sword.name(), "Longsword");
+    /// assert_eq!(sword.display_char(), ')');
+    /// ```
+    pub fn new(name: String, item_type: ItemType, position: Position) -> Self {
+        Self {
+            id: new_entity_id(),
+            position,
+            charges: matches!(item_type, ItemType::Consumable(ConsumableType::Wand))
+                .then_some(crate::WAND_CHARGES),
+            cursed: false,
+            enchantment: 0,
+            appraised: false,
+            item_type,
+            name,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Lifts the item's curse, clearing any penalty it put on the item.
+    ///
+    /// Returns true if the item was cursed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{ArmorType, Item, ItemType, Position};
+    ///
+    /// let mut helm = Item::new(
+    ///     "Iron helm".to_string(),
+    ///     ItemType::Armor(ArmorType::Helmet),
+    ///     Position::new(0, 0),
+    /// );
+    /// helm.cursed = true;
+    /// helm.enchantment = -2;
+    /// assert!(helm.remove_curse());
+    /// assert_eq!((helm.cursed, helm.enchantment), (false, 0));
+    /// assert!(!helm.remove_curse());
+    /// ```
+    pub fn remove_curse(&mut self) -> bool {
+        if !self.cursed {
+            return false;
+        }
+        self.cursed = false;
+        self.enchantment = self.enchantment.max(0);
+        self.appraised = true;
+        true
+    }
+}
+
+impl Entity for Item {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn display_char(&self) -> char {
+        self.item_type.display_char()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn entity_type(&self) -> EntityType {
+        EntityType::Item(self.item_type.clone())
+    }
+
+    fn is_alive(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self) -> ThatchResult<Vec<GameEvent>> {
+        Ok(Vec::new())
+    }
+
+    fn handle_event(&mut self, _event: &GameEvent) -> ThatchResult<Vec<GameEvent>> {
+        Ok(Vec::new())
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    fn set_metadata(&mut self, key: String, value: String) {
+        self.metadata.insert(key, value);
+    }
+}
+
+/// A friendly character the player can talk to.
+///
+/// NPCs keep shops and sanctuaries. They have no stats, so they can be
+/// neither hurt nor killed, and what they say depends on their [`NpcRole`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Npc {
+    /// Unique entity ID
+    pub id: EntityId,
+    /// Current position in the world
+    pub position: Position,
+    /// What the NPC does, which decides what it says
+    pub role: NpcRole,
+    /// NPC name
+    pub name: String,
+    /// LLDM integration metadata
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub metadata: HashMap<String, String>,
+}
+
+impl Npc {
+    /// Creates a new NPC named after its role.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Entity, Npc, NpcRole, Position};
+    ///
+    /// let priest = Npc::new(NpcRole::Priest, Position::new(3, 4));
+    /// assert_eq!(priest.name(), "Priest");
+    /// assert!(!priest.is_hostile());
+    /// ```
+    pub fn new(role: NpcRole, position: Position) -> Self {
+        Self {
+            id: new_entity_id(),
+            position,
+            role,
+            name: role.display_name().to_string(),
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl Entity for Npc {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn display_char(&self) -> char {
+        'p'
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn entity_type(&self) -> EntityType {
+        EntityType::Npc
+    }
+
+    fn is_alive(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self) -> ThatchResult<Vec<GameEvent>> {
+        Ok(Vec::new())
+    }
+
+    fn handle_event(&mut self, _event: &GameEvent) -> ThatchResult<Vec<GameEvent>> {
+        Ok(Vec::new())
+    }
+
+    fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    fn set_metadata(&mut self, key: String, value: String) {
+        self.metadata.insert(key, value);
+    }
 }
 
 /// Concrete entity types for serialization.
 ///
 /// This enum replaces the trait object approach due to Rust's serialization
 /// limitations with trait objects. Each variant represents a concrete entity type.
+/// Everything else goes through [`ConcreteEntity::as_entity`] and the
+/// [`Entity`] trait's component accessors, so adding a variant only needs new
+/// arms in `as_entity` and `as_entity_mut`.
+// There is only ever one player, so its larger size costs little
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConcreteEntity {
     Player(PlayerCharacter),
-    // Additional concrete types will be added as we implement them
+    Monster(Monster),
+    Item(Item),
+    Npc(Npc),
 }
 
 impl ConcreteEntity {
-    /// Gets the entity ID.
-    pub fn id(&self) -> EntityId {
+    /// Borrows the entity through the common [`Entity`] interface.
+    pub fn as_entity(&self) -> &dyn Entity {
         match self {
-            ConcreteEntity::Player(player) => player.id(),
+            ConcreteEntity::Player(player) => player,
+            ConcreteEntity::Monster(monster) => monster,
+            ConcreteEntity::Item(item) => item,
+            ConcreteEntity::Npc(npc) => npc,
         }
     }
 
-    /// Gets the entity position.
-    pub fn position(&self) -> Position {
+    /// Mutably borrows the entity through the common [`Entity`] interface.
+    pub fn as_entity_mut(&mut self) -> &mut dyn Entity {
         match self {
-            ConcreteEntity::Player(player) => player.position(),
+            ConcreteEntity::Player(player) => player,
+            ConcreteEntity::Monster(monster) => monster,
+            ConcreteEntity::Item(item) => item,
+            ConcreteEntity::Npc(npc) => npc,
         }
     }
 
-    /// Checks if the entity is alive.
-    pub fn is_alive(&self) -> bool {
+    /// Gets the player character, if this entity is the player.
+    pub fn as_player(&self) -> Option<&PlayerCharacter> {
+        match self {
+            ConcreteEntity::Player(player) => Some(player),
+            _ => None,
+        }
+    }
+
+    /// Gets the player character mutably, if this entity is the player.
+    pub fn as_player_mut(&mut self) -> Option<&mut PlayerCharacter> {
+        match self {
+            ConcreteEntity::Player(player) => Some(player),
+            _ => None,
+        }
+    }
+
+    /// Gets the monster, if this entity is a monster.
+    pub fn as_monster(&self) -> Option<&Monster> {
+        match self {
+            ConcreteEntity::Monster(monster) => Some(monster),
+            _ => None,
+        }
+    }
+
+    /// Gets the monster mutably, if this entity is a monster.
+    pub fn as_monster_mut(&mut self) -> Option<&mut Monster> {
         match self {
-            ConcreteEntity::Player(player) => player.is_alive(),
+            ConcreteEntity::Monster(monster) => Some(monster),
+            _ => None,
         }
     }
+
+    /// Gets the NPC, if this entity is one.
+    pub fn as_npc(&self) -> Option<&Npc> {
+        match self {
+            ConcreteEntity::Npc(npc) => Some(npc),
+            _ => None,
+        }
+    }
+
+    /// Gets the entity ID.
+    pub fn id(&self) -> EntityId {
+        self.as_entity().id()
+    }
+
+    /// Gets the entity position.
+    pub fn position(&self) -> Position {
+        self.as_entity().position()
+    }
+
+    /// Checks if the entity is alive.
+    pub fn is_alive(&self) -> bool {
+        self.as_entity().is_alive()
+    }
+
+    /// Gets the entity name.
+    pub fn name(&self) -> &str {
+        self.as_entity().name()
+    }
+
+    /// Gets the entity stats, if it has any.
+    pub fn stats(&self) -> Option<&EntityStats> {
+        self.as_entity().stats()
+    }
 }
 
 impl From<PlayerCharacter> for ConcreteEntity {
@@ -569,6 +1483,24 @@ impl From<PlayerCharacter> for ConcreteEntity {
     }
 }
 
+impl From<Monster> for ConcreteEntity {
+    fn from(monster: Monster) -> Self {
+        ConcreteEntity::Monster(monster)
+    }
+}
+
+impl From<Item> for ConcreteEntity {
+    fn from(item: Item) -> Self {
+        ConcreteEntity::Item(item)
+    }
+}
+
+impl From<Npc> for ConcreteEntity {
+    fn from(npc: Npc) -> Self {
+        ConcreteEntity::Npc(npc)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -684,6 +1616,41 @@ mod tests {
         assert_eq!(dragon_stats.level, 20);
     }
 
+    #[test]
+    fn test_monster_damage_and_death() {
+        let mut monster = Monster::new(MonsterType::Goblin, Position::origin());
+        let killer = new_entity_id();
+
+        let events = monster
+            .handle_event(&GameEvent::EntityDamaged {
+                entity_id: monster.id(),
+                damage: 1000,
+                source: Some(killer),
+            })
+            .unwrap();
+
+        assert!(!monster.is_alive());
+        assert!(events.contains(&GameEvent::EntityDied {
+            entity_id: monster.id(),
+            killer: Some(killer),
+        }));
+    }
+
+    #[test]
+    fn test_concrete_entity_dispatch() {
+        let player: ConcreteEntity = PlayerCharacter::new("Hero".to_string(), Position::origin()).into();
+        let mut monster: ConcreteEntity = Monster::new(MonsterType::Orc, Position::new(1, 1)).into();
+
+        assert!(player.as_player().is_some());
+        assert!(monster.as_player().is_none());
+        assert!(!player.as_entity().is_hostile());
+        assert!(monster.as_entity().is_hostile());
+        assert_eq!(monster.stats().unwrap().attack, 12);
+
+        monster.as_entity_mut().set_position(Position::new(2, 2));
+        assert_eq!(monster.position(), Position::new(2, 2));
+    }
+
     #[test]
     fn test_entity_serialization() {
         let player = PlayerCharacter::new("Test".to_string(), Position::new(1, 2));
@@ -0,0 +1,123 @@
+//! # Event Log Module
+//!
+//! Newline-delimited JSON stream of everything that happens in a run.
+//!
+//! When the game is started with `--event-log <path>`, every executed action,
+//! every [`GameEvent`] and a marker at the end of each turn are appended to the
+//! file as they happen, one JSON object per line. External tools can tail the
+//! file to follow a run without being built into the game:
+//!
+//! ```json
+//! {"kind":"action","turn":4,"action":{"Search":{"actor":"…","metadata":{}}}}
+//! {"kind":"event","turn":4,"event":{"SecretFound":{"finder_id":"…","position":{"x":3,"y":5}}}}
+//! {"kind":"turn","turn":5}
+//! ```
+
+use crate::{ConcreteAction, GameEvent, ThatchResult};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+
+/// One line of the event log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EventLogEntry<'a> {
+    /// An action that executed successfully
+    Action {
+        /// Turn the action was taken on
+        turn: u64,
+        /// The action
+        action: &'a ConcreteAction,
+    },
+    /// Something that happened, including events raised in response to others
+    Event {
+        /// Turn the event happened on
+        turn: u64,
+        /// The event
+        event: &'a GameEvent,
+    },
+    /// A new turn has started
+    Turn {
+        /// Number of the turn that started
+        turn: u64,
+    },
+}
+
+/// Appends [`EventLogEntry`] lines to a file.
+#[derive(Debug)]
+pub struct EventLog {
+    /// Output file; each entry is flushed as soon as its line is complete
+    writer: LineWriter<File>,
+}
+
+impl EventLog {
+    /// Opens `path` for appending, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn open(path: &Path) -> ThatchResult<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: LineWriter::new(file),
+        })
+    }
+
+    /// Writes one entry as a line of JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry cannot be serialized or written.
+    pub fn record(&mut self, entry: &EventLogEntry) -> ThatchResult<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_entity_id, WaitAction};
+
+    #[test]
+    fn test_entries_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+        let action = ConcreteAction::Wait(WaitAction::new(new_entity_id()));
+        let event = GameEvent::Message {
+            text: "Hello".to_string(),
+            importance: crate::MessageImportance::Info,
+            channel: crate::MessageChannel::System,
+        };
+
+        let mut log = EventLog::open(&path).unwrap();
+        log.record(&EventLogEntry::Action {
+            turn: 0,
+            action: &action,
+        })
+        .unwrap();
+        log.record(&EventLogEntry::Event {
+            turn: 0,
+            event: &event,
+        })
+        .unwrap();
+        // Reopening appends rather than truncating
+        let mut log = EventLog::open(&path).unwrap();
+        log.record(&EventLogEntry::Turn { turn: 1 }).unwrap();
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let kinds: Vec<&str> = lines
+            .iter()
+            .map(|line| line["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["action", "event", "turn"]);
+        assert_eq!(lines[1]["event"]["Message"]["text"], "Hello");
+        assert_eq!(lines[2]["turn"], 1);
+    }
+}
@@ -0,0 +1,172 @@
+//! # Exploration
+//!
+//! How much of each floor the player has seen.
+//!
+//! Every time the player looks around, the current floor is surveyed: the
+//! tiles the player could walk to from where they stand are counted along with
+//! how many of them have been seen, and any room with a seen floor tile is
+//! marked discovered. Tiles behind undiscovered secret doors are not
+//! reachable until the door is found. The results are kept per floor in
+//! [`GameStatistics`](crate::GameStatistics) for the side panel, the stairs
+//! prompt and the morgue file.
+
+use crate::{Level, Position, TileType};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// How much of a floor the player has explored.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::LevelExploration;
+///
+/// let exploration = LevelExploration {
+///     seen_tiles: 45,
+///     reachable_tiles: 100,
+///     rooms_discovered: 3,
+///     total_rooms: 7,
+/// };
+/// assert_eq!(exploration.tile_percentage(), 45);
+/// assert_eq!(exploration.summary(), "45% explored, 3/7 rooms");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelExploration {
+    /// Reachable tiles the player has seen
+    pub seen_tiles: u32,
+    /// Tiles the player can walk to, through doors but not undiscovered secret doors
+    pub reachable_tiles: u32,
+    /// Rooms with at least one floor tile seen
+    pub rooms_discovered: u32,
+    /// Rooms on the floor
+    pub total_rooms: u32,
+}
+
+impl LevelExploration {
+    /// Surveys how much of `level` the player has explored, walking out from
+    /// `origin`, and marks every room they have seen into as discovered.
+    pub fn survey(level: &mut Level, origin: Position) -> Self {
+        let width = level.width as usize;
+        let index = |position: Position| position.y as usize * width + position.x as usize;
+        let walkable = |level: &Level, position: Position| {
+            level.get_tile(position).is_some_and(|tile| {
+                tile.tile_type.is_passable() || matches!(tile.tile_type, TileType::Door { .. })
+            })
+        };
+
+        // The player's own tile counts even if they stand somewhere impassable
+        let mut exploration = Self::default();
+        if level.get_tile(origin).is_some() {
+            let mut visited = vec![false; width * level.height as usize];
+            visited[index(origin)] = true;
+            let mut queue = VecDeque::from([origin]);
+            while let Some(position) = queue.pop_front() {
+                exploration.reachable_tiles += 1;
+                if level
+                    .get_tile(position)
+                    .is_some_and(|tile| tile.is_explored())
+                {
+                    exploration.seen_tiles += 1;
+                }
+                for next in position.cardinal_adjacent_positions() {
+                    if walkable(level, next) && !visited[index(next)] {
+                        visited[index(next)] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let tiles = &level.tiles;
+        for room in &mut level.rooms {
+            room.discovered = room.discovered
+                || room.floor_positions().iter().any(|position| {
+                    tiles
+                        .get(position.y as usize)
+                        .and_then(|row| row.get(position.x as usize))
+                        .is_some_and(|tile| tile.is_explored())
+                });
+            exploration.rooms_discovered += u32::from(room.discovered);
+        }
+        exploration.total_rooms = level.rooms.len() as u32;
+        exploration
+    }
+
+    /// Gets the percentage of reachable tiles the player has seen.
+    pub fn tile_percentage(&self) -> u32 {
+        (self.seen_tiles * 100)
+            .checked_div(self.reachable_tiles)
+            .unwrap_or(0)
+            .min(100)
+    }
+
+    /// Describes the exploration in a few words, for the side panel.
+    pub fn summary(&self) -> String {
+        format!(
+            "{}% explored, {}/{} rooms",
+            self.tile_percentage(),
+            self.rooms_discovered,
+            self.total_rooms
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Room, RoomType, Tile};
+
+    #[test]
+    fn test_survey_counts_reachable_tiles_and_rooms() {
+        let mut level = Level::new(0, 12, 5);
+        for x in 1..11 {
+            level.set_tile(Position::new(x, 2), Tile::floor()).unwrap();
+        }
+        level
+            .set_tile(
+                Position::new(4, 2),
+                Tile::new(TileType::Door { is_open: false }),
+            )
+            .unwrap();
+        level
+            .set_tile(Position::new(8, 2), Tile::new(TileType::SecretDoor))
+            .unwrap();
+        level
+            .rooms
+            .push(Room::new(0, Position::new(0, 1), 4, 3, RoomType::Normal));
+        level
+            .rooms
+            .push(Room::new(1, Position::new(5, 1), 3, 3, RoomType::Normal));
+        level
+            .rooms
+            .push(Room::new(2, Position::new(9, 1), 3, 3, RoomType::Normal));
+
+        // The closed door is walked through, but nothing past the secret door counts
+        let mut game_state = GameState::new_with_level(level, 12345).unwrap();
+        let start = Position::new(1, 2);
+        game_state
+            .initialize_player("Mapper".to_string(), start)
+            .unwrap();
+        game_state.get_player_mut().unwrap().sight_radius = 0;
+        game_state.update_player_visibility(start).unwrap();
+        let exploration = game_state.current_exploration();
+        assert_eq!(
+            (exploration.seen_tiles, exploration.reachable_tiles),
+            (1, 7)
+        );
+        assert_eq!(
+            (exploration.rooms_discovered, exploration.total_rooms),
+            (1, 3)
+        );
+        assert_eq!(game_state.statistics.rooms_discovered, 1);
+
+        // Walking on into the next room discovers it too
+        let next = Position::new(6, 2);
+        game_state.update_player_visibility(next).unwrap();
+        assert_eq!(
+            game_state.current_exploration().summary(),
+            "28% explored, 2/3 rooms"
+        );
+        assert_eq!(game_state.statistics.rooms_discovered, 2);
+    }
+}
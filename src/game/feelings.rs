@@ -0,0 +1,139 @@
+//! # Level Feelings
+//!
+//! Hints about a floor given the first time the player arrives.
+//!
+//! When the player first steps onto a floor, the game looks over what the
+//! generator put there and warns of anything unusual: a monster from far
+//! deeper down, a crowd of guards, or more treasure than usual. Floors with
+//! nothing remarkable get no message.
+
+use crate::{Level, MessageImportance, RoomType, VAULT_METADATA_KEY};
+
+/// Fewest waiting monsters for a floor to feel dangerous.
+pub const DANGEROUS_FLOOR_MONSTERS: usize = 4;
+
+/// Lowest treasure score for a floor to feel rich.
+///
+/// Each treasure room counts two, and each container or pile of items on the
+/// ground counts one.
+pub const RICH_FLOOR_SCORE: usize = 6;
+
+/// Something unusual about a floor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelFeeling {
+    /// A monster from several floors deeper waits here
+    TerriblePresence,
+    /// Many monsters wait here
+    Dangerous,
+    /// The floor holds more treasure than usual
+    Rich,
+    /// A vault was built on the floor
+    Vault,
+}
+
+impl LevelFeeling {
+    /// Gets the message shown for the feeling.
+    pub fn message(self) -> &'static str {
+        match self {
+            LevelFeeling::TerriblePresence => "You feel a terrible presence on this floor...",
+            LevelFeeling::Dangerous => "The air is thick with menace. This floor feels dangerous.",
+            LevelFeeling::Rich => "You catch the glint of gold. This floor feels rich.",
+            LevelFeeling::Vault => "Someone built something here with care, and guards it still.",
+        }
+    }
+
+    /// Gets how prominently the message is shown.
+    pub fn importance(self) -> MessageImportance {
+        match self {
+            LevelFeeling::TerriblePresence => MessageImportance::Critical,
+            LevelFeeling::Dangerous => MessageImportance::Important,
+            LevelFeeling::Rich | LevelFeeling::Vault => MessageImportance::Normal,
+        }
+    }
+}
+
+/// Works out the feelings for a floor from what was generated on it.
+///
+/// Waiting monsters are only counted before they spawn, so this is meant to
+/// be called as the player first arrives.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{level_feelings, Level, LevelFeeling, Position, Room, RoomType};
+///
+/// let mut level = Level::new(0, 40, 20);
+/// assert!(level_feelings(&level).is_empty());
+///
+/// for id in 0..3 {
+///     let room = Room::new(id, Position::new(id as i32 * 10, 0), 6, 6, RoomType::Treasure);
+///     level.rooms.push(room);
+/// }
+/// assert_eq!(level_feelings(&level), vec![LevelFeeling::Rich]);
+/// ```
+pub fn level_feelings(level: &Level) -> Vec<LevelFeeling> {
+    let mut feelings = Vec::new();
+    if level
+        .waiting_monsters
+        .iter()
+        .any(|waiting| waiting.out_of_depth)
+    {
+        feelings.push(LevelFeeling::TerriblePresence);
+    }
+    if level.waiting_monsters.len() >= DANGEROUS_FLOOR_MONSTERS {
+        feelings.push(LevelFeeling::Dangerous);
+    }
+
+    let treasure_rooms = level
+        .rooms
+        .iter()
+        .filter(|room| room.room_type == RoomType::Treasure)
+        .count();
+    let score = treasure_rooms * 2 + level.containers.len() + level.ground_items.iter().count();
+    if score >= RICH_FLOOR_SCORE {
+        feelings.push(LevelFeeling::Rich);
+    }
+
+    if level
+        .rooms
+        .iter()
+        .any(|room| room.metadata.contains_key(VAULT_METADATA_KEY))
+    {
+        feelings.push(LevelFeeling::Vault);
+    }
+    feelings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, TemplateLibrary, WaitingMonster};
+
+    #[test]
+    fn test_feelings_warn_of_monsters_from_deeper_down() {
+        let mut level = Level::new(2, 30, 10);
+        let templates = TemplateLibrary::builtin().unwrap();
+        let goblin = templates.monster_template("Goblin").unwrap();
+        for x in 1..4 {
+            level.waiting_monsters.push(WaitingMonster {
+                position: Position::new(x, 1),
+                monster: goblin.clone(),
+                out_of_depth: false,
+            });
+        }
+        assert!(level_feelings(&level).is_empty());
+
+        let troll = templates.monster_template("Troll").unwrap();
+        level.waiting_monsters.push(WaitingMonster {
+            position: Position::new(5, 1),
+            monster: troll.clone(),
+            out_of_depth: true,
+        });
+        let feelings = level_feelings(&level);
+        assert_eq!(
+            feelings,
+            vec![LevelFeeling::TerriblePresence, LevelFeeling::Dangerous]
+        );
+        assert_eq!(feelings[0].importance(), MessageImportance::Critical);
+    }
+}
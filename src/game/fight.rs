@@ -0,0 +1,139 @@
+//! # Auto-Fight
+//!
+//! Fighting the nearest hostile without aiming each blow.
+//!
+//! The auto-fight command picks the nearest visible hostile and then, one turn
+//! per step like travel, attacks it when it is next to the player or walks a
+//! step towards it otherwise, so fights can be won on a touch screen without
+//! precise directional input. It stops once the foe dies or is lost from view,
+//! or when an [`Interruption`] fires: a new hostile, low health, or a critical
+//! message. Any key press stops it too.
+//!
+//! [`Interruption`]: crate::Interruption
+
+use crate::{EntityId, InterruptionWatch};
+
+/// Farthest away, in tiles walked, a hostile can be to be picked as a foe.
+pub const FIGHT_RANGE: u32 = 12;
+
+/// Fraction of maximum health below which auto-fight stops.
+pub const FIGHT_HEALTH_THRESHOLD: f32 = 0.3;
+
+/// State of an in-progress auto-fight command.
+#[derive(Debug, Clone)]
+pub struct FightState {
+    /// Hostile being fought, if fighting
+    pub target: Option<EntityId>,
+    /// Last action execution time for speed control
+    pub last_action_time: Option<std::time::Instant>,
+    /// Delay between blows and steps in milliseconds
+    pub action_delay_ms: u64,
+    /// What was already in view when the fight started, and what should stop it
+    pub watch: InterruptionWatch,
+}
+
+impl FightState {
+    /// Creates an idle fight state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            last_action_time: None,
+            action_delay_ms: 100,
+            watch: InterruptionWatch::new(Some(FIGHT_HEALTH_THRESHOLD)),
+        }
+    }
+
+    /// Stops fighting.
+    pub fn cancel(&mut self) {
+        self.target = None;
+        self.last_action_time = None;
+        self.watch.clear();
+    }
+
+    /// Returns true while there is a foe to fight.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Checks if enough time has passed for the next blow or step.
+    #[must_use]
+    pub fn can_perform_action(&self) -> bool {
+        self.last_action_time.is_none_or(|last_time| {
+            last_time.elapsed().as_millis() >= u128::from(self.action_delay_ms)
+        })
+    }
+}
+
+impl Default for FightState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConcreteAction, GameState, Monster, MonsterType, Position, Tile};
+
+    #[test]
+    fn test_fight_closes_in_and_attacks_the_nearest_hostile() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 3..=12 {
+                level.set_tile(Position::new(x, 5), Tile::floor()).unwrap();
+            }
+        }
+        game_state
+            .initialize_player("Brawler".to_string(), Position::new(3, 5))
+            .unwrap();
+        assert!(game_state.start_fight().is_err());
+
+        let goblin = Monster::new(MonsterType::Goblin, Position::new(6, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+        game_state
+            .update_player_visibility(Position::new(3, 5))
+            .unwrap();
+        game_state.start_fight().unwrap();
+        game_state.fight_state.action_delay_ms = 0;
+        assert_eq!(game_state.fight_state.target, Some(goblin_id));
+
+        // Two steps along the corridor, then a blow
+        let mut actions = Vec::new();
+        for _ in 0..3 {
+            assert_eq!(game_state.check_fight_interruption(), None);
+            let action = game_state.get_fight_action().unwrap().unwrap();
+            for event in action.execute(&mut game_state).unwrap() {
+                game_state.process_event(&event).unwrap();
+            }
+            actions.push(action);
+        }
+        assert!(matches!(actions[0], ConcreteAction::Move(_)));
+        assert!(matches!(actions[1], ConcreteAction::Move(_)));
+        assert!(matches!(actions[2], ConcreteAction::Attack(_)));
+        let player_position = game_state.get_player().unwrap().position;
+        assert_eq!(player_position, Position::new(5, 5));
+
+        // A newcomer calls the fight off
+        let orc = Monster::new(MonsterType::Orc, Position::new(8, 5));
+        let orc_id = game_state.add_entity(orc.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(orc_id);
+        game_state
+            .update_player_visibility(Position::new(5, 5))
+            .unwrap();
+        let reason = game_state.check_fight_interruption().unwrap();
+        assert!(reason.contains("hostile"));
+        assert!(!game_state.is_fighting());
+        assert!(game_state.get_fight_action().unwrap().is_none());
+    }
+}
@@ -0,0 +1,223 @@
+//! # Ground Module
+//!
+//! Items lying on the floor of a level.
+//!
+//! Any number of items can share a tile. They pile up in the order they were
+//! dropped, so the last one dropped is on top and is the one drawn on the map.
+//! Identical consumables and ammunition dropped on the same tile merge into a
+//! single [`ItemStack`] with a count.
+
+use crate::{EntityId, Item, ItemType, Position};
+use serde::{Deserialize, Serialize};
+
+/// One or more identical items lying on a tile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    /// The item; its position is the tile the stack lies on
+    pub item: Item,
+    /// Number of items in the stack, at least one
+    pub count: u32,
+}
+
+impl ItemStack {
+    /// Creates a stack holding a single item.
+    pub fn new(item: Item) -> Self {
+        Self { item, count: 1 }
+    }
+
+    /// Checks if `item` can join this stack.
+    ///
+    /// Only consumables and ammunition stack, and only with items of the same
+    /// type and name on the same tile. Charged items such as wands never stack.
+    pub fn stacks_with(&self, item: &Item) -> bool {
+        matches!(item.item_type, ItemType::Consumable(_) | ItemType::Ammo(_))
+            && item.charges.is_none()
+            && self.item.position == item.position
+            && self.item.item_type == item.item_type
+            && self.item.name == item.name
+    }
+
+    /// Describes the stack for messages, such as "3 x Potion of healing",
+    /// given the name the player knows the item by.
+    pub fn label(&self, name: &str) -> String {
+        if self.count > 1 {
+            format!("{} x {}", self.count, name)
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// Every item lying on the floor of one level.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{ConsumableType, GroundItems, Item, ItemType, Position};
+///
+/// let position = Position::new(3, 4);
+/// let potion = ItemType::Consumable(ConsumableType::HealthPotion);
+/// let mut ground = GroundItems::new();
+/// ground.drop_item(Item::new("Potion of healing".to_string(), potion.clone(), position));
+/// ground.drop_item(Item::new("Potion of healing".to_string(), potion, position));
+///
+/// assert_eq!(ground.stacks_at(position).count(), 1);
+/// assert_eq!(ground.top_at(position).unwrap().count, 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroundItems {
+    /// Stacks in the order they were dropped; later stacks lie on top
+    stacks: Vec<ItemStack>,
+}
+
+impl GroundItems {
+    /// Creates an empty floor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts an item on the floor at its position, stacking it if it can.
+    pub fn drop_item(&mut self, item: Item) {
+        match self
+            .stacks
+            .iter_mut()
+            .find(|stack| stack.stacks_with(&item))
+        {
+            Some(stack) => stack.count += 1,
+            None => self.stacks.push(ItemStack::new(item)),
+        }
+    }
+
+    /// Puts a whole stack on the floor at its item's position, merging it into
+    /// a matching stack if there is one.
+    pub fn drop_stack(&mut self, stack: ItemStack) {
+        match self
+            .stacks
+            .iter_mut()
+            .find(|existing| existing.stacks_with(&stack.item))
+        {
+            Some(existing) => existing.count += stack.count,
+            None => self.stacks.push(stack),
+        }
+    }
+
+    /// Iterates over the stacks on a tile, from the bottom of the pile to the top.
+    pub fn stacks_at(&self, position: Position) -> impl Iterator<Item = &ItemStack> {
+        self.stacks
+            .iter()
+            .filter(move |stack| stack.item.position == position)
+    }
+
+    /// Gets the stack on top of the pile on a tile.
+    pub fn top_at(&self, position: Position) -> Option<&ItemStack> {
+        self.stacks_at(position).last()
+    }
+
+    /// Takes one item from the stack with the given item id.
+    ///
+    /// Taking from a stack of several leaves the rest behind under a fresh
+    /// item id.
+    pub fn take(&mut self, item_id: EntityId) -> Option<Item> {
+        let index = self
+            .stacks
+            .iter()
+            .position(|stack| stack.item.id == item_id)?;
+        let stack = &mut self.stacks[index];
+        if stack.count > 1 {
+            stack.count -= 1;
+            let taken = stack.item.clone();
+            stack.item.id = crate::new_entity_id();
+            Some(taken)
+        } else {
+            Some(self.stacks.remove(index).item)
+        }
+    }
+
+    /// Takes the whole stack with the given item id.
+    pub fn take_stack(&mut self, item_id: EntityId) -> Option<ItemStack> {
+        let index = self
+            .stacks
+            .iter()
+            .position(|stack| stack.item.id == item_id)?;
+        Some(self.stacks.remove(index))
+    }
+
+    /// Builds the "You see here" message for a tile, if anything lies on it.
+    ///
+    /// `name_of` gives the name the player knows each item by.
+    pub fn describe_at(
+        &self,
+        position: Position,
+        name_of: impl Fn(&Item) -> String,
+    ) -> Option<String> {
+        let mut labels: Vec<String> = self
+            .stacks_at(position)
+            .map(|stack| stack.label(&name_of(&stack.item)))
+            .collect();
+        labels.reverse();
+        (!labels.is_empty()).then(|| format!("You see here: {}.", labels.join(", ")))
+    }
+
+    /// Iterates over every stack on the floor.
+    pub fn iter(&self) -> impl Iterator<Item = &ItemStack> {
+        self.stacks.iter()
+    }
+
+    /// Iterates over every stack on the floor mutably.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut ItemStack> {
+        self.stacks.iter_mut()
+    }
+
+    /// Returns true if nothing lies on the floor.
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsumableType, WeaponType};
+
+    #[test]
+    fn test_piles_stack_consumables_only() {
+        let position = Position::new(1, 1);
+        let potion = ItemType::Consumable(ConsumableType::HealthPotion);
+        let dagger = ItemType::Weapon(WeaponType::Dagger);
+        let mut ground = GroundItems::new();
+
+        ground.drop_item(Item::new("Dagger".to_string(), dagger.clone(), position));
+        ground.drop_item(Item::new("Dagger".to_string(), dagger, position));
+        ground.drop_item(Item::new(
+            "Potion of healing".to_string(),
+            potion.clone(),
+            position,
+        ));
+        ground.drop_item(Item::new(
+            "Potion of healing".to_string(),
+            potion.clone(),
+            position,
+        ));
+        ground.drop_item(Item::new(
+            "Potion of healing".to_string(),
+            potion,
+            Position::new(2, 1),
+        ));
+
+        assert_eq!(ground.stacks_at(position).count(), 3);
+        let name_of = |item: &Item| item.name.clone();
+        assert_eq!(
+            ground.describe_at(position, name_of).unwrap(),
+            "You see here: 2 x Potion of healing, Dagger, Dagger."
+        );
+
+        let top_id = ground.top_at(position).unwrap().item.id;
+        let taken = ground.take(top_id).unwrap();
+        assert_eq!(taken.name, "Potion of healing");
+        assert_ne!(ground.top_at(position).unwrap().item.id, taken.id);
+        assert_eq!(ground.top_at(position).unwrap().count, 1);
+        ground.take(ground.top_at(position).unwrap().item.id);
+        assert_eq!(ground.top_at(position).unwrap().item.name, "Dagger");
+        assert!(ground.describe_at(Position::new(5, 5), name_of).is_none());
+    }
+}
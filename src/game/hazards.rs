@@ -0,0 +1,124 @@
+//! # Hazards
+//!
+//! Terrain that hurts whatever steps onto it.
+//!
+//! Lava sets anything wading through it on fire and burns it for
+//! [`LAVA_DAMAGE`] on top. A chasm drops whatever steps in to the floor below,
+//! where the player takes [`FALL_DAMAGE`]. Floors share one grid, since stairs
+//! down on one floor sit where the stairs up are on the floor below, so a fall
+//! lands right under the chasm, or on the [`landing_spot`] nearest to it when
+//! that tile is solid rock or itself a hazard. Paths charge
+//! [`HAZARD_PATH_COST`] for stepping onto a hazard, so travel, autoexplore and
+//! monsters go around unless there is no other way.
+
+use crate::{Level, Position};
+
+/// Damage taken on each step onto lava, besides the burning it causes.
+pub const LAVA_DAMAGE: u32 = 6;
+
+/// Turns an entity keeps burning after stepping onto lava.
+pub const LAVA_BURNING_DURATION: u32 = 3;
+
+/// Damage the player takes from falling to the floor below.
+pub const FALL_DAMAGE: u32 = 8;
+
+/// What stepping onto a hazard costs a path, against 1 for any other tile.
+pub const HAZARD_PATH_COST: u32 = 25;
+
+/// Finds the safe tile on `level` nearest to `above`, the point a fall from
+/// the floor above comes down at, for which `taken` returns false.
+///
+/// A safe tile can be walked on and is not a hazard. Ties go to the topmost,
+/// then leftmost tile, so a fall always lands in the same place. Returns
+/// `None` if the level has no safe tile left.
+pub fn landing_spot(
+    level: &Level,
+    above: Position,
+    taken: impl Fn(Position) -> bool,
+) -> Option<Position> {
+    (0..level.height as i32)
+        .flat_map(|y| (0..level.width as i32).map(move |x| Position::new(x, y)))
+        .filter(|&position| {
+            level
+                .get_tile(position)
+                .is_some_and(|tile| tile.tile_type.is_passable() && !tile.tile_type.is_hazard())
+                && !taken(position)
+        })
+        .min_by_key(|&position| (above.manhattan_distance(position), position.y, position.x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ConcreteAction, Direction, Entity, GameEvent, GameState, MoveAction, PathfindingUtils,
+        StatusKind, Tile, TileType,
+    };
+
+    #[test]
+    fn test_lava_burns_and_chasms_drop_to_the_floor_below() {
+        let mut game_state = GameState::new(31);
+        let mut below = Level::new(1, 20, 20);
+        let level = game_state.world.current_level_mut().unwrap();
+        for x in 5..12 {
+            for y in 5..12 {
+                level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+                below.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+            }
+        }
+        level
+            .set_tile(Position::new(7, 5), Tile::new(TileType::Lava))
+            .unwrap();
+        level
+            .set_tile(Position::new(9, 5), Tile::new(TileType::Chasm))
+            .unwrap();
+        // Right under the chasm is rock, so the fall lands beside it
+        below.set_tile(Position::new(9, 5), Tile::wall()).unwrap();
+        game_state.world.add_level(below);
+        let player_id = game_state
+            .initialize_player("Scorched".to_string(), Position::new(6, 5))
+            .unwrap();
+
+        // Paths step around the lava rather than through it
+        let level = game_state.world.current_level().unwrap();
+        let path = PathfindingUtils::new()
+            .find_path(level, Position::new(6, 5), Position::new(8, 5))
+            .unwrap();
+        assert_eq!(path.len(), 4);
+        assert!(!path.contains(&Position::new(7, 5)));
+
+        let step = |game_state: &mut GameState| {
+            ConcreteAction::Move(MoveAction::new(player_id, Direction::East))
+                .execute(game_state)
+                .unwrap()
+        };
+        let events = step(&mut game_state);
+        let burned = GameEvent::EntityDamaged {
+            entity_id: player_id,
+            damage: LAVA_DAMAGE,
+            source: None,
+        };
+        assert!(events.contains(&burned));
+        assert!(game_state
+            .get_player()
+            .unwrap()
+            .statuses
+            .has(StatusKind::Burning));
+
+        step(&mut game_state);
+        let events = step(&mut game_state);
+        assert_eq!(game_state.world.current_level_id, 1);
+        assert_eq!(
+            game_state.get_player().unwrap().position(),
+            Position::new(8, 5)
+        );
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GameEvent::PlayerChangedLevel { new_level: 1, .. })));
+        assert!(events.contains(&GameEvent::EntityDamaged {
+            entity_id: player_id,
+            damage: FALL_DAMAGE,
+            source: None,
+        }));
+    }
+}
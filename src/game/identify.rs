@@ -0,0 +1,145 @@
+//! # Identification Module
+//!
+//! Unidentified potions and scrolls.
+//!
+//! At the start of a run every potion and scroll kind is given a random
+//! appearance, such as "murky potion" or "scroll labeled ZELGO MER", drawn from
+//! the run's [`RngStream::Appearance`] stream. Until the player identifies a
+//! kind, by using one or by reading a scroll of identify, items of that kind
+//! are shown by their appearance instead of their real name.
+
+use crate::{
+    Item, RngService, RngStream, MAGIC_MAPPING_SCROLL, PORTAL_SCROLL, REMOVE_CURSE_SCROLL,
+    TELEPORT_SCROLL,
+};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Names of the potion kinds that start the run unidentified.
+pub const POTION_KINDS: [&str; 2] = ["Potion of healing", "Potion of mana"];
+
+/// Name of the scroll that identifies an unknown item in the reader's pack.
+pub const IDENTIFY_SCROLL: &str = "Scroll of identify";
+
+/// Name of the scroll with nothing written on it, which is what water leaves
+/// of any other.
+pub const BLANK_SCROLL: &str = "Blank scroll";
+
+/// Names of the scroll kinds that start the run unidentified.
+pub const SCROLL_KINDS: [&str; 6] = [
+    IDENTIFY_SCROLL,
+    PORTAL_SCROLL,
+    TELEPORT_SCROLL,
+    MAGIC_MAPPING_SCROLL,
+    REMOVE_CURSE_SCROLL,
+    BLANK_SCROLL,
+];
+
+/// Looks an unidentified potion can have.
+const POTION_APPEARANCES: [&str; 6] = ["murky", "bubbling", "fizzy", "smoky", "golden", "violet"];
+
+/// Labels an unidentified scroll can have.
+const SCROLL_LABELS: [&str; 8] = [
+    "ZELGO MER",
+    "XIXAXA",
+    "FOOBIE BLETCH",
+    "ELAM EBOW",
+    "VERR YED",
+    "READ ME",
+    "KIRJE",
+    "PRATYAVAYAH",
+];
+
+/// The run's appearance table and the kinds the player has identified.
+///
+/// Kinds are keyed by their real name. Items whose name is not in the table
+/// are always known.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{ConsumableType, IdentificationTable, Item, ItemType, Position};
+///
+/// let mut table = IdentificationTable::new(7);
+/// let potion = Item::new(
+///     "Potion of healing".to_string(),
+///     ItemType::Consumable(ConsumableType::HealthPotion),
+///     Position::new(0, 0),
+/// );
+/// assert!(table.display_name(&potion).ends_with(" potion"));
+///
+/// assert!(table.identify("Potion of healing"));
+/// assert_eq!(table.display_name(&potion), "Potion of healing");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentificationTable {
+    /// Appearance of each kind, by real name
+    appearances: BTreeMap<String, String>,
+    /// Real names of the kinds the player has identified
+    identified: BTreeSet<String>,
+}
+
+impl IdentificationTable {
+    /// Deals out appearances for a run started with `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = RngService::new(seed).stream_rng(RngStream::Appearance);
+        let mut potions = POTION_APPEARANCES.to_vec();
+        potions.shuffle(&mut rng);
+        let mut scrolls = SCROLL_LABELS.to_vec();
+        scrolls.shuffle(&mut rng);
+
+        let potions = POTION_KINDS
+            .iter()
+            .zip(potions)
+            .map(|(kind, look)| (kind.to_string(), format!("{} potion", look)));
+        let scrolls = SCROLL_KINDS
+            .iter()
+            .zip(scrolls)
+            .map(|(kind, label)| (kind.to_string(), format!("scroll labeled {}", label)));
+
+        Self {
+            appearances: potions.chain(scrolls).collect(),
+            identified: BTreeSet::new(),
+        }
+    }
+
+    /// Checks if the player knows what items called `name` are.
+    pub fn is_identified(&self, name: &str) -> bool {
+        !self.appearances.contains_key(name) || self.identified.contains(name)
+    }
+
+    /// Marks a kind as identified, returning true if it was unknown before.
+    pub fn identify(&mut self, name: &str) -> bool {
+        !self.is_identified(name) && self.identified.insert(name.to_string())
+    }
+
+    /// Returns the name to show the player for an item.
+    pub fn display_name(&self, item: &Item) -> String {
+        match self.appearances.get(&item.name) {
+            Some(appearance) if !self.identified.contains(&item.name) => appearance.clone(),
+            _ => item.name.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appearances_are_seeded_and_distinct() {
+        let table = IdentificationTable::new(99);
+        assert_eq!(table, IdentificationTable::new(99));
+
+        let looks: BTreeSet<&String> = table.appearances.values().collect();
+        assert_eq!(looks.len(), POTION_KINDS.len() + SCROLL_KINDS.len());
+        assert!(SCROLL_KINDS.iter().all(|kind| !table.is_identified(kind)));
+        assert!(table.is_identified("Longsword"));
+
+        let mut table = table;
+        assert!(table.identify("Blank scroll"));
+        assert!(!table.identify("Blank scroll"));
+        assert!(!table.identify("Longsword"));
+    }
+}
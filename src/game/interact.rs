@@ -0,0 +1,182 @@
+//! # Interaction Menu
+//!
+//! Everything the player can do where they stand.
+//!
+//! The interact key lists, as [`InteractionEntry`]s, each [`Interaction`] on
+//! offer at or next to the player's tile: items to pick up, a corpse to
+//! butcher, stairs to take, an altar, an NPC, a chest, and doors to open or
+//! close. Picking one does just what its own key would, so a touch player can
+//! reach all of them through a single button.
+
+use crate::{
+    altar_within_reach, ButcherAction, Direction, Entity, GameState, Position, StairDirection,
+    TalkAction, TileType,
+};
+
+/// Something the player can do at or next to their tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interaction {
+    /// Pick up the top item underfoot
+    PickUp,
+    /// Butcher the corpse underfoot
+    Butcher,
+    /// Take the stairs underfoot
+    UseStairs(StairDirection),
+    /// Pray at the altar within reach
+    Pray,
+    /// Talk to the NPC within reach
+    Talk,
+    /// Open the chest within reach
+    OpenContainer,
+    /// Open the closed door at a position next to the player
+    OpenDoor(Position),
+    /// Close the open door at a position next to the player
+    CloseDoor(Position),
+}
+
+/// An interaction listed in the interaction menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteractionEntry {
+    /// What choosing the entry does
+    pub interaction: Interaction,
+    /// Text shown for the entry
+    pub label: String,
+}
+
+impl InteractionEntry {
+    /// Lists what the player can do where they stand, underfoot first.
+    pub fn list(game_state: &GameState) -> Vec<Self> {
+        let Some(player) = game_state.get_player() else {
+            return Vec::new();
+        };
+        let Some(level) = game_state.world.current_level() else {
+            return Vec::new();
+        };
+        let position = player.position();
+        let mut entries = Vec::new();
+        let mut add = |interaction, label: String| entries.push(Self { interaction, label });
+
+        if let Some(stack) = level.ground_items.top_at(position) {
+            let name = game_state.item_display_name(&stack.item);
+            add(Interaction::PickUp, format!("Pick up the {}", name));
+        }
+        if let Some(corpse) = ButcherAction::corpse_under_player(game_state).and_then(|id| {
+            level
+                .ground_items
+                .stacks_at(position)
+                .find(|s| s.item.id == id)
+        }) {
+            add(
+                Interaction::Butcher,
+                format!("Butcher the {}", corpse.item.name),
+            );
+        }
+        match level.get_tile(position).map(|tile| &tile.tile_type) {
+            Some(TileType::StairsUp) => add(
+                Interaction::UseStairs(StairDirection::Up),
+                "Climb the stairs".to_string(),
+            ),
+            Some(TileType::StairsDown) => add(
+                Interaction::UseStairs(StairDirection::Down),
+                "Descend the stairs".to_string(),
+            ),
+            _ => {}
+        }
+        if altar_within_reach(level, position).is_some() {
+            add(Interaction::Pray, "Pray at the altar".to_string());
+        }
+        if let Some(npc) =
+            TalkAction::npc_within_reach(game_state).and_then(|id| game_state.entities.get(&id))
+        {
+            add(Interaction::Talk, format!("Talk to {}", npc.name()));
+        }
+        if let Some(container) = game_state.container_within_reach(position) {
+            add(
+                Interaction::OpenContainer,
+                format!("Open the {}", container.name),
+            );
+        }
+        for next in position.adjacent_positions() {
+            let delta = Position::new(next.x - position.x, next.y - position.y);
+            let side = Direction::from_delta(delta).map_or("", |direction| match direction {
+                Direction::North => " to the north",
+                Direction::South => " to the south",
+                Direction::East => " to the east",
+                Direction::West => " to the west",
+            });
+            match level.get_tile(next).map(|tile| &tile.tile_type) {
+                Some(TileType::Door { is_open: false }) => add(
+                    Interaction::OpenDoor(next),
+                    format!("Open the door{}", side),
+                ),
+                Some(TileType::Door { is_open: true }) => add(
+                    Interaction::CloseDoor(next),
+                    format!("Close the door{}", side),
+                ),
+                _ => {}
+            }
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConcreteAction, DoorAction, Item, ItemType, Tile, WeaponType};
+
+    #[test]
+    fn test_interactions_list_what_is_underfoot_and_doors_next_door() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            level
+                .set_tile(Position::new(5, 5), Tile::new(TileType::StairsDown))
+                .unwrap();
+            let door = Tile::new(TileType::Door { is_open: false });
+            level.set_tile(Position::new(6, 5), door).unwrap();
+        }
+        game_state
+            .initialize_player("Doorman".to_string(), Position::new(5, 5))
+            .unwrap();
+        let dagger = Item::new(
+            "Dagger".to_string(),
+            ItemType::Weapon(WeaponType::Dagger),
+            Position::new(5, 5),
+        );
+        game_state.drop_item_on_ground(dagger).unwrap();
+
+        let interactions = |game_state: &GameState| -> Vec<Interaction> {
+            InteractionEntry::list(game_state)
+                .into_iter()
+                .map(|entry| entry.interaction)
+                .collect()
+        };
+        let entries = InteractionEntry::list(&game_state);
+        assert_eq!(entries[0].label, "Pick up the Dagger");
+        assert_eq!(
+            interactions(&game_state),
+            [
+                Interaction::PickUp,
+                Interaction::UseStairs(StairDirection::Down),
+                Interaction::OpenDoor(Position::new(6, 5)),
+            ]
+        );
+        assert_eq!(entries[2].label, "Open the door to the east");
+
+        // Once open, the door can be closed again, but not with something in the doorway
+        let player_id = game_state.player_id.unwrap();
+        let open = ConcreteAction::Door(DoorAction::open(player_id, Position::new(6, 5)));
+        open.execute(&mut game_state).unwrap();
+        assert!(open.execute(&mut game_state).is_err());
+        assert!(interactions(&game_state).contains(&Interaction::CloseDoor(Position::new(6, 5))));
+        let blocker = Item::new(
+            "Rock".to_string(),
+            ItemType::Weapon(WeaponType::Dagger),
+            Position::new(6, 5),
+        );
+        game_state.drop_item_on_ground(blocker).unwrap();
+        let close = ConcreteAction::Door(DoorAction::close(player_id, Position::new(6, 5)));
+        assert!(close.execute(&mut game_state).is_err());
+    }
+}
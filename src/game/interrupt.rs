@@ -0,0 +1,178 @@
+//! # Interrupt Module
+//!
+//! Shared rules for stopping repeated actions when something needs attention.
+//!
+//! Autoexplore and resting both repeat an action turn after turn. Each keeps an
+//! [`InterruptionWatch`] that remembers what was already in view when it
+//! started, so only new hostiles and items stop it, along with critical
+//! messages and, optionally, low health. Counted commands and runs keep one
+//! too, and a run also stops for new features such as stairs.
+
+use crate::{EntityId, GameState, Position};
+use std::collections::HashSet;
+
+/// Reasons a repeated action stops on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    /// A hostile came into view
+    HostileSpotted,
+    /// Player health fell below the threshold
+    LowHealth,
+    /// An item came into view
+    ItemSpotted,
+    /// A critical message was shown
+    CriticalMessage,
+    /// A feature such as stairs came into view
+    FeatureSpotted,
+}
+
+impl Interruption {
+    /// Returns a short lowercase explanation, for use in a longer message.
+    pub fn reason(self) -> &'static str {
+        match self {
+            Interruption::HostileSpotted => "you spot a hostile creature",
+            Interruption::LowHealth => "your health is low",
+            Interruption::ItemSpotted => "you spot an item",
+            Interruption::CriticalMessage => "something important happened",
+            Interruption::FeatureSpotted => "you spot something of interest",
+        }
+    }
+}
+
+/// What a repeated action has already seen, and what should stop it.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptionWatch {
+    /// Hostiles already in view when watching started; only new ones interrupt
+    pub known_hostiles: HashSet<EntityId>,
+    /// Items already in view when watching started; only new ones interrupt
+    pub known_items: HashSet<EntityId>,
+    /// Whether a critical message was shown since watching started
+    pub critical_message_pending: bool,
+    /// Fraction of maximum health below which to interrupt, if any
+    pub health_threshold: Option<f32>,
+    /// Features already in view when watching started, if new ones interrupt
+    pub known_features: Option<HashSet<Position>>,
+}
+
+impl InterruptionWatch {
+    /// Creates a watch that interrupts below `health_threshold`, if given.
+    pub fn new(health_threshold: Option<f32>) -> Self {
+        Self {
+            health_threshold,
+            ..Self::default()
+        }
+    }
+
+    /// Starts watching, ignoring the hostiles and items already in view.
+    pub fn start(&mut self, known_hostiles: HashSet<EntityId>, known_items: HashSet<EntityId>) {
+        self.known_hostiles = known_hostiles;
+        self.known_items = known_items;
+        self.critical_message_pending = false;
+        self.known_features = None;
+    }
+
+    /// Starts watching like [`InterruptionWatch::start`], also stopping for
+    /// features other than `known_features` that come into view.
+    pub fn start_with_features(
+        &mut self,
+        known_hostiles: HashSet<EntityId>,
+        known_items: HashSet<EntityId>,
+        known_features: HashSet<Position>,
+    ) {
+        self.start(known_hostiles, known_items);
+        self.known_features = Some(known_features);
+    }
+
+    /// Forgets everything seen so far.
+    pub fn clear(&mut self) {
+        self.known_hostiles.clear();
+        self.known_items.clear();
+        self.critical_message_pending = false;
+        self.known_features = None;
+    }
+
+    /// Records that a critical message was shown.
+    pub fn note_critical_message(&mut self) {
+        self.critical_message_pending = true;
+    }
+
+    /// Checks whether anything new needs the player's attention.
+    #[must_use]
+    pub fn check(&self, game_state: &GameState) -> Option<Interruption> {
+        if self.critical_message_pending {
+            return Some(Interruption::CriticalMessage);
+        }
+
+        if let Some(threshold) = self.health_threshold {
+            let low_health = game_state.get_player().is_some_and(|player| {
+                (player.stats.health as f32) < player.stats.max_health as f32 * threshold
+            });
+            if low_health {
+                return Some(Interruption::LowHealth);
+            }
+        }
+
+        if game_state
+            .visible_hostiles()
+            .iter()
+            .any(|id| !self.known_hostiles.contains(id))
+        {
+            return Some(Interruption::HostileSpotted);
+        }
+
+        if game_state
+            .visible_items()
+            .iter()
+            .any(|id| !self.known_items.contains(id))
+        {
+            return Some(Interruption::ItemSpotted);
+        }
+
+        if let Some(known_features) = &self.known_features {
+            if game_state
+                .visible_features()
+                .iter()
+                .any(|position| !known_features.contains(position))
+            {
+                return Some(Interruption::FeatureSpotted);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Monster, MonsterType, Position};
+
+    #[test]
+    fn test_watch_ignores_known_hostiles() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        game_state
+            .initialize_player("Watcher".to_string(), position)
+            .unwrap();
+        let goblin = Monster::new(MonsterType::Goblin, Position::new(6, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+        game_state.update_player_visibility(position).unwrap();
+
+        let mut watch = InterruptionWatch::new(None);
+        assert_eq!(watch.check(&game_state), Some(Interruption::HostileSpotted));
+
+        watch.start(game_state.visible_hostiles(), game_state.visible_items());
+        assert_eq!(watch.check(&game_state), None);
+
+        watch.note_critical_message();
+        assert_eq!(
+            watch.check(&game_state),
+            Some(Interruption::CriticalMessage)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! # Inventory Screen
+//!
+//! What the player carries, and what they can do with each item.
+//!
+//! The inventory screen lists the pack and worn gear as [`InventoryEntry`]s
+//! and offers the [`InventoryCommand`]s that make sense for the selected one.
+//! Every command but examining is carried out as an ordinary game action, so
+//! managing the pack from the screen takes turns just like the shortcut keys.
+
+use crate::{
+    ConcreteAction, DropItemAction, Entity, EntityId, EquipItemAction, GameState, ItemType,
+    ThatchError, ThatchResult, ThrowAction, UnequipItemAction, UseItemAction,
+};
+
+/// Something the player can do with an item on the inventory screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryCommand {
+    /// Drink, read or otherwise use the item
+    Use,
+    /// Put on a piece of gear
+    Wear,
+    /// Take off a piece of worn gear
+    TakeOff,
+    /// Drop the item where the player stands
+    Drop,
+    /// Throw the item at the nearest visible hostile
+    Throw,
+    /// Read the item's description without taking a turn
+    Examine,
+}
+
+impl InventoryCommand {
+    /// Returns the label shown on the command's button.
+    pub fn label(self) -> &'static str {
+        match self {
+            InventoryCommand::Use => "Use",
+            InventoryCommand::Wear => "Wear",
+            InventoryCommand::TakeOff => "Take off",
+            InventoryCommand::Drop => "Drop",
+            InventoryCommand::Throw => "Throw",
+            InventoryCommand::Examine => "Examine",
+        }
+    }
+
+    /// Returns the key that gives the command on the inventory screen.
+    pub fn key(self) -> char {
+        match self {
+            InventoryCommand::Use => 'U',
+            InventoryCommand::Wear => 'E',
+            InventoryCommand::TakeOff => 'R',
+            InventoryCommand::Drop => 'D',
+            InventoryCommand::Throw => 'T',
+            InventoryCommand::Examine => 'X',
+        }
+    }
+
+    /// Builds the game action that carries the command out on an item, or
+    /// `None` for examining, which takes no turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no player, the item is not carried, or a
+    /// throw has nothing to aim at.
+    pub fn action(
+        self,
+        game_state: &GameState,
+        item_id: EntityId,
+    ) -> ThatchResult<Option<ConcreteAction>> {
+        let player = game_state
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let player_id = player.id();
+        let item = game_state
+            .carried_items
+            .get(&item_id)
+            .ok_or_else(|| ThatchError::InvalidAction("You are not carrying that".to_string()))?;
+
+        let action = match self {
+            InventoryCommand::Use => {
+                ConcreteAction::UseItem(UseItemAction::new(player_id, item_id))
+            }
+            InventoryCommand::Wear => {
+                let slot = item.item_type.equip_slot().ok_or_else(|| {
+                    ThatchError::InvalidAction("You cannot wear that".to_string())
+                })?;
+                ConcreteAction::Equip(EquipItemAction::new(player_id, item_id, slot.to_string()))
+            }
+            InventoryCommand::TakeOff => {
+                let slot = player
+                    .equipment
+                    .iter()
+                    .find(|(_, id)| **id == item_id)
+                    .map(|(slot, _)| slot.clone())
+                    .ok_or_else(|| {
+                        ThatchError::InvalidAction("You are not wearing that".to_string())
+                    })?;
+                ConcreteAction::Unequip(UnequipItemAction::new(player_id, slot))
+            }
+            InventoryCommand::Drop => ConcreteAction::Drop(DropItemAction::new(player_id, item_id)),
+            InventoryCommand::Throw => {
+                ConcreteAction::Throw(ThrowAction::item_at_nearest_hostile(game_state, item_id)?)
+            }
+            InventoryCommand::Examine => return Ok(None),
+        };
+        Ok(Some(action))
+    }
+}
+
+/// An item listed on the inventory screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryEntry {
+    /// The carried item
+    pub item_id: EntityId,
+    /// Character the item is drawn with on the map
+    pub glyph: char,
+    /// Name the player knows the item by
+    pub label: String,
+    /// Whether the item is worn rather than in the pack
+    pub worn: bool,
+    /// What can be done with the item, the first being the default
+    pub commands: Vec<InventoryCommand>,
+}
+
+impl InventoryEntry {
+    /// Lists the player's worn gear followed by the rest of their pack.
+    pub fn list(game_state: &GameState) -> Vec<Self> {
+        let Some(player) = game_state.get_player() else {
+            return Vec::new();
+        };
+        let mut worn: Vec<(&String, &EntityId)> = player.equipment.iter().collect();
+        worn.sort();
+
+        worn.into_iter()
+            .map(|(_, &id)| (id, true))
+            .chain(player.inventory.iter().map(|&id| (id, false)))
+            .filter_map(|(id, worn)| {
+                let item = game_state.carried_items.get(&id)?;
+                let commands = if worn {
+                    vec![InventoryCommand::TakeOff, InventoryCommand::Examine]
+                } else {
+                    let first = match item.item_type {
+                        ItemType::Consumable(_) => Some(InventoryCommand::Use),
+                        _ if item.item_type.equip_slot().is_some() => Some(InventoryCommand::Wear),
+                        _ => None,
+                    };
+                    first
+                        .into_iter()
+                        .chain([
+                            InventoryCommand::Drop,
+                            InventoryCommand::Throw,
+                            InventoryCommand::Examine,
+                        ])
+                        .collect()
+                };
+                Some(Self {
+                    item_id: id,
+                    glyph: item.item_type.display_char(),
+                    label: game_state.item_display_name(item),
+                    worn,
+                    commands,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsumableType, Item, Position, WeaponType};
+
+    #[test]
+    fn test_inventory_commands_become_game_actions() {
+        let mut game_state = GameState::new(12345);
+        game_state
+            .initialize_player("Packrat".to_string(), Position::new(5, 5))
+            .unwrap();
+        let sword = Item::new(
+            "Longsword".to_string(),
+            ItemType::Weapon(WeaponType::Sword),
+            Position::new(5, 5),
+        );
+        let sword_id = game_state.give_item_to_player(sword, false).unwrap();
+        let potion = Item::new(
+            "Potion of healing".to_string(),
+            ItemType::Consumable(ConsumableType::HealthPotion),
+            Position::new(5, 5),
+        );
+        let potion_id = game_state.give_item_to_player(potion, false).unwrap();
+
+        let entry = |game_state: &GameState, id| {
+            InventoryEntry::list(game_state)
+                .into_iter()
+                .find(|entry| entry.item_id == id)
+                .unwrap()
+        };
+        assert_eq!(
+            entry(&game_state, sword_id).commands[0],
+            InventoryCommand::Wear
+        );
+        assert_eq!(
+            entry(&game_state, potion_id).commands[0],
+            InventoryCommand::Use
+        );
+        assert!(InventoryCommand::Examine
+            .action(&game_state, potion_id)
+            .unwrap()
+            .is_none());
+        // Nothing in sight to throw at
+        assert!(InventoryCommand::Throw
+            .action(&game_state, potion_id)
+            .is_err());
+
+        // Once worn, the sword can only be taken off or examined
+        let wear = InventoryCommand::Wear
+            .action(&game_state, sword_id)
+            .unwrap()
+            .unwrap();
+        wear.execute(&mut game_state).unwrap();
+        let worn = entry(&game_state, sword_id);
+        assert!(worn.worn);
+        assert_eq!(
+            worn.commands,
+            [InventoryCommand::TakeOff, InventoryCommand::Examine]
+        );
+
+        // Dropping leaves the potion on the floor under the player
+        let drop = InventoryCommand::Drop
+            .action(&game_state, potion_id)
+            .unwrap()
+            .unwrap();
+        drop.execute(&mut game_state).unwrap();
+        assert!(!game_state.carried_items.contains_key(&potion_id));
+        let level = game_state.world.current_level().unwrap();
+        let top = level.ground_items.top_at(Position::new(5, 5)).unwrap();
+        assert_eq!(top.item.id, potion_id);
+    }
+}
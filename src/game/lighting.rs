@@ -0,0 +1,110 @@
+//! # Lighting Module
+//!
+//! Light levels for tiles and the sources that produce them.
+//!
+//! Every level has an ambient light level, set by depth, that lights the whole
+//! floor evenly. On top of that, [`LightSource`]s placed by the generator (wall
+//! torches, glowing fungus) and the lantern the player holds in their light
+//! slot light the tiles around them, fading linearly with distance and
+//! stopping at walls. The brightest contribution wins. Tiles
+//! darker than [`MIN_VISIBLE_LIGHT`] cannot be seen even inside the player's
+//! sight radius.
+
+use crate::Position;
+use serde::{Deserialize, Serialize};
+
+/// Light level below which a tile is too dark to see.
+pub const MIN_VISIBLE_LIGHT: f32 = 0.25;
+
+/// Kinds of light source that can be placed in a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LightKind {
+    /// Torch mounted on a wall; bright with a wide radius
+    Torch,
+    /// Patch of glowing fungus; dim with a small radius
+    Fungus,
+    /// Lantern carried by an entity
+    Lantern,
+}
+
+impl LightKind {
+    /// Returns how many tiles away this kind of light reaches.
+    pub fn radius(self) -> u32 {
+        match self {
+            LightKind::Torch => 6,
+            LightKind::Fungus => 3,
+            LightKind::Lantern => 4,
+        }
+    }
+
+    /// Returns the light level at the source itself, from 0.0 to 1.0.
+    pub fn intensity(self) -> f32 {
+        match self {
+            LightKind::Torch => 1.0,
+            LightKind::Fungus => 0.6,
+            LightKind::Lantern => 0.9,
+        }
+    }
+
+    /// Returns a short human-readable name for this light kind.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            LightKind::Torch => "Torch",
+            LightKind::Fungus => "Glowing Fungus",
+            LightKind::Lantern => "Lantern",
+        }
+    }
+}
+
+/// A light source at a fixed position in a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightSource {
+    /// What kind of light this is
+    pub kind: LightKind,
+    /// Tile the light shines from
+    pub position: Position,
+}
+
+impl LightSource {
+    /// Creates a light source of the given kind at `position`.
+    pub fn new(kind: LightKind, position: Position) -> Self {
+        Self { kind, position }
+    }
+
+    /// Returns the light this source casts on `target`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{LightKind, LightSource, Position};
+    ///
+    /// let torch = LightSource::new(LightKind::Torch, Position::new(0, 0));
+    /// assert_eq!(torch.light_at(Position::new(0, 0)), 1.0);
+    /// assert!(torch.light_at(Position::new(3, 0)) < 1.0);
+    /// assert_eq!(torch.light_at(Position::new(20, 0)), 0.0);
+    /// ```
+    pub fn light_at(&self, target: Position) -> f32 {
+        let radius = self.kind.radius() as f64;
+        let distance = self.position.euclidean_distance(target);
+        if distance > radius {
+            return 0.0;
+        }
+        self.kind.intensity() * (1.0 - distance / (radius + 1.0)) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_fades_with_distance() {
+        let fungus = LightSource::new(LightKind::Fungus, Position::new(5, 5));
+
+        let near = fungus.light_at(Position::new(5, 6));
+        let far = fungus.light_at(Position::new(5, 8));
+        assert!(near > far);
+        assert!(far > 0.0);
+        assert_eq!(fungus.light_at(Position::new(5, 9)), 0.0);
+    }
+}
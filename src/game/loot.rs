@@ -0,0 +1,131 @@
+//! # Loot Module
+//!
+//! What monsters leave behind when they die.
+//!
+//! A dying monster drops its corpse where it fell, along with whatever its
+//! loot table rolls. Loot tables come from the monster's template and are
+//! rolled on the [`RngStream::Loot`] stream, so the same fight always drops the
+//! same things. Corpses are ordinary items lying on the floor: they can be
+//! butchered into food or raised as an ally with the Raise Dead spell.
+
+use crate::{ConsumableType, Item, ItemType, Monster, Position, RngService, RngStream};
+use serde::{Deserialize, Serialize};
+
+/// Name of the food a butchered corpse yields.
+pub const MEAT_NAME: &str = "Chunk of meat";
+
+/// Chunks of meat butchering one corpse yields.
+pub const BUTCHER_YIELD: u32 = 2;
+
+/// One entry of a monster's loot table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootDrop {
+    /// Name of the dropped item
+    pub name: String,
+    /// What kind of item it is
+    pub item_type: ItemType,
+    /// Chance of the item dropping, from 0 (never) to 1 (always)
+    pub chance: f64,
+}
+
+/// Creates the corpse a monster leaves at `position`.
+pub fn corpse_of(monster: &Monster, position: Position) -> Item {
+    Item::new(
+        format!("{} corpse", monster.name),
+        ItemType::Corpse(monster.monster_type.clone()),
+        position,
+    )
+}
+
+/// Rolls each entry of a loot table, returning the items dropped at `position`.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{roll_loot, ItemType, LootDrop, Position, RngService};
+///
+/// let table = [
+///     LootDrop { name: "Gold coins".to_string(), item_type: ItemType::Treasure, chance: 1.0 },
+///     LootDrop { name: "Nothing".to_string(), item_type: ItemType::Treasure, chance: 0.0 },
+/// ];
+/// let drops = roll_loot(&table, &mut RngService::new(7), Position::new(2, 2));
+/// assert_eq!(drops.len(), 1);
+/// assert_eq!(drops[0].name, "Gold coins");
+/// ```
+pub fn roll_loot(table: &[LootDrop], rng: &mut RngService, position: Position) -> Vec<Item> {
+    table
+        .iter()
+        .filter(|drop| rng.gen_bool(RngStream::Loot, drop.chance))
+        .map(|drop| Item::new(drop.name.clone(), drop.item_type.clone(), position))
+        .collect()
+}
+
+/// Creates the food butchering a corpse at `position` yields.
+pub fn butcher_yield(position: Position) -> Vec<Item> {
+    let meat = ItemType::Consumable(ConsumableType::Food);
+    (0..BUTCHER_YIELD)
+        .map(|_| Item::new(MEAT_NAME.to_string(), meat.clone(), position))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, ButcherAction, GameEvent, GameState, MonsterType};
+
+    #[test]
+    fn test_dead_monster_leaves_corpse_and_loot_to_butcher() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        let player_id = game_state
+            .initialize_player("Butcher".to_string(), position)
+            .unwrap();
+        let mut goblin = Monster::new(MonsterType::Goblin, position);
+        goblin.loot = vec![LootDrop {
+            name: "Gold coins".to_string(),
+            item_type: ItemType::Treasure,
+            chance: 1.0,
+        }];
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+
+        let died = GameEvent::EntityDied {
+            entity_id: goblin_id,
+            killer: Some(player_id),
+        };
+        let events = game_state.process_event(&died).unwrap();
+        let dropped = events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::ItemDropped { .. }))
+            .count();
+        assert_eq!(dropped, 2);
+        assert_eq!(game_state.rng.draws(RngStream::Loot), 1);
+        let level = game_state.world.current_level().unwrap();
+        let names: Vec<&str> = level
+            .ground_items
+            .stacks_at(position)
+            .map(|stack| stack.item.name.as_str())
+            .collect();
+        assert_eq!(names, ["Goblin corpse", "Gold coins"]);
+
+        // The corpse is found under the coins and carved into meat
+        let corpse_id = ButcherAction::corpse_under_player(&game_state).unwrap();
+        ButcherAction::new(player_id, corpse_id)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(ButcherAction::corpse_under_player(&game_state).is_none());
+        let level = game_state.world.current_level().unwrap();
+        let meat = level.ground_items.top_at(position).unwrap();
+        assert_eq!(
+            (meat.item.name.as_str(), meat.count),
+            (MEAT_NAME, BUTCHER_YIELD)
+        );
+        assert!(ButcherAction::new(player_id, corpse_id)
+            .validate(&game_state)
+            .is_err());
+    }
+}
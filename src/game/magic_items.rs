@@ -0,0 +1,156 @@
+//! # Magic Items
+//!
+//! Scrolls with one-off effects and wands that cast spells.
+//!
+//! Scrolls are used up when read, like any other consumable. Wands are not:
+//! each starts with [`WAND_CHARGES`] charges, and zapping one casts its spell
+//! through the same events as [`CastSpellAction`], without costing mana. The
+//! wand crumbles once its last charge is spent. Both are used through
+//! [`UseItemAction`].
+//!
+//! [`CastSpellAction`]: crate::CastSpellAction
+//! [`UseItemAction`]: crate::UseItemAction
+
+use crate::Spell;
+
+/// Name of the scroll that moves the reader to a random spot on the floor.
+pub const TELEPORT_SCROLL: &str = "Scroll of teleportation";
+
+/// Name of the scroll that reveals the layout of the whole floor.
+pub const MAGIC_MAPPING_SCROLL: &str = "Scroll of magic mapping";
+
+/// Name of the scroll that lifts curses from the reader's belongings.
+pub const REMOVE_CURSE_SCROLL: &str = "Scroll of remove curse";
+
+/// Charges a wand holds when found.
+pub const WAND_CHARGES: u32 = 5;
+
+/// A wand and the spell it casts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wand {
+    /// Item name of the wand
+    pub name: &'static str,
+    /// Name of the spell each charge casts
+    pub spell: &'static str,
+}
+
+/// Every wand in the game.
+pub const WANDS: [Wand; 2] = [
+    Wand {
+        name: "Wand of magic missile",
+        spell: "Magic Missile",
+    },
+    Wand {
+        name: "Wand of fire",
+        spell: "Fire Bolt",
+    },
+];
+
+impl Wand {
+    /// Looks up a wand by its item name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::Wand;
+    ///
+    /// let wand = Wand::by_name("Wand of fire").unwrap();
+    /// assert_eq!(wand.spell().unwrap().name, "Fire Bolt");
+    /// assert!(Wand::by_name("Wand of wishing").is_none());
+    /// ```
+    pub fn by_name(name: &str) -> Option<&'static Wand> {
+        WANDS.iter().find(|wand| wand.name == name)
+    }
+
+    /// Gets the spell the wand casts.
+    pub fn spell(&self) -> Option<&'static Spell> {
+        Spell::by_name(self.spell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Action, ConsumableType, GameEvent, GameState, Item, ItemType, Monster, MonsterType,
+        TileType, UseItemAction,
+    };
+
+    #[test]
+    fn test_scrolls_map_and_teleport_and_wands_run_out() {
+        assert!(WANDS.iter().all(|wand| wand.spell().is_some()));
+
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        let player_id = game_state
+            .initialize_player("Reader".to_string(), spawn)
+            .unwrap();
+        let level = game_state.world.current_level_mut().unwrap();
+        level.entities.retain(|&id| id == player_id);
+        let give = |game_state: &mut GameState, name: &str, kind| {
+            let item = Item::new(name.to_string(), ItemType::Consumable(kind), spawn);
+            game_state.give_item_to_player(item, false).unwrap()
+        };
+
+        // Magic mapping reveals every bit of open floor
+        let map = give(
+            &mut game_state,
+            MAGIC_MAPPING_SCROLL,
+            ConsumableType::Scroll,
+        );
+        UseItemAction::new(player_id, map)
+            .execute(&mut game_state)
+            .unwrap();
+        let level = game_state.world.current_level().unwrap();
+        let floors = level
+            .tiles
+            .iter()
+            .flatten()
+            .filter(|t| t.tile_type == TileType::Floor);
+        assert!(floors.clone().count() > 0);
+        assert!(floors.into_iter().all(|tile| tile.explored));
+
+        // Teleportation lands the reader somewhere else on the floor
+        let scroll = give(&mut game_state, TELEPORT_SCROLL, ConsumableType::Scroll);
+        UseItemAction::new(player_id, scroll)
+            .execute(&mut game_state)
+            .unwrap();
+        let landed = game_state.get_player().unwrap().position;
+        assert_ne!(landed, spawn);
+
+        // A wand needs something to aim at, and crumbles with its last charge
+        let wand = give(
+            &mut game_state,
+            "Wand of magic missile",
+            ConsumableType::Wand,
+        );
+        assert_eq!(game_state.carried_items[&wand].charges, Some(WAND_CHARGES));
+        assert!(UseItemAction::new(player_id, wand)
+            .execute(&mut game_state)
+            .is_err());
+        assert_eq!(game_state.carried_items[&wand].charges, Some(WAND_CHARGES));
+
+        let level = game_state.world.current_level().unwrap();
+        let taken = |p| game_state.get_entity_at_position(p).is_some();
+        let spot = crate::ally_spot(level, landed, taken);
+        let goblin_id = game_state
+            .add_entity(Monster::new(MonsterType::Goblin, spot.unwrap()).into())
+            .unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+        game_state.update_player_visibility(landed).unwrap();
+
+        game_state.carried_items.get_mut(&wand).unwrap().charges = Some(1);
+        let events = UseItemAction::new(player_id, wand)
+            .execute(&mut game_state)
+            .unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            GameEvent::EntityDamaged { entity_id, .. } if *entity_id == goblin_id
+        )));
+        assert!(!game_state.carried_items.contains_key(&wand));
+    }
+}
@@ -8,16 +8,100 @@
 //! - Entity-component system for game objects
 //! - Action system for MCP-compatible commands
 
+pub mod accessibility;
+pub mod achievements;
 pub mod actions;
+pub mod allies;
 pub mod autoexplore;
+pub mod character_sheet;
+pub mod classes;
+pub mod containers;
+pub mod crash;
+pub mod danger;
+pub mod dialogue;
 pub mod entities;
+pub mod event_log;
+pub mod exploration;
+pub mod feelings;
+pub mod fight;
+pub mod ground;
+pub mod hazards;
+pub mod identify;
+pub mod interact;
+pub mod interrupt;
+pub mod inventory;
+pub mod lighting;
+pub mod loot;
+pub mod magic_items;
+pub mod morgue;
+pub mod movement;
+pub mod noise;
+pub mod ordered;
+pub mod overlays;
+pub mod prayer;
+pub mod pressure;
+pub mod quests;
+pub mod rest;
+pub mod rewind;
+pub mod rng;
+pub mod saves;
+pub mod score;
+pub mod spells;
 pub mod state;
+pub mod status;
+pub mod tile_effects;
+pub mod travel;
+pub mod tutorial;
+pub mod uniques;
+pub mod visibility;
 pub mod world;
 
+pub use accessibility::*;
+pub use achievements::*;
 pub use actions::*;
+pub use allies::*;
 pub use autoexplore::*;
+pub use character_sheet::*;
+pub use classes::*;
+pub use containers::*;
+pub use crash::*;
+pub use danger::*;
+pub use dialogue::*;
 pub use entities::*;
+pub use event_log::*;
+pub use exploration::*;
+pub use feelings::*;
+pub use fight::*;
+pub use ground::*;
+pub use hazards::*;
+pub use identify::*;
+pub use interact::*;
+pub use interrupt::*;
+pub use inventory::*;
+pub use lighting::*;
+pub use loot::*;
+pub use magic_items::*;
+pub use morgue::*;
+pub use movement::*;
+pub use noise::*;
+pub use ordered::*;
+pub use overlays::*;
+pub use prayer::*;
+pub use pressure::*;
+pub use quests::*;
+pub use rest::*;
+pub use rewind::*;
+pub use rng::*;
+pub use saves::*;
+pub use score::*;
+pub use spells::*;
 pub use state::*;
+pub use status::*;
+pub use tile_effects::*;
+pub use travel::*;
+pub use tutorial::*;
+pub use uniques::*;
+pub use visibility::*;
 pub use world::*;
 
 use serde::{Deserialize, Serialize};
@@ -76,6 +160,51 @@ impl Position {
         (dx * dx + dy * dy).sqrt()
     }
 
+    /// Returns the positions on a straight line to `end`, including both ends.
+    ///
+    /// Uses Bresenham's line algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::Position;
+    ///
+    /// let line = Position::new(0, 0).line_to(Position::new(3, 1));
+    /// assert_eq!(line.first(), Some(&Position::new(0, 0)));
+    /// assert_eq!(line.last(), Some(&Position::new(3, 1)));
+    /// assert_eq!(line.len(), 4);
+    /// ```
+    pub fn line_to(self, end: Position) -> Vec<Position> {
+        let mut points = Vec::new();
+
+        let (mut x, mut y) = (self.x, self.y);
+        let dx = (end.x - x).abs();
+        let dy = (end.y - y).abs();
+        let sx = if x < end.x { 1 } else { -1 };
+        let sy = if y < end.y { 1 } else { -1 };
+        let mut err = dx - dy;
+
+        loop {
+            points.push(Position::new(x, y));
+
+            if x == end.x && y == end.y {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 > -dy {
+                err -= dy;
+                x += sx;
+            }
+            if e2 < dx {
+                err += dx;
+                y += sy;
+            }
+        }
+
+        points
+    }
+
     /// Returns only the 4 cardinal adjacent positions (no diagonals).
     /// This is now the default adjacent positions method.
     pub fn adjacent_positions(self) -> Vec<Position> {
@@ -151,6 +280,30 @@ impl Direction {
         }
     }
 
+    /// Gets the direction that best points from `from` towards `to`, along
+    /// whichever axis is farther, or None if the positions are the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Direction, Position};
+    ///
+    /// let from = Position::new(2, 2);
+    /// assert_eq!(Direction::toward(from, Position::new(5, 3)), Some(Direction::East));
+    /// assert_eq!(Direction::toward(from, Position::new(1, -4)), Some(Direction::North));
+    /// assert_eq!(Direction::toward(from, from), None);
+    /// ```
+    pub fn toward(from: Position, to: Position) -> Option<Direction> {
+        let delta = to - from;
+        if delta.x == 0 && delta.y == 0 {
+            None
+        } else if delta.x.abs() > delta.y.abs() {
+            Some(if delta.x > 0 { Direction::East } else { Direction::West })
+        } else {
+            Some(if delta.y > 0 { Direction::South } else { Direction::North })
+        }
+    }
+
     /// Returns all 4 cardinal directions.
     pub fn all() -> Vec<Direction> {
         vec![
@@ -0,0 +1,517 @@
+//! # Morgue Module
+//!
+//! Character dumps ("morgue files") written when a run ends.
+//!
+//! A morgue file is a permanent record of a finished game: how it ended, the
+//! character's final stats, run statistics, how much of each level was explored,
+//! what the player was carrying, what they killed, and the last messages they saw.
+//! Each dump is written twice, as human-readable plain text and as JSON so that
+//! external tools (or the LLDM) can analyse past runs.
+
+use crate::{
+    config, CharacterClass, Entity, EntityStats, GameCompletionState, GameState, GameStatistics,
+};
+use crate::{ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Default number of trailing messages recorded in a morgue file.
+pub const DEFAULT_MORGUE_MESSAGE_COUNT: usize = 20;
+
+/// Exploration summary for a single level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelExplorationSummary {
+    /// Level identifier (0-based depth)
+    pub level_id: u32,
+    /// Optional level name
+    pub name: Option<String>,
+    /// Theme the LLDM gave the level, if any
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Number of tiles the player has explored
+    pub explored_tiles: usize,
+    /// Number of passable tiles on the level, or only those the player could
+    /// reach if the level was surveyed
+    pub passable_tiles: usize,
+    /// Number of rooms the player discovered
+    #[serde(default)]
+    pub rooms_discovered: u32,
+    /// Number of rooms on the level
+    #[serde(default)]
+    pub total_rooms: u32,
+}
+
+impl LevelExplorationSummary {
+    /// Returns the percentage of passable tiles that were explored (0.0 to 100.0).
+    pub fn explored_percentage(&self) -> f64 {
+        if self.passable_tiles == 0 {
+            return 0.0;
+        }
+        (self.explored_tiles as f64 / self.passable_tiles as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Complete record of a finished run.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{GameState, MorgueReport, Position};
+///
+/// let mut game_state = GameState::new(12345);
+/// game_state.initialize_player("Hero".to_string(), Position::new(5, 5)).unwrap();
+///
+/// let report = MorgueReport::from_game_state(&game_state, &["You have died!".to_string()], 10);
+/// assert_eq!(report.character_name, "Hero");
+/// assert!(report.to_text().contains("Hero"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MorgueReport {
+    /// Game version that produced this report
+    pub version: String,
+    /// World seed of the run
+    pub seed: u64,
+    /// Unix timestamp (seconds) when the report was created
+    pub created_at: u64,
+    /// How the run ended
+    pub completion_state: GameCompletionState,
+    /// Name of the player character
+    pub character_name: String,
+    /// Class of the player character, if a player existed
+    #[serde(default)]
+    pub character_class: Option<CharacterClass>,
+    /// Final player stats, if a player existed
+    pub final_stats: Option<EntityStats>,
+    /// Level the player was on when the run ended
+    pub final_level: u32,
+    /// Deepest level the player reached
+    pub max_depth: u32,
+    /// Number of turns played
+    pub turns: u64,
+    /// Total play time in seconds
+    pub play_time_seconds: u64,
+    /// Run statistics
+    pub statistics: GameStatistics,
+    /// Exploration summaries for every level the player saw
+    pub explored_levels: Vec<LevelExplorationSummary>,
+    /// Names of carried items (identifiers for items that could not be found)
+    pub inventory: Vec<String>,
+    /// Names of equipped items by slot
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub equipment: HashMap<String, String>,
+    /// Names of everything the player killed, in order
+    pub kill_list: Vec<String>,
+    /// The last messages shown to the player
+    pub last_messages: Vec<String>,
+}
+
+impl MorgueReport {
+    /// Builds a morgue report from the current game state.
+    ///
+    /// `messages` is the message history shown to the player; only the last
+    /// `message_count` entries are kept.
+    pub fn from_game_state(
+        game_state: &GameState,
+        messages: &[String],
+        message_count: usize,
+    ) -> Self {
+        let player = game_state.get_player();
+
+        let mut explored_levels: Vec<LevelExplorationSummary> = game_state
+            .world
+            .levels
+            .values()
+            .map(|level| {
+                let tiles = level.tiles.iter().flat_map(|row| row.iter());
+                let mut summary = LevelExplorationSummary {
+                    level_id: level.id,
+                    name: level.name.clone(),
+                    theme: level.theme.as_ref().map(|theme| theme.name.clone()),
+                    explored_tiles: tiles.clone().filter(|tile| tile.is_explored()).count(),
+                    passable_tiles: tiles.filter(|tile| tile.tile_type.is_passable()).count(),
+                    rooms_discovered: level.rooms.iter().filter(|room| room.discovered).count()
+                        as u32,
+                    total_rooms: level.rooms.len() as u32,
+                };
+                if let Some(surveyed) = game_state.statistics.level_exploration.get(&level.id) {
+                    summary.explored_tiles = surveyed.seen_tiles as usize;
+                    summary.passable_tiles = surveyed.reachable_tiles as usize;
+                }
+                summary
+            })
+            .filter(|summary| summary.explored_tiles > 0)
+            .collect();
+        explored_levels.sort_by_key(|summary| summary.level_id);
+
+        let start = messages.len().saturating_sub(message_count);
+        let item_label = |id| {
+            game_state
+                .carried_item_name(id)
+                .map_or_else(|| id.to_string(), str::to_string)
+        };
+
+        Self {
+            version: crate::VERSION.to_string(),
+            seed: game_state.world.seed,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0),
+            completion_state: game_state.completion_state.clone(),
+            character_name: player
+                .map(|player| player.name().to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            character_class: player.map(|player| player.class),
+            final_stats: player.map(|player| player.stats.clone()),
+            final_level: game_state.world.current_level_id,
+            max_depth: game_state.world.max_depth,
+            turns: game_state.turn_number,
+            play_time_seconds: game_state.total_play_time,
+            statistics: game_state.statistics.clone(),
+            explored_levels,
+            inventory: player
+                .map(|player| player.inventory.iter().map(|&id| item_label(id)).collect())
+                .unwrap_or_default(),
+            equipment: player
+                .map(|player| {
+                    player
+                        .equipment
+                        .iter()
+                        .map(|(slot, &id)| (slot.clone(), item_label(id)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            kill_list: game_state.statistics.kill_list.clone(),
+            last_messages: messages[start..].to_vec(),
+        }
+    }
+
+    /// Returns a short, human-readable description of how the run ended.
+    pub fn ending_description(&self) -> &'static str {
+        self.completion_state.description()
+    }
+
+    /// Renders the report as plain text.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        // Writing to a String cannot fail, so results are ignored
+        let _ = writeln!(text, "Thatch Roguelike v{} - Character Dump", self.version);
+        let _ = writeln!(text, "==========================================");
+        let _ = writeln!(text);
+        match self.character_class {
+            Some(class) => {
+                let _ = writeln!(
+                    text,
+                    "{} the {}: {}",
+                    self.character_name,
+                    class.display_name(),
+                    self.ending_description()
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    text,
+                    "{}: {}",
+                    self.character_name,
+                    self.ending_description()
+                );
+            }
+        }
+        let _ = writeln!(
+            text,
+            "Final level: {} (deepest: {})",
+            self.final_level + 1,
+            self.max_depth + 1
+        );
+        let _ = writeln!(
+            text,
+            "Turns: {}    Play time: {}s    Seed: {}",
+            self.turns, self.play_time_seconds, self.seed
+        );
+        let _ = writeln!(text);
+
+        if let Some(stats) = &self.final_stats {
+            let _ = writeln!(text, "Final Stats");
+            let _ = writeln!(text, "-----------");
+            let _ = writeln!(text, "Health: {}/{}", stats.health, stats.max_health);
+            let _ = writeln!(text, "Mana: {}/{}", stats.mana, stats.max_mana);
+            let _ = writeln!(
+                text,
+                "Attack: {}    Defense: {}",
+                stats.attack, stats.defense
+            );
+            let _ = writeln!(text, "Speed: {}", stats.speed);
+            let _ = writeln!(
+                text,
+                "Level: {}    Experience: {}",
+                stats.level, stats.experience
+            );
+            let _ = writeln!(text);
+        }
+
+        let stats = &self.statistics;
+        let _ = writeln!(text, "Statistics");
+        let _ = writeln!(text, "----------");
+        let _ = writeln!(text, "Enemies defeated: {}", stats.enemies_defeated);
+        let _ = writeln!(text, "Levels explored: {}", stats.levels_explored);
+        let _ = writeln!(text, "Items collected: {}", stats.items_collected);
+        let _ = writeln!(text, "Damage dealt: {}", stats.damage_dealt);
+        let _ = writeln!(text, "Damage taken: {}", stats.damage_taken);
+        let _ = writeln!(text, "Steps taken: {}", stats.steps_taken);
+        let _ = writeln!(text, "Rooms discovered: {}", stats.rooms_discovered);
+        let _ = writeln!(text, "Secrets found: {}", stats.secrets_found);
+        let _ = writeln!(text);
+
+        let _ = writeln!(text, "Explored Levels");
+        let _ = writeln!(text, "---------------");
+        if self.explored_levels.is_empty() {
+            let _ = writeln!(text, "(none)");
+        }
+        for summary in &self.explored_levels {
+            let _ = writeln!(
+                text,
+                "Level {:>2}: {:>5.1}% explored, {}/{} rooms{}{}",
+                summary.level_id + 1,
+                summary.explored_percentage(),
+                summary.rooms_discovered,
+                summary.total_rooms,
+                summary
+                    .name
+                    .as_ref()
+                    .map(|name| format!(" ({})", name))
+                    .unwrap_or_default(),
+                summary
+                    .theme
+                    .as_ref()
+                    .map(|theme| format!(", {}", theme))
+                    .unwrap_or_default()
+            );
+        }
+        let _ = writeln!(text);
+
+        let _ = writeln!(text, "Inventory");
+        let _ = writeln!(text, "---------");
+        if self.inventory.is_empty() && self.equipment.is_empty() {
+            let _ = writeln!(text, "(empty)");
+        }
+        let mut slots: Vec<_> = self.equipment.iter().collect();
+        slots.sort();
+        for (slot, item) in slots {
+            let _ = writeln!(text, "[{}] {}", slot, item);
+        }
+        for item in &self.inventory {
+            let _ = writeln!(text, "{}", item);
+        }
+        let _ = writeln!(text);
+
+        let _ = writeln!(text, "Kills");
+        let _ = writeln!(text, "-----");
+        if self.kill_list.is_empty() {
+            let _ = writeln!(text, "(none)");
+        }
+        for name in &self.kill_list {
+            let _ = writeln!(text, "{}", name);
+        }
+        let _ = writeln!(text);
+
+        let _ = writeln!(text, "Last Messages");
+        let _ = writeln!(text, "-------------");
+        for message in &self.last_messages {
+            let _ = writeln!(text, "{}", message);
+        }
+
+        text
+    }
+
+    /// Serializes the report to pretty-printed JSON.
+    pub fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string_pretty(self).map_err(ThatchError::from)
+    }
+
+    /// Returns the file stem used for this report's files.
+    pub fn file_stem(&self) -> String {
+        let name: String = self
+            .character_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}-{}-{}", name, self.seed, self.created_at)
+    }
+
+    /// Writes the plain-text and JSON dumps into `directory`, creating it if needed.
+    ///
+    /// Returns the path of the plain-text file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the directory cannot be created or either file
+    /// cannot be written.
+    pub fn write_to_directory(&self, directory: &Path) -> ThatchResult<PathBuf> {
+        std::fs::create_dir_all(directory)?;
+
+        let stem = self.file_stem();
+        let text_path = directory.join(format!("{}.txt", stem));
+        let json_path = directory.join(format!("{}.json", stem));
+
+        std::fs::write(&text_path, self.to_text())?;
+        std::fs::write(&json_path, self.to_json()?)?;
+
+        Ok(text_path)
+    }
+
+    /// Writes the dumps into the default morgue directory.
+    pub fn write_to_default_directory(&self) -> ThatchResult<PathBuf> {
+        self.write_to_directory(Path::new(config::MORGUE_DIRECTORY))
+    }
+
+    /// Loads every JSON report in `directory`, newest first.
+    ///
+    /// Files that cannot be read or parsed are skipped, and a missing
+    /// directory yields no reports.
+    pub fn load_directory(directory: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return Vec::new();
+        };
+
+        let mut reports: Vec<Self> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        reports.sort_by_key(|report| std::cmp::Reverse(report.created_at));
+        reports
+    }
+
+    /// Returns a one-line summary for lists of past runs.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{} - {} on level {} after {} turns",
+            self.character_name,
+            self.ending_description(),
+            self.final_level + 1,
+            self.turns
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    fn create_finished_game() -> GameState {
+        let mut game_state = GameState::new(4242);
+        game_state
+            .initialize_player("Morgue Test".to_string(), Position::new(5, 5))
+            .unwrap();
+        game_state
+            .update_player_visibility(Position::new(5, 5))
+            .unwrap();
+        game_state.statistics.kill_list.push("Goblin".to_string());
+        game_state.completion_state = GameCompletionState::PlayerDied;
+        game_state
+    }
+
+    #[test]
+    fn test_report_from_game_state() {
+        let game_state = create_finished_game();
+        let messages: Vec<String> = (0..30).map(|i| format!("Message {}", i)).collect();
+
+        let report = MorgueReport::from_game_state(&game_state, &messages, 5);
+
+        assert_eq!(report.character_name, "Morgue Test");
+        assert_eq!(report.seed, 4242);
+        assert_eq!(report.completion_state, GameCompletionState::PlayerDied);
+        assert!(report.final_stats.is_some());
+        assert_eq!(report.kill_list, vec!["Goblin".to_string()]);
+        assert_eq!(report.last_messages.len(), 5);
+        assert_eq!(report.last_messages[4], "Message 29");
+        assert_eq!(report.explored_levels.len(), 1);
+    }
+
+    #[test]
+    fn test_report_text_contents() {
+        let game_state = create_finished_game();
+        let report = MorgueReport::from_game_state(&game_state, &["Farewell".to_string()], 10);
+        let text = report.to_text();
+
+        assert!(text.contains("Morgue Test"));
+        assert!(text.contains("Died in the dungeon"));
+        assert!(text.contains("Goblin"));
+        assert!(text.contains("Farewell"));
+    }
+
+    #[test]
+    fn test_report_json_round_trip() {
+        let game_state = create_finished_game();
+        let report = MorgueReport::from_game_state(&game_state, &[], 10);
+
+        let json = report.to_json().unwrap();
+        let loaded: MorgueReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.character_name, report.character_name);
+        assert_eq!(loaded.kill_list, report.kill_list);
+    }
+
+    #[test]
+    fn test_write_to_directory() {
+        let game_state = create_finished_game();
+        let report = MorgueReport::from_game_state(&game_state, &[], 10);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let morgue_dir = temp_dir.path().join("morgue");
+
+        let text_path = report.write_to_directory(&morgue_dir).unwrap();
+
+        assert!(text_path.exists());
+        assert!(text_path.with_extension("json").exists());
+        assert!(text_path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("Morgue_Test"));
+    }
+
+    #[test]
+    fn test_load_directory() {
+        let game_state = create_finished_game();
+        let report = MorgueReport::from_game_state(&game_state, &[], 10);
+        let temp_dir = tempfile::tempdir().unwrap();
+        report.write_to_directory(temp_dir.path()).unwrap();
+
+        let reports = MorgueReport::load_directory(temp_dir.path());
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].character_name, "Morgue Test");
+        assert!(reports[0].summary_line().contains("Died in the dungeon"));
+
+        assert!(MorgueReport::load_directory(&temp_dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_explored_percentage() {
+        let summary = LevelExplorationSummary {
+            level_id: 0,
+            name: None,
+            theme: None,
+            explored_tiles: 25,
+            passable_tiles: 100,
+            rooms_discovered: 2,
+            total_rooms: 5,
+        };
+        assert_eq!(summary.explored_percentage(), 25.0);
+
+        let empty = LevelExplorationSummary {
+            level_id: 0,
+            name: None,
+            theme: None,
+            explored_tiles: 0,
+            passable_tiles: 0,
+            rooms_discovered: 0,
+            total_rooms: 0,
+        };
+        assert_eq!(empty.explored_percentage(), 0.0);
+    }
+}
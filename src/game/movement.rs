@@ -0,0 +1,217 @@
+//! # Movement
+//!
+//! Conveniences that save keystrokes when walking around.
+//!
+//! Walking into a closed door can open it, as the [`DoorOpening`] setting
+//! says, and travel and autoexplore route through closed doors, opening them
+//! on the way. A run (Shift and a direction, or capital HJKL) keeps walking,
+//! following the bends of a corridor, until [`run_direction`] finds somewhere
+//! worth stopping: a junction, a doorway, anything lying on the floor, or the
+//! end of the way. Like autoexplore, it also stops when a hostile, an item or
+//! a feature such as stairs comes into view.
+
+use crate::{
+    ConcreteAction, Direction, DoorAction, EntityId, Level, MoveAction, Position, TileType,
+};
+use serde::{Deserialize, Serialize};
+
+/// Most steps one run takes before it stops on its own.
+pub const RUN_STEP_LIMIT: u32 = 200;
+
+/// What stepping through a closed door costs a route, counting the turn
+/// spent opening it.
+pub const CLOSED_DOOR_PATH_COST: u32 = 2;
+
+/// What walking into a closed door does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoorOpening {
+    /// Nothing; doors are only opened on purpose
+    Off,
+    /// The door is opened, and stepping through takes another turn
+    #[default]
+    TwoTurns,
+    /// The door is opened and stepped through in the same turn
+    OneTurn,
+}
+
+impl DoorOpening {
+    /// Every setting, in the order the options step through them
+    pub const ALL: [DoorOpening; 3] = [
+        DoorOpening::Off,
+        DoorOpening::TwoTurns,
+        DoorOpening::OneTurn,
+    ];
+
+    /// Returns a short human-readable name for the setting.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            DoorOpening::Off => "Off",
+            DoorOpening::TwoTurns => "Open, then step",
+            DoorOpening::OneTurn => "Open and step",
+        }
+    }
+
+    /// Gets how doors on a travel or autoexplore route are opened; routes
+    /// open them even when walking into them does not.
+    pub fn on_routes(self) -> Self {
+        match self {
+            DoorOpening::Off => DoorOpening::TwoTurns,
+            opening => opening,
+        }
+    }
+
+    /// Gets the action taking `actor` at `from` a step in `direction`, which
+    /// opens the door instead if one is closed in the way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{ConcreteAction, Direction, DoorOpening, Level, Position, Tile, TileType};
+    ///
+    /// let mut level = Level::new(0, 5, 5);
+    /// let door = Position::new(2, 1);
+    /// level.set_tile(door, Tile::new(TileType::Door { is_open: false })).unwrap();
+    /// let actor = thatch::new_entity_id();
+    /// let from = Position::new(1, 1);
+    ///
+    /// let step = DoorOpening::OneTurn.step(&level, actor, from, Direction::East);
+    /// assert!(matches!(step, ConcreteAction::Door(ref door) if door.enter));
+    /// let step = DoorOpening::Off.step(&level, actor, from, Direction::East);
+    /// assert!(matches!(step, ConcreteAction::Move(_)));
+    /// ```
+    pub fn step(
+        self,
+        level: &Level,
+        actor: EntityId,
+        from: Position,
+        direction: Direction,
+    ) -> ConcreteAction {
+        let to = from + direction.to_delta();
+        let closed_door = level
+            .get_tile(to)
+            .is_some_and(|tile| tile.tile_type == TileType::Door { is_open: false });
+
+        match self {
+            DoorOpening::TwoTurns if closed_door => {
+                ConcreteAction::Door(DoorAction::open(actor, to))
+            }
+            DoorOpening::OneTurn if closed_door => {
+                ConcreteAction::Door(DoorAction::open_and_enter(actor, to))
+            }
+            _ => ConcreteAction::Move(MoveAction::new(actor, direction)),
+        }
+    }
+}
+
+/// Gets the direction a run heading `heading` goes on in from `position`, or
+/// None if it should stop there.
+///
+/// A run keeps straight on while the way ahead is clear and nothing opens up
+/// to either side that was not open a step back, so it runs the length of a
+/// corridor or along a wall, and stops where a side passage or doorway opens
+/// up. Where a corridor bends with no other way to go, the run turns with it.
+/// It also stops on any tile other than plain floor and on anything lying on
+/// the floor.
+pub fn run_direction(level: &Level, position: Position, heading: Direction) -> Option<Direction> {
+    let tile = level.get_tile(position)?;
+    if tile.tile_type != TileType::Floor || level.ground_items.top_at(position).is_some() {
+        return None;
+    }
+
+    // Doors count as openings, so a door in a corridor wall is a junction
+    let open = |at: Position| {
+        level.get_tile(at).is_some_and(|tile| {
+            tile.tile_type.is_passable() || matches!(tile.tile_type, TileType::Door { .. })
+        })
+    };
+    let sides = match heading {
+        Direction::North | Direction::South => [Direction::West, Direction::East],
+        Direction::East | Direction::West => [Direction::North, Direction::South],
+    };
+    let openings = |at: Position| sides.map(|side| open(at + side.to_delta()));
+    let behind = position - heading.to_delta();
+    let ahead = position + heading.to_delta();
+
+    let opened_up = openings(position)
+        .into_iter()
+        .zip(openings(behind))
+        .any(|(now, before)| now && !before);
+    if level.is_passable(ahead) && !opened_up {
+        return Some(heading);
+    }
+
+    // A corridor bend: the only way on is to one side, with rock in the corner
+    let ways_on: Vec<Direction> = sides
+        .into_iter()
+        .filter(|side| open(position + side.to_delta()))
+        .collect();
+    match ways_on[..] {
+        [side] if !open(ahead) && !open(behind + side.to_delta()) => level
+            .is_passable(position + side.to_delta())
+            .then_some(side),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Entity, GameState, Tile};
+
+    /// Carves `positions` out of solid rock on the current level.
+    fn carve(game_state: &mut GameState, positions: &[(i32, i32)]) {
+        let level = game_state.world.current_level_mut().unwrap();
+        for y in 0..level.height as i32 {
+            for x in 0..level.width as i32 {
+                level.set_tile(Position::new(x, y), Tile::wall()).unwrap();
+            }
+        }
+        for &(x, y) in positions {
+            level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_runs_follow_corridors_and_doors_open_on_the_way() {
+        let mut game_state = GameState::new(12);
+        // A corridor east from (2, 2) that bends south at (6, 2), with a side
+        // passage north at (4, 1) and a door at (6, 6)
+        let mut tiles = vec![(2, 2), (3, 2), (4, 2), (5, 2), (6, 2), (4, 1)];
+        tiles.extend((3..6).map(|y| (6, y)));
+        tiles.extend([(6, 7), (6, 8)]);
+        carve(&mut game_state, &tiles);
+        let door = Position::new(6, 6);
+        let level = game_state.world.current_level_mut().unwrap();
+        level
+            .set_tile(door, Tile::new(TileType::Door { is_open: false }))
+            .unwrap();
+        let level = game_state.world.current_level().unwrap();
+
+        // The junction with the side passage stops a run east
+        let run = |from: (i32, i32), heading| {
+            run_direction(level, Position::new(from.0, from.1), heading)
+        };
+        assert_eq!(run((3, 2), Direction::East), Some(Direction::East));
+        assert_eq!(run((4, 2), Direction::East), None);
+        // Past it, the run turns the corner and stops short of the door
+        assert_eq!(run((5, 2), Direction::East), Some(Direction::East));
+        assert_eq!(run((6, 2), Direction::East), Some(Direction::South));
+        assert_eq!(run((6, 4), Direction::South), Some(Direction::South));
+        assert_eq!(run((6, 5), Direction::South), None);
+
+        // Walking into the door opens it, and the one-turn setting steps through
+        let player_id = game_state
+            .initialize_player("Runner".to_string(), Position::new(6, 5))
+            .unwrap();
+        let level = game_state.world.current_level().unwrap();
+        let from = Position::new(6, 5);
+        let step = DoorOpening::OneTurn.step(level, player_id, from, Direction::South);
+        step.execute(&mut game_state).unwrap();
+        assert_eq!(game_state.get_player().unwrap().position(), door);
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(
+            level.get_tile(door).unwrap().tile_type,
+            TileType::Door { is_open: true }
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! # Noise Module
+//!
+//! Sound, and the stealth that comes with it.
+//!
+//! Actions make noise as they happen: a footstep is quiet, a fight is loud,
+//! and a shout carries far. Noise spreads out from where it was made through
+//! passable tiles, losing a point of volume for every tile it crosses and
+//! [`DOOR_DAMPING`] more for a closed door. Monsters that hear a noise while
+//! they cannot see the player go to investigate where it came from.
+
+use crate::{Level, Position, TileType};
+use std::collections::{HashMap, VecDeque};
+
+/// Volume of a single footstep.
+pub const NOISE_WALK: u32 = 3;
+
+/// Volume of a melee attack.
+pub const NOISE_FIGHT: u32 = 10;
+
+/// Volume of a shout.
+pub const NOISE_SHOUT: u32 = 16;
+
+/// Extra volume lost passing through a closed door.
+pub const DOOR_DAMPING: u32 = 4;
+
+/// Spreads a noise over a level.
+///
+/// Returns how loud the noise is on every tile it reaches; tiles where it has
+/// faded to nothing are left out.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{propagate_noise, Level, Position, Tile};
+///
+/// let mut level = Level::new(0, 10, 3);
+/// for x in 0..10 {
+///     level.set_tile(Position::new(x, 1), Tile::floor()).unwrap();
+/// }
+/// let heard = propagate_noise(&level, Position::new(0, 1), 4);
+/// assert_eq!(heard[&Position::new(2, 1)], 2);
+/// assert!(!heard.contains_key(&Position::new(4, 1)));
+/// ```
+pub fn propagate_noise(level: &Level, origin: Position, volume: u32) -> HashMap<Position, u32> {
+    let mut heard = HashMap::from([(origin, volume)]);
+    let mut frontier = VecDeque::from([origin]);
+
+    while let Some(position) = frontier.pop_front() {
+        let loudness = heard[&position];
+        for next in position.adjacent_positions() {
+            let Some(tile) = level.get_tile(next) else {
+                continue;
+            };
+            let loss = match tile.tile_type {
+                TileType::Door { is_open: false } => 1 + DOOR_DAMPING,
+                ref tile_type if tile_type.is_passable() => 1,
+                _ => continue,
+            };
+            let Some(remaining) = loudness.checked_sub(loss).filter(|&left| left > 0) else {
+                continue;
+            };
+            if heard.get(&next).is_none_or(|&known| known < remaining) {
+                heard.insert(next, remaining);
+                frontier.push_back(next);
+            }
+        }
+    }
+    heard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tile;
+
+    #[test]
+    fn test_noise_fades_with_distance_and_doors() {
+        // Two rooms joined by a closed door at (5, 2)
+        let mut level = Level::new(0, 11, 5);
+        for y in 1..4 {
+            for x in (1..5).chain(6..10) {
+                level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+            }
+        }
+        let door = Position::new(5, 2);
+        level
+            .set_tile(door, Tile::new(TileType::Door { is_open: false }))
+            .unwrap();
+
+        let heard = propagate_noise(&level, Position::new(1, 2), NOISE_FIGHT);
+        assert_eq!(heard[&Position::new(4, 2)], NOISE_FIGHT - 3);
+        assert_eq!(heard[&door], NOISE_FIGHT - 4 - DOOR_DAMPING);
+        assert_eq!(heard[&Position::new(6, 2)], NOISE_FIGHT - 5 - DOOR_DAMPING);
+        assert!(!heard.contains_key(&Position::new(0, 2)));
+
+        // Footsteps stay in the room
+        let heard = propagate_noise(&level, Position::new(1, 2), NOISE_WALK);
+        assert!(!heard.contains_key(&door));
+    }
+}
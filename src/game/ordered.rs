@@ -0,0 +1,103 @@
+//! # Ordered Serialization
+//!
+//! Writing hash maps out in a stable order.
+//!
+//! Hash maps iterate in a different order every time the game runs, so a map
+//! serialized as is makes two saves of the same game differ and breaks diffs
+//! and replays. Maps that are looked up often, like the entity table, stay
+//! hash maps in memory and are written out with their keys sorted through
+//! [`sorted_map`]; `#[serde(serialize_with = "crate::sorted_map")]` on a field
+//! is all it takes. Loading needs nothing special.
+
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// Serializes a hash map with its keys in order.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Serialize)]
+/// struct Tags {
+///     #[serde(serialize_with = "thatch::sorted_map")]
+///     tags: HashMap<String, u32>,
+/// }
+///
+/// let tags = Tags {
+///     tags: HashMap::from([("b".to_string(), 2), ("c".to_string(), 3), ("a".to_string(), 1)]),
+/// };
+/// assert_eq!(serde_json::to_string(&tags).unwrap(), r#"{"tags":{"a":1,"b":2,"c":3}}"#);
+/// ```
+pub fn sorted_map<S, K, V>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+/// Serializes an optional hash map with its keys in order, like [`sorted_map`].
+pub fn sorted_optional_map<S, K, V>(
+    map: &Option<HashMap<K, V>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    match map {
+        Some(map) => serializer.serialize_some(&map.iter().collect::<BTreeMap<_, _>>()),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConsumableType, GameState, Item, ItemType, Monster, MonsterType, Position};
+
+    #[test]
+    fn test_saves_of_the_same_game_are_byte_identical() {
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        game_state
+            .initialize_player("Archivist".to_string(), spawn)
+            .unwrap();
+        for index in 0..20 {
+            let position = Position::new(spawn.x + index, spawn.y);
+            let monster_id = game_state
+                .add_entity(Monster::new(MonsterType::Goblin, position).into())
+                .unwrap();
+            game_state
+                .world
+                .current_level_mut()
+                .unwrap()
+                .add_entity(monster_id);
+            let mut item = Item::new(
+                format!("Potion {}", index),
+                ItemType::Consumable(ConsumableType::HealthPotion),
+                position,
+            );
+            item.metadata
+                .insert(format!("note {}", index), "shaken".to_string());
+            if index % 2 == 0 {
+                game_state.give_item_to_player(item, false).unwrap();
+            } else {
+                game_state.carried_items.insert(item.id, item);
+            }
+            game_state
+                .config_flags
+                .insert(format!("flag {}", index), index % 2 == 0);
+        }
+
+        // A loaded game has fresh hash maps that iterate in another order
+        let saved = game_state.save_to_json().unwrap();
+        let loaded = GameState::load_from_json(&saved).unwrap();
+        assert_eq!(loaded.save_to_json().unwrap(), saved);
+        assert_eq!(loaded.clone().save_to_json().unwrap(), saved);
+    }
+}
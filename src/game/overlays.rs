@@ -0,0 +1,173 @@
+//! # Overlays Module
+//!
+//! Marks left on the floor by what happens there.
+//!
+//! A tile can carry one [`TileOverlay`] on top of its type: blood spilled by
+//! melee hits, scorch marks where something caught fire, and rubble where a
+//! room's ceiling caved in. Overlays are saved with the level, drawn as a tint and a secondary
+//! glyph, shown by the examine command and listed in LLM snapshots, so the
+//! floors keep a record of the fights that happened on them.
+
+use serde::{Deserialize, Serialize};
+
+/// What clambering over rubble costs a path, against 1 for clean floor.
+pub const RUBBLE_PATH_COST: u32 = 2;
+
+/// A mark left on a tile.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{Level, Position, Tile, TileOverlay};
+///
+/// let mut level = Level::new(0, 5, 5);
+/// let position = Position::new(2, 2);
+/// level.set_tile(position, Tile::floor()).unwrap();
+///
+/// assert!(level.add_overlay(position, TileOverlay::Blood));
+/// assert!(!level.add_overlay(Position::new(0, 0), TileOverlay::Blood));
+/// assert_eq!(level.get_tile(position).unwrap().overlay, Some(TileOverlay::Blood));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileOverlay {
+    /// Blood spattered by a melee hit
+    Blood,
+    /// Soot and char left by fire
+    Scorch,
+    /// Broken stone from a caved-in ceiling
+    Rubble,
+}
+
+impl TileOverlay {
+    /// Returns a short human-readable name for the overlay.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            TileOverlay::Blood => "Blood",
+            TileOverlay::Scorch => "Scorch marks",
+            TileOverlay::Rubble => "Rubble",
+        }
+    }
+
+    /// Returns what stepping onto a tile with this overlay costs a path at
+    /// least; only rubble slows the going.
+    pub fn path_cost(self) -> u32 {
+        match self {
+            TileOverlay::Rubble => RUBBLE_PATH_COST,
+            TileOverlay::Blood | TileOverlay::Scorch => 1,
+        }
+    }
+
+    /// Returns the character drawn in place of a bare floor tile.
+    pub fn to_char(self) -> char {
+        match self {
+            TileOverlay::Blood => ',',
+            TileOverlay::Scorch => '`',
+            TileOverlay::Rubble => ':',
+        }
+    }
+
+    /// Describes the overlay for the examine command and LLM snapshots.
+    pub fn description(self) -> &'static str {
+        match self {
+            TileOverlay::Blood => "Blood is spattered across the ground.",
+            TileOverlay::Scorch => "The ground is blackened with soot.",
+            TileOverlay::Rubble => "Broken stone litters the ground.",
+        }
+    }
+
+    /// Returns true if this overlay should replace `existing`.
+    ///
+    /// Rubble buries anything beneath it and fire burns blood away, but
+    /// fresh blood does not cover scorch marks or rubble.
+    pub fn covers(self, existing: TileOverlay) -> bool {
+        self.rank() >= existing.rank()
+    }
+
+    /// Orders overlays by which covers which.
+    fn rank(self) -> u8 {
+        match self {
+            TileOverlay::Blood => 0,
+            TileOverlay::Scorch => 1,
+            TileOverlay::Rubble => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AttackAction, GameEvent, GameState, Monster, MonsterType, Position, StatusKind, Tile,
+    };
+
+    #[test]
+    fn test_melee_and_fire_leave_overlays() {
+        let mut game_state = GameState::new(12345);
+        let level = game_state.world.current_level_mut().unwrap();
+        for x in 4..8 {
+            let mut tile = Tile::floor();
+            tile.explored = true;
+            level.set_tile(Position::new(x, 5), tile).unwrap();
+        }
+        let player_id = game_state
+            .initialize_player("Brawler".to_string(), Position::new(5, 5))
+            .unwrap();
+        let goblin = Monster::new(MonsterType::Goblin, Position::new(6, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        let overlay_at = |game_state: &GameState, x| {
+            let level = game_state.world.current_level().unwrap();
+            level.get_tile(Position::new(x, 5)).unwrap().overlay
+        };
+
+        // A melee hit bloodies the floor under its target
+        let attack = AttackAction::new(player_id, goblin_id);
+        let events = crate::Action::execute(&attack, &mut game_state).unwrap();
+        for event in &events {
+            game_state.process_event(event).unwrap();
+        }
+        assert_eq!(overlay_at(&game_state, 6), Some(TileOverlay::Blood));
+
+        // Harm from afar does not
+        let trap = GameEvent::EntityDamaged {
+            entity_id: player_id,
+            damage: 1,
+            source: None,
+        };
+        game_state.process_event(&trap).unwrap();
+        assert_eq!(overlay_at(&game_state, 5), None);
+
+        // Catching fire scorches the floor
+        let ignite = GameEvent::StatusApplied {
+            entity_id: player_id,
+            kind: StatusKind::Burning,
+            turns: 3,
+        };
+        game_state.process_event(&ignite).unwrap();
+        assert_eq!(overlay_at(&game_state, 5), Some(TileOverlay::Scorch));
+        assert!(game_state
+            .describe_position(Position::new(5, 5))
+            .contains(TileOverlay::Scorch.description()));
+        let snapshot = game_state.snapshot_for_llm(crate::DEFAULT_SNAPSHOT_TOKEN_BUDGET);
+        assert_eq!(
+            snapshot.aftermath,
+            [
+                "(5, 5): The ground is blackened with soot.",
+                "(6, 5): Blood is spattered across the ground."
+            ]
+        );
+
+        // Fire burns blood away, but blood does not cover scorch marks
+        assert!(TileOverlay::Scorch.covers(TileOverlay::Blood));
+        assert!(!TileOverlay::Blood.covers(TileOverlay::Scorch));
+
+        // Overlays are saved with the level
+        let json = serde_json::to_string(&game_state.world).unwrap();
+        let world: crate::World = serde_json::from_str(&json).unwrap();
+        let tile = world
+            .current_level()
+            .unwrap()
+            .get_tile(Position::new(5, 5))
+            .unwrap();
+        assert_eq!(tile.overlay, Some(TileOverlay::Scorch));
+    }
+}
@@ -0,0 +1,168 @@
+//! # Prayer
+//!
+//! Altars, the deities they belong to, and what praying at them does.
+//!
+//! Every sanctuary holds an altar to the floor's deity, named by the LLDM the
+//! first time the player meets it. Praying at an altar grants one
+//! [`PrayerBoon`], chosen by what the player needs most: healing when badly
+//! hurt, purification when carrying cursed gear, and otherwise a blessing on
+//! the wielded weapon. The gods only listen every [`PRAYER_TIMEOUT_TURNS`]
+//! turns; pray sooner and nothing answers.
+
+use crate::{Level, Position, TileEffect};
+use serde::{Deserialize, Serialize};
+
+/// Turns after a granted prayer before the gods listen again.
+pub const PRAYER_TIMEOUT_TURNS: u64 = 500;
+
+/// Highest enchantment a blessing can raise a weapon to.
+pub const MAX_BLESSING: i32 = 3;
+
+/// Names of the deities a floor can be given when no model is connected.
+pub const DEITY_NAMES: [&str; 6] = ["Velka", "Orun", "Sefet", "Ilmar", "Toth", "Ashera"];
+
+/// The god a floor's altars belong to.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::Deity;
+///
+/// let deity = Deity::new("Velka", "keeper of the Sunken Halls");
+/// assert_eq!(deity.title(), "Velka, keeper of the Sunken Halls");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deity {
+    /// Name the deity is called by
+    pub name: String,
+    /// What the deity watches over, such as "keeper of the deep"
+    pub epithet: String,
+}
+
+impl Deity {
+    /// Creates a deity.
+    pub fn new(name: impl Into<String>, epithet: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            epithet: epithet.into(),
+        }
+    }
+
+    /// Gets the deity's name with its epithet.
+    pub fn title(&self) -> String {
+        format!("{}, {}", self.name, self.epithet)
+    }
+}
+
+/// What a deity grants a player who prays at its altar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrayerBoon {
+    /// Restores the player to full health
+    Healing,
+    /// Lifts the curses from everything the player carries
+    Purification,
+    /// Raises the enchantment of the wielded weapon by one
+    Blessing,
+}
+
+impl PrayerBoon {
+    /// Gets the message shown when `deity` grants the boon.
+    pub fn message(self, deity: &str) -> String {
+        match self {
+            PrayerBoon::Healing => format!("{} hears you. Warmth floods your wounds.", deity),
+            PrayerBoon::Purification => {
+                format!(
+                    "{} hears you. A malevolent aura lifts from your belongings.",
+                    deity
+                )
+            }
+            PrayerBoon::Blessing => format!("{} hears you. Your weapon glows softly.", deity),
+        }
+    }
+}
+
+/// Finds an altar under or next to `position`.
+pub fn altar_within_reach(level: &Level, position: Position) -> Option<Position> {
+    std::iter::once(position)
+        .chain(position.adjacent_positions())
+        .find(|&next| {
+            level
+                .get_tile(next)
+                .and_then(|tile| tile.tile_type.effect())
+                .is_some_and(|effect| *effect == TileEffect::Altar)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, ArmorType, GameState, Item, ItemType, PrayAction, TileType, WeaponType};
+
+    #[test]
+    fn test_prayer_heals_purifies_blesses_and_waits() {
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        let player_id = game_state
+            .initialize_player("Pilgrim".to_string(), spawn)
+            .unwrap();
+        let pray = PrayAction::new(player_id);
+        assert!(pray.validate(&game_state).is_err());
+
+        let altar = spawn + Position::new(1, 0);
+        let level = game_state.world.current_level_mut().unwrap();
+        level
+            .set_tile(altar, TileEffect::Altar.into_tile())
+            .unwrap();
+        assert_eq!(altar_within_reach(level, spawn), Some(altar));
+        assert!(matches!(
+            level.get_tile(altar).unwrap().tile_type,
+            TileType::Special { .. }
+        ));
+
+        // A badly hurt player is healed, and the floor's deity is remembered
+        let max_health = game_state.get_player().unwrap().stats.max_health;
+        game_state.get_player_mut().unwrap().stats.health = 1;
+        let events = pray.execute(&mut game_state).unwrap();
+        for event in &events {
+            game_state.process_event(event).unwrap();
+        }
+        let deity = game_state
+            .world
+            .current_level()
+            .unwrap()
+            .deity
+            .clone()
+            .unwrap();
+        assert!(DEITY_NAMES.contains(&deity.name.as_str()));
+        let message = PrayerBoon::Healing.message(&deity.name);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            crate::GameEvent::Message { text, .. } if *text == message
+        )));
+        assert_eq!(game_state.get_player().unwrap().stats.health, max_health);
+
+        // Praying again too soon does nothing
+        let mut ring = Item::new("Ring".to_string(), ItemType::Armor(ArmorType::Ring), spawn);
+        ring.cursed = true;
+        let ring_id = game_state.give_item_to_player(ring, false).unwrap();
+        pray.execute(&mut game_state).unwrap();
+        assert!(game_state.carried_items[&ring_id].cursed);
+
+        // Once the gods listen again, curses are lifted before weapons are blessed
+        game_state.turn_number += PRAYER_TIMEOUT_TURNS;
+        pray.execute(&mut game_state).unwrap();
+        assert!(!game_state.carried_items[&ring_id].cursed);
+
+        let sword = Item::new(
+            "Sword".to_string(),
+            ItemType::Weapon(WeaponType::Sword),
+            spawn,
+        );
+        let sword_id = game_state.give_item_to_player(sword, true).unwrap();
+        let attack = game_state.get_player().unwrap().stats.attack;
+        game_state.turn_number += PRAYER_TIMEOUT_TURNS;
+        pray.execute(&mut game_state).unwrap();
+        assert_eq!(game_state.carried_items[&sword_id].enchantment, 1);
+        assert_eq!(game_state.get_player().unwrap().stats.attack, attack + 1);
+    }
+}
@@ -0,0 +1,212 @@
+//! # Dungeon Pressure
+//!
+//! Wandering monsters that come looking for a player who lingers.
+//!
+//! Every level keeps a [`SpawnScheduler`] counting the turns the player has
+//! spent on it. After [`PRESSURE_GRACE_TURNS`], a wandering monster arrives
+//! every [`WANDERER_INTERVAL`] turns, somewhere out of sight, and heads for
+//! the player. The longer the player stays, the shorter the wait: the interval
+//! halves every [`PRESSURE_HALVING_TURNS`] turns past the grace period, down
+//! to [`MIN_WANDERER_INTERVAL`]. Resting forever on one floor is never safe.
+
+use crate::{
+    FloorTheme, Level, MonsterTemplate, Position, TemplateLibrary, TileType,
+    FAVORED_SPECIES_WEIGHT_SCALE,
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Turns the player can spend on a level before wandering monsters appear.
+pub const PRESSURE_GRACE_TURNS: u64 = 400;
+
+/// Turns between wandering monsters once the grace period is over.
+pub const WANDERER_INTERVAL: u64 = 150;
+
+/// Shortest wait between wandering monsters, however long the player stays.
+pub const MIN_WANDERER_INTERVAL: u64 = 30;
+
+/// Turns past the grace period over which the wait between wanderers halves.
+pub const PRESSURE_HALVING_TURNS: u64 = 300;
+
+/// Most wandering monsters that arrive on one level.
+pub const MAX_WANDERERS_PER_LEVEL: u32 = 12;
+
+/// Fewest steps, counted along rows and columns, between the player and where
+/// a wanderer arrives.
+pub const WANDERER_MIN_DISTANCE: u32 = 12;
+
+/// Decides when wandering monsters arrive on a level, and which.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{SpawnScheduler, TemplateLibrary, PRESSURE_GRACE_TURNS, WANDERER_INTERVAL};
+///
+/// let mut scheduler = SpawnScheduler::new(0, &TemplateLibrary::builtin().unwrap());
+/// let due: Vec<u64> = (1..=PRESSURE_GRACE_TURNS + WANDERER_INTERVAL)
+///     .filter(|_| {
+///         let due = scheduler.tick();
+///         if due {
+///             scheduler.record_spawn();
+///         }
+///         due
+///     })
+///     .collect();
+/// assert_eq!(due, vec![PRESSURE_GRACE_TURNS, PRESSURE_GRACE_TURNS + WANDERER_INTERVAL]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpawnScheduler {
+    /// Names of the species that can wander onto the level
+    pub wanderers: Vec<String>,
+    /// Turns the player has spent on the level
+    pub turns_on_level: u64,
+    /// Turn on the level the next wanderer is due, or zero for the end of the grace period
+    pub next_spawn: u64,
+    /// Wanderers that have arrived so far
+    pub spawned: u32,
+}
+
+impl SpawnScheduler {
+    /// Creates a scheduler for a level, with the species allowed at its depth.
+    pub fn new(depth: u32, templates: &TemplateLibrary) -> Self {
+        Self {
+            wanderers: templates
+                .monsters
+                .iter()
+                .filter(|monster| monster.min_depth <= depth)
+                .map(|monster| monster.name.clone())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Gets the wait before the next wanderer, after `turns_on_level` turns on the level.
+    pub fn interval(turns_on_level: u64) -> u64 {
+        let lingering = turns_on_level.saturating_sub(PRESSURE_GRACE_TURNS);
+        let halvings = (lingering / PRESSURE_HALVING_TURNS).min(63);
+        (WANDERER_INTERVAL >> halvings).max(MIN_WANDERER_INTERVAL)
+    }
+
+    /// Counts a turn spent on the level, returning true if a wanderer is due.
+    ///
+    /// A wanderer stays due every turn until one is recorded with
+    /// [`record_spawn`](Self::record_spawn).
+    pub fn tick(&mut self) -> bool {
+        self.turns_on_level += 1;
+        let due = self.next_spawn.max(PRESSURE_GRACE_TURNS);
+        self.turns_on_level >= due
+            && self.spawned < MAX_WANDERERS_PER_LEVEL
+            && !self.wanderers.is_empty()
+    }
+
+    /// Counts a wanderer that has arrived and schedules the next.
+    pub fn record_spawn(&mut self) {
+        self.next_spawn = self.turns_on_level + Self::interval(self.turns_on_level);
+        self.spawned += 1;
+    }
+
+    /// Picks a wandering species from `templates`, weighted by spawn weight,
+    /// with the species the floor's theme favors made more common.
+    pub fn pick_wanderer<'a>(
+        &self,
+        templates: &'a TemplateLibrary,
+        theme: Option<&FloorTheme>,
+        rng: &mut StdRng,
+    ) -> Option<&'a MonsterTemplate> {
+        let species: Vec<&MonsterTemplate> = self
+            .wanderers
+            .iter()
+            .filter_map(|name| templates.monster_template(name))
+            .collect();
+        let favored = |monster: &MonsterTemplate| {
+            theme.is_some_and(|theme| theme.favored_species.contains(&monster.species))
+        };
+        species
+            .choose_weighted(rng, |monster| {
+                if favored(monster) {
+                    monster.weight.saturating_mul(FAVORED_SPECIES_WEIGHT_SCALE)
+                } else {
+                    monster.weight
+                }
+            })
+            .ok()
+            .copied()
+    }
+}
+
+/// Picks open floor out of the player's sight and at least
+/// [`WANDERER_MIN_DISTANCE`] tiles away, for a wanderer to arrive on.
+pub fn wanderer_spot(level: &Level, player: Position, rng: &mut StdRng) -> Option<Position> {
+    let spots: Vec<Position> = (0..level.height as i32)
+        .flat_map(|y| (0..level.width as i32).map(move |x| Position::new(x, y)))
+        .filter(|&position| {
+            level
+                .get_tile(position)
+                .is_some_and(|tile| tile.tile_type == TileType::Floor && !tile.is_visible())
+                && position.manhattan_distance(player) >= WANDERER_MIN_DISTANCE
+        })
+        .collect();
+    (!spots.is_empty()).then(|| spots[rng.gen_range(0..spots.len())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+
+    #[test]
+    fn test_lingering_brings_wanderers_sooner_and_sooner() {
+        assert_eq!(SpawnScheduler::interval(0), WANDERER_INTERVAL);
+        let later = PRESSURE_GRACE_TURNS + PRESSURE_HALVING_TURNS;
+        assert_eq!(SpawnScheduler::interval(later), WANDERER_INTERVAL / 2);
+        assert_eq!(SpawnScheduler::interval(u64::MAX), MIN_WANDERER_INTERVAL);
+
+        // Only species allowed at the level's depth wander in
+        let templates = TemplateLibrary::builtin().unwrap();
+        let scheduler = SpawnScheduler::new(0, &templates);
+        assert!(scheduler.wanderers.iter().all(|name| {
+            templates
+                .monster_template(name)
+                .is_some_and(|monster| monster.min_depth == 0)
+        }));
+        assert!(!SpawnScheduler::default().tick());
+
+        // A wanderer that finds nowhere to arrive stays due and is not counted
+        let mut scheduler = SpawnScheduler::new(0, &templates);
+        scheduler.turns_on_level = PRESSURE_GRACE_TURNS;
+        assert!(scheduler.tick() && scheduler.tick());
+        assert_eq!(scheduler.spawned, 0);
+        scheduler.record_spawn();
+        assert!(!scheduler.tick());
+        assert_eq!(scheduler.spawned, 1);
+
+        // A player who waits out the grace period on a floor gets company
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        game_state
+            .initialize_player("Sleeper".to_string(), spawn)
+            .unwrap();
+        let monsters = |game_state: &GameState| {
+            let level = game_state.world.current_level().unwrap();
+            level
+                .entities
+                .iter()
+                .filter(|id| {
+                    game_state
+                        .entities
+                        .get(id)
+                        .is_some_and(|e| e.as_monster().is_some())
+                })
+                .count()
+        };
+        let before = monsters(&game_state);
+        for _ in 0..PRESSURE_GRACE_TURNS {
+            game_state.advance_turn().unwrap();
+        }
+        assert_eq!(monsters(&game_state), before + 1);
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(level.spawn_scheduler.spawned, 1);
+    }
+}
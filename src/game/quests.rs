@@ -0,0 +1,244 @@
+//! # Quests Module
+//!
+//! Objectives the player can work towards for a reward.
+//!
+//! A [`Quest`] pairs an [`Objective`] with a [`QuestReward`]. Objectives are
+//! predicates over game events: the game state turns each event into a
+//! [`QuestTrigger`], with names already looked up, and the [`QuestLog`] checks
+//! it against every active quest. Quests can be written by hand or generated
+//! from room and floor metadata by
+//! [`LldmClient::generate_quest`](crate::LldmClient::generate_quest).
+
+use crate::ItemType;
+use serde::{Deserialize, Serialize};
+
+/// What a quest asks of the player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    /// Kill a number of monsters with the given name
+    Kill { target: String, count: u32 },
+    /// Reach a dungeon level, by level id
+    ReachFloor { floor: u32 },
+    /// Pick up an item with the given name
+    Retrieve { item: String },
+}
+
+impl Objective {
+    /// Gets how many matching triggers complete the objective.
+    pub fn required(&self) -> u32 {
+        match self {
+            Objective::Kill { count, .. } => *count,
+            Objective::ReachFloor { .. } | Objective::Retrieve { .. } => 1,
+        }
+    }
+
+    /// Checks if a trigger counts towards the objective.
+    pub fn matches(&self, trigger: &QuestTrigger) -> bool {
+        match (self, trigger) {
+            (Objective::Kill { target, .. }, QuestTrigger::Killed(name)) => target == name,
+            (Objective::ReachFloor { floor }, QuestTrigger::ReachedFloor(reached)) => {
+                reached >= floor
+            }
+            (Objective::Retrieve { item }, QuestTrigger::PickedUp(name)) => item == name,
+            _ => false,
+        }
+    }
+
+    /// Describes the objective for the quest log.
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::Kill { target, count: 1 } => format!("Kill a {}", target),
+            Objective::Kill { target, count } => format!("Kill {} x {}", count, target),
+            Objective::ReachFloor { floor } => format!("Reach dungeon level {}", floor + 1),
+            Objective::Retrieve { item } => format!("Find the {}", item),
+        }
+    }
+}
+
+/// Something that happened in the game which may advance a quest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestTrigger<'a> {
+    /// The player killed a monster with this name
+    Killed(&'a str),
+    /// The player arrived on this level
+    ReachedFloor(u32),
+    /// The player picked up an item with this name
+    PickedUp(&'a str),
+}
+
+/// What completing a quest earns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuestReward {
+    /// Experience points
+    Experience(u32),
+    /// An item placed in the player's pack
+    Item { name: String, item_type: ItemType },
+}
+
+impl QuestReward {
+    /// Describes the reward for the quest log.
+    pub fn describe(&self) -> String {
+        match self {
+            QuestReward::Experience(amount) => format!("{} XP", amount),
+            QuestReward::Item { name, .. } => name.clone(),
+        }
+    }
+}
+
+/// One quest and the player's progress on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quest {
+    /// Short name shown in the quest log
+    pub title: String,
+    /// Flavor text explaining the quest
+    pub description: String,
+    /// What the player has to do
+    pub objective: Objective,
+    /// Matching triggers seen so far
+    pub progress: u32,
+    /// What the player earns on completion
+    pub reward: QuestReward,
+}
+
+impl Quest {
+    /// Creates a quest with no progress.
+    pub fn new(
+        title: String,
+        description: String,
+        objective: Objective,
+        reward: QuestReward,
+    ) -> Self {
+        Self {
+            title,
+            description,
+            objective,
+            progress: 0,
+            reward,
+        }
+    }
+
+    /// Returns true once the objective has been met.
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.objective.required()
+    }
+}
+
+/// Every quest the player has been given, in the order received.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{Objective, Quest, QuestLog, QuestReward, QuestTrigger};
+///
+/// let mut log = QuestLog::new();
+/// log.add(Quest::new(
+///     "Goblin trouble".to_string(),
+///     "The goblins must go.".to_string(),
+///     Objective::Kill { target: "Goblin".to_string(), count: 2 },
+///     QuestReward::Experience(50),
+/// ));
+///
+/// assert!(log.advance(&QuestTrigger::Killed("Goblin")).is_empty());
+/// assert!(log.advance(&QuestTrigger::Killed("Orc")).is_empty());
+/// let completed = log.advance(&QuestTrigger::Killed("Goblin"));
+/// assert_eq!(completed[0].title, "Goblin trouble");
+/// assert_eq!(log.active().count(), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestLog {
+    /// Active and completed quests
+    quests: Vec<Quest>,
+}
+
+impl QuestLog {
+    /// Creates an empty quest log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a quest, unless one with the same title was already given.
+    ///
+    /// Returns true if the quest was added.
+    pub fn add(&mut self, quest: Quest) -> bool {
+        if self.quests.iter().any(|known| known.title == quest.title) {
+            return false;
+        }
+        self.quests.push(quest);
+        true
+    }
+
+    /// Counts a trigger towards every active quest it matches.
+    ///
+    /// Returns the quests this trigger completed.
+    pub fn advance(&mut self, trigger: &QuestTrigger) -> Vec<Quest> {
+        let mut completed = Vec::new();
+        for quest in self.quests.iter_mut() {
+            if quest.is_complete() || !quest.objective.matches(trigger) {
+                continue;
+            }
+            quest.progress += 1;
+            if quest.is_complete() {
+                completed.push(quest.clone());
+            }
+        }
+        completed
+    }
+
+    /// Iterates over every quest, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Quest> {
+        self.quests.iter()
+    }
+
+    /// Iterates over the quests still in progress.
+    pub fn active(&self) -> impl Iterator<Item = &Quest> {
+        self.quests.iter().filter(|quest| !quest.is_complete())
+    }
+
+    /// Returns true if the player has never been given a quest.
+    pub fn is_empty(&self) -> bool {
+        self.quests.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_objectives_match_their_triggers_only() {
+        let descend = Quest::new(
+            "Down we go".to_string(),
+            "Deeper.".to_string(),
+            Objective::ReachFloor { floor: 3 },
+            QuestReward::Experience(75),
+        );
+        let fetch = Quest::new(
+            "Fetch".to_string(),
+            "Bring back the dagger.".to_string(),
+            Objective::Retrieve {
+                item: "Dagger".to_string(),
+            },
+            QuestReward::Item {
+                name: "Potion of healing".to_string(),
+                item_type: ItemType::Consumable(crate::ConsumableType::HealthPotion),
+            },
+        );
+        let mut log = QuestLog::new();
+        assert!(log.add(descend.clone()));
+        assert!(log.add(fetch));
+        assert!(!log.add(descend));
+
+        assert!(log.advance(&QuestTrigger::ReachedFloor(2)).is_empty());
+        assert!(log.advance(&QuestTrigger::PickedUp("Mace")).is_empty());
+        assert!(log.advance(&QuestTrigger::Killed("Dagger")).is_empty());
+        let completed = log.advance(&QuestTrigger::ReachedFloor(4));
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].objective.describe(), "Reach dungeon level 4");
+
+        // Completed quests stay in the log but never complete twice
+        assert!(log.advance(&QuestTrigger::ReachedFloor(5)).is_empty());
+        assert_eq!(log.iter().count(), 2);
+        assert_eq!(log.active().count(), 1);
+        assert_eq!(log.advance(&QuestTrigger::PickedUp("Dagger")).len(), 1);
+    }
+}
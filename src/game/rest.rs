@@ -0,0 +1,95 @@
+//! # Rest Module
+//!
+//! Resting repeats [`RestAction`] turn after turn until the player is fully
+//! recovered, [`REST_TURN_CAP`] turns have passed, or an [`Interruption`]
+//! fires. Like travel, one turn is taken per frame so the scene stays responsive
+//! and any key press stops resting.
+//!
+//! [`RestAction`]: crate::RestAction
+
+use crate::{Interruption, InterruptionWatch};
+
+/// Most turns a single rest command lasts.
+pub const REST_TURN_CAP: u32 = 100;
+
+/// Why a rest command finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestOutcome {
+    /// Health and mana are full
+    Recovered,
+    /// The turn cap was reached
+    TurnCap,
+    /// Something needed the player's attention
+    Interrupted(Interruption),
+}
+
+/// State of an in-progress rest command.
+#[derive(Debug, Clone)]
+pub struct RestState {
+    /// Whether the player is resting
+    pub active: bool,
+    /// Turns rested so far
+    pub turns_rested: u32,
+    /// What was already in view when resting started, and what should stop it
+    pub watch: InterruptionWatch,
+}
+
+impl RestState {
+    /// Creates an idle rest state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            turns_rested: 0,
+            watch: InterruptionWatch::new(None),
+        }
+    }
+
+    /// Stops resting and returns the message reporting how it went.
+    pub fn finish(&mut self, outcome: RestOutcome) -> String {
+        let turns = self.turns_rested;
+        self.cancel();
+        match outcome {
+            RestOutcome::Recovered => {
+                format!("You rest for {} turns and feel fully recovered.", turns)
+            }
+            RestOutcome::TurnCap => format!("You rest for {} turns.", turns),
+            RestOutcome::Interrupted(interruption) => format!(
+                "You stop resting after {} turns: {}.",
+                turns,
+                interruption.reason()
+            ),
+        }
+    }
+
+    /// Stops resting without a report.
+    pub fn cancel(&mut self) {
+        self.active = false;
+        self.turns_rested = 0;
+        self.watch.clear();
+    }
+}
+
+impl Default for RestState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_finish_reports_turns() {
+        let mut rest = RestState::new();
+        rest.active = true;
+        rest.turns_rested = 7;
+
+        let message = rest.finish(RestOutcome::Interrupted(Interruption::HostileSpotted));
+        assert!(message.contains("7 turns"));
+        assert!(message.contains(Interruption::HostileSpotted.reason()));
+        assert!(!rest.active);
+        assert_eq!(rest.turns_rested, 0);
+    }
+}
@@ -0,0 +1,183 @@
+//! # Rewind Module
+//!
+//! Time travel for debugging: undoing the last few turns of a game.
+//!
+//! A [`RewindHistory`] keeps a copy of the game every
+//! [`REWIND_CHECKPOINT_INTERVAL`] turns and every player action taken since
+//! the oldest copy. Rewinding restores the last copy at or before the turn
+//! asked for and replays the actions after it through a [`HeadlessSession`],
+//! so turns resolve exactly as they did in play. The seeded
+//! [`RngService`](crate::RngService) travels with the copy, so the replayed
+//! turns roll the same dice; only entities created during play, whose ids are
+//! random, can make a replay diverge, and a diverging rewind is refused.
+//!
+//! [`HeadlessSession`]: crate::HeadlessSession
+
+use crate::{ConcreteAction, GameState, HeadlessSession, ThatchError, ThatchResult};
+use std::collections::VecDeque;
+
+/// Turns between the copies of the game a rewind starts from.
+pub const REWIND_CHECKPOINT_INTERVAL: u64 = 20;
+
+/// Most copies of the game kept, bounding how far back a rewind can go.
+pub const REWIND_MAX_CHECKPOINTS: usize = 10;
+
+/// Copies of a game and the actions taken since, for undoing turns.
+#[derive(Debug, Clone)]
+pub struct RewindHistory {
+    /// Turns between checkpoints
+    pub interval: u64,
+    /// Copies of the game at the start of a turn, oldest first
+    checkpoints: VecDeque<GameState>,
+    /// Player actions since the oldest checkpoint, with the turn each was taken on
+    actions: Vec<(u64, ConcreteAction)>,
+}
+
+impl Default for RewindHistory {
+    fn default() -> Self {
+        Self::new(REWIND_CHECKPOINT_INTERVAL)
+    }
+}
+
+impl RewindHistory {
+    /// Creates an empty history that copies the game every `interval` turns.
+    pub fn new(interval: u64) -> Self {
+        Self {
+            interval: interval.max(1),
+            checkpoints: VecDeque::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Copies the game if a checkpoint is due; call at the start of a turn.
+    ///
+    /// A game with another player, or one earlier than the newest checkpoint,
+    /// is a different game, so the history starts over with it.
+    pub fn observe(&mut self, game_state: &GameState) {
+        let Some(newest) = self.checkpoints.back() else {
+            self.checkpoint(game_state.clone());
+            return;
+        };
+        if newest.player_id != game_state.player_id || newest.turn_number > game_state.turn_number {
+            self.checkpoints.clear();
+            self.actions.clear();
+            self.checkpoint(game_state.clone());
+        } else if game_state.turn_number >= newest.turn_number + self.interval {
+            self.checkpoint(game_state.clone());
+        }
+    }
+
+    /// Remembers a player action taken on `turn`.
+    pub fn record(&mut self, turn: u64, action: &ConcreteAction) {
+        self.actions.push((turn, action.clone()));
+    }
+
+    /// Gets the earliest turn the game can be rewound to, if any.
+    pub fn earliest_turn(&self) -> Option<u64> {
+        self.checkpoints
+            .front()
+            .map(|checkpoint| checkpoint.turn_number)
+    }
+
+    /// Rebuilds the game as it was `turns` turns before `game_state`.
+    ///
+    /// History after the returned turn is forgotten, so rewinding again goes
+    /// further back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the turn is older than every checkpoint or a
+    /// replayed action no longer succeeds.
+    pub fn rewind(&mut self, game_state: &GameState, turns: u64) -> ThatchResult<GameState> {
+        let target = game_state.turn_number.saturating_sub(turns);
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.turn_number <= target)
+            .ok_or_else(|| match self.earliest_turn() {
+                Some(turn) => ThatchError::InvalidAction(format!(
+                    "Turn {} is before the earliest kept turn {}",
+                    target, turn
+                )),
+                None => ThatchError::InvalidAction("Nothing to rewind".to_string()),
+            })?;
+
+        let start = checkpoint.turn_number;
+        let mut session = HeadlessSession::new(checkpoint.clone());
+        for (turn, action) in &self.actions {
+            if (start..target).contains(turn) {
+                session.submit(action).map_err(|e| {
+                    ThatchError::InvalidState(format!("Replay diverged on turn {}: {}", turn, e))
+                })?;
+            }
+        }
+
+        self.checkpoints
+            .retain(|checkpoint| checkpoint.turn_number <= target);
+        self.actions.retain(|(turn, _)| *turn < target);
+        Ok(session.game_state)
+    }
+
+    /// Keeps a copy of the game, dropping the oldest beyond the limit along
+    /// with the actions only it needed.
+    fn checkpoint(&mut self, game_state: GameState) {
+        self.checkpoints.push_back(game_state);
+        while self.checkpoints.len() > REWIND_MAX_CHECKPOINTS {
+            self.checkpoints.pop_front();
+        }
+        if let Some(oldest) = self.earliest_turn() {
+            self.actions.retain(|(turn, _)| *turn >= oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, Entity, MoveAction, Position, Tile, WaitAction};
+
+    #[test]
+    fn test_rewind_replays_from_the_last_checkpoint() {
+        let mut game_state = GameState::new(21);
+        let level = game_state.world.current_level_mut().unwrap();
+        for x in 5..12 {
+            for y in 5..12 {
+                level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+            }
+        }
+        let player_id = game_state
+            .initialize_player("Rewound".to_string(), Position::new(5, 5))
+            .unwrap();
+        let mut history = RewindHistory::new(4);
+        let mut session = HeadlessSession::new(game_state);
+        let mut positions = Vec::new();
+        for turn in 0..10 {
+            history.observe(&session.game_state);
+            positions.push(session.game_state.get_player().unwrap().position());
+            let direction = if turn % 2 == 0 {
+                Direction::East
+            } else {
+                Direction::South
+            };
+            let action = match turn {
+                3 => ConcreteAction::Wait(WaitAction::new(player_id)),
+                _ => ConcreteAction::Move(MoveAction::new(player_id, direction)),
+            };
+            session.submit(&action).unwrap();
+            history.record(session.game_state.turn_number - 1, &action);
+        }
+        assert_eq!(session.game_state.turn_number, 10);
+
+        // Turn 7 is replayed from the checkpoint at turn 4
+        let rewound = history.rewind(&session.game_state, 3).unwrap();
+        assert_eq!(rewound.turn_number, 7);
+        assert_eq!(rewound.get_player().unwrap().position(), positions[7]);
+
+        // Rewinding again goes further back; with no history there is nothing to undo
+        let rewound = history.rewind(&rewound, 7).unwrap();
+        assert_eq!(rewound.turn_number, 0);
+        assert_eq!(rewound.get_player().unwrap().position(), positions[0]);
+        assert!(RewindHistory::default().rewind(&rewound, 1).is_err());
+    }
+}
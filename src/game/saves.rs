@@ -0,0 +1,355 @@
+//! # Saves
+//!
+//! Manual saves, autosaves and recovering from a crash.
+//!
+//! The pause menu saves to [`config::SAVE_FILE`]. On top of that, the
+//! [`SaveManager`] autosaves whenever the player changes level and every few
+//! turns, as set in the options. Autosaves rotate through
+//! [`config::AUTOSAVE_SLOTS`] files, overwriting the oldest, so a save
+//! interrupted halfway never costs more than one autosave. At startup, an
+//! autosave newer than the manual save means the last session ended without
+//! saving, and the player is offered to pick up from it.
+//!
+//! By default saves follow [`SaveMode::Permadeath`]: loading a save uses it
+//! up, saving also quits to the main menu, and a run that ends takes its
+//! autosaves with it, so a dead character cannot be restored. Players can opt
+//! out with [`SaveMode::Casual`] in the options.
+//!
+//! [`config::SAVE_FILE`]: crate::config::SAVE_FILE
+//! [`config::AUTOSAVE_SLOTS`]: crate::config::AUTOSAVE_SLOTS
+
+use crate::{config, GameState, ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How saves are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveMode {
+    /// Loading a save deletes it and saving quits, so death is final
+    #[default]
+    Permadeath,
+    /// Saves are kept when loaded and can be made at any time
+    Casual,
+}
+
+impl SaveMode {
+    /// Every mode, in the order the options step through them
+    pub const ALL: [SaveMode; 2] = [SaveMode::Permadeath, SaveMode::Casual];
+
+    /// Returns a short human-readable name for the mode.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            SaveMode::Permadeath => "Permadeath",
+            SaveMode::Casual => "Casual",
+        }
+    }
+
+    /// Returns true if saves are used up when loaded.
+    pub fn is_permadeath(self) -> bool {
+        self == SaveMode::Permadeath
+    }
+}
+
+/// Why an autosave was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutosaveReason {
+    /// The player moved to another level
+    LevelChange,
+    /// The autosave interval has passed since the last autosave
+    Interval,
+}
+
+/// Decides when to autosave and where saves go.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{AutosaveReason, GameState, SaveManager};
+///
+/// let directory = std::env::temp_dir().join("thatch_saves_doc");
+/// std::fs::create_dir_all(&directory).unwrap();
+/// let mut saves = SaveManager::in_directory(&directory);
+/// saves.interval = 50;
+///
+/// let mut game_state = GameState::new(12345);
+/// saves.track(&game_state);
+/// game_state.turn_number = 50;
+/// assert_eq!(saves.update(&game_state).unwrap(), Some(AutosaveReason::Interval));
+/// assert!(saves.newest_autosave().is_some());
+/// # std::fs::remove_dir_all(&directory).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaveManager {
+    /// File the pause menu saves to
+    pub save_path: PathBuf,
+    /// Files autosaves rotate through
+    pub autosave_paths: Vec<PathBuf>,
+    /// Turns between autosaves, or zero to autosave only on level changes
+    pub interval: u64,
+    /// Whether saves are used up when loaded
+    pub mode: SaveMode,
+    /// Turn of the last autosave, or of the start of tracking
+    last_turn: u64,
+    /// Level the player was on at the last check
+    last_level: Option<u32>,
+}
+
+impl Default for SaveManager {
+    fn default() -> Self {
+        Self::in_directory(Path::new("."))
+    }
+}
+
+impl SaveManager {
+    /// Creates a manager keeping the save and autosave files in `directory`.
+    pub fn in_directory(directory: &Path) -> Self {
+        let autosave_paths = (1..=config::AUTOSAVE_SLOTS)
+            .map(|slot| directory.join(format!("{}_{}.json", config::AUTOSAVE_FILE_STEM, slot)))
+            .collect();
+        Self {
+            save_path: directory.join(config::SAVE_FILE),
+            autosave_paths,
+            interval: config::DEFAULT_AUTOSAVE_TURNS,
+            mode: SaveMode::default(),
+            last_turn: 0,
+            last_level: None,
+        }
+    }
+
+    /// Starts counting turns and level changes from the state of a new or loaded game.
+    pub fn track(&mut self, game_state: &GameState) {
+        self.last_turn = game_state.turn_number;
+        self.last_level = Some(game_state.world.current_level_id);
+    }
+
+    /// Checks whether the game is due for an autosave.
+    pub fn autosave_reason(&self, game_state: &GameState) -> Option<AutosaveReason> {
+        if self
+            .last_level
+            .is_some_and(|level| level != game_state.world.current_level_id)
+        {
+            return Some(AutosaveReason::LevelChange);
+        }
+        if self.interval > 0 && game_state.turn_number >= self.last_turn + self.interval {
+            return Some(AutosaveReason::Interval);
+        }
+        None
+    }
+
+    /// Autosaves the game if it is due, returning why it was saved.
+    ///
+    /// Finished games are never autosaved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the autosave could not be written; the manager
+    /// still moves on, so a failing disk is not retried every turn.
+    pub fn update(&mut self, game_state: &GameState) -> ThatchResult<Option<AutosaveReason>> {
+        if game_state.is_game_ended() {
+            return Ok(None);
+        }
+        let Some(reason) = self.autosave_reason(game_state) else {
+            return Ok(None);
+        };
+        self.track(game_state);
+        self.autosave(game_state)?;
+        Ok(Some(reason))
+    }
+
+    /// Writes the game to the autosave slot that was written longest ago.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be written.
+    pub fn autosave(&self, game_state: &GameState) -> ThatchResult<PathBuf> {
+        let path = self
+            .autosave_paths
+            .iter()
+            .min_by_key(|path| modified(path))
+            .cloned()
+            .unwrap_or_else(|| self.save_path.clone());
+        game_state.save_to_file(&path)?;
+        Ok(path)
+    }
+
+    /// Writes the game to the manual save file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be written.
+    pub fn save(&self, game_state: &GameState) -> ThatchResult<()> {
+        game_state.save_to_file(&self.save_path)
+    }
+
+    /// Loads a save or autosave to resume play.
+    ///
+    /// With [`SaveMode::Permadeath`], the loaded file and every autosave are
+    /// deleted, so the run can only go on from the game now in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or if it holds a run that
+    /// has already ended.
+    pub fn load(&self, path: &Path) -> ThatchResult<GameState> {
+        let game_state = GameState::load_from_file(path)?;
+        if game_state.is_game_ended() {
+            return Err(ThatchError::InvalidState(
+                "That run has already ended and cannot be restored".to_string(),
+            ));
+        }
+        if self.mode.is_permadeath() {
+            remove_if_present(path)?;
+            self.discard_autosaves()?;
+        }
+        Ok(game_state)
+    }
+
+    /// Forgets the autosaves of a run that has ended, unless saves are casual.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an autosave could not be deleted.
+    pub fn end_run(&self) -> ThatchResult<()> {
+        if self.mode.is_permadeath() {
+            self.discard_autosaves()?;
+        }
+        Ok(())
+    }
+
+    /// Deletes every autosave.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an autosave could not be deleted.
+    pub fn discard_autosaves(&self) -> ThatchResult<()> {
+        for path in &self.autosave_paths {
+            remove_if_present(path)?;
+        }
+        Ok(())
+    }
+
+    /// Finds the most recently written autosave.
+    pub fn newest_autosave(&self) -> Option<PathBuf> {
+        self.autosave_paths
+            .iter()
+            .filter_map(|path| Some((modified(path)?, path)))
+            .max_by_key(|(time, _)| *time)
+            .map(|(_, path)| path.clone())
+    }
+
+    /// Finds an autosave written after the manual save, if there is one.
+    ///
+    /// Such an autosave holds progress the player never saved themselves,
+    /// usually because the game closed unexpectedly.
+    pub fn recoverable_autosave(&self) -> Option<PathBuf> {
+        let autosave = self.newest_autosave()?;
+        match modified(&self.save_path) {
+            Some(saved) if modified(&autosave)? <= saved => None,
+            _ => Some(autosave),
+        }
+    }
+}
+
+/// Deletes a file, doing nothing if it does not exist.
+fn remove_if_present(path: &Path) -> ThatchResult<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Gets when a file was last written, or None if it does not exist.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_autosaves_rotate_and_offer_recovery() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut saves = SaveManager::in_directory(temp_dir.path());
+        saves.interval = 10;
+
+        let mut game_state = GameState::new(12345);
+        saves.track(&game_state);
+        game_state.turn_number = 9;
+        assert_eq!(saves.update(&game_state).unwrap(), None);
+        assert_eq!(saves.recoverable_autosave(), None);
+
+        game_state.turn_number = 10;
+        assert_eq!(
+            saves.update(&game_state).unwrap(),
+            Some(AutosaveReason::Interval)
+        );
+        let first = saves.newest_autosave().unwrap();
+        assert_eq!(first, saves.autosave_paths[0]);
+
+        // Changing level saves at once, into the other slot
+        game_state.turn_number = 12;
+        game_state.world.current_level_id += 1;
+        assert_eq!(
+            saves.update(&game_state).unwrap(),
+            Some(AutosaveReason::LevelChange)
+        );
+        assert_eq!(saves.newest_autosave().unwrap(), saves.autosave_paths[1]);
+
+        // A manual save made later hides the autosaves; an older one does not
+        saves.save(&game_state).unwrap();
+        let past = SystemTime::now() - Duration::from_secs(60);
+        for path in &saves.autosave_paths {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(past)
+                .unwrap();
+        }
+        assert_eq!(saves.recoverable_autosave(), None);
+        let older = past - Duration::from_secs(60);
+        let save_file = std::fs::File::options()
+            .write(true)
+            .open(&saves.save_path)
+            .unwrap();
+        save_file.set_modified(older).unwrap();
+        assert!(saves.recoverable_autosave().is_some());
+        assert!(GameState::load_from_file(&saves.recoverable_autosave().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_permadeath_uses_up_saves() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut saves = SaveManager::in_directory(temp_dir.path());
+        let game_state = GameState::new(12345);
+
+        // Casual saves survive loading
+        saves.mode = SaveMode::Casual;
+        saves.save(&game_state).unwrap();
+        saves.load(&saves.save_path).unwrap();
+        assert!(saves.save_path.exists());
+
+        // Permadeath saves and autosaves do not
+        saves.mode = SaveMode::Permadeath;
+        saves.autosave(&game_state).unwrap();
+        saves.load(&saves.save_path).unwrap();
+        assert!(!saves.save_path.exists());
+        assert_eq!(saves.newest_autosave(), None);
+        assert!(saves.load(&saves.save_path).is_err());
+
+        // A run that has ended cannot be restored from a copy of its save
+        let mut dead = game_state.clone();
+        dead.completion_state = crate::GameCompletionState::PlayerDied;
+        saves.autosave(&game_state).unwrap();
+        saves.end_run().unwrap();
+        assert_eq!(saves.newest_autosave(), None);
+        dead.save_to_file(&saves.save_path).unwrap();
+        assert!(saves.load(&saves.save_path).is_err());
+    }
+}
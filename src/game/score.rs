@@ -0,0 +1,269 @@
+//! # Score
+//!
+//! Points for a finished run, and the best runs kept between games.
+//!
+//! A [`Score`] adds up points for every level reached, each treasure carried
+//! out, each kill and the way the run ended, less a point for every
+//! [`TURNS_PER_PENALTY_POINT`] turns taken, so a quick run beats a slow one
+//! that got as far. The ending screen shows the score, and the
+//! [`HighScoreTable`] in [`config::HIGH_SCORES_FILE`] keeps the best score for
+//! each seed and class, so replaying a seed as the same class only counts if
+//! it does better.
+//!
+//! [`config::HIGH_SCORES_FILE`]: crate::config::HIGH_SCORES_FILE
+
+use crate::{CharacterClass, GameCompletionState, ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Points for each dungeon level reached, counting from 1.
+pub const DEPTH_POINTS: u64 = 100;
+
+/// Points for each treasure the player carries at the end.
+pub const TREASURE_POINTS: u64 = 25;
+
+/// Points for each monster the player killed.
+pub const KILL_POINTS: u64 = 10;
+
+/// Points for leaving the dungeon alive without the Amulet.
+pub const ESCAPE_BONUS: u64 = 250;
+
+/// Points for leaving the dungeon with the Amulet.
+pub const VICTORY_BONUS: u64 = 5000;
+
+/// Turns taken for each point the score loses.
+pub const TURNS_PER_PENALTY_POINT: u64 = 10;
+
+/// Most runs the high-score table keeps.
+pub const MAX_HIGH_SCORES: usize = 20;
+
+/// The points a run earned, part by part.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{GameCompletionState, Score};
+///
+/// // Reached level 3 with two treasures and five kills, then escaped
+/// let score = Score::new(3, 2, 5, 400, &GameCompletionState::EscapedEarly);
+/// assert_eq!(score.total(), 300 + 50 + 50 + 250 - 40);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    /// Points for the deepest level reached
+    pub depth: u64,
+    /// Points for treasure carried
+    pub treasure: u64,
+    /// Points for kills
+    pub kills: u64,
+    /// Points for how the run ended
+    pub ending: u64,
+    /// Points lost to the turns taken
+    pub turn_penalty: u64,
+}
+
+impl Score {
+    /// Scores a run that reached `levels` levels, counting from 1, carrying
+    /// `treasures` treasures, with `kills` kills in `turns` turns.
+    pub fn new(
+        levels: u32,
+        treasures: u32,
+        kills: u32,
+        turns: u64,
+        ending: &GameCompletionState,
+    ) -> Self {
+        Self {
+            depth: u64::from(levels) * DEPTH_POINTS,
+            treasure: u64::from(treasures) * TREASURE_POINTS,
+            kills: u64::from(kills) * KILL_POINTS,
+            ending: match ending {
+                GameCompletionState::EscapedEarly => ESCAPE_BONUS,
+                GameCompletionState::EscapedWithArtifact => VICTORY_BONUS,
+                GameCompletionState::Playing | GameCompletionState::PlayerDied => 0,
+            },
+            turn_penalty: turns / TURNS_PER_PENALTY_POINT,
+        }
+    }
+
+    /// Gets the score, which is never below zero.
+    pub fn total(&self) -> u64 {
+        (self.depth + self.treasure + self.kills + self.ending).saturating_sub(self.turn_penalty)
+    }
+
+    /// Describes where the points came from, for the ending screen.
+    pub fn breakdown(&self) -> String {
+        format!(
+            "depth {} + treasure {} + kills {} + ending {} - turns {}",
+            self.depth, self.treasure, self.kills, self.ending, self.turn_penalty
+        )
+    }
+}
+
+/// A finished run in the high-score table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    /// Total score of the run
+    pub score: u64,
+    /// World seed of the run
+    pub seed: u64,
+    /// Class the player chose
+    pub class: CharacterClass,
+    /// Name of the player character
+    pub character_name: String,
+    /// How the run ended
+    pub ending: GameCompletionState,
+    /// Deepest level reached, counting from 1
+    pub depth: u32,
+    /// Turns the run took
+    pub turns: u64,
+}
+
+impl HighScore {
+    /// Returns a one-line summary for the records screen.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{} - {} the {}, seed {}: {} on level {} after {} turns",
+            self.score,
+            self.character_name,
+            self.class.display_name(),
+            self.seed,
+            self.ending.description(),
+            self.depth,
+            self.turns
+        )
+    }
+}
+
+/// The best runs, best first, with one run for each seed and class.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{CharacterClass, GameCompletionState, HighScore, HighScoreTable};
+///
+/// let run = |score| HighScore {
+///     score,
+///     seed: 7,
+///     class: CharacterClass::default(),
+///     character_name: "Player".to_string(),
+///     ending: GameCompletionState::PlayerDied,
+///     depth: 2,
+///     turns: 300,
+/// };
+/// let mut table = HighScoreTable::default();
+/// assert_eq!(table.record(run(120)), Some(1));
+/// // The same seed and class only count again for a better score
+/// assert_eq!(table.record(run(90)), None);
+/// assert_eq!(table.record(run(150)), Some(1));
+/// assert_eq!(table.scores.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighScoreTable {
+    /// The runs, best first
+    pub scores: Vec<HighScore>,
+}
+
+impl HighScoreTable {
+    /// Loads the table from a JSON file.
+    pub fn load(path: &Path) -> ThatchResult<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(ThatchError::from)
+    }
+
+    /// Loads the table from a JSON file, starting afresh if it is missing or unreadable.
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    /// Writes the table to a JSON file.
+    pub fn save(&self, path: &Path) -> ThatchResult<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Adds a run, replacing the run with the same seed and class if it
+    /// scored less.
+    ///
+    /// Returns the run's place in the table, counting from 1, or None if it
+    /// did not make the table.
+    pub fn record(&mut self, run: HighScore) -> Option<usize> {
+        let same = |entry: &HighScore| entry.seed == run.seed && entry.class == run.class;
+        if let Some(index) = self.scores.iter().position(same) {
+            if self.scores[index].score >= run.score {
+                return None;
+            }
+            self.scores.remove(index);
+        }
+
+        let index = self
+            .scores
+            .partition_point(|entry| entry.score >= run.score);
+        if index >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.scores.insert(index, run);
+        self.scores.truncate(MAX_HIGH_SCORES);
+        Some(index + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Item, ItemType, Position};
+
+    #[test]
+    fn test_scores_count_the_run_and_the_table_keeps_the_best() {
+        let mut game_state = GameState::new(9);
+        game_state
+            .initialize_player("Scorer".to_string(), Position::new(5, 5))
+            .unwrap();
+        for _ in 0..3 {
+            let coins = Item::new(
+                "Gold coins".to_string(),
+                ItemType::Treasure,
+                Position::new(5, 5),
+            );
+            game_state.give_item_to_player(coins, false).unwrap();
+        }
+        game_state.statistics.max_depth_reached = 4;
+        game_state.statistics.enemies_defeated = 6;
+        game_state.turn_number = 1234;
+
+        let score = game_state.score();
+        assert_eq!(
+            score,
+            Score::new(5, 3, 6, 1234, &GameCompletionState::Playing)
+        );
+        assert_eq!(score.total(), 500 + 75 + 60 - 123);
+        assert_eq!(game_state.run_summary().score, score);
+
+        // Runs are ranked best first, and the table survives a round trip
+        let run = |score, seed| HighScore {
+            score,
+            seed,
+            class: CharacterClass::default(),
+            character_name: "Scorer".to_string(),
+            ending: GameCompletionState::PlayerDied,
+            depth: 5,
+            turns: 1234,
+        };
+        let mut table = HighScoreTable::default();
+        assert_eq!(table.record(run(100, 1)), Some(1));
+        assert_eq!(table.record(run(300, 2)), Some(1));
+        assert_eq!(table.record(run(200, 3)), Some(2));
+        let scores: Vec<u64> = table.scores.iter().map(|entry| entry.score).collect();
+        assert_eq!(scores, [300, 200, 100]);
+        for seed in 10..10 + MAX_HIGH_SCORES as u64 {
+            table.record(run(500, seed));
+        }
+        assert_eq!(table.scores.len(), MAX_HIGH_SCORES);
+        assert_eq!(table.record(run(50, 99)), None);
+
+        let path = std::env::temp_dir().join(format!("thatch_scores_{}.json", std::process::id()));
+        table.save(&path).unwrap();
+        assert_eq!(HighScoreTable::load_or_default(&path), table);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+//! # Spells Module
+//!
+//! Spells are plain data: a mana cost, a range, what they target, and what they
+//! do on arrival. [`CastSpellAction`] turns a spell into ordinary game events,
+//! so damage goes through the combat events and lingering effects through the
+//! status effect system. Force spells push their target back as forced
+//! movement. Necromancy raises a corpse from the floor as an ally.
+//!
+//! [`CastSpellAction`]: crate::CastSpellAction
+
+use crate::StatusKind;
+
+/// Who a spell affects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellTarget {
+    /// The caster
+    Caster,
+    /// The nearest visible hostile within range
+    NearestHostile,
+    /// The nearest visible corpse within range, which the spell raises as an ally
+    NearestCorpse,
+}
+
+/// A spell the player can learn and cast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spell {
+    /// Spell name, also used to look the spell up
+    pub name: &'static str,
+    /// Short description for the spell menu
+    pub description: &'static str,
+    /// Mana spent on casting
+    pub mana_cost: u32,
+    /// Farthest a target can be, in tiles walked
+    pub range: u32,
+    /// Who the spell affects
+    pub target: SpellTarget,
+    /// Damage dealt to the target
+    pub damage: u32,
+    /// Health restored to the target
+    pub healing: u32,
+    /// Status effect applied to the target, with its duration in turns
+    pub status: Option<(StatusKind, u32)>,
+    /// Tiles the target is pushed away from the caster
+    pub knockback: u32,
+}
+
+/// Every spell in the game.
+pub const SPELLS: [Spell; 7] = [
+    Spell {
+        name: "Magic Missile",
+        description: "A dart of force that never misses.",
+        mana_cost: 5,
+        range: 8,
+        target: SpellTarget::NearestHostile,
+        damage: 12,
+        healing: 0,
+        status: None,
+        knockback: 0,
+    },
+    Spell {
+        name: "Fire Bolt",
+        description: "Scorches a foe and sets it alight.",
+        mana_cost: 12,
+        range: 6,
+        target: SpellTarget::NearestHostile,
+        damage: 10,
+        healing: 0,
+        status: Some((StatusKind::Burning, 3)),
+        knockback: 0,
+    },
+    Spell {
+        name: "Force Blast",
+        description: "A wave of force that hurls a foe back into the walls.",
+        mana_cost: 8,
+        range: 5,
+        target: SpellTarget::NearestHostile,
+        damage: 4,
+        healing: 0,
+        status: None,
+        knockback: 3,
+    },
+    Spell {
+        name: "Befuddle",
+        description: "Clouds a foe's mind so it stumbles about.",
+        mana_cost: 6,
+        range: 6,
+        target: SpellTarget::NearestHostile,
+        damage: 0,
+        healing: 0,
+        status: Some((StatusKind::Confused, crate::CONFUSED_DURATION)),
+        knockback: 0,
+    },
+    Spell {
+        name: "Mend",
+        description: "Knits your wounds closed.",
+        mana_cost: 10,
+        range: 0,
+        target: SpellTarget::Caster,
+        damage: 0,
+        healing: 25,
+        status: None,
+        knockback: 0,
+    },
+    Spell {
+        name: "Douse",
+        description: "Drenches you, putting out any flames.",
+        mana_cost: 4,
+        range: 0,
+        target: SpellTarget::Caster,
+        damage: 0,
+        healing: 0,
+        status: Some((StatusKind::Soaked, crate::SOAKED_DURATION)),
+        knockback: 0,
+    },
+    Spell {
+        name: "Raise Dead",
+        description: "Calls a nearby corpse back to fight at your side.",
+        mana_cost: 15,
+        range: 4,
+        target: SpellTarget::NearestCorpse,
+        damage: 0,
+        healing: 0,
+        status: None,
+        knockback: 0,
+    },
+];
+
+impl Spell {
+    /// Looks up a spell by name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::Spell;
+    ///
+    /// assert_eq!(Spell::by_name("Mend").unwrap().name, "Mend");
+    /// assert!(Spell::by_name("Wish").is_none());
+    /// ```
+    pub fn by_name(name: &str) -> Option<&'static Spell> {
+        SPELLS.iter().find(|spell| spell.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spells_have_unique_names_and_effects() {
+        for (index, spell) in SPELLS.iter().enumerate() {
+            assert!(SPELLS[index + 1..]
+                .iter()
+                .all(|other| other.name != spell.name));
+            assert!(spell.mana_cost > 0);
+            let raises = spell.target == SpellTarget::NearestCorpse;
+            assert!(spell.damage > 0 || spell.healing > 0 || spell.status.is_some() || raises);
+            if spell.knockback > 0 {
+                assert_eq!(spell.target, SpellTarget::NearestHostile);
+            }
+            if spell.target == SpellTarget::Caster {
+                assert_eq!(spell.range, 0);
+            }
+        }
+    }
+}
@@ -7,12 +7,21 @@
 //! for game operations and maintains consistency across all game components.
 
 use crate::{
-    ActionQueue, AutoexploreState, ConcreteEntity, Direction, Entity, EntityId, EntityStats,
-    GameEvent, Level, MoveAction, PlayerCharacter, Position, StairDirection, ThatchError,
-    ThatchResult, TileType, UseStairsAction, World,
+    AchievementTracker, ActionQueue, AmmoType, AutoexploreState, CharacterClass, ConcreteAction,
+    ConcreteEntity, ConsumableType, Direction, DoorOpening, Entity, EntityId, EntityStats,
+    FieldOfView, FightState, GameEvent, GenerationConfig, GeneratorKind, IdentificationTable, Item,
+    ItemStack, ItemType, Level, LevelExploration, LightKind, LldmMemory, LldmRequestGate,
+    LldmRequestPolicy, LldmTelemetry, LlmSnapshot, MemoryFact, MemoryKind, MoveAction,
+    PlayerCharacter, Position, Quest, QuestLog, QuestReward, QuestTrigger, RepeatState,
+    RestOutcome, RestState, RngService, RngStream, Score, StairDirection, StatusKind,
+    TemplateLibrary, ThatchError, ThatchResult, TileEffect, TileOverlay, TileType,
+    TravelDestination, TravelState, Tutorial, TutorialTrigger, UseStairsAction, VaultLibrary,
+    World, BLANK_SCROLL, WATER_BREATHING_RING,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 /// Central game state containing all game data and systems.
@@ -25,8 +34,17 @@ pub struct GameState {
     /// The game world containing all levels
     pub world: World,
     /// All entities in the game, indexed by ID
+    #[serde(serialize_with = "crate::sorted_map")]
     pub entities: HashMap<EntityId, ConcreteEntity>,
-    /// Spatial index mapping positions to entity IDs
+    /// Items carried or worn by the player, indexed by ID; they are off the map
+    #[serde(default)]
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub carried_items: HashMap<EntityId, Item>,
+    /// Appearances of unidentified potions and scrolls, and which are known
+    #[serde(default)]
+    pub identification: IdentificationTable,
+    /// Spatial index mapping positions to entity IDs (rebuilt on load)
+    #[serde(skip)]
     pub position_index: HashMap<Position, Vec<EntityId>>,
     /// The player entity ID
     pub player_id: Option<EntityId>,
@@ -40,18 +58,67 @@ pub struct GameState {
     /// Total play time in seconds
     pub total_play_time: u64,
     /// Game configuration flags
+    #[serde(serialize_with = "crate::sorted_map")]
     pub config_flags: HashMap<String, bool>,
     /// Game statistics for player progress
     pub statistics: GameStatistics,
+    /// Quests the player has been given
+    #[serde(default)]
+    pub quests: QuestLog,
+    /// Achievements earned this run
+    #[serde(default)]
+    pub achievements: AchievementTracker,
+    /// Progress through the tutorial, if this game is one
+    #[serde(default)]
+    pub tutorial: Option<Tutorial>,
     /// Random number generator seed
     pub rng_seed: u64,
+    /// Deterministic RNG streams for runtime gameplay
+    #[serde(default)]
+    pub rng: RngService,
+    /// Layout algorithm used for this dungeon's floors
+    #[serde(default)]
+    pub generator: GeneratorKind,
+    /// Whether floors are generated as they are needed; saves keep only this and
+    /// the seed, shape and layout the settings for the missing floors come from
+    #[serde(default)]
+    pub lazy_floors: bool,
+    /// Settings for floors not generated yet, when the world is generated lazily
+    /// (not serialized, see [`GameState::restore_lazy_generation`])
+    #[serde(skip)]
+    pub lazy_generation: Option<GenerationConfig>,
+    /// Monster and item templates that monsters arriving mid-game are spawned
+    /// from (not serialized)
+    #[serde(skip)]
+    pub templates: TemplateLibrary,
     /// LLDM integration state
     pub lldm_state: LldmState,
     /// Current game completion state
     pub completion_state: GameCompletionState,
+    /// Most recent messages, oldest first
+    #[serde(default)]
+    pub recent_messages: VecDeque<RecentMessage>,
     /// Autoexplore debug state (not serialized)
     #[serde(skip)]
     pub autoexplore_state: AutoexploreState,
+    /// Multi-step travel state (not serialized)
+    #[serde(skip)]
+    pub travel_state: TravelState,
+    /// Multi-turn rest state (not serialized)
+    #[serde(skip)]
+    pub rest_state: RestState,
+    /// Repeated command state (not serialized)
+    #[serde(skip)]
+    pub repeat_state: RepeatState,
+    /// Auto-fight state (not serialized)
+    #[serde(skip)]
+    pub fight_state: FightState,
+    /// What walking into a closed door does (not serialized)
+    #[serde(skip)]
+    pub door_opening: DoorOpening,
+    /// The player's last field of view (not serialized)
+    #[serde(skip)]
+    pub field_of_view: Option<FieldOfView>,
 }
 
 /// Game statistics tracking player progress and achievements.
@@ -77,6 +144,15 @@ pub struct GameStatistics {
     pub rooms_discovered: u32,
     /// Secrets found
     pub secrets_found: u32,
+    /// Names of entities killed by the player, in order
+    #[serde(default)]
+    pub kill_list: Vec<String>,
+    /// Turn the player first picked up the Amulet, if they have
+    #[serde(default)]
+    pub artifact_found_turn: Option<u64>,
+    /// How much of each level the player has explored, by level ID
+    #[serde(default)]
+    pub level_exploration: BTreeMap<u32, LevelExploration>,
 }
 
 impl GameStatistics {
@@ -93,9 +169,20 @@ impl GameStatistics {
             steps_taken: 0,
             rooms_discovered: 0,
             secrets_found: 0,
+            kill_list: Vec::new(),
+            artifact_found_turn: None,
+            level_exploration: BTreeMap::new(),
         }
     }
 
+    /// Records the latest survey of a level, counting any rooms discovered
+    /// since the last one.
+    pub fn record_exploration(&mut self, level_id: u32, exploration: LevelExploration) {
+        let previous = self.level_exploration.insert(level_id, exploration);
+        let known = previous.map_or(0, |previous| previous.rooms_discovered);
+        self.rooms_discovered += exploration.rooms_discovered.saturating_sub(known);
+    }
+
     /// Updates statistics based on a game event.
     pub fn update_from_event(&mut self, event: &GameEvent) {
         match event {
@@ -105,14 +192,17 @@ impl GameStatistics {
             GameEvent::EntityDamaged { damage, .. } => {
                 self.damage_dealt += *damage as u64;
             }
-            GameEvent::EntityDied { killer, .. } => {
-                if killer.is_some() {
-                    self.enemies_defeated += 1;
-                }
+            GameEvent::EntityDied {
+                killer: Some(_), ..
+            } => {
+                self.enemies_defeated += 1;
             }
             GameEvent::ItemPickedUp { .. } => {
                 self.items_collected += 1;
             }
+            GameEvent::SecretFound { .. } => {
+                self.secrets_found += 1;
+            }
             _ => {}
         }
     }
@@ -124,19 +214,140 @@ impl Default for GameStatistics {
     }
 }
 
+/// Name of the artifact lying on the deepest level; escaping with it wins the game.
+pub const ARTIFACT_NAME: &str = "Amulet of Ages";
+
+/// Content cache key of the epitaph the LLDM writes for a fallen player.
+pub const EPITAPH_KEY: &str = "epitaph";
+
 /// Game completion state for handling endings.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameCompletionState {
     /// Game is still in progress
     Playing,
-    /// Player escaped from level 1 (medium ending)
+    /// Player escaped from level 1 without the Amulet (medium ending)
     EscapedEarly,
-    /// Player reached bottom level and won (good ending)
-    CompletedDungeon,
+    /// Player escaped from level 1 carrying the Amulet (good ending)
+    EscapedWithArtifact,
     /// Player died
     PlayerDied,
 }
 
+impl GameCompletionState {
+    /// Returns a short, human-readable description of how the run ended.
+    pub fn description(&self) -> &'static str {
+        match self {
+            GameCompletionState::Playing => "Still playing",
+            GameCompletionState::EscapedEarly => "Escaped the dungeon early",
+            GameCompletionState::EscapedWithArtifact => "Escaped with the Amulet of Ages",
+            GameCompletionState::PlayerDied => "Died in the dungeon",
+        }
+    }
+}
+
+/// A message remembered for LLM snapshots and the MCP query tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "SavedMessage")]
+pub struct RecentMessage {
+    /// Turn the message was shown on
+    pub turn: u64,
+    /// The message text
+    pub text: String,
+}
+
+/// A remembered message as saved; older saves hold only its text.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedMessage {
+    Text(String),
+    Turned { turn: u64, text: String },
+}
+
+impl From<SavedMessage> for RecentMessage {
+    fn from(saved: SavedMessage) -> Self {
+        match saved {
+            SavedMessage::Text(text) => Self { turn: 0, text },
+            SavedMessage::Turned { turn, text } => Self { turn, text },
+        }
+    }
+}
+
+/// A disagreement between the entity table, the spatial index, and the level
+/// entity lists, as reported by [`GameState::validate_consistency`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsistencyIssue {
+    /// An entity is missing from the spatial index at its position
+    NotIndexed {
+        entity_id: EntityId,
+        position: Position,
+    },
+    /// The spatial index lists an entity at a position it does not occupy
+    StaleIndexEntry {
+        entity_id: EntityId,
+        position: Position,
+    },
+    /// The spatial index lists an entity more than once at one position
+    DuplicateIndexEntry {
+        entity_id: EntityId,
+        position: Position,
+    },
+    /// A level lists an entity that does not exist
+    UnknownLevelEntity { entity_id: EntityId, level_id: u32 },
+    /// More than one level lists the same entity
+    EntityOnMultipleLevels {
+        entity_id: EntityId,
+        level_ids: Vec<u32>,
+    },
+    /// The player ID does not refer to an existing entity
+    MissingPlayer { player_id: EntityId },
+}
+
+impl fmt::Display for ConsistencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsistencyIssue::NotIndexed {
+                entity_id,
+                position,
+            } => write!(
+                f,
+                "entity {} at ({}, {}) is not indexed",
+                entity_id, position.x, position.y
+            ),
+            ConsistencyIssue::StaleIndexEntry {
+                entity_id,
+                position,
+            } => write!(
+                f,
+                "index lists entity {} at ({}, {}) but it is not there",
+                entity_id, position.x, position.y
+            ),
+            ConsistencyIssue::DuplicateIndexEntry {
+                entity_id,
+                position,
+            } => write!(
+                f,
+                "entity {} is indexed twice at ({}, {})",
+                entity_id, position.x, position.y
+            ),
+            ConsistencyIssue::UnknownLevelEntity {
+                entity_id,
+                level_id,
+            } => write!(f, "level {} lists unknown entity {}", level_id, entity_id),
+            ConsistencyIssue::EntityOnMultipleLevels {
+                entity_id,
+                level_ids,
+            } => write!(
+                f,
+                "entity {} is listed on levels {:?}",
+                entity_id, level_ids
+            ),
+            ConsistencyIssue::MissingPlayer { player_id } => {
+                write!(f, "player entity {} does not exist", player_id)
+            }
+        }
+    }
+}
+
 /// State for LLDM (LLM Dungeon Master) integration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LldmState {
@@ -145,11 +356,21 @@ pub struct LldmState {
     /// Current LLDM session ID
     pub session_id: Option<String>,
     /// LLDM-generated content cache
+    #[serde(serialize_with = "crate::sorted_map")]
     pub content_cache: HashMap<String, String>,
     /// Pending LLDM requests
     pub pending_requests: Vec<LldmRequest>,
     /// LLDM configuration
     pub config: LldmConfig,
+    /// What has happened so far this run, for narrative continuity
+    #[serde(default)]
+    pub memory: LldmMemory,
+    /// Counts of what has happened to requests sent to the LLDM
+    #[serde(default)]
+    pub telemetry: LldmTelemetry,
+    /// Rate limit and circuit breaker of the LLDM endpoint
+    #[serde(skip)]
+    pub gate: LldmRequestGate,
 }
 
 /// Configuration for LLDM integration.
@@ -165,6 +386,9 @@ pub struct LldmConfig {
     pub max_tokens: u32,
     /// Whether to use cached responses
     pub use_cache: bool,
+    /// How requests are limited, retried and timed out
+    #[serde(default)]
+    pub requests: LldmRequestPolicy,
 }
 
 /// Request to the LLDM system.
@@ -175,6 +399,7 @@ pub struct LldmRequest {
     /// Type of content to generate
     pub request_type: String,
     /// Context for generation
+    #[serde(serialize_with = "crate::sorted_map")]
     pub context: HashMap<String, String>,
     /// Whether this is urgent
     pub priority: LldmPriority,
@@ -207,6 +432,8 @@ impl GameState {
         Self {
             world: World::new(seed),
             entities: HashMap::new(),
+            carried_items: HashMap::new(),
+            identification: IdentificationTable::new(seed),
             position_index: HashMap::new(),
             player_id: None,
             action_queue: ActionQueue::new(),
@@ -216,6 +443,14 @@ impl GameState {
             config_flags: HashMap::new(),
             statistics: GameStatistics::new(),
             rng_seed: seed,
+            rng: RngService::new(seed),
+            generator: GeneratorKind::default(),
+            lazy_floors: false,
+            lazy_generation: None,
+            templates: TemplateLibrary::default(),
+            quests: QuestLog::new(),
+            achievements: AchievementTracker::new(),
+            tutorial: None,
             lldm_state: LldmState {
                 enabled: false,
                 session_id: None,
@@ -227,31 +462,55 @@ impl GameState {
                     temperature: 0.7,
                     max_tokens: 1000,
                     use_cache: true,
+                    requests: LldmRequestPolicy::default(),
                 },
+                memory: LldmMemory::new(),
+                telemetry: LldmTelemetry::default(),
+                gate: LldmRequestGate::default(),
             },
             completion_state: GameCompletionState::Playing,
+            recent_messages: VecDeque::new(),
             autoexplore_state: AutoexploreState::new(),
+            travel_state: TravelState::new(),
+            rest_state: RestState::new(),
+            repeat_state: RepeatState::new(),
+            fight_state: FightState::new(),
+            door_opening: DoorOpening::default(),
+            field_of_view: None,
         }
     }
 
     /// Creates a new game state with a complete 3D dungeon pre-generated.
     ///
-    /// This method generates all 26 floors at once with proper stair alignment,
+    /// This method generates every floor at once with proper stair alignment,
     /// which is more efficient and ensures consistency across levels.
     pub fn new_with_complete_dungeon(seed: u64) -> ThatchResult<Self> {
-        use crate::{GenerationConfig, RoomCorridorGenerator, WorldGenerator};
-        use rand::{rngs::StdRng, SeedableRng};
+        Self::new_with_generation_config(GenerationConfig::new(seed))
+    }
+
+    /// Creates a new game state with a complete dungeon built from `config`.
+    ///
+    /// The layout algorithm is taken from [`GenerationConfig::generator`] and
+    /// remembered so lazily generated floors use the same style. The number and
+    /// size of the floors come from [`GenerationConfig::shape`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shape is invalid or the world cannot be generated.
+    pub fn new_with_generation_config(config: GenerationConfig) -> ThatchResult<Self> {
+        config.shape.validate()?;
 
-        let config = GenerationConfig::new(seed);
-        let mut rng = StdRng::seed_from_u64(seed);
-        let generator = RoomCorridorGenerator::new();
+        let seed = config.seed;
+        let generator = config.generator.world_generator();
 
         // Generate complete 3D dungeon
-        let world = generator.generate_world(&config, &mut rng)?;
+        let world = generator.generate_world(&config)?;
 
         Ok(Self {
             world,
             entities: HashMap::new(),
+            carried_items: HashMap::new(),
+            identification: IdentificationTable::new(seed),
             position_index: HashMap::new(),
             player_id: None,
             action_queue: ActionQueue::new(),
@@ -261,6 +520,14 @@ impl GameState {
             config_flags: HashMap::new(),
             statistics: GameStatistics::new(),
             rng_seed: seed,
+            rng: RngService::new(seed),
+            generator: config.generator,
+            lazy_floors: false,
+            lazy_generation: None,
+            templates: config.templates.clone(),
+            quests: QuestLog::new(),
+            achievements: AchievementTracker::new(),
+            tutorial: None,
             lldm_state: LldmState {
                 enabled: false,
                 session_id: None,
@@ -272,13 +539,76 @@ impl GameState {
                     temperature: 0.7,
                     max_tokens: 1000,
                     use_cache: true,
+                    requests: LldmRequestPolicy::default(),
                 },
+                memory: LldmMemory::new(),
+                telemetry: LldmTelemetry::default(),
+                gate: LldmRequestGate::default(),
             },
             completion_state: GameCompletionState::Playing,
+            recent_messages: VecDeque::new(),
             autoexplore_state: AutoexploreState::new(),
+            travel_state: TravelState::new(),
+            rest_state: RestState::new(),
+            repeat_state: RepeatState::new(),
+            fight_state: FightState::new(),
+            door_opening: DoorOpening::default(),
+            field_of_view: None,
         })
     }
 
+    /// Creates a new game state whose floors are generated as they are needed.
+    ///
+    /// Only the first [`LAZY_INITIAL_FLOORS`](crate::LAZY_INITIAL_FLOORS) floors
+    /// exist at first, so the game starts quickly. Deeper floors are generated
+    /// when the player takes the stairs to them, or ahead of time by a
+    /// [`FloorStreamer`](crate::FloorStreamer).
+    pub fn new_lazy_with_generation_config(config: GenerationConfig) -> ThatchResult<Self> {
+        config.shape.validate()?;
+        let mut game_state = Self::new(config.seed);
+        game_state.world = crate::new_lazy_world(&config)?;
+        game_state.generator = config.generator;
+        game_state.templates = config.templates.clone();
+        game_state.lazy_floors = true;
+        game_state.lazy_generation = Some(config);
+        Ok(game_state)
+    }
+
+    /// Rebuilds the settings for a lazy world's missing floors after loading.
+    ///
+    /// Saves keep only the world's seed, shape and layout, so the other
+    /// settings are the defaults, with the game's templates and `vaults`.
+    pub fn restore_lazy_generation(&mut self, vaults: VaultLibrary) {
+        if !self.lazy_floors {
+            return;
+        }
+        self.lazy_generation = Some(GenerationConfig {
+            generator: self.generator,
+            shape: self.world.shape,
+            templates: self.templates.clone(),
+            vaults,
+            use_lldm: self.lldm_state.enabled,
+            ..GenerationConfig::new(self.rng_seed)
+        });
+    }
+
+    /// Turns the game into the tutorial, replacing the first floor with the
+    /// [`tutorial_level`](crate::tutorial_level) built from `templates`.
+    ///
+    /// Call this before the player is placed, on the first floor's player
+    /// spawn; the instructions for the first step are left to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tutorial floor does not fit the world's floors.
+    pub fn start_tutorial(&mut self, templates: &TemplateLibrary) -> ThatchResult<()> {
+        let shape = self.world.shape;
+        let level = crate::tutorial_level(shape.floor_width, shape.floor_height, templates)?;
+        self.world.add_level(level);
+        self.tutorial = Some(Tutorial::new());
+        Ok(())
+    }
+
     /// Initializes the game with a player character.
     ///
     /// # Examples
@@ -294,9 +624,34 @@ impl GameState {
         &mut self,
         name: String,
         position: Position,
+    ) -> ThatchResult<EntityId> {
+        self.initialize_player_with_class(name, position, CharacterClass::default())
+    }
+
+    /// Creates the player character of `class` and hands out its starting items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{CharacterClass, GameState, Position};
+    ///
+    /// let mut game_state = GameState::new(12345);
+    /// let position = Position::new(5, 5);
+    /// game_state
+    ///     .initialize_player_with_class("Hero".to_string(), position, CharacterClass::Rogue)
+    ///     .unwrap();
+    /// let player = game_state.get_player().unwrap();
+    /// assert_eq!(player.class, CharacterClass::Rogue);
+    /// assert!(player.equipment.contains_key("weapon"));
+    /// ```
+    pub fn initialize_player_with_class(
+        &mut self,
+        name: String,
+        position: Position,
+        class: CharacterClass,
     ) -> ThatchResult<EntityId> {
         // Create player character
-        let player = PlayerCharacter::new(name, position);
+        let player = PlayerCharacter::with_class(name, position, class);
         let player_id = player.id();
 
         // Add to entities
@@ -314,30 +669,335 @@ impl GameState {
             level.add_entity(player_id);
         }
 
+        // Hand out the class's starting items, which the player knows on sight
+        for starting_item in class.starting_items() {
+            self.identification.identify(starting_item.name);
+            for _ in 0..starting_item.count {
+                let item = Item::new(
+                    starting_item.name.to_string(),
+                    starting_item.item_type.clone(),
+                    position,
+                );
+                self.give_item_to_player(item, starting_item.equipped)?;
+            }
+        }
+
+        // Give the player something to aim for from the start, announced like
+        // any quest offered later
+        let mut events: VecDeque<GameEvent> = self.offer_floor_quest().into_iter().collect();
+        while let Some(event) = events.pop_front() {
+            events.extend(self.process_event(&event)?);
+        }
+        self.place_npcs();
+        self.name_unique_items();
+        self.announce_level_feelings();
+        self.place_waiting_monsters();
+
         // Start game timer
         self.game_start_time = Some(Instant::now());
 
         Ok(player_id)
     }
 
-    /// Gets the player character if it exists.
-    pub fn get_player(&self) -> Option<&PlayerCharacter> {
-        if let Some(player_id) = self.player_id {
-            if let Some(ConcreteEntity::Player(player)) = self.entities.get(&player_id) {
-                return Some(player);
+    /// Puts an item in the player's pack, or wears it if `equip` is set and it
+    /// has an equipment slot.
+    ///
+    /// Anything already worn in that slot moves to the pack. Arrows and bolts
+    /// always go in the quiver instead.
+    pub fn give_item_to_player(&mut self, item: Item, equip: bool) -> ThatchResult<EntityId> {
+        let item_id = item.id;
+        if item.item_type.is_ammo() {
+            self.quiver_ammo(ItemStack::new(item))?;
+            return Ok(item_id);
+        }
+        let slot = item.item_type.equip_slot().filter(|_| equip);
+        let player = self
+            .get_player_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+
+        let mut previous = None;
+        match slot {
+            Some(slot) => {
+                previous = player.equip_item(slot.to_string(), item_id);
+                if let Some(previous) = previous {
+                    player.add_to_inventory(previous)?;
+                }
             }
+            None => player.add_to_inventory(item_id)?,
         }
-        None
+        self.carried_items.insert(item_id, item);
+        if slot.is_some() {
+            if let Some(previous) = previous {
+                self.apply_enchantment(previous, false);
+            }
+            self.apply_enchantment(item_id, true);
+        }
+
+        Ok(item_id)
     }
 
-    /// Gets the player character mutably if it exists.
-    pub fn get_player_mut(&mut self) -> Option<&mut PlayerCharacter> {
-        if let Some(player_id) = self.player_id {
-            if let Some(ConcreteEntity::Player(player)) = self.entities.get_mut(&player_id) {
-                return Some(player);
+    /// Adds a carried item's enchantment to the player's stats as it is put
+    /// on, or takes it away again as it comes off.
+    ///
+    /// Weapons change attack and everything else worn changes defense.
+    pub fn apply_enchantment(&mut self, item_id: EntityId, wearing: bool) {
+        let Some(item) = self.carried_items.get(&item_id) else {
+            return;
+        };
+        let weapon = matches!(item.item_type, ItemType::Weapon(_));
+        let change = if wearing {
+            item.enchantment
+        } else {
+            -item.enchantment
+        };
+        if let Some(player) = self.get_player_mut() {
+            let stat = if weapon {
+                &mut player.stats.attack
+            } else {
+                &mut player.stats.defense
+            };
+            *stat = stat.saturating_add_signed(change);
+        }
+    }
+
+    /// Lifts the curses from everything the player carries or wears.
+    ///
+    /// Worn gear loses its penalty at once. Returns how many items were
+    /// cursed.
+    pub fn remove_curses(&mut self) -> u32 {
+        let worn: Vec<EntityId> = self
+            .get_player()
+            .map(|player| player.equipment.values().copied().collect())
+            .unwrap_or_default();
+        let cursed: Vec<EntityId> = self
+            .carried_items
+            .iter()
+            .filter(|(_, item)| item.cursed)
+            .map(|(&id, _)| id)
+            .collect();
+        for &item_id in &cursed {
+            let wearing = worn.contains(&item_id);
+            if wearing {
+                self.apply_enchantment(item_id, false);
+            }
+            if let Some(item) = self.carried_items.get_mut(&item_id) {
+                item.remove_curse();
+            }
+            if wearing {
+                self.apply_enchantment(item_id, true);
             }
         }
-        None
+        cursed.len() as u32
+    }
+
+    /// Puts a stack of ammunition in the player's quiver.
+    ///
+    /// Ammunition of another kind already in the quiver is dropped at the
+    /// player's feet and returned.
+    pub fn quiver_ammo(&mut self, stack: ItemStack) -> ThatchResult<Option<ItemStack>> {
+        let player = self
+            .get_player_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let position = player.position();
+        let Some(mut displaced) = player.fill_quiver(stack) else {
+            return Ok(None);
+        };
+
+        displaced.item.position = position;
+        let level = self
+            .world
+            .current_level_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        level.ground_items.drop_stack(displaced.clone());
+        Ok(Some(displaced))
+    }
+
+    /// Gets the launcher the player has ready and the ammunition it shoots.
+    pub fn player_launcher(&self) -> Option<(&Item, AmmoType)> {
+        let launcher_id = self.get_player()?.get_equipped_item("ranged")?;
+        let launcher = self.carried_items.get(launcher_id)?;
+        match &launcher.item_type {
+            ItemType::Weapon(weapon) => Some((launcher, weapon.ammo()?)),
+            _ => None,
+        }
+    }
+
+    /// Gets how many shots the player's quiver holds for their launcher.
+    pub fn shots_ready(&self) -> u32 {
+        let Some((_, ammo)) = self.player_launcher() else {
+            return 0;
+        };
+        self.get_player()
+            .and_then(|player| player.quiver.as_ref())
+            .filter(|stack| stack.item.item_type == ItemType::Ammo(ammo))
+            .map_or(0, |stack| stack.count)
+    }
+
+    /// Takes an item out of the player's pack, leaving it in nobody's hands.
+    ///
+    /// Worn items must be taken off first.
+    pub fn take_item_from_player(&mut self, item_id: EntityId) -> ThatchResult<Item> {
+        let player = self
+            .get_player_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        if !player.remove_from_inventory(&item_id) {
+            return Err(ThatchError::InvalidAction(
+                "That item is not in your pack".to_string(),
+            ));
+        }
+        self.carried_items.remove(&item_id).ok_or_else(|| {
+            ThatchError::InvalidState(format!("Carried item {} is missing", item_id))
+        })
+    }
+
+    /// Puts an item on the floor of the current level at the item's position.
+    pub fn drop_item_on_ground(&mut self, item: Item) -> ThatchResult<()> {
+        let level = self
+            .world
+            .current_level_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        level.ground_items.drop_item(item);
+        Ok(())
+    }
+
+    /// Checks if the player is carrying the Amulet, in the pack or worn.
+    pub fn player_has_artifact(&self) -> bool {
+        self.get_player().is_some_and(|player| {
+            player
+                .inventory
+                .iter()
+                .chain(player.equipment.values())
+                .any(|id| self.carried_item_name(*id) == Some(ARTIFACT_NAME))
+        })
+    }
+
+    /// Gets the kind of light the player holds in their light slot, if any.
+    pub fn player_light(&self) -> Option<LightKind> {
+        let item = self
+            .get_player()?
+            .get_equipped_item("light")
+            .and_then(|id| self.carried_items.get(id))?;
+        match item.item_type {
+            ItemType::Light(kind) => Some(kind),
+            _ => None,
+        }
+    }
+
+    /// Checks if the player can stay in water indefinitely without drowning,
+    /// by wearing a [`WATER_BREATHING_RING`] or through their metadata.
+    pub fn player_can_breathe_water(&self) -> bool {
+        self.get_player().is_some_and(|player| {
+            player.can_breathe_water()
+                || player
+                    .equipment
+                    .values()
+                    .any(|id| self.carried_item_name(*id) == Some(WATER_BREATHING_RING))
+        })
+    }
+
+    /// Washes the ink off every written scroll in the player's pack, returning
+    /// how many were ruined.
+    fn soak_scrolls(&mut self) -> usize {
+        let Some(pack) = self.get_player().map(|player| player.inventory.clone()) else {
+            return 0;
+        };
+        let mut ruined = 0;
+        for id in pack {
+            let Some(item) = self.carried_items.get_mut(&id) else {
+                continue;
+            };
+            if item.item_type == ItemType::Consumable(ConsumableType::Scroll)
+                && item.name != BLANK_SCROLL
+            {
+                item.name = BLANK_SCROLL.to_string();
+                ruined += 1;
+            }
+        }
+        ruined
+    }
+
+    /// Gets the name the player knows an item by, with any charges it has left.
+    ///
+    /// Curses and enchantments only show once the item has been appraised.
+    pub fn item_display_name(&self, item: &Item) -> String {
+        let mut name = self.identification.display_name(item);
+        if item.appraised {
+            if item.enchantment != 0 {
+                name = format!("{} ({:+})", name, item.enchantment);
+            }
+            if item.cursed {
+                name = format!("cursed {}", name);
+            }
+        }
+        match item.charges {
+            Some(charges) => format!("{} ({} charges)", name, charges),
+            None => name,
+        }
+    }
+
+    /// Describes an item for the inventory screen, telling only what the
+    /// player knows about it, like [`Self::item_display_name`].
+    pub fn describe_item(&self, item: &Item) -> String {
+        let kind = match &item.item_type {
+            ItemType::Weapon(weapon) if weapon.ammo().is_some() => {
+                "A launcher, shooting from the quiver.".to_string()
+            }
+            ItemType::Weapon(_) => "A weapon, wielded in melee or thrown.".to_string(),
+            ItemType::Armor(_) => format!(
+                "Armor, worn in the {} slot.",
+                item.item_type.equip_slot().unwrap_or("body")
+            ),
+            ItemType::Consumable(crate::ConsumableType::Food) => {
+                "Food, eaten to stave off hunger.".to_string()
+            }
+            ItemType::Consumable(crate::ConsumableType::Wand) => {
+                "A wand, spent once its charges run out.".to_string()
+            }
+            ItemType::Consumable(_) => "Used up when used.".to_string(),
+            ItemType::Ammo(_) => "Ammunition, shot from a launcher.".to_string(),
+            ItemType::QuestItem => "An item of some importance.".to_string(),
+            ItemType::Treasure => "Treasure, valuable but of no other use.".to_string(),
+            ItemType::Corpse(monster) => {
+                format!("The body of a {}.", monster.display_name().to_lowercase())
+            }
+            ItemType::Light(kind) => format!(
+                "A {}, lighting the way when held.",
+                kind.display_name().to_lowercase()
+            ),
+            ItemType::Custom(kind) => format!("A {}.", kind.to_lowercase()),
+        };
+
+        let mut description = format!("{}: {}", self.item_display_name(item), kind);
+        if let Some(lore) = item.metadata.get(crate::UNIQUE_LORE_KEY) {
+            description = format!("{} {}", description, lore);
+        }
+        if !self.identification.is_identified(&item.name) {
+            description.push_str(" You don't know what it does yet.");
+        } else if item.item_type.equip_slot().is_some() && !item.appraised {
+            description.push_str(" You don't know whether it is cursed.");
+        }
+        description
+    }
+
+    /// Gets the name of a carried item, if the player has it.
+    pub fn carried_item_name(&self, item_id: EntityId) -> Option<&str> {
+        self.carried_items
+            .get(&item_id)
+            .map(|item| item.name.as_str())
+    }
+
+    /// Gets the player character if it exists.
+    pub fn get_player(&self) -> Option<&PlayerCharacter> {
+        self.player_id
+            .and_then(|player_id| self.entities.get(&player_id))
+            .and_then(ConcreteEntity::as_player)
+    }
+
+    /// Gets the player character mutably if it exists.
+    pub fn get_player_mut(&mut self) -> Option<&mut PlayerCharacter> {
+        self.player_id
+            .and_then(|player_id| self.entities.get_mut(&player_id))
+            .and_then(ConcreteEntity::as_player_mut)
     }
 
     /// Creates a new game state with a specific level.
@@ -353,6 +1013,8 @@ impl GameState {
         Ok(Self {
             world,
             entities: HashMap::new(),
+            carried_items: HashMap::new(),
+            identification: IdentificationTable::new(seed),
             position_index: HashMap::new(),
             player_id: None,
             action_queue: ActionQueue::new(),
@@ -362,6 +1024,14 @@ impl GameState {
             config_flags: HashMap::new(),
             statistics: GameStatistics::new(),
             rng_seed: seed,
+            rng: RngService::new(seed),
+            generator: GeneratorKind::default(),
+            lazy_floors: false,
+            lazy_generation: None,
+            templates: TemplateLibrary::default(),
+            quests: QuestLog::new(),
+            achievements: AchievementTracker::new(),
+            tutorial: None,
             lldm_state: LldmState {
                 enabled: false,
                 session_id: None,
@@ -373,10 +1043,21 @@ impl GameState {
                     temperature: 0.7,
                     max_tokens: 1000,
                     use_cache: true,
+                    requests: LldmRequestPolicy::default(),
                 },
+                memory: LldmMemory::new(),
+                telemetry: LldmTelemetry::default(),
+                gate: LldmRequestGate::default(),
             },
             completion_state: GameCompletionState::Playing,
+            recent_messages: VecDeque::new(),
             autoexplore_state: AutoexploreState::new(),
+            travel_state: TravelState::new(),
+            rest_state: RestState::new(),
+            repeat_state: RepeatState::new(),
+            fight_state: FightState::new(),
+            door_opening: DoorOpening::default(),
+            field_of_view: None,
         })
     }
 
@@ -466,8 +1147,8 @@ impl GameState {
 
         // Update entity position
         match self.entities.get_mut(&entity_id) {
-            Some(ConcreteEntity::Player(player)) => {
-                player.set_position(new_position);
+            Some(entity) => {
+                entity.as_entity_mut().set_position(new_position);
             }
             None => {
                 return Err(ThatchError::InvalidState(format!(
@@ -483,82 +1164,497 @@ impl GameState {
         Ok(())
     }
 
-    /// Gets an entity at a specific position.
-    pub fn get_entity_at_position(&self, position: Position) -> Option<EntityId> {
-        self.position_index
-            .get(&position)
-            .and_then(|entities| entities.first().copied())
-    }
-
-    /// Gets all entities at a specific position.
-    pub fn get_entities_at_position(&self, position: Position) -> Vec<EntityId> {
-        self.position_index
-            .get(&position)
-            .cloned()
-            .unwrap_or_default()
-    }
-
-    /// Gets entity stats (if applicable).
-    pub fn get_entity_stats(&self, entity_id: EntityId) -> Option<&EntityStats> {
-        match self.entities.get(&entity_id) {
-            Some(ConcreteEntity::Player(player)) => Some(&player.stats),
+    /// Runs the effect of the special tile or hazard an entity just stepped onto.
+    ///
+    /// Fountains, plaques and altars only respond to the player; traps,
+    /// teleporters, lava and chasms catch anyone. A teleport moves the entity
+    /// immediately and is reported with an [`GameEvent::EntityMoved`], and a
+    /// chasm drops it to the floor below at once. Returns the events the
+    /// effect caused, which is empty if the tile has no effect.
+    pub fn trigger_tile_effect(
+        &mut self,
+        entity_id: EntityId,
+        position: Position,
+    ) -> ThatchResult<Vec<GameEvent>> {
+        let tile_type = self
+            .world
+            .current_level()
+            .and_then(|level| level.get_tile(position))
+            .map(|tile| tile.tile_type.clone());
+        let effect = match tile_type {
+            Some(TileType::Lava) => return Ok(self.burn_in_lava(entity_id)),
+            Some(TileType::Chasm) => return self.fall_into_chasm(entity_id, position),
+            Some(tile_type) => tile_type.effect().cloned(),
             None => None,
-        }
-    }
-
-    /// Processes a game event and updates state accordingly.
-    pub fn process_event(&mut self, event: &GameEvent) -> ThatchResult<Vec<GameEvent>> {
-        let mut response_events = Vec::new();
-
-        // Update statistics
-        self.statistics.update_from_event(event);
+        };
+        let Some(effect) = effect else {
+            return Ok(Vec::new());
+        };
 
-        // Handle event-specific processing
-        match event {
-            GameEvent::EntityMoved {
-                entity_id,
-                from: _,
-                to,
-            } => {
-                // Position index is already updated by set_entity_position
-                // Update visibility if this is the player
-                if Some(*entity_id) == self.player_id {
-                    self.update_player_visibility(*to)?;
+        let is_player = Some(entity_id) == self.player_id;
+        let message = |text: &str, importance| GameEvent::Message {
+            text: text.to_string(),
+            importance,
+            channel: crate::MessageChannel::Exploration,
+        };
+        let mut events = Vec::new();
+        match effect {
+            TileEffect::HealFountain { amount } if is_player => {
+                events.push(message(
+                    "You drink from the fountain and feel refreshed.",
+                    crate::MessageImportance::Normal,
+                ));
+                events.push(GameEvent::EntityHealed {
+                    entity_id,
+                    amount,
+                    source: None,
+                });
+                if let Some(tile) = self
+                    .world
+                    .current_level_mut()
+                    .and_then(|level| level.get_tile_mut(position))
+                {
+                    tile.tile_type = TileEffect::DryFountain.into_tile().tile_type;
                 }
             }
-
-            GameEvent::EntityDamaged {
-                entity_id,
-                damage: _,
-                source: _,
-            } => {
-                // Forward to the entity for handling
-                if let Some(entity) = self.entities.get_mut(entity_id) {
-                    match entity {
-                        ConcreteEntity::Player(player) => {
-                            let events = player.handle_event(event)?;
-                            response_events.extend(events);
-                        }
+            TileEffect::Teleporter { destination } => {
+                let clear = self
+                    .world
+                    .current_level()
+                    .and_then(|level| level.portal_exit(position))
+                    .is_some()
+                    && self.get_entity_at_position(destination).is_none();
+                if clear {
+                    self.set_entity_position(entity_id, destination)?;
+                    if is_player {
+                        events.push(message(
+                            "The runes flare and the world lurches around you!",
+                            crate::MessageImportance::Important,
+                        ));
                     }
+                    events.push(GameEvent::EntityMoved {
+                        entity_id,
+                        from: position,
+                        to: destination,
+                    });
+                } else if is_player {
+                    events.push(message(
+                        "The runes flicker, but nothing happens.",
+                        crate::MessageImportance::Info,
+                    ));
                 }
             }
-
-            GameEvent::EntityDied { entity_id, .. } => {
-                #[cfg(feature = "dev-tools")]
-                tracing::info!("Entity {} died", entity_id);
-                #[cfg(not(feature = "dev-tools"))]
-                println!("Entity {} died", entity_id);
-                
-                // Remove entity from world
-                if let Some(position) = self.get_entity_position(*entity_id) {
-                    self.remove_entity_from_position_index(*entity_id, position);
+            TileEffect::Trap { damage, found } => {
+                if is_player {
+                    events.push(message(
+                        "A dart shoots out of the floor!",
+                        crate::MessageImportance::Important,
+                    ));
                 }
-
-                // Remove from current level
-                if let Some(level) = self.world.current_level_mut() {
-                    level.remove_entity(entity_id);
+                // Setting a trap off gives it away
+                if !found {
+                    if let Some(tile) = self
+                        .world
+                        .current_level_mut()
+                        .and_then(|level| level.get_tile_mut(position))
+                    {
+                        tile.tile_type = TileEffect::Trap {
+                            damage,
+                            found: true,
+                        }
+                        .into_tile()
+                        .tile_type;
+                    }
                 }
-
+                events.push(GameEvent::EntityDamaged {
+                    entity_id,
+                    damage,
+                    source: None,
+                });
+            }
+            TileEffect::Plaque { text } if is_player => {
+                events.push(message(
+                    &format!("The plaque reads: \"{}\"", text),
+                    crate::MessageImportance::Normal,
+                ));
+            }
+            TileEffect::Altar if is_player => {
+                let deity = self.floor_deity();
+                events.push(message(
+                    &format!(
+                        "You stand before the altar of {}. (P to pray)",
+                        deity.title()
+                    ),
+                    crate::MessageImportance::Normal,
+                ));
+            }
+            TileEffect::HealFountain { .. }
+            | TileEffect::Plaque { .. }
+            | TileEffect::Altar
+            | TileEffect::DryFountain => {}
+        }
+        Ok(events)
+    }
+
+    /// Sets an entity that stepped onto lava on fire and burns it for
+    /// [`LAVA_DAMAGE`](crate::LAVA_DAMAGE).
+    ///
+    /// Being soaked keeps the flames off, but not the heat.
+    fn burn_in_lava(&mut self, entity_id: EntityId) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if Some(entity_id) == self.player_id {
+            events.push(GameEvent::Message {
+                text: "The lava sears you!".to_string(),
+                importance: crate::MessageImportance::Critical,
+                channel: crate::MessageChannel::Exploration,
+            });
+        }
+        if let Some(statuses) = self
+            .entities
+            .get_mut(&entity_id)
+            .and_then(|entity| entity.as_entity_mut().statuses_mut())
+        {
+            statuses.apply(StatusKind::Burning, crate::LAVA_BURNING_DURATION);
+        }
+        events.push(GameEvent::EntityDamaged {
+            entity_id,
+            damage: crate::LAVA_DAMAGE,
+            source: None,
+        });
+        events
+    }
+
+    /// Drops an entity that stepped into the chasm at `position` to the floor
+    /// below.
+    ///
+    /// The player arrives on the floor as if by the stairs, but on the
+    /// [`landing_spot`](crate::landing_spot) under the chasm, and takes
+    /// [`FALL_DAMAGE`](crate::FALL_DAMAGE). A monster is carried off to the
+    /// floor below if it has been generated, and is lost otherwise. Nothing
+    /// falls from the deepest floor, which has nothing under it.
+    fn fall_into_chasm(
+        &mut self,
+        entity_id: EntityId,
+        position: Position,
+    ) -> ThatchResult<Vec<GameEvent>> {
+        let old_level = self.world.current_level_id;
+        if old_level >= self.world.shape.deepest_floor() {
+            return Ok(Vec::new());
+        }
+        let new_level = old_level + 1;
+        let message = |text: String, importance| GameEvent::Message {
+            text,
+            importance,
+            channel: crate::MessageChannel::Exploration,
+        };
+
+        if Some(entity_id) != self.player_id {
+            let seen = self
+                .world
+                .current_level()
+                .and_then(|level| level.get_tile(position))
+                .is_some_and(|tile| tile.is_visible());
+            let name = self
+                .entities
+                .get(&entity_id)
+                .map(|entity| entity.name().to_string());
+            if let Some(level) = self.world.current_level_mut() {
+                level.remove_entity(&entity_id);
+            }
+            self.remove_entity_from_position_index(entity_id, position);
+            let spot = self.world.get_level(new_level).and_then(|level| {
+                crate::landing_spot(level, position, |spot| {
+                    self.get_entity_at_position(spot).is_some()
+                })
+            });
+            match spot {
+                Some(spot) => {
+                    if let Some(level) = self.world.levels.get_mut(&new_level) {
+                        level.add_entity(entity_id);
+                    }
+                    self.set_entity_position(entity_id, spot)?;
+                }
+                None => {
+                    self.entities.remove(&entity_id);
+                }
+            }
+            let text = format!("The {} falls into the chasm!", name.unwrap_or_default());
+            return Ok(if seen {
+                vec![message(text, crate::MessageImportance::Normal)]
+            } else {
+                Vec::new()
+            });
+        }
+
+        self.enter_level(new_level, Some(position))?;
+        Ok(vec![
+            message(
+                "You fall into the chasm and crash onto the floor below!".to_string(),
+                crate::MessageImportance::Critical,
+            ),
+            GameEvent::PlayerChangedLevel {
+                player_id: entity_id,
+                old_level,
+                new_level,
+                direction: crate::StairDirection::Down,
+            },
+            GameEvent::EntityDamaged {
+                entity_id,
+                damage: crate::FALL_DAMAGE,
+                source: None,
+            },
+        ])
+    }
+
+    /// Opens a pair of teleporters between an entity and a distant part of the
+    /// floor, and pulls the entity through.
+    ///
+    /// The near end opens on the floor tile the entity stands on and the far
+    /// end on a random unoccupied floor tile at least [`crate::PORTAL_MIN_DISTANCE`]
+    /// steps away. Nothing happens if either end has no room to open.
+    pub fn open_portal(&mut self, entity_id: EntityId) -> ThatchResult<Vec<GameEvent>> {
+        let origin = self
+            .get_entity_position(entity_id)
+            .ok_or_else(|| ThatchError::InvalidState("Entity not found".to_string()))?;
+        let level = self
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        let is_floor = |pos| {
+            level
+                .get_tile(pos)
+                .is_some_and(|tile| tile.tile_type == TileType::Floor)
+        };
+
+        let mut candidates: Vec<Position> = Vec::new();
+        if is_floor(origin) {
+            candidates = crate::PathfindingUtils::new()
+                .distance_map(level, origin)
+                .into_iter()
+                .filter(|&(pos, distance)| {
+                    distance >= crate::PORTAL_MIN_DISTANCE
+                        && is_floor(pos)
+                        && self.get_entity_at_position(pos).is_none()
+                })
+                .map(|(pos, _)| pos)
+                .collect();
+            // Sorted so the pick depends only on the seed, not on hash order
+            candidates.sort_by_key(|pos| (pos.y, pos.x));
+        }
+        if candidates.is_empty() {
+            return Ok(vec![GameEvent::Message {
+                text: "The air shimmers for a moment, then settles.".to_string(),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            }]);
+        }
+
+        let index = self.rng.gen_range(RngStream::Teleport, 0..candidates.len());
+        let destination = candidates[index];
+        let level = self
+            .world
+            .current_level_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        level.set_tile(origin, TileEffect::Teleporter { destination }.into_tile())?;
+        level.set_tile(
+            destination,
+            TileEffect::Teleporter {
+                destination: origin,
+            }
+            .into_tile(),
+        )?;
+        self.set_entity_position(entity_id, destination)?;
+
+        Ok(vec![
+            GameEvent::Message {
+                text: "A shimmering portal tears open and pulls you through!".to_string(),
+                importance: crate::MessageImportance::Important,
+                channel: crate::MessageChannel::Exploration,
+            },
+            GameEvent::EntityMoved {
+                entity_id,
+                from: origin,
+                to: destination,
+            },
+        ])
+    }
+
+    /// Moves an entity to a random unoccupied floor tile on the current level.
+    ///
+    /// Unlike a portal this leaves nothing behind, and the spot need not be
+    /// reachable on foot.
+    pub fn teleport_entity(&mut self, entity_id: EntityId) -> ThatchResult<Vec<GameEvent>> {
+        let origin = self
+            .get_entity_position(entity_id)
+            .ok_or_else(|| ThatchError::InvalidState("Entity not found".to_string()))?;
+        let level = self
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        // Scanned row by row so the pick depends only on the seed
+        let spots: Vec<Position> = (0..level.height as i32)
+            .flat_map(|y| (0..level.width as i32).map(move |x| Position::new(x, y)))
+            .filter(|&pos| {
+                pos != origin
+                    && level
+                        .get_tile(pos)
+                        .is_some_and(|tile| tile.tile_type == TileType::Floor)
+                    && self.get_entity_at_position(pos).is_none()
+            })
+            .collect();
+        if spots.is_empty() {
+            return Ok(vec![GameEvent::Message {
+                text: "You feel a wrenching sensation, but stay where you are.".to_string(),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            }]);
+        }
+
+        let destination = spots[self.rng.gen_range(RngStream::Teleport, 0..spots.len())];
+        self.set_entity_position(entity_id, destination)?;
+        Ok(vec![
+            GameEvent::Message {
+                text: "The world blurs around you!".to_string(),
+                importance: crate::MessageImportance::Important,
+                channel: crate::MessageChannel::Exploration,
+            },
+            GameEvent::EntityMoved {
+                entity_id,
+                from: origin,
+                to: destination,
+            },
+        ])
+    }
+
+    /// Gets an entity at a specific position.
+    pub fn get_entity_at_position(&self, position: Position) -> Option<EntityId> {
+        self.position_index
+            .get(&position)
+            .and_then(|entities| entities.first().copied())
+    }
+
+    /// Gets all entities at a specific position.
+    pub fn get_entities_at_position(&self, position: Position) -> Vec<EntityId> {
+        self.position_index
+            .get(&position)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Gets entity stats (if applicable).
+    pub fn get_entity_stats(&self, entity_id: EntityId) -> Option<&EntityStats> {
+        self.entities
+            .get(&entity_id)
+            .and_then(ConcreteEntity::stats)
+    }
+
+    /// Processes a game event and updates state accordingly.
+    pub fn process_event(&mut self, event: &GameEvent) -> ThatchResult<Vec<GameEvent>> {
+        let mut response_events = Vec::new();
+
+        // Update statistics
+        self.statistics.update_from_event(event);
+        if let GameEvent::EntityDamaged {
+            entity_id, damage, ..
+        } = event
+        {
+            if Some(*entity_id) == self.player_id {
+                self.statistics.damage_taken += *damage as u64;
+            }
+        }
+
+        // Count the event towards any quests it advances
+        response_events.extend(self.advance_quests(event)?);
+        response_events.extend(self.advance_tutorial(event));
+
+        // Check for achievements the event and updated statistics earned
+        let earned = self
+            .achievements
+            .observe(event, &self.statistics, self.player_id);
+        response_events.extend(
+            earned
+                .into_iter()
+                .map(|achievement| GameEvent::AchievementUnlocked { achievement }),
+        );
+
+        // Remember messages for LLM snapshots
+        if let GameEvent::Message { text, .. } = event {
+            if self.recent_messages.len() == crate::config::RECENT_MESSAGE_LIMIT {
+                self.recent_messages.pop_front();
+            }
+            self.recent_messages.push_back(RecentMessage {
+                turn: self.turn_number,
+                text: text.clone(),
+            });
+        }
+
+        // Handle event-specific processing
+        match event {
+            // Position index is already updated by set_entity_position, so
+            // only the player's moves need more
+            GameEvent::EntityMoved { entity_id, to, .. } if Some(*entity_id) == self.player_id => {
+                self.update_player_visibility(*to)?;
+                self.remember_room_at(*to);
+
+                // Mention anything lying where the player stepped
+                let seen = self.world.current_level().and_then(|level| {
+                    level
+                        .ground_items
+                        .describe_at(*to, |item| self.item_display_name(item))
+                });
+                let chest = self
+                    .world
+                    .current_level()
+                    .and_then(|level| level.container_at(*to))
+                    .map(|container| format!("There is a {} here.", container.name));
+                for text in chest.into_iter().chain(seen) {
+                    response_events.push(GameEvent::Message {
+                        text,
+                        importance: crate::MessageImportance::Normal,
+                        channel: crate::MessageChannel::Exploration,
+                    });
+                }
+            }
+
+            GameEvent::EntityDamaged { entity_id, .. }
+            | GameEvent::EntityHealed { entity_id, .. }
+            | GameEvent::StatusApplied { entity_id, .. } => {
+                // Forward to the entity for handling
+                if let Some(entity) = self.entities.get_mut(entity_id) {
+                    let events = entity.as_entity_mut().handle_event(event)?;
+                    response_events.extend(events);
+                }
+                self.leave_overlay(event);
+            }
+
+            GameEvent::EntityDied { entity_id, killer } => {
+                #[cfg(feature = "dev-tools")]
+                tracing::info!("Entity {} died", entity_id);
+                #[cfg(not(feature = "dev-tools"))]
+                println!("Entity {} died", entity_id);
+
+                // Record player kills for the morgue file
+                if self.credits_player(*killer) {
+                    if let Some(entity) = self.entities.get(entity_id) {
+                        let name = entity.name().to_string();
+                        self.statistics.kill_list.push(name.clone());
+                        let text = format!("Slew a {}.", name);
+                        self.remember(MemoryKind::Kill, name, text);
+                    }
+                }
+
+                response_events.extend(self.drop_remains(*entity_id));
+
+                // Remove entity from world
+                if let Some(position) = self.get_entity_position(*entity_id) {
+                    self.remove_entity_from_position_index(*entity_id, position);
+                }
+
+                // Remove from current level
+                if let Some(level) = self.world.current_level_mut() {
+                    level.remove_entity(entity_id);
+                }
+
                 // If this is the player, handle game over
                 if Some(*entity_id) == self.player_id {
                     #[cfg(feature = "dev-tools")]
@@ -567,27 +1663,129 @@ impl GameState {
                     println!("PLAYER DIED! Setting completion state to PlayerDied");
                     self.statistics.deaths += 1;
                     self.completion_state = GameCompletionState::PlayerDied;
+                    self.request_epitaph(*killer);
                     response_events.push(GameEvent::Message {
                         text: "Game Over! Press any key to continue...".to_string(),
                         importance: crate::MessageImportance::Critical,
+                        channel: crate::MessageChannel::System,
                     });
                 }
             }
 
+            GameEvent::ItemPickedUp { item_id, picker_id }
+                if Some(*picker_id) == self.player_id
+                    && self.carried_item_name(*item_id) == Some(ARTIFACT_NAME)
+                    && self.statistics.artifact_found_turn.is_none() =>
+            {
+                self.statistics.artifact_found_turn = Some(self.turn_number);
+                response_events.push(GameEvent::Message {
+                    text: format!(
+                        "You hold the {}! Now carry it back up to the surface.",
+                        ARTIFACT_NAME
+                    ),
+                    importance: crate::MessageImportance::Critical,
+                    channel: crate::MessageChannel::Narrative,
+                });
+            }
+
+            GameEvent::AchievementUnlocked { achievement } => {
+                response_events.push(GameEvent::Message {
+                    text: format!(
+                        "Achievement unlocked: {}! ({})",
+                        achievement.display_name(),
+                        achievement.description()
+                    ),
+                    importance: crate::MessageImportance::Important,
+                    channel: crate::MessageChannel::System,
+                });
+            }
+
+            GameEvent::NoiseMade {
+                source,
+                position,
+                volume,
+            } => {
+                self.alert_monsters(*source, *position, *volume);
+            }
+
+            GameEvent::PlayerChangedLevel {
+                old_level,
+                new_level,
+                ..
+            } => {
+                response_events.extend(self.place_npcs());
+                self.name_unique_items();
+                response_events.extend(self.announce_level_feelings());
+                response_events.extend(self.place_waiting_monsters());
+                if new_level > old_level {
+                    response_events.extend(self.offer_floor_quest());
+                }
+            }
+
+            GameEvent::SecretFound {
+                finder_id,
+                position,
+            } if Some(*finder_id) == self.player_id => {
+                let trap = self
+                    .world
+                    .current_level()
+                    .and_then(|level| level.get_tile(*position))
+                    .is_some_and(|tile| {
+                        matches!(tile.tile_type.effect(), Some(TileEffect::Trap { .. }))
+                    });
+                let text = if trap {
+                    "You find a trap!"
+                } else {
+                    "You discover a secret door!"
+                };
+                response_events.push(GameEvent::Message {
+                    text: text.to_string(),
+                    importance: crate::MessageImportance::Important,
+                    channel: crate::MessageChannel::Exploration,
+                });
+            }
+
             _ => {}
         }
 
+        // Critical messages interrupt any travel, autoexplore, or rest in progress
+        let critical_message = std::iter::once(event)
+            .chain(response_events.iter())
+            .any(|e| {
+                matches!(
+                    e,
+                    GameEvent::Message {
+                        importance: crate::MessageImportance::Critical,
+                        ..
+                    }
+                )
+            });
+        if critical_message {
+            self.travel_state.cancel();
+            self.repeat_state.cancel();
+            self.autoexplore_state.watch.note_critical_message();
+            self.rest_state.watch.note_critical_message();
+            self.fight_state.watch.note_critical_message();
+        }
+
         Ok(response_events)
     }
 
     /// Updates player's field of view and tile visibility.
     /// This preserves exploration state while updating current visibility.
+    ///
+    /// Only tiles in the previous and new [`FieldOfView`] are touched, and
+    /// nothing is done when the view has not changed since the last call.
     pub fn update_player_visibility(&mut self, player_position: Position) -> ThatchResult<()> {
+        crate::profile_scope!("update_player_visibility");
         let player = self
             .get_player()
             .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
 
         let sight_radius = player.sight_radius as i32;
+        let carried_light = self
+            .player_light()
+            .map(|kind| crate::LightSource::new(kind, player_position));
 
         // Simple visibility algorithm (can be improved with line-of-sight)
         let level = self
@@ -595,27 +1793,54 @@ impl GameState {
             .current_level_mut()
             .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
 
-        // Reset all tiles to not visible (but preserve exploration state)
-        for row in &mut level.tiles {
-            for tile in row {
-                tile.visible = false; // Don't use set_visible as it would mark as explored
-            }
+        let view = FieldOfView {
+            level_id: level.id,
+            center: player_position,
+            radius: sight_radius,
+            carried_light,
+            ambient_light: level.ambient_light,
+            light_sources: level.light_sources.clone(),
+        };
+        let previous = self.field_of_view.take();
+        if previous.as_ref() == Some(&view) {
+            // Nothing the view depends on has changed, so neither has the view
+            self.field_of_view = previous;
+            return Ok(());
         }
 
-        // Set visible tiles within sight radius
-        for dy in -sight_radius..=sight_radius {
-            for dx in -sight_radius..=sight_radius {
-                let pos = Position::new(player_position.x + dx, player_position.y + dy);
-
-                // Check if position is within sight radius (circular)
-                if player_position.euclidean_distance(pos) <= sight_radius as f64 {
+        // Reset the tiles that were in view (but preserve exploration state)
+        match previous.filter(|previous| previous.level_id == view.level_id) {
+            Some(previous) => {
+                for pos in previous.area() {
                     if let Some(tile) = level.get_tile_mut(pos) {
-                        tile.set_visible(true); // This marks as explored and visible
+                        tile.visible = false; // Don't use set_visible as it would mark as explored
+                    }
+                }
+            }
+            None => {
+                for row in &mut level.tiles {
+                    for tile in row {
+                        tile.visible = false;
                     }
                 }
             }
         }
 
+        // Set visible tiles within sight radius that are bright enough to see
+        for pos in view.area() {
+            let light = level.light_level_at(pos, carried_light);
+            if let Some(tile) = level.get_tile_mut(pos) {
+                tile.light = light;
+                if light >= crate::MIN_VISIBLE_LIGHT || pos == player_position {
+                    tile.set_visible(true); // This marks as explored and visible
+                }
+            }
+        }
+
+        let level_id = level.id;
+        let exploration = LevelExploration::survey(level, player_position);
+        self.statistics.record_exploration(level_id, exploration);
+        self.field_of_view = Some(view);
         Ok(())
     }
 
@@ -631,86 +1856,836 @@ impl GameState {
         // Process any pending LLDM requests
         self.process_lldm_requests()?;
 
-        // Additional turn processing can be added here
-        Ok(vec![])
-    }
-
-    /// Gets current game time information.
-    pub fn get_game_time_info(&self) -> GameTimeInfo {
-        let elapsed = self
-            .game_start_time
-            .map(|start| start.elapsed())
-            .unwrap_or(Duration::ZERO);
-
-        GameTimeInfo {
-            turn_number: self.turn_number,
-            elapsed_time: elapsed,
-            total_play_time: Duration::from_secs(self.total_play_time),
+        // Apply terrain and status effects to the player
+        let mut events = self.apply_player_environment();
+        events.extend(self.apply_monster_statuses());
+        events.extend(self.move_allies());
+        events.extend(self.move_monsters());
+        events.extend(self.spawn_wanderer());
+
+        // Magic slowly comes back
+        if let Some(player) = self.get_player_mut() {
+            player
+                .stats
+                .restore_mana(crate::config::MANA_REGEN_PER_TURN);
         }
+
+        Ok(events)
     }
 
-    /// Gets configuration flag value.
-    pub fn get_config_flag(&self, flag: &str) -> bool {
-        self.config_flags.get(flag).copied().unwrap_or(false)
+    /// Returns true if the player is confused, which also stops travel,
+    /// walking repeats and autoexplore.
+    pub fn player_is_confused(&self) -> bool {
+        self.get_player()
+            .is_some_and(|player| player.statuses.has(StatusKind::Confused))
     }
 
-    /// Sets configuration flag value.
-    pub fn set_config_flag(&mut self, flag: String, value: bool) {
-        self.config_flags.insert(flag, value);
+    /// Rolls whether a confused entity stumbles off course, returning the
+    /// direction it lurches in if it does.
+    pub fn stumble_direction(&mut self, entity_id: EntityId) -> Option<Direction> {
+        let confused = self
+            .entities
+            .get(&entity_id)
+            .and_then(|entity| entity.as_entity().statuses())
+            .is_some_and(|statuses| statuses.has(StatusKind::Confused));
+        if !confused
+            || !self
+                .rng
+                .gen_bool(RngStream::Ai, crate::CONFUSION_STUMBLE_CHANCE)
+        {
+            return None;
+        }
+        let directions = Direction::all();
+        Some(directions[self.rng.gen_range(RngStream::Ai, 0..directions.len())])
     }
 
-    /// Adds entity to position index.
-    fn add_entity_to_position_index(&mut self, entity_id: EntityId, position: Position) {
-        self.position_index
-            .entry(position)
-            .or_default()
-            .push(entity_id);
+    /// Gets the action a confused actor ends up taking instead of `action`.
+    ///
+    /// Returns None unless the action is a move and the actor stumbles. A
+    /// stumble towards a wall or another entity costs the turn in place.
+    pub fn stumble(&mut self, action: &ConcreteAction) -> Option<ConcreteAction> {
+        let ConcreteAction::Move(step) = action else {
+            return None;
+        };
+        let direction = self.stumble_direction(step.actor)?;
+        let from = self.get_entity_position(step.actor)?;
+        let to = from + direction.to_delta();
+        let open = self
+            .world
+            .current_level()
+            .is_some_and(|level| level.is_passable(to))
+            && self.get_entity_at_position(to).is_none();
+        Some(if open {
+            ConcreteAction::Move(MoveAction::new(step.actor, direction))
+        } else {
+            ConcreteAction::Wait(crate::WaitAction::new(step.actor))
+        })
     }
 
-    /// Removes entity from position index.
-    fn remove_entity_from_position_index(&mut self, entity_id: EntityId, position: Position) {
-        if let Some(entities) = self.position_index.get_mut(&position) {
-            entities.retain(|&id| id != entity_id);
-            if entities.is_empty() {
-                self.position_index.remove(&position);
+    /// Applies a turn of status effects to the monsters on the current level.
+    ///
+    /// Burning monsters take [`BURNING_DAMAGE`](crate::BURNING_DAMAGE) each turn
+    /// until the flames die out.
+    fn apply_monster_statuses(&mut self) -> Vec<GameEvent> {
+        let Some(level) = self.world.current_level() else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        for entity_id in level.entities.clone() {
+            if Some(entity_id) == self.player_id {
+                continue;
+            }
+            let Some(statuses) = self
+                .entities
+                .get_mut(&entity_id)
+                .and_then(|entity| entity.as_entity_mut().statuses_mut())
+            else {
+                continue;
+            };
+            let burning = statuses.has(StatusKind::Burning);
+            statuses.tick();
+            if burning {
+                events.push(GameEvent::EntityDamaged {
+                    entity_id,
+                    damage: crate::BURNING_DAMAGE,
+                    source: None,
+                });
             }
         }
+        events
     }
 
-    /// Processes pending LLDM requests.
-    fn process_lldm_requests(&mut self) -> ThatchResult<()> {
-        if !self.lldm_state.enabled {
-            return Ok(());
+    /// Brings a wandering monster onto the current level when its spawn
+    /// scheduler says the player has lingered long enough.
+    ///
+    /// The wanderer arrives out of sight and sets off towards the player. If
+    /// there is nowhere out of sight for it, it tries again the next turn.
+    fn spawn_wanderer(&mut self) -> Vec<GameEvent> {
+        let Some(player_position) = self.get_player().map(|player| player.position()) else {
+            return Vec::new();
+        };
+        let Some(level) = self.world.current_level_mut() else {
+            return Vec::new();
+        };
+        if !level.spawn_scheduler.tick() {
+            return Vec::new();
         }
+        let mut rng = self.rng.stream_rng(RngStream::Spawn);
+        let Some(level) = self.world.current_level() else {
+            return Vec::new();
+        };
+        let spot = crate::wanderer_spot(level, player_position, &mut rng)
+            .filter(|&position| self.get_entity_at_position(position).is_none());
+        let template =
+            level
+                .spawn_scheduler
+                .pick_wanderer(&self.templates, level.theme.as_ref(), &mut rng);
+        let (Some(position), Some(template)) = (spot, template) else {
+            return Vec::new();
+        };
 
-        // In a full implementation, this would make actual API calls
-        // For now, we just clear processed requests
-        self.lldm_state.pending_requests.clear();
-
-        Ok(())
+        let mut monster = template.spawn(position);
+        monster.investigating = Some(player_position);
+        let species = monster.monster_type.clone();
+        let Ok(monster_id) = self.add_entity(monster.into()) else {
+            return Vec::new();
+        };
+        if let Some(level) = self.world.current_level_mut() {
+            level.spawn_scheduler.record_spawn();
+            level.add_entity(monster_id);
+        }
+        vec![
+            GameEvent::EntityCreated {
+                entity_id: monster_id,
+                entity_type: crate::EntityType::Monster(species),
+                position,
+            },
+            GameEvent::Message {
+                text: "You hear something moving in the dark.".to_string(),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Exploration,
+            },
+        ]
     }
 
-    /// Saves the game state to JSON.
-    pub fn save_to_json(&self) -> ThatchResult<String> {
-        serde_json::to_string_pretty(self).map_err(ThatchError::from)
-    }
+    /// Moves hostile monsters on the current level a step towards what they
+    /// are after.
+    ///
+    /// A monster that sees the player heads for them and remembers where they
+    /// were, so it keeps searching after losing sight of them; otherwise it
+    /// follows up the last noise it heard. It gives up once it gets there or
+    /// finds no way there, and waits while something blocks its path.
+    ///
+    /// Monsters heading for the player all walk downhill on one
+    /// [`DijkstraMap`](crate::DijkstraMap) built once per turn, so a crowd
+    /// costs little more than a single chaser and flows around its own
+    /// members. Anything else is reached with a path search of its own.
+    /// Confused monsters sometimes step the wrong way, as with
+    /// [`stumble_direction`](Self::stumble_direction).
+    fn move_monsters(&mut self) -> Vec<GameEvent> {
+        let Some(level) = self.world.current_level() else {
+            return Vec::new();
+        };
+        let player_position = self.get_player().map(|player| player.position());
+        let pathfinding = crate::PathfindingUtils::new();
+        let mut chase_map: Option<crate::DijkstraMap> = None;
+
+        let mut events = Vec::new();
+        for entity_id in level.entities.clone() {
+            let sees_player = self.monster_sees_player(entity_id);
+            let Some(monster) = self
+                .entities
+                .get_mut(&entity_id)
+                .and_then(ConcreteEntity::as_monster_mut)
+                .filter(|monster| monster.hostile)
+            else {
+                continue;
+            };
+            if sees_player {
+                monster.investigating = player_position;
+            }
+            let Some(target) = monster.investigating else {
+                continue;
+            };
+            let from = monster.position;
 
-    /// Loads game state from JSON.
-    pub fn load_from_json(json: &str) -> ThatchResult<Self> {
-        serde_json::from_str(json).map_err(ThatchError::from)
+            let step = if Some(target) == player_position {
+                let Some(level) = self.world.current_level() else {
+                    continue;
+                };
+                let map = chase_map.get_or_insert_with(|| crate::DijkstraMap::new(level, target));
+                // With no free way downhill the monster stays put, which reads
+                // as a blocked step below
+                map.distance(from).map(|_| {
+                    map.downhill(from, |next| self.get_entity_at_position(next).is_some())
+                        .unwrap_or(from)
+                })
+            } else {
+                self.world
+                    .current_level()
+                    .and_then(|level| pathfinding.find_path(level, from, target))
+                    .and_then(|path| path.first().copied())
+            };
+            // Confused monsters lurch about, bumping into whatever is there
+            let step = match (step, self.stumble_direction(entity_id)) {
+                (Some(step), Some(direction)) if step != from => {
+                    let lurch = from + direction.to_delta();
+                    let open = self
+                        .world
+                        .current_level()
+                        .is_some_and(|level| level.is_passable(lurch));
+                    Some(if open { lurch } else { from })
+                }
+                (step, _) => step,
+            };
+            match step {
+                Some(step) if self.get_entity_at_position(step).is_none() => {
+                    if self.set_entity_position(entity_id, step).is_ok() {
+                        events.push(GameEvent::EntityMoved {
+                            entity_id,
+                            from,
+                            to: step,
+                        });
+                        let sprung = self.trigger_tile_effect(entity_id, step);
+                        events.extend(sprung.unwrap_or_default());
+                    }
+                    if self.get_entity_position(entity_id) == Some(target) {
+                        self.stop_investigating(entity_id);
+                    }
+                }
+                Some(_) => {}
+                None => self.stop_investigating(entity_id),
+            }
+        }
+        events
     }
 
-    /// Handles level progression when player uses stairs.
+    /// Lets the player's allies on the current level take their turns.
     ///
-    /// Returns true if the level change was successful, false if it triggers a game ending.
-    pub fn use_stairs(&mut self, direction: crate::StairDirection) -> ThatchResult<bool> {
+    /// An ally sent to attack closes in on its target and strikes it, going
+    /// back to following once the target is dead or gone. Other allies strike
+    /// a hostile next to them, and following allies otherwise step towards a
+    /// player more than [`ALLY_FOLLOW_DISTANCE`](crate::ALLY_FOLLOW_DISTANCE)
+    /// tiles away. Each hostile is struck by at most one ally a turn.
+    fn move_allies(&mut self) -> Vec<GameEvent> {
+        let Some(level) = self.world.current_level() else {
+            return Vec::new();
+        };
+        let on_level = level.entities.clone();
+        let player_position = self.get_player().map(|player| player.position());
+        let pathfinding = crate::PathfindingUtils::new();
+
+        let mut struck = HashSet::new();
+        let mut events = Vec::new();
+        for &entity_id in &on_level {
+            let Some(command) = self
+                .entities
+                .get(&entity_id)
+                .and_then(ConcreteEntity::as_monster)
+                .filter(|monster| monster.is_alive())
+                .and_then(|monster| monster.ally)
+            else {
+                continue;
+            };
+            let command = match command {
+                crate::AllyCommand::Attack(target)
+                    if !on_level.contains(&target) || !self.is_entity_alive(target) =>
+                {
+                    crate::AllyCommand::Follow
+                }
+                command => command,
+            };
+            if let Some(monster) = self
+                .entities
+                .get_mut(&entity_id)
+                .and_then(ConcreteEntity::as_monster_mut)
+            {
+                monster.ally = Some(command);
+            }
+            let Some(from) = self.get_entity_position(entity_id) else {
+                continue;
+            };
+
+            let foe = match command {
+                crate::AllyCommand::Attack(target) => Some(target),
+                _ => from
+                    .adjacent_positions()
+                    .into_iter()
+                    .flat_map(|position| self.get_entities_at_position(position))
+                    .find(|&id| {
+                        on_level.contains(&id)
+                            && self.is_entity_alive(id)
+                            && self
+                                .entities
+                                .get(&id)
+                                .is_some_and(|e| e.as_entity().is_hostile())
+                    }),
+            };
+            let adjacent = |id| {
+                self.get_entity_position(id)
+                    .is_some_and(|position| position.manhattan_distance(from) == 1)
+            };
+            if let Some(foe) = foe.filter(|&foe| adjacent(foe) && !struck.contains(&foe)) {
+                let attack = crate::AttackAction::new(entity_id, foe);
+                if let Ok(hit) = crate::Action::execute(&attack, self) {
+                    struck.insert(foe);
+                    events.extend(hit);
+                }
+                continue;
+            }
+
+            let goal = match command {
+                crate::AllyCommand::Attack(target) => self.get_entity_position(target),
+                crate::AllyCommand::Follow => player_position.filter(|&position| {
+                    position.manhattan_distance(from) > crate::ALLY_FOLLOW_DISTANCE
+                }),
+                crate::AllyCommand::Stay => None,
+            };
+            let step = goal.and_then(|goal| {
+                let level = self.world.current_level()?;
+                pathfinding.find_path(level, from, goal)?.first().copied()
+            });
+            if let Some(step) = step.filter(|&step| self.get_entity_at_position(step).is_none()) {
+                if self.set_entity_position(entity_id, step).is_ok() {
+                    events.push(GameEvent::EntityMoved {
+                        entity_id,
+                        from,
+                        to: step,
+                    });
+                    let sprung = self.trigger_tile_effect(entity_id, step);
+                    events.extend(sprung.unwrap_or_default());
+                }
+            }
+        }
+        events
+    }
+
+    /// Sends monsters on the current level that hear a noise to investigate it.
+    ///
+    /// Monsters that can already see the player are chasing them instead.
+    fn alert_monsters(&mut self, source: EntityId, origin: Position, volume: u32) {
+        let Some(level) = self.world.current_level() else {
+            return;
+        };
+        let heard = crate::propagate_noise(level, origin, volume);
+
+        for entity_id in level.entities.clone() {
+            if entity_id == source || self.monster_sees_player(entity_id) {
+                continue;
+            }
+            if let Some(monster) = self
+                .entities
+                .get_mut(&entity_id)
+                .and_then(ConcreteEntity::as_monster_mut)
+                .filter(|monster| monster.hostile && heard.contains_key(&monster.position))
+            {
+                monster.investigating = Some(origin);
+            }
+        }
+    }
+
+    /// Checks if a monster on the current level can see the player.
+    ///
+    /// Sight works both ways, so a monster sees the player when it stands on a
+    /// tile the player can see, within the monster's perception.
+    pub fn monster_sees_player(&self, entity_id: EntityId) -> bool {
+        let (Some(monster), Some(player)) = (
+            self.entities
+                .get(&entity_id)
+                .and_then(ConcreteEntity::as_monster),
+            self.get_player(),
+        ) else {
+            return false;
+        };
+        let in_view = self
+            .world
+            .current_level()
+            .and_then(|level| level.get_tile(monster.position))
+            .is_some_and(|tile| tile.is_visible());
+        let distance = monster.position.manhattan_distance(player.position());
+        in_view && distance <= monster.stats.perception
+    }
+
+    /// Makes a monster forget what it was investigating.
+    fn stop_investigating(&mut self, entity_id: EntityId) {
+        if let Some(monster) = self
+            .entities
+            .get_mut(&entity_id)
+            .and_then(ConcreteEntity::as_monster_mut)
+        {
+            monster.investigating = None;
+        }
+    }
+
+    /// Applies a turn of water, drowning, and status effects to the player.
+    ///
+    /// Standing in water soaks the player (putting out any flames) and costs
+    /// breath; a failed swim check costs an extra turn of breath and washes
+    /// the ink off the scrolls in their pack. Once the
+    /// player has been in water for more than [`config::BREATH_TURNS`](crate::config::BREATH_TURNS)
+    /// turns they take drowning damage each turn, unless they can breathe water.
+    fn apply_player_environment(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let Some(player_id) = self.player_id else {
+            return events;
+        };
+        let in_water = self
+            .get_entity_position(player_id)
+            .and_then(|pos| self.world.current_level()?.get_tile(pos))
+            .is_some_and(|tile| tile.tile_type == TileType::Water);
+        let swim_check_passed = !in_water
+            || self
+                .rng
+                .gen_bool(RngStream::Terrain, crate::config::SWIM_CHECK_CHANCE);
+        let breathes_water = self.player_can_breathe_water();
+        let ruined_scrolls = if swim_check_passed {
+            0
+        } else {
+            self.soak_scrolls()
+        };
+
+        let Some(player) = self
+            .entities
+            .get_mut(&player_id)
+            .and_then(ConcreteEntity::as_player_mut)
+        else {
+            return events;
+        };
+        let mut message = |text: &str, importance| {
+            events.push(GameEvent::Message {
+                text: text.to_string(),
+                importance,
+                channel: crate::MessageChannel::Exploration,
+            })
+        };
+
+        if in_water {
+            if player.statuses.has(StatusKind::Burning) {
+                message(
+                    "The water puts out the flames.",
+                    crate::MessageImportance::Important,
+                );
+            }
+            player
+                .statuses
+                .apply(StatusKind::Soaked, crate::SOAKED_DURATION);
+
+            if ruined_scrolls > 0 {
+                message(
+                    "The water washes the ink off your scrolls.",
+                    crate::MessageImportance::Important,
+                );
+            }
+            if breathes_water {
+                player.turns_in_water = 0;
+            } else {
+                player.turns_in_water += if swim_check_passed { 1 } else { 2 };
+                if !swim_check_passed {
+                    message(
+                        "You swallow a mouthful of water.",
+                        crate::MessageImportance::Normal,
+                    );
+                }
+                if player.turns_in_water == crate::config::BREATH_TURNS {
+                    message(
+                        "You are running out of breath!",
+                        crate::MessageImportance::Important,
+                    );
+                }
+            }
+        } else {
+            player.turns_in_water = 0;
+        }
+
+        let drowning = player.turns_in_water > crate::config::BREATH_TURNS;
+        let burning = player.statuses.has(StatusKind::Burning);
+        for expired in player.statuses.tick() {
+            message(
+                &format!(
+                    "You are no longer {}.",
+                    expired.display_name().to_lowercase()
+                ),
+                crate::MessageImportance::Info,
+            );
+        }
+
+        if drowning {
+            message("You are drowning!", crate::MessageImportance::Critical);
+            events.push(GameEvent::EntityDamaged {
+                entity_id: player_id,
+                damage: crate::config::DROWNING_DAMAGE,
+                source: None,
+            });
+        }
+        if burning {
+            events.push(GameEvent::EntityDamaged {
+                entity_id: player_id,
+                damage: crate::BURNING_DAMAGE,
+                source: None,
+            });
+        }
+
+        events
+    }
+
+    /// Gets current game time information.
+    pub fn get_game_time_info(&self) -> GameTimeInfo {
+        let elapsed = self
+            .game_start_time
+            .map(|start| start.elapsed())
+            .unwrap_or(Duration::ZERO);
+
+        GameTimeInfo {
+            turn_number: self.turn_number,
+            elapsed_time: elapsed,
+            total_play_time: Duration::from_secs(self.total_play_time),
+        }
+    }
+
+    /// Builds the run summary shown on the ending screen.
+    pub fn run_summary(&self) -> RunSummary {
+        RunSummary {
+            statistics: self.statistics.clone(),
+            turns: self.turn_number,
+            play_time_seconds: self.total_play_time,
+            seed: self.rng_seed,
+            score: self.score(),
+            epitaph: self.epitaph(),
+        }
+    }
+
+    /// Scores the run so far from the deepest level reached, the treasure the
+    /// player carries, their kills, the turns taken and how the run ended.
+    pub fn score(&self) -> Score {
+        let treasures = self.get_player().map_or(0, |player| {
+            player
+                .inventory
+                .iter()
+                .chain(player.equipment.values())
+                .filter_map(|id| self.carried_items.get(id))
+                .filter(|item| item.item_type == ItemType::Treasure)
+                .count()
+        });
+        Score::new(
+            self.statistics.max_depth_reached + 1,
+            treasures as u32,
+            self.statistics.enemies_defeated,
+            self.turn_number,
+            &self.completion_state,
+        )
+    }
+
+    /// Gets configuration flag value.
+    pub fn get_config_flag(&self, flag: &str) -> bool {
+        self.config_flags.get(flag).copied().unwrap_or(false)
+    }
+
+    /// Sets configuration flag value.
+    pub fn set_config_flag(&mut self, flag: String, value: bool) {
+        self.config_flags.insert(flag, value);
+    }
+
+    /// Adds entity to position index.
+    fn add_entity_to_position_index(&mut self, entity_id: EntityId, position: Position) {
+        self.position_index
+            .entry(position)
+            .or_default()
+            .push(entity_id);
+    }
+
+    /// Removes entity from position index.
+    fn remove_entity_from_position_index(&mut self, entity_id: EntityId, position: Position) {
+        if let Some(entities) = self.position_index.get_mut(&position) {
+            entities.retain(|&id| id != entity_id);
+            if entities.is_empty() {
+                self.position_index.remove(&position);
+            }
+        }
+    }
+
+    /// Captures a compact description of the game for a language model.
+    ///
+    /// The snapshot is truncated to fit roughly `token_budget` tokens once
+    /// serialized; see [`LlmSnapshot`] for what it contains.
+    pub fn snapshot_for_llm(&self, token_budget: usize) -> LlmSnapshot {
+        LlmSnapshot::capture(self, token_budget)
+    }
+
+    /// Rebuilds the spatial index from the current entity positions.
+    ///
+    /// The index is not saved, so this runs after loading and can be called
+    /// whenever the index may have drifted from the entities.
+    pub fn rebuild_spatial_index(&mut self) {
+        self.position_index.clear();
+        for entity in self.entities.values() {
+            self.position_index
+                .entry(entity.position())
+                .or_default()
+                .push(entity.id());
+        }
+    }
+
+    /// Checks that the spatial index and level entity lists agree with the
+    /// entity table.
+    ///
+    /// Returns every problem found; an empty list means the state is consistent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{GameState, Position};
+    ///
+    /// let mut game_state = GameState::new(12345);
+    /// game_state.initialize_player("Hero".to_string(), Position::new(5, 5)).unwrap();
+    /// assert!(game_state.validate_consistency().is_empty());
+    /// ```
+    pub fn validate_consistency(&self) -> Vec<ConsistencyIssue> {
+        let mut issues = Vec::new();
+
+        for (&position, ids) in &self.position_index {
+            for (index, &entity_id) in ids.iter().enumerate() {
+                if ids[..index].contains(&entity_id) {
+                    issues.push(ConsistencyIssue::DuplicateIndexEntry {
+                        entity_id,
+                        position,
+                    });
+                } else if self.get_entity_position(entity_id) != Some(position) {
+                    issues.push(ConsistencyIssue::StaleIndexEntry {
+                        entity_id,
+                        position,
+                    });
+                }
+            }
+        }
+
+        for (&entity_id, entity) in &self.entities {
+            let position = entity.position();
+            let indexed = self
+                .position_index
+                .get(&position)
+                .is_some_and(|ids| ids.contains(&entity_id));
+            if !indexed {
+                issues.push(ConsistencyIssue::NotIndexed {
+                    entity_id,
+                    position,
+                });
+            }
+        }
+
+        let mut level_ids: Vec<u32> = self.world.levels.keys().copied().collect();
+        level_ids.sort_unstable();
+        let mut listed_on: HashMap<EntityId, Vec<u32>> = HashMap::new();
+        for level_id in level_ids {
+            for &entity_id in &self.world.levels[&level_id].entities {
+                if !self.entities.contains_key(&entity_id) {
+                    issues.push(ConsistencyIssue::UnknownLevelEntity {
+                        entity_id,
+                        level_id,
+                    });
+                }
+                listed_on.entry(entity_id).or_default().push(level_id);
+            }
+        }
+        for (entity_id, level_ids) in listed_on {
+            if level_ids.len() > 1 {
+                issues.push(ConsistencyIssue::EntityOnMultipleLevels {
+                    entity_id,
+                    level_ids,
+                });
+            }
+        }
+
+        if let Some(player_id) = self.player_id {
+            if !self.entities.contains_key(&player_id) {
+                issues.push(ConsistencyIssue::MissingPlayer { player_id });
+            }
+        }
+
+        issues
+    }
+
+    /// Drops pending LLDM requests if there is no endpoint to send them to.
+    ///
+    /// With one, they are left for an [`LldmWorker`](crate::LldmWorker) to send.
+    fn process_lldm_requests(&mut self) -> ThatchResult<()> {
+        if !self.lldm_state.enabled || self.lldm_state.config.endpoint.is_none() {
+            self.lldm_state.pending_requests.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Asks the LLDM, when it is enabled, to write the fallen player's epitaph.
+    fn request_epitaph(&mut self, killer: Option<EntityId>) {
+        if !self.lldm_state.enabled {
+            return;
+        }
+        let Some(player) = self.get_player() else {
+            return;
+        };
+        let cause_of_death = killer
+            .and_then(|killer| self.entities.get(&killer))
+            .map_or_else(
+                || "the dungeon".to_string(),
+                |killer| format!("a {}", killer.name()),
+            );
+        let context = HashMap::from([
+            ("character_name".to_string(), player.name().to_string()),
+            ("class".to_string(), player.class.display_name().to_string()),
+            (
+                "depth".to_string(),
+                (self.world.current_level_id + 1).to_string(),
+            ),
+            ("cause_of_death".to_string(), cause_of_death),
+        ]);
+        self.lldm_state.pending_requests.push(LldmRequest {
+            id: EPITAPH_KEY.to_string(),
+            request_type: crate::EPITAPH.to_string(),
+            context,
+            priority: LldmPriority::Normal,
+            created_at: self.turn_number,
+        });
+    }
+
+    /// Gets the epitaph the LLDM wrote for the fallen player, once it is back.
+    pub fn epitaph(&self) -> Option<String> {
+        let completion = self.lldm_state.content_cache.get(EPITAPH_KEY)?;
+        let registry = crate::PromptRegistry::builtin();
+        let epitaph: crate::Epitaph = registry
+            .template(crate::EPITAPH)
+            .and_then(|template| template.parse(completion))
+            .ok()?;
+        Some(epitaph.epitaph)
+    }
+
+    /// Saves the game state to JSON.
+    pub fn save_to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string_pretty(self).map_err(ThatchError::from)
+    }
+
+    /// Loads game state from JSON.
+    pub fn load_from_json(json: &str) -> ThatchResult<Self> {
+        let mut game_state: Self = serde_json::from_str(json).map_err(ThatchError::from)?;
+        game_state.rebuild_spatial_index();
+        for level in game_state.world.levels.values_mut() {
+            level.rebuild_portals();
+            // Saves from before room graphs were stored
+            if level.graph.rooms.len() != level.rooms.len() {
+                level.build_graph();
+            }
+        }
+        game_state.restore_lazy_generation(VaultLibrary::default());
+        Ok(game_state)
+    }
+
+    /// Saves the game state to a JSON file.
+    pub fn save_to_file(&self, path: &Path) -> ThatchResult<()> {
+        std::fs::write(path, self.save_to_json()?)?;
+        Ok(())
+    }
+
+    /// Loads game state from a JSON file written by [`GameState::save_to_file`].
+    pub fn load_from_file(path: &Path) -> ThatchResult<Self> {
+        Self::load_from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Gets the ending that taking the stairs under the player would trigger.
+    ///
+    /// Returns `None` if the player is not standing on stairs in `direction`
+    /// or the stairs lead to another level. Only the stairs up out of the
+    /// first level end the run, and how depends on whether the player has the
+    /// Amulet.
+    pub fn stairs_ending(&self, direction: &StairDirection) -> Option<GameCompletionState> {
+        let player_pos = self.get_player()?.position();
+        let tile = self.world.current_level()?.get_tile(player_pos)?;
+
+        match direction {
+            StairDirection::Up
+                if tile.tile_type == TileType::StairsUp && self.world.current_level_id == 0 =>
+            {
+                Some(self.escape_ending())
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the ending for leaving the dungeon now.
+    fn escape_ending(&self) -> GameCompletionState {
+        if self.player_has_artifact() {
+            GameCompletionState::EscapedWithArtifact
+        } else {
+            GameCompletionState::EscapedEarly
+        }
+    }
+
+    /// Gets how much of the current level the player had explored the last
+    /// time they looked around.
+    pub fn current_exploration(&self) -> LevelExploration {
+        self.statistics
+            .level_exploration
+            .get(&self.world.current_level_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Gets the percentage of the current level's reachable tiles that have
+    /// been explored.
+    pub fn explored_percentage(&self) -> u32 {
+        self.current_exploration().tile_percentage()
+    }
+
+    /// Handles level progression when player uses stairs.
+    ///
+    /// Returns true if the level change was successful, false if it triggers a game ending.
+    pub fn use_stairs(&mut self, direction: crate::StairDirection) -> ThatchResult<bool> {
         let current_level_id = self.world.current_level_id;
 
         match direction {
             crate::StairDirection::Up => {
                 if current_level_id == 0 {
-                    // Going up from level 1 triggers escape ending
-                    self.completion_state = GameCompletionState::EscapedEarly;
+                    // Going up from level 1 leaves the dungeon, with or without the Amulet
+                    self.completion_state = self.escape_ending();
                     return Ok(false);
                 }
                 // Go back to previous level
@@ -718,10 +2693,10 @@ impl GameState {
                 self.change_to_level(target_level_id)?;
             }
             crate::StairDirection::Down => {
-                if current_level_id >= 25 {
-                    // Going down from level 26 (0-indexed 25) triggers win ending
-                    self.completion_state = GameCompletionState::CompletedDungeon;
-                    return Ok(false);
+                if current_level_id >= self.world.shape.deepest_floor() {
+                    return Err(ThatchError::InvalidAction(
+                        "There is nothing deeper than this".to_string(),
+                    ));
                 }
                 // Go to next level (generate if needed)
                 let target_level_id = current_level_id + 1;
@@ -734,6 +2709,18 @@ impl GameState {
 
     /// Changes to the specified level, generating it if it doesn't exist.
     fn change_to_level(&mut self, level_id: u32) -> ThatchResult<()> {
+        self.enter_level(level_id, None)
+    }
+
+    /// Changes to the specified level, arriving by the stairs or, for a fall
+    /// from a chasm at `fell_from` on the floor above, on the
+    /// [`landing_spot`](crate::landing_spot) under it.
+    fn enter_level(&mut self, level_id: u32, fell_from: Option<Position>) -> ThatchResult<()> {
+        // Lazy worlds generate any floors still missing down to the target
+        if let Some(config) = &self.lazy_generation {
+            crate::extend_world(config, &mut self.world, level_id)?;
+        }
+
         // If level doesn't exist, generate it
         if !self.world.levels.contains_key(&level_id) {
             // For the new 3D generation system, all levels should already exist
@@ -750,6 +2737,30 @@ impl GameState {
             }
         }
 
+        // Climbing arrives on the stairs down of the floor above, descending on its stairs up
+        let old_level_id = self.world.current_level_id;
+        let climbing = level_id < old_level_id;
+
+        // Following allies close to the player take the stairs with them
+        let player_position = self.get_player().map(|player| player.position());
+        let followers: Vec<EntityId> = self
+            .allies()
+            .into_iter()
+            .filter(|&id| {
+                let close = self
+                    .get_entity_position(id)
+                    .zip(player_position)
+                    .is_some_and(|(position, player)| {
+                        position.manhattan_distance(player) <= crate::ALLY_STAIRS_RANGE
+                    });
+                let follows = self.entities[&id]
+                    .as_monster()
+                    .and_then(|monster| monster.ally)
+                    .is_some_and(crate::AllyCommand::follows);
+                close && follows
+            })
+            .collect();
+
         // Move player entity from current level to target level
         if let Some(player_id) = self.player_id {
             // Remove from current level
@@ -760,10 +2771,22 @@ impl GameState {
             // Change level
             self.world.change_level(level_id)?;
 
-            // Add to new level and move to spawn point (stairs)
+            // Add to new level and move to spawn point (stairs), or where a fall lands
+            let landing = fell_from.and_then(|above| {
+                let level = self.world.current_level()?;
+                crate::landing_spot(level, above, |spot| {
+                    self.get_entities_at_position(spot)
+                        .iter()
+                        .any(|&id| id != player_id)
+                })
+            });
             if let Some(new_level) = self.world.current_level_mut() {
                 new_level.add_entity(player_id);
-                let spawn_pos = new_level.player_spawn; // This is now always stairs up
+                let spawn_pos = match (landing, new_level.stairs_down_position) {
+                    (Some(landing), _) => landing,
+                    (None, Some(stairs_down)) if climbing => stairs_down,
+                    _ => new_level.player_spawn, // This is now always stairs up
+                };
 
                 // Update entity position
                 let old_pos = if let Some(player) = self.get_player() {
@@ -778,6 +2801,7 @@ impl GameState {
                 }
                 self.add_entity_to_position_index(player_id, spawn_pos);
             }
+            self.bring_followers(&followers, old_level_id);
 
             // CRITICAL: Update visibility immediately after level change
             // This ensures the player can see around them when entering a level
@@ -802,15 +2826,15 @@ impl GameState {
 
     /// Generates a new level with the specified ID.
     fn generate_level(&mut self, level_id: u32) -> ThatchResult<()> {
-        use crate::{GenerationConfig, Generator, RoomCorridorGenerator};
-        use rand::{rngs::StdRng, SeedableRng};
-
         // Create level-specific seed based on world seed and level ID
-        let level_seed = self.rng_seed.wrapping_add(level_id as u64 * 1000);
-        let mut rng = StdRng::seed_from_u64(level_seed);
+        let mut rng = crate::generation::utils::floor_rng(self.rng_seed, level_id);
 
-        let config = GenerationConfig::default();
-        let generator = RoomCorridorGenerator::new();
+        let config = GenerationConfig {
+            generator: self.generator,
+            use_lldm: self.lldm_state.enabled,
+            ..GenerationConfig::default()
+        };
+        let generator = self.generator.level_generator();
 
         let mut level = generator.generate(&config, &mut rng)?;
         level.id = level_id;
@@ -872,219 +2896,1530 @@ impl GameState {
         }
     }
 
-    /// Resets the game state for a new game.
-    pub fn reset_for_new_game(&mut self) -> ThatchResult<()> {
-        // Clear all levels except level 0
-        self.world.levels.retain(|&id, _| id == 0);
-        self.world.current_level_id = 0;
-        self.world.max_depth = 0;
+    /// Moves allies that followed the player onto the current level, setting
+    /// them down around the player.
+    ///
+    /// Allies with no room to stand near the player stay behind.
+    fn bring_followers(&mut self, followers: &[EntityId], from_level: u32) {
+        let Some(arrival) = self.get_player().map(|player| player.position()) else {
+            return;
+        };
+        for &ally_id in followers {
+            let spot = self.world.current_level().and_then(|level| {
+                crate::ally_spot(level, arrival, |position| {
+                    self.get_entity_at_position(position).is_some()
+                })
+            });
+            let Some(spot) = spot else {
+                continue;
+            };
+            if let Some(level) = self.world.levels.get_mut(&from_level) {
+                level.remove_entity(&ally_id);
+            }
+            if let Some(level) = self.world.current_level_mut() {
+                level.add_entity(ally_id);
+            }
+            let _ = self.set_entity_position(ally_id, spot);
+        }
+    }
+
+    /// Resets the game state for a new game.
+    pub fn reset_for_new_game(&mut self) -> ThatchResult<()> {
+        // Clear all levels except level 0
+        self.world.levels.retain(|&id, _| id == 0);
+        self.world.current_level_id = 0;
+        self.world.max_depth = 0;
+
+        // Regenerate level 0
+        self.generate_level(0)?;
+
+        // Reset player position to spawn
+        if let Some(player_id) = self.player_id {
+            let spawn_pos = if let Some(level) = self.world.current_level() {
+                level.player_spawn
+            } else {
+                Position::new(0, 0)
+            };
+
+            let old_pos = if let Some(player) = self.get_player() {
+                player.position()
+            } else {
+                spawn_pos // fallback
+            };
+
+            self.remove_entity_from_position_index(player_id, old_pos);
+            if let Some(player) = self.get_player_mut() {
+                player.set_position(spawn_pos);
+            }
+            self.add_entity_to_position_index(player_id, spawn_pos);
+        }
+
+        // Reset game state
+        self.completion_state = GameCompletionState::Playing;
+        self.turn_number = 0;
+        self.statistics = GameStatistics::new();
+        self.game_start_time = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Checks if the game has ended.
+    pub fn is_game_ended(&self) -> bool {
+        self.completion_state != GameCompletionState::Playing
+    }
+
+    /// Gets the current completion state.
+    pub fn get_completion_state(&self) -> &GameCompletionState {
+        &self.completion_state
+    }
+
+    /// Toggles autoexplore debug mode.
+    ///
+    /// Hostiles and items already in view when autoexplore starts will not
+    /// interrupt it.
+    pub fn toggle_autoexplore(&mut self) -> bool {
+        let enabled = self.autoexplore_state.toggle();
+        if enabled {
+            let (hostiles, items) = (self.visible_hostiles(), self.visible_items());
+            self.autoexplore_state.watch.start(hostiles, items);
+        }
+        enabled
+    }
+
+    /// Pauses autoexplore if something needs the player's attention.
+    ///
+    /// Returns the message explaining why autoexplore paused, if it did.
+    pub fn check_autoexplore_interruption(&mut self) -> Option<String> {
+        if self.autoexplore_state.enabled && self.player_is_confused() {
+            self.autoexplore_state.pause();
+            return Some("You are too confused to explore.".to_string());
+        }
+        let interruption = self.autoexplore_state.find_interruption(self)?;
+        self.autoexplore_state.pause();
+        Some(AutoexploreState::pause_message(interruption))
+    }
+
+    /// Gets the next autoexplore action if enabled and ready.
+    pub fn get_autoexplore_action(&mut self) -> ThatchResult<Option<crate::ConcreteAction>> {
+        if !self.autoexplore_state.enabled || !self.autoexplore_state.can_perform_action() {
+            return Ok(None);
+        }
+
+        let player = self
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let player_pos = player.position();
+        let player_id = player.id();
+
+        // Check if we're already on stairs down
+        if let Some(level) = self.world.current_level() {
+            if let Some(tile) = level.get_tile(player_pos) {
+                if tile.tile_type == TileType::StairsDown {
+                    // We're on stairs down, use them
+                    self.autoexplore_state.mark_action_performed();
+                    return Ok(Some(crate::ConcreteAction::UseStairs(
+                        UseStairsAction::new(player_id, StairDirection::Down),
+                    )));
+                }
+            }
+        }
+
+        // If we have a current path, follow it
+        if let Some(next_pos) = self.autoexplore_state.next_step(player_pos) {
+            let step = self
+                .get_direction_to_position(player_pos, next_pos)
+                .and_then(|direction| self.route_step(player_id, player_pos, direction));
+            if let Some(action) = step {
+                self.autoexplore_state.mark_action_performed();
+                return Ok(Some(action));
+            }
+            // Path is invalid, clear it
+            self.autoexplore_state.current_path.clear();
+        }
+
+        // We need a new path - find stairs down
+        if let Some(stairs_down_pos) = self.find_stairs_down() {
+            if let Some(path) = self.autoexplore_find_path(player_pos, stairs_down_pos)? {
+                self.autoexplore_state.current_path = path;
+                self.autoexplore_state.target = Some(stairs_down_pos);
+
+                // Return the first move in the path
+                if let Some(next_pos) = self.autoexplore_state.next_step(player_pos) {
+                    let step = self
+                        .get_direction_to_position(player_pos, next_pos)
+                        .and_then(|direction| self.route_step(player_id, player_pos, direction));
+                    if let Some(action) = step {
+                        self.autoexplore_state.mark_action_performed();
+                        return Ok(Some(action));
+                    }
+                }
+            }
+        }
+
+        // No stairs down found or no path available
+        Ok(None)
+    }
+
+    /// Gets the action taking the player along a route a step in `direction`
+    /// from `from`, opening any closed door in the way as
+    /// [`DoorOpening::on_routes`] says.
+    ///
+    /// Routes keep each step until the player stands on it, so a door that
+    /// opens without letting the player through is followed by the same step
+    /// again. Returns None if there is no level.
+    pub(crate) fn route_step(
+        &self,
+        player_id: EntityId,
+        from: Position,
+        direction: Direction,
+    ) -> Option<ConcreteAction> {
+        let level = self.world.current_level()?;
+        Some(
+            self.door_opening
+                .on_routes()
+                .step(level, player_id, from, direction),
+        )
+    }
+
+    /// Helper method to get direction between positions for autoexplore.
+    fn get_direction_to_position(&self, from: Position, to: Position) -> Option<Direction> {
+        let delta = to - from;
+        Direction::from_delta(delta)
+    }
+
+    /// Helper method to find stairs down position for autoexplore.
+    fn find_stairs_down(&self) -> Option<Position> {
+        let level = self.world.current_level()?;
+        level.stairs_down_position
+    }
+
+    /// Helper method for autoexplore pathfinding.
+    fn autoexplore_find_path(
+        &self,
+        start: Position,
+        goal: Position,
+    ) -> ThatchResult<Option<Vec<Position>>> {
+        let level = self
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        Ok(
+            crate::PathfindingUtils::new().find_route(level, start, goal, |position| {
+                self.get_entity_at_position(position).is_some()
+            }),
+        )
+    }
+
+    /// Checks if autoexplore is currently enabled.
+    pub fn is_autoexplore_enabled(&self) -> bool {
+        self.autoexplore_state.enabled
+    }
+
+    /// Starts travelling to an explored, passable destination.
+    ///
+    /// The path is computed with the autoexplore A* pathfinder and then walked
+    /// one step per call to [`GameState::get_travel_action`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidAction` if the destination is out of bounds, unexplored,
+    /// impassable, or unreachable.
+    pub fn start_travel(&mut self, destination: Position) -> ThatchResult<()> {
+        let player_pos = self
+            .get_player()
+            .map(|player| player.position())
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+
+        let level = self
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+        let tile = level.get_tile(destination).ok_or_else(|| {
+            ThatchError::InvalidAction("Destination is out of bounds".to_string())
+        })?;
+
+        if !tile.is_explored() {
+            return Err(ThatchError::InvalidAction(
+                "You don't know how to get there".to_string(),
+            ));
+        }
+        if !tile.tile_type.is_passable() {
+            return Err(ThatchError::InvalidAction(
+                "You can't walk there".to_string(),
+            ));
+        }
+        if destination == player_pos {
+            self.travel_state.cancel();
+            return Ok(());
+        }
+
+        let path = self
+            .autoexplore_find_path(player_pos, destination)?
+            .ok_or_else(|| ThatchError::InvalidAction("No path to destination".to_string()))?;
+
+        let known_hostiles = self.visible_hostiles();
+        self.travel_state.start(destination, path, known_hostiles);
+        Ok(())
+    }
+
+    /// Lists the destinations currently available in the travel menu.
+    ///
+    /// Stairs are only offered once explored, and rooms once any of their
+    /// tiles has been seen.
+    pub fn travel_destinations(&self) -> Vec<TravelDestination> {
+        let mut destinations = Vec::new();
+        let Some(level) = self.world.current_level() else {
+            return destinations;
+        };
+
+        let is_explored = |pos: Option<Position>| {
+            pos.and_then(|p| level.get_tile(p))
+                .is_some_and(|tile| tile.is_explored())
+        };
+
+        if is_explored(level.stairs_up_position) {
+            destinations.push(TravelDestination::StairsUp);
+        }
+        if is_explored(level.stairs_down_position) {
+            destinations.push(TravelDestination::StairsDown);
+        }
+        if self.find_nearest_unexplored().is_some() {
+            destinations.push(TravelDestination::NearestUnexplored);
+        }
+
+        for room in &level.rooms {
+            if self.room_travel_target(room.id).is_some() {
+                let label = room
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("Room {} ({:?})", room.id + 1, room.room_type));
+                destinations.push(TravelDestination::Room { id: room.id, label });
+            }
+        }
+
+        destinations
+    }
+
+    /// Starts travelling to a named destination from the travel menu.
+    pub fn start_travel_to(&mut self, destination: &TravelDestination) -> ThatchResult<()> {
+        let level = self
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
+
+        let target = match destination {
+            TravelDestination::StairsUp => level.stairs_up_position,
+            TravelDestination::StairsDown => level.stairs_down_position,
+            TravelDestination::NearestUnexplored => self.find_nearest_unexplored(),
+            TravelDestination::Room { id, .. } => self.room_travel_target(*id),
+        }
+        .ok_or_else(|| {
+            ThatchError::InvalidAction(format!("{} is not known", destination.label()))
+        })?;
+
+        self.start_travel(target)
+    }
+
+    /// Finds the closest reachable explored tile that borders unexplored territory.
+    ///
+    /// Uses a breadth-first search over explored, passable tiles starting from
+    /// the player, so the result is the nearest frontier by walking distance.
+    pub fn find_nearest_unexplored(&self) -> Option<Position> {
+        let start = self.get_player()?.position();
+        let level = self.world.current_level()?;
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(start);
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            let neighbors = [
+                Position::new(current.x, current.y - 1),
+                Position::new(current.x, current.y + 1),
+                Position::new(current.x - 1, current.y),
+                Position::new(current.x + 1, current.y),
+            ];
+
+            let borders_unexplored = neighbors
+                .iter()
+                .any(|&pos| level.get_tile(pos).is_some_and(|tile| !tile.is_explored()));
+            if current != start && borders_unexplored {
+                return Some(current);
+            }
+
+            for neighbor in neighbors {
+                if visited.contains(&neighbor) {
+                    continue;
+                }
+                if let Some(tile) = level.get_tile(neighbor) {
+                    if tile.is_explored() && tile.tile_type.is_passable() {
+                        visited.insert(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Picks the explored, passable tile of a room closest to its center.
+    fn room_travel_target(&self, room_id: u32) -> Option<Position> {
+        let level = self.world.current_level()?;
+        let room = level.rooms.iter().find(|room| room.id == room_id)?;
+        let center = room.center();
+
+        (0..room.height as i32)
+            .flat_map(|dy| (0..room.width as i32).map(move |dx| (dx, dy)))
+            .map(|(dx, dy)| Position::new(room.top_left.x + dx, room.top_left.y + dy))
+            .filter(|&pos| {
+                level
+                    .get_tile(pos)
+                    .is_some_and(|tile| tile.is_explored() && tile.tile_type.is_passable())
+            })
+            .min_by_key(|&pos| pos.manhattan_distance(center))
+    }
+
+    /// Gets the ids of hostile entities on visible tiles of the current level.
+    pub fn visible_hostiles(&self) -> HashSet<EntityId> {
+        let Some(level) = self.world.current_level() else {
+            return HashSet::new();
+        };
+
+        level
+            .entities
+            .iter()
+            .filter(|&&id| {
+                self.entities
+                    .get(&id)
+                    .is_some_and(|entity| entity.as_entity().is_hostile())
+            })
+            .filter(|&&id| {
+                self.get_entity_position(id)
+                    .and_then(|pos| level.get_tile(pos))
+                    .is_some_and(|tile| tile.is_visible())
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns true if a kill by `killer` counts as the player's.
+    ///
+    /// Kills by the player's allies are credited to the player.
+    pub fn credits_player(&self, killer: Option<EntityId>) -> bool {
+        let Some(killer) = killer else {
+            return false;
+        };
+        Some(killer) == self.player_id
+            || self
+                .entities
+                .get(&killer)
+                .and_then(ConcreteEntity::as_monster)
+                .is_some_and(crate::Monster::is_ally)
+    }
+
+    /// Lists the player's living allies on the current level.
+    pub fn allies(&self) -> Vec<EntityId> {
+        let Some(level) = self.world.current_level() else {
+            return Vec::new();
+        };
+        level
+            .entities
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.entities
+                    .get(id)
+                    .and_then(ConcreteEntity::as_monster)
+                    .is_some_and(|monster| monster.is_ally() && monster.is_alive())
+            })
+            .collect()
+    }
+
+    /// Gives every ally on the current level a command.
+    ///
+    /// Returns no events if the player has no allies here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{AllyCommand, GameState, Monster, MonsterType, Position};
+    ///
+    /// let mut game_state = GameState::new(12345);
+    /// assert!(game_state.command_allies(AllyCommand::Stay).is_empty());
+    ///
+    /// let mut skeleton = Monster::new(MonsterType::Skeleton, Position::new(3, 3));
+    /// skeleton.hostile = false;
+    /// skeleton.ally = Some(AllyCommand::Follow);
+    /// let skeleton_id = game_state.add_entity(skeleton.into()).unwrap();
+    /// game_state.world.current_level_mut().unwrap().add_entity(skeleton_id);
+    ///
+    /// assert_eq!(game_state.command_allies(AllyCommand::Stay).len(), 1);
+    /// let skeleton = game_state.entities[&skeleton_id].as_monster().unwrap();
+    /// assert_eq!(skeleton.ally, Some(AllyCommand::Stay));
+    /// ```
+    pub fn command_allies(&mut self, command: crate::AllyCommand) -> Vec<GameEvent> {
+        let allies = self.allies();
+        for ally_id in &allies {
+            if let Some(monster) = self
+                .entities
+                .get_mut(ally_id)
+                .and_then(ConcreteEntity::as_monster_mut)
+            {
+                monster.ally = Some(command);
+            }
+        }
+        if allies.is_empty() {
+            return Vec::new();
+        }
+        vec![GameEvent::Message {
+            text: command.message().to_string(),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Combat,
+        }]
+    }
+
+    /// Gets the command the player would give their allies next.
+    ///
+    /// Commands cycle from follow to stay to attacking the hostile nearest the
+    /// player, skipping the attack if no hostile is in sight.
+    pub fn next_ally_command(&self) -> crate::AllyCommand {
+        let current = self
+            .allies()
+            .first()
+            .and_then(|id| self.entities.get(id))
+            .and_then(ConcreteEntity::as_monster)
+            .and_then(|monster| monster.ally)
+            .unwrap_or_default();
+        let target = self
+            .get_player()
+            .and_then(|player| self.nearest_visible_hostile(player.position(), u32::MAX));
+        current.next(target)
+    }
+
+    /// Gets the visible hostile closest to `origin`, if one is within `range` tiles walked.
+    pub fn nearest_visible_hostile(&self, origin: Position, range: u32) -> Option<EntityId> {
+        self.visible_hostiles()
+            .into_iter()
+            .filter_map(|id| {
+                let distance = origin.manhattan_distance(self.get_entity_position(id)?);
+                (distance <= range).then_some((distance, id))
+            })
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, id)| id)
+    }
+
+    /// Gets the visible corpse closest to `origin` within `range` tiles walked,
+    /// skipping corpses with something standing on them.
+    pub fn nearest_visible_corpse(&self, origin: Position, range: u32) -> Option<EntityId> {
+        let level = self.world.current_level()?;
+        level
+            .ground_items
+            .iter()
+            .map(|stack| &stack.item)
+            .filter(|item| matches!(item.item_type, crate::ItemType::Corpse(_)))
+            .filter(|item| {
+                level
+                    .get_tile(item.position)
+                    .is_some_and(|tile| tile.is_visible())
+                    && self.get_entity_at_position(item.position).is_none()
+            })
+            .filter(|item| origin.manhattan_distance(item.position) <= range)
+            .min_by_key(|item| origin.manhattan_distance(item.position))
+            .map(|item| item.id)
+    }
+
+    /// Raises a corpse on the current level as an allied monster of its kind.
+    ///
+    /// Returns the events announcing the new monster.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `corpse_id` is not a corpse on the current level.
+    pub fn raise_corpse(&mut self, corpse_id: EntityId) -> ThatchResult<Vec<GameEvent>> {
+        let gone = || ThatchError::InvalidAction("There is no corpse to raise".to_string());
+        let level = self.world.current_level_mut().ok_or_else(gone)?;
+        let is_corpse = level.ground_items.iter().any(|stack| {
+            stack.item.id == corpse_id && matches!(stack.item.item_type, crate::ItemType::Corpse(_))
+        });
+        let corpse = is_corpse
+            .then(|| level.ground_items.take(corpse_id))
+            .flatten()
+            .ok_or_else(gone)?;
+        let crate::ItemType::Corpse(species) = corpse.item_type else {
+            return Err(gone());
+        };
+
+        let mut monster = crate::Monster::new(species.clone(), corpse.position);
+        monster.name = format!("Raised {}", monster.name);
+        monster.hostile = false;
+        monster.ally = Some(crate::AllyCommand::Follow);
+        let monster_id = self.add_entity(monster.into())?;
+        if let Some(level) = self.world.current_level_mut() {
+            level.add_entity(monster_id);
+        }
+
+        Ok(vec![
+            GameEvent::EntityCreated {
+                entity_id: monster_id,
+                entity_type: crate::EntityType::Monster(species),
+                position: corpse.position,
+            },
+            GameEvent::Message {
+                text: format!("The {} rises to serve you!", corpse.name),
+                importance: crate::MessageImportance::Important,
+                channel: crate::MessageChannel::Combat,
+            },
+        ])
+    }
+
+    /// Counts an event towards the player's quests and rewards any completed.
+    fn advance_quests(&mut self, event: &GameEvent) -> ThatchResult<Vec<GameEvent>> {
+        let completed = match event {
+            GameEvent::EntityDied { entity_id, killer } if self.credits_player(*killer) => {
+                let name = self
+                    .entities
+                    .get(entity_id)
+                    .map(|entity| entity.name().to_string());
+                name.map(|name| self.quests.advance(&QuestTrigger::Killed(&name)))
+            }
+            GameEvent::PlayerChangedLevel { new_level, .. } => {
+                Some(self.quests.advance(&QuestTrigger::ReachedFloor(*new_level)))
+            }
+            GameEvent::ItemPickedUp { item_id, picker_id }
+                if Some(*picker_id) == self.player_id =>
+            {
+                let name = self.carried_item_name(*item_id).map(str::to_string);
+                name.map(|name| self.quests.advance(&QuestTrigger::PickedUp(&name)))
+            }
+            _ => None,
+        };
+
+        let mut events = Vec::new();
+        for quest in completed.unwrap_or_default() {
+            events.push(self.reward_quest(&quest)?);
+        }
+        Ok(events)
+    }
+
+    /// Moves the tutorial on if the event finishes one of its steps, giving
+    /// the instructions for the next.
+    fn advance_tutorial(&mut self, event: &GameEvent) -> Option<GameEvent> {
+        self.tutorial.as_ref()?;
+        let trigger = match event {
+            GameEvent::EntityMoved { entity_id, to, .. } if Some(*entity_id) == self.player_id => {
+                let doorway = self
+                    .world
+                    .current_level()
+                    .and_then(|level| level.get_tile(*to))
+                    .is_some_and(|tile| matches!(tile.tile_type, TileType::Door { .. }));
+                if doorway {
+                    TutorialTrigger::EnteredDoorway
+                } else {
+                    TutorialTrigger::Moved
+                }
+            }
+            GameEvent::EntityDied { killer, .. } if self.credits_player(*killer) => {
+                TutorialTrigger::Killed
+            }
+            GameEvent::ItemPickedUp { picker_id, .. } if Some(*picker_id) == self.player_id => {
+                TutorialTrigger::PickedUp
+            }
+            GameEvent::PlayerChangedLevel {
+                old_level,
+                new_level,
+                ..
+            } if new_level > old_level => TutorialTrigger::Descended,
+            _ => return None,
+        };
+        let instructions = self.tutorial.as_mut()?.advance(trigger)?;
+        Some(GameEvent::Message {
+            text: instructions.to_string(),
+            importance: crate::MessageImportance::Important,
+            channel: crate::MessageChannel::System,
+        })
+    }
+
+    /// Gives the player a completed quest's reward.
+    ///
+    /// Item rewards go in the pack, or at the player's feet if it is full.
+    fn reward_quest(&mut self, quest: &Quest) -> ThatchResult<GameEvent> {
+        let player = self
+            .get_player_mut()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let position = player.position();
+        match &quest.reward {
+            QuestReward::Experience(amount) => player.stats.experience += amount,
+            QuestReward::Item { name, item_type } => {
+                let item = Item::new(name.clone(), item_type.clone(), position);
+                if !player.can_pick_up_item() {
+                    if let Some(level) = self.world.current_level_mut() {
+                        level.ground_items.drop_item(item);
+                    }
+                } else {
+                    self.give_item_to_player(item, false)?;
+                }
+            }
+        }
+
+        Ok(GameEvent::Message {
+            text: format!(
+                "Quest complete: {}! You receive {}.",
+                quest.title,
+                quest.reward.describe()
+            ),
+            importance: crate::MessageImportance::Important,
+            channel: crate::MessageChannel::Narrative,
+        })
+    }
+
+    /// Gets the deity the current floor's altars belong to, asking the LLDM to
+    /// name it the first time.
+    pub fn floor_deity(&mut self) -> crate::Deity {
+        let client = crate::LldmClient::new();
+        let Some(level) = self.world.current_level_mut() else {
+            return client.generate_deity(&HashMap::new());
+        };
+        let deity = match &level.deity {
+            Some(deity) => deity.clone(),
+            None => client.generate_deity(&client.quest_context(level, None)),
+        };
+        level.deity = Some(deity.clone());
+        deity
+    }
+
+    /// Asks the LLDM for a quest about the room the player is in, and adds it
+    /// to the quest log unless the player already has it.
+    fn offer_floor_quest(&mut self) -> Option<GameEvent> {
+        let position = self.get_player()?.position();
+        let level = self.world.current_level()?;
+        let room = level.rooms.iter().find(|room| room.contains(position));
+        let quest = crate::LldmClient::new().generate_quest(level, room);
+        let title = quest.title.clone();
+
+        self.quests.add(quest).then(|| GameEvent::Message {
+            text: format!("New quest: {}. Press Q to review your quests.", title),
+            importance: crate::MessageImportance::Important,
+            channel: crate::MessageChannel::Narrative,
+        })
+    }
+
+    /// Notes a fact about the run in the LLDM's memory, on the current level
+    /// and turn.
+    ///
+    /// Facts of a kind already noted about the same subject are ignored.
+    pub fn remember(&mut self, kind: MemoryKind, subject: String, text: String) {
+        self.lldm_state.memory.remember(MemoryFact {
+            kind,
+            subject,
+            depth: self.world.current_level_id + 1,
+            turn: self.turn_number,
+            text,
+        });
+    }
+
+    /// Notes the named room containing `position`, if there is one.
+    fn remember_room_at(&mut self, position: Position) {
+        let name = self
+            .world
+            .current_level()
+            .and_then(|level| level.rooms.iter().find(|room| room.contains(position)))
+            .and_then(|room| room.name.clone());
+        if let Some(name) = name {
+            let text = format!("Entered {}.", name);
+            self.remember(MemoryKind::Room, name, text);
+        }
+    }
+
+    /// Marks the floor with the aftermath of a hit or a fire.
+    ///
+    /// Melee hits spill blood under their target and anything that catches
+    /// fire scorches the ground it stands on.
+    fn leave_overlay(&mut self, event: &GameEvent) {
+        let (entity_id, overlay) = match event {
+            GameEvent::EntityDamaged {
+                entity_id,
+                source: Some(source),
+                ..
+            } => {
+                let melee = self
+                    .get_entity_position(*entity_id)
+                    .zip(self.get_entity_position(*source))
+                    .is_some_and(|(target, attacker)| target.manhattan_distance(attacker) <= 1);
+                if !melee {
+                    return;
+                }
+                (*entity_id, TileOverlay::Blood)
+            }
+            GameEvent::StatusApplied {
+                entity_id,
+                kind: StatusKind::Burning,
+                ..
+            } => {
+                // Soaked targets do not catch
+                let burning = self
+                    .entities
+                    .get(entity_id)
+                    .and_then(|entity| entity.as_entity().statuses())
+                    .is_some_and(|statuses| statuses.has(StatusKind::Burning));
+                if !burning {
+                    return;
+                }
+                (*entity_id, TileOverlay::Scorch)
+            }
+            _ => return,
+        };
+
+        let position = self.get_entity_position(entity_id);
+        if let Some((level, position)) = self.world.current_level_mut().zip(position) {
+            level.add_overlay(position, overlay);
+        }
+    }
+
+    /// Gets what an NPC says when the player talks to it.
+    ///
+    /// NPCs speak their role's script, unless the LLDM is enabled; then the
+    /// lines are generated from the conversation context the first time and
+    /// cached in the LLDM state for every later conversation.
+    pub fn npc_dialogue(&mut self, npc_id: EntityId) -> ThatchResult<Vec<String>> {
+        let npc = self
+            .entities
+            .get(&npc_id)
+            .and_then(ConcreteEntity::as_npc)
+            .ok_or_else(|| ThatchError::InvalidAction("There is nobody to talk to".to_string()))?;
+        if !self.lldm_state.enabled {
+            return Ok(npc
+                .role
+                .script()
+                .iter()
+                .map(|line| line.to_string())
+                .collect());
+        }
+
+        let key = format!("dialogue:{}", npc_id);
+        if self.lldm_state.config.use_cache {
+            if let Some(cached) = self.lldm_state.content_cache.get(&key) {
+                return Ok(cached.lines().map(str::to_string).collect());
+            }
+        }
+        let client = crate::LldmClient::new();
+        let lines = client.generate_dialogue(&client.dialogue_context(self, npc));
+        if self.lldm_state.config.use_cache {
+            self.lldm_state.content_cache.insert(key, lines.join("\n"));
+        }
+        Ok(lines)
+    }
+
+    /// Places a keeper in every shop and sanctuary of the current level.
+    ///
+    /// Each level is staffed once, the first time the player arrives.
+    fn place_npcs(&mut self) -> Vec<GameEvent> {
+        const STAFFED: &str = "npcs_placed";
+        let Some(level) = self.world.current_level_mut() else {
+            return Vec::new();
+        };
+        if level
+            .metadata
+            .insert(STAFFED.to_string(), "true".to_string())
+            .is_some()
+        {
+            return Vec::new();
+        }
+        let posts: Vec<(crate::NpcRole, Position)> = level
+            .rooms
+            .iter()
+            .filter_map(|room| Some((crate::NpcRole::for_room(&room.room_type)?, room.center())))
+            .filter(|&(_, position)| level.is_passable(position))
+            .collect();
+
+        let mut events = Vec::new();
+        for (role, position) in posts {
+            if self.get_entity_at_position(position).is_some() {
+                continue;
+            }
+            let npc = crate::Npc::new(role, position);
+            let Ok(npc_id) = self.add_entity(npc.into()) else {
+                continue;
+            };
+            if let Some(level) = self.world.current_level_mut() {
+                level.add_entity(npc_id);
+            }
+            events.push(GameEvent::EntityCreated {
+                entity_id: npc_id,
+                entity_type: crate::EntityType::Npc,
+                position,
+            });
+        }
+        events
+    }
+
+    /// Has the LLDM, when it is enabled, rename the unique items lying on the
+    /// current level.
+    ///
+    /// Each level's items are named once, the first time the player arrives.
+    /// The names are cached in the LLDM state, and any the LLDM gets wrong are
+    /// dropped in favor of the names the items were generated with; only the
+    /// name and lore ever change, never what the item does.
+    fn name_unique_items(&mut self) {
+        const NAMED: &str = "uniques_named";
+        if !self.lldm_state.enabled {
+            return;
+        }
+        let Some(level) = self.world.current_level_mut() else {
+            return;
+        };
+        if level
+            .metadata
+            .insert(NAMED.to_string(), "true".to_string())
+            .is_some()
+        {
+            return;
+        }
+
+        let client = crate::LldmClient::new();
+        let uniques: Vec<(EntityId, HashMap<String, String>)> = level
+            .ground_items
+            .iter()
+            .filter(|stack| crate::unique_template_of(&stack.item).is_some())
+            .map(|stack| (stack.item.id, client.unique_context(level, &stack.item)))
+            .collect();
+        for (item_id, context) in uniques {
+            let key = format!("unique:{}", item_id);
+            let cached = self
+                .lldm_state
+                .content_cache
+                .get(&key)
+                .filter(|_| self.lldm_state.config.use_cache)
+                .and_then(|cached| cached.split_once('\n'))
+                .map(|(name, lore)| crate::UniqueFlavor {
+                    name: name.to_string(),
+                    lore: lore.to_string(),
+                });
+            let flavor = cached.unwrap_or_else(|| client.generate_unique_flavor(&context));
+            if !flavor.is_valid() {
+                continue;
+            }
+            if self.lldm_state.config.use_cache {
+                let text = format!("{}\n{}", flavor.name, flavor.lore);
+                self.lldm_state.content_cache.insert(key, text);
+            }
+            if let Some(level) = self.world.current_level_mut() {
+                if let Some(stack) = level
+                    .ground_items
+                    .iter_mut()
+                    .find(|stack| stack.item.id == item_id)
+                {
+                    crate::reflavor_unique(&mut stack.item, &flavor);
+                }
+            }
+        }
+    }
+
+    /// Tells the player the current level's theme, if it has one, and what
+    /// feels unusual about it.
+    ///
+    /// Each level is described once, the first time the player arrives.
+    fn announce_level_feelings(&mut self) -> Vec<GameEvent> {
+        const DESCRIBED: &str = "feelings_given";
+        let Some(level) = self.world.current_level_mut() else {
+            return Vec::new();
+        };
+        if level
+            .metadata
+            .insert(DESCRIBED.to_string(), "true".to_string())
+            .is_some()
+        {
+            return Vec::new();
+        }
+        let theme = level.theme.iter().map(|theme| GameEvent::Message {
+            text: format!("You enter {}.", theme.name),
+            importance: crate::MessageImportance::Normal,
+            channel: crate::MessageChannel::Narrative,
+        });
+        let feelings = crate::level_feelings(level)
+            .into_iter()
+            .map(|feeling| GameEvent::Message {
+                text: feeling.message().to_string(),
+                importance: feeling.importance(),
+                channel: crate::MessageChannel::Exploration,
+            });
+        theme.chain(feelings).collect()
+    }
+
+    /// Spawns the monsters the generator left waiting on the current level.
+    ///
+    /// They wait with the level until the player first arrives, and are
+    /// spawned once.
+    fn place_waiting_monsters(&mut self) -> Vec<GameEvent> {
+        let Some(level) = self.world.current_level_mut() else {
+            return Vec::new();
+        };
+        let waiting = std::mem::take(&mut level.waiting_monsters);
+
+        let mut events = Vec::new();
+        for waiting_monster in waiting {
+            if self
+                .get_entity_at_position(waiting_monster.position)
+                .is_some()
+            {
+                continue;
+            }
+            let monster = waiting_monster.monster.spawn(waiting_monster.position);
+            let species = monster.monster_type.clone();
+            let Ok(monster_id) = self.add_entity(monster.into()) else {
+                continue;
+            };
+            if let Some(level) = self.world.current_level_mut() {
+                level.add_entity(monster_id);
+            }
+            events.push(GameEvent::EntityCreated {
+                entity_id: monster_id,
+                entity_type: crate::EntityType::Monster(species),
+                position: waiting_monster.position,
+            });
+        }
+        events
+    }
+
+    /// Drops a dead monster's corpse and rolled loot where it fell.
+    ///
+    /// Returns an [`GameEvent::ItemDropped`] for everything left behind; other
+    /// entities leave nothing.
+    fn drop_remains(&mut self, entity_id: EntityId) -> Vec<GameEvent> {
+        let Some(ConcreteEntity::Monster(monster)) = self.entities.get(&entity_id) else {
+            return Vec::new();
+        };
+        let position = monster.position;
+        let mut remains = vec![crate::corpse_of(monster, position)];
+        remains.extend(crate::roll_loot(&monster.loot, &mut self.rng, position));
 
-        // Regenerate level 0
-        self.generate_level(0)?;
+        let Some(level) = self.world.current_level_mut() else {
+            return Vec::new();
+        };
+        remains
+            .into_iter()
+            .map(|item| {
+                let event = GameEvent::ItemDropped {
+                    item_id: item.id,
+                    dropper_id: entity_id,
+                    position,
+                };
+                level.ground_items.drop_item(item);
+                event
+            })
+            .collect()
+    }
 
-        // Reset player position to spawn
-        if let Some(player_id) = self.player_id {
-            let spawn_pos = if let Some(level) = self.world.current_level() {
-                level.player_spawn
-            } else {
-                Position::new(0, 0)
-            };
+    /// Gets the container on or next to `origin` on the current level, if any.
+    pub fn container_within_reach(&self, origin: Position) -> Option<&crate::Container> {
+        self.world
+            .current_level()?
+            .containers
+            .iter()
+            .find(|container| {
+                container.position.manhattan_distance(origin) <= crate::CONTAINER_REACH
+            })
+    }
 
-            let old_pos = if let Some(player) = self.get_player() {
-                player.position()
-            } else {
-                spawn_pos // fallback
-            };
+    /// Gets the positions of visible stairs, hazards and other features on
+    /// the current level.
+    ///
+    /// Only tiles inside the player's [`FieldOfView`] can be visible, so only
+    /// those are looked at rather than the whole floor.
+    pub fn visible_features(&self) -> HashSet<Position> {
+        let Some(level) = self.world.current_level() else {
+            return HashSet::new();
+        };
+        let Some(view) = self
+            .field_of_view
+            .as_ref()
+            .filter(|view| view.level_id == level.id)
+        else {
+            return HashSet::new();
+        };
 
-            self.remove_entity_from_position_index(player_id, old_pos);
-            if let Some(player) = self.get_player_mut() {
-                player.set_position(spawn_pos);
-            }
-            self.add_entity_to_position_index(player_id, spawn_pos);
+        view.area()
+            .filter(|&position| {
+                level
+                    .get_tile(position)
+                    .is_some_and(|tile| tile.is_visible() && tile.tile_type.is_feature())
+            })
+            .collect()
+    }
+
+    /// Gets the ids of items on visible tiles of the current level, whether
+    /// they lie on the floor or are item entities.
+    pub fn visible_items(&self) -> HashSet<EntityId> {
+        let Some(level) = self.world.current_level() else {
+            return HashSet::new();
+        };
+        let is_visible = |pos: Position| level.get_tile(pos).is_some_and(|tile| tile.is_visible());
+
+        let ground = level
+            .ground_items
+            .iter()
+            .filter(|stack| is_visible(stack.item.position))
+            .map(|stack| stack.item.id);
+        level
+            .entities
+            .iter()
+            .filter(|&&id| {
+                self.entities.get(&id).is_some_and(|entity| {
+                    matches!(entity.as_entity().entity_type(), crate::EntityType::Item(_))
+                })
+            })
+            .filter(|&&id| self.get_entity_position(id).is_some_and(is_visible))
+            .copied()
+            .chain(ground)
+            .collect()
+    }
+
+    /// Stops travel if the player is confused or a hostile has come into view
+    /// since travel started.
+    ///
+    /// Returns the interruption message to show the player, if any.
+    pub fn check_travel_interruption(&mut self) -> Option<String> {
+        if !self.travel_state.is_active() {
+            return None;
         }
 
-        // Reset game state
-        self.completion_state = GameCompletionState::Playing;
-        self.turn_number = 0;
-        self.statistics = GameStatistics::new();
-        self.game_start_time = Some(Instant::now());
+        if self.player_is_confused() {
+            self.travel_state.cancel();
+            Some("You are too confused to find your way.".to_string())
+        } else if self.spotted_new_hostile(&self.travel_state.known_hostiles) {
+            self.travel_state.cancel();
+            Some("You spot something nearby and stop travelling.".to_string())
+        } else {
+            None
+        }
+    }
 
-        Ok(())
+    /// Returns true if a hostile not among `known_hostiles` is in view.
+    fn spotted_new_hostile(&self, known_hostiles: &HashSet<EntityId>) -> bool {
+        self.visible_hostiles()
+            .iter()
+            .any(|id| !known_hostiles.contains(id))
     }
 
-    /// Checks if the game has ended.
-    pub fn is_game_ended(&self) -> bool {
-        self.completion_state != GameCompletionState::Playing
+    /// Cancels any travel in progress.
+    pub fn cancel_travel(&mut self) {
+        self.travel_state.cancel();
     }
 
-    /// Gets the current completion state.
-    pub fn get_completion_state(&self) -> &GameCompletionState {
-        &self.completion_state
+    /// Checks if the player is currently travelling.
+    pub fn is_travelling(&self) -> bool {
+        self.travel_state.is_active()
     }
 
-    /// Toggles autoexplore debug mode.
-    pub fn toggle_autoexplore(&mut self) -> bool {
-        self.autoexplore_state.toggle()
+    /// Starts taking a movement or wait action `count` times.
+    ///
+    /// Like autoexplore, the repeat stops when a hostile or item comes into view.
+    pub fn start_repeat(&mut self, action: ConcreteAction, count: u32) {
+        let (hostiles, items) = (self.visible_hostiles(), self.visible_items());
+        self.repeat_state.start(action, count, hostiles, items);
     }
 
-    /// Gets the next autoexplore action if enabled and ready.
-    pub fn get_autoexplore_action(&mut self) -> ThatchResult<Option<crate::ConcreteAction>> {
-        if !self.autoexplore_state.enabled || !self.autoexplore_state.can_perform_action() {
-            return Ok(None);
+    /// Stops repeating if a hostile or item has come into view since the
+    /// repeat started, or a feature since a run started, or a repeated walk if
+    /// the player is confused.
+    ///
+    /// Returns the interruption message to show the player, if any.
+    pub fn check_repeat_interruption(&mut self) -> Option<String> {
+        if !self.repeat_state.is_active() {
+            return None;
         }
 
-        let player = self
-            .get_player()
+        let walking = matches!(self.repeat_state.action, Some(ConcreteAction::Move(_)));
+        if walking && self.player_is_confused() {
+            self.repeat_state.cancel();
+            Some("You are too confused to keep walking.".to_string())
+        } else if let Some(interruption) = self.repeat_state.watch.check(self) {
+            self.repeat_state.cancel();
+            Some(format!("You stop as {}.", interruption.reason()))
+        } else {
+            None
+        }
+    }
+
+    /// Cancels any repeat in progress.
+    pub fn cancel_repeat(&mut self) {
+        self.repeat_state.cancel();
+    }
+
+    /// Checks if the player is currently repeating a command.
+    pub fn is_repeating(&self) -> bool {
+        self.repeat_state.is_active()
+    }
+
+    /// Starts running in `direction`, walking on until something is worth
+    /// stopping for.
+    ///
+    /// Like a repeat, the run stops when a hostile or item comes into view,
+    /// and it stops for new features as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no player.
+    pub fn start_run(&mut self, direction: Direction) -> ThatchResult<()> {
+        let player_id = self
+            .player_id
             .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
-        let player_pos = player.position();
-        let player_id = player.id();
+        let step = ConcreteAction::Move(MoveAction::new(player_id, direction));
+        let (hostiles, items) = (self.visible_hostiles(), self.visible_items());
+        let features = self.visible_features();
+        self.repeat_state.start_run(step, hostiles, items, features);
+        Ok(())
+    }
 
-        // Check if we're already on stairs down
-        if let Some(level) = self.world.current_level() {
-            if let Some(tile) = level.get_tile(player_pos) {
-                if tile.tile_type == TileType::StairsDown {
-                    // We're on stairs down, use them
-                    self.autoexplore_state.mark_action_performed();
-                    return Ok(Some(crate::ConcreteAction::UseStairs(
-                        UseStairsAction::new(player_id, StairDirection::Down),
-                    )));
+    /// Gets the next repeat of the command, if one is active and ready.
+    ///
+    /// Each step of a run after the first goes the way [`run_direction`]
+    /// says, and the run ends where it says to stop.
+    ///
+    /// [`run_direction`]: crate::run_direction
+    pub fn get_repeat_action(&mut self) -> Option<ConcreteAction> {
+        if !self.repeat_state.can_perform_action() {
+            return None;
+        }
+        if self.repeat_state.running && self.repeat_state.last_action_time.is_some() {
+            let heading = match &self.repeat_state.action {
+                Some(ConcreteAction::Move(step)) => Some(step.direction),
+                _ => None,
+            };
+            let position = self.get_player().map(|player| player.position());
+            let next = heading
+                .zip(position)
+                .zip(self.world.current_level())
+                .and_then(|((heading, position), level)| {
+                    crate::run_direction(level, position, heading)
+                });
+            match (next, &mut self.repeat_state.action) {
+                (Some(direction), Some(ConcreteAction::Move(step))) => step.direction = direction,
+                _ => {
+                    self.repeat_state.cancel();
+                    return None;
                 }
             }
         }
+        self.repeat_state.next_action()
+    }
 
-        // If we have a current path, follow it
-        if !self.autoexplore_state.current_path.is_empty() {
-            let next_pos = self.autoexplore_state.current_path.remove(0);
-            if let Some(direction) = self.get_direction_to_position(player_pos, next_pos) {
-                self.autoexplore_state.mark_action_performed();
-                return Ok(Some(crate::ConcreteAction::Move(MoveAction {
-                    actor: player_id,
-                    direction,
-                    metadata: HashMap::new(),
-                })));
-            }
-            // Path is invalid, clear it
-            self.autoexplore_state.current_path.clear();
+    /// Starts resting until recovered, interrupted, or the turn cap is reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidAction` if the player is already fully recovered or a
+    /// hostile is in view.
+    pub fn start_rest(&mut self) -> ThatchResult<()> {
+        let player = self
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        if player.stats.is_fully_recovered() {
+            return Err(ThatchError::InvalidAction(
+                "You are already fully rested".to_string(),
+            ));
+        }
+        if !self.visible_hostiles().is_empty() {
+            return Err(ThatchError::InvalidAction(
+                "You can't rest with enemies in view".to_string(),
+            ));
         }
 
-        // We need a new path - find stairs down
-        if let Some(stairs_down_pos) = self.find_stairs_down() {
-            if let Some(path) = self.autoexplore_find_path(player_pos, stairs_down_pos)? {
-                self.autoexplore_state.current_path = path;
-                self.autoexplore_state.target = Some(stairs_down_pos);
+        let items = self.visible_items();
+        self.rest_state.active = true;
+        self.rest_state.turns_rested = 0;
+        self.rest_state.watch.start(HashSet::new(), items);
+        Ok(())
+    }
 
-                // Return the first move in the path
-                if !self.autoexplore_state.current_path.is_empty() {
-                    let next_pos = self.autoexplore_state.current_path.remove(0);
-                    if let Some(direction) = self.get_direction_to_position(player_pos, next_pos) {
-                        self.autoexplore_state.mark_action_performed();
-                        return Ok(Some(crate::ConcreteAction::Move(MoveAction {
-                            actor: player_id,
-                            direction,
-                            metadata: HashMap::new(),
-                        })));
-                    }
-                }
-            }
+    /// Stops resting if the player has recovered, the turn cap is reached, or
+    /// something needs the player's attention.
+    ///
+    /// Returns the message reporting how many turns passed, if resting stopped.
+    pub fn check_rest_completion(&mut self) -> Option<String> {
+        if !self.rest_state.active {
+            return None;
         }
 
-        // No stairs down found or no path available
-        Ok(None)
+        let outcome = if let Some(interruption) = self.rest_state.watch.check(self) {
+            RestOutcome::Interrupted(interruption)
+        } else if self
+            .get_player()
+            .is_none_or(|player| player.stats.is_fully_recovered())
+        {
+            RestOutcome::Recovered
+        } else if self.rest_state.turns_rested >= crate::REST_TURN_CAP {
+            RestOutcome::TurnCap
+        } else {
+            return None;
+        };
+        Some(self.rest_state.finish(outcome))
     }
 
-    /// Helper method to get direction between positions for autoexplore.
-    fn get_direction_to_position(&self, from: Position, to: Position) -> Option<Direction> {
-        let delta = to - from;
-        Direction::from_delta(delta)
+    /// Cancels any rest in progress.
+    pub fn cancel_rest(&mut self) {
+        self.rest_state.cancel();
     }
 
-    /// Helper method to find stairs down position for autoexplore.
-    fn find_stairs_down(&self) -> Option<Position> {
-        let level = self.world.current_level()?;
-        level.stairs_down_position
+    /// Checks if the player is currently resting.
+    pub fn is_resting(&self) -> bool {
+        self.rest_state.active
     }
 
-    /// Helper method for autoexplore pathfinding.
-    fn autoexplore_find_path(
-        &self,
-        start: Position,
-        goal: Position,
-    ) -> ThatchResult<Option<Vec<Position>>> {
-        let level = self
+    /// Gets the next turn of rest as an action, if the player is resting.
+    pub fn get_rest_action(&mut self) -> Option<ConcreteAction> {
+        if !self.rest_state.active {
+            return None;
+        }
+        let player_id = self.player_id?;
+        self.rest_state.turns_rested += 1;
+        Some(ConcreteAction::Rest(crate::RestAction::new(player_id)))
+    }
+
+    /// Starts fighting the nearest visible hostile within
+    /// [`FIGHT_RANGE`](crate::FIGHT_RANGE) tiles.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidAction` if there is no hostile in view to fight.
+    pub fn start_fight(&mut self) -> ThatchResult<()> {
+        let player_pos = self
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?
+            .position();
+        let target = self
+            .nearest_visible_hostile(player_pos, crate::FIGHT_RANGE)
+            .ok_or_else(|| {
+                ThatchError::InvalidAction("There is nothing in view to fight".to_string())
+            })?;
+
+        let (hostiles, items) = (self.visible_hostiles(), self.visible_items());
+        self.fight_state.cancel();
+        self.fight_state.target = Some(target);
+        self.fight_state.watch.start(hostiles, items);
+        Ok(())
+    }
+
+    /// Stops fighting once the foe is dead or out of sight, or if the player
+    /// is confused or something else needs their attention.
+    ///
+    /// Returns the message explaining why the fight stopped, if it did and
+    /// the foe's death does not already say so.
+    pub fn check_fight_interruption(&mut self) -> Option<String> {
+        let target = self.fight_state.target?;
+        let message = if !self.is_entity_alive(target) {
+            None
+        } else if !self.visible_hostiles().contains(&target) {
+            Some("You lose sight of your foe.".to_string())
+        } else if self.player_is_confused() {
+            Some("You are too confused to fight on your own.".to_string())
+        } else if let Some(interruption) = self.fight_state.watch.check(self) {
+            Some(format!("You stop fighting: {}.", interruption.reason()))
+        } else {
+            return None;
+        };
+        self.fight_state.cancel();
+        message
+    }
+
+    /// Cancels any fight in progress.
+    pub fn cancel_fight(&mut self) {
+        self.fight_state.cancel();
+    }
+
+    /// Checks if the player is currently auto-fighting.
+    pub fn is_fighting(&self) -> bool {
+        self.fight_state.is_active()
+    }
+
+    /// Gets the next blow or step of the fight, if one is active and ready.
+    ///
+    /// The player attacks a foe next to them and otherwise steps along a path
+    /// towards it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidAction` and stops fighting if the foe cannot be reached.
+    pub fn get_fight_action(&mut self) -> ThatchResult<Option<ConcreteAction>> {
+        let Some(target) = self.fight_state.target else {
+            return Ok(None);
+        };
+        if !self.fight_state.can_perform_action() {
+            return Ok(None);
+        }
+        let player_id = self
+            .player_id
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let (Some(from), Some(to)) = (
+            self.get_entity_position(player_id),
+            self.get_entity_position(target),
+        ) else {
+            self.fight_state.cancel();
+            return Ok(None);
+        };
+        self.fight_state.last_action_time = Some(Instant::now());
+
+        if from.manhattan_distance(to) == 1 {
+            return Ok(Some(ConcreteAction::Attack(crate::AttackAction::new(
+                player_id, target,
+            ))));
+        }
+        let step = self
             .world
             .current_level()
-            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
-
-        // A* algorithm implementation
-        let mut open_set = BinaryHeap::new();
-        let mut came_from = HashMap::new();
-        let mut g_score = HashMap::new();
-        let mut f_score = HashMap::new();
-
-        g_score.insert(start, 0.0);
-        f_score.insert(start, start.euclidean_distance(goal));
-        open_set.push(crate::autoexplore::AStarNode {
-            position: start,
-            f_score: start.euclidean_distance(goal),
-        });
+            .and_then(|level| crate::PathfindingUtils::new().find_path(level, from, to))
+            .and_then(|path| path.first().copied())
+            .filter(|&step| self.get_entity_at_position(step).is_none())
+            .and_then(|step| self.get_direction_to_position(from, step));
+        match step {
+            Some(direction) => Ok(Some(ConcreteAction::Move(MoveAction::new(
+                player_id, direction,
+            )))),
+            None => {
+                self.fight_state.cancel();
+                Err(ThatchError::InvalidAction(
+                    "There is no way to reach your foe".to_string(),
+                ))
+            }
+        }
+    }
 
-        while let Some(current_node) = open_set.pop() {
-            let current = current_node.position;
+    /// Gets the next travel step as a move action, if travel is active and ready.
+    pub fn get_travel_action(&mut self) -> ThatchResult<Option<ConcreteAction>> {
+        if !self.travel_state.is_active() || !self.travel_state.can_perform_action() {
+            return Ok(None);
+        }
 
-            if current == goal {
-                // Reconstruct path
-                let mut path = Vec::new();
-                let mut current_pos = goal;
+        let player = self
+            .get_player()
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+        let player_pos = player.position();
+        let player_id = player.id();
 
-                while let Some(&prev) = came_from.get(&current_pos) {
-                    path.push(current_pos);
-                    current_pos = prev;
-                }
+        let Some(next_pos) = self.travel_state.next_step(player_pos) else {
+            return Ok(None);
+        };
 
-                path.reverse();
-                return Ok(Some(path));
+        let step = self
+            .get_direction_to_position(player_pos, next_pos)
+            .and_then(|direction| self.route_step(player_id, player_pos, direction));
+        match step {
+            Some(action) => Ok(Some(action)),
+            None => {
+                self.travel_state.cancel();
+                Err(ThatchError::InvalidState(
+                    "Travel path is no longer valid".to_string(),
+                ))
             }
+        }
+    }
 
-            // Check all adjacent positions
-            for neighbor in current.adjacent_positions() {
-                if !level.is_valid_position(neighbor) {
-                    continue;
-                }
+    /// Describes what the player knows about a position, for the examine and look commands.
+    ///
+    /// Unexplored positions yield a generic message; entities are only described
+    /// when the tile is currently visible. Positions inside a room also include
+    /// the room's name, description, and any LLDM flavor text.
+    pub fn describe_position(&self, position: Position) -> String {
+        let Some(level) = self.world.current_level() else {
+            return "There is nothing there.".to_string();
+        };
+        let Some(tile) = level.get_tile(position) else {
+            return "There is nothing there.".to_string();
+        };
 
-                // Check if tile is passable
-                let tile = level.get_tile(neighbor).unwrap();
-                if !tile.tile_type.is_passable() {
-                    continue;
-                }
+        if !tile.is_explored() {
+            return "You haven't seen that place yet.".to_string();
+        }
 
-                // Check if there's an entity blocking the path (except at goal)
-                if neighbor != goal && self.get_entity_at_position(neighbor).is_some() {
-                    continue;
-                }
+        let mut description = format!(
+            "{}: {}",
+            tile.tile_type.display_name(),
+            tile.tile_type.description()
+        );
 
-                let tentative_g_score = g_score.get(&current).unwrap_or(&f64::INFINITY) + 1.0;
+        if let Some(source) = level.light_source_at(position) {
+            description.push_str(&format!(
+                " A {} glows here.",
+                source.kind.display_name().to_lowercase()
+            ));
+        }
 
-                if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&f64::INFINITY) {
-                    came_from.insert(neighbor, current);
-                    g_score.insert(neighbor, tentative_g_score);
-                    let f = tentative_g_score + neighbor.euclidean_distance(goal);
-                    f_score.insert(neighbor, f);
+        if let Some(overlay) = tile.overlay {
+            description.push(' ');
+            description.push_str(overlay.description());
+        }
 
-                    // Add to open set if not already there with a better score
-                    open_set.push(crate::autoexplore::AStarNode {
-                        position: neighbor,
-                        f_score: f,
-                    });
+        if let Some(container) = level.container_at(position) {
+            let state = if container.opened { "open" } else { "closed" };
+            description.push_str(&format!(" A {} {} sits here.", state, container.name));
+        }
+
+        if tile.is_visible() {
+            for entity_id in self.get_entities_at_position(position) {
+                if let Some(entity) = self.entities.get(&entity_id) {
+                    if Some(entity_id) == self.player_id {
+                        description.push_str(" You are standing here.");
+                    } else {
+                        description.push_str(&format!(" {} is here.", entity.name()));
+                    }
                 }
             }
+        } else {
+            description.push_str(" (remembered)");
+        }
+
+        if let Some(room) = level.rooms.iter().find(|room| room.contains(position)) {
+            if let Some(name) = &room.name {
+                description.push_str(&format!(" This is {}.", name));
+            }
+            for text in [room.description.as_deref(), room.flavor_text()]
+                .into_iter()
+                .flatten()
+            {
+                description.push(' ');
+                description.push_str(text);
+            }
         }
 
-        Ok(None) // No path found
+        description
     }
+}
 
-    /// Checks if autoexplore is currently enabled.
-    pub fn is_autoexplore_enabled(&self) -> bool {
-        self.autoexplore_state.enabled
+/// Summary of a finished (or in-progress) run for the ending screen.
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    /// Statistics gathered during the run
+    pub statistics: GameStatistics,
+    /// Turns taken
+    pub turns: u64,
+    /// Total play time in seconds
+    pub play_time_seconds: u64,
+    /// World seed, for sharing and replaying the run
+    pub seed: u64,
+    /// Points the run has earned
+    pub score: Score,
+    /// Epitaph the LLDM wrote, if the player died and it has come back
+    pub epitaph: Option<String>,
+}
+
+impl RunSummary {
+    /// Formats the play time as `H:MM:SS`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{GameState, RunSummary};
+    ///
+    /// let mut summary = GameState::new(1).run_summary();
+    /// summary.play_time_seconds = 3725;
+    /// assert_eq!(summary.formatted_play_time(), "1:02:05");
+    /// ```
+    pub fn formatted_play_time(&self) -> String {
+        let hours = self.play_time_seconds / 3600;
+        let minutes = (self.play_time_seconds % 3600) / 60;
+        let seconds = self.play_time_seconds % 60;
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
     }
 }
 
@@ -1112,7 +4447,11 @@ impl Default for LldmState {
                 temperature: 0.7,
                 max_tokens: 1000,
                 use_cache: true,
+                requests: LldmRequestPolicy::default(),
             },
+            memory: LldmMemory::new(),
+            telemetry: LldmTelemetry::default(),
+            gate: LldmRequestGate::default(),
         }
     }
 }
@@ -1120,7 +4459,7 @@ impl Default for LldmState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Position;
+    use crate::{Position, Tile};
 
     #[test]
     fn test_game_state_creation() {
@@ -1145,6 +4484,38 @@ mod tests {
         assert_eq!(game_state.get_entity_position(player_id), Some(position));
     }
 
+    #[test]
+    fn test_death_asks_the_lldm_for_an_epitaph() {
+        let mut game_state = GameState::new(12345);
+        game_state.lldm_state.enabled = true;
+        game_state.lldm_state.config.endpoint = Some("http://localhost:8080".to_string());
+        let player_id = game_state
+            .initialize_player("Ada".to_string(), Position::new(5, 5))
+            .unwrap();
+
+        game_state
+            .process_event(&GameEvent::EntityDied {
+                entity_id: player_id,
+                killer: None,
+            })
+            .unwrap();
+        let request = &game_state.lldm_state.pending_requests[0];
+        assert_eq!(request.request_type, crate::EPITAPH);
+        assert_eq!(request.context["character_name"], "Ada");
+        assert_eq!(request.context["cause_of_death"], "the dungeon");
+        assert_eq!(game_state.run_summary().epitaph, None);
+
+        // The completion is cached under the request's ID once it is back
+        game_state.lldm_state.content_cache.insert(
+            request.id.clone(),
+            "{\"epitaph\": \"She mapped every room but her own.\"}".to_string(),
+        );
+        assert_eq!(
+            game_state.run_summary().epitaph.as_deref(),
+            Some("She mapped every room but her own.")
+        );
+    }
+
     #[test]
     fn test_entity_position_management() {
         let mut game_state = GameState::new(12345);
@@ -1170,6 +4541,67 @@ mod tests {
         assert_eq!(game_state.get_entity_at_position(new_pos), Some(player_id));
     }
 
+    #[test]
+    fn test_water_soaks_and_drowns_player() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .set_tile(position, crate::Tile::new(TileType::Water))
+            .unwrap();
+        let player_id = game_state
+            .initialize_player("Swimmer".to_string(), position)
+            .unwrap();
+
+        game_state.advance_turn().unwrap();
+        assert!(game_state
+            .get_player()
+            .unwrap()
+            .statuses
+            .has(StatusKind::Soaked));
+
+        let drowned = (0..crate::config::BREATH_TURNS * 2)
+            .flat_map(|_| game_state.advance_turn().unwrap())
+            .any(|event| matches!(event, GameEvent::EntityDamaged { entity_id, .. } if entity_id == player_id));
+        assert!(drowned);
+    }
+
+    #[test]
+    fn test_water_ruins_scrolls_and_ring_lets_player_breathe() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .set_tile(position, crate::Tile::new(TileType::Water))
+            .unwrap();
+        let player_id = game_state
+            .initialize_player("Swimmer".to_string(), position)
+            .unwrap();
+        let scroll = Item::new(
+            crate::TELEPORT_SCROLL.to_string(),
+            ItemType::Consumable(ConsumableType::Scroll),
+            position,
+        );
+        let scroll_id = game_state.give_item_to_player(scroll, false).unwrap();
+        let ring = Item::new(
+            WATER_BREATHING_RING.to_string(),
+            ItemType::Armor(crate::ArmorType::Ring),
+            position,
+        );
+        game_state.give_item_to_player(ring, true).unwrap();
+        assert!(game_state.player_can_breathe_water());
+
+        let drowned = (0..crate::config::BREATH_TURNS * 10)
+            .flat_map(|_| game_state.advance_turn().unwrap())
+            .any(|event| matches!(event, GameEvent::EntityDamaged { entity_id, .. } if entity_id == player_id));
+        assert!(!drowned);
+        assert_eq!(game_state.carried_item_name(scroll_id), Some(BLANK_SCROLL));
+    }
+
     #[test]
     fn test_turn_advancement() {
         let mut game_state = GameState::new(12345);
@@ -1232,6 +4664,231 @@ mod tests {
         let _loaded_state = GameState::load_from_json(&json).unwrap();
     }
 
+    #[test]
+    fn test_spatial_index_rebuilt_after_load() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        let player_id = game_state
+            .initialize_player("Saved".to_string(), position)
+            .unwrap();
+
+        let json = game_state.save_to_json().unwrap();
+        let loaded = GameState::load_from_json(&json).unwrap();
+
+        assert_eq!(loaded.get_entity_at_position(position), Some(player_id));
+        assert!(loaded.validate_consistency().is_empty());
+    }
+
+    #[test]
+    fn test_class_loadout_survives_save() {
+        let mut game_state = GameState::new(12345);
+        game_state
+            .initialize_player_with_class(
+                "Merlin".to_string(),
+                Position::new(5, 5),
+                CharacterClass::Mage,
+            )
+            .unwrap();
+
+        let json = game_state.save_to_json().unwrap();
+        let loaded = GameState::load_from_json(&json).unwrap();
+        let player = loaded.get_player().unwrap();
+
+        assert_eq!(player.class, CharacterClass::Mage);
+        assert_eq!(
+            player.stats.max_mana,
+            CharacterClass::Mage.starting_stats().max_mana
+        );
+        let weapon = *player.equipment.get("weapon").unwrap();
+        assert_eq!(loaded.carried_item_name(weapon), Some("Oak staff"));
+        assert_eq!(
+            player.inventory.len() + player.equipment.len(),
+            CharacterClass::Mage.starting_items().len()
+        );
+        assert!(loaded.validate_consistency().is_empty());
+    }
+
+    #[test]
+    fn test_validate_consistency_reports_drift() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        let stale = Position::new(9, 9);
+        let player_id = game_state
+            .initialize_player("Drifter".to_string(), position)
+            .unwrap();
+        let ghost_id = crate::new_entity_id();
+
+        game_state.position_index.clear();
+        game_state.position_index.insert(stale, vec![player_id]);
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(ghost_id);
+
+        let issues = game_state.validate_consistency();
+        assert!(issues.contains(&ConsistencyIssue::NotIndexed {
+            entity_id: player_id,
+            position,
+        }));
+        assert!(issues.contains(&ConsistencyIssue::StaleIndexEntry {
+            entity_id: player_id,
+            position: stale,
+        }));
+        assert!(issues.contains(&ConsistencyIssue::UnknownLevelEntity {
+            entity_id: ghost_id,
+            level_id: 0,
+        }));
+
+        game_state.rebuild_spatial_index();
+        assert_eq!(game_state.validate_consistency().len(), 1);
+    }
+
+    #[test]
+    fn test_rest_until_recovered() {
+        let mut game_state = GameState::new(12345);
+        game_state
+            .initialize_player("Rester".to_string(), Position::new(5, 5))
+            .unwrap();
+        assert!(game_state.start_rest().is_err());
+
+        game_state.get_player_mut().unwrap().stats.health = 90;
+        game_state.start_rest().unwrap();
+
+        let mut report = None;
+        while report.is_none() {
+            let action = game_state.get_rest_action().unwrap();
+            action.execute(&mut game_state).unwrap();
+            report = game_state.check_rest_completion();
+        }
+
+        assert!(!game_state.is_resting());
+        assert!(game_state.get_player().unwrap().stats.is_fully_recovered());
+        assert!(report.unwrap().contains("5 turns"));
+    }
+
+    #[test]
+    fn test_lazy_world_generates_floors_on_demand() {
+        let config = GenerationConfig::new(31);
+        let mut game_state = GameState::new_lazy_with_generation_config(config).unwrap();
+        assert_eq!(
+            game_state.world.level_count() as u32,
+            crate::LAZY_INITIAL_FLOORS
+        );
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        game_state
+            .initialize_player("Delver".to_string(), spawn)
+            .unwrap();
+
+        for depth in 1..=3 {
+            assert!(game_state.use_stairs(StairDirection::Down).unwrap());
+            assert_eq!(game_state.world.current_level_id, depth);
+        }
+        assert_eq!(game_state.world.level_count(), 4);
+        let above = game_state.world.get_level(2).unwrap().stairs_down_position;
+        assert_eq!(
+            game_state.world.current_level().unwrap().stairs_up_position,
+            above
+        );
+
+        // Saved games remember how to generate the floors still missing, without
+        // storing the settings themselves
+        let json = serde_json::to_string(&game_state).unwrap();
+        assert!(!json.contains("depth_table"));
+        let mut restored = GameState::load_from_json(&json).unwrap();
+        for game in [&mut game_state, &mut restored] {
+            let config = game.lazy_generation.clone().unwrap();
+            crate::extend_world(&config, &mut game.world, 6).unwrap();
+        }
+        assert_eq!(
+            restored.world.levels[&6].stairs_down_position,
+            game_state.world.levels[&6].stairs_down_position
+        );
+    }
+
+    #[test]
+    fn test_escaping_with_artifact_wins() {
+        use crate::Action;
+
+        let mut level = Level::new(0, 10, 10);
+        let stairs = Position::new(2, 2);
+        level
+            .set_tile(stairs, crate::Tile::new(TileType::StairsUp))
+            .unwrap();
+        let mut game_state = GameState::new_with_level(level, 12345).unwrap();
+        let player_id = game_state
+            .initialize_player("Bearer".to_string(), stairs)
+            .unwrap();
+        let amulet = crate::Item::new(
+            ARTIFACT_NAME.to_string(),
+            crate::ItemType::QuestItem,
+            stairs,
+        );
+        game_state.drop_item_on_ground(amulet.clone()).unwrap();
+
+        crate::PickUpAction::new(player_id, amulet.id)
+            .execute(&mut game_state)
+            .unwrap();
+        game_state
+            .process_event(&GameEvent::ItemPickedUp {
+                item_id: amulet.id,
+                picker_id: player_id,
+            })
+            .unwrap();
+        assert!(game_state.player_has_artifact());
+        assert_eq!(game_state.statistics.artifact_found_turn, Some(0));
+        assert_eq!(
+            game_state.stairs_ending(&StairDirection::Up),
+            Some(GameCompletionState::EscapedWithArtifact)
+        );
+
+        assert!(!game_state.use_stairs(StairDirection::Up).unwrap());
+        assert_eq!(
+            game_state.completion_state,
+            GameCompletionState::EscapedWithArtifact
+        );
+    }
+
+    #[test]
+    fn test_stairs_ending_on_first_level() {
+        let mut level = Level::new(0, 10, 10);
+        let stairs = Position::new(2, 2);
+        level
+            .set_tile(stairs, crate::Tile::new(TileType::StairsUp))
+            .unwrap();
+        level
+            .set_tile(Position::new(3, 2), crate::Tile::floor())
+            .unwrap();
+        let mut game_state = GameState::new_with_level(level, 12345).unwrap();
+        game_state
+            .initialize_player("Quitter".to_string(), stairs)
+            .unwrap();
+
+        assert_eq!(
+            game_state.stairs_ending(&StairDirection::Up),
+            Some(GameCompletionState::EscapedEarly)
+        );
+        assert_eq!(game_state.stairs_ending(&StairDirection::Down), None);
+
+        game_state.update_player_visibility(stairs).unwrap();
+        assert_eq!(game_state.explored_percentage(), 100);
+    }
+
+    #[test]
+    fn test_new_with_generation_config_uses_generator() {
+        let config = GenerationConfig {
+            generator: GeneratorKind::Bsp,
+            ..GenerationConfig::new(777)
+        };
+        let game_state = GameState::new_with_generation_config(config).unwrap();
+
+        assert_eq!(game_state.generator, GeneratorKind::Bsp);
+        let level = game_state.world.get_level(0).unwrap();
+        for (i, a) in level.rooms.iter().enumerate() {
+            assert!(level.rooms[i + 1..].iter().all(|b| !a.overlaps(b)));
+        }
+    }
+
     #[test]
     fn test_3d_dungeon_initialization() {
         let seed = 12345;
@@ -1271,10 +4928,13 @@ mod tests {
         let mut game_state = GameState::new_with_complete_dungeon(seed).unwrap();
 
         // Create and add player
-        let player_entity = ConcreteEntity::Player(PlayerCharacter::new("TestHero".to_string()));
+        let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+            "TestHero".to_string(),
+            Position::new(0, 0),
+        ));
         let player_id = player_entity.id();
         game_state.add_entity(player_entity).unwrap();
-        game_state.set_player(player_id).unwrap();
+        game_state.set_player_id(player_id);
 
         // Start on level 0
         assert_eq!(game_state.world.current_level_id, 0);
@@ -1284,10 +4944,19 @@ mod tests {
         assert!(level_changed, "Should successfully change levels");
         assert_eq!(game_state.world.current_level_id, 1);
 
-        // Use stairs up to go back to level 0
+        // Use stairs up to go back to level 0, arriving on the stairs just climbed
         let level_changed = game_state.use_stairs(StairDirection::Up).unwrap();
         assert!(level_changed, "Should successfully change levels");
         assert_eq!(game_state.world.current_level_id, 0);
+        let stairs_down = game_state
+            .world
+            .current_level()
+            .unwrap()
+            .stairs_down_position;
+        assert_eq!(
+            game_state.get_player().map(|player| player.position()),
+            stairs_down
+        );
 
         // Try to go up from level 0 (should trigger escape ending)
         let level_changed = game_state.use_stairs(StairDirection::Up).unwrap();
@@ -1306,21 +4975,24 @@ mod tests {
         let mut game_state = GameState::new_with_complete_dungeon(seed).unwrap();
 
         // Create and add player
-        let player_entity = ConcreteEntity::Player(PlayerCharacter::new("TestHero".to_string()));
+        let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+            "TestHero".to_string(),
+            Position::new(0, 0),
+        ));
         let player_id = player_entity.id();
         game_state.add_entity(player_entity).unwrap();
-        game_state.set_player(player_id).unwrap();
+        game_state.set_player_id(player_id);
 
         // Go to level 25
         game_state.world.change_level(25).unwrap();
         assert_eq!(game_state.world.current_level_id, 25);
 
-        // Try to go down from level 25 (should trigger win ending)
-        let level_changed = game_state.use_stairs(StairDirection::Down).unwrap();
-        assert!(!level_changed, "Should not change levels - game should end");
+        // There is no going down from level 25, and no ending either
+        assert!(game_state.use_stairs(StairDirection::Down).is_err());
+        assert_eq!(game_state.world.current_level_id, 25);
         assert_eq!(
             game_state.completion_state,
-            crate::GameCompletionState::CompletedDungeon
+            crate::GameCompletionState::Playing
         );
     }
 
@@ -1332,10 +5004,13 @@ mod tests {
         let seed = 11111;
         let mut game_state_3d = GameState::new_with_complete_dungeon(seed).unwrap();
 
-        let player_entity = ConcreteEntity::Player(PlayerCharacter::new("TestHero".to_string()));
+        let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+            "TestHero".to_string(),
+            Position::new(0, 0),
+        ));
         let player_id = player_entity.id();
         game_state_3d.add_entity(player_entity).unwrap();
-        game_state_3d.set_player(player_id).unwrap();
+        game_state_3d.set_player_id(player_id);
 
         // Should be able to change to any level 0-25
         for level_id in 0..26 {
@@ -1354,10 +5029,13 @@ mod tests {
 
         // Test single level system (should generate on demand)
         let mut game_state_single = GameState::new(seed);
-        let player_entity_2 = ConcreteEntity::Player(PlayerCharacter::new("TestHero2".to_string()));
+        let player_entity_2 = ConcreteEntity::Player(PlayerCharacter::new(
+            "TestHero2".to_string(),
+            Position::new(0, 0),
+        ));
         let player_id_2 = player_entity_2.id();
         game_state_single.add_entity(player_entity_2).unwrap();
-        game_state_single.set_player(player_id_2).unwrap();
+        game_state_single.set_player_id(player_id_2);
 
         // Should start with 1 level
         assert_eq!(game_state_single.world.levels.len(), 1);
@@ -1376,10 +5054,13 @@ mod tests {
         let mut game_state = GameState::new_with_complete_dungeon(seed).unwrap();
 
         // Create and add player
-        let player_entity = ConcreteEntity::Player(PlayerCharacter::new("TestHero".to_string()));
+        let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+            "TestHero".to_string(),
+            Position::new(0, 0),
+        ));
         let player_id = player_entity.id();
         game_state.add_entity(player_entity).unwrap();
-        game_state.set_player(player_id).unwrap();
+        game_state.set_player_id(player_id);
 
         // Set initial position
         let initial_pos = Position::new(10, 10);
@@ -1398,4 +5079,492 @@ mod tests {
         // Player should be in the entities list of level 1
         assert!(level_1.entities.contains(&player_id));
     }
+
+    #[test]
+    fn test_travel_along_explored_corridor() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 3..=8 {
+                let mut tile = Tile::floor();
+                tile.mark_explored();
+                level.set_tile(Position::new(x, 5), tile).unwrap();
+            }
+        }
+        game_state
+            .initialize_player("Traveller".to_string(), Position::new(3, 5))
+            .unwrap();
+
+        // Walls and unexplored tiles are rejected
+        assert!(game_state.start_travel(Position::new(3, 4)).is_err());
+        assert!(game_state.start_travel(Position::new(20, 20)).is_err());
+
+        game_state.start_travel(Position::new(7, 5)).unwrap();
+        assert!(game_state.is_travelling());
+        assert_eq!(game_state.travel_state.path.len(), 4);
+
+        match game_state.get_travel_action().unwrap() {
+            Some(ConcreteAction::Move(action)) => assert_eq!(action.direction, Direction::East),
+            other => panic!("Expected a move action, got {:?}", other),
+        }
+
+        game_state.cancel_travel();
+        assert!(!game_state.is_travelling());
+    }
+
+    #[test]
+    fn test_travel_steps_again_when_a_doorway_is_blocked() {
+        let mut game_state = GameState::new(12345);
+        let door = Position::new(5, 5);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 3..=7 {
+                let mut tile = Tile::floor();
+                tile.mark_explored();
+                level.set_tile(Position::new(x, 5), tile).unwrap();
+            }
+            let mut tile = Tile::new(TileType::Door { is_open: false });
+            tile.mark_explored();
+            level.set_tile(door, tile).unwrap();
+        }
+        game_state
+            .initialize_player("Traveller".to_string(), Position::new(4, 5))
+            .unwrap();
+        game_state.door_opening = DoorOpening::OneTurn;
+        game_state.travel_state.action_delay_ms = 0;
+        game_state.start_travel(Position::new(7, 5)).unwrap();
+
+        // The door opens, but the goblin in the doorway keeps the player back
+        let goblin = crate::Monster::new(crate::MonsterType::Goblin, door);
+        game_state.add_entity(goblin.into()).unwrap();
+        let action = game_state.get_travel_action().unwrap().unwrap();
+        assert!(matches!(action, ConcreteAction::Door(ref door) if door.enter));
+        action.execute(&mut game_state).unwrap();
+        assert_eq!(
+            game_state.get_player().unwrap().position(),
+            Position::new(4, 5)
+        );
+
+        // So the next step heads for the doorway again
+        match game_state.get_travel_action().unwrap() {
+            Some(ConcreteAction::Move(action)) => assert_eq!(action.direction, Direction::East),
+            other => panic!("Expected a move action, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_repeat_stops_when_a_hostile_comes_into_view() {
+        let mut game_state = GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Counter".to_string(), Position::new(5, 5))
+            .unwrap();
+        let wait = ConcreteAction::Wait(crate::WaitAction::new(player_id));
+
+        game_state.start_repeat(wait.clone(), 2);
+        assert!(game_state.is_repeating());
+        assert!(game_state.get_repeat_action().is_some());
+        assert_eq!(game_state.check_repeat_interruption(), None);
+
+        // A goblin stepping into view ends the repeat early
+        let goblin = crate::Monster::new(crate::MonsterType::Goblin, Position::new(7, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        let level = game_state.world.current_level_mut().unwrap();
+        level.add_entity(goblin_id);
+        level
+            .get_tile_mut(Position::new(7, 5))
+            .unwrap()
+            .set_visible(true);
+        assert!(game_state.check_repeat_interruption().is_some());
+        assert!(!game_state.is_repeating());
+        assert!(game_state.get_repeat_action().is_none());
+    }
+
+    #[test]
+    fn test_run_turns_corners_and_stops_when_stairs_come_into_view() {
+        let mut game_state = GameState::new(12345);
+        // A corridor east along y = 5 that bends south at x = 10 and east again
+        // at y = 12, with the stairs out of sight round the second corner
+        let level = game_state.world.current_level_mut().unwrap();
+        for y in 0..level.height as i32 {
+            for x in 0..level.width as i32 {
+                level.set_tile(Position::new(x, y), Tile::wall()).unwrap();
+            }
+        }
+        let corridor = (2..=10)
+            .map(|x| Position::new(x, 5))
+            .chain((6..=12).map(|y| Position::new(10, y)))
+            .chain((11..=15).map(|x| Position::new(x, 12)));
+        for position in corridor {
+            level.set_tile(position, Tile::floor()).unwrap();
+        }
+        let stairs = Position::new(16, 12);
+        level
+            .set_tile(stairs, Tile::new(TileType::StairsDown))
+            .unwrap();
+        let player_id = game_state
+            .initialize_player("Runner".to_string(), Position::new(2, 5))
+            .unwrap();
+        game_state.repeat_state.action_delay_ms = 0;
+        assert!(!game_state.visible_features().contains(&stairs));
+
+        game_state.start_run(Direction::East).unwrap();
+        let mut stopped = None;
+        while game_state.is_repeating() {
+            stopped = game_state.check_repeat_interruption();
+            let Some(step) = game_state.get_repeat_action() else {
+                break;
+            };
+            step.execute(&mut game_state).unwrap();
+            let position = game_state.get_entity_position(player_id).unwrap();
+            game_state.update_player_visibility(position).unwrap();
+        }
+
+        // The run took the first corner, and the stairs stopped it short of them
+        let position = game_state.get_entity_position(player_id).unwrap();
+        assert!(position.x >= 10 && position.y > 5 && position != stairs);
+        assert!(stopped.unwrap().contains("something of interest"));
+    }
+
+    #[test]
+    fn test_confusion_sends_player_and_monsters_stumbling() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for y in 3..=16 {
+                for x in 3..=16 {
+                    level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+                }
+            }
+        }
+        let player_id = game_state
+            .initialize_player("Dizzy".to_string(), Position::new(3, 3))
+            .unwrap();
+        let step = ConcreteAction::Move(MoveAction::new(player_id, Direction::East));
+        assert!(game_state.stumble(&step).is_none());
+
+        let confuse = GameEvent::StatusApplied {
+            entity_id: player_id,
+            kind: StatusKind::Confused,
+            turns: crate::CONFUSED_DURATION,
+        };
+        game_state.process_event(&confuse).unwrap();
+        let stumbles: Vec<Option<ConcreteAction>> =
+            (0..40).map(|_| game_state.stumble(&step)).collect();
+        assert!(stumbles.iter().any(Option::is_none));
+        assert!(stumbles.iter().flatten().any(|stumble| {
+            matches!(stumble, ConcreteAction::Move(other) if other.direction != Direction::East)
+        }));
+        // Stumbling into the corner wall wastes the turn
+        assert!(stumbles
+            .iter()
+            .flatten()
+            .any(|stumble| matches!(stumble, ConcreteAction::Wait(_))));
+        let wait = ConcreteAction::Wait(crate::WaitAction::new(player_id));
+        assert!(game_state.stumble(&wait).is_none());
+
+        // A confused player stops walking on repeat
+        game_state.start_repeat(step.clone(), 5);
+        assert!(game_state.check_repeat_interruption().is_some());
+        assert!(!game_state.is_repeating());
+
+        // A confused monster does not always take the step it chose
+        let goblin = crate::Monster::new(crate::MonsterType::Goblin, Position::new(15, 15));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+        game_state
+            .entities
+            .get_mut(&goblin_id)
+            .and_then(|entity| entity.as_entity_mut().statuses_mut())
+            .unwrap()
+            .apply(StatusKind::Confused, crate::CONFUSED_DURATION);
+        let mut landed = HashSet::new();
+        for _ in 0..20 {
+            game_state
+                .set_entity_position(goblin_id, Position::new(15, 15))
+                .unwrap();
+            let monster = game_state
+                .entities
+                .get_mut(&goblin_id)
+                .and_then(ConcreteEntity::as_monster_mut)
+                .unwrap();
+            monster.investigating = Some(Position::new(16, 15));
+            game_state.move_monsters();
+            landed.insert(game_state.get_entity_position(goblin_id).unwrap());
+        }
+        assert!(landed.contains(&Position::new(16, 15)));
+        assert!(landed.len() > 1);
+    }
+
+    #[test]
+    fn test_describe_position() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(4, 4);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            let mut tile = Tile::new(TileType::StairsDown);
+            tile.mark_explored();
+            level.set_tile(position, tile).unwrap();
+        }
+
+        assert!(game_state
+            .describe_position(position)
+            .starts_with("Stairs Down"));
+        assert_eq!(
+            game_state.describe_position(Position::new(10, 10)),
+            "You haven't seen that place yet."
+        );
+    }
+
+    #[test]
+    fn test_look_action_describes_room_flavor() {
+        use crate::{Action, LookAction, Room, RoomType, ROOM_FLAVOR_METADATA_KEY};
+
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        let player_id = game_state
+            .initialize_player("Looker".to_string(), position)
+            .unwrap();
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            let mut tile = Tile::floor();
+            tile.mark_explored();
+            level.set_tile(position, tile).unwrap();
+
+            let mut room = Room::new(0, Position::new(3, 3), 6, 6, RoomType::Library);
+            room.name = Some("the Dusty Archive".to_string());
+            room.set_metadata(
+                ROOM_FLAVOR_METADATA_KEY.to_string(),
+                "Pages rustle without a breeze.".to_string(),
+            );
+            level.rooms.push(room);
+        }
+
+        let events = LookAction::new(player_id, position)
+            .execute(&mut game_state)
+            .unwrap();
+
+        assert_eq!(game_state.turn_number, 0);
+        match &events[..] {
+            [GameEvent::Message { text, .. }] => {
+                assert!(text.contains("This is the Dusty Archive."));
+                assert!(text.ends_with("Pages rustle without a breeze."));
+            }
+            other => panic!("expected a single message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_travel_destinations_and_nearest_unexplored() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 3..=8 {
+                level.set_tile(Position::new(x, 5), Tile::floor()).unwrap();
+            }
+            // Explore a small area, leaving the corridor unexplored past x = 6
+            for y in 4..=7 {
+                for x in 2..=7 {
+                    if (x, y) != (7, 5) {
+                        level
+                            .get_tile_mut(Position::new(x, y))
+                            .unwrap()
+                            .mark_explored();
+                    }
+                }
+            }
+            let mut stairs = Tile::new(TileType::StairsDown);
+            stairs.mark_explored();
+            level.set_tile(Position::new(3, 6), stairs).unwrap();
+            level.stairs_down_position = Some(Position::new(3, 6));
+        }
+        game_state
+            .initialize_player("Traveller".to_string(), Position::new(3, 5))
+            .unwrap();
+
+        // (6, 5) is the last explored floor tile before the unexplored (7, 5)
+        assert_eq!(
+            game_state.find_nearest_unexplored(),
+            Some(Position::new(6, 5))
+        );
+
+        let destinations = game_state.travel_destinations();
+        assert!(destinations.contains(&TravelDestination::StairsDown));
+        assert!(destinations.contains(&TravelDestination::NearestUnexplored));
+        assert!(!destinations.contains(&TravelDestination::StairsUp));
+
+        game_state
+            .start_travel_to(&TravelDestination::NearestUnexplored)
+            .unwrap();
+        assert_eq!(
+            game_state.travel_state.destination,
+            Some(Position::new(6, 5))
+        );
+    }
+
+    #[test]
+    fn test_travel_menu_reaches_rooms_past_the_ninth() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 2..=24 {
+                let mut floor = Tile::floor();
+                floor.mark_explored();
+                level.set_tile(Position::new(x, 5), floor).unwrap();
+            }
+            // A row of twelve one-tile rooms along the corridor
+            for id in 0..12u32 {
+                let top_left = Position::new(2 + id as i32 * 2, 5);
+                let room = crate::Room::new(id, top_left, 1, 1, crate::RoomType::Normal);
+                level.rooms.push(room);
+            }
+        }
+        game_state
+            .initialize_player("Traveller".to_string(), Position::new(2, 5))
+            .unwrap();
+
+        let destinations = game_state.travel_destinations();
+        assert!(destinations.len() > crate::TRAVEL_MENU_PAGE_SIZE);
+        let mut menu = crate::TravelMenu::new(destinations);
+        assert_eq!(menu.page_count(), 2);
+
+        // The twelfth room is the last entry on the second page
+        menu.next_page();
+        let last_room = menu.page_entries().last().cloned().unwrap();
+        assert!(matches!(last_room, TravelDestination::Room { id: 11, .. }));
+        game_state.start_travel_to(&last_room).unwrap();
+        assert_eq!(
+            game_state.travel_state.destination,
+            Some(Position::new(24, 5))
+        );
+    }
+
+    #[test]
+    fn test_monsters_investigate_noises_they_hear() {
+        let mut game_state = GameState::new(12345);
+        {
+            let level = game_state.world.current_level_mut().unwrap();
+            for x in 1..=12 {
+                level.set_tile(Position::new(x, 5), Tile::floor()).unwrap();
+            }
+        }
+        let player_id = game_state
+            .initialize_player("Loudmouth".to_string(), Position::new(2, 5))
+            .unwrap();
+        let goblin = crate::Monster::new(crate::MonsterType::Goblin, Position::new(10, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+        let investigating = |game_state: &GameState| {
+            game_state.entities[&goblin_id]
+                .as_monster()
+                .unwrap()
+                .investigating
+        };
+
+        // Footsteps are too quiet to carry that far
+        game_state
+            .process_event(&GameEvent::NoiseMade {
+                source: player_id,
+                position: Position::new(2, 5),
+                volume: crate::NOISE_WALK,
+            })
+            .unwrap();
+        assert_eq!(investigating(&game_state), None);
+        game_state.advance_turn().unwrap();
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(10, 5))
+        );
+
+        let shout = crate::ShoutAction::new(player_id);
+        for event in crate::Action::execute(&shout, &mut game_state).unwrap() {
+            game_state.process_event(&event).unwrap();
+        }
+        assert_eq!(investigating(&game_state), Some(Position::new(2, 5)));
+
+        let events = game_state.advance_turn().unwrap();
+        assert!(events.contains(&GameEvent::EntityMoved {
+            entity_id: goblin_id,
+            from: Position::new(10, 5),
+            to: Position::new(9, 5),
+        }));
+
+        // The goblin closes in and waits beside the player blocking the spot
+        for _ in 0..10 {
+            game_state.advance_turn().unwrap();
+        }
+        assert_eq!(
+            game_state.get_entity_position(goblin_id),
+            Some(Position::new(3, 5))
+        );
+    }
+
+    #[test]
+    fn test_quests_are_offered_and_rewarded() {
+        let mut game_state = GameState::new_with_complete_dungeon(4242).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        let player_id = game_state
+            .initialize_player("Questor".to_string(), spawn)
+            .unwrap();
+        let quest = game_state.quests.active().next().unwrap().clone();
+        assert_eq!(quest.objective, crate::Objective::ReachFloor { floor: 1 });
+        assert!(game_state
+            .recent_messages
+            .iter()
+            .any(|message| message.text.starts_with("New quest: ")));
+
+        game_state.quests.add(Quest::new(
+            "Pest control".to_string(),
+            "Rats in the cellar.".to_string(),
+            crate::Objective::Kill {
+                target: "Goblin".to_string(),
+                count: 1,
+            },
+            QuestReward::Item {
+                name: "Dagger".to_string(),
+                item_type: crate::ItemType::Weapon(crate::WeaponType::Dagger),
+            },
+        ));
+        let goblin = crate::Monster::new(crate::MonsterType::Goblin, Position::new(1, 1));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        let events = game_state
+            .process_event(&GameEvent::EntityDied {
+                entity_id: goblin_id,
+                killer: Some(player_id),
+            })
+            .unwrap();
+        assert!(events.contains(&GameEvent::Message {
+            text: "Quest complete: Pest control! You receive Dagger.".to_string(),
+            importance: crate::MessageImportance::Important,
+            channel: crate::MessageChannel::Narrative,
+        }));
+        let pack = &game_state.get_player().unwrap().inventory;
+        assert!(pack
+            .iter()
+            .any(|id| game_state.carried_item_name(*id) == Some("Dagger")));
+
+        // Going down finishes the first quest and offers the next one
+        game_state.change_to_level(1).unwrap();
+        let events = game_state
+            .process_event(&GameEvent::PlayerChangedLevel {
+                player_id,
+                old_level: 0,
+                new_level: 1,
+                direction: StairDirection::Down,
+            })
+            .unwrap();
+        let experience = game_state.get_player().unwrap().stats.experience;
+        assert_eq!(experience, crate::QUEST_XP_PER_LEVEL);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GameEvent::Message { text, .. }
+                if text.starts_with("New quest"))));
+    }
 }
@@ -0,0 +1,187 @@
+//! # Status Effects
+//!
+//! Timed conditions attached to entities, such as being soaked or on fire.
+//!
+//! Effects are stored per entity in a [`StatusEffects`] set and tick down once
+//! per turn from [`GameState::advance_turn`](crate::GameState::advance_turn).
+//! Some effects interact: getting soaked puts out flames, and a soaked entity
+//! cannot catch fire. A confused entity sometimes stumbles in a random
+//! direction instead of the one it chose, player and monster alike.
+
+use serde::{Deserialize, Serialize};
+
+/// Turns an entity stays soaked after leaving water.
+pub const SOAKED_DURATION: u32 = 10;
+
+/// Damage taken each turn while burning.
+pub const BURNING_DAMAGE: u32 = 4;
+
+/// Turns confusion lasts.
+pub const CONFUSED_DURATION: u32 = 6;
+
+/// Chance that a confused entity's step goes in a random direction.
+pub const CONFUSION_STUMBLE_CHANCE: f64 = 0.5;
+
+/// Kinds of status effect an entity can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Drenched by water; immune to catching fire
+    Soaked,
+    /// On fire, taking damage every turn
+    Burning,
+    /// Dazed, sometimes stumbling the wrong way
+    Confused,
+}
+
+impl StatusKind {
+    /// Every status kind
+    pub const ALL: [StatusKind; 3] = [
+        StatusKind::Soaked,
+        StatusKind::Burning,
+        StatusKind::Confused,
+    ];
+
+    /// Returns a short human-readable name for this status.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            StatusKind::Soaked => "Soaked",
+            StatusKind::Burning => "Burning",
+            StatusKind::Confused => "Confused",
+        }
+    }
+}
+
+/// A single active status effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    /// Which effect this is
+    pub kind: StatusKind,
+    /// Turns left before the effect wears off
+    pub turns_remaining: u32,
+}
+
+/// The set of status effects currently affecting an entity.
+///
+/// Each kind appears at most once; reapplying an effect refreshes its duration.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{StatusEffects, StatusKind};
+///
+/// let mut statuses = StatusEffects::new();
+/// statuses.apply(StatusKind::Burning, 3);
+/// statuses.apply(StatusKind::Soaked, 5);
+/// assert!(!statuses.has(StatusKind::Burning));
+/// assert!(statuses.has(StatusKind::Soaked));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffects {
+    /// Active effects in the order they were applied
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Creates an empty set of status effects.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies an effect for `turns` turns, refreshing it if already active.
+    ///
+    /// Soaking extinguishes burning, and burning cannot be applied while soaked.
+    /// Returns true if the effect is active afterwards.
+    pub fn apply(&mut self, kind: StatusKind, turns: u32) -> bool {
+        match kind {
+            StatusKind::Soaked => {
+                self.remove(StatusKind::Burning);
+            }
+            StatusKind::Burning if self.resisted_by(kind).is_some() => return false,
+            StatusKind::Burning | StatusKind::Confused => {}
+        }
+
+        match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+            Some(effect) => effect.turns_remaining = effect.turns_remaining.max(turns),
+            None => self.effects.push(StatusEffect {
+                kind,
+                turns_remaining: turns,
+            }),
+        }
+        true
+    }
+
+    /// Gets the active effect that keeps `kind` from taking hold, if any, as
+    /// being soaked keeps fire off.
+    pub fn resisted_by(&self, kind: StatusKind) -> Option<StatusKind> {
+        match kind {
+            StatusKind::Burning if self.has(StatusKind::Soaked) => Some(StatusKind::Soaked),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the effect is active.
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Removes an effect, returning true if it was active.
+    pub fn remove(&mut self, kind: StatusKind) -> bool {
+        let before = self.effects.len();
+        self.effects.retain(|effect| effect.kind != kind);
+        self.effects.len() != before
+    }
+
+    /// Counts every effect down by one turn and returns the kinds that expired.
+    pub fn tick(&mut self) -> Vec<StatusKind> {
+        for effect in &mut self.effects {
+            effect.turns_remaining = effect.turns_remaining.saturating_sub(1);
+        }
+        let expired = self
+            .effects
+            .iter()
+            .filter(|effect| effect.turns_remaining == 0)
+            .map(|effect| effect.kind)
+            .collect();
+        self.effects.retain(|effect| effect.turns_remaining > 0);
+        expired
+    }
+
+    /// Returns true if no effects are active.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_refreshes_duration() {
+        let mut statuses = StatusEffects::new();
+        statuses.apply(StatusKind::Soaked, 2);
+        statuses.apply(StatusKind::Soaked, 5);
+
+        assert_eq!(statuses.effects.len(), 1);
+        assert_eq!(statuses.effects[0].turns_remaining, 5);
+    }
+
+    #[test]
+    fn test_soaked_blocks_burning() {
+        let mut statuses = StatusEffects::new();
+        statuses.apply(StatusKind::Soaked, 3);
+
+        assert!(!statuses.apply(StatusKind::Burning, 3));
+        assert!(!statuses.has(StatusKind::Burning));
+    }
+
+    #[test]
+    fn test_tick_expires_effects() {
+        let mut statuses = StatusEffects::new();
+        statuses.apply(StatusKind::Burning, 1);
+
+        assert_eq!(statuses.tick(), vec![StatusKind::Burning]);
+        assert!(statuses.is_empty());
+        assert!(statuses.tick().is_empty());
+    }
+}
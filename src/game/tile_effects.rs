@@ -0,0 +1,213 @@
+//! # Tile Effects Module
+//!
+//! Scripted behavior attached to special tiles.
+//!
+//! A [`TileType::Special`] tile can carry a [`TileEffect`] that fires when
+//! something steps onto it. Movement resolution asks the game state for the
+//! effect's events, so fountains heal, traps hurt, teleporters move the player
+//! and altars call the player to prayer through the same event system as
+//! every other action. The generator scatters effects through a level's
+//! rooms, and reading a [`PORTAL_SCROLL`] tears open a new pair of
+//! teleporters.
+
+use crate::{Position, Tile, TileType};
+use serde::{Deserialize, Serialize};
+
+/// Health restored by drinking from a fountain.
+pub const FOUNTAIN_HEAL: u32 = 15;
+
+/// Damage dealt by a floor trap.
+pub const FLOOR_TRAP_DAMAGE: u32 = 4;
+
+/// Name of the scroll that opens a portal to a distant part of the floor.
+pub const PORTAL_SCROLL: &str = "Scroll of portal";
+
+/// Fewest steps a scroll's portal leads away from its reader.
+pub const PORTAL_MIN_DISTANCE: u32 = 20;
+
+/// What happens when something steps onto a special tile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileEffect {
+    /// Heals the player once, then runs dry
+    HealFountain { amount: u32 },
+    /// Moves whoever steps on it to the paired teleporter
+    Teleporter { destination: Position },
+    /// Hurts whoever steps on it, every time; it passes for floor until found
+    Trap {
+        damage: u32,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        found: bool,
+    },
+    /// Shows its inscription to the player
+    Plaque { text: String },
+    /// An altar to the floor's deity, where the player can pray
+    Altar,
+    /// A fountain that has been drunk dry
+    DryFountain,
+}
+
+impl TileEffect {
+    /// Returns a short human-readable name for the effect's tile.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TileEffect::HealFountain { .. } => "Fountain",
+            TileEffect::Teleporter { .. } => "Teleporter",
+            TileEffect::Trap { .. } => "Trap",
+            TileEffect::Plaque { .. } => "Plaque",
+            TileEffect::Altar => "Altar",
+            TileEffect::DryFountain => "Dry Fountain",
+        }
+    }
+
+    /// Returns the character representation for rendering.
+    pub fn to_char(&self) -> char {
+        match self {
+            TileEffect::HealFountain { .. } | TileEffect::DryFountain => '{',
+            TileEffect::Teleporter { .. } => 'O',
+            TileEffect::Trap { .. } => '^',
+            TileEffect::Plaque { .. } => '_',
+            TileEffect::Altar => '|',
+        }
+    }
+
+    /// Describes the tile for the examine command.
+    pub fn description(&self) -> &'static str {
+        match self {
+            TileEffect::HealFountain { .. } => "A fountain of clear, faintly glowing water.",
+            TileEffect::Teleporter { .. } => "A circle of runes humming with power.",
+            TileEffect::Trap { .. } => "A pressure plate set into the floor.",
+            TileEffect::Plaque { .. } => "A bronze plaque bearing an inscription.",
+            TileEffect::Altar => "A white stone altar, cool and humming with quiet power.",
+            TileEffect::DryFountain => "A dry fountain basin.",
+        }
+    }
+
+    /// Returns true if this is a trap the player has not found yet.
+    pub fn is_hidden(&self) -> bool {
+        matches!(self, TileEffect::Trap { found: false, .. })
+    }
+
+    /// Creates a special tile carrying this effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{TileEffect, TileType};
+    ///
+    /// let tile = TileEffect::Trap { damage: 4, found: true }.into_tile();
+    /// assert_eq!(tile.tile_type.display_name(), "Trap");
+    /// assert_eq!(tile.tile_type.effect(), Some(&TileEffect::Trap { damage: 4, found: true }));
+    /// assert!(tile.tile_type.is_passable());
+    ///
+    /// // Until it is found, a trap passes for floor
+    /// let hidden = TileEffect::Trap { damage: 4, found: false }.into_tile();
+    /// assert_eq!(hidden.tile_type.display_name(), "Floor");
+    /// assert_eq!(hidden.tile_type.to_char(), '.');
+    /// ```
+    pub fn into_tile(self) -> Tile {
+        Tile::new(TileType::Special {
+            description: self.description().to_string(),
+            effect: Some(self),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, Direction, GameEvent, GameState, MoveAction};
+
+    #[test]
+    fn test_stepping_on_special_tiles_runs_their_effect() {
+        let mut game_state = GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Wanderer".to_string(), Position::new(5, 5))
+            .unwrap();
+        let level = game_state.world.current_level_mut().unwrap();
+        let fountain = Position::new(6, 5);
+        let teleporter = Position::new(7, 5);
+        let destination = Position::new(7, 9);
+        level
+            .set_tile(
+                fountain,
+                TileEffect::HealFountain { amount: 10 }.into_tile(),
+            )
+            .unwrap();
+        level
+            .set_tile(
+                teleporter,
+                TileEffect::Teleporter { destination }.into_tile(),
+            )
+            .unwrap();
+        for x in 6..=8 {
+            level.set_tile(Position::new(x, 9), Tile::floor()).unwrap();
+        }
+        level
+            .set_tile(
+                Position::new(8, 9),
+                TileEffect::Trap { damage: 3, found: false }.into_tile(),
+            )
+            .unwrap();
+        let step = |game_state: &mut GameState, direction| -> Vec<GameEvent> {
+            let events = MoveAction::new(player_id, direction)
+                .execute(game_state)
+                .unwrap();
+            for event in &events {
+                game_state.process_event(event).unwrap();
+            }
+            events
+        };
+
+        // The fountain heals once and then runs dry, still looking like a fountain
+        let events = step(&mut game_state, Direction::East);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GameEvent::EntityHealed { amount: 10, .. })));
+        let level = game_state.world.current_level().unwrap();
+        let dry = &level.get_tile(fountain).unwrap().tile_type;
+        assert_eq!(dry.effect(), Some(&TileEffect::DryFountain));
+        assert_eq!(dry.clone().to_char(), '{');
+
+        // The teleporter sends the player to its pair
+        step(&mut game_state, Direction::East);
+        assert_eq!(game_state.get_entity_position(player_id), Some(destination));
+
+        // The hidden trap passes for floor until it goes off
+        let trap = Position::new(8, 9);
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(level.get_tile(trap).unwrap().tile_type.display_name(), "Floor");
+        let events = step(&mut game_state, Direction::East);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GameEvent::EntityDamaged { damage: 3, .. })));
+        assert_eq!(game_state.statistics.damage_taken, 3);
+        let level = game_state.world.current_level().unwrap();
+        let found = &level.get_tile(trap).unwrap().tile_type;
+        assert_eq!(found.display_name(), "Trap");
+        assert_eq!(found.path_cost(), crate::HAZARD_PATH_COST);
+    }
+
+    #[test]
+    fn test_portal_scroll_opens_a_way_to_a_distant_floor_tile() {
+        let mut level = crate::Level::new(0, 40, 3);
+        for x in 0..40 {
+            level.set_tile(Position::new(x, 1), Tile::floor()).unwrap();
+        }
+        let mut game_state = GameState::new_with_level(level, 7).unwrap();
+        let start = Position::new(2, 1);
+        let player_id = game_state
+            .initialize_player("Reader".to_string(), start)
+            .unwrap();
+
+        let events = game_state.open_portal(player_id).unwrap();
+        let arrived = game_state.get_entity_position(player_id).unwrap();
+        assert!(arrived.manhattan_distance(start) >= PORTAL_MIN_DISTANCE);
+        assert!(matches!(events.last(), Some(GameEvent::EntityMoved { to, .. }) if *to == arrived));
+
+        // The two ends lead to each other
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(level.portal_exit(start), Some(arrived));
+        assert_eq!(level.portal_exit(arrived), Some(start));
+        assert_eq!(level.portals().len(), 2);
+    }
+}
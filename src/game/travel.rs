@@ -0,0 +1,381 @@
+//! # Travel Module
+//!
+//! Multi-step travel along a precomputed path.
+//!
+//! Travel is used for click-to-move and other "walk me there" commands. A path is
+//! computed once with the autoexplore A* pathfinder and then executed one step per
+//! action, rate-limited the same way autoexplore is so the player can watch the
+//! character move.
+//!
+//! A movement or wait command given a count prefix is repeated the same way,
+//! and stops for the same reasons travel does. So is a run, which walks on
+//! until [`run_direction`] finds somewhere to stop.
+//!
+//! [`run_direction`]: crate::run_direction
+
+use crate::{ConcreteAction, EntityId, InterruptionWatch, Position};
+use std::collections::HashSet;
+
+/// Named destinations offered by the keyboard travel menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TravelDestination {
+    /// The up staircase on the current level
+    StairsUp,
+    /// The down staircase on the current level
+    StairsDown,
+    /// The closest explored tile that borders unexplored territory
+    NearestUnexplored,
+    /// A discovered room, by room id
+    Room { id: u32, label: String },
+}
+
+impl TravelDestination {
+    /// Returns the label shown in the travel menu.
+    pub fn label(&self) -> String {
+        match self {
+            TravelDestination::StairsUp => "Stairs up".to_string(),
+            TravelDestination::StairsDown => "Stairs down".to_string(),
+            TravelDestination::NearestUnexplored => "Nearest unexplored area".to_string(),
+            TravelDestination::Room { label, .. } => label.clone(),
+        }
+    }
+}
+
+/// Destinations shown on each page of the travel menu, one per number key 1-9.
+pub const TRAVEL_MENU_PAGE_SIZE: usize = 9;
+
+/// The open travel menu, split into pages that are chosen from with 1-9.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{TravelDestination, TravelMenu};
+///
+/// let rooms = (0..12)
+///     .map(|id| TravelDestination::Room { id, label: format!("Room {}", id + 1) })
+///     .collect();
+/// let mut menu = TravelMenu::new(rooms);
+/// assert_eq!(menu.page_count(), 2);
+///
+/// menu.next_page();
+/// assert_eq!(menu.page_entries().len(), 3);
+/// assert_eq!(menu.select(2).unwrap().label(), "Room 12");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TravelMenu {
+    /// Every destination, in menu order
+    pub destinations: Vec<TravelDestination>,
+    /// Page being shown, counting from 0
+    pub page: usize,
+}
+
+impl TravelMenu {
+    /// Opens the menu on its first page.
+    pub fn new(destinations: Vec<TravelDestination>) -> Self {
+        Self {
+            destinations,
+            page: 0,
+        }
+    }
+
+    /// Gets the number of pages, which is at least 1.
+    pub fn page_count(&self) -> usize {
+        self.destinations
+            .len()
+            .div_ceil(TRAVEL_MENU_PAGE_SIZE)
+            .max(1)
+    }
+
+    /// Gets the destinations on the page being shown.
+    pub fn page_entries(&self) -> &[TravelDestination] {
+        let start = (self.page * TRAVEL_MENU_PAGE_SIZE).min(self.destinations.len());
+        let end = (start + TRAVEL_MENU_PAGE_SIZE).min(self.destinations.len());
+        &self.destinations[start..end]
+    }
+
+    /// Turns to the next page, wrapping round to the first.
+    pub fn next_page(&mut self) {
+        self.page = (self.page + 1) % self.page_count();
+    }
+
+    /// Turns to the previous page, wrapping round to the last.
+    pub fn previous_page(&mut self) {
+        self.page = (self.page + self.page_count() - 1) % self.page_count();
+    }
+
+    /// Gets the destination at a zero-based index on the page being shown.
+    pub fn select(&self, index: usize) -> Option<&TravelDestination> {
+        self.page_entries().get(index)
+    }
+}
+
+/// State of an in-progress travel command.
+#[derive(Debug, Clone)]
+pub struct TravelState {
+    /// Final destination of the current travel, if any
+    pub destination: Option<Position>,
+    /// Remaining positions to step through, in order
+    pub path: Vec<Position>,
+    /// Last action execution time for speed control
+    pub last_action_time: Option<std::time::Instant>,
+    /// Delay between steps in milliseconds
+    pub action_delay_ms: u64,
+    /// Hostiles already in view when travel started; only new ones interrupt
+    pub known_hostiles: HashSet<EntityId>,
+}
+
+impl TravelState {
+    /// Creates an idle travel state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            destination: None,
+            path: Vec::new(),
+            last_action_time: None,
+            action_delay_ms: 50,
+            known_hostiles: HashSet::new(),
+        }
+    }
+
+    /// Starts travelling along `path` towards `destination`.
+    ///
+    /// `known_hostiles` are the hostiles already visible, which will not
+    /// interrupt the trip.
+    pub fn start(
+        &mut self,
+        destination: Position,
+        path: Vec<Position>,
+        known_hostiles: HashSet<EntityId>,
+    ) {
+        self.destination = Some(destination);
+        self.path = path;
+        self.last_action_time = None;
+        self.known_hostiles = known_hostiles;
+    }
+
+    /// Cancels any travel in progress.
+    pub fn cancel(&mut self) {
+        self.destination = None;
+        self.path.clear();
+        self.last_action_time = None;
+        self.known_hostiles.clear();
+    }
+
+    /// Returns true if there are steps left to take.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.destination.is_some() && !self.path.is_empty()
+    }
+
+    /// Checks if enough time has passed for the next step.
+    #[must_use]
+    pub fn can_perform_action(&self) -> bool {
+        self.last_action_time.is_none_or(|last_time| {
+            last_time.elapsed().as_millis() >= u128::from(self.action_delay_ms)
+        })
+    }
+
+    /// Gets the next step from `from`, marking the action time.
+    ///
+    /// A step stays on the path until the player stands on it, so one that
+    /// left them where they were, like a door opened into a blocked doorway,
+    /// is taken again. Clears the destination once the final step is reached.
+    pub fn next_step(&mut self, from: Position) -> Option<Position> {
+        if self.path.first() == Some(&from) {
+            self.path.remove(0);
+        }
+        let Some(&next) = self.path.first() else {
+            self.destination = None;
+            return None;
+        };
+
+        self.last_action_time = Some(std::time::Instant::now());
+        Some(next)
+    }
+}
+
+impl Default for TravelState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State of a movement or wait command being repeated.
+#[derive(Debug, Clone)]
+pub struct RepeatState {
+    /// Action to repeat, if one is being repeated
+    pub action: Option<ConcreteAction>,
+    /// Times the action is still to be taken
+    pub remaining: u32,
+    /// Last action execution time for speed control
+    pub last_action_time: Option<std::time::Instant>,
+    /// Delay between repeats in milliseconds
+    pub action_delay_ms: u64,
+    /// What was already in view when the repeat started; only new hostiles,
+    /// items and, on a run, features interrupt
+    pub watch: InterruptionWatch,
+    /// Whether the repeat is a run, which turns with corridors and stops at
+    /// junctions
+    pub running: bool,
+}
+
+impl RepeatState {
+    /// Creates an idle repeat state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            action: None,
+            remaining: 0,
+            last_action_time: None,
+            action_delay_ms: 50,
+            watch: InterruptionWatch::new(None),
+            running: false,
+        }
+    }
+
+    /// Starts taking `action` `count` times.
+    ///
+    /// `known_hostiles` and `known_items` are those already visible, which
+    /// will not interrupt the repeat.
+    pub fn start(
+        &mut self,
+        action: ConcreteAction,
+        count: u32,
+        known_hostiles: HashSet<EntityId>,
+        known_items: HashSet<EntityId>,
+    ) {
+        self.action = Some(action);
+        self.remaining = count;
+        self.last_action_time = None;
+        self.watch.start(known_hostiles, known_items);
+        self.running = false;
+    }
+
+    /// Starts a run with the first `step`, going on for up to
+    /// [`RUN_STEP_LIMIT`] steps.
+    ///
+    /// Features in `known_features` are already visible, and will not
+    /// interrupt the run any more than the known hostiles and items.
+    ///
+    /// [`RUN_STEP_LIMIT`]: crate::RUN_STEP_LIMIT
+    pub fn start_run(
+        &mut self,
+        step: ConcreteAction,
+        known_hostiles: HashSet<EntityId>,
+        known_items: HashSet<EntityId>,
+        known_features: HashSet<Position>,
+    ) {
+        self.action = Some(step);
+        self.remaining = crate::RUN_STEP_LIMIT;
+        self.last_action_time = None;
+        self.watch
+            .start_with_features(known_hostiles, known_items, known_features);
+        self.running = true;
+    }
+
+    /// Cancels any repeat in progress.
+    pub fn cancel(&mut self) {
+        self.action = None;
+        self.remaining = 0;
+        self.last_action_time = None;
+        self.watch.clear();
+        self.running = false;
+    }
+
+    /// Returns true if there are repeats left to take.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.action.is_some() && self.remaining > 0
+    }
+
+    /// Checks if enough time has passed for the next repeat.
+    #[must_use]
+    pub fn can_perform_action(&self) -> bool {
+        self.last_action_time.is_none_or(|last_time| {
+            last_time.elapsed().as_millis() >= u128::from(self.action_delay_ms)
+        })
+    }
+
+    /// Returns the next repeat of the action, marking the action time.
+    ///
+    /// Clears the action once the final repeat has been handed out.
+    pub fn next_action(&mut self) -> Option<ConcreteAction> {
+        if !self.is_active() {
+            self.action = None;
+            return None;
+        }
+
+        self.remaining -= 1;
+        self.last_action_time = Some(std::time::Instant::now());
+        if self.remaining == 0 {
+            self.action.take()
+        } else {
+            self.action.clone()
+        }
+    }
+}
+
+impl Default for RepeatState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_travel_state_lifecycle() {
+        let mut travel = TravelState::new();
+        assert!(!travel.is_active());
+
+        let destination = Position::new(3, 1);
+        travel.start(
+            destination,
+            vec![Position::new(2, 1), Position::new(3, 1)],
+            HashSet::new(),
+        );
+        assert!(travel.is_active());
+
+        let start = Position::new(1, 1);
+        assert_eq!(travel.next_step(start), Some(Position::new(2, 1)));
+        assert!(travel.is_active());
+        // A step that left the player in place is taken again
+        assert_eq!(travel.next_step(start), Some(Position::new(2, 1)));
+        assert_eq!(travel.next_step(Position::new(2, 1)), Some(destination));
+        assert!(travel.is_active());
+        assert_eq!(travel.next_step(destination), None);
+        assert!(!travel.is_active());
+        assert!(travel.destination.is_none());
+    }
+
+    #[test]
+    fn test_travel_cancel() {
+        let mut travel = TravelState::new();
+        travel.start(
+            Position::new(5, 5),
+            vec![Position::new(4, 5)],
+            HashSet::new(),
+        );
+
+        travel.cancel();
+        assert!(!travel.is_active());
+        assert!(travel.path.is_empty());
+    }
+
+    #[test]
+    fn test_repeat_state_counts_down() {
+        let mut repeat = RepeatState::new();
+        assert!(!repeat.is_active());
+
+        let wait = ConcreteAction::Wait(crate::WaitAction::new(crate::new_entity_id()));
+        repeat.start(wait, 2, HashSet::new(), HashSet::new());
+        assert!(repeat.is_active());
+        assert!(repeat.next_action().is_some());
+        assert!(repeat.is_active());
+        assert!(repeat.next_action().is_some());
+        assert!(!repeat.is_active());
+        assert!(repeat.next_action().is_none());
+    }
+}
@@ -0,0 +1,275 @@
+//! # Tutorial
+//!
+//! A hand-built first floor that teaches the game a step at a time.
+//!
+//! The tutorial floor replaces the generated first floor of a new game: a
+//! room to start in, a door out of it, a goblin to fight, a potion to pick up
+//! and the stairs down to the real dungeon. A [`Tutorial`] walks the player
+//! through it; the game state turns each event into a [`TutorialTrigger`],
+//! and the trigger that finishes the current [`TutorialStep`] brings up the
+//! instructions for the next. Triggers for later steps skip ahead, so a
+//! player who knows what to do is never held back.
+
+use crate::{
+    Level, Position, TemplateLibrary, ThatchError, ThatchResult, Tile, TileType, WaitingMonster,
+};
+use serde::{Deserialize, Serialize};
+
+/// Map of the tutorial floor: `#` and spaces are rock, `.` floor, `+` a
+/// closed door, `<` and `>` stairs, `g` a goblin and `!` a potion.
+const TUTORIAL_MAP: [&str; 11] = [
+    "#######",
+    "#<....#                  ###########",
+    "#.....#                  #.........#",
+    "#.....+..................+....g....#",
+    "#.....#                  #.........#",
+    "#######                  #......!..#",
+    "                         #####+#####",
+    "                             #.#",
+    "                         #####.#####",
+    "                         #....>....#",
+    "                         ###########",
+];
+
+/// Monster template standing at `g` on the tutorial map.
+const TUTORIAL_MONSTER: &str = "Goblin";
+
+/// Item template lying at `!` on the tutorial map.
+const TUTORIAL_ITEM: &str = "Potion of healing";
+
+/// Something the player did that may finish a tutorial step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialTrigger {
+    /// Took a step
+    Moved,
+    /// Stepped into a doorway
+    EnteredDoorway,
+    /// Killed a monster
+    Killed,
+    /// Picked up an item
+    PickedUp,
+    /// Went down the stairs
+    Descended,
+}
+
+/// A lesson of the tutorial, in the order they are taught.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TutorialStep {
+    /// Walking around
+    #[default]
+    Walk,
+    /// Opening doors by walking into them
+    Doors,
+    /// Fighting a monster
+    Combat,
+    /// Picking up and using items
+    Items,
+    /// Taking the stairs down
+    Stairs,
+    /// Every lesson is done
+    Finished,
+}
+
+impl TutorialStep {
+    /// Every step, in the order they are taught
+    pub const ALL: [TutorialStep; 6] = [
+        TutorialStep::Walk,
+        TutorialStep::Doors,
+        TutorialStep::Combat,
+        TutorialStep::Items,
+        TutorialStep::Stairs,
+        TutorialStep::Finished,
+    ];
+
+    /// Returns the instructions shown when the step begins.
+    pub fn instructions(self) -> &'static str {
+        match self {
+            TutorialStep::Walk => {
+                "Welcome to the tutorial! Walk with WASD, the arrow keys or hjkl."
+            }
+            TutorialStep::Doors => {
+                "A door leads east out of this room. Walk into it to open it, then step through."
+            }
+            TutorialStep::Combat => {
+                "A goblin guards the room at the end of the corridor. Walk into it to attack, \
+                 or press Tab to fight the nearest foe."
+            }
+            TutorialStep::Items => {
+                "A potion lies in the goblin's room. Step onto the ! and press , to pick it up."
+            }
+            TutorialStep::Stairs => {
+                "I shows your pack and U uses what is in it. The stairs down (>) lie through \
+                 the south door: stand on them and press > to go deeper."
+            }
+            TutorialStep::Finished => {
+                "You have finished the tutorial, and the real dungeon starts here. \
+                 Press ? for every command. Good luck!"
+            }
+        }
+    }
+
+    /// Gets the trigger that finishes the step, or None once the tutorial is over.
+    pub fn finished_by(self) -> Option<TutorialTrigger> {
+        match self {
+            TutorialStep::Walk => Some(TutorialTrigger::Moved),
+            TutorialStep::Doors => Some(TutorialTrigger::EnteredDoorway),
+            TutorialStep::Combat => Some(TutorialTrigger::Killed),
+            TutorialStep::Items => Some(TutorialTrigger::PickedUp),
+            TutorialStep::Stairs => Some(TutorialTrigger::Descended),
+            TutorialStep::Finished => None,
+        }
+    }
+}
+
+/// Progress through the tutorial.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{Tutorial, TutorialStep, TutorialTrigger};
+///
+/// let mut tutorial = Tutorial::new();
+/// assert!(tutorial.advance(TutorialTrigger::Moved).is_some());
+/// assert_eq!(tutorial.step, TutorialStep::Doors);
+///
+/// // Picking up the potion early skips the lessons before it
+/// tutorial.advance(TutorialTrigger::PickedUp);
+/// assert_eq!(tutorial.step, TutorialStep::Stairs);
+/// assert!(tutorial.advance(TutorialTrigger::Moved).is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tutorial {
+    /// The lesson being taught
+    pub step: TutorialStep,
+}
+
+impl Tutorial {
+    /// Starts the tutorial at its first step.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true once every lesson is done.
+    pub fn is_finished(&self) -> bool {
+        self.step == TutorialStep::Finished
+    }
+
+    /// Checks a trigger against the current step and those after it.
+    ///
+    /// If it finishes one of them, the tutorial moves on to the step after
+    /// that and its instructions are returned.
+    pub fn advance(&mut self, trigger: TutorialTrigger) -> Option<&'static str> {
+        let current = TutorialStep::ALL
+            .iter()
+            .position(|&step| step == self.step)?;
+        let finished = TutorialStep::ALL[current..]
+            .iter()
+            .position(|step| step.finished_by() == Some(trigger))?;
+        self.step = TutorialStep::ALL[current + finished + 1];
+        Some(self.step.instructions())
+    }
+}
+
+/// Builds the tutorial floor as level 0 with the given size, taking its
+/// goblin and potion from `templates`.
+///
+/// The player starts on the stairs up.
+///
+/// # Errors
+///
+/// Returns an error if the tutorial map does not fit in a floor of that size.
+pub fn tutorial_level(width: u32, height: u32, templates: &TemplateLibrary) -> ThatchResult<Level> {
+    let map_width = TUTORIAL_MAP.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+    if map_width + 2 > width || TUTORIAL_MAP.len() as u32 + 2 > height {
+        return Err(ThatchError::InvalidState(format!(
+            "The tutorial floor does not fit in {}x{} tiles",
+            width, height
+        )));
+    }
+
+    let mut level = Level::new(0, width, height);
+    level.name = Some("Tutorial".to_string());
+    for (y, row) in TUTORIAL_MAP.iter().enumerate() {
+        for (x, glyph) in row.chars().enumerate() {
+            let position = Position::new(x as i32 + 1, y as i32 + 1);
+            let tile_type = match glyph {
+                '.' | 'g' | '!' => TileType::Floor,
+                '+' => TileType::Door { is_open: false },
+                '<' => {
+                    level.stairs_up_position = Some(position);
+                    level.player_spawn = position;
+                    TileType::StairsUp
+                }
+                '>' => {
+                    level.stairs_down_position = Some(position);
+                    TileType::StairsDown
+                }
+                _ => continue,
+            };
+            level.set_tile(position, Tile::new(tile_type))?;
+
+            if glyph == 'g' {
+                if let Some(template) = templates.monster_template(TUTORIAL_MONSTER) {
+                    level.waiting_monsters.push(WaitingMonster {
+                        position,
+                        monster: template.clone(),
+                        out_of_depth: false,
+                    });
+                }
+            } else if glyph == '!' {
+                if let Some(template) = templates.item_template(TUTORIAL_ITEM) {
+                    level.ground_items.drop_item(template.spawn(position));
+                }
+            }
+        }
+    }
+    Ok(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConcreteAction, Direction, GameEvent, GameState, MoveAction};
+
+    #[test]
+    fn test_tutorial_floor_teaches_a_step_at_a_time() {
+        let mut game_state = GameState::new(5);
+        game_state
+            .start_tutorial(&TemplateLibrary::builtin().unwrap())
+            .unwrap();
+        let level = game_state.world.current_level().unwrap();
+        let start = level.player_spawn;
+        assert_eq!(level.get_tile(start).unwrap().tile_type, TileType::StairsUp);
+        assert!(level.stairs_down_position.is_some());
+        assert_eq!(level.ground_items.iter().count(), 1);
+
+        let player_id = game_state
+            .initialize_player("Pupil".to_string(), start)
+            .unwrap();
+        let goblins = game_state
+            .entities
+            .values()
+            .filter(|entity| entity.name() == TUTORIAL_MONSTER)
+            .count();
+        assert_eq!(goblins, 1);
+
+        // The first step brings up the lesson on doors
+        let events = ConcreteAction::Move(MoveAction::new(player_id, Direction::East))
+            .execute(&mut game_state)
+            .unwrap();
+        let mut responses = Vec::new();
+        for event in &events {
+            responses.extend(game_state.process_event(event).unwrap());
+        }
+        let doors = GameEvent::Message {
+            text: TutorialStep::Doors.instructions().to_string(),
+            importance: crate::MessageImportance::Important,
+            channel: crate::MessageChannel::System,
+        };
+        assert!(responses.contains(&doors));
+        assert_eq!(
+            game_state.tutorial.as_ref().unwrap().step,
+            TutorialStep::Doors
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! # Unique Items
+//!
+//! Rare, named gear with a story, whose power the engine alone decides.
+//!
+//! A unique item is forged from a [`UniqueTemplate`], which fixes what kind of
+//! weapon or armor it is, and an enchantment that must fall within
+//! [`unique_enchantment_range`] for the floor it lies on. Only its name and
+//! lore come from a [`UniqueFlavor`]: the offline name tables when the floor
+//! is generated, and, when the LLDM is enabled, words the LLDM writes once the
+//! player arrives, cached in the LLDM state. Flavor that fails
+//! [`UniqueFlavor::is_valid`] is thrown away, so nothing the LLDM writes can
+//! change how an item plays.
+
+use crate::{ArmorType, Item, ItemType, Position, ThatchError, ThatchResult, WeaponType};
+use std::ops::RangeInclusive;
+
+/// Metadata key marking an item as unique.
+pub const UNIQUE_KEY: &str = "unique";
+
+/// Metadata key holding a unique item's lore.
+pub const UNIQUE_LORE_KEY: &str = "lore";
+
+/// Smallest enchantment a unique item can have.
+pub const UNIQUE_MIN_ENCHANTMENT: i32 = 1;
+
+/// Largest enchantment a unique item can have, however deep.
+pub const UNIQUE_MAX_ENCHANTMENT: i32 = 5;
+
+/// Floors between each rise in the best enchantment a unique item can have.
+pub const UNIQUE_FLOORS_PER_ENCHANTMENT: u32 = 3;
+
+/// Longest name a unique item can be given.
+pub const MAX_UNIQUE_NAME_LENGTH: usize = 40;
+
+/// Longest lore a unique item can be given.
+pub const MAX_UNIQUE_LORE_LENGTH: usize = 200;
+
+/// What kind of gear a unique item is, and the word it is named for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniqueTemplate {
+    /// Word the item is named for, such as "Blade"
+    pub noun: &'static str,
+    /// Kind of weapon or armor the item is
+    pub item_type: ItemType,
+}
+
+/// Every kind of gear a unique item can be.
+pub static UNIQUE_TEMPLATES: [UniqueTemplate; 8] = [
+    UniqueTemplate {
+        noun: "Blade",
+        item_type: ItemType::Weapon(WeaponType::Sword),
+    },
+    UniqueTemplate {
+        noun: "Fang",
+        item_type: ItemType::Weapon(WeaponType::Dagger),
+    },
+    UniqueTemplate {
+        noun: "Maul",
+        item_type: ItemType::Weapon(WeaponType::Mace),
+    },
+    UniqueTemplate {
+        noun: "Staff",
+        item_type: ItemType::Weapon(WeaponType::Staff),
+    },
+    UniqueTemplate {
+        noun: "Crown",
+        item_type: ItemType::Armor(ArmorType::Helmet),
+    },
+    UniqueTemplate {
+        noun: "Mail",
+        item_type: ItemType::Armor(ArmorType::ChestArmor),
+    },
+    UniqueTemplate {
+        noun: "Aegis",
+        item_type: ItemType::Armor(ArmorType::Shield),
+    },
+    UniqueTemplate {
+        noun: "Band",
+        item_type: ItemType::Armor(ArmorType::Ring),
+    },
+];
+
+/// Owners unique items are named after when the LLDM is not naming them.
+pub const UNIQUE_OWNERS: [&str; 8] = [
+    "Aldric", "Morwen", "Thane", "Isolde", "Varek", "Sable", "Corvin", "Ysra",
+];
+
+/// Epithets unique items are named with when the LLDM is not naming them.
+pub const UNIQUE_EPITHETS: [&str; 6] = [
+    "the Last Light",
+    "Ashen Vows",
+    "the Drowned King",
+    "Quiet Ruin",
+    "the Hollow Star",
+    "Broken Oaths",
+];
+
+/// Gets the enchantments a unique item found on a floor may have.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{unique_enchantment_range, UNIQUE_MAX_ENCHANTMENT};
+///
+/// assert_eq!(unique_enchantment_range(0), 1..=2);
+/// assert_eq!(unique_enchantment_range(6), 1..=4);
+/// assert_eq!(*unique_enchantment_range(100).end(), UNIQUE_MAX_ENCHANTMENT);
+/// ```
+pub fn unique_enchantment_range(floor_id: u32) -> RangeInclusive<i32> {
+    let best = UNIQUE_MIN_ENCHANTMENT + 1 + (floor_id / UNIQUE_FLOORS_PER_ENCHANTMENT) as i32;
+    UNIQUE_MIN_ENCHANTMENT..=best.min(UNIQUE_MAX_ENCHANTMENT)
+}
+
+/// The name and lore of a unique item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniqueFlavor {
+    /// Name the item is known by
+    pub name: String,
+    /// A line or two of its history
+    pub lore: String,
+}
+
+impl UniqueFlavor {
+    /// Names an item from the offline name tables, after an owner or an
+    /// epithet picked by `roll`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{UniqueFlavor, UNIQUE_TEMPLATES};
+    ///
+    /// let flavor = UniqueFlavor::offline(&UNIQUE_TEMPLATES[0], 0);
+    /// assert_eq!(flavor.name, "Aldric's Blade");
+    /// assert!(flavor.is_valid());
+    /// ```
+    pub fn offline(template: &UniqueTemplate, roll: usize) -> Self {
+        let owner = UNIQUE_OWNERS[roll / 2 % UNIQUE_OWNERS.len()];
+        let epithet = UNIQUE_EPITHETS[roll / 2 % UNIQUE_EPITHETS.len()];
+        let noun = template.noun.to_lowercase();
+        if roll.is_multiple_of(2) {
+            Self {
+                name: format!("{}'s {}", owner, template.noun),
+                lore: format!(
+                    "{} carried this {} into the deep and never came back.",
+                    owner, noun
+                ),
+            }
+        } else {
+            Self {
+                name: format!("{} of {}", template.noun, epithet),
+                lore: format!("Whoever forged this {} meant it to outlast them.", noun),
+            }
+        }
+    }
+
+    /// Returns true if the name and lore are single lines, neither empty nor
+    /// longer than [`MAX_UNIQUE_NAME_LENGTH`] and [`MAX_UNIQUE_LORE_LENGTH`].
+    pub fn is_valid(&self) -> bool {
+        let fits = |text: &str, most: usize| {
+            !text.trim().is_empty() && text.chars().count() <= most && !text.contains('\n')
+        };
+        fits(&self.name, MAX_UNIQUE_NAME_LENGTH) && fits(&self.lore, MAX_UNIQUE_LORE_LENGTH)
+    }
+}
+
+/// Forges a unique item found on a floor at `position`.
+///
+/// The item is appraised from the start, so its enchantment shows.
+///
+/// # Errors
+///
+/// Returns an error if the enchantment is outside
+/// [`unique_enchantment_range`] for the floor, or the flavor is not valid.
+pub fn forge_unique(
+    template: &UniqueTemplate,
+    floor_id: u32,
+    enchantment: i32,
+    flavor: &UniqueFlavor,
+    position: Position,
+) -> ThatchResult<Item> {
+    if !unique_enchantment_range(floor_id).contains(&enchantment) {
+        return Err(ThatchError::InvalidState(format!(
+            "A unique item on floor {} cannot have enchantment {}",
+            floor_id, enchantment
+        )));
+    }
+    if !flavor.is_valid() {
+        return Err(ThatchError::InvalidState(format!(
+            "Unusable name for a unique item: {:?}",
+            flavor.name
+        )));
+    }
+
+    let mut item = Item::new(flavor.name.clone(), template.item_type.clone(), position);
+    item.enchantment = enchantment;
+    item.appraised = true;
+    item.metadata
+        .insert(UNIQUE_KEY.to_string(), template.noun.to_string());
+    item.metadata
+        .insert(UNIQUE_LORE_KEY.to_string(), flavor.lore.clone());
+    Ok(item)
+}
+
+/// Gets the template a unique item was forged from, if it is unique.
+pub fn unique_template_of(item: &Item) -> Option<&'static UniqueTemplate> {
+    let noun = item.metadata.get(UNIQUE_KEY)?;
+    UNIQUE_TEMPLATES
+        .iter()
+        .find(|template| template.noun == noun)
+}
+
+/// Gives a unique item a new name and lore, leaving everything else about it
+/// as it was forged.
+///
+/// Returns false, changing nothing, if the item is not unique or the flavor
+/// is not valid.
+pub fn reflavor_unique(item: &mut Item, flavor: &UniqueFlavor) -> bool {
+    if unique_template_of(item).is_none() || !flavor.is_valid() {
+        return false;
+    }
+    item.name = flavor.name.clone();
+    item.metadata
+        .insert(UNIQUE_LORE_KEY.to_string(), flavor.lore.clone());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{place_unique_item, GameState, GenerationConfig, Level, Room, RoomType, Tile};
+
+    #[test]
+    fn test_unique_items_keep_their_mechanics_whatever_they_are_called() {
+        let template = &UNIQUE_TEMPLATES[0];
+        let flavor = UniqueFlavor::offline(template, 3);
+        let position = Position::new(5, 5);
+        assert!(forge_unique(template, 0, UNIQUE_MAX_ENCHANTMENT, &flavor, position).is_err());
+        let rambling = UniqueFlavor {
+            name: "x".repeat(MAX_UNIQUE_NAME_LENGTH + 1),
+            lore: flavor.lore.clone(),
+        };
+        assert!(forge_unique(template, 0, 1, &rambling, position).is_err());
+
+        // Generated uniques always fall within the floor's bounds
+        let config = GenerationConfig::new(7);
+        let room = Room::new(1, Position::new(2, 2), 6, 6, RoomType::Treasure);
+        let placed: Vec<(u32, Item)> = (0..40)
+            .filter_map(|floor_id| {
+                let mut level = Level::new(floor_id, 12, 12);
+                for position in room.floor_positions() {
+                    level.set_tile(position, Tile::floor()).unwrap();
+                }
+                place_unique_item(&mut level, std::slice::from_ref(&room), &config)?;
+                let item = level.ground_items.iter().next()?.item.clone();
+                Some((floor_id, item))
+            })
+            .collect();
+        assert!(!placed.is_empty());
+        for (floor_id, item) in &placed {
+            assert!(unique_template_of(item).is_some());
+            assert!(unique_enchantment_range(*floor_id).contains(&item.enchantment));
+        }
+
+        // With the LLDM enabled, arriving renames the item but changes nothing else
+        let mut game_state = GameState::new(12345);
+        game_state.lldm_state.enabled = true;
+        let blade = forge_unique(template, 0, 2, &flavor, Position::new(6, 5)).unwrap();
+        let blade_id = blade.id;
+        game_state.drop_item_on_ground(blade.clone()).unwrap();
+        game_state
+            .initialize_player("Seeker".to_string(), Position::new(5, 5))
+            .unwrap();
+        let level = game_state.world.current_level().unwrap();
+        let named = &level.ground_items.top_at(Position::new(6, 5)).unwrap().item;
+        assert_eq!(named.id, blade_id);
+        assert_ne!(named.name, blade.name);
+        assert_eq!((named.enchantment, &named.item_type), (2, &blade.item_type));
+        assert!(game_state
+            .describe_item(named)
+            .contains(&named.metadata[UNIQUE_LORE_KEY]));
+        assert_eq!(game_state.lldm_state.content_cache.len(), 1);
+    }
+}
@@ -0,0 +1,160 @@
+//! # Visibility
+//!
+//! The player's field of view, remembered between turns.
+//!
+//! Working out what the player can see only ever touches tiles within their
+//! sight radius, so rather than darkening the whole floor each turn, only the
+//! tiles inside the last [`FieldOfView`] are reset before the new one is lit.
+//! When nothing the view depends on has changed since last time, as when the
+//! player waits, the view is kept as it is. Changing floors or loading a game
+//! starts from a clean slate.
+
+use crate::{LightSource, Position};
+
+/// Everything the player's view of a floor depends on.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{FieldOfView, Position};
+///
+/// let view = FieldOfView {
+///     level_id: 0,
+///     center: Position::new(5, 5),
+///     radius: 1,
+///     carried_light: None,
+///     ambient_light: 0.5,
+///     light_sources: Vec::new(),
+/// };
+/// let area: Vec<Position> = view.area().collect();
+/// assert_eq!(area.len(), 5);
+/// assert!(area.contains(&Position::new(5, 4)));
+/// assert!(!area.contains(&Position::new(4, 4)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldOfView {
+    /// Floor the view was taken on
+    pub level_id: u32,
+    /// Where the player stood
+    pub center: Position,
+    /// The player's sight radius
+    pub radius: i32,
+    /// Light the player was carrying
+    pub carried_light: Option<LightSource>,
+    /// Ambient light of the floor
+    pub ambient_light: f32,
+    /// Light sources placed on the floor
+    pub light_sources: Vec<LightSource>,
+}
+
+impl FieldOfView {
+    /// Gets every position within the view's radius of its center.
+    pub fn area(&self) -> impl Iterator<Item = Position> + '_ {
+        let radius = self.radius;
+        (-radius..=radius)
+            .flat_map(move |dy| (-radius..=radius).map(move |dx| (dx, dy)))
+            .map(|(dx, dy)| Position::new(self.center.x + dx, self.center.y + dy))
+            .filter(move |&pos| self.center.euclidean_distance(pos) <= radius as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Action, GameState, LightKind, LightSource, Position, UnequipItemAction};
+    use std::collections::BTreeSet;
+
+    fn visible_tiles(game_state: &GameState) -> BTreeSet<(i32, i32)> {
+        let level = game_state.world.current_level().unwrap();
+        let mut visible = BTreeSet::new();
+        for (y, row) in level.tiles.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if tile.visible {
+                    visible.insert((x as i32, y as i32));
+                }
+            }
+        }
+        visible
+    }
+
+    #[test]
+    fn test_incremental_view_matches_a_full_recompute() {
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        game_state
+            .initialize_player("Watcher".to_string(), spawn)
+            .unwrap();
+
+        // Walk away from the spawn, waiting now and then, and compare each
+        // view with one worked out from scratch
+        let level = game_state.world.current_level().unwrap();
+        let mut path = vec![spawn];
+        let mut position = spawn;
+        for step in 0..12 {
+            let next = position
+                .cardinal_adjacent_positions()
+                .into_iter()
+                .rfind(|next| level.is_passable(*next) && !path.contains(next));
+            position = next.unwrap_or(position);
+            path.push(position);
+            if step % 4 == 0 {
+                path.push(position);
+            }
+        }
+        for &position in &path {
+            game_state.update_player_visibility(position).unwrap();
+            let mut fresh = game_state.clone();
+            fresh.field_of_view = None;
+            fresh.update_player_visibility(position).unwrap();
+            assert_eq!(visible_tiles(&game_state), visible_tiles(&fresh));
+        }
+        assert!(!visible_tiles(&game_state).is_empty());
+
+        // Waiting keeps the view, but a new light nearby is taken into account
+        let kept = game_state.field_of_view.clone();
+        game_state.update_player_visibility(position).unwrap();
+        assert_eq!(game_state.field_of_view, kept);
+
+        let level = game_state.world.current_level_mut().unwrap();
+        level.ambient_light = 0.0;
+        level
+            .light_sources
+            .push(LightSource::new(LightKind::Torch, position));
+        game_state.update_player_visibility(position).unwrap();
+        assert_ne!(game_state.field_of_view, kept);
+        let mut fresh = game_state.clone();
+        fresh.field_of_view = None;
+        fresh.update_player_visibility(position).unwrap();
+        assert_eq!(visible_tiles(&game_state), visible_tiles(&fresh));
+
+        // A far-off position shares no tiles with the last view
+        let far = Position::new(position.x + 40, position.y);
+        game_state.update_player_visibility(far).unwrap();
+        assert!(!visible_tiles(&game_state).contains(&(position.x, position.y)));
+    }
+
+    #[test]
+    fn test_lantern_lights_the_way_only_while_held() {
+        let mut game_state = GameState::new_with_complete_dungeon(12345).unwrap();
+        let spawn = game_state.world.current_level().unwrap().player_spawn;
+        let player_id = game_state
+            .initialize_player("Lamplighter".to_string(), spawn)
+            .unwrap();
+        assert_eq!(game_state.player_light(), Some(LightKind::Lantern));
+
+        let level = game_state.world.current_level_mut().unwrap();
+        level.ambient_light = 0.0;
+        level.light_sources.clear();
+        game_state.update_player_visibility(spawn).unwrap();
+        assert!(visible_tiles(&game_state).len() > 1);
+
+        UnequipItemAction::new(player_id, "light".to_string())
+            .execute(&mut game_state)
+            .unwrap();
+        assert_eq!(game_state.player_light(), None);
+        game_state.update_player_visibility(spawn).unwrap();
+        assert_eq!(
+            visible_tiles(&game_state),
+            BTreeSet::from([(spawn.x, spawn.y)])
+        );
+    }
+}
@@ -6,9 +6,16 @@
 //! and collections of entities. This module provides the core data structures
 //! and operations for managing the game world.
 
-use crate::{config, EntityId, Position, ThatchError, ThatchResult};
+use crate::{
+    config, Container, Deity, EntityId, FloorTheme, GroundItems, LevelGraph, LightSource,
+    MonsterTemplate, Position, Room, SpawnScheduler, ThatchError, ThatchResult, TileEffect,
+    TileOverlay,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// What wading into water costs a path, against 1 for clean floor.
+pub const WATER_PATH_COST: u32 = 3;
 
 /// Represents different types of tiles in the game world.
 ///
@@ -23,14 +30,25 @@ pub enum TileType {
     Wall,
     /// Door that can be opened/closed (future: LLDM can create special doors)
     Door { is_open: bool },
+    /// Hidden door that looks and behaves like a wall until found by searching
+    SecretDoor,
     /// Stairs leading to another level
     StairsUp,
     /// Stairs leading down to another level
     StairsDown,
-    /// Water that might slow movement or require swimming
+    /// Water that can be swum through, soaking and eventually drowning swimmers
     Water,
-    /// Special tile type for LLDM-generated content
-    Special { description: String },
+    /// Molten rock that burns anything wading through it
+    Lava,
+    /// A drop to the floor below; whatever steps in falls through
+    Chasm,
+    /// Special tile type for LLDM-generated content, optionally scripted with
+    /// an effect that fires when something steps onto it
+    Special {
+        description: String,
+        #[serde(default)]
+        effect: Option<TileEffect>,
+    },
 }
 
 impl TileType {
@@ -48,38 +66,170 @@ impl TileType {
     /// ```
     pub fn is_passable(&self) -> bool {
         match self {
-            TileType::Floor | TileType::StairsUp | TileType::StairsDown | TileType::Water => true,
-            TileType::Wall => false,
+            TileType::Floor
+            | TileType::StairsUp
+            | TileType::StairsDown
+            | TileType::Water
+            | TileType::Lava
+            | TileType::Chasm => true,
+            TileType::Wall | TileType::SecretDoor => false,
             TileType::Door { is_open } => *is_open,
             TileType::Special { .. } => true, // Default to passable for LLDM content
         }
     }
 
+    /// Returns true if this tile is worth stopping a run to look at: stairs,
+    /// a hazard or anything special the player can see.
+    pub fn is_feature(&self) -> bool {
+        match self {
+            TileType::StairsUp | TileType::StairsDown => true,
+            TileType::Special { effect, .. } => !effect.as_ref().is_some_and(TileEffect::is_hidden),
+            _ => self.is_hazard(),
+        }
+    }
+
+    /// Returns true if a travel or autoexplore route can lead through this
+    /// tile: it can be walked on, or is a closed door to open on the way.
+    pub fn is_routable(&self) -> bool {
+        self.is_passable() || matches!(self, TileType::Door { .. })
+    }
+
     /// Returns true if sight can pass through this tile.
     pub fn is_transparent(&self) -> bool {
         match self {
-            TileType::Floor | TileType::StairsUp | TileType::StairsDown | TileType::Water => true,
-            TileType::Wall => false,
+            TileType::Floor
+            | TileType::StairsUp
+            | TileType::StairsDown
+            | TileType::Water
+            | TileType::Lava
+            | TileType::Chasm => true,
+            TileType::Wall | TileType::SecretDoor => false,
             TileType::Door { is_open } => *is_open,
             TileType::Special { .. } => true, // Default to transparent for LLDM content
         }
     }
 
+    /// Returns a short human-readable name for this tile type.
+    pub fn display_name(&self) -> &str {
+        match self {
+            TileType::Floor => "Floor",
+            // Secret doors pass for walls until they are found
+            TileType::Wall | TileType::SecretDoor => "Wall",
+            TileType::Door { is_open: true } => "Open Door",
+            TileType::Door { is_open: false } => "Closed Door",
+            TileType::StairsUp => "Stairs Up",
+            TileType::StairsDown => "Stairs Down",
+            TileType::Water => "Water",
+            TileType::Lava => "Lava",
+            TileType::Chasm => "Chasm",
+            // Traps pass for floor until they are found
+            TileType::Special { effect: Some(effect), .. } if effect.is_hidden() => "Floor",
+            TileType::Special { effect, .. } => {
+                effect.as_ref().map_or("Special", TileEffect::display_name)
+            }
+        }
+    }
+
+    /// Returns a one-sentence description used by the examine command.
+    pub fn description(&self) -> &str {
+        match self {
+            TileType::Floor => "Worn flagstones cover the ground here.",
+            TileType::Wall | TileType::SecretDoor => "A solid wall of rough-hewn stone.",
+            TileType::Door { is_open: true } => "An open wooden door.",
+            TileType::Door { is_open: false } => "A closed wooden door.",
+            TileType::StairsUp => "A staircase leading up towards the surface.",
+            TileType::StairsDown => "A staircase descending deeper into the dungeon.",
+            TileType::Water => "Dark, still water of unknown depth.",
+            TileType::Lava => "A sluggish river of glowing molten rock.",
+            TileType::Chasm => "A yawning drop to the floor below.",
+            TileType::Special { effect: Some(effect), .. } if effect.is_hidden() => {
+                TileType::Floor.description()
+            }
+            TileType::Special { description, .. } => description,
+        }
+    }
+
     /// Returns the character representation for rendering.
     pub fn to_char(self) -> char {
         match self {
             TileType::Floor => '.',
-            TileType::Wall => '#',
+            TileType::Wall | TileType::SecretDoor => '#',
             TileType::Door { is_open: true } => '/',
             TileType::Door { is_open: false } => '+',
             TileType::StairsUp => '<',
             TileType::StairsDown => '>',
             TileType::Water => '~',
-            TileType::Special { .. } => '?', // LLDM can override this
+            TileType::Lava => ';',
+            TileType::Chasm => '-',
+            TileType::Special {
+                effect: Some(effect),
+                ..
+            } if effect.is_hidden() => '.',
+            TileType::Special {
+                effect: Some(effect),
+                ..
+            } => effect.to_char(),
+            TileType::Special { effect: None, .. } => '?', // LLDM can override this
+        }
+    }
+
+    /// Returns true if stepping onto this tile hurts, so paths avoid it.
+    pub fn is_hazard(&self) -> bool {
+        matches!(self, TileType::Lava | TileType::Chasm)
+    }
+
+    /// Returns what stepping onto this tile costs a path, so that paths keep
+    /// to clean floor, wade through water only to save a long walk, and cross
+    /// hazards or traps the player knows of only when there is no other way.
+    /// A closed door costs the turn spent opening it as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{TileType, HAZARD_PATH_COST, WATER_PATH_COST};
+    ///
+    /// assert_eq!(TileType::Floor.path_cost(), 1);
+    /// assert_eq!(TileType::Water.path_cost(), WATER_PATH_COST);
+    /// assert_eq!(TileType::Lava.path_cost(), HAZARD_PATH_COST);
+    /// ```
+    pub fn path_cost(&self) -> u32 {
+        match self {
+            TileType::Water => WATER_PATH_COST,
+            TileType::Lava | TileType::Chasm => crate::HAZARD_PATH_COST,
+            TileType::Special {
+                effect: Some(TileEffect::Trap { found: true, .. }),
+                ..
+            } => crate::HAZARD_PATH_COST,
+            TileType::Door { is_open: false } => crate::CLOSED_DOOR_PATH_COST,
+            _ => 1,
+        }
+    }
+
+    /// Gets the effect a special tile runs when stepped on, if it has one.
+    pub fn effect(&self) -> Option<&TileEffect> {
+        match self {
+            TileType::Special { effect, .. } => effect.as_ref(),
+            _ => None,
         }
     }
 }
 
+/// Color theme for a level's walls and floors, chosen by depth.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TilePalette {
+    /// Plain grey stone of the upper floors
+    #[default]
+    Stone,
+    /// Damp, moss-covered caverns
+    Moss,
+    /// Pale crypt stone and bone
+    Crypt,
+    /// Scorched rock near the deep fires
+    Ember,
+    /// Dark violet stone of the lowest floors
+    Abyss,
+}
+
 /// Represents a single tile in the game world.
 ///
 /// Contains the tile type and any additional metadata needed for
@@ -92,8 +242,15 @@ pub struct Tile {
     pub explored: bool,
     /// Whether this tile is currently visible to the player
     pub visible: bool,
+    /// Light level from 0.0 (dark) to 1.0, updated along with visibility
+    #[serde(default)]
+    pub light: f32,
     /// Optional metadata for LLDM-generated content
+    #[serde(serialize_with = "crate::sorted_optional_map")]
     pub metadata: Option<HashMap<String, String>>,
+    /// Mark left on the tile by what happened here, such as spilled blood
+    #[serde(default)]
+    pub overlay: Option<TileOverlay>,
 }
 
 impl Tile {
@@ -113,7 +270,9 @@ impl Tile {
             tile_type,
             explored: false,
             visible: false,
+            light: 0.0,
             metadata: None,
+            overlay: None,
         }
     }
 
@@ -127,6 +286,13 @@ impl Tile {
         Self::new(TileType::Wall)
     }
 
+    /// Returns what stepping onto this tile costs a path: the cost of its
+    /// type, or of the overlay on it if that is slower going.
+    pub fn path_cost(&self) -> u32 {
+        let overlay = self.overlay.map_or(1, TileOverlay::path_cost);
+        self.tile_type.path_cost().max(overlay)
+    }
+
     /// Marks this tile as explored by the player.
     pub fn mark_explored(&mut self) {
         self.explored = true;
@@ -164,6 +330,18 @@ impl Tile {
     }
 }
 
+/// A monster the generator placed on a level, not spawned until the player arrives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaitingMonster {
+    /// Where the monster stands
+    pub position: Position,
+    /// Template the monster is spawned from
+    pub monster: MonsterTemplate,
+    /// Whether the monster belongs several floors deeper
+    #[serde(default)]
+    pub out_of_depth: bool,
+}
+
 /// Represents a single level/floor in the dungeon.
 ///
 /// Each level contains a 2D grid of tiles and tracks entities present
@@ -190,7 +368,49 @@ pub struct Level {
     /// Optional name/description for this level (LLDM can set this)
     pub name: Option<String>,
     /// Level-specific metadata for LLDM integration
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
+    /// Rooms placed by the generator, used for travel destinations
+    #[serde(default)]
+    pub rooms: Vec<Room>,
+    /// Which rooms lead to which, built once the floor is generated
+    #[serde(default)]
+    pub graph: LevelGraph,
+    /// Color theme used when rendering this level
+    #[serde(default)]
+    pub palette: TilePalette,
+    /// Light level of the whole floor before any light sources, from 0.0 to 1.0
+    #[serde(default = "default_ambient_light")]
+    pub ambient_light: f32,
+    /// Torches, fungus, and other fixed lights placed on this level
+    #[serde(default)]
+    pub light_sources: Vec<LightSource>,
+    /// Items lying on the floor
+    #[serde(default)]
+    pub ground_items: GroundItems,
+    /// Chests and other containers placed on this level
+    #[serde(default)]
+    pub containers: Vec<Container>,
+    /// Monsters placed by the generator, spawned when the player first arrives
+    #[serde(default)]
+    pub waiting_monsters: Vec<WaitingMonster>,
+    /// When wandering monsters arrive while the player stays on this level
+    #[serde(default)]
+    pub spawn_scheduler: SpawnScheduler,
+    /// God the level's altars belong to, once the player has met it
+    #[serde(default)]
+    pub deity: Option<Deity>,
+    /// Theme the LLDM gave the level before it was generated, if any
+    #[serde(default)]
+    pub theme: Option<FloorTheme>,
+    /// Positions of the level's teleporters, kept by [`Level::set_tile`]
+    /// (rebuilt on load)
+    #[serde(skip)]
+    teleporters: Vec<Position>,
+}
+
+fn default_ambient_light() -> f32 {
+    1.0
 }
 
 impl Level {
@@ -226,6 +446,18 @@ impl Level {
             stairs_down_position: None,
             name: None,
             metadata: HashMap::new(),
+            rooms: Vec::new(),
+            graph: LevelGraph::default(),
+            palette: TilePalette::default(),
+            ambient_light: default_ambient_light(),
+            light_sources: Vec::new(),
+            ground_items: GroundItems::new(),
+            containers: Vec::new(),
+            waiting_monsters: Vec::new(),
+            spawn_scheduler: SpawnScheduler::default(),
+            deity: None,
+            theme: None,
+            teleporters: Vec::new(),
         }
     }
 
@@ -265,6 +497,16 @@ impl Level {
         Some(&mut self.tiles[pos.y as usize][pos.x as usize])
     }
 
+    /// Rebuilds the room graph from the current rooms and tiles.
+    ///
+    /// Each room's `connections` are set to its neighbours in the graph.
+    pub fn build_graph(&mut self) {
+        self.graph = LevelGraph::build(self);
+        for room in &mut self.rooms {
+            room.connections = self.graph.neighbors(room.id);
+        }
+    }
+
     /// Sets the tile at the specified position.
     ///
     /// Returns an error if the position is out of bounds.
@@ -275,7 +517,12 @@ impl Level {
                 pos, self.width, self.height
             )));
         }
+        let teleporter = matches!(tile.tile_type.effect(), Some(TileEffect::Teleporter { .. }));
         self.tiles[pos.y as usize][pos.x as usize] = tile;
+        self.teleporters.retain(|&other| other != pos);
+        if teleporter {
+            self.teleporters.push(pos);
+        }
         Ok(())
     }
 
@@ -286,6 +533,101 @@ impl Level {
             .unwrap_or(false)
     }
 
+    /// Gets what stepping onto the tile at `pos` costs a path; see
+    /// [`Tile::path_cost`]. Positions off the level cost as much as floor.
+    pub fn path_cost(&self, pos: Position) -> u32 {
+        self.get_tile(pos).map_or(1, Tile::path_cost)
+    }
+
+    /// Gets where stepping onto a teleporter at `pos` leaves an entity.
+    ///
+    /// Returns `None` if there is no teleporter there or its far end is blocked.
+    pub fn portal_exit(&self, pos: Position) -> Option<Position> {
+        match self.get_tile(pos)?.tile_type.effect()? {
+            TileEffect::Teleporter { destination } if self.is_passable(*destination) => {
+                Some(*destination)
+            }
+            _ => None,
+        }
+    }
+
+    /// Lists every working teleporter on the level with where it leads.
+    ///
+    /// Only the teleporters placed through [`Level::set_tile`] are checked, so
+    /// this does not scan the grid.
+    pub fn portals(&self) -> Vec<(Position, Position)> {
+        self.teleporters
+            .iter()
+            .filter_map(|&pos| self.portal_exit(pos).map(|exit| (pos, exit)))
+            .collect()
+    }
+
+    /// Finds the level's teleporters again after it is loaded.
+    pub fn rebuild_portals(&mut self) {
+        self.teleporters.clear();
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if matches!(tile.tile_type.effect(), Some(TileEffect::Teleporter { .. })) {
+                    self.teleporters.push(Position::new(x as i32, y as i32));
+                }
+            }
+        }
+    }
+
+    /// Returns true if there is a secret door or a hidden trap at `pos`.
+    pub fn is_secret(&self, pos: Position) -> bool {
+        self.get_tile(pos).is_some_and(|tile| {
+            tile.tile_type == TileType::SecretDoor
+                || tile.tile_type.effect().is_some_and(TileEffect::is_hidden)
+        })
+    }
+
+    /// Uncovers a secret at `pos`: a secret door becomes a closed door and a
+    /// hidden trap shows. Returns true if there was a secret.
+    pub fn reveal_secret(&mut self, pos: Position) -> bool {
+        let Some(tile) = self.get_tile_mut(pos) else {
+            return false;
+        };
+        match &tile.tile_type {
+            TileType::SecretDoor => tile.tile_type = TileType::Door { is_open: false },
+            TileType::Special {
+                effect: Some(TileEffect::Trap { damage, found: false }),
+                ..
+            } => {
+                let damage = *damage;
+                tile.tile_type = TileEffect::Trap { damage, found: true }.into_tile().tile_type;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Marks the whole floor as explored, as a magic map does.
+    ///
+    /// Open ground is revealed along with the walls around it; solid rock and
+    /// secret doors show as walls. Returns how many tiles were newly explored.
+    pub fn reveal_map(&mut self) -> usize {
+        let open = |tile: &Tile| !matches!(tile.tile_type, TileType::Wall | TileType::SecretDoor);
+        let mut revealed = Vec::new();
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                let pos = Position::new(x as i32, y as i32);
+                let near_open = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| Position::new(pos.x + dx, pos.y + dy)))
+                    .any(|near| self.get_tile(near).is_some_and(open));
+                if !tile.explored && near_open {
+                    revealed.push(pos);
+                }
+            }
+        }
+        for &pos in &revealed {
+            if let Some(tile) = self.get_tile_mut(pos) {
+                tile.mark_explored();
+            }
+        }
+        revealed.len()
+    }
+
     /// Checks if the given position is transparent (sight can pass through).
     pub fn is_transparent(&self, pos: Position) -> bool {
         self.get_tile(pos)
@@ -293,6 +635,73 @@ impl Level {
             .unwrap_or(false)
     }
 
+    /// Checks if nothing opaque stands between two positions. The ends
+    /// themselves may be opaque, so a wall facing a torch is still lit.
+    pub fn has_clear_line(&self, from: Position, to: Position) -> bool {
+        let line = from.line_to(to);
+        let between = line.len().saturating_sub(2);
+        line.into_iter()
+            .skip(1)
+            .take(between)
+            .all(|pos| self.is_transparent(pos))
+    }
+
+    /// Returns the light level at a position.
+    ///
+    /// This is the brightest of the ambient light, every placed light source,
+    /// and the optional `carried` light (such as the player's lantern). Light
+    /// does not pass walls, so a source only lights tiles it has a clear line to.
+    pub fn light_level_at(&self, pos: Position, carried: Option<LightSource>) -> f32 {
+        self.light_sources
+            .iter()
+            .chain(carried.iter())
+            .map(|source| (source, source.light_at(pos)))
+            .filter(|&(source, light)| light > 0.0 && self.has_clear_line(source.position, pos))
+            .map(|(_, light)| light)
+            .fold(self.ambient_light, f32::max)
+            .min(1.0)
+    }
+
+    /// Gets the light source placed at a position, if any.
+    pub fn light_source_at(&self, pos: Position) -> Option<&LightSource> {
+        self.light_sources
+            .iter()
+            .find(|source| source.position == pos)
+    }
+
+    /// Leaves an overlay on the tile at a position.
+    ///
+    /// Only ground that can be walked on and is not water or a hazard takes a
+    /// mark, and an existing overlay is only replaced by one that covers it.
+    /// Returns true if the overlay was placed.
+    pub fn add_overlay(&mut self, pos: Position, overlay: TileOverlay) -> bool {
+        let Some(tile) = self.get_tile_mut(pos) else {
+            return false;
+        };
+        let holds_marks = tile.tile_type.is_passable()
+            && tile.tile_type != TileType::Water
+            && !tile.tile_type.is_hazard();
+        if !holds_marks || tile.overlay.is_some_and(|existing| !overlay.covers(existing)) {
+            return false;
+        }
+        tile.overlay = Some(overlay);
+        true
+    }
+
+    /// Gets the container sitting at a position, if any.
+    pub fn container_at(&self, pos: Position) -> Option<&Container> {
+        self.containers
+            .iter()
+            .find(|container| container.position == pos)
+    }
+
+    /// Gets mutable access to a container by ID.
+    pub fn container_mut(&mut self, container_id: EntityId) -> Option<&mut Container> {
+        self.containers
+            .iter_mut()
+            .find(|container| container.id == container_id)
+    }
+
     /// Adds an entity to this level.
     pub fn add_entity(&mut self, entity_id: EntityId) {
         if !self.entities.contains(&entity_id) {
@@ -321,6 +730,75 @@ impl Level {
     }
 }
 
+/// Smallest width or height a generated floor can have, in tiles.
+pub const MIN_FLOOR_SIZE: u32 = 20;
+
+/// How many floors a world has and how big each one is.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::WorldShape;
+///
+/// let shape = WorldShape { floor_count: 5, ..WorldShape::default() };
+/// assert_eq!(shape.deepest_floor(), 4);
+/// assert!(shape.has_floor_below(3));
+/// assert!(!shape.has_floor_below(4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldShape {
+    /// Number of floors; the deepest holds the Amulet and has no stairs down
+    pub floor_count: u32,
+    /// Width of each generated floor in tiles
+    pub floor_width: u32,
+    /// Height of each generated floor in tiles
+    pub floor_height: u32,
+}
+
+impl Default for WorldShape {
+    fn default() -> Self {
+        Self {
+            floor_count: config::DUNGEON_LEVELS,
+            floor_width: config::DEFAULT_DUNGEON_WIDTH,
+            floor_height: config::DEFAULT_DUNGEON_HEIGHT,
+        }
+    }
+}
+
+impl WorldShape {
+    /// Gets the ID of the deepest floor.
+    pub fn deepest_floor(&self) -> u32 {
+        self.floor_count.saturating_sub(1)
+    }
+
+    /// Returns true if there is a floor below the one with the given ID.
+    pub fn has_floor_below(&self, floor_id: u32) -> bool {
+        floor_id + 1 < self.floor_count
+    }
+
+    /// Checks that a world of this shape can be generated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no floors or a floor is narrower or
+    /// shorter than [`MIN_FLOOR_SIZE`].
+    pub fn validate(&self) -> ThatchResult<()> {
+        if self.floor_count == 0 {
+            return Err(ThatchError::InvalidState(
+                "A world needs at least one floor".to_string(),
+            ));
+        }
+        if self.floor_width < MIN_FLOOR_SIZE || self.floor_height < MIN_FLOOR_SIZE {
+            return Err(ThatchError::InvalidState(format!(
+                "Floors must be at least {0}x{0} tiles, not {1}x{2}",
+                MIN_FLOOR_SIZE, self.floor_width, self.floor_height
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// The complete game world containing multiple levels.
 ///
 /// Manages the collection of levels and provides methods for
@@ -328,7 +806,7 @@ impl Level {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct World {
     /// All levels in the world, indexed by level ID
-    pub levels: HashMap<u32, Level>,
+    pub levels: BTreeMap<u32, Level>,
     /// The currently active level
     pub current_level_id: u32,
     /// Maximum depth reached by the player
@@ -336,7 +814,11 @@ pub struct World {
     /// World seed for consistent generation
     pub seed: u64,
     /// World-wide metadata for LLDM integration
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
+    /// Number and size of the world's floors
+    #[serde(default)]
+    pub shape: WorldShape,
 }
 
 impl World {
@@ -353,12 +835,9 @@ impl World {
     /// assert!(world.levels.contains_key(&0));
     /// ```
     pub fn new(seed: u64) -> Self {
-        let mut levels = HashMap::new();
-        let initial_level = Level::new(
-            0,
-            config::DEFAULT_DUNGEON_WIDTH,
-            config::DEFAULT_DUNGEON_HEIGHT,
-        );
+        let mut levels = BTreeMap::new();
+        let shape = WorldShape::default();
+        let initial_level = Level::new(0, shape.floor_width, shape.floor_height);
         levels.insert(0, initial_level);
 
         Self {
@@ -367,6 +846,7 @@ impl World {
             max_depth: 0,
             seed,
             metadata: HashMap::new(),
+            shape,
         }
     }
 
@@ -451,6 +931,46 @@ mod tests {
         assert!(!TileType::Door { is_open: false }.is_transparent());
     }
 
+    #[test]
+    fn test_secret_door_looks_like_wall() {
+        assert!(!TileType::SecretDoor.is_passable());
+        assert!(!TileType::SecretDoor.is_transparent());
+        assert_eq!(TileType::SecretDoor.to_char(), TileType::Wall.to_char());
+        assert_eq!(
+            TileType::SecretDoor.display_name(),
+            TileType::Wall.display_name()
+        );
+    }
+
+    #[test]
+    fn test_level_light_levels() {
+        let mut level = Level::new(0, 20, 20);
+        level.ambient_light = 0.1;
+        level
+            .light_sources
+            .push(LightSource::new(crate::LightKind::Torch, Position::new(2, 2)));
+
+        assert_eq!(level.light_level_at(Position::new(2, 2), None), 1.0);
+        assert_eq!(level.light_level_at(Position::new(18, 18), None), 0.1);
+
+        let lantern = LightSource::new(crate::LightKind::Lantern, Position::new(18, 18));
+        assert!(level.light_level_at(Position::new(18, 18), Some(lantern)) > 0.1);
+        assert!(level.light_source_at(Position::new(2, 2)).is_some());
+
+        // A wall casts a shadow but is lit itself
+        for x in 3..=5 {
+            level
+                .set_tile(Position::new(x, 2), Tile::new(TileType::Floor))
+                .unwrap();
+        }
+        assert!(level.light_level_at(Position::new(5, 2), None) > 0.1);
+        level
+            .set_tile(Position::new(4, 2), Tile::new(TileType::Wall))
+            .unwrap();
+        assert!(level.light_level_at(Position::new(4, 2), None) > 0.1);
+        assert_eq!(level.light_level_at(Position::new(5, 2), None), 0.1);
+    }
+
     #[test]
     fn test_tile_creation() {
         let tile = Tile::new(TileType::Floor);
@@ -484,6 +1004,39 @@ mod tests {
         assert_eq!(level.tiles[0].len(), 10);
     }
 
+    #[test]
+    fn test_world_shape_sets_floor_count_and_size() {
+        let shape = WorldShape {
+            floor_count: 3,
+            floor_width: 40,
+            floor_height: 30,
+        };
+        assert!(WorldShape { floor_width: 10, ..shape }.validate().is_err());
+        assert!(WorldShape { floor_count: 0, ..shape }.validate().is_err());
+
+        let config = crate::GenerationConfig {
+            shape,
+            generator: crate::GeneratorKind::Bsp,
+            ..crate::GenerationConfig::for_testing(9)
+        };
+        let mut game_state = crate::GameState::new_with_generation_config(config).unwrap();
+        assert_eq!(game_state.world.shape, shape);
+        assert_eq!(game_state.world.level_count(), 3);
+        for level in game_state.world.levels.values() {
+            assert_eq!((level.width, level.height), (40, 30));
+        }
+
+        // The third floor is the bottom, and holds the Amulet
+        let deepest = game_state.world.get_level(2).unwrap();
+        assert!(deepest.stairs_down_position.is_none());
+        assert!(deepest
+            .ground_items
+            .iter()
+            .any(|stack| stack.item.name == crate::ARTIFACT_NAME));
+        game_state.world.current_level_id = 2;
+        assert!(game_state.use_stairs(crate::StairDirection::Down).is_err());
+    }
+
     #[test]
     fn test_level_bounds_checking() {
         let level = Level::new(0, 10, 10);
@@ -0,0 +1,444 @@
+//! # BSP Dungeon Generation
+//!
+//! Classic room-and-corridor floors built by binary space partitioning.
+//!
+//! The level is split recursively into leaves, each leaf receives a single
+//! room, and sibling subtrees are joined by 1-tile corridors. Rooms never
+//! overlap, which gives traditional roguelike floors instead of the cave-like
+//! layouts produced by [`RoomCorridorGenerator`](crate::RoomCorridorGenerator).
+
+use super::{
+    curse_items, place_out_of_depth_monster, place_unique_item, place_vault, themed_floor_config,
+    utils, GenerationConfig, Generator, Room, RoomType, WorldGenerator,
+};
+use crate::game::{Level, Position, SpawnScheduler, Tile, TileType, World};
+use crate::{ThatchError, ThatchResult};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+/// Rectangular region of the level produced by partitioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Leaf {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Leaf {
+    fn contains(&self, pos: Position) -> bool {
+        pos.x >= self.x
+            && pos.y >= self.y
+            && pos.x < self.x + self.width as i32
+            && pos.y < self.y + self.height as i32
+    }
+}
+
+/// Room-and-corridor generator based on binary space partitioning.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{BspDungeonGenerator, GenerationConfig, Generator};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let generator = BspDungeonGenerator::new();
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let level = generator.generate(&GenerationConfig::new(7), &mut rng).unwrap();
+/// assert!(level.rooms.len() > 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BspDungeonGenerator {
+    /// Smallest width or height a leaf may be split down to
+    pub min_leaf_size: u32,
+    /// Maximum number of times the level is split
+    pub max_depth: u32,
+}
+
+impl BspDungeonGenerator {
+    /// Creates a BSP generator with default settings.
+    pub fn new() -> Self {
+        Self {
+            min_leaf_size: 10,
+            max_depth: 5,
+        }
+    }
+
+    /// Generates a single floor.
+    ///
+    /// If `stairs_up` is given, the floor is built so that position is reachable
+    /// floor and holds the up stairs. Down stairs are placed in the room farthest
+    /// from the spawn point when `place_stairs_down` is set.
+    pub fn generate_floor_with_stairs(
+        &self,
+        floor_id: u32,
+        stairs_up: Option<Position>,
+        place_stairs_down: bool,
+        config: &GenerationConfig,
+        rng: &mut StdRng,
+    ) -> ThatchResult<Level> {
+        crate::profile_scope!("bsp_generate_floor");
+        let mut level = Level::new(
+            floor_id,
+            config.shape.floor_width,
+            config.shape.floor_height,
+        );
+        let (config, theme) = themed_floor_config(floor_id, config);
+        let config = &config;
+        let profile = config.depth_table.profile_for(floor_id);
+        level.palette = profile.palette;
+        level.ambient_light = profile.ambient_light;
+        let root = Leaf {
+            x: 0,
+            y: 0,
+            width: config.shape.floor_width,
+            height: config.shape.floor_height,
+        };
+
+        let mut rooms = Vec::new();
+        self.build(root, 0, stairs_up, &mut level, &mut rooms, config, rng)?;
+
+        // Loops make the floor less linear
+        for pair in rooms.windows(2) {
+            if rng.gen_bool(config.extra_connection_chance.clamp(0.0, 1.0)) {
+                self.carve_corridor(&mut level, pair[0].center(), pair[1].center(), rng)?;
+            }
+        }
+        utils::balance_room_types(&mut rooms, floor_id, config.seed);
+        if let Some(theme) = &theme {
+            theme.furnish(&mut rooms, floor_id, config.seed);
+        }
+
+        level.player_spawn = match stairs_up {
+            Some(up_pos) => {
+                if !level
+                    .get_tile(up_pos)
+                    .is_some_and(|t| t.tile_type.is_passable())
+                {
+                    // The stairs fell outside every room, so tunnel to the closest one
+                    let nearest = rooms
+                        .iter()
+                        .map(Room::center)
+                        .min_by_key(|center| center.manhattan_distance(up_pos))
+                        .ok_or_else(|| {
+                            ThatchError::GenerationFailed("BSP floor has no rooms".to_string())
+                        })?;
+                    self.carve_corridor(&mut level, up_pos, nearest, rng)?;
+                }
+                level.set_tile(up_pos, Tile::new(TileType::StairsUp))?;
+                level.stairs_up_position = Some(up_pos);
+                up_pos
+            }
+            None => {
+                // Floor 0's stairs up, under the player's feet, are the way out of the dungeon
+                let entrance = rooms[0].center();
+                level.set_tile(entrance, Tile::new(TileType::StairsUp))?;
+                level.stairs_up_position = Some(entrance);
+                entrance
+            }
+        };
+
+        if place_stairs_down {
+            let down_pos = utils::find_stairs_down_position(&level, &rooms, rng)?;
+            level.set_tile(down_pos, Tile::new(TileType::StairsDown))?;
+            level.stairs_down_position = Some(down_pos);
+        }
+
+        place_vault(&mut level, &mut rooms, config);
+        place_out_of_depth_monster(&mut level, &rooms, config);
+        level.spawn_scheduler = SpawnScheduler::new(level.id, &config.templates);
+        utils::place_water(&mut level, &rooms, config.water_chance, rng)?;
+        utils::place_hazards(&mut level, &rooms, floor_id, place_stairs_down, rng)?;
+        utils::place_secret_doors(&mut level, &rooms, config.secret_door_chance, rng)?;
+        utils::place_light_sources(&mut level, &rooms, rng);
+        utils::place_containers(&mut level, &rooms, &config.templates, rng);
+        curse_items(&mut level, config);
+        place_unique_item(&mut level, &rooms, config);
+        utils::place_special_tiles(&mut level, &rooms, rng)?;
+        utils::place_rubble(&mut level, &rooms, config.seed);
+        if floor_id == config.shape.deepest_floor() {
+            utils::place_artifact(&mut level, &rooms, rng);
+        }
+
+        level.rooms = rooms;
+        level.theme = theme;
+        level.build_graph();
+        utils::validate_level(&level)?;
+
+        Ok(level)
+    }
+
+    /// Partitions `leaf`, carving a room in every final leaf and joining siblings.
+    ///
+    /// Returns the index of a room in this subtree for the parent to connect to.
+    #[allow(clippy::too_many_arguments)]
+    fn build(
+        &self,
+        leaf: Leaf,
+        depth: u32,
+        anchor: Option<Position>,
+        level: &mut Level,
+        rooms: &mut Vec<Room>,
+        config: &GenerationConfig,
+        rng: &mut StdRng,
+    ) -> ThatchResult<usize> {
+        if depth < self.max_depth {
+            if let Some((first, second)) = self.split(leaf, rng) {
+                let a = self.build(first, depth + 1, anchor, level, rooms, config, rng)?;
+                let b = self.build(second, depth + 1, anchor, level, rooms, config, rng)?;
+                let (from, to) = (rooms[a].center(), rooms[b].center());
+                self.carve_corridor(level, from, to, rng)?;
+                return Ok(if rng.gen_bool(0.5) { a } else { b });
+            }
+        }
+
+        let anchor = anchor.filter(|&pos| leaf.contains(pos));
+        let room = self.room_in_leaf(rooms.len() as u32, leaf, anchor, config, rng);
+        for pos in room.floor_positions() {
+            level.set_tile(pos, Tile::floor())?;
+        }
+        rooms.push(room);
+        Ok(rooms.len() - 1)
+    }
+
+    /// Splits a leaf in two, or returns None if it is too small to split.
+    fn split(&self, leaf: Leaf, rng: &mut StdRng) -> Option<(Leaf, Leaf)> {
+        // Cut across the longer side so leaves stay roughly square
+        let cut_rows = if leaf.width as f32 >= leaf.height as f32 * 1.25 {
+            false
+        } else if leaf.height as f32 >= leaf.width as f32 * 1.25 {
+            true
+        } else {
+            rng.gen_bool(0.5)
+        };
+
+        let size = if cut_rows { leaf.height } else { leaf.width };
+        if size < self.min_leaf_size * 2 {
+            return None;
+        }
+        let cut = rng.gen_range(self.min_leaf_size..=size - self.min_leaf_size);
+
+        Some(if cut_rows {
+            (
+                Leaf {
+                    height: cut,
+                    ..leaf
+                },
+                Leaf {
+                    y: leaf.y + cut as i32,
+                    height: leaf.height - cut,
+                    ..leaf
+                },
+            )
+        } else {
+            (
+                Leaf { width: cut, ..leaf },
+                Leaf {
+                    x: leaf.x + cut as i32,
+                    width: leaf.width - cut,
+                    ..leaf
+                },
+            )
+        })
+    }
+
+    /// Picks a room that fits inside a leaf, keeping a 1-tile gap to its neighbours.
+    ///
+    /// When an anchor is given the room is placed so its floor covers the anchor
+    /// if the leaf allows it.
+    fn room_in_leaf(
+        &self,
+        id: u32,
+        leaf: Leaf,
+        anchor: Option<Position>,
+        config: &GenerationConfig,
+        rng: &mut StdRng,
+    ) -> Room {
+        let width = Self::room_extent(leaf.width, config, rng);
+        let height = Self::room_extent(leaf.height, config, rng);
+        let x = Self::place_along_axis(leaf.x, leaf.width, width, anchor.map(|p| p.x), rng);
+        let y = Self::place_along_axis(leaf.y, leaf.height, height, anchor.map(|p| p.y), rng);
+
+        Room::new(id, Position::new(x, y), width, height, RoomType::Normal)
+    }
+
+    /// Picks a room size (including walls) for a leaf side of length `span`.
+    fn room_extent(span: u32, config: &GenerationConfig, rng: &mut StdRng) -> u32 {
+        let max = config.max_room_size.min(span - 1).max(3);
+        let min = config.min_room_size.clamp(3, max);
+        rng.gen_range(min..=max)
+    }
+
+    /// Picks the start of a room of length `size` within `[start, start + span - 1)`.
+    fn place_along_axis(
+        start: i32,
+        span: u32,
+        size: u32,
+        anchor: Option<i32>,
+        rng: &mut StdRng,
+    ) -> i32 {
+        let mut low = start;
+        let mut high = start + span as i32 - 1 - size as i32;
+
+        // Keep the anchor on the room's floor, not its walls
+        if let Some(anchor) = anchor {
+            let anchored_low = low.max(anchor - size as i32 + 2);
+            let anchored_high = high.min(anchor - 1);
+            if anchored_low <= anchored_high {
+                low = anchored_low;
+                high = anchored_high;
+            }
+        }
+
+        rng.gen_range(low..=high.max(low))
+    }
+
+    /// Carves an L-shaped 1-tile corridor, leaving stairs and existing floor alone.
+    fn carve_corridor(
+        &self,
+        level: &mut Level,
+        from: Position,
+        to: Position,
+        rng: &mut StdRng,
+    ) -> ThatchResult<()> {
+        let corner = if rng.gen_bool(0.5) {
+            Position::new(to.x, from.y)
+        } else {
+            Position::new(from.x, to.y)
+        };
+
+        for (start, end) in [(from, corner), (corner, to)] {
+            let (dx, dy) = ((end.x - start.x).signum(), (end.y - start.y).signum());
+            let mut pos = start;
+            loop {
+                if level
+                    .get_tile(pos)
+                    .is_some_and(|tile| tile.tile_type == TileType::Wall)
+                {
+                    level.set_tile(pos, Tile::floor())?;
+                }
+                if pos == end {
+                    break;
+                }
+                pos = Position::new(pos.x + dx, pos.y + dy);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for BspDungeonGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Generator<Level> for BspDungeonGenerator {
+    fn generate(&self, config: &GenerationConfig, rng: &mut StdRng) -> ThatchResult<Level> {
+        self.generate_floor_with_stairs(0, None, true, config, rng)
+    }
+
+    fn validate(&self, level: &Level, _config: &GenerationConfig) -> ThatchResult<()> {
+        utils::validate_level(level)
+    }
+
+    fn generator_type(&self) -> &'static str {
+        "BspDungeonGenerator"
+    }
+}
+
+impl WorldGenerator for BspDungeonGenerator {
+    fn generate_floor(
+        &self,
+        floor_id: u32,
+        stairs_up: Option<Position>,
+        config: &GenerationConfig,
+        rng: &mut StdRng,
+    ) -> ThatchResult<Level> {
+        let place_stairs_down = config.shape.has_floor_below(floor_id);
+        self.generate_floor_with_stairs(floor_id, stairs_up, place_stairs_down, config, rng)
+    }
+
+    fn validate_world(&self, world: &World, _config: &GenerationConfig) -> ThatchResult<()> {
+        utils::validate_world_stairs(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_bsp_rooms_do_not_overlap() {
+        let generator = BspDungeonGenerator::new();
+        let mut rng = StdRng::seed_from_u64(2024);
+        let level = generator
+            .generate(&GenerationConfig::new(2024), &mut rng)
+            .unwrap();
+
+        assert!(level.rooms.len() >= 4);
+        for (i, a) in level.rooms.iter().enumerate() {
+            for b in &level.rooms[i + 1..] {
+                assert!(!a.overlaps(b), "rooms {} and {} overlap", a.id, b.id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bsp_floor_is_connected() {
+        let generator = BspDungeonGenerator::new();
+        let mut rng = StdRng::seed_from_u64(99);
+        let level = generator
+            .generate(&GenerationConfig::new(99), &mut rng)
+            .unwrap();
+
+        let start = level.player_spawn;
+        let mut seen = std::collections::HashSet::from([start]);
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            for next in pos.adjacent_positions() {
+                if level
+                    .get_tile(next)
+                    .is_some_and(|t| t.tile_type.is_passable())
+                    && seen.insert(next)
+                {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let passable = level
+            .tiles
+            .iter()
+            .flatten()
+            .filter(|tile| tile.tile_type.is_passable())
+            .count();
+        assert_eq!(seen.len(), passable);
+        assert!(seen.contains(&level.stairs_down_position.unwrap()));
+    }
+
+    #[test]
+    fn test_bsp_world_stairs_align() {
+        let generator = BspDungeonGenerator::new();
+        let config = GenerationConfig::new(5);
+        let world = generator.generate_world(&config).unwrap();
+
+        generator.validate_world(&world, &config).unwrap();
+        let entrance = world.get_level(0).unwrap();
+        assert_eq!(entrance.stairs_up_position, Some(entrance.player_spawn));
+        let deepest = world.get_level(world.shape.deepest_floor()).unwrap();
+        assert!(deepest
+            .ground_items
+            .iter()
+            .any(|stack| stack.item.name == crate::ARTIFACT_NAME));
+        assert!(deepest.stairs_down_position.is_none());
+        assert_eq!(deepest.palette, crate::TilePalette::Abyss);
+        assert_eq!(world.level_count() as u32, config.shape.floor_count);
+        assert_eq!(
+            world.get_level(3).unwrap().stairs_up_position,
+            world.get_level(2).unwrap().stairs_down_position
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! # Depth Profiles
+//!
+//! Per-depth tuning for dungeon generation.
+//!
+//! A [`DepthTable`] is an ordered list of [`DepthProfile`]s, each taking effect
+//! from its `min_depth` until the next profile starts. Profiles control how many
+//! rooms a floor gets, scale the base monster/item density and secret door
+//! chance from [`GenerationConfig`](crate::GenerationConfig), and pick the floor's [`TilePalette`]
+//! and ambient light level.
+//!
+//! The table is plain serializable data, so it can be replaced with a JSON file:
+//!
+//! ```json
+//! {
+//!   "profiles": [
+//!     { "min_depth": 0, "min_extra_rooms": 2, "max_extra_rooms": 5,
+//!       "monster_scale": 1.0, "item_scale": 1.0, "secret_door_scale": 1.0,
+//!       "palette": "Stone", "ambient_light": 1.0 },
+//!     { "min_depth": 10, "min_extra_rooms": 4, "max_extra_rooms": 8,
+//!       "monster_scale": 2.0, "item_scale": 0.8, "secret_door_scale": 2.0,
+//!       "palette": "Crypt", "ambient_light": 0.3 }
+//!   ]
+//! }
+//! ```
+
+use crate::game::TilePalette;
+use crate::{ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Generation settings for a band of dungeon depths.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthProfile {
+    /// First level id this profile applies to
+    pub min_depth: u32,
+    /// Minimum number of rooms placed in addition to the stair rooms
+    pub min_extra_rooms: u32,
+    /// Maximum number of rooms placed in addition to the stair rooms
+    pub max_extra_rooms: u32,
+    /// Multiplier applied to the base monster density
+    pub monster_scale: f64,
+    /// Multiplier applied to the base item density
+    pub item_scale: f64,
+    /// Multiplier applied to the base secret door chance
+    pub secret_door_scale: f64,
+    /// Wall and floor colors for levels in this band
+    pub palette: TilePalette,
+    /// Light level of floors in this band before any light sources (0.0 to 1.0)
+    #[serde(default = "default_ambient_light")]
+    pub ambient_light: f32,
+}
+
+fn default_ambient_light() -> f32 {
+    1.0
+}
+
+impl Default for DepthProfile {
+    fn default() -> Self {
+        Self {
+            min_depth: 0,
+            min_extra_rooms: 2,
+            max_extra_rooms: 5,
+            monster_scale: 1.0,
+            item_scale: 1.0,
+            secret_door_scale: 1.0,
+            palette: TilePalette::Stone,
+            ambient_light: default_ambient_light(),
+        }
+    }
+}
+
+/// Ordered table of depth profiles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthTable {
+    /// Profiles sorted by ascending `min_depth`, starting at depth 0
+    pub profiles: Vec<DepthProfile>,
+}
+
+impl DepthTable {
+    /// Creates the standard table used by new games.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{DepthTable, TilePalette};
+    ///
+    /// let table = DepthTable::new();
+    /// assert_eq!(table.profile_for(0).palette, TilePalette::Stone);
+    /// assert!(table.profile_for(25).monster_scale > table.profile_for(0).monster_scale);
+    /// ```
+    pub fn new() -> Self {
+        let profile =
+            |min_depth, rooms: (u32, u32), monsters, items, secrets, palette, ambient_light| {
+                DepthProfile {
+                    min_depth,
+                    min_extra_rooms: rooms.0,
+                    max_extra_rooms: rooms.1,
+                    monster_scale: monsters,
+                    item_scale: items,
+                    secret_door_scale: secrets,
+                    palette,
+                    ambient_light,
+                }
+            };
+
+        Self {
+            profiles: vec![
+                profile(0, (2, 5), 1.0, 1.0, 1.0, TilePalette::Stone, 1.0),
+                profile(5, (3, 6), 1.25, 1.0, 1.5, TilePalette::Moss, 0.6),
+                profile(10, (3, 7), 1.5, 0.9, 2.0, TilePalette::Crypt, 0.3),
+                profile(17, (4, 8), 1.75, 0.8, 2.5, TilePalette::Ember, 0.2),
+                profile(23, (4, 9), 2.0, 0.75, 3.0, TilePalette::Abyss, 0.1),
+            ],
+        }
+    }
+
+    /// Parses and validates a table from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed or the table is invalid.
+    pub fn from_json(json: &str) -> ThatchResult<Self> {
+        let table: Self = serde_json::from_str(json)?;
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Loads and validates a table from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the table is invalid.
+    pub fn load(path: &Path) -> ThatchResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Checks that the table covers depth 0, is sorted, and has sane values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first problem found.
+    pub fn validate(&self) -> ThatchResult<()> {
+        if self.profiles.first().map(|p| p.min_depth) != Some(0) {
+            return Err(ThatchError::InvalidState(
+                "Depth table must start with a profile for depth 0".to_string(),
+            ));
+        }
+        if self
+            .profiles
+            .windows(2)
+            .any(|pair| pair[0].min_depth >= pair[1].min_depth)
+        {
+            return Err(ThatchError::InvalidState(
+                "Depth profiles must be sorted by increasing min_depth".to_string(),
+            ));
+        }
+        for profile in &self.profiles {
+            if profile.min_extra_rooms > profile.max_extra_rooms {
+                return Err(ThatchError::InvalidState(format!(
+                    "Depth profile {} has more minimum than maximum rooms",
+                    profile.min_depth
+                )));
+            }
+            let scales = [
+                profile.monster_scale,
+                profile.item_scale,
+                profile.secret_door_scale,
+            ];
+            if scales
+                .iter()
+                .any(|scale| !scale.is_finite() || *scale < 0.0)
+            {
+                return Err(ThatchError::InvalidState(format!(
+                    "Depth profile {} has a negative or invalid scale",
+                    profile.min_depth
+                )));
+            }
+            if !(0.0..=1.0).contains(&profile.ambient_light) {
+                return Err(ThatchError::InvalidState(format!(
+                    "Depth profile {} has ambient light outside 0.0 to 1.0",
+                    profile.min_depth
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the profile in effect at `depth`.
+    ///
+    /// Falls back to [`DepthProfile::default`] if no profile covers the depth.
+    pub fn profile_for(&self, depth: u32) -> DepthProfile {
+        self.profiles
+            .iter()
+            .rev()
+            .find(|profile| profile.min_depth <= depth)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Default for DepthTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenerationConfig;
+
+    #[test]
+    fn test_profile_lookup_by_depth() {
+        let table = DepthTable::new();
+
+        assert_eq!(table.profile_for(0).min_depth, 0);
+        assert_eq!(table.profile_for(4).min_depth, 0);
+        assert_eq!(table.profile_for(5).palette, TilePalette::Moss);
+        assert_eq!(table.profile_for(26).palette, TilePalette::Abyss);
+        assert!(table.profile_for(26).ambient_light < table.profile_for(0).ambient_light);
+    }
+
+    #[test]
+    fn test_config_scales_with_depth() {
+        let config = GenerationConfig::new(1);
+        let shallow = config.for_depth(0);
+        let deep = config.for_depth(24);
+
+        assert_eq!(shallow.monster_density, config.monster_density);
+        assert!(deep.monster_density > shallow.monster_density);
+        assert!(deep.secret_door_chance > shallow.secret_door_chance);
+        assert!(deep.secret_door_chance <= 1.0);
+    }
+
+    #[test]
+    fn test_table_round_trip_and_validation() {
+        let json = serde_json::to_string(&DepthTable::new()).unwrap();
+        assert_eq!(DepthTable::from_json(&json).unwrap(), DepthTable::new());
+
+        let mut unsorted = DepthTable::new();
+        unsorted.profiles.swap(1, 2);
+        assert!(unsorted.validate().is_err());
+
+        let missing_surface = DepthTable {
+            profiles: DepthTable::new().profiles[1..].to_vec(),
+        };
+        assert!(missing_surface.validate().is_err());
+    }
+}
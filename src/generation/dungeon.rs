@@ -6,9 +6,12 @@
 //! interesting, connected layouts. The system supports various generation strategies
 //! and can be enhanced by the LLDM for unique architectural features.
 
-use crate::game::{Level, Position, Tile, TileType, World};
+use crate::game::{Level, Position, SpawnScheduler, Tile, TileType, World};
 use crate::generation::utils;
-use crate::generation::{GenerationConfig, Generator, Room, RoomType};
+use crate::generation::{
+    curse_items, place_out_of_depth_monster, place_unique_item, place_vault, request_floor_theme,
+    themed_floor_config, GenerationConfig, Generator, Room, RoomType,
+};
 use crate::{ThatchError, ThatchResult};
 use rand::{rngs::StdRng, Rng};
 use std::cmp::Ordering;
@@ -48,7 +51,7 @@ impl Ord for AStarNode {
 /// Primary dungeon generator using overlapping rooms and progressive wall placement.
 ///
 /// This generator creates entire 3D dungeons by:
-/// 1. Placing stairs on all 26 floors first to ensure vertical connectivity
+/// 1. Placing stairs on every floor first to ensure vertical connectivity
 /// 2. Placing rooms around stairs and randomly on each floor
 /// 3. Starting with all non-room spaces as open floor
 /// 4. Progressively adding walls while maintaining connectivity
@@ -63,7 +66,7 @@ pub struct RoomCorridorGenerator {
     pub max_placement_attempts: u32,
     /// Whether to ensure all rooms are connected (always true for this algorithm)
     pub ensure_connectivity: bool,
-    /// Whether to generate all 26 floors at once (3D generation)
+    /// Whether to generate every floor at once (3D generation)
     pub generate_all_floors: bool,
 }
 
@@ -353,12 +356,17 @@ impl RoomCorridorGenerator {
     }
 
     /// Progressively adds walls while maintaining connectivity.
+    ///
+    /// Most candidates are accepted by a constant-time local check (see
+    /// [`Self::is_locally_removable`]); only walls that might cut the level in two
+    /// fall back to a single flood fill over the level.
     fn progressive_wall_placement(
         &self,
         level: &mut Level,
         rooms: &[Room],
         rng: &mut StdRng,
     ) -> ThatchResult<()> {
+        crate::profile_scope!("progressive_wall_placement");
         let mut connectivity_failures = 0;
         let mut available_positions = self.get_non_room_floor_positions(level, rooms);
 
@@ -373,6 +381,10 @@ impl RoomCorridorGenerator {
         let max_walls_to_place = available_positions.len() / 3; // Only convert 1/3 of available positions
         let mut walls_placed = 0;
 
+        // The local check only proves that connectivity is preserved, so it is
+        // only valid while the rooms start out connected
+        let rooms_connected = self.all_rooms_connected(level, rooms)?;
+
         while connectivity_failures < max_failures
             && !available_positions.is_empty()
             && walls_placed < max_walls_to_place
@@ -386,7 +398,9 @@ impl RoomCorridorGenerator {
             level.set_tile(pos, Tile::wall())?;
 
             // Test if all rooms are still connected
-            if self.all_rooms_connected(level, rooms)? {
+            let still_connected = (rooms_connected && Self::is_locally_removable(level, pos))
+                || self.all_rooms_connected(level, rooms)?;
+            if still_connected {
                 // Wall placement is valid, keep it
                 walls_placed += 1;
             } else {
@@ -419,26 +433,57 @@ impl RoomCorridorGenerator {
         positions
     }
 
-    /// Tests if all rooms are connected using A* pathfinding.
+    /// Tests if all rooms are connected using a single flood fill from the first room.
     fn all_rooms_connected(&self, level: &Level, rooms: &[Room]) -> ThatchResult<bool> {
         if rooms.len() < 2 {
             return Ok(true);
         }
 
-        // Pick a random point in the first room as our reference
-        let start_room = &rooms[0];
-        let start_pos = start_room.center();
+        // Everything reachable from the first room's center
+        let reachable = self.flood_fill_reachable(level, rooms[0].center())?;
 
-        // Test connectivity from start room to all other rooms
-        for target_room in &rooms[1..] {
-            let target_pos = target_room.center();
+        Ok(rooms[1..]
+            .iter()
+            .all(|room| reachable.contains(&room.center())))
+    }
 
-            if !self.has_path(level, start_pos, target_pos)? {
-                return Ok(false);
-            }
+    /// Checks whether walling off `pos` keeps its passable neighbors connected
+    /// without going through `pos`.
+    ///
+    /// The passable cardinal neighbors are grouped by walking the 8-tile ring
+    /// around `pos`: two adjacent cardinals are linked when the diagonal between
+    /// them is passable too. A single group means every path through `pos` can
+    /// detour around it, so connectivity is unchanged. Returning false does not
+    /// mean connectivity breaks, only that a global check is needed.
+    fn is_locally_removable(level: &Level, pos: Position) -> bool {
+        let passable = |dx: i32, dy: i32| level.is_passable(Position::new(pos.x + dx, pos.y + dy));
+
+        // Cardinals in ring order (N, E, S, W) and the diagonals between them
+        let cardinals = [
+            passable(0, -1),
+            passable(1, 0),
+            passable(0, 1),
+            passable(-1, 0),
+        ];
+        let diagonals = [
+            passable(1, -1),
+            passable(1, 1),
+            passable(-1, 1),
+            passable(-1, -1),
+        ];
+
+        let open_count = cardinals.iter().filter(|&&open| open).count();
+        if open_count <= 1 {
+            return true;
         }
 
-        Ok(true)
+        // Each link joins cardinal i to cardinal i + 1 around the ring
+        let links = (0..4)
+            .filter(|&i| cardinals[i] && cardinals[(i + 1) % 4] && diagonals[i])
+            .count();
+
+        // With all four cardinals open, four links form a cycle but three suffice
+        links + 1 >= open_count
     }
 
     /// Uses A* pathfinding to check if there's a path between two positions.
@@ -593,7 +638,7 @@ impl RoomCorridorGenerator {
         &self,
         level: &mut Level,
         rooms: &[Room],
-        _config: &GenerationConfig,
+        config: &GenerationConfig,
         rng: &mut StdRng,
     ) -> ThatchResult<()> {
         if rooms.is_empty() {
@@ -609,7 +654,7 @@ impl RoomCorridorGenerator {
         level.player_spawn = stairs_up_pos;
 
         // Create stairs down room if not the deepest level
-        if level.id < 25 {
+        if config.shape.has_floor_below(level.id) {
             // Don't add stairs down on final level
             let stairs_down_pos =
                 self.find_stairs_position_avoiding(level, rooms, false, stairs_up_pos, rng)?;
@@ -816,150 +861,47 @@ impl RoomCorridorGenerator {
 
     /// Generates points along a line between two positions using Bresenham's algorithm.
     fn line_between_points(&self, start: Position, end: Position) -> Vec<Position> {
-        let mut points = Vec::new();
-
-        let mut x0 = start.x;
-        let mut y0 = start.y;
-        let x1 = end.x;
-        let y1 = end.y;
-
-        let dx = (x1 - x0).abs();
-        let dy = (y1 - y0).abs();
-        let sx = if x0 < x1 { 1 } else { -1 };
-        let sy = if y0 < y1 { 1 } else { -1 };
-        let mut err = dx - dy;
-
-        loop {
-            points.push(Position::new(x0, y0));
-
-            if x0 == x1 && y0 == y1 {
-                break;
-            }
-
-            let e2 = 2 * err;
-
-            if e2 > -dy {
-                err -= dy;
-                x0 += sx;
-            }
-
-            if e2 < dx {
-                err += dx;
-                y0 += sy;
-            }
-        }
-
-        points
+        start.line_to(end)
     }
 
-    /// Generates a complete 3D dungeon with all 26 floors at once.
+    /// Generates a complete 3D dungeon with every floor at once.
     ///
-    /// This method creates all levels with aligned stairs and proper connectivity:
-    /// 1. Places stairs on all floors first to ensure vertical alignment
-    /// 2. Creates rooms around stairs and randomly places additional rooms
-    /// 3. Applies the standard generation algorithm to each floor
-    pub fn generate_complete_dungeon(
-        &self,
-        config: &GenerationConfig,
-        rng: &mut StdRng,
-    ) -> ThatchResult<World> {
-        let mut world = World::new(config.seed);
-
-        // Step 1: Generate stairs positions for all 27 floors
-        let stair_positions = self.generate_stair_layout(config, rng)?;
-
-        // Step 2: Generate each floor with pre-placed stairs
-        for floor_id in 0..27 {
-            let level = self.generate_floor_with_stairs(floor_id, &stair_positions, config, rng)?;
-
-            world.add_level(level);
-        }
-
-        Ok(world)
-    }
-
-    /// Generates the stair layout for all 26 floors.
+    /// The number and size of the floors come from [`GenerationConfig::shape`].
     ///
-    /// Returns a map of floor_id -> (stairs_up_pos, stairs_down_pos)
-    /// Ensures vertical alignment between floors.
-    fn generate_stair_layout(
-        &self,
-        _config: &GenerationConfig,
-        rng: &mut StdRng,
-    ) -> ThatchResult<HashMap<u32, (Option<Position>, Option<Position>)>> {
-        let mut stair_positions = HashMap::new();
-
-        // Determine level dimensions (consistent across all floors)
-        let level_width = 80; // Fixed reasonable size
-        let level_height = 50;
-
-        // Generate stairs positions ensuring vertical alignment
-        for floor_id in 0..27 {
-            let stairs_up = if floor_id > 0 {
-                // Use the down stairs position from the floor above
-                stair_positions
-                    .get(&(floor_id - 1))
-                    .and_then(|(_, down_pos)| *down_pos)
-            } else {
-                None // No up stairs on floor 0
-            };
-
-            let stairs_down = if floor_id < 26 {
-                // Generate a new down stairs position for this floor
-                let x = rng.gen_range(5..(level_width as i32 - 5));
-                let y = rng.gen_range(5..(level_height as i32 - 5));
-
-                // Ensure down stairs is not too close to up stairs
-                let pos = if let Some(up_pos) = stairs_up {
-                    let mut attempts = 0;
-                    let mut candidate_pos = Position::new(x, y);
-
-                    while attempts < 20 && candidate_pos.manhattan_distance(up_pos) < 10 {
-                        let new_x = rng.gen_range(5..(level_width as i32 - 5));
-                        let new_y = rng.gen_range(5..(level_height as i32 - 5));
-                        candidate_pos = Position::new(new_x, new_y);
-                        attempts += 1;
-                    }
-
-                    candidate_pos
-                } else {
-                    Position::new(x, y)
-                };
-
-                Some(pos)
-            } else {
-                None // No down stairs on floor 26
-            };
-
-            stair_positions.insert(floor_id, (stairs_up, stairs_down));
-        }
-
-        Ok(stair_positions)
+    /// Floors are generated from the top down so every stair sits inside a real room:
+    /// 1. Builds a room around the stairs up, which the floor above already placed
+    /// 2. Places the rest of the floor's rooms
+    /// 3. Chooses the stairs down inside the room farthest from the stairs up
+    /// 4. Hands the stairs down to the next floor as its stairs up, keeping floors aligned
+    pub fn generate_complete_dungeon(&self, config: &GenerationConfig) -> ThatchResult<World> {
+        crate::profile_scope!("generate_complete_dungeon");
+        self.generate_world(config)
     }
 
-    /// Generates a single floor with pre-placed stairs.
+    /// Generates a single floor whose stairs up are fixed by the floor above.
     fn generate_floor_with_stairs(
         &self,
         floor_id: u32,
-        stair_positions: &HashMap<u32, (Option<Position>, Option<Position>)>,
+        stairs_up_pos: Option<Position>,
+        place_stairs_down: bool,
         config: &GenerationConfig,
         rng: &mut StdRng,
     ) -> ThatchResult<Level> {
-        let level_width = 80;
-        let level_height = 50;
+        let level_width = config.shape.floor_width;
+        let level_height = config.shape.floor_height;
         let mut level = Level::new(floor_id, level_width, level_height);
 
-        // Get stairs positions for this floor
-        let (stairs_up_pos, stairs_down_pos) = stair_positions
-            .get(&floor_id)
-            .cloned()
-            .unwrap_or((None, None));
+        // Scale room counts, densities, palette, and lighting with depth, and let the LLDM
+        // theme the floor before anything is laid out
+        let profile = config.depth_table.profile_for(floor_id);
+        let (config, theme) = themed_floor_config(floor_id, config);
+        let config = &config;
+        level.palette = profile.palette;
+        level.ambient_light = profile.ambient_light;
 
-        // Set stairs positions in level
         level.stairs_up_position = stairs_up_pos;
-        level.stairs_down_position = stairs_down_pos;
 
-        // Step 1: Create rooms around stairs and additional random rooms
+        // Step 1: Create a room around the stairs up and additional random rooms
         let mut rooms = Vec::new();
         let mut room_id = 0;
 
@@ -970,22 +912,13 @@ impl RoomCorridorGenerator {
             room_id += 1;
         }
 
-        // Create room around stairs down (if exists)
-        if let Some(down_pos) = stairs_down_pos {
-            let room = self.create_room_around_position(room_id, down_pos, config, rng, &level)?;
-            rooms.push(room);
-            room_id += 1;
-        }
-
-        // Add 2-5 additional random rooms, with more attempts if we don't have many rooms yet
-        let target_additional_rooms = rng.gen_range(2..=5);
+        // Add the profile's additional random rooms, with more attempts if we don't have many rooms yet
+        let target_additional_rooms =
+            rng.gen_range(profile.min_extra_rooms as usize..=profile.max_extra_rooms as usize);
         let mut attempts = 0;
         let max_attempts = target_additional_rooms * 10; // More attempts per room
 
-        while rooms.len()
-            < (target_additional_rooms
-                + if stairs_up_pos.is_some() { 1 } else { 0 }
-                + if stairs_down_pos.is_some() { 1 } else { 0 })
+        while rooms.len() < target_additional_rooms + usize::from(stairs_up_pos.is_some())
             && attempts < max_attempts
         {
             if let Some(room) = self.try_place_room_overlapping(&level, config, rng, room_id)? {
@@ -1007,6 +940,10 @@ impl RoomCorridorGenerator {
             );
             rooms.push(center_room);
         }
+        utils::balance_room_types(&mut rooms, floor_id, config.seed);
+        if let Some(theme) = &theme {
+            theme.furnish(&mut rooms, floor_id, config.seed);
+        }
 
         // Set player spawn to stairs up position, or center of first room if no stairs up
         level.player_spawn = if let Some(up_pos) = stairs_up_pos {
@@ -1016,10 +953,22 @@ impl RoomCorridorGenerator {
             rooms[0].center()
         };
 
+        // Floor 0's stairs up, under the player's feet, are the way out of the dungeon
+        if floor_id == 0 {
+            level.stairs_up_position = Some(level.player_spawn);
+        }
+        let stairs_up_pos = level.stairs_up_position;
+
         // Step 2: Initialize level with rooms and open floor everywhere else
         self.initialize_level_with_rooms(&mut level, &rooms)?;
 
-        // Step 3: Place stairs tiles
+        // Step 3: Choose the stairs down inside a room and place stairs tiles
+        let stairs_down_pos = if place_stairs_down {
+            Some(utils::find_stairs_down_position(&level, &rooms, rng)?)
+        } else {
+            None
+        };
+        level.stairs_down_position = stairs_down_pos;
         if let Some(up_pos) = stairs_up_pos {
             level.set_tile(up_pos, Tile::new(TileType::StairsUp))?;
         }
@@ -1038,7 +987,25 @@ impl RoomCorridorGenerator {
             }
         }
 
-        // Step 6: Fill unreachable areas with walls (disabled for now to debug)
+        // Step 6: Stamp a vault, hide a monster from deeper down, flood pools and rivers,
+        // open lava and chasms, hide secret doors, and light rooms
+        place_vault(&mut level, &mut rooms, config);
+        place_out_of_depth_monster(&mut level, &rooms, config);
+        level.spawn_scheduler = SpawnScheduler::new(level.id, &config.templates);
+        utils::place_water(&mut level, &rooms, config.water_chance, rng)?;
+        utils::place_hazards(&mut level, &rooms, floor_id, place_stairs_down, rng)?;
+        utils::place_secret_doors(&mut level, &rooms, config.secret_door_chance, rng)?;
+        utils::place_light_sources(&mut level, &rooms, rng);
+        utils::place_containers(&mut level, &rooms, &config.templates, rng);
+        curse_items(&mut level, config);
+        place_unique_item(&mut level, &rooms, config);
+        utils::place_special_tiles(&mut level, &rooms, rng)?;
+        utils::place_rubble(&mut level, &rooms, config.seed);
+        if floor_id == config.shape.deepest_floor() {
+            utils::place_artifact(&mut level, &rooms, rng);
+        }
+
+        // Step 7: Fill unreachable areas with walls (disabled for now to debug)
         // NOTE: This step might be too aggressive for 3D generation
         // self.fill_unreachable_areas(&mut level)?;
 
@@ -1057,6 +1024,9 @@ impl RoomCorridorGenerator {
             ));
         }
 
+        level.rooms = rooms;
+        level.theme = theme;
+        level.build_graph();
         utils::validate_level(&level)?;
 
         Ok(level)
@@ -1098,7 +1068,7 @@ impl Generator<Level> for RoomCorridorGenerator {
     fn generate(&self, config: &GenerationConfig, rng: &mut StdRng) -> ThatchResult<Level> {
         if self.generate_all_floors {
             // For 3D generation, just return the first floor of a complete dungeon
-            let world = self.generate_complete_dungeon(config, rng)?;
+            let world = self.generate_complete_dungeon(config)?;
             return world.get_level(0).cloned().ok_or_else(|| {
                 ThatchError::GenerationFailed(
                     "Failed to get first level from generated world".to_string(),
@@ -1114,7 +1084,8 @@ impl Generator<Level> for RoomCorridorGenerator {
         let mut level = Level::new(0, width, height);
 
         // Step 1: Place rooms (overlapping allowed)
-        let rooms = self.place_rooms(&mut level, config, rng)?;
+        let mut rooms = self.place_rooms(&mut level, config, rng)?;
+        utils::balance_room_types(&mut rooms, level.id, config.seed);
 
         // Step 2: Initialize level with rooms and open floor everywhere else
         self.initialize_level_with_rooms(&mut level, &rooms)?;
@@ -1130,10 +1101,24 @@ impl Generator<Level> for RoomCorridorGenerator {
         // Step 5: Fill unreachable areas with walls
         self.fill_unreachable_areas(&mut level)?;
 
+        // Step 6: Stamp a vault, hide a monster from deeper down, flood pools and rivers,
+        // hide secret doors, and light rooms
+        place_vault(&mut level, &mut rooms, config);
+        place_out_of_depth_monster(&mut level, &rooms, config);
+        level.spawn_scheduler = SpawnScheduler::new(level.id, &config.templates);
+        utils::place_water(&mut level, &rooms, config.water_chance, rng)?;
+        utils::place_secret_doors(&mut level, &rooms, config.secret_door_chance, rng)?;
+        utils::place_light_sources(&mut level, &rooms, rng);
+        utils::place_containers(&mut level, &rooms, &config.templates, rng);
+        curse_items(&mut level, config);
+        place_unique_item(&mut level, &rooms, config);
+        utils::place_special_tiles(&mut level, &rooms, rng)?;
+        utils::place_rubble(&mut level, &rooms, config.seed);
+
+        level.rooms = rooms;
+
         // Apply LLDM enhancements if enabled
-        if config.use_lldm {
-            // LLDM enhancement would be implemented here
-        }
+        self.apply_lldm_enhancements(&mut level, config, rng)?;
 
         // Final validation
         utils::validate_level(&level)?;
@@ -1147,6 +1132,7 @@ impl Generator<Level> for RoomCorridorGenerator {
                 self.create_stair_connection(&mut level, stairs_up, stairs_down)?;
             }
         }
+        level.build_graph();
 
         Ok(level)
     }
@@ -1159,36 +1145,18 @@ impl Generator<Level> for RoomCorridorGenerator {
         "RoomCorridorGenerator"
     }
 
+    /// Themes a level generated on its own, which is laid out before the LLDM
+    /// is asked, so only the theme's libraries apply.
     fn apply_lldm_enhancements(
         &self,
         level: &mut Level,
         config: &GenerationConfig,
-        rng: &mut StdRng,
+        _rng: &mut StdRng,
     ) -> ThatchResult<()> {
-        if !config.use_lldm {
-            return Ok(());
-        }
-
-        // LLDM enhancement implementation would go here
-        // For now, just add some random special tiles
-        let enhancement_count = (level.width * level.height / 200) as usize;
-
-        for _ in 0..enhancement_count {
-            let x = rng.gen_range(0..level.width) as i32;
-            let y = rng.gen_range(0..level.height) as i32;
-            let pos = Position::new(x, y);
-
-            if let Some(tile) = level.get_tile(pos) {
-                if tile.tile_type == TileType::Floor && rng.gen_bool(config.lldm_enhancement_chance)
-                {
-                    let special_tile = Tile::new(TileType::Special {
-                        description: "A mysterious tile with unknown properties".to_string(),
-                    });
-                    level.set_tile(pos, special_tile)?;
-                }
-            }
+        if let Some(theme) = request_floor_theme(level.id, config) {
+            theme.furnish(&mut level.rooms, level.id, config.seed);
+            level.theme = Some(theme);
         }
-
         Ok(())
     }
 }
@@ -1196,47 +1164,55 @@ impl Generator<Level> for RoomCorridorGenerator {
 /// Trait for generating complete dungeon worlds.
 pub trait WorldGenerator {
     /// Generates a complete multi-level world.
-    fn generate_world(&self, config: &GenerationConfig, rng: &mut StdRng) -> ThatchResult<World>;
+    ///
+    /// Every floor is generated from its own [`utils::floor_rng`], so a world
+    /// generated all at once matches one generated floor by floor.
+    fn generate_world(&self, config: &GenerationConfig) -> ThatchResult<World> {
+        let mut world = World::new(config.seed);
+        world.shape = config.shape;
+        let mut stairs_up = None;
+
+        // Each floor's up stairs sit where the floor above put its down stairs
+        for floor_id in 0..config.shape.floor_count {
+            let mut rng = utils::floor_rng(config.seed, floor_id);
+            let level = self.generate_floor(floor_id, stairs_up, config, &mut rng)?;
+            stairs_up = level.stairs_down_position;
+            world.add_level(level);
+        }
+
+        Ok(world)
+    }
+
+    /// Generates a single floor of a world.
+    ///
+    /// `stairs_up` is where the floor above put its stairs down, or `None` for
+    /// floor 0. The deepest floor gets no stairs down.
+    fn generate_floor(
+        &self,
+        floor_id: u32,
+        stairs_up: Option<Position>,
+        config: &GenerationConfig,
+        rng: &mut StdRng,
+    ) -> ThatchResult<Level>;
 
     /// Validates a generated world.
     fn validate_world(&self, world: &World, config: &GenerationConfig) -> ThatchResult<()>;
 }
 
 impl WorldGenerator for RoomCorridorGenerator {
-    fn generate_world(&self, config: &GenerationConfig, rng: &mut StdRng) -> ThatchResult<World> {
-        self.generate_complete_dungeon(config, rng)
+    fn generate_floor(
+        &self,
+        floor_id: u32,
+        stairs_up: Option<Position>,
+        config: &GenerationConfig,
+        rng: &mut StdRng,
+    ) -> ThatchResult<Level> {
+        let place_stairs_down = config.shape.has_floor_below(floor_id);
+        self.generate_floor_with_stairs(floor_id, stairs_up, place_stairs_down, config, rng)
     }
 
     fn validate_world(&self, world: &World, _config: &GenerationConfig) -> ThatchResult<()> {
-        // Validate each level in the world
-        for level in world.levels.values() {
-            utils::validate_level(level)?;
-        }
-
-        // Validate stair connectivity between levels
-        for level_id in 0..25 {
-            if let (Some(current_level), Some(next_level)) =
-                (world.get_level(level_id), world.get_level(level_id + 1))
-            {
-                // Check that down stairs on current level align with up stairs on next level
-                if let (Some(down_pos), Some(up_pos)) = (
-                    current_level.stairs_down_position,
-                    next_level.stairs_up_position,
-                ) {
-                    if down_pos != up_pos {
-                        return Err(ThatchError::GenerationFailed(format!(
-                            "Stair misalignment between levels {} and {}: down at {:?}, up at {:?}",
-                            level_id,
-                            level_id + 1,
-                            down_pos,
-                            up_pos
-                        )));
-                    }
-                }
-            }
-        }
-
-        Ok(())
+        utils::validate_world_stairs(world)
     }
 }
 
@@ -1393,6 +1369,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_is_locally_removable() {
+        let mut level = Level::new(0, 10, 10);
+
+        // Open 3x3 area: the center can be walled off without splitting anything
+        for y in 4..=6 {
+            for x in 4..=6 {
+                level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+            }
+        }
+        assert!(RoomCorridorGenerator::is_locally_removable(
+            &level,
+            Position::new(5, 5)
+        ));
+
+        // A one-wide horizontal corridor: the middle tile is a cut point
+        let mut corridor = Level::new(0, 10, 10);
+        for x in 3..=7 {
+            corridor
+                .set_tile(Position::new(x, 5), Tile::floor())
+                .unwrap();
+        }
+        assert!(!RoomCorridorGenerator::is_locally_removable(
+            &corridor,
+            Position::new(5, 5)
+        ));
+
+        // The corridor's dead end is always safe
+        assert!(RoomCorridorGenerator::is_locally_removable(
+            &corridor,
+            Position::new(7, 5)
+        ));
+    }
+
     #[test]
     fn test_flood_fill_reachable() {
         let generator = RoomCorridorGenerator::new();
@@ -1511,49 +1521,44 @@ mod tests {
     }
 
     #[test]
-    fn test_3d_stair_layout_generation() {
+    fn test_3d_stairs_sit_inside_rooms() {
         let generator = RoomCorridorGenerator::new();
         let config = GenerationConfig::for_testing(12345);
-        let mut rng = utils::create_rng(&config);
-
-        let stair_positions = generator.generate_stair_layout(&config, &mut rng).unwrap();
-
-        // Should have positions for all 26 floors
-        assert_eq!(stair_positions.len(), 26);
-
-        // Floor 0 should have no up stairs but should have down stairs
-        let (up_0, down_0) = stair_positions.get(&0).unwrap();
-        assert!(up_0.is_none());
-        assert!(down_0.is_some());
-
-        // Floor 25 should have up stairs but no down stairs
-        let (up_25, down_25) = stair_positions.get(&25).unwrap();
-        assert!(up_25.is_some());
-        assert!(down_25.is_none());
-
-        // Middle floors should have both up and down stairs
-        for floor_id in 1..25 {
-            let (up_pos, down_pos) = stair_positions.get(&floor_id).unwrap();
-            assert!(up_pos.is_some(), "Floor {} should have up stairs", floor_id);
-            assert!(
-                down_pos.is_some(),
-                "Floor {} should have down stairs",
-                floor_id
-            );
-        }
+        let world = generator.generate_complete_dungeon(&config).unwrap();
+        let last_floor = world.levels.len() as u32 - 1;
+
+        for floor_id in 0..=last_floor {
+            let level = world.get_level(floor_id).unwrap();
+            let in_a_room = |pos: Position| {
+                level
+                    .rooms
+                    .iter()
+                    .any(|room| room.floor_positions().contains(&pos))
+            };
 
-        // Verify stair alignment: down stairs on floor N should match up stairs on floor N+1
-        for floor_id in 0..25 {
-            let (_, down_pos) = stair_positions.get(&floor_id).unwrap();
-            let (up_pos_next, _) = stair_positions.get(&(floor_id + 1)).unwrap();
-
-            assert_eq!(
-                down_pos,
-                up_pos_next,
-                "Stairs should align between floors {} and {}",
-                floor_id,
-                floor_id + 1
-            );
+            // Stairs up are built around, so they always have a room
+            let up_pos = level.stairs_up_position.unwrap();
+            assert!(level.rooms.iter().any(|room| room.contains(up_pos)));
+
+            match level.stairs_down_position {
+                Some(down_pos) => {
+                    assert!(floor_id < last_floor, "Floor {} should be the bottom", floor_id);
+                    assert!(
+                        in_a_room(down_pos),
+                        "Floor {} stairs down are outside rooms",
+                        floor_id
+                    );
+                    assert_ne!(down_pos, up_pos);
+                    assert_eq!(
+                        world.get_level(floor_id + 1).unwrap().stairs_up_position,
+                        Some(down_pos),
+                        "Stairs should align between floors {} and {}",
+                        floor_id,
+                        floor_id + 1
+                    );
+                }
+                None => assert_eq!(floor_id, last_floor, "Floor {} has no way down", floor_id),
+            }
         }
     }
 
@@ -1561,11 +1566,7 @@ mod tests {
     fn test_complete_dungeon_generation() {
         let generator = RoomCorridorGenerator::new();
         let config = GenerationConfig::for_testing(54321);
-        let mut rng = utils::create_rng(&config);
-
-        let world = generator
-            .generate_complete_dungeon(&config, &mut rng)
-            .unwrap();
+        let world = generator.generate_complete_dungeon(&config).unwrap();
 
         // Should have all 26 levels
         assert_eq!(world.levels.len(), 26);
@@ -1604,10 +1605,8 @@ mod tests {
     fn test_world_generator_trait() {
         let generator = RoomCorridorGenerator::new();
         let config = GenerationConfig::for_testing(98765);
-        let mut rng = utils::create_rng(&config);
-
         // Test world generation through trait
-        let world = generator.generate_world(&config, &mut rng).unwrap();
+        let world = generator.generate_world(&config).unwrap();
 
         // Test world validation through trait
         assert!(generator.validate_world(&world, &config).is_ok());
@@ -1668,12 +1667,8 @@ mod tests {
         let config = GenerationConfig::for_testing(99999);
         let mut rng = utils::create_rng(&config);
 
-        // Generate stair layout
-        let stair_positions = generator.generate_stair_layout(&config, &mut rng).unwrap();
-
         // Test generating just floor 0
-        let floor_0_result =
-            generator.generate_floor_with_stairs(0, &stair_positions, &config, &mut rng);
+        let floor_0_result = generator.generate_floor_with_stairs(0, None, true, &config, &mut rng);
 
         match floor_0_result {
             Ok(level) => {
@@ -1699,40 +1694,34 @@ mod tests {
     #[test]
     fn test_stair_alignment_consistency() {
         let generator = RoomCorridorGenerator::new();
-        let config = GenerationConfig::for_testing(44444);
-        let mut rng = utils::create_rng(&config);
 
-        // Generate multiple stair layouts and verify consistency
+        // Generate multiple dungeons and verify consistency
         for seed_offset in 0..5 {
-            let mut test_rng =
-                utils::create_rng(&GenerationConfig::for_testing(44444 + seed_offset));
-            let stair_positions = generator
-                .generate_stair_layout(&config, &mut test_rng)
-                .unwrap();
-
-            // Verify basic properties
-            assert_eq!(stair_positions.len(), 26);
-
-            // Check first and last floors
-            let (up_0, down_0) = stair_positions.get(&0).unwrap();
-            assert!(up_0.is_none());
-            assert!(down_0.is_some());
-
-            let (up_25, down_25) = stair_positions.get(&25).unwrap();
-            assert!(up_25.is_some());
-            assert!(down_25.is_none());
+            let config = GenerationConfig::for_testing(44444 + seed_offset);
+            let world = generator.generate_complete_dungeon(&config).unwrap();
+            let last_floor = world.levels.len() as u32 - 1;
+
+            // Floor 0's stairs up lead out of the dungeon, from the spawn point
+            let floor_0 = world.get_level(0).unwrap();
+            assert_eq!(floor_0.stairs_up_position, Some(floor_0.player_spawn));
+            assert!(world
+                .get_level(last_floor)
+                .unwrap()
+                .stairs_down_position
+                .is_none());
 
             // Verify alignment
-            for floor_id in 0..25 {
-                let (_, down_current) = stair_positions.get(&floor_id).unwrap();
-                let (up_next, _) = stair_positions.get(&(floor_id + 1)).unwrap();
+            for floor_id in 0..last_floor {
+                let down_current = world.get_level(floor_id).unwrap().stairs_down_position;
+                let next = world.get_level(floor_id + 1).unwrap();
+                assert!(down_current.is_some());
                 assert_eq!(
-                    down_current,
-                    up_next,
+                    down_current, next.stairs_up_position,
                     "Stairs misaligned between floors {} and {}",
                     floor_id,
                     floor_id + 1
                 );
+                assert_eq!(next.player_spawn, down_current.unwrap());
             }
         }
     }
@@ -1775,7 +1764,6 @@ mod tests {
     #[test]
     fn test_progressive_wall_placement_3d_vs_single() {
         let config = GenerationConfig::for_testing(66666);
-        let mut rng = utils::create_rng(&config);
 
         // Create identical starting levels
         let mut level_3d = Level::new(0, 30, 20);
@@ -1835,12 +1823,8 @@ mod tests {
     fn test_generate_complete_dungeon_performance() {
         let generator = RoomCorridorGenerator::new();
         let config = GenerationConfig::for_testing(77777);
-        let mut rng = utils::create_rng(&config);
-
         let start_time = std::time::Instant::now();
-        let world = generator
-            .generate_complete_dungeon(&config, &mut rng)
-            .unwrap();
+        let world = generator.generate_complete_dungeon(&config).unwrap();
         let generation_time = start_time.elapsed();
 
         // Should complete in reasonable time (less than 30 seconds in debug mode)
@@ -1874,11 +1858,7 @@ mod tests {
     fn test_3d_generation_stair_connectivity() {
         let generator = RoomCorridorGenerator::new();
         let config = GenerationConfig::for_testing(88888);
-        let mut rng = utils::create_rng(&config);
-
-        let world = generator
-            .generate_complete_dungeon(&config, &mut rng)
-            .unwrap();
+        let world = generator.generate_complete_dungeon(&config).unwrap();
 
         // Test that stairs are connected within each level
         for level_id in 1..25 {
@@ -1904,9 +1884,7 @@ mod tests {
 
         // Test with invalid config (this shouldn't fail but let's test the pipeline)
         let config = GenerationConfig::for_testing(99999);
-        let mut rng = utils::create_rng(&config);
-
-        let world = generator.generate_world(&config, &mut rng);
+        let world = generator.generate_world(&config);
         assert!(
             world.is_ok(),
             "World generation should handle edge cases gracefully"
@@ -2,20 +2,39 @@
 //!
 //! Procedural encounter and monster placement system with LLDM integration
 //! for creating dynamic, narrative-driven encounters.
+//!
+//! Now and then a floor also hides an out-of-depth monster: a species that
+//! would normally only be met several floors further down, waiting in one of
+//! the rooms away from the stairs.
 
-use crate::{GenerationConfig, Generator, ThatchResult};
+use crate::{GenerationConfig, Generator, Level, Room, ThatchResult, WaitingMonster};
 use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Chance of a floor holding an out-of-depth monster.
+pub const OUT_OF_DEPTH_CHANCE: f64 = 0.08;
+
+/// Most floors deeper than the current one an out-of-depth monster comes from.
+pub const OUT_OF_DEPTH_LEVELS: u32 = 5;
+
+/// Salt of the out-of-depth pass's [`utils::pass_rng`](super::utils::pass_rng) stream.
+const OUT_OF_DEPTH_SEED: u64 = 0x0000_00d0_0d5e;
 
-/// Placeholder for encounter generation system.
+/// Rolls monster species from the configuration's templates.
 ///
-/// This will be implemented later with comprehensive encounter generation
-/// including monster placement, trap generation, and LLDM-enhanced encounters.
+/// Generates one species name per unit of monster density, picked from the
+/// species available at depth 0. Placing the monsters in a level is left to
+/// the caller.
 pub struct EncounterGenerator;
 
 impl Generator<Vec<String>> for EncounterGenerator {
-    fn generate(&self, _config: &GenerationConfig, _rng: &mut StdRng) -> ThatchResult<Vec<String>> {
-        // Placeholder implementation
-        Ok(Vec::new())
+    fn generate(&self, config: &GenerationConfig, rng: &mut StdRng) -> ThatchResult<Vec<String>> {
+        let count = config.monster_density.round() as usize;
+        Ok((0..count)
+            .filter_map(|_| config.templates.pick_monster(0, rng))
+            .map(|monster| monster.name.clone())
+            .collect())
     }
 
     fn validate(&self, _content: &Vec<String>, _config: &GenerationConfig) -> ThatchResult<()> {
@@ -26,3 +45,80 @@ impl Generator<Vec<String>> for EncounterGenerator {
         "EncounterGenerator"
     }
 }
+
+/// Queues a monster from deeper down on a floor, with chance [`OUT_OF_DEPTH_CHANCE`].
+///
+/// The species first appears up to [`OUT_OF_DEPTH_LEVELS`] floors below this
+/// one. It waits on open floor in one of the
+/// [`eligible_special_rooms`](super::utils::eligible_special_rooms), clear of
+/// the stairs. Returns the species placed.
+pub fn place_out_of_depth_monster(
+    level: &mut Level,
+    rooms: &[Room],
+    config: &GenerationConfig,
+) -> Option<String> {
+    let mut rng = super::utils::pass_rng(config.seed, OUT_OF_DEPTH_SEED, level.id);
+    if !rng.gen_bool(OUT_OF_DEPTH_CHANCE) {
+        return None;
+    }
+    let template = config
+        .templates
+        .pick_out_of_depth_monster(level.id, OUT_OF_DEPTH_LEVELS, &mut rng)?;
+
+    let reserved = [
+        Some(level.player_spawn),
+        level.stairs_up_position,
+        level.stairs_down_position,
+    ];
+    let spots: Vec<_> = super::utils::eligible_special_rooms(rooms)
+        .flat_map(|(_, room)| room.floor_positions())
+        .filter(|&position| {
+            level.get_tile(position).is_some_and(|tile| tile.tile_type == crate::TileType::Floor)
+                && !reserved.contains(&Some(position))
+                && !level.waiting_monsters.iter().any(|waiting| waiting.position == position)
+        })
+        .collect();
+    let position = *spots.choose(&mut rng)?;
+    level.waiting_monsters.push(WaitingMonster {
+        position,
+        monster: template.clone(),
+        out_of_depth: true,
+    });
+    Some(template.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, RoomType, Tile};
+
+    #[test]
+    fn test_out_of_depth_monsters_come_from_deeper_floors() {
+        let config = GenerationConfig::new(3);
+        let far_room = Room::new(1, Position::new(8, 0), 6, 6, RoomType::Normal);
+        let placed: Vec<(u32, WaitingMonster)> = (0..100)
+            .filter_map(|floor_id| {
+                let mut level = Level::new(floor_id, 20, 10);
+                let rooms = vec![
+                    Room::new(0, Position::new(0, 0), 6, 6, RoomType::Normal),
+                    far_room.clone(),
+                ];
+                for room in &rooms {
+                    for position in room.floor_positions() {
+                        level.set_tile(position, Tile::floor()).unwrap();
+                    }
+                }
+                place_out_of_depth_monster(&mut level, &rooms, &config)?;
+                Some((floor_id, level.waiting_monsters.pop()?))
+            })
+            .collect();
+
+        assert!(!placed.is_empty());
+        for (floor_id, waiting) in placed {
+            assert!(waiting.out_of_depth);
+            assert!(waiting.monster.min_depth > floor_id);
+            assert!(waiting.monster.min_depth <= floor_id + OUT_OF_DEPTH_LEVELS);
+            assert!(far_room.floor_positions().contains(&waiting.position));
+        }
+    }
+}
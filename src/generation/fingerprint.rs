@@ -0,0 +1,205 @@
+//! # World Fingerprints
+//!
+//! Stable hashes of generated worlds, for checking that a seed builds the
+//! same dungeon on every platform.
+//!
+//! A fingerprint covers everything the generator decides about a floor: its
+//! tiles, rooms, stairs, lights, items, chests and waiting monsters. Entity
+//! IDs are random and left out, as is anything that changes during play such
+//! as exploration and lighting. Floors are hashed in order of their ID,
+//! numbers as little-endian bytes and floats by their bits, and the hash is
+//! FNV-1a, which unlike the standard library's hasher is the same on every
+//! platform and Rust release. The golden hashes in this module's tests pin
+//! the output for a few seeds, and `thatch --dump-world-hash` prints them for
+//! any seed.
+
+use crate::{Item, Level, Position, World};
+use serde::Serialize;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a hasher with helpers for the values found in a level.
+struct Fingerprint(u64);
+
+impl Fingerprint {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn position(&mut self, position: Position) {
+        self.bytes(&position.x.to_le_bytes());
+        self.bytes(&position.y.to_le_bytes());
+    }
+
+    fn optional_position(&mut self, position: Option<Position>) {
+        self.bool(position.is_some());
+        if let Some(position) = position {
+            self.position(position);
+        }
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.bytes(&[u8::from(value)]);
+    }
+
+    /// Hashes a string with its length, so neighbouring strings cannot run together.
+    fn str(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.bytes(value.as_bytes());
+    }
+
+    /// Hashes a value by its JSON form, for enums and other small types
+    /// without maps inside them.
+    fn json(&mut self, value: &impl Serialize) {
+        self.str(&serde_json::to_string(value).unwrap_or_default());
+    }
+
+    fn item(&mut self, item: &Item) {
+        self.str(&item.name);
+        self.json(&item.item_type);
+        self.position(item.position);
+        self.json(&item.charges);
+        self.bool(item.cursed);
+        self.bytes(&item.enchantment.to_le_bytes());
+    }
+}
+
+/// Hashes what the generator decided about a level.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{level_hash, Level, Position, Tile};
+///
+/// let mut level = Level::new(0, 10, 10);
+/// let blank = level_hash(&level);
+/// assert_eq!(level_hash(&level.clone()), blank);
+///
+/// level.set_tile(Position::new(3, 3), Tile::floor()).unwrap();
+/// assert_ne!(level_hash(&level), blank);
+/// ```
+pub fn level_hash(level: &Level) -> u64 {
+    let mut hash = Fingerprint::new();
+    hash.u32(level.id);
+    hash.u32(level.width);
+    hash.u32(level.height);
+    for tile in level.tiles.iter().flatten() {
+        hash.json(&tile.tile_type);
+    }
+    hash.position(level.player_spawn);
+    hash.optional_position(level.stairs_up_position);
+    hash.optional_position(level.stairs_down_position);
+    hash.json(&level.palette);
+    hash.u32(level.ambient_light.to_bits());
+
+    hash.u64(level.rooms.len() as u64);
+    for room in &level.rooms {
+        hash.u32(room.id);
+        hash.position(room.top_left);
+        hash.u32(room.width);
+        hash.u32(room.height);
+        hash.json(&room.room_type);
+        hash.json(&room.connections);
+    }
+    hash.u64(level.light_sources.len() as u64);
+    for source in &level.light_sources {
+        hash.json(&source.kind);
+        hash.position(source.position);
+    }
+    hash.u64(level.ground_items.iter().count() as u64);
+    for stack in level.ground_items.iter() {
+        hash.item(&stack.item);
+        hash.u32(stack.count);
+    }
+    hash.u64(level.containers.len() as u64);
+    for container in &level.containers {
+        hash.str(&container.name);
+        hash.position(container.position);
+        hash.bool(container.locked);
+        hash.json(&container.trap);
+        hash.u64(container.contents.len() as u64);
+        for item in &container.contents {
+            hash.item(item);
+        }
+    }
+    hash.u64(level.waiting_monsters.len() as u64);
+    for waiting in &level.waiting_monsters {
+        hash.position(waiting.position);
+        hash.str(&waiting.monster.name);
+        hash.bool(waiting.out_of_depth);
+    }
+    for wanderer in &level.spawn_scheduler.wanderers {
+        hash.str(wanderer);
+    }
+    hash.0
+}
+
+/// Hashes every floor of a world, returning each floor's ID and hash in
+/// order of ID.
+pub fn floor_hashes(world: &World) -> Vec<(u32, u64)> {
+    world
+        .levels
+        .iter()
+        .map(|(&id, level)| (id, level_hash(level)))
+        .collect()
+}
+
+/// Hashes a whole world by combining its [`floor_hashes`].
+pub fn world_hash(world: &World) -> u64 {
+    let mut hash = Fingerprint::new();
+    for (id, floor) in floor_hashes(world) {
+        hash.u32(id);
+        hash.u64(floor);
+    }
+    hash.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, GenerationConfig, GeneratorKind};
+
+    /// Hashes of worlds generated with the default settings, as
+    /// (seed, layout, world hash). A change to generation that alters the
+    /// dungeon a seed builds should update these on purpose.
+    const GOLDEN_WORLDS: [(u64, GeneratorKind, u64); 4] = [
+        (12345, GeneratorKind::Caverns, 0xe49e_6fc7_8561_246a),
+        (42, GeneratorKind::Caverns, 0x1c5b_f76c_73d0_b83a),
+        (12345, GeneratorKind::Bsp, 0x9177_5ddc_760d_06cd),
+        (7, GeneratorKind::Bsp, 0xa199_5bc6_52b5_fe70),
+    ];
+
+    fn generate(seed: u64, generator: GeneratorKind) -> World {
+        let config = GenerationConfig {
+            generator,
+            ..GenerationConfig::new(seed)
+        };
+        GameState::new_with_generation_config(config).unwrap().world
+    }
+
+    #[test]
+    fn test_seeds_generate_their_golden_worlds() {
+        for (seed, generator, golden) in GOLDEN_WORLDS {
+            let world = generate(seed, generator);
+            let hash = world_hash(&world);
+            assert_eq!(hash, golden, "seed {} with the {} layout", seed, generator);
+            // Generating again in the same process must not depend on map order
+            assert_eq!(world_hash(&generate(seed, generator)), hash);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! # Room Graph
+//!
+//! The rooms of a level and the corridors between them.
+//!
+//! Once a floor is generated its rooms become the nodes of a [`LevelGraph`].
+//! Two rooms are joined by an edge when a corridor leads from one to the
+//! other without passing through a third room, or when they share an opening
+//! or overlap. Doors count as open, hidden or not, so the graph describes the
+//! layout as built rather than what the player has found. The graph is
+//! stored on the [`Level`] and used for placing quests, planning patrols, and
+//! describing the shape of a floor to the LLDM.
+
+use crate::{Level, Position, TileType};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// A way between two rooms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomEdge {
+    /// The room with the lower ID
+    pub from: u32,
+    /// The room with the higher ID
+    pub to: u32,
+    /// Tiles of corridor between the rooms, zero if they touch
+    pub corridor_length: u32,
+}
+
+impl RoomEdge {
+    /// Gets the room at the other end of the edge from `room_id`.
+    pub fn other(&self, room_id: u32) -> Option<u32> {
+        if self.from == room_id {
+            Some(self.to)
+        } else if self.to == room_id {
+            Some(self.from)
+        } else {
+            None
+        }
+    }
+}
+
+/// Rooms as nodes and the corridors joining them as edges.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{Level, LevelGraph, Position, Room, RoomType, Tile};
+///
+/// // Two rooms joined by a three-tile corridor along y = 3
+/// let mut level = Level::new(0, 20, 10);
+/// let left = Room::new(0, Position::new(1, 1), 5, 5, RoomType::Normal);
+/// let right = Room::new(1, Position::new(9, 1), 5, 5, RoomType::Treasure);
+/// for room in [&left, &right] {
+///     for position in room.floor_positions() {
+///         level.set_tile(position, Tile::floor()).unwrap();
+///     }
+/// }
+/// for x in 5..=9 {
+///     level.set_tile(Position::new(x, 3), Tile::floor()).unwrap();
+/// }
+/// level.rooms = vec![left, right];
+///
+/// let graph = LevelGraph::build(&level);
+/// assert_eq!(graph.neighbors(0), vec![1]);
+/// assert_eq!(graph.corridor_length(0, 1), Some(3));
+/// assert_eq!(graph.route(1, 0), Some(vec![1, 0]));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelGraph {
+    /// IDs of every room on the level, in order
+    pub rooms: Vec<u32>,
+    /// Ways between rooms, each listed once, ordered by their rooms
+    pub edges: Vec<RoomEdge>,
+}
+
+impl LevelGraph {
+    /// Builds the graph of a level's rooms from its tiles.
+    pub fn build(level: &Level) -> Self {
+        let mut owners: HashMap<Position, Vec<u32>> = HashMap::new();
+        for room in &level.rooms {
+            for position in room.all_positions() {
+                owners.entry(position).or_default().push(room.id);
+            }
+        }
+        let open = |position: Position| {
+            level.get_tile(position).is_some_and(|tile| {
+                tile.tile_type.is_passable()
+                    || matches!(tile.tile_type, TileType::Door { .. } | TileType::SecretDoor)
+            })
+        };
+
+        // Walk out of each room through corridors, stopping at the first room reached
+        let mut lengths: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+        for room in &level.rooms {
+            let mut queue: VecDeque<(Position, u32)> = room
+                .all_positions()
+                .into_iter()
+                .filter(|&position| open(position))
+                .map(|position| (position, 0))
+                .collect();
+            let mut visited: HashSet<Position> =
+                queue.iter().map(|(position, _)| *position).collect();
+
+            while let Some((position, length)) = queue.pop_front() {
+                for neighbor in position.cardinal_adjacent_positions() {
+                    if !open(neighbor) || !visited.insert(neighbor) {
+                        continue;
+                    }
+                    let others: Vec<u32> = owners
+                        .get(&neighbor)
+                        .map(|ids| ids.iter().copied().filter(|&id| id != room.id).collect())
+                        .unwrap_or_default();
+                    if others.is_empty() {
+                        queue.push_back((neighbor, length + 1));
+                    }
+                    for other in others {
+                        let key = (room.id.min(other), room.id.max(other));
+                        let shortest = lengths.entry(key).or_insert(length);
+                        *shortest = (*shortest).min(length);
+                    }
+                }
+            }
+        }
+
+        Self {
+            rooms: level.rooms.iter().map(|room| room.id).collect(),
+            edges: lengths
+                .into_iter()
+                .map(|((from, to), corridor_length)| RoomEdge {
+                    from,
+                    to,
+                    corridor_length,
+                })
+                .collect(),
+        }
+    }
+
+    /// Lists the rooms one edge away from a room, in order.
+    pub fn neighbors(&self, room_id: u32) -> Vec<u32> {
+        let mut neighbors: Vec<u32> = self
+            .edges
+            .iter()
+            .filter_map(|edge| edge.other(room_id))
+            .collect();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    /// Gets the corridor length between two rooms, or None if no edge joins them.
+    pub fn corridor_length(&self, a: u32, b: u32) -> Option<u32> {
+        let (from, to) = (a.min(b), a.max(b));
+        self.edges
+            .iter()
+            .find(|edge| edge.from == from && edge.to == to)
+            .map(|edge| edge.corridor_length)
+    }
+
+    /// Finds a route through the fewest rooms, both ends included.
+    ///
+    /// Returns None if the rooms are not connected.
+    pub fn route(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        if !self.rooms.contains(&from) || !self.rooms.contains(&to) {
+            return None;
+        }
+        let mut came_from: HashMap<u32, u32> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        came_from.insert(from, from);
+        while let Some(room) = queue.pop_front() {
+            if room == to {
+                let mut route = vec![to];
+                let mut current = to;
+                while current != from {
+                    current = came_from[&current];
+                    route.push(current);
+                }
+                route.reverse();
+                return Some(route);
+            }
+            for neighbor in self.neighbors(room) {
+                if let Entry::Vacant(entry) = came_from.entry(neighbor) {
+                    entry.insert(room);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        None
+    }
+
+    /// Lists the rooms with a single way in, good spots for quest goals.
+    pub fn dead_ends(&self) -> Vec<u32> {
+        self.rooms
+            .iter()
+            .copied()
+            .filter(|&room| self.neighbors(room).len() == 1)
+            .collect()
+    }
+
+    /// Groups the rooms into sets that can all reach each other.
+    pub fn components(&self) -> Vec<Vec<u32>> {
+        let mut seen = HashSet::new();
+        let mut components = Vec::new();
+        for &start in &self.rooms {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(room) = queue.pop_front() {
+                for neighbor in self.neighbors(room) {
+                    if seen.insert(neighbor) {
+                        component.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Describes each room and where it leads, one line per room.
+    ///
+    /// Only rooms for which `include` returns true are listed, so callers can
+    /// leave out rooms the player has not seen.
+    pub fn describe(&self, level: &Level, include: impl Fn(u32) -> bool) -> Vec<String> {
+        level
+            .rooms
+            .iter()
+            .filter(|room| include(room.id))
+            .map(|room| {
+                let kind = room.room_type.name();
+                let exits: Vec<String> = self
+                    .neighbors(room.id)
+                    .into_iter()
+                    .filter(|&other| include(other))
+                    .map(|other| match self.corridor_length(room.id, other) {
+                        Some(0) | None => format!("room {} (adjoining)", other),
+                        Some(length) => format!("room {} ({} tile corridor)", other, length),
+                    })
+                    .collect();
+                if exits.is_empty() {
+                    format!("Room {} ({}): no known exits", room.id, kind)
+                } else {
+                    format!("Room {} ({}): leads to {}", room.id, kind, exits.join(", "))
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Room, RoomType, Tile};
+
+    #[test]
+    fn test_graph_joins_rooms_through_corridors_only() {
+        // Three rooms in a row; the middle one sits between the outer two
+        let mut level = Level::new(0, 30, 10);
+        let rooms = [
+            Room::new(0, Position::new(1, 1), 5, 5, RoomType::Normal),
+            Room::new(1, Position::new(8, 1), 5, 5, RoomType::Normal),
+            Room::new(2, Position::new(15, 1), 5, 5, RoomType::Boss),
+        ];
+        for room in &rooms {
+            for position in room.floor_positions() {
+                level.set_tile(position, Tile::floor()).unwrap();
+            }
+        }
+        for x in 5..=8 {
+            level.set_tile(Position::new(x, 3), Tile::floor()).unwrap();
+        }
+        level
+            .set_tile(Position::new(12, 2), Tile::new(TileType::SecretDoor))
+            .unwrap();
+        for x in 13..=15 {
+            level.set_tile(Position::new(x, 2), Tile::floor()).unwrap();
+        }
+        level.rooms = rooms.to_vec();
+
+        let graph = LevelGraph::build(&level);
+        assert_eq!(graph.neighbors(1), vec![0, 2]);
+        assert_eq!(graph.corridor_length(0, 2), None);
+        assert_eq!(graph.corridor_length(2, 1), Some(2));
+        assert_eq!(graph.route(0, 2), Some(vec![0, 1, 2]));
+        assert_eq!(graph.dead_ends(), vec![0, 2]);
+        assert_eq!(graph.components(), vec![vec![0, 1, 2]]);
+
+        let lines = graph.describe(&level, |room| room != 2);
+        assert_eq!(
+            lines[1],
+            "Room 1 (normal): leads to room 0 (2 tile corridor)"
+        );
+        assert_eq!(lines.len(), 2);
+
+        // Generated floors come with their graph
+        let game_state = crate::GameState::new(12345);
+        let level = game_state.world.current_level().unwrap();
+        assert_eq!(level.graph.rooms.len(), level.rooms.len());
+        for room in &level.rooms {
+            assert_eq!(room.connections, level.graph.neighbors(room.id));
+        }
+    }
+}
@@ -2,20 +2,53 @@
 //!
 //! Procedural item generation system for creating weapons, armor, consumables,
 //! and unique items with potential LLDM enhancements.
+//!
+//! Once a floor's items are placed, [`curse_items`] curses some of the gear
+//! among them, more of it the deeper the floor, and now and then
+//! [`place_unique_item`] leaves a unique item for the player to find.
 
-use crate::{GenerationConfig, Generator, ThatchResult};
+use crate::{
+    forge_unique, unique_enchantment_range, GenerationConfig, Generator, Item, Level, Room,
+    RoomType, ThatchResult, UniqueFlavor, UNIQUE_OWNERS, UNIQUE_TEMPLATES,
+};
 use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Chance for a piece of gear on the first floor to be cursed.
+pub const BASE_CURSE_CHANCE: f64 = 0.05;
+
+/// Added chance for gear to be cursed on each floor further down.
+pub const CURSE_CHANCE_PER_FLOOR: f64 = 0.01;
+
+/// Highest chance for gear to be cursed, however deep.
+pub const MAX_CURSE_CHANCE: f64 = 0.3;
+
+/// Largest penalty a curse puts on an item's enchantment.
+pub const MAX_CURSE_PENALTY: i32 = 3;
+
+/// Salt of the cursing pass's [`utils::pass_rng`](super::utils::pass_rng) stream.
+const CURSE_SEED: u64 = 0x0000_c0a5_e5ed;
+
+/// Chance of a floor holding a unique item.
+pub const UNIQUE_ITEM_CHANCE: f64 = 0.2;
+
+/// Salt of the unique item pass's [`utils::pass_rng`](super::utils::pass_rng) stream.
+const UNIQUE_SEED: u64 = 0x0000_0011_9e17;
 
-/// Placeholder for item generation system.
+/// Rolls item archetypes from the configuration's templates.
 ///
-/// This will be implemented later with comprehensive item generation
-/// including weapons, armor, consumables, and LLDM-enhanced unique items.
+/// Generates one item name per unit of item density, picked from the archetypes
+/// available at depth 0. Placing the items in a level is left to the caller.
 pub struct ItemGenerator;
 
 impl Generator<Vec<String>> for ItemGenerator {
-    fn generate(&self, _config: &GenerationConfig, _rng: &mut StdRng) -> ThatchResult<Vec<String>> {
-        // Placeholder implementation
-        Ok(Vec::new())
+    fn generate(&self, config: &GenerationConfig, rng: &mut StdRng) -> ThatchResult<Vec<String>> {
+        let count = config.item_density.round() as usize;
+        Ok((0..count)
+            .filter_map(|_| config.templates.pick_item(0, rng))
+            .map(|item| item.name.clone())
+            .collect())
     }
 
     fn validate(&self, _content: &Vec<String>, _config: &GenerationConfig) -> ThatchResult<()> {
@@ -26,3 +59,134 @@ impl Generator<Vec<String>> for ItemGenerator {
         "ItemGenerator"
     }
 }
+
+/// Gets the chance for a piece of gear generated on a floor to be cursed.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{curse_chance, BASE_CURSE_CHANCE, MAX_CURSE_CHANCE};
+///
+/// assert_eq!(curse_chance(0), BASE_CURSE_CHANCE);
+/// assert!(curse_chance(5) > curse_chance(0));
+/// assert_eq!(curse_chance(100), MAX_CURSE_CHANCE);
+/// ```
+pub fn curse_chance(floor_id: u32) -> f64 {
+    (BASE_CURSE_CHANCE + floor_id as f64 * CURSE_CHANCE_PER_FLOOR).min(MAX_CURSE_CHANCE)
+}
+
+/// Curses some of the gear lying on a floor or waiting in its chests.
+///
+/// Each weapon and piece of armor is cursed with probability
+/// [`curse_chance`] for the floor, taking a penalty of up to
+/// [`MAX_CURSE_PENALTY`] to its enchantment. Neither shows until the player
+/// finds out. Returns the number of items cursed.
+pub fn curse_items(level: &mut Level, config: &GenerationConfig) -> u32 {
+    let mut rng = super::utils::pass_rng(config.seed, CURSE_SEED, level.id);
+    let chance = curse_chance(level.id);
+    let mut curse = |item: &mut Item| {
+        if item.item_type.equip_slot().is_none() || !rng.gen_bool(chance) {
+            return false;
+        }
+        item.cursed = true;
+        item.enchantment = -rng.gen_range(1..=MAX_CURSE_PENALTY);
+        true
+    };
+
+    let mut cursed = 0;
+    for stack in level.ground_items.iter_mut() {
+        cursed += curse(&mut stack.item) as u32;
+    }
+    for container in &mut level.containers {
+        for item in &mut container.contents {
+            cursed += curse(item) as u32;
+        }
+    }
+    cursed
+}
+
+/// Leaves a unique item on a floor, with chance [`UNIQUE_ITEM_CHANCE`].
+///
+/// Its kind and enchantment are rolled within the bounds of
+/// [`forge_unique`], and it is named from the offline name tables; the LLDM
+/// may rename it once the player arrives. It lies on open floor in a treasure
+/// room if the floor has one, or else in one of the
+/// [`eligible_special_rooms`](super::utils::eligible_special_rooms). Returns
+/// the item's name.
+pub fn place_unique_item(
+    level: &mut Level,
+    rooms: &[Room],
+    config: &GenerationConfig,
+) -> Option<String> {
+    let mut rng = super::utils::pass_rng(config.seed, UNIQUE_SEED, level.id);
+    if !rng.gen_bool(UNIQUE_ITEM_CHANCE) {
+        return None;
+    }
+    let template = UNIQUE_TEMPLATES.choose(&mut rng)?;
+    let enchantment = rng.gen_range(unique_enchantment_range(level.id));
+    let flavor = UniqueFlavor::offline(template, rng.gen_range(0..UNIQUE_OWNERS.len() * 2));
+
+    let treasure: Vec<&Room> = rooms
+        .iter()
+        .filter(|room| room.room_type == RoomType::Treasure)
+        .collect();
+    let candidates = if treasure.is_empty() {
+        super::utils::eligible_special_rooms(rooms)
+            .map(|(_, room)| room)
+            .collect()
+    } else {
+        treasure
+    };
+    let spots: Vec<_> = candidates
+        .into_iter()
+        .flat_map(Room::floor_positions)
+        .filter(|&position| {
+            level.get_tile(position).is_some_and(|tile| tile.tile_type == crate::TileType::Floor)
+                && position != level.player_spawn
+                && level.container_at(position).is_none()
+        })
+        .collect();
+    let position = *spots.choose(&mut rng)?;
+
+    let item = forge_unique(template, level.id, enchantment, &flavor, position).ok()?;
+    let name = item.name.clone();
+    level.ground_items.drop_item(item);
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArmorType, ItemType, Position};
+
+    #[test]
+    fn test_deeper_floors_curse_more_gear() {
+        let config = GenerationConfig::new(7);
+        let curse_floor = |floor_id| {
+            let mut level = Level::new(floor_id, 20, 20);
+            for x in 0..20 {
+                let helm = ItemType::Armor(ArmorType::Helmet);
+                let position = Position::new(x, 1);
+                level.ground_items.drop_item(Item::new("Iron helm".to_string(), helm, position));
+                let potion = ItemType::Consumable(crate::ConsumableType::HealthPotion);
+                let position = Position::new(x, 2);
+                level.ground_items.drop_item(Item::new("Potion".to_string(), potion, position));
+            }
+            let cursed = curse_items(&mut level, &config);
+            let potions_cursed = level
+                .ground_items
+                .iter()
+                .any(|stack| stack.item.cursed && stack.item.name == "Potion");
+            assert!(!potions_cursed);
+            assert!(level
+                .ground_items
+                .iter()
+                .filter(|stack| stack.item.cursed)
+                .all(|stack| (-MAX_CURSE_PENALTY..0).contains(&stack.item.enchantment)));
+            cursed
+        };
+        let shallow: u32 = (0..10).map(curse_floor).sum();
+        let deep: u32 = (25..35).map(curse_floor).sum();
+        assert!(deep > shallow);
+    }
+}
@@ -6,20 +6,99 @@
 //! It includes dungeon layout generation, item creation, and encounter placement.
 //! The system is designed to integrate with the LLDM for enhanced content generation.
 
+pub mod bsp;
+pub mod depth;
 pub mod dungeon;
 pub mod encounters;
+pub mod fingerprint;
+pub mod graph;
 pub mod items;
+pub mod streaming;
+pub mod templates;
+pub mod themes;
+pub mod vaults;
 
+pub use bsp::*;
+pub use depth::*;
 pub use dungeon::*;
 pub use encounters::*;
+pub use fingerprint::*;
+pub use graph::*;
 pub use items::*;
-
-use crate::game::{Level, Position, TileType};
+pub use streaming::*;
+pub use templates::*;
+pub use themes::*;
+pub use vaults::*;
+
+use crate::game::{
+    ChestTrap, Container, Item, ItemType, Level, LightKind, LightSource, Position, Tile, TileEffect,
+    TileOverlay, TileType, World, WorldShape, ARTIFACT_NAME, CHEST_KEY, FLOOR_TRAP_DAMAGE,
+    FOUNTAIN_HEAL,
+};
 use crate::{ThatchError, ThatchResult};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Layout algorithm used to build dungeon floors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GeneratorKind {
+    /// Overlapping rooms carved by progressive wall placement ([`RoomCorridorGenerator`])
+    #[default]
+    Caverns,
+    /// Non-overlapping rooms joined by 1-tile corridors ([`BspDungeonGenerator`])
+    Bsp,
+}
+
+impl GeneratorKind {
+    /// Name used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            GeneratorKind::Caverns => "caverns",
+            GeneratorKind::Bsp => "bsp",
+        }
+    }
+
+    /// Creates the generator for complete multi-floor dungeons.
+    pub fn world_generator(self) -> Box<dyn WorldGenerator> {
+        match self {
+            GeneratorKind::Caverns => Box::new(RoomCorridorGenerator::new()),
+            GeneratorKind::Bsp => Box::new(BspDungeonGenerator::new()),
+        }
+    }
+
+    /// Creates the generator for single floors.
+    pub fn level_generator(self) -> Box<dyn Generator<Level>> {
+        match self {
+            GeneratorKind::Caverns => Box::new(RoomCorridorGenerator::new()),
+            GeneratorKind::Bsp => Box::new(BspDungeonGenerator::new()),
+        }
+    }
+}
+
+impl fmt::Display for GeneratorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for GeneratorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "caverns" => Ok(GeneratorKind::Caverns),
+            "bsp" => Ok(GeneratorKind::Bsp),
+            other => Err(format!(
+                "Unknown generator '{}' (expected 'caverns' or 'bsp')",
+                other
+            )),
+        }
+    }
+}
 
 /// Configuration for procedural generation.
 ///
@@ -43,14 +122,30 @@ pub struct GenerationConfig {
     pub extra_connection_chance: f64,
     /// Probability of secret doors (0.0 to 1.0)
     pub secret_door_chance: f64,
+    /// Probability of each room holding a pool, and of a floor having a river (0.0 to 1.0)
+    #[serde(default)]
+    pub water_chance: f64,
     /// Monster density (monsters per 100 floor tiles)
     pub monster_density: f64,
     /// Item density (items per 100 floor tiles)
     pub item_density: f64,
     /// Whether to use LLDM for content enhancement
     pub use_lldm: bool,
-    /// LLDM enhancement probability (0.0 to 1.0)
-    pub lldm_enhancement_chance: f64,
+    /// Layout algorithm used for each floor
+    #[serde(default)]
+    pub generator: GeneratorKind,
+    /// Per-depth scaling of room counts, densities, and palette
+    #[serde(default)]
+    pub depth_table: DepthTable,
+    /// Monster species and item archetypes to place
+    #[serde(default)]
+    pub templates: TemplateLibrary,
+    /// Number and size of the floors in a generated world
+    #[serde(default)]
+    pub shape: WorldShape,
+    /// Prefab set pieces stamped into some floors
+    #[serde(default)]
+    pub vaults: VaultLibrary,
 }
 
 impl GenerationConfig {
@@ -75,10 +170,15 @@ impl GenerationConfig {
             corridor_width: 1,
             extra_connection_chance: 0.15,
             secret_door_chance: 0.05,
+            water_chance: 0.1,
             monster_density: 2.0,
             item_density: 1.5,
             use_lldm: false,
-            lldm_enhancement_chance: 0.3,
+            generator: GeneratorKind::Caverns,
+            depth_table: DepthTable::new(),
+            templates: TemplateLibrary::default(),
+            shape: WorldShape::default(),
+            vaults: VaultLibrary::default(),
         }
     }
 
@@ -93,15 +193,21 @@ impl GenerationConfig {
             corridor_width: 1,
             extra_connection_chance: 0.1,
             secret_door_chance: 0.0,
+            water_chance: 0.0,
             monster_density: 1.0,
             item_density: 0.5,
             use_lldm: false,
-            lldm_enhancement_chance: 0.0,
+            generator: GeneratorKind::Caverns,
+            depth_table: DepthTable::new(),
+            templates: TemplateLibrary::default(),
+            shape: WorldShape::default(),
+            vaults: VaultLibrary::default(),
         }
     }
 
-    /// Creates a configuration for detailed, complex levels.
-    pub fn for_detailed_generation(seed: u64) -> Self {
+    /// Creates a configuration for detailed, complex levels, themed by the
+    /// LLDM if `use_lldm` is set.
+    pub fn for_detailed_generation(seed: u64, use_lldm: bool) -> Self {
         Self {
             seed,
             min_room_size: 6,
@@ -111,10 +217,29 @@ impl GenerationConfig {
             corridor_width: 1,
             extra_connection_chance: 0.25,
             secret_door_chance: 0.1,
+            water_chance: 0.15,
             monster_density: 3.0,
             item_density: 2.5,
-            use_lldm: true,
-            lldm_enhancement_chance: 0.4,
+            use_lldm,
+            generator: GeneratorKind::Caverns,
+            depth_table: DepthTable::new(),
+            templates: TemplateLibrary::default(),
+            shape: WorldShape::default(),
+            vaults: VaultLibrary::default(),
+        }
+    }
+
+    /// Returns a copy of this configuration scaled for the given depth.
+    ///
+    /// Monster and item density and the secret door chance are multiplied by
+    /// the depth profile's scales; the chance is capped at 1.0.
+    pub fn for_depth(&self, depth: u32) -> GenerationConfig {
+        let profile = self.depth_table.profile_for(depth);
+        GenerationConfig {
+            monster_density: self.monster_density * profile.monster_scale,
+            item_density: self.item_density * profile.item_scale,
+            secret_door_chance: (self.secret_door_chance * profile.secret_door_scale).min(1.0),
+            ..self.clone()
         }
     }
 }
@@ -125,6 +250,71 @@ impl Default for GenerationConfig {
     }
 }
 
+/// Chance of each room getting a wall torch.
+pub const TORCH_CHANCE: f64 = 0.5;
+
+/// Chance of each room getting a patch of glowing fungus.
+pub const FUNGUS_CHANCE: f64 = 0.25;
+
+/// Chance of a treasure room's chest being locked.
+pub const CHEST_LOCKED_CHANCE: f64 = 0.4;
+
+/// Chance of a treasure room's chest being trapped.
+pub const CHEST_TRAP_CHANCE: f64 = 0.25;
+
+/// Most items a chest can hold.
+pub const MAX_CHEST_ITEMS: usize = 3;
+
+/// Chance of a level getting a healing fountain.
+pub const FOUNTAIN_CHANCE: f64 = 0.3;
+
+/// Chance of a level getting a pair of linked teleporters.
+pub const TELEPORTER_CHANCE: f64 = 0.25;
+
+/// Chance of a level getting an inscribed plaque.
+pub const PLAQUE_CHANCE: f64 = 0.2;
+
+/// Chance of each room outside shops and sanctuaries getting a floor trap.
+pub const FLOOR_TRAP_CHANCE: f64 = 0.15;
+
+/// First floor, counting from 0, on which lava and chasms appear.
+pub const HAZARD_MIN_DEPTH: u32 = 4;
+
+/// Chance of each ordinary room on a deep enough floor getting a lava pool or chasm.
+pub const HAZARD_CHANCE: f64 = 0.2;
+
+/// Chance of each ordinary room having partly caved in, leaving a heap of rubble.
+pub const RUBBLE_CHANCE: f64 = 0.15;
+
+/// Most shops a floor can have.
+pub const MAX_SHOPS_PER_FLOOR: usize = 1;
+
+/// Every this many floors, counting from the first, is guaranteed a sanctuary.
+pub const SANCTUARY_INTERVAL: u32 = 3;
+
+/// Added chance per floor of depth for an ordinary room to become a treasure room.
+pub const TREASURE_CHANCE_PER_FLOOR: f64 = 0.01;
+
+/// Largest added chance for an ordinary room to become a treasure room.
+pub const MAX_TREASURE_CHANCE: f64 = 0.15;
+
+/// Salt of the room balancing pass's [`utils::pass_rng`] stream.
+const ROOM_BALANCE_SEED: u64 = 0x0005_eed0_b41a;
+
+/// Salt of the rubble pass's [`utils::pass_rng`] stream.
+const RUBBLE_SEED: u64 = 0x0005_eed0_4ab1;
+
+/// Inscriptions a generated plaque can bear.
+pub const PLAQUE_INSCRIPTIONS: [&str; 4] = [
+    "Here fell the Seventh Company. May the stairs remember them.",
+    "Turn back. The Amulet does not want to be found.",
+    "Dug by the hands of the Thatch guild, year of the long winter.",
+    "What is lost below is never lost for good.",
+];
+
+/// Room metadata key holding LLDM-generated flavor text for the look command.
+pub const ROOM_FLAVOR_METADATA_KEY: &str = "flavor_text";
+
 /// Represents a rectangular room in the dungeon.
 ///
 /// Rooms are the primary structural element of generated dungeons.
@@ -151,6 +341,7 @@ pub struct Room {
     /// Optional description (LLDM can set this)
     pub description: Option<String>,
     /// Room-specific metadata for LLDM integration
+    #[serde(serialize_with = "crate::sorted_map")]
     pub metadata: HashMap<String, String>,
 }
 
@@ -181,6 +372,25 @@ pub enum RoomType {
     LldmGenerated { subtype: String },
 }
 
+impl RoomType {
+    /// Returns a short lowercase name for the room type.
+    pub fn name(&self) -> &str {
+        match self {
+            RoomType::Normal => "normal",
+            RoomType::Treasure => "treasure",
+            RoomType::Boss => "boss",
+            RoomType::Shop => "shop",
+            RoomType::Puzzle => "puzzle",
+            RoomType::Sanctuary => "sanctuary",
+            RoomType::Library => "library",
+            RoomType::Prison => "prison",
+            RoomType::Throne => "throne",
+            RoomType::Secret => "secret",
+            RoomType::LldmGenerated { subtype } => subtype,
+        }
+    }
+}
+
 impl Room {
     /// Creates a new room with the given parameters.
     ///
@@ -343,6 +553,12 @@ impl Room {
     pub fn get_metadata(&self, key: &str) -> Option<&String> {
         self.metadata.get(key)
     }
+
+    /// Gets the LLDM-generated flavor text for this room, if any.
+    pub fn flavor_text(&self) -> Option<&str> {
+        self.get_metadata(ROOM_FLAVOR_METADATA_KEY)
+            .map(String::as_str)
+    }
 }
 
 /// Trait for procedural generators.
@@ -381,6 +597,96 @@ pub mod utils {
         StdRng::seed_from_u64(config.seed)
     }
 
+    /// Creates the random number generator for one floor of a world.
+    ///
+    /// Each floor gets its own stream, so a floor comes out the same no matter
+    /// when, or on which thread, it is generated.
+    pub fn floor_rng(seed: u64, floor_id: u32) -> StdRng {
+        StdRng::seed_from_u64(seed.wrapping_add(floor_id as u64 * 1000))
+    }
+
+    /// Creates the random number generator for a pass run over a floor after
+    /// its layout is dug.
+    ///
+    /// Every such pass mixes its own `salt` into the world seed, so it draws
+    /// from a stream apart from the layout's and from the other passes'.
+    /// Adding, removing or reordering a pass never shifts the layout or what
+    /// the other passes roll.
+    pub fn pass_rng(seed: u64, salt: u64, floor_id: u32) -> StdRng {
+        floor_rng(seed ^ salt, floor_id)
+    }
+
+    /// Gets the rooms of a floor that may hold a vault, an encounter or a
+    /// special item, with their indices.
+    ///
+    /// That is every room but the first, where the player arrives, and the
+    /// shops and sanctuaries, which stay peaceful.
+    pub fn eligible_special_rooms(rooms: &[Room]) -> impl Iterator<Item = (usize, &Room)> {
+        rooms
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, room)| !matches!(room.room_type, RoomType::Shop | RoomType::Sanctuary))
+    }
+
+    /// Gets the added chance for an ordinary room on a floor to become a treasure room.
+    pub fn treasure_room_chance(floor_id: u32) -> f64 {
+        (floor_id as f64 * TREASURE_CHANCE_PER_FLOOR).min(MAX_TREASURE_CHANCE)
+    }
+
+    /// Evens out the special rooms of a floor after their types are rolled.
+    ///
+    /// Shops past [`MAX_SHOPS_PER_FLOOR`] become ordinary rooms, every
+    /// [`SANCTUARY_INTERVAL`]th floor gets a sanctuary if it has none, and
+    /// ordinary rooms become treasure rooms with [`treasure_room_chance`]. The
+    /// first room, where the player arrives, counts toward the shop limit but
+    /// never becomes a sanctuary or a treasure room. Rolls come from a
+    /// [`pass_rng`] stream. Returns the number of rooms changed.
+    pub fn balance_room_types(rooms: &mut [Room], floor_id: u32, seed: u64) -> usize {
+        let mut rng = pass_rng(seed, ROOM_BALANCE_SEED, floor_id);
+        let mut changed = 0;
+
+        let mut shops = 0;
+        for room in rooms.iter_mut() {
+            if room.room_type == RoomType::Shop {
+                shops += 1;
+                if shops > MAX_SHOPS_PER_FLOOR {
+                    room.room_type = RoomType::Normal;
+                    changed += 1;
+                }
+            }
+        }
+
+        let Some((_, rooms)) = rooms.split_first_mut() else {
+            return changed;
+        };
+
+        let sanctuary_floor = (floor_id + 1).is_multiple_of(SANCTUARY_INTERVAL);
+        if sanctuary_floor && !rooms.iter().any(|room| room.room_type == RoomType::Sanctuary) {
+            let ordinary: Vec<usize> = (0..rooms.len())
+                .filter(|&index| rooms[index].room_type == RoomType::Normal)
+                .collect();
+            let index = if ordinary.is_empty() {
+                (!rooms.is_empty()).then(|| rng.gen_range(0..rooms.len()))
+            } else {
+                Some(ordinary[rng.gen_range(0..ordinary.len())])
+            };
+            if let Some(index) = index {
+                rooms[index].room_type = RoomType::Sanctuary;
+                changed += 1;
+            }
+        }
+
+        let treasure_chance = treasure_room_chance(floor_id);
+        for room in rooms.iter_mut().filter(|room| room.room_type == RoomType::Normal) {
+            if rng.gen_bool(treasure_chance) {
+                room.room_type = RoomType::Treasure;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
     /// Checks if two rooms are adjacent (for corridor placement).
     pub fn rooms_are_adjacent(room1: &Room, room2: &Room, max_distance: u32) -> bool {
         let center1 = room1.center();
@@ -432,6 +738,529 @@ pub mod utils {
         // Additional validation can be added here
         Ok(())
     }
+
+    /// Hides a secret door in some room walls.
+    ///
+    /// Each room gets one secret door with probability `chance`, placed on a
+    /// wall tile that separates the room's floor from passable ground outside.
+    /// Secret doors only ever add shortcuts, so connectivity is never reduced.
+    /// Returns the number of doors placed.
+    pub fn place_secret_doors(
+        level: &mut Level,
+        rooms: &[Room],
+        chance: f64,
+        rng: &mut StdRng,
+    ) -> ThatchResult<u32> {
+        if chance <= 0.0 {
+            return Ok(0);
+        }
+
+        let passable = |level: &Level, pos: Position| {
+            level
+                .get_tile(pos)
+                .is_some_and(|tile| tile.tile_type.is_passable())
+        };
+
+        let mut placed = 0;
+        for room in rooms {
+            if !rng.gen_bool(chance.min(1.0)) {
+                continue;
+            }
+
+            let candidates: Vec<Position> = room
+                .wall_positions()
+                .into_iter()
+                .filter(|&pos| {
+                    level
+                        .get_tile(pos)
+                        .is_some_and(|tile| tile.tile_type == TileType::Wall)
+                })
+                .filter(|&pos| {
+                    let horizontal = passable(level, Position::new(pos.x - 1, pos.y))
+                        && passable(level, Position::new(pos.x + 1, pos.y));
+                    let vertical = passable(level, Position::new(pos.x, pos.y - 1))
+                        && passable(level, Position::new(pos.x, pos.y + 1));
+                    horizontal || vertical
+                })
+                .collect();
+
+            if !candidates.is_empty() {
+                let pos = candidates[rng.gen_range(0..candidates.len())];
+                level.set_tile(pos, Tile::new(TileType::SecretDoor))?;
+                placed += 1;
+            }
+        }
+
+        Ok(placed)
+    }
+
+    /// Floods parts of a level with water pools and rivers.
+    ///
+    /// Each room gets a roughly elliptical pool in its interior with probability
+    /// `chance`, and with the same probability a river meanders across the whole
+    /// level. Only floor tiles are flooded, and the player spawn and stairs are
+    /// never touched. Water is passable, so connectivity is unaffected.
+    /// Returns the number of tiles flooded.
+    pub fn place_water(
+        level: &mut Level,
+        rooms: &[Room],
+        chance: f64,
+        rng: &mut StdRng,
+    ) -> ThatchResult<u32> {
+        if chance <= 0.0 {
+            return Ok(0);
+        }
+        let chance = chance.min(1.0);
+
+        let mut flooded = 0;
+        let mut flood = |level: &mut Level, pos: Position| -> ThatchResult<()> {
+            let is_floor = level
+                .get_tile(pos)
+                .is_some_and(|tile| tile.tile_type == TileType::Floor);
+            if is_floor && pos != level.player_spawn {
+                level.set_tile(pos, Tile::new(TileType::Water))?;
+                flooded += 1;
+            }
+            Ok(())
+        };
+
+        for room in rooms {
+            // Leave a dry ring inside the walls so the room's doorways stay dry
+            if room.width < 6 || room.height < 6 || !rng.gen_bool(chance) {
+                continue;
+            }
+            let center = room.center();
+            let radius_x = (room.width as i32 - 4) / 2;
+            let radius_y = (room.height as i32 - 4) / 2;
+            for dy in -radius_y..=radius_y {
+                for dx in -radius_x..=radius_x {
+                    let nx = dx as f64 / radius_x.max(1) as f64;
+                    let ny = dy as f64 / radius_y.max(1) as f64;
+                    if nx * nx + ny * ny <= 1.0 {
+                        flood(level, Position::new(center.x + dx, center.y + dy))?;
+                    }
+                }
+            }
+        }
+
+        if level.height > 4 && rng.gen_bool(chance) {
+            let mut y = rng.gen_range(2..level.height as i32 - 2);
+            for x in 1..level.width as i32 - 1 {
+                flood(level, Position::new(x, y))?;
+                y = (y + rng.gen_range(-1..=1)).clamp(2, level.height as i32 - 3);
+                flood(level, Position::new(x, y))?;
+            }
+        }
+
+        Ok(flooded)
+    }
+
+    /// Opens lava pools and chasms in the ordinary rooms of a deep floor.
+    ///
+    /// Floors above [`HAZARD_MIN_DEPTH`] are left alone. Below it, each
+    /// ordinary room without stairs gets a pool with probability
+    /// [`HAZARD_CHANCE`], filling the middle of the room and leaving a safe
+    /// ring inside the walls so the room can still be walked around. Chasms
+    /// need a floor under them, so with `chasms` unset every pool is lava.
+    /// Like [`place_water`], only floor tiles other than the player spawn are
+    /// touched, and none holding an item or a waiting monster. Returns the
+    /// number of tiles turned into hazards.
+    pub fn place_hazards(
+        level: &mut Level,
+        rooms: &[Room],
+        depth: u32,
+        chasms: bool,
+        rng: &mut StdRng,
+    ) -> ThatchResult<u32> {
+        if depth < HAZARD_MIN_DEPTH {
+            return Ok(0);
+        }
+
+        let mut placed = 0;
+        for room in rooms {
+            // Small rooms would be all hazard, and special and stair rooms keep their floors
+            let has_stairs = [level.stairs_up_position, level.stairs_down_position]
+                .into_iter()
+                .flatten()
+                .any(|stairs| room.contains(stairs));
+            if room.width < 7
+                || room.height < 7
+                || room.room_type != RoomType::Normal
+                || has_stairs
+                || !rng.gen_bool(HAZARD_CHANCE)
+            {
+                continue;
+            }
+            let tile_type = if chasms && rng.gen_bool(0.5) {
+                TileType::Chasm
+            } else {
+                TileType::Lava
+            };
+            let center = room.center();
+            let radius_x = (room.width as i32 - 5) / 2;
+            let radius_y = (room.height as i32 - 5) / 2;
+            for dy in -radius_y..=radius_y {
+                for dx in -radius_x..=radius_x {
+                    let pos = Position::new(center.x + dx, center.y + dy);
+                    let is_floor = level
+                        .get_tile(pos)
+                        .is_some_and(|tile| tile.tile_type == TileType::Floor);
+                    let occupied = level.ground_items.top_at(pos).is_some()
+                        || level.waiting_monsters.iter().any(|waiting| waiting.position == pos);
+                    if is_floor && !occupied && pos != level.player_spawn {
+                        level.set_tile(pos, Tile::new(tile_type.clone()))?;
+                        placed += 1;
+                    }
+                }
+            }
+        }
+        Ok(placed)
+    }
+
+    /// Heaps rubble where the ceilings of a few ordinary rooms have caved in.
+    ///
+    /// Each ordinary room gets a ragged heap of [`TileOverlay::Rubble`] around
+    /// one of its floor tiles with probability [`RUBBLE_CHANCE`]. Rubble only
+    /// slows the going, so connectivity is unaffected, and the stairs and the
+    /// player spawn are kept clear. Rolls come from a [`pass_rng`] stream.
+    /// Returns the number of tiles covered.
+    pub fn place_rubble(level: &mut Level, rooms: &[Room], seed: u64) -> u32 {
+        let mut rng = pass_rng(seed, RUBBLE_SEED, level.id);
+        let mut covered = 0;
+        for room in rooms {
+            let floor = room.floor_positions();
+            if room.room_type != RoomType::Normal
+                || floor.is_empty()
+                || !rng.gen_bool(RUBBLE_CHANCE)
+            {
+                continue;
+            }
+            let center = floor[rng.gen_range(0..floor.len())];
+            for pos in std::iter::once(center).chain(center.adjacent_positions()) {
+                let is_floor = level
+                    .get_tile(pos)
+                    .is_some_and(|tile| tile.tile_type == TileType::Floor);
+                if is_floor
+                    && pos != level.player_spawn
+                    && rng.gen_bool(0.6)
+                    && level.add_overlay(pos, TileOverlay::Rubble)
+                {
+                    covered += 1;
+                }
+            }
+        }
+        covered
+    }
+
+    /// Places wall torches and glowing fungus in rooms.
+    ///
+    /// Each room gets a torch on a wall tile bordering its floor with
+    /// probability [`TORCH_CHANCE`], and a fungus patch on one of its floor
+    /// tiles with probability [`FUNGUS_CHANCE`]. Returns the number of lights placed.
+    pub fn place_light_sources(level: &mut Level, rooms: &[Room], rng: &mut StdRng) -> u32 {
+        let mut placed = 0;
+        for room in rooms {
+            if rng.gen_bool(TORCH_CHANCE) {
+                let candidates: Vec<Position> = room
+                    .wall_positions()
+                    .into_iter()
+                    .filter(|&pos| {
+                        level
+                            .get_tile(pos)
+                            .is_some_and(|tile| tile.tile_type == TileType::Wall)
+                            && pos
+                                .cardinal_adjacent_positions()
+                                .into_iter()
+                                .any(|neighbor| level.is_passable(neighbor))
+                    })
+                    .collect();
+                if !candidates.is_empty() {
+                    let pos = candidates[rng.gen_range(0..candidates.len())];
+                    level.light_sources.push(LightSource::new(LightKind::Torch, pos));
+                    placed += 1;
+                }
+            }
+
+            if rng.gen_bool(FUNGUS_CHANCE) {
+                let candidates: Vec<Position> = room
+                    .floor_positions()
+                    .into_iter()
+                    .filter(|&pos| {
+                        level
+                            .get_tile(pos)
+                            .is_some_and(|tile| tile.tile_type == TileType::Floor)
+                    })
+                    .collect();
+                if !candidates.is_empty() {
+                    let pos = candidates[rng.gen_range(0..candidates.len())];
+                    level.light_sources.push(LightSource::new(LightKind::Fungus, pos));
+                    placed += 1;
+                }
+            }
+        }
+        placed
+    }
+
+    /// Places a chest of loot from `templates` in every treasure room.
+    ///
+    /// A chest is locked with probability [`CHEST_LOCKED_CHANCE`], in which case
+    /// its key is left on the floor of another room, and trapped with
+    /// probability [`CHEST_TRAP_CHANCE`]. A chest is never locked when no other
+    /// room has floor free for the key. Returns the number of chests placed.
+    pub fn place_containers(
+        level: &mut Level,
+        rooms: &[Room],
+        templates: &TemplateLibrary,
+        rng: &mut StdRng,
+    ) -> u32 {
+        let open_floor = |level: &Level, room: &Room| -> Vec<Position> {
+            room.floor_positions()
+                .into_iter()
+                .filter(|&pos| {
+                    level
+                        .get_tile(pos)
+                        .is_some_and(|tile| tile.tile_type == TileType::Floor)
+                        && level.container_at(pos).is_none()
+                })
+                .collect()
+        };
+
+        let mut placed = 0;
+        for (index, room) in rooms
+            .iter()
+            .enumerate()
+            .filter(|(_, room)| room.room_type == RoomType::Treasure)
+        {
+            let candidates = open_floor(level, room);
+            if candidates.is_empty() {
+                continue;
+            }
+            let pos = candidates[rng.gen_range(0..candidates.len())];
+
+            let contents = (0..rng.gen_range(1..=MAX_CHEST_ITEMS))
+                .filter_map(|_| templates.pick_item(level.id, rng))
+                .map(|item| item.spawn(pos))
+                .collect();
+            let mut chest = Container::chest(pos, contents);
+            if rng.gen_bool(CHEST_TRAP_CHANCE) {
+                chest.trap = Some(if rng.gen_bool(0.5) {
+                    ChestTrap::Needle
+                } else {
+                    ChestTrap::Flame
+                });
+            }
+            let key_spots: Vec<Position> = rooms
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .flat_map(|(_, other)| open_floor(level, other))
+                .collect();
+            if !key_spots.is_empty() && rng.gen_bool(CHEST_LOCKED_CHANCE) {
+                chest.locked = true;
+                let key_pos = key_spots[rng.gen_range(0..key_spots.len())];
+                level.ground_items.drop_item(Item::new(
+                    CHEST_KEY.to_string(),
+                    ItemType::QuestItem,
+                    key_pos,
+                ));
+            }
+            level.containers.push(chest);
+            placed += 1;
+        }
+        placed
+    }
+
+    /// Scatters scripted special tiles through a level's rooms.
+    ///
+    /// The level gets a healing fountain with probability [`FOUNTAIN_CHANCE`],
+    /// a pair of teleporters leading to each other in two different rooms with
+    /// probability [`TELEPORTER_CHANCE`] and a plaque with probability
+    /// [`PLAQUE_CHANCE`]. Each room other than a shop or sanctuary also gets a
+    /// hidden floor trap with probability [`FLOOR_TRAP_CHANCE`], and each sanctuary an
+    /// altar just north of where its priest stands. Only open floor away from
+    /// the player spawn is used. Returns the number of tiles placed.
+    pub fn place_special_tiles(
+        level: &mut Level,
+        rooms: &[Room],
+        rng: &mut StdRng,
+    ) -> ThatchResult<u32> {
+        let pick_spot = |level: &Level, room: &Room, rng: &mut StdRng| -> Option<Position> {
+            let candidates: Vec<Position> = room
+                .floor_positions()
+                .into_iter()
+                .filter(|&pos| {
+                    level
+                        .get_tile(pos)
+                        .is_some_and(|tile| tile.tile_type == TileType::Floor)
+                        && pos != level.player_spawn
+                        && level.container_at(pos).is_none()
+                })
+                .collect();
+            (!candidates.is_empty()).then(|| candidates[rng.gen_range(0..candidates.len())])
+        };
+        if rooms.is_empty() {
+            return Ok(0);
+        }
+
+        let mut placed = 0;
+        let single = |level: &mut Level, effect: TileEffect, rng: &mut StdRng| {
+            let room = &rooms[rng.gen_range(0..rooms.len())];
+            match pick_spot(level, room, rng) {
+                Some(pos) => level.set_tile(pos, effect.into_tile()).map(|_| 1),
+                None => Ok(0),
+            }
+        };
+        if rng.gen_bool(FOUNTAIN_CHANCE) {
+            let fountain = TileEffect::HealFountain {
+                amount: FOUNTAIN_HEAL,
+            };
+            placed += single(level, fountain, rng)?;
+        }
+        if rng.gen_bool(PLAQUE_CHANCE) {
+            let text = PLAQUE_INSCRIPTIONS[rng.gen_range(0..PLAQUE_INSCRIPTIONS.len())];
+            placed += single(level, TileEffect::Plaque { text: text.to_string() }, rng)?;
+        }
+
+        if rooms.len() > 1 && rng.gen_bool(TELEPORTER_CHANCE) {
+            let first = rng.gen_range(0..rooms.len());
+            let second = (first + rng.gen_range(1..rooms.len())) % rooms.len();
+            let from = pick_spot(level, &rooms[first], rng);
+            let to = pick_spot(level, &rooms[second], rng);
+            if let (Some(from), Some(to)) = (from, to) {
+                level.set_tile(from, TileEffect::Teleporter { destination: to }.into_tile())?;
+                level.set_tile(to, TileEffect::Teleporter { destination: from }.into_tile())?;
+                placed += 2;
+            }
+        }
+
+        for room in rooms {
+            if matches!(room.room_type, RoomType::Shop | RoomType::Sanctuary)
+                || !rng.gen_bool(FLOOR_TRAP_CHANCE)
+            {
+                continue;
+            }
+            if let Some(pos) = pick_spot(level, room, rng) {
+                let trap = TileEffect::Trap {
+                    damage: FLOOR_TRAP_DAMAGE,
+                    found: false,
+                };
+                level.set_tile(pos, trap.into_tile())?;
+                placed += 1;
+            }
+        }
+
+        for room in rooms.iter().filter(|room| room.room_type == RoomType::Sanctuary) {
+            let pos = room.center() + Position::new(0, -1);
+            let open = level
+                .get_tile(pos)
+                .is_some_and(|tile| tile.tile_type == TileType::Floor);
+            if open && pos != level.player_spawn && level.container_at(pos).is_none() {
+                level.set_tile(pos, TileEffect::Altar.into_tile())?;
+                placed += 1;
+            }
+        }
+        Ok(placed)
+    }
+
+    /// Leaves the Amulet on the floor of the room farthest from the level's
+    /// stairs up.
+    ///
+    /// Returns where it was placed, or `None` if no room had open floor.
+    pub fn place_artifact(level: &mut Level, rooms: &[Room], rng: &mut StdRng) -> Option<Position> {
+        let arrival = level.stairs_up_position.unwrap_or(level.player_spawn);
+        let mut by_distance: Vec<&Room> = rooms.iter().collect();
+        by_distance
+            .sort_by_key(|room| std::cmp::Reverse(room.center().manhattan_distance(arrival)));
+
+        let position = by_distance.into_iter().find_map(|room| {
+            let candidates: Vec<Position> = room
+                .floor_positions()
+                .into_iter()
+                .filter(|&pos| {
+                    level
+                        .get_tile(pos)
+                        .is_some_and(|tile| tile.tile_type == TileType::Floor)
+                        && level.container_at(pos).is_none()
+                })
+                .collect();
+            (!candidates.is_empty()).then(|| candidates[rng.gen_range(0..candidates.len())])
+        })?;
+
+        level.ground_items.drop_item(Item::new(
+            ARTIFACT_NAME.to_string(),
+            ItemType::QuestItem,
+            position,
+        ));
+        Some(position)
+    }
+
+    /// Finds a floor tile for the down stairs in the room farthest from the spawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no room has an open floor tile other than the spawn.
+    pub fn find_stairs_down_position(
+        level: &Level,
+        rooms: &[Room],
+        rng: &mut StdRng,
+    ) -> ThatchResult<Position> {
+        let spawn = level.player_spawn;
+        let mut candidates: Vec<&Room> = rooms.iter().collect();
+        candidates.sort_by_key(|room| std::cmp::Reverse(room.center().manhattan_distance(spawn)));
+
+        for room in candidates {
+            let floor: Vec<Position> = room
+                .floor_positions()
+                .into_iter()
+                .filter(|&pos| {
+                    pos != spawn
+                        && level
+                            .get_tile(pos)
+                            .is_some_and(|tile| tile.tile_type == TileType::Floor)
+                })
+                .collect();
+            if !floor.is_empty() {
+                return Ok(floor[rng.gen_range(0..floor.len())]);
+            }
+        }
+
+        Err(ThatchError::GenerationFailed(
+            "No floor tile available for down stairs".to_string(),
+        ))
+    }
+
+    /// Validates every level of a world and checks that stairs line up between floors.
+    pub fn validate_world_stairs(world: &World) -> ThatchResult<()> {
+        // Validate each level in the world
+        for level in world.levels.values() {
+            validate_level(level)?;
+        }
+
+        // Validate stair connectivity between levels
+        for level_id in 0..world.shape.deepest_floor() {
+            if let (Some(current_level), Some(next_level)) =
+                (world.get_level(level_id), world.get_level(level_id + 1))
+            {
+                // Check that down stairs on current level align with up stairs on next level
+                if let (Some(down_pos), Some(up_pos)) = (
+                    current_level.stairs_down_position,
+                    next_level.stairs_up_position,
+                ) {
+                    if down_pos != up_pos {
+                        return Err(ThatchError::GenerationFailed(format!(
+                            "Stair misalignment between levels {} and {}: down at {:?}, up at {:?}",
+                            level_id,
+                            level_id + 1,
+                            down_pos,
+                            up_pos
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -448,6 +1277,17 @@ mod tests {
         assert!(config.min_rooms <= config.max_rooms);
     }
 
+    #[test]
+    fn test_generator_kind_parsing() {
+        assert_eq!("bsp".parse::<GeneratorKind>(), Ok(GeneratorKind::Bsp));
+        assert_eq!(
+            "Caverns".parse::<GeneratorKind>(),
+            Ok(GeneratorKind::Caverns)
+        );
+        assert!("maze".parse::<GeneratorKind>().is_err());
+        assert_eq!(GeneratorKind::default(), GeneratorKind::Caverns);
+    }
+
     #[test]
     fn test_room_creation() {
         let room = Room::new(1, Position::new(5, 5), 10, 8, RoomType::Normal);
@@ -530,6 +1370,44 @@ mod tests {
         assert!(room.connections.contains(&3));
     }
 
+    #[test]
+    fn test_balance_room_types_limits_shops_and_adds_sanctuaries() {
+        let types = [
+            RoomType::Shop,
+            RoomType::Shop,
+            RoomType::Shop,
+            RoomType::Normal,
+            RoomType::Treasure,
+        ];
+        let rooms: Vec<Room> = types
+            .into_iter()
+            .enumerate()
+            .map(|(id, room_type)| Room::new(id as u32, Position::new(0, 0), 5, 5, room_type))
+            .collect();
+        let count = |rooms: &[Room], room_type: RoomType| {
+            rooms.iter().filter(|room| room.room_type == room_type).count()
+        };
+
+        // A shop in the first room counts toward the limit like any other
+        let mut balanced = rooms.clone();
+        utils::balance_room_types(&mut balanced, 0, 42);
+        assert_eq!(balanced[0].room_type, RoomType::Shop);
+        assert_eq!(count(&balanced, RoomType::Shop), MAX_SHOPS_PER_FLOOR);
+        assert_eq!(count(&balanced, RoomType::Sanctuary), 0);
+
+        // Sanctuary floors always get one, the same one for the same seed
+        let floor = SANCTUARY_INTERVAL - 1;
+        let mut first = rooms.clone();
+        let mut second = rooms.clone();
+        utils::balance_room_types(&mut first, floor, 42);
+        utils::balance_room_types(&mut second, floor, 42);
+        assert_eq!(count(&first, RoomType::Sanctuary), 1);
+        assert_eq!(first, second);
+
+        assert_eq!(utils::treasure_room_chance(0), 0.0);
+        assert_eq!(utils::treasure_room_chance(100), MAX_TREASURE_CHANCE);
+    }
+
     #[test]
     fn test_room_metadata() {
         let mut room = Room::new(1, Position::new(5, 5), 10, 8, RoomType::Normal);
@@ -562,4 +1440,117 @@ mod tests {
         assert!(utils::rooms_are_adjacent(&room1, &room2, 20));
         assert!(!utils::rooms_are_adjacent(&room1, &room3, 20));
     }
+
+    #[test]
+    fn test_utils_place_water_floods_only_floor() {
+        let mut level = Level::new(0, 20, 12);
+        let room = Room::new(1, Position::new(2, 2), 10, 8, RoomType::Normal);
+        for pos in room.floor_positions() {
+            level.set_tile(pos, Tile::floor()).unwrap();
+        }
+        level.player_spawn = room.center();
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let flooded =
+            utils::place_water(&mut level, std::slice::from_ref(&room), 1.0, &mut rng).unwrap();
+
+        assert!(flooded > 0);
+        assert_eq!(level.get_tile(room.center()).unwrap().tile_type, TileType::Floor);
+        for pos in room.wall_positions() {
+            assert_eq!(level.get_tile(pos).unwrap().tile_type, TileType::Wall);
+        }
+    }
+
+    #[test]
+    fn test_utils_place_rubble_in_ordinary_rooms() {
+        let rooms: Vec<Room> = (0..40)
+            .map(|id| Room::new(id, Position::new(2, 2), 8, 8, RoomType::Normal))
+            .collect();
+        let mut level = Level::new(0, 12, 12);
+        for pos in rooms[0].floor_positions() {
+            level.set_tile(pos, Tile::floor()).unwrap();
+        }
+        level.player_spawn = rooms[0].center();
+
+        assert!(utils::place_rubble(&mut level, &rooms, 11) > 0);
+        assert_eq!(level.get_tile(level.player_spawn).unwrap().overlay, None);
+        let shops = [Room::new(0, Position::new(2, 2), 8, 8, RoomType::Shop)];
+        let mut shop_level = Level::new(0, 12, 12);
+        for pos in shops[0].floor_positions() {
+            shop_level.set_tile(pos, Tile::floor()).unwrap();
+        }
+        assert_eq!(utils::place_rubble(&mut shop_level, &shops, 11), 0);
+    }
+
+    #[test]
+    fn test_utils_place_hazards_only_on_deep_floors() {
+        let room = Room::new(1, Position::new(2, 2), 11, 9, RoomType::Normal);
+        let mut level = Level::new(0, 20, 14);
+        for pos in room.floor_positions() {
+            level.set_tile(pos, Tile::floor()).unwrap();
+        }
+        let mut rng = StdRng::seed_from_u64(3);
+        let rooms = vec![room.clone(); 20];
+        assert_eq!(utils::place_hazards(&mut level, &rooms, 0, true, &mut rng).unwrap(), 0);
+
+        let depth = HAZARD_MIN_DEPTH;
+        assert!(utils::place_hazards(&mut level, &rooms, depth, false, &mut rng).unwrap() > 0);
+        let hazards: Vec<TileType> = room
+            .floor_positions()
+            .into_iter()
+            .map(|pos| level.get_tile(pos).unwrap().tile_type.clone())
+            .filter(TileType::is_hazard)
+            .collect();
+        assert!(hazards.iter().all(|tile_type| *tile_type == TileType::Lava));
+        // The ring inside the walls stays walkable
+        let inner = Position::new(room.top_left.x + 1, room.top_left.y + 1);
+        assert_eq!(level.get_tile(inner).unwrap().tile_type, TileType::Floor);
+    }
+
+    #[test]
+    fn test_utils_place_containers_hides_keys_in_other_rooms() {
+        let vault = Room::new(1, Position::new(1, 1), 6, 6, RoomType::Treasure);
+        let hall = Room::new(2, Position::new(10, 1), 6, 6, RoomType::Normal);
+        let templates = TemplateLibrary::builtin().unwrap();
+        let mut locked = 0;
+        for seed in 0..40 {
+            let mut level = Level::new(0, 20, 10);
+            for pos in vault
+                .floor_positions()
+                .into_iter()
+                .chain(hall.floor_positions())
+            {
+                level.set_tile(pos, Tile::floor()).unwrap();
+            }
+            let rooms = [vault.clone(), hall.clone()];
+            let mut rng = StdRng::seed_from_u64(seed);
+            assert_eq!(
+                utils::place_containers(&mut level, &rooms, &templates, &mut rng),
+                1
+            );
+
+            let keys: Vec<Position> = level
+                .ground_items
+                .iter()
+                .filter(|stack| stack.item.name == CHEST_KEY)
+                .map(|stack| stack.item.position)
+                .collect();
+            if level.containers[0].locked {
+                locked += 1;
+                assert_eq!(keys.len(), 1);
+                assert!(hall.contains(keys[0]));
+            } else {
+                assert!(keys.is_empty());
+            }
+
+            // With nowhere else for the key, the chest is never locked
+            let mut alone = Level::new(0, 20, 10);
+            for pos in vault.floor_positions() {
+                alone.set_tile(pos, Tile::floor()).unwrap();
+            }
+            utils::place_containers(&mut alone, &rooms[..1], &templates, &mut rng);
+            assert!(!alone.containers[0].locked);
+        }
+        assert!(locked > 0);
+    }
 }
@@ -0,0 +1,212 @@
+//! # Floor Streaming
+//!
+//! Lazy world generation, so a game can start before every floor exists.
+//!
+//! A lazy world begins with only [`LAZY_INITIAL_FLOORS`] floors. The rest are
+//! generated in order, either ahead of time by a [`FloorStreamer`] running on a
+//! background thread or on demand when the player takes the stairs to a floor
+//! that is not ready yet. Every floor is generated from its own seeded
+//! [`utils::floor_rng`] and the stairs down of the floor above, so both paths
+//! produce the same floor and stairs stay aligned either way.
+
+use super::{utils, GenerationConfig};
+use crate::{Level, ThatchResult, World};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Floors generated before a lazy world's game starts.
+pub const LAZY_INITIAL_FLOORS: u32 = 2;
+
+/// Creates a lazy world holding only its first floors.
+///
+/// # Errors
+///
+/// Returns an error if a floor cannot be generated.
+pub fn new_lazy_world(config: &GenerationConfig) -> ThatchResult<World> {
+    let mut world = World::new(config.seed);
+    world.shape = config.shape;
+    // Drop the placeholder floor so floor 0 is generated like every other
+    world.levels.clear();
+    extend_world(config, &mut world, LAZY_INITIAL_FLOORS - 1)?;
+    Ok(world)
+}
+
+/// Generates one floor of a lazy world, given the floor above it.
+///
+/// # Errors
+///
+/// Returns an error if the floor cannot be generated.
+pub fn generate_lazy_floor(
+    config: &GenerationConfig,
+    floor_id: u32,
+    above: Option<&Level>,
+) -> ThatchResult<Level> {
+    let stairs_up = above.and_then(|level| level.stairs_down_position);
+    let mut rng = utils::floor_rng(config.seed, floor_id);
+    config
+        .generator
+        .world_generator()
+        .generate_floor(floor_id, stairs_up, config, &mut rng)
+}
+
+/// Generates floors in order until `world` has every floor down to `floor_id`.
+///
+/// Lazy worlds always hold floors `0..n` with no gaps, so the next floor to
+/// generate is the one after the deepest.
+///
+/// # Errors
+///
+/// Returns an error if a floor cannot be generated.
+pub fn extend_world(
+    config: &GenerationConfig,
+    world: &mut World,
+    floor_id: u32,
+) -> ThatchResult<()> {
+    let last = floor_id.min(config.shape.deepest_floor());
+    loop {
+        let next = world.level_count() as u32;
+        if next > last {
+            return Ok(());
+        }
+        let above = next.checked_sub(1).and_then(|id| world.get_level(id));
+        let level = generate_lazy_floor(config, next, above)?;
+        world.add_level(level);
+    }
+}
+
+/// Generates a lazy world's remaining floors on a background thread.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{FloorStreamer, GameState, GenerationConfig};
+///
+/// let config = GenerationConfig::for_testing(3);
+/// let mut game_state = GameState::new_lazy_with_generation_config(config).unwrap();
+/// let streamer = FloorStreamer::for_game(&game_state).unwrap();
+///
+/// assert_eq!(game_state.world.level_count(), 2);
+/// while streamer.wait(&mut game_state.world).unwrap() {}
+/// assert_eq!(game_state.world.level_count() as u32, game_state.world.shape.floor_count);
+/// ```
+#[derive(Debug)]
+pub struct FloorStreamer {
+    /// Floors from the background thread, in order
+    floors: Receiver<ThatchResult<Level>>,
+}
+
+impl FloorStreamer {
+    /// Starts generating every floor `world` is still missing.
+    pub fn spawn(config: GenerationConfig, world: &World) -> Self {
+        let first = world.level_count() as u32;
+        let mut above = first
+            .checked_sub(1)
+            .and_then(|id| world.get_level(id))
+            .cloned();
+        let (sender, floors) = mpsc::channel();
+
+        thread::spawn(move || {
+            for floor_id in first..config.shape.floor_count {
+                let floor = generate_lazy_floor(&config, floor_id, above.as_ref());
+                above = floor.as_ref().ok().cloned();
+                // Stop after a failure, or once nobody is listening
+                if sender.send(floor).is_err() || above.is_none() {
+                    return;
+                }
+            }
+        });
+
+        Self { floors }
+    }
+
+    /// Starts streaming the rest of a game's world, if it is generated lazily.
+    pub fn for_game(game_state: &crate::GameState) -> Option<Self> {
+        let config = game_state.lazy_generation.clone()?;
+        Some(Self::spawn(config, &game_state.world))
+    }
+
+    /// Adds the floors finished since the last call to `world`.
+    ///
+    /// Floors the game already generated on demand are skipped; they are
+    /// identical to the streamed ones. Returns the number of floors added.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error if the background thread failed to generate a floor.
+    pub fn poll(&self, world: &mut World) -> ThatchResult<usize> {
+        let mut added = 0;
+        for floor in self.floors.try_iter() {
+            added += usize::from(Self::add(world, floor?));
+        }
+        Ok(added)
+    }
+
+    /// Blocks until the next floor is finished and adds it to `world`.
+    ///
+    /// Returns `false` once every floor has been streamed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error if the background thread failed to generate a floor.
+    pub fn wait(&self, world: &mut World) -> ThatchResult<bool> {
+        match self.floors.recv() {
+            Ok(floor) => {
+                Self::add(world, floor?);
+                Ok(true)
+            }
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Adds a streamed floor unless the world already has it.
+    fn add(world: &mut World, floor: Level) -> bool {
+        if world.levels.contains_key(&floor.id) {
+            return false;
+        }
+        world.add_level(floor);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::floor_hashes;
+
+    #[test]
+    fn test_streamed_and_on_demand_floors_match() {
+        let config = GenerationConfig::for_testing(808);
+        let mut streamed = new_lazy_world(&config).unwrap();
+        let mut on_demand = streamed.clone();
+        assert_eq!(streamed.level_count() as u32, LAZY_INITIAL_FLOORS);
+
+        let streamer = FloorStreamer::spawn(config.clone(), &streamed);
+        while streamer.wait(&mut streamed).unwrap() {}
+        assert_eq!(streamed.level_count() as u32, config.shape.floor_count);
+        utils::validate_world_stairs(&streamed).unwrap();
+
+        // Jumping straight to a deep floor fills in everything above it
+        extend_world(&config, &mut on_demand, 5).unwrap();
+        assert_eq!(on_demand.level_count(), 6);
+        for floor_id in 0..6 {
+            let (a, b) = (&streamed.levels[&floor_id], &on_demand.levels[&floor_id]);
+            assert_eq!(a.stairs_up_position, b.stairs_up_position);
+            assert_eq!(a.stairs_down_position, b.stairs_down_position);
+            assert_eq!(a.rooms.len(), b.rooms.len());
+        }
+    }
+
+    #[test]
+    fn test_lazy_and_eager_worlds_match() {
+        let config = GenerationConfig::for_testing(4829);
+        let eager = config
+            .generator
+            .world_generator()
+            .generate_world(&config)
+            .unwrap();
+        let mut lazy = new_lazy_world(&config).unwrap();
+        extend_world(&config, &mut lazy, config.shape.deepest_floor()).unwrap();
+
+        assert_eq!(floor_hashes(&lazy), floor_hashes(&eager));
+    }
+}
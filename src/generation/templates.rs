@@ -0,0 +1,454 @@
+//! # Templates
+//!
+//! Data-driven monster species and item archetypes.
+//!
+//! A [`TemplateLibrary`] lists every monster and item the generators can place,
+//! with the depth each first appears at and a spawn weight. Monsters may also
+//! carry a loot table of items they drop on death. The standard library
+//! is the JSON file [`config::TEMPLATES_FILE`](crate::config::TEMPLATES_FILE),
+//! built into the game so it works without an assets folder:
+//!
+//! ```json
+//! {
+//!   "monsters": [
+//!     { "name": "Goblin", "species": "Goblin", "health": 20, "attack": 5, "defense": 2,
+//!       "speed": 110, "perception": 8, "min_depth": 0, "weight": 10,
+//!       "loot": [ { "name": "Gold coins", "item_type": "Treasure", "chance": 0.3 } ] }
+//!   ],
+//!   "items": [
+//!     { "name": "Potion of healing", "item_type": { "Consumable": "HealthPotion" },
+//!       "min_depth": 0, "weight": 10 }
+//!   ]
+//! }
+//! ```
+//!
+//! The game loads and validates the file from disk at startup when it exists,
+//! and dev-tools builds reload it through a [`TemplateWatcher`] whenever it
+//! changes, so edits show up in the next dungeon generated.
+
+use crate::{
+    EntityStats, Item, ItemType, LootDrop, Monster, MonsterType, Position, ThatchError,
+    ThatchResult,
+};
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The standard templates, compiled into the game.
+const BUILTIN_TEMPLATES: &str = include_str!("../../assets/data/templates.json");
+
+/// How often a [`TemplateWatcher`] looks at its file.
+pub const TEMPLATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A monster species the generators can spawn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonsterTemplate {
+    /// Name shown for monsters spawned from this template
+    pub name: String,
+    /// Monster type, which decides the glyph
+    pub species: MonsterType,
+    /// Maximum health
+    pub health: u32,
+    /// Physical attack power
+    pub attack: u32,
+    /// Physical defense
+    pub defense: u32,
+    /// Movement speed (higher = faster)
+    pub speed: u32,
+    /// Awareness of the player
+    pub perception: u32,
+    /// First level id the species can appear on
+    pub min_depth: u32,
+    /// Relative chance of being picked among the species allowed at a depth
+    pub weight: u32,
+    /// Items the monster may drop when it dies
+    #[serde(default)]
+    pub loot: Vec<LootDrop>,
+}
+
+impl MonsterTemplate {
+    /// Creates a hostile monster from this template.
+    pub fn spawn(&self, position: Position) -> Monster {
+        let mut monster = Monster::new(self.species.clone(), position);
+        monster.name = self.name.clone();
+        monster.loot = self.loot.clone();
+        monster.stats = EntityStats {
+            health: self.health,
+            max_health: self.health,
+            attack: self.attack,
+            defense: self.defense,
+            speed: self.speed,
+            perception: self.perception,
+            ..monster.stats
+        };
+        monster
+    }
+
+    /// Describes the species for tooltips: how hard it hits, how well it is
+    /// protected, how fast it moves and what it may drop.
+    pub fn describe(&self) -> String {
+        let pace = match self.speed {
+            speed if speed > 100 => "fast",
+            speed if speed < 100 => "slow",
+            _ => "steady",
+        };
+        let mut text = format!(
+            "attack {}, defense {}, {} pace",
+            self.attack, self.defense, pace
+        );
+        if !self.loot.is_empty() {
+            let names: Vec<&str> = self.loot.iter().map(|drop| drop.name.as_str()).collect();
+            text.push_str(&format!("; may drop {}", names.join(", ")));
+        }
+        text
+    }
+}
+
+/// An item archetype the generators can place.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemTemplate {
+    /// Item name
+    pub name: String,
+    /// What kind of item it is
+    pub item_type: ItemType,
+    /// First level id the item can appear on
+    pub min_depth: u32,
+    /// Relative chance of being picked among the items allowed at a depth
+    pub weight: u32,
+}
+
+impl ItemTemplate {
+    /// Creates an item from this template.
+    pub fn spawn(&self, position: Position) -> Item {
+        Item::new(self.name.clone(), self.item_type.clone(), position)
+    }
+}
+
+/// Every monster species and item archetype the generators can use.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::TemplateLibrary;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let library = TemplateLibrary::builtin().unwrap();
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let monster = library.pick_monster(0, &mut rng).unwrap();
+/// assert_eq!(monster.min_depth, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateLibrary {
+    /// Monster species
+    pub monsters: Vec<MonsterTemplate>,
+    /// Item archetypes
+    pub items: Vec<ItemTemplate>,
+}
+
+impl TemplateLibrary {
+    /// Gets the standard library compiled into the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the built-in templates are invalid.
+    pub fn builtin() -> ThatchResult<Self> {
+        Self::from_json(BUILTIN_TEMPLATES)
+    }
+
+    /// Parses and validates a library from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed or the library is invalid.
+    pub fn from_json(json: &str) -> ThatchResult<Self> {
+        let library: Self = serde_json::from_str(json)?;
+        library.validate()?;
+        Ok(library)
+    }
+
+    /// Loads and validates a library from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the library is invalid.
+    pub fn load(path: &Path) -> ThatchResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Checks that both lists have something available from depth 0, names
+    /// are unique, weights are positive, monsters have health, and loot
+    /// chances are between 0 and 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first problem found.
+    pub fn validate(&self) -> ThatchResult<()> {
+        let invalid = |message: String| Err(ThatchError::InvalidState(message));
+
+        if !self.monsters.iter().any(|monster| monster.min_depth == 0) {
+            return invalid("Templates need a monster available from depth 0".to_string());
+        }
+        if !self.items.iter().any(|item| item.min_depth == 0) {
+            return invalid("Templates need an item available from depth 0".to_string());
+        }
+
+        let mut names = HashSet::new();
+        let entries = self
+            .monsters
+            .iter()
+            .map(|monster| (&monster.name, monster.weight))
+            .chain(self.items.iter().map(|item| (&item.name, item.weight)));
+        for (name, weight) in entries {
+            if !names.insert(name) {
+                return invalid(format!("Template '{}' is defined more than once", name));
+            }
+            if weight == 0 {
+                return invalid(format!("Template '{}' has a spawn weight of zero", name));
+            }
+        }
+
+        if let Some(monster) = self.monsters.iter().find(|monster| monster.health == 0) {
+            return invalid(format!("Monster template '{}' has no health", monster.name));
+        }
+        for monster in &self.monsters {
+            let out_of_range = |drop: &&LootDrop| !(0.0..=1.0).contains(&drop.chance);
+            if let Some(drop) = monster.loot.iter().find(out_of_range) {
+                return invalid(format!(
+                    "Loot '{}' of '{}' has a chance outside 0 to 1",
+                    drop.name, monster.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the template monsters with this name are spawned from.
+    pub fn monster_template(&self, name: &str) -> Option<&MonsterTemplate> {
+        self.monsters.iter().find(|monster| monster.name == name)
+    }
+
+    /// Gets the template items with this name are spawned from.
+    pub fn item_template(&self, name: &str) -> Option<&ItemTemplate> {
+        self.items.iter().find(|item| item.name == name)
+    }
+
+    /// Describes a monster for a tooltip, with its current health and, when
+    /// it was spawned from a template, what the template says about it.
+    pub fn describe_monster(&self, monster: &Monster) -> String {
+        let health = format!(
+            "{} ({}/{} HP)",
+            monster.name, monster.stats.health, monster.stats.max_health
+        );
+        match self.monster_template(&monster.name) {
+            Some(template) => format!("{}: {}", health, template.describe()),
+            None => health,
+        }
+    }
+
+    /// Picks a monster species allowed at `depth`, weighted by spawn weight.
+    pub fn pick_monster(&self, depth: u32, rng: &mut StdRng) -> Option<&MonsterTemplate> {
+        pick_weighted(&self.monsters, depth, rng, |monster| {
+            (monster.min_depth, monster.weight)
+        })
+    }
+
+    /// Picks a monster species that first appears between one and `levels`
+    /// floors deeper than `depth`, weighted by spawn weight.
+    pub fn pick_out_of_depth_monster(
+        &self,
+        depth: u32,
+        levels: u32,
+        rng: &mut StdRng,
+    ) -> Option<&MonsterTemplate> {
+        let deeper: Vec<&MonsterTemplate> = self
+            .monsters
+            .iter()
+            .filter(|monster| monster.min_depth > depth)
+            .collect();
+        pick_weighted(&deeper, depth + levels, rng, |monster| {
+            (monster.min_depth, monster.weight)
+        })
+        .copied()
+    }
+
+    /// Picks an item archetype allowed at `depth`, weighted by spawn weight.
+    pub fn pick_item(&self, depth: u32, rng: &mut StdRng) -> Option<&ItemTemplate> {
+        pick_weighted(&self.items, depth, rng, |item| {
+            (item.min_depth, item.weight)
+        })
+    }
+}
+
+impl Default for TemplateLibrary {
+    /// Gets the built-in library, or an empty one if it is invalid, which
+    /// the tests rule out.
+    fn default() -> Self {
+        Self::builtin().unwrap_or_else(|_| Self {
+            monsters: Vec::new(),
+            items: Vec::new(),
+        })
+    }
+}
+
+/// Picks an entry whose minimum depth is at most `depth`, with chances in
+/// proportion to the entries' weights.
+fn pick_weighted<'a, T>(
+    entries: &'a [T],
+    depth: u32,
+    rng: &mut StdRng,
+    depth_and_weight: impl Fn(&T) -> (u32, u32),
+) -> Option<&'a T> {
+    let allowed: Vec<(&T, u32)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let (min_depth, weight) = depth_and_weight(entry);
+            (min_depth <= depth).then_some((entry, weight))
+        })
+        .collect();
+    let total: u32 = allowed.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.gen_range(0..total);
+    for (entry, weight) in allowed {
+        if roll < weight {
+            return Some(entry);
+        }
+        roll -= weight;
+    }
+    None
+}
+
+/// Watches a template file and reloads it when it changes.
+#[derive(Debug, Clone)]
+pub struct TemplateWatcher {
+    /// File being watched
+    path: PathBuf,
+    /// Modification time of the file when it was last checked
+    modified: Option<SystemTime>,
+    /// When the file was last checked
+    checked: Option<Instant>,
+}
+
+impl TemplateWatcher {
+    /// Starts watching `path`, treating its current contents as already loaded.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = Self::modified_time(&path);
+        Self {
+            path,
+            modified,
+            checked: None,
+        }
+    }
+
+    /// Reloads the library if the file changed since the last check.
+    ///
+    /// The file is looked at no more than once every
+    /// [`TEMPLATE_POLL_INTERVAL`]. Returns `None` if nothing changed or it is
+    /// too soon to look, otherwise the result of loading the new contents.
+    pub fn poll(&mut self, now: Instant) -> Option<ThatchResult<TemplateLibrary>> {
+        let due = self
+            .checked
+            .is_none_or(|checked| now.duration_since(checked) >= TEMPLATE_POLL_INTERVAL);
+        if !due {
+            return None;
+        }
+        self.checked = Some(now);
+
+        let modified = Self::modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(TemplateLibrary::load(&self.path))
+    }
+
+    /// Gets when a file was last modified, if it exists.
+    fn modified_time(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_builtin_templates_are_valid_and_depth_gated() {
+        let library = TemplateLibrary::builtin().unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..50 {
+            assert_eq!(library.pick_monster(0, &mut rng).unwrap().min_depth, 0);
+            assert!(library.pick_item(3, &mut rng).unwrap().min_depth <= 3);
+        }
+        let dragon = library
+            .monsters
+            .iter()
+            .find(|m| m.species == MonsterType::Dragon);
+        let spawned = dragon.unwrap().spawn(Position::new(1, 1));
+        assert_eq!(spawned.stats.health, spawned.stats.max_health);
+        assert_eq!(spawned.loot, dragon.unwrap().loot);
+        assert!(!spawned.loot.is_empty());
+
+        let mut duplicated = library.clone();
+        duplicated.items.push(library.items[0].clone());
+        assert!(duplicated.validate().is_err());
+
+        let mut weightless = library.clone();
+        weightless.monsters[0].weight = 0;
+        assert!(weightless.validate().is_err());
+
+        let mut reckless = library;
+        let dragon = reckless
+            .monsters
+            .iter_mut()
+            .find(|m| m.species == MonsterType::Dragon);
+        dragon.unwrap().loot[0].chance = 1.5;
+        assert!(reckless.validate().is_err());
+    }
+
+    #[test]
+    fn test_monsters_are_described_from_their_template() {
+        let library = TemplateLibrary::builtin().unwrap();
+        let mut goblin = library
+            .monster_template("Goblin")
+            .unwrap()
+            .spawn(Position::new(1, 1));
+        goblin.stats.health = 7;
+        assert_eq!(
+            library.describe_monster(&goblin),
+            "Goblin (7/20 HP): attack 5, defense 2, fast pace; may drop Gold coins, Dagger"
+        );
+
+        // Monsters without a template are described by their health alone
+        goblin.name = "Snaggletooth".to_string();
+        assert_eq!(library.describe_monster(&goblin), "Snaggletooth (7/20 HP)");
+    }
+
+    #[test]
+    fn test_watcher_reloads_edits_at_most_once_an_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("templates.json");
+        std::fs::write(&path, BUILTIN_TEMPLATES).unwrap();
+        let mut watcher = TemplateWatcher::new(&path);
+        let start = Instant::now();
+        assert!(watcher.poll(start).is_none());
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(watcher.poll(start + TEMPLATE_POLL_INTERVAL / 2).is_none());
+        let reloaded = watcher.poll(start + TEMPLATE_POLL_INTERVAL).unwrap();
+        assert_eq!(reloaded.unwrap(), TemplateLibrary::builtin().unwrap());
+        assert!(watcher.poll(start + TEMPLATE_POLL_INTERVAL * 2).is_none());
+    }
+}
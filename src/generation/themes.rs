@@ -0,0 +1,253 @@
+//! # Floor Themes
+//!
+//! A theme the LLDM picks for a floor before it is generated, such as "the
+//! flooded archives", and what it changes about the floor.
+//!
+//! When [`GenerationConfig::use_lldm`] is set, [`request_floor_theme`] hands
+//! the LLDM the floor's
+//! [`floor_theme_context`](crate::LldmClient::floor_theme_context) and reads
+//! the words of the theme it names: a flooded or sunken theme raises the water
+//! chance, archives turn ordinary rooms into libraries, and a crypt or a
+//! goblin warren makes skeletons or goblins more common. Only those words
+//! change how a floor plays, so nothing else the LLDM writes can. The theme is
+//! kept on the level for narration and the morgue file.
+
+use super::{utils, GenerationConfig};
+use crate::{LldmClient, MonsterType, Room, RoomType};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Themes the LLDM picks from until a model is connected.
+pub const FLOOR_THEME_NAMES: [&str; 8] = [
+    "the flooded archives",
+    "a goblin warren",
+    "the bone crypts",
+    "the sunken halls",
+    "an orc war camp",
+    "the arcane library",
+    "a troll den",
+    "the ember deep",
+];
+
+/// Longest theme name a floor can be given.
+pub const MAX_THEME_NAME_LENGTH: usize = 40;
+
+/// Multiplier a watery theme puts on the floor's water chance.
+pub const THEME_WATER_SCALE: f64 = 3.0;
+
+/// Ordinary rooms a scholarly theme turns into libraries.
+pub const THEME_LIBRARY_ROOMS: u32 = 2;
+
+/// Multiplier a theme puts on the spawn weight of the species it favors.
+pub const FAVORED_SPECIES_WEIGHT_SCALE: u32 = 4;
+
+/// Salt of the library pass's [`utils::pass_rng`] stream.
+const THEME_SEED: u64 = 0x0000_7e4e_f100;
+
+/// What a word in a theme's name changes about the floor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ThemeEffect {
+    /// More pools and rivers
+    Water,
+    /// Ordinary rooms become libraries
+    Libraries,
+    /// A species is more common
+    Species(MonsterType),
+}
+
+impl ThemeEffect {
+    /// Gets the effect of a lowercase word in a theme's name, if it has one.
+    fn of_word(word: &str) -> Option<Self> {
+        let species = match word {
+            "flooded" | "sunken" | "drowned" | "marsh" => return Some(Self::Water),
+            "archive" | "archives" | "library" | "libraries" => return Some(Self::Libraries),
+            "goblin" | "goblins" | "warren" => MonsterType::Goblin,
+            "orc" | "orcs" | "war" => MonsterType::Orc,
+            "arcane" | "wizard" | "wizards" => MonsterType::Wizard,
+            "bone" | "bones" | "crypt" | "crypts" | "tomb" | "tombs" => MonsterType::Skeleton,
+            "troll" | "trolls" => MonsterType::Troll,
+            "dragon" | "wyrm" | "ember" => MonsterType::Dragon,
+            _ => return None,
+        };
+        Some(Self::Species(species))
+    }
+}
+
+/// A floor's theme and the changes it makes to how the floor is generated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloorTheme {
+    /// Name of the theme, such as "the flooded archives"
+    pub name: String,
+    /// Multiplier on the floor's water chance
+    pub water_scale: f64,
+    /// Ordinary rooms turned into libraries
+    pub library_rooms: u32,
+    /// Species made more common on the floor
+    pub favored_species: Vec<MonsterType>,
+}
+
+impl FloorTheme {
+    /// Reads a theme's modifiers from the words of its name.
+    ///
+    /// Returns `None` if the name is empty, more than one line, or longer
+    /// than [`MAX_THEME_NAME_LENGTH`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{FloorTheme, MonsterType, THEME_LIBRARY_ROOMS};
+    ///
+    /// let theme = FloorTheme::from_name("the Flooded Archives").unwrap();
+    /// assert!(theme.water_scale > 1.0);
+    /// assert_eq!(theme.library_rooms, THEME_LIBRARY_ROOMS);
+    ///
+    /// let theme = FloorTheme::from_name("a goblin warren").unwrap();
+    /// assert_eq!(theme.favored_species, vec![MonsterType::Goblin]);
+    /// assert!(FloorTheme::from_name("").is_none());
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_THEME_NAME_LENGTH || name.contains('\n') {
+            return None;
+        }
+
+        let mut theme = Self {
+            name: name.to_string(),
+            water_scale: 1.0,
+            library_rooms: 0,
+            favored_species: Vec::new(),
+        };
+        let lowercase = name.to_lowercase();
+        for word in lowercase.split(|c: char| !c.is_alphabetic()) {
+            match ThemeEffect::of_word(word) {
+                Some(ThemeEffect::Water) => theme.water_scale = THEME_WATER_SCALE,
+                Some(ThemeEffect::Libraries) => theme.library_rooms = THEME_LIBRARY_ROOMS,
+                Some(ThemeEffect::Species(species))
+                    if !theme.favored_species.contains(&species) =>
+                {
+                    theme.favored_species.push(species)
+                }
+                _ => {}
+            }
+        }
+        Some(theme)
+    }
+
+    /// Returns a copy of `config` with the water chance and monster weights
+    /// the theme calls for; the chance is capped at 1.0.
+    pub fn apply(&self, config: &GenerationConfig) -> GenerationConfig {
+        let mut themed = config.clone();
+        themed.water_chance = (config.water_chance * self.water_scale).min(1.0);
+        for monster in &mut themed.templates.monsters {
+            if self.favored_species.contains(&monster.species) {
+                monster.weight = monster.weight.saturating_mul(FAVORED_SPECIES_WEIGHT_SCALE);
+            }
+        }
+        themed
+    }
+
+    /// Turns up to [`library_rooms`](Self::library_rooms) ordinary rooms into
+    /// libraries, leaving the first room, where the player arrives, alone.
+    /// Returns the number of rooms changed.
+    pub fn furnish(&self, rooms: &mut [Room], floor_id: u32, seed: u64) -> usize {
+        let mut rng = utils::pass_rng(seed, THEME_SEED, floor_id);
+        let mut ordinary: Vec<&mut Room> = rooms
+            .iter_mut()
+            .skip(1)
+            .filter(|room| room.room_type == RoomType::Normal)
+            .collect();
+        ordinary.shuffle(&mut rng);
+        let count = ordinary.len().min(self.library_rooms as usize);
+        for room in ordinary.into_iter().take(count) {
+            room.room_type = RoomType::Library;
+        }
+        count
+    }
+}
+
+/// Asks the LLDM for the theme of a floor about to be generated.
+///
+/// Returns `None` when [`GenerationConfig::use_lldm`] is off or the theme it
+/// names is unusable.
+pub fn request_floor_theme(floor_id: u32, config: &GenerationConfig) -> Option<FloorTheme> {
+    if !config.use_lldm {
+        return None;
+    }
+    let client = LldmClient::new();
+    let name = client.generate_floor_theme(&client.floor_theme_context(floor_id, config));
+    FloorTheme::from_name(&name)
+}
+
+/// Scales `config` for a floor's depth and, when the LLDM is in use, for the
+/// theme it picks, which is returned alongside.
+pub fn themed_floor_config(
+    floor_id: u32,
+    config: &GenerationConfig,
+) -> (GenerationConfig, Option<FloorTheme>) {
+    let scaled = config.for_depth(floor_id);
+    match request_floor_theme(floor_id, config) {
+        Some(theme) => (theme.apply(&scaled), Some(theme)),
+        None => (scaled, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, MorgueReport, Position, DEFAULT_SNAPSHOT_TOKEN_BUDGET};
+
+    #[test]
+    fn test_floor_themes_shape_generation_and_are_remembered() {
+        let mut config = GenerationConfig::new(4242);
+        assert!(themed_floor_config(0, &config).1.is_none());
+
+        config.use_lldm = true;
+        for floor_id in 0..FLOOR_THEME_NAMES.len() as u32 {
+            let (themed, theme) = themed_floor_config(floor_id, &config);
+            let theme = theme.unwrap();
+            assert!(FLOOR_THEME_NAMES.contains(&theme.name.as_str()));
+            assert_eq!(
+                themed.water_chance,
+                (config.water_chance * theme.water_scale).min(1.0)
+            );
+            for (monster, plain) in themed
+                .templates
+                .monsters
+                .iter()
+                .zip(&config.templates.monsters)
+            {
+                let favored = theme.favored_species.contains(&monster.species);
+                assert_eq!(monster.weight > plain.weight, favored);
+            }
+        }
+
+        // Archives turn ordinary rooms, but never the arrival room, into libraries
+        let archives = FloorTheme::from_name("the flooded archives").unwrap();
+        let mut rooms: Vec<Room> = (0..5)
+            .map(|id| Room::new(id, Position::new(id as i32 * 8, 1), 6, 6, RoomType::Normal))
+            .collect();
+        assert_eq!(archives.furnish(&mut rooms, 3, config.seed), 2);
+        assert_eq!(rooms[0].room_type, RoomType::Normal);
+        let libraries = rooms
+            .iter()
+            .filter(|room| room.room_type == RoomType::Library);
+        assert_eq!(libraries.count(), 2);
+
+        // Every generated floor keeps its theme, which the morgue file records
+        let world = config.generator.world_generator().generate_world(&config);
+        let world = world.unwrap();
+        assert!(world.levels.values().all(|level| level.theme.is_some()));
+        let theme = world.levels[&0].theme.clone().unwrap();
+        let spawn = world.levels[&0].player_spawn;
+        let mut game_state = GameState::new(4242);
+        game_state.world = world;
+        game_state
+            .initialize_player("Themed".to_string(), spawn)
+            .unwrap();
+        game_state.update_player_visibility(spawn).unwrap();
+        let snapshot = game_state.snapshot_for_llm(DEFAULT_SNAPSHOT_TOKEN_BUDGET);
+        assert_eq!(snapshot.theme, Some(theme.name.clone()));
+        let report = MorgueReport::from_game_state(&game_state, &[], 0);
+        assert!(report.to_text().contains(&theme.name));
+    }
+}
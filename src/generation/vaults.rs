@@ -0,0 +1,602 @@
+//! # Vaults
+//!
+//! Hand-authored set pieces stamped into generated floors.
+//!
+//! A [`Vault`] is a small prefab layout written as a text file in
+//! [`config::VAULTS_DIRECTORY`]. A header names the vault, gives the first
+//! depth it can appear on, and maps extra glyphs to monsters and items from
+//! the [`TemplateLibrary`]; the map follows a `---` line:
+//!
+//! ```text
+//! name: Goblin Barracks
+//! min_depth: 0
+//! legend: g = monster Goblin
+//! legend: $ = item Gold coins
+//! ---
+//! #######
+//! #g...g#
+//! +..$..+
+//! #######
+//! ```
+//!
+//! `#` is wall, `.` floor, `+` a closed door and `~` water. A space leaves
+//! the floor underneath as generated, so vaults need not be rectangular.
+//! Monsters and items stand on floor. With chance [`VAULT_CHANCE`], a floor
+//! gets one vault allowed at its depth, turned and mirrored at random, inside
+//! a room it fits in. A placement that would cut off any part of the floor is
+//! undone. The vaults are built into the game, so they work without an assets
+//! folder; a file on disk replaces the built-in vault of the same name.
+//!
+//! [`config::VAULTS_DIRECTORY`]: crate::config::VAULTS_DIRECTORY
+
+use crate::{
+    GenerationConfig, Level, Position, Room, TemplateLibrary, ThatchError, ThatchResult,
+    Tile, TileType, WaitingMonster,
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+
+/// The standard vaults, compiled into the game.
+const BUILTIN_VAULTS: [&str; 3] = [
+    include_str!("../../assets/vaults/goblin_barracks.txt"),
+    include_str!("../../assets/vaults/flooded_shrine.txt"),
+    include_str!("../../assets/vaults/skeleton_crypt.txt"),
+];
+
+/// Chance of a floor getting a vault.
+pub const VAULT_CHANCE: f64 = 0.3;
+
+/// Room metadata key holding the name of the vault stamped into the room.
+pub const VAULT_METADATA_KEY: &str = "vault";
+
+/// Salt of the vault pass's [`utils::pass_rng`](super::utils::pass_rng) stream.
+const VAULT_SEED: u64 = 0x0000_7a17_5eed;
+
+/// Glyphs with a fixed meaning, which a legend cannot redefine.
+const TILE_GLYPHS: [char; 5] = ['#', '.', '+', '~', ' '];
+
+/// What a legend glyph places.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VaultMarker {
+    /// A monster spawned from the template with this name
+    Monster(String),
+    /// An item spawned from the template with this name
+    Item(String),
+}
+
+/// A glyph of a vault's map and what it places.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultLegend {
+    /// Glyph used in the map
+    pub glyph: char,
+    /// Monster or item placed on the floor there
+    pub marker: VaultMarker,
+}
+
+/// How a vault is turned before it is stamped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VaultTransform {
+    /// Clockwise quarter turns, from 0 to 3
+    pub quarter_turns: u8,
+    /// Whether the map is flipped left to right before turning
+    pub mirrored: bool,
+}
+
+impl VaultTransform {
+    /// Picks one of the eight turns and flips at random.
+    pub fn random(rng: &mut StdRng) -> Self {
+        Self {
+            quarter_turns: rng.gen_range(0..4),
+            mirrored: rng.gen_bool(0.5),
+        }
+    }
+}
+
+/// A hand-authored room layout with monsters and items.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{Vault, VaultMarker, VaultTransform};
+///
+/// let vault = Vault::parse("name: Cell\nlegend: g = monster Goblin\n---\n###\n+g#\n").unwrap();
+/// assert_eq!(vault.marker('g'), Some(&VaultMarker::Monster("Goblin".to_string())));
+///
+/// let turned = vault.grid(VaultTransform { quarter_turns: 1, mirrored: false });
+/// assert_eq!(turned, vec![vec!['+', '#'], vec!['g', '#'], vec!['#', '#']]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vault {
+    /// Name of the vault, unique within a library
+    pub name: String,
+    /// First level id the vault can appear on
+    pub min_depth: u32,
+    /// Glyphs placing monsters and items
+    pub legend: Vec<VaultLegend>,
+    /// Rows of the map, all the same length
+    pub rows: Vec<String>,
+}
+
+impl Vault {
+    /// Parses a vault from its text form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is malformed, the vault has no name or
+    /// map, or the map uses a glyph the legend does not define.
+    pub fn parse(text: &str) -> ThatchResult<Self> {
+        let invalid = |message: String| ThatchError::InvalidState(message);
+        let (header, map) = text
+            .split_once("\n---\n")
+            .or_else(|| text.split_once("\n---\r\n"))
+            .ok_or_else(|| invalid("Vault has no '---' line before its map".to_string()))?;
+
+        let mut name = None;
+        let mut min_depth = 0;
+        let mut legend = Vec::new();
+        for line in header
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("Vault header line '{}' has no ':'", line)))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "min_depth" => {
+                    min_depth = value.parse().map_err(|_| {
+                        invalid(format!("Vault min_depth '{}' is not a number", value))
+                    })?
+                }
+                "legend" => legend.push(parse_legend(value)?),
+                other => return Err(invalid(format!("Unknown vault header '{}'", other))),
+            }
+        }
+        let name = name.ok_or_else(|| invalid("Vault has no name".to_string()))?;
+
+        let mut rows: Vec<String> = map.lines().map(|row| row.trim_end().to_string()).collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width == 0 {
+            return Err(invalid(format!("Vault '{}' has an empty map", name)));
+        }
+        for row in &mut rows {
+            let padding = width - row.chars().count();
+            row.extend(std::iter::repeat_n(' ', padding));
+        }
+
+        let vault = Self {
+            name,
+            min_depth,
+            legend,
+            rows,
+        };
+        let glyphs = vault.rows.iter().flat_map(|row| row.chars());
+        for glyph in glyphs.collect::<HashSet<char>>() {
+            if !TILE_GLYPHS.contains(&glyph) && vault.marker(glyph).is_none() {
+                return Err(invalid(format!(
+                    "Vault '{}' uses '{}', which its legend does not define",
+                    vault.name, glyph
+                )));
+            }
+        }
+        Ok(vault)
+    }
+
+    /// Gets what a legend glyph places, if the legend defines it.
+    pub fn marker(&self, glyph: char) -> Option<&VaultMarker> {
+        self.legend
+            .iter()
+            .find(|entry| entry.glyph == glyph)
+            .map(|entry| &entry.marker)
+    }
+
+    /// Gets the map as rows of glyphs, flipped and turned.
+    pub fn grid(&self, transform: VaultTransform) -> Vec<Vec<char>> {
+        let mut grid: Vec<Vec<char>> = self.rows.iter().map(|row| row.chars().collect()).collect();
+        if transform.mirrored {
+            for row in &mut grid {
+                row.reverse();
+            }
+        }
+        for _ in 0..transform.quarter_turns % 4 {
+            let height = grid.len();
+            let width = grid.first().map_or(0, Vec::len);
+            grid = (0..width)
+                .map(|x| (0..height).rev().map(|y| grid[y][x]).collect())
+                .collect();
+        }
+        grid
+    }
+}
+
+/// Parses a legend entry such as `g = monster Goblin`.
+fn parse_legend(value: &str) -> ThatchResult<VaultLegend> {
+    let invalid = || {
+        ThatchError::InvalidState(format!(
+            "Vault legend '{}' is not '<glyph> = monster|item <name>'",
+            value
+        ))
+    };
+    let (glyph, marker) = value.split_once('=').ok_or_else(invalid)?;
+    let mut glyphs = glyph.trim().chars();
+    let glyph = match (glyphs.next(), glyphs.next()) {
+        (Some(glyph), None) if !TILE_GLYPHS.contains(&glyph) => glyph,
+        _ => return Err(invalid()),
+    };
+    let (kind, name) = marker.trim().split_once(' ').ok_or_else(invalid)?;
+    let name = name.trim().to_string();
+    let marker = match kind {
+        "monster" => VaultMarker::Monster(name),
+        "item" => VaultMarker::Item(name),
+        _ => return Err(invalid()),
+    };
+    Ok(VaultLegend { glyph, marker })
+}
+
+/// Every vault the generators can stamp.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{TemplateLibrary, VaultLibrary};
+///
+/// let library = VaultLibrary::builtin().unwrap();
+/// assert!(library.vaults.iter().any(|vault| vault.min_depth == 0));
+/// assert!(library.validate(&TemplateLibrary::builtin().unwrap()).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultLibrary {
+    /// The vaults, in the order they were loaded
+    pub vaults: Vec<Vault>,
+}
+
+impl VaultLibrary {
+    /// Gets the standard vaults compiled into the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a built-in vault is invalid.
+    pub fn builtin() -> ThatchResult<Self> {
+        let vaults = BUILTIN_VAULTS
+            .iter()
+            .map(|text| Vault::parse(text))
+            .collect::<ThatchResult<_>>()?;
+        Ok(Self { vaults })
+    }
+
+    /// Loads every `.txt` vault in a directory on top of the built-in vaults.
+    ///
+    /// A vault with the name of a built-in one replaces it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a file cannot be read, or a vault
+    /// is invalid.
+    pub fn load_directory(directory: &Path) -> ThatchResult<Self> {
+        let mut paths: Vec<_> = std::fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+            .collect();
+        paths.sort();
+
+        let mut library = Self::builtin()?;
+        for path in paths {
+            let vault = Vault::parse(&std::fs::read_to_string(&path)?)
+                .map_err(|e| ThatchError::InvalidState(format!("{}: {}", path.display(), e)))?;
+            match library
+                .vaults
+                .iter_mut()
+                .find(|known| known.name == vault.name)
+            {
+                Some(known) => *known = vault,
+                None => library.vaults.push(vault),
+            }
+        }
+        Ok(library)
+    }
+
+    /// Checks that names are unique and every marker names a template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first problem found.
+    pub fn validate(&self, templates: &TemplateLibrary) -> ThatchResult<()> {
+        let mut names = HashSet::new();
+        for vault in &self.vaults {
+            if !names.insert(&vault.name) {
+                return Err(ThatchError::InvalidState(format!(
+                    "Vault '{}' is defined more than once",
+                    vault.name
+                )));
+            }
+            for entry in &vault.legend {
+                let known = match &entry.marker {
+                    VaultMarker::Monster(name) => templates.monster_template(name).is_some(),
+                    VaultMarker::Item(name) => templates.item_template(name).is_some(),
+                };
+                if !known {
+                    return Err(ThatchError::InvalidState(format!(
+                        "Vault '{}' places {:?}, which has no template",
+                        vault.name, entry.marker
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Picks a vault allowed at `depth`, each equally likely.
+    pub fn pick(&self, depth: u32, rng: &mut StdRng) -> Option<&Vault> {
+        let allowed: Vec<&Vault> = self
+            .vaults
+            .iter()
+            .filter(|vault| vault.min_depth <= depth)
+            .collect();
+        allowed.choose(rng).copied()
+    }
+}
+
+impl Default for VaultLibrary {
+    /// Gets the built-in vaults, or none if they are invalid, which the tests
+    /// rule out.
+    fn default() -> Self {
+        Self::builtin().unwrap_or_else(|_| Self { vaults: Vec::new() })
+    }
+}
+
+/// Stamps a vault into one of a floor's rooms, with chance [`VAULT_CHANCE`].
+///
+/// The vault goes fully inside one of the
+/// [`eligible_special_rooms`](super::utils::eligible_special_rooms), clear of
+/// the stairs and the player spawn. Items are left on the ground, monsters are
+/// queued in [`Level::waiting_monsters`], and the room is marked with
+/// [`VAULT_METADATA_KEY`]. Returns the name of the vault placed.
+pub fn place_vault(
+    level: &mut Level,
+    rooms: &mut [Room],
+    config: &GenerationConfig,
+) -> Option<String> {
+    let mut rng = super::utils::pass_rng(config.seed, VAULT_SEED, level.id);
+    if !rng.gen_bool(VAULT_CHANCE) {
+        return None;
+    }
+    let vault = config.vaults.pick(level.id, &mut rng)?;
+    let grid = vault.grid(VaultTransform::random(&mut rng));
+    let (width, height) = (grid[0].len() as i32, grid.len() as i32);
+
+    let reserved = [
+        Some(level.player_spawn),
+        level.stairs_up_position,
+        level.stairs_down_position,
+    ];
+    let mut spots: Vec<(usize, Position)> = super::utils::eligible_special_rooms(rooms)
+        .filter_map(|(index, room)| {
+            let spare_x = room.width as i32 - 2 - width;
+            let spare_y = room.height as i32 - 2 - height;
+            if spare_x < 0 || spare_y < 0 {
+                return None;
+            }
+            let origin = Position::new(
+                room.top_left.x + 1 + rng.gen_range(0..=spare_x),
+                room.top_left.y + 1 + rng.gen_range(0..=spare_y),
+            );
+            let covers = |position: &Position| {
+                (origin.x..origin.x + width).contains(&position.x)
+                    && (origin.y..origin.y + height).contains(&position.y)
+            };
+            (!reserved.iter().flatten().any(covers)).then_some((index, origin))
+        })
+        .collect();
+    spots.shuffle(&mut rng);
+
+    for (index, origin) in spots {
+        if stamp(level, vault, &grid, origin, &config.templates) {
+            rooms[index].set_metadata(VAULT_METADATA_KEY.to_string(), vault.name.clone());
+            return Some(vault.name.clone());
+        }
+    }
+    None
+}
+
+/// Stamps a vault's map at `origin`, undoing it if any open ground is cut off.
+///
+/// Returns true if the vault was kept.
+fn stamp(
+    level: &mut Level,
+    vault: &Vault,
+    grid: &[Vec<char>],
+    origin: Position,
+    templates: &TemplateLibrary,
+) -> bool {
+    let before = reachable(level);
+    let mut replaced = Vec::new();
+    let mut markers = Vec::new();
+    for (dy, row) in grid.iter().enumerate() {
+        for (dx, &glyph) in row.iter().enumerate() {
+            let position = Position::new(origin.x + dx as i32, origin.y + dy as i32);
+            let tile_type = match glyph {
+                ' ' => continue,
+                '#' => TileType::Wall,
+                '+' => TileType::Door { is_open: false },
+                '~' => TileType::Water,
+                '.' => TileType::Floor,
+                _ => {
+                    markers.push((position, vault.marker(glyph)));
+                    TileType::Floor
+                }
+            };
+            let Some(tile) = level.get_tile(position).cloned() else {
+                continue;
+            };
+            replaced.push((position, tile));
+            let _ = level.set_tile(position, Tile::new(tile_type));
+        }
+    }
+
+    let after = reachable(level);
+    let footprint: HashSet<Position> = replaced.iter().map(|(position, _)| *position).collect();
+    let cut_off = before
+        .iter()
+        .any(|position| !footprint.contains(position) && !after.contains(position));
+    let sealed = markers
+        .iter()
+        .any(|(position, _)| !after.contains(position));
+    if cut_off || sealed {
+        for (position, tile) in replaced {
+            let _ = level.set_tile(position, tile);
+        }
+        return false;
+    }
+
+    for (position, marker) in markers {
+        match marker {
+            Some(VaultMarker::Monster(name)) => {
+                if let Some(template) = templates.monster_template(name) {
+                    level.waiting_monsters.push(WaitingMonster {
+                        position,
+                        monster: template.clone(),
+                        out_of_depth: false,
+                    });
+                }
+            }
+            Some(VaultMarker::Item(name)) => {
+                if let Some(template) = templates.item_template(name) {
+                    level.ground_items.drop_item(template.spawn(position));
+                }
+            }
+            None => {}
+        }
+    }
+    true
+}
+
+/// Finds the tiles that can be reached from the player spawn, counting doors as open.
+fn reachable(level: &Level) -> HashSet<Position> {
+    let open = |position: Position| {
+        level.get_tile(position).is_some_and(|tile| {
+            tile.tile_type.is_passable() || matches!(tile.tile_type, TileType::Door { .. })
+        })
+    };
+    let mut seen = HashSet::from([level.player_spawn]);
+    let mut queue = VecDeque::from([level.player_spawn]);
+    while let Some(position) = queue.pop_front() {
+        for neighbor in position.cardinal_adjacent_positions() {
+            if open(neighbor) && seen.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RoomType;
+
+    #[test]
+    fn test_vaults_parse_turn_and_stamp_into_rooms() {
+        let text = "name: Den\nmin_depth: 2\nlegend: g = monster Goblin\nlegend: ! = item Dagger\n\
+                    ---\n####\n#g!+\n####\n";
+        let vault = Vault::parse(text).unwrap();
+        assert_eq!(vault.min_depth, 2);
+        let mirrored = vault.grid(VaultTransform {
+            quarter_turns: 2,
+            mirrored: true,
+        });
+        assert_eq!(mirrored[1], vec!['#', 'g', '!', '+']);
+        assert_eq!(
+            vault
+                .grid(VaultTransform {
+                    quarter_turns: 3,
+                    mirrored: false
+                })
+                .len(),
+            4
+        );
+        assert!(Vault::parse("name: Bad\n---\n#x#\n").is_err());
+        assert!(Vault::parse("name: Bad\nlegend: # = item Dagger\n---\n#\n").is_err());
+
+        let templates = TemplateLibrary::builtin().unwrap();
+        let unknown = Vault::parse("name: Odd\nlegend: z = monster Zombie\n---\nz\n").unwrap();
+        let library = VaultLibrary {
+            vaults: vec![unknown],
+        };
+        assert!(library.validate(&templates).is_err());
+
+        // A vault fits in the open room and keeps the floor connected
+        let mut level = Level::new(0, 20, 12);
+        let rooms = vec![
+            Room::new(0, Position::new(0, 0), 6, 12, RoomType::Normal),
+            Room::new(1, Position::new(6, 0), 14, 12, RoomType::Normal),
+        ];
+        for room in &rooms {
+            for position in room.floor_positions() {
+                level.set_tile(position, Tile::floor()).unwrap();
+            }
+        }
+        for y in 1..11 {
+            level.set_tile(Position::new(5, y), Tile::floor()).unwrap();
+            level.set_tile(Position::new(6, y), Tile::floor()).unwrap();
+        }
+        level.player_spawn = Position::new(2, 5);
+        let before = reachable(&level).len();
+        let grid = vault.grid(VaultTransform::default());
+        assert!(stamp(
+            &mut level,
+            &vault,
+            &grid,
+            Position::new(10, 4),
+            &templates
+        ));
+        assert_eq!(level.waiting_monsters[0].position, Position::new(11, 5));
+        assert_eq!(level.ground_items.iter().count(), 1);
+        assert!(reachable(&level).len() < before);
+
+        // Walling off the corridor would cut the floor in two, so it is undone
+        let wall: Vec<Vec<char>> = (0..10).map(|_| vec!['#']).collect();
+        assert!(!stamp(
+            &mut level,
+            &vault,
+            &wall,
+            Position::new(5, 1),
+            &templates
+        ));
+        assert!(level.is_passable(Position::new(5, 5)));
+
+        // Generated floors carry the vaults they were given
+        let config = GenerationConfig::new(7);
+        let placed = (0..20).filter(|&floor_id| {
+            let mut level = Level::new(floor_id, 40, 30);
+            let mut rooms = vec![
+                Room::new(0, Position::new(1, 1), 6, 6, RoomType::Normal),
+                Room::new(1, Position::new(10, 5), 20, 20, RoomType::Normal),
+            ];
+            for room in &rooms {
+                for position in room.floor_positions() {
+                    level.set_tile(position, Tile::floor()).unwrap();
+                }
+            }
+            for x in 4..15 {
+                level.set_tile(Position::new(x, 6), Tile::floor()).unwrap();
+            }
+            level.player_spawn = Position::new(3, 3);
+            place_vault(&mut level, &mut rooms, &config).is_some()
+                && rooms[1].metadata.contains_key(VAULT_METADATA_KEY)
+        });
+        assert!(placed.count() > 0);
+    }
+}
@@ -0,0 +1,532 @@
+//! # Key Bindings
+//!
+//! The table of keys and the commands they give.
+//!
+//! [`InputHandler`] looks keys up in its [`KeyBinding`] table, and the help
+//! screen is built from the same table with [`help_sections`], so the help
+//! always lists the keys that actually work, however they are bound. Bindings
+//! earlier in the table win when more than one matches.
+//!
+//! [`InputHandler`]: super::InputHandler
+
+use super::PlayerInput;
+use crate::{Position, StairDirection};
+use macroquad::prelude::KeyCode;
+
+/// Which modifier key has to be held for a binding to apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    /// No modifier held
+    None,
+    /// Shift held
+    Shift,
+    /// Ctrl held
+    Ctrl,
+    /// Applies whatever modifiers are held
+    Any,
+}
+
+impl Modifier {
+    /// Returns true if a binding with this modifier applies while `held` is.
+    pub fn matches(self, held: Modifier) -> bool {
+        self == Modifier::Any || self == held
+    }
+
+    /// Returns the prefix shown before the key in the help.
+    fn prefix(self) -> &'static str {
+        match self {
+            Modifier::None | Modifier::Any => "",
+            Modifier::Shift => "Shift+",
+            Modifier::Ctrl => "Ctrl+",
+        }
+    }
+}
+
+/// Groups the help screen lists commands under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingCategory {
+    /// Walking, running and stairs
+    Movement,
+    /// Fighting, searching and other things done in the dungeon
+    Actions,
+    /// The pack and what lies on the floor
+    Items,
+    /// Screens and overlays describing the game
+    Information,
+    /// Zooming and looking around the map
+    View,
+    /// Commands for testing the game
+    Debug,
+}
+
+impl BindingCategory {
+    /// Every category, in the order the help lists them
+    pub const ALL: [BindingCategory; 6] = [
+        BindingCategory::Movement,
+        BindingCategory::Actions,
+        BindingCategory::Items,
+        BindingCategory::Information,
+        BindingCategory::View,
+        BindingCategory::Debug,
+    ];
+
+    /// Returns the heading shown in the help.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            BindingCategory::Movement => "Movement",
+            BindingCategory::Actions => "Actions",
+            BindingCategory::Items => "Items",
+            BindingCategory::Information => "Information",
+            BindingCategory::View => "View",
+            BindingCategory::Debug => "Debug",
+        }
+    }
+}
+
+/// Keys that give a command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBinding {
+    /// Group the command is listed under in the help
+    pub category: BindingCategory,
+    /// Keys that each give the command
+    pub keys: Vec<KeyCode>,
+    /// Modifier that has to be held with the key
+    pub modifier: Modifier,
+    /// Whether the binding only applies while Vi-keys are enabled
+    pub vi_keys: bool,
+    /// Command given
+    pub input: PlayerInput,
+    /// What the command does, as shown in the help
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    /// Creates a binding that applies with no modifier held.
+    pub fn new(
+        category: BindingCategory,
+        keys: &[KeyCode],
+        input: PlayerInput,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            category,
+            keys: keys.to_vec(),
+            modifier: Modifier::None,
+            vi_keys: false,
+            input,
+            description,
+        }
+    }
+
+    /// Makes the binding apply only while `modifier` is held.
+    pub fn with_modifier(mut self, modifier: Modifier) -> Self {
+        self.modifier = modifier;
+        self
+    }
+
+    /// Makes the binding apply only while Vi-keys are enabled.
+    pub fn vi_only(mut self) -> Self {
+        self.vi_keys = true;
+        self
+    }
+
+    /// Returns the keys as shown in the help, such as `Shift+Up, Shift+W`.
+    pub fn keys_label(&self) -> String {
+        self.keys
+            .iter()
+            .map(|&key| format!("{}{}", self.modifier.prefix(), key_name(key)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Returns the name of a key as shown in the help.
+pub fn key_name(key: KeyCode) -> String {
+    let name = match key {
+        KeyCode::Comma => ",",
+        KeyCode::Period => ".",
+        KeyCode::Slash => "/",
+        KeyCode::Equal => "=",
+        KeyCode::Minus => "-",
+        KeyCode::GraveAccent => "`",
+        KeyCode::KpAdd => "Keypad +",
+        KeyCode::KpSubtract => "Keypad -",
+        KeyCode::Escape => "Esc",
+        other => return format!("{:?}", other),
+    };
+    name.to_string()
+}
+
+/// Gets the bindings the game starts with.
+pub fn default_bindings() -> Vec<KeyBinding> {
+    use BindingCategory::*;
+
+    let directions = [
+        (
+            [KeyCode::Up, KeyCode::W],
+            KeyCode::K,
+            (0, -1),
+            ["Move north", "Run north", "Look north"],
+        ),
+        (
+            [KeyCode::Down, KeyCode::S],
+            KeyCode::J,
+            (0, 1),
+            ["Move south", "Run south", "Look south"],
+        ),
+        (
+            [KeyCode::Left, KeyCode::A],
+            KeyCode::H,
+            (-1, 0),
+            ["Move west", "Run west", "Look west"],
+        ),
+        (
+            [KeyCode::Right, KeyCode::D],
+            KeyCode::L,
+            (1, 0),
+            ["Move east", "Run east", "Look east"],
+        ),
+    ];
+
+    // Keys that ignore modifiers come first, so `+` zooms however it is typed
+    let mut bindings = vec![
+        KeyBinding::new(
+            Information,
+            &[KeyCode::Escape],
+            PlayerInput::Quit,
+            "Open the menu",
+        )
+        .with_modifier(Modifier::Any),
+        KeyBinding::new(
+            View,
+            &[KeyCode::Equal, KeyCode::KpAdd],
+            PlayerInput::ZoomIn,
+            "Zoom in",
+        )
+        .with_modifier(Modifier::Any),
+        KeyBinding::new(
+            View,
+            &[KeyCode::Minus, KeyCode::KpSubtract],
+            PlayerInput::ZoomOut,
+            "Zoom out",
+        )
+        .with_modifier(Modifier::Any),
+    ];
+
+    for (keys, vi_key, (x, y), [step, run, look]) in directions {
+        let delta = Position::new(x, y);
+        bindings.extend([
+            KeyBinding::new(Movement, &keys, PlayerInput::Move(delta), step),
+            KeyBinding::new(Movement, &[vi_key], PlayerInput::Move(delta), step).vi_only(),
+            KeyBinding::new(Movement, &keys, PlayerInput::Run(delta), run)
+                .with_modifier(Modifier::Shift),
+            KeyBinding::new(Movement, &[vi_key], PlayerInput::Run(delta), run)
+                .with_modifier(Modifier::Shift)
+                .vi_only(),
+            KeyBinding::new(View, &keys, PlayerInput::Pan(delta), look)
+                .with_modifier(Modifier::Ctrl),
+        ]);
+    }
+
+    bindings.extend([
+        KeyBinding::new(
+            Movement,
+            &[KeyCode::Space, KeyCode::Period],
+            PlayerInput::Wait,
+            "Wait",
+        ),
+        KeyBinding::new(
+            Movement,
+            &[KeyCode::Comma],
+            PlayerInput::UseStairs(StairDirection::Up),
+            "Go up the stairs",
+        )
+        .with_modifier(Modifier::Shift),
+        KeyBinding::new(
+            Movement,
+            &[KeyCode::Period],
+            PlayerInput::UseStairs(StairDirection::Down),
+            "Go down the stairs",
+        )
+        .with_modifier(Modifier::Shift),
+        KeyBinding::new(
+            Movement,
+            &[KeyCode::G],
+            PlayerInput::OpenTravelMenu,
+            "Travel menu",
+        ),
+        KeyBinding::new(
+            Movement,
+            &[KeyCode::R],
+            PlayerInput::Rest,
+            "Rest until recovered",
+        ),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::Tab],
+            PlayerInput::AutoFight,
+            "Fight the nearest foe",
+        ),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::F],
+            PlayerInput::Search,
+            "Search for secrets",
+        ),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::F],
+            PlayerInput::Fire,
+            "Fire the ready launcher",
+        )
+        .with_modifier(Modifier::Shift),
+        KeyBinding::new(Actions, &[KeyCode::T], PlayerInput::Throw, "Throw a weapon"),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::C],
+            PlayerInput::UseAbility,
+            "Use the class ability",
+        )
+        .with_modifier(Modifier::Shift),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::Z],
+            PlayerInput::OpenSpellMenu,
+            "Cast a spell",
+        ),
+        KeyBinding::new(Actions, &[KeyCode::V], PlayerInput::Shout, "Shout"),
+        KeyBinding::new(Actions, &[KeyCode::E], PlayerInput::Talk, "Talk"),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::P],
+            PlayerInput::Pray,
+            "Pray at an altar",
+        ),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::Y],
+            PlayerInput::CommandAllies,
+            "Command allies",
+        ),
+        KeyBinding::new(
+            Actions,
+            &[KeyCode::Enter],
+            PlayerInput::Interact,
+            "Everything doable here",
+        ),
+        KeyBinding::new(
+            Items,
+            &[KeyCode::I],
+            PlayerInput::ShowInventory,
+            "Inventory",
+        ),
+        KeyBinding::new(Items, &[KeyCode::Comma], PlayerInput::PickUp, "Pick up"),
+        KeyBinding::new(
+            Items,
+            &[KeyCode::U],
+            PlayerInput::OpenUseMenu,
+            "Use, wear or remove an item",
+        ),
+        KeyBinding::new(
+            Items,
+            &[KeyCode::O],
+            PlayerInput::OpenContainer,
+            "Open a chest",
+        ),
+        KeyBinding::new(
+            Items,
+            &[KeyCode::B],
+            PlayerInput::Butcher,
+            "Butcher a corpse",
+        ),
+        KeyBinding::new(Information, &[KeyCode::F1], PlayerInput::Help, "Help"),
+        KeyBinding::new(Information, &[KeyCode::Slash], PlayerInput::Help, "Help")
+            .with_modifier(Modifier::Shift),
+        KeyBinding::new(Information, &[KeyCode::X], PlayerInput::Look, "Look"),
+        KeyBinding::new(
+            Information,
+            &[KeyCode::C],
+            PlayerInput::ShowCharacterSheet,
+            "Character sheet",
+        ),
+        KeyBinding::new(
+            Information,
+            &[KeyCode::Q],
+            PlayerInput::ShowQuestLog,
+            "Quest log",
+        ),
+        KeyBinding::new(
+            Information,
+            &[KeyCode::M],
+            PlayerInput::ShowMessageLog,
+            "Message log",
+        ),
+        KeyBinding::new(
+            View,
+            &[KeyCode::F2],
+            PlayerInput::ToggleDanger,
+            "Danger overlay",
+        ),
+        KeyBinding::new(
+            Debug,
+            &[KeyCode::F12],
+            PlayerInput::ToggleAutoexplore,
+            "Autoexplore",
+        ),
+        KeyBinding::new(
+            Debug,
+            &[KeyCode::F11],
+            PlayerInput::DebugDamage,
+            "Take damage",
+        ),
+        KeyBinding::new(
+            Debug,
+            &[KeyCode::F10],
+            PlayerInput::DebugValidate,
+            "Check the state",
+        ),
+    ]);
+
+    #[cfg(feature = "dev-tools")]
+    bindings.extend([
+        KeyBinding::new(
+            Debug,
+            &[KeyCode::F3],
+            PlayerInput::ToggleProfiler,
+            "Profiler",
+        ),
+        KeyBinding::new(Debug, &[KeyCode::U], PlayerInput::Undo(1), "Undo turns")
+            .with_modifier(Modifier::Shift),
+    ]);
+    bindings
+}
+
+/// Controls that are not keys in the binding table, listed after it in the help.
+pub const OTHER_CONTROLS: [(&str, &str); 6] = [
+    ("0-9", "Type a count for the next move or wait"),
+    ("`", "Repeat the last move or wait"),
+    ("Mouse wheel", "Zoom"),
+    ("Click, tap", "Travel to a tile"),
+    ("Right-click, long press", "Examine a tile"),
+    ("Swipe", "Move"),
+];
+
+/// One command in the help, with every key that gives it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpEntry {
+    /// The keys, as shown in the help
+    pub keys: String,
+    /// What the command does
+    pub description: String,
+}
+
+/// Commands listed under one heading in the help.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    /// Heading of the section
+    pub title: String,
+    /// Commands in the section
+    pub entries: Vec<HelpEntry>,
+}
+
+/// Builds the help from a binding table, grouping commands by category and
+/// listing each command once with every key that gives it.
+///
+/// Vi-key bindings are left out unless `vi_keys` is set, and only commands
+/// whose keys, description or heading contain `query`, ignoring case, are
+/// listed. Sections left empty are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{default_bindings, help_sections};
+///
+/// let sections = help_sections(&default_bindings(), true, "stairs");
+/// assert_eq!(sections.len(), 1);
+/// assert_eq!(sections[0].entries[1].keys, "Shift+.");
+/// ```
+pub fn help_sections(bindings: &[KeyBinding], vi_keys: bool, query: &str) -> Vec<HelpSection> {
+    let query = query.to_lowercase();
+    let mut sections: Vec<HelpSection> = BindingCategory::ALL
+        .iter()
+        .map(|&category| {
+            let mut entries: Vec<(&PlayerInput, HelpEntry)> = Vec::new();
+            let usable = bindings
+                .iter()
+                .filter(|binding| binding.category == category && (vi_keys || !binding.vi_keys));
+            for binding in usable {
+                let keys = binding.keys_label();
+                match entries
+                    .iter_mut()
+                    .find(|(input, _)| **input == binding.input)
+                {
+                    Some((_, entry)) => entry.keys = format!("{}, {}", entry.keys, keys),
+                    None => entries.push((
+                        &binding.input,
+                        HelpEntry {
+                            keys,
+                            description: binding.description.to_string(),
+                        },
+                    )),
+                }
+            }
+            HelpSection {
+                title: category.display_name().to_string(),
+                entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+            }
+        })
+        .collect();
+    sections.push(HelpSection {
+        title: "Other".to_string(),
+        entries: OTHER_CONTROLS
+            .iter()
+            .map(|&(keys, description)| HelpEntry {
+                keys: keys.to_string(),
+                description: description.to_string(),
+            })
+            .collect(),
+    });
+
+    for section in &mut sections {
+        let title_matches = section.title.to_lowercase().contains(&query);
+        section.entries.retain(|entry| {
+            title_matches
+                || entry.keys.to_lowercase().contains(&query)
+                || entry.description.to_lowercase().contains(&query)
+        });
+    }
+    sections.retain(|section| !section.entries.is_empty());
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_lists_each_command_once_with_all_its_keys() {
+        let bindings = default_bindings();
+        let sections = help_sections(&bindings, true, "");
+        let movement = &sections[0];
+        assert_eq!(movement.title, "Movement");
+        let north = movement
+            .entries
+            .iter()
+            .find(|entry| entry.description == "Move north")
+            .unwrap();
+        assert_eq!(north.keys, "Up, W, K");
+
+        // Vi-keys drop out of the help when they are turned off
+        let sections = help_sections(&bindings, false, "run north");
+        assert_eq!(sections[0].entries[0].keys, "Shift+Up, Shift+W");
+
+        // Searching matches headings as well as commands
+        let sections = help_sections(&bindings, true, "VIEW");
+        assert_eq!(sections.len(), 1);
+        assert!(sections[0]
+            .entries
+            .iter()
+            .any(|entry| entry.description == "Zoom in"));
+        assert!(help_sections(&bindings, true, "no such command").is_empty());
+    }
+}
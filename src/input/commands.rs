@@ -1,6 +1,15 @@
 //! # Command Definitions
 //!
 //! Command parsing and definitions for player input handling.
+//!
+//! Movement and waiting can be repeated. Typing a count before the command,
+//! such as `1 0 j`, takes it that many times, and the repeat key takes the
+//! last one again, with any count typed before it.
+
+use super::PlayerInput;
+
+/// Largest count a prefix can hold; further digits are ignored.
+pub const MAX_REPEAT_COUNT: u32 = 999;
 
 /// Placeholder for command definitions.
 pub struct Command;
@@ -17,3 +26,125 @@ impl Command {
         Self
     }
 }
+
+/// Parses count prefixes and remembers the last repeatable command.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{CountPrefix, PlayerInput, Position};
+///
+/// let mut prefix = CountPrefix::new();
+/// prefix.push_digit(1);
+/// prefix.push_digit(0);
+/// assert_eq!(prefix.pending(), Some(10));
+///
+/// let south = PlayerInput::Move(Position::new(0, 1));
+/// assert_eq!(
+///     prefix.apply(south.clone()),
+///     PlayerInput::Repeat(Box::new(south.clone()), 10)
+/// );
+/// assert_eq!(prefix.repeat_last(), Some(south));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CountPrefix {
+    /// Count typed so far, if any
+    count: Option<u32>,
+    /// Last movement or wait command given
+    last: Option<PlayerInput>,
+}
+
+impl CountPrefix {
+    /// Creates a parser with no count typed and nothing to repeat.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a typed digit to the end of the count.
+    pub fn push_digit(&mut self, digit: u32) {
+        let count = self.count.unwrap_or(0) * 10 + digit;
+        if count <= MAX_REPEAT_COUNT {
+            self.count = Some(count);
+        }
+    }
+
+    /// Gets the count typed so far, if any.
+    pub fn pending(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Forgets the count typed so far.
+    pub fn clear(&mut self) {
+        self.count = None;
+    }
+
+    /// Applies any typed count to an input, consuming the count.
+    ///
+    /// Movement and waiting given a count above one become a
+    /// [`PlayerInput::Repeat`], and are remembered for the repeat key. A count
+    /// typed before any other input is dropped.
+    pub fn apply(&mut self, input: PlayerInput) -> PlayerInput {
+        let count = self.count.take();
+        if !Self::is_repeatable(&input) {
+            return input;
+        }
+
+        self.last = Some(input.clone());
+        match count {
+            Some(count) if count > 1 => PlayerInput::Repeat(Box::new(input), count),
+            _ => input,
+        }
+    }
+
+    /// Takes the last movement or wait command again, with any typed count.
+    ///
+    /// Returns None if there is nothing to repeat yet.
+    pub fn repeat_last(&mut self) -> Option<PlayerInput> {
+        let Some(last) = self.last.clone() else {
+            self.count = None;
+            return None;
+        };
+        Some(self.apply(last))
+    }
+
+    /// Returns true for the inputs a count can repeat.
+    fn is_repeatable(input: &PlayerInput) -> bool {
+        matches!(input, PlayerInput::Move(_) | PlayerInput::Wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn test_count_prefix_repeats_movement_and_waiting_only() {
+        let mut prefix = CountPrefix::new();
+        assert_eq!(prefix.repeat_last(), None);
+
+        // A count is dropped by inputs it cannot repeat
+        prefix.push_digit(5);
+        assert_eq!(prefix.apply(PlayerInput::Search), PlayerInput::Search);
+        assert_eq!(prefix.pending(), None);
+        assert_eq!(prefix.repeat_last(), None);
+
+        // A count of one is just the command
+        prefix.push_digit(1);
+        assert_eq!(prefix.apply(PlayerInput::Wait), PlayerInput::Wait);
+
+        // The repeat key takes the last command again, with a fresh count
+        let east = PlayerInput::Move(Position::new(1, 0));
+        prefix.apply(east.clone());
+        prefix.push_digit(3);
+        assert_eq!(
+            prefix.repeat_last(),
+            Some(PlayerInput::Repeat(Box::new(east), 3))
+        );
+
+        for _ in 0..5 {
+            prefix.push_digit(9);
+        }
+        assert_eq!(prefix.pending(), Some(999));
+    }
+}
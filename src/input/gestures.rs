@@ -0,0 +1,213 @@
+//! # Touch Gestures
+//!
+//! Gesture recognition for touch screens.
+//!
+//! A finger on the map is tracked from the moment it touches down. Lifting it
+//! after sliding at least the swipe distance moves the player one step in the
+//! swipe's direction, lifting it without sliding taps the tile underneath to
+//! travel there, and holding it still for the long-press delay examines that
+//! tile. Both thresholds come from the settings.
+
+use crate::Position;
+use std::time::{Duration, Instant};
+
+/// Default distance in pixels a finger has to slide for a swipe.
+pub const DEFAULT_SWIPE_DISTANCE: f32 = 40.0;
+
+/// Default time in milliseconds a finger has to be held still for a long press.
+pub const DEFAULT_LONG_PRESS_MS: u64 = 500;
+
+/// Thresholds used to tell gestures apart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureConfig {
+    /// Distance in pixels a finger has to slide for a swipe
+    pub swipe_distance: f32,
+    /// Time a finger has to be held still for a long press
+    pub long_press: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            swipe_distance: DEFAULT_SWIPE_DISTANCE,
+            long_press: Duration::from_millis(DEFAULT_LONG_PRESS_MS),
+        }
+    }
+}
+
+/// A recognized touch gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A slide in one of eight directions, as a unit step
+    Swipe(Position),
+    /// A quick touch at a screen point
+    Tap(f32, f32),
+    /// A touch held still at a screen point
+    LongPress(f32, f32),
+}
+
+/// A finger currently on the screen.
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    /// Where the finger touched down
+    start: (f32, f32),
+    /// Where the finger was last seen
+    last: (f32, f32),
+    /// When the finger touched down
+    started_at: Instant,
+    /// Whether the press has slid far enough to be a swipe
+    moved: bool,
+    /// Whether a long press has already been reported
+    held: bool,
+}
+
+/// Turns touch begin, move and end events into [`Gesture`]s.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use thatch::{Gesture, GestureRecognizer, Position};
+///
+/// let mut gestures = GestureRecognizer::default();
+/// let now = Instant::now();
+///
+/// gestures.begin((100.0, 100.0), now);
+/// gestures.track((100.0, 160.0));
+/// let swipe = gestures.end(now + Duration::from_millis(120));
+/// assert_eq!(swipe, Some(Gesture::Swipe(Position::new(0, 1))));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GestureRecognizer {
+    /// Thresholds used to tell gestures apart
+    pub config: GestureConfig,
+    /// The finger being tracked, if any
+    press: Option<Press>,
+}
+
+impl GestureRecognizer {
+    /// Creates a recognizer with the given thresholds.
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            press: None,
+        }
+    }
+
+    /// Starts tracking a finger that touched down at `point`.
+    pub fn begin(&mut self, point: (f32, f32), now: Instant) {
+        self.press = Some(Press {
+            start: point,
+            last: point,
+            started_at: now,
+            moved: false,
+            held: false,
+        });
+    }
+
+    /// Follows the tracked finger to `point`.
+    pub fn track(&mut self, point: (f32, f32)) {
+        let swipe_distance = self.config.swipe_distance;
+        if let Some(press) = &mut self.press {
+            press.last = point;
+            let (dx, dy) = (point.0 - press.start.0, point.1 - press.start.1);
+            if (dx * dx + dy * dy).sqrt() >= swipe_distance {
+                press.moved = true;
+            }
+        }
+    }
+
+    /// Reports a long press once the tracked finger has been held still long enough.
+    ///
+    /// Called every frame while the finger is down; the long press is only
+    /// reported once per touch.
+    pub fn hold(&mut self, now: Instant) -> Option<Gesture> {
+        let long_press = self.config.long_press;
+        let press = self.press.as_mut()?;
+        if press.moved || press.held || now.duration_since(press.started_at) < long_press {
+            return None;
+        }
+        press.held = true;
+        Some(Gesture::LongPress(press.start.0, press.start.1))
+    }
+
+    /// Stops tracking the finger as it lifts, returning the swipe or tap it made.
+    ///
+    /// A press already reported as a long press makes no further gesture.
+    pub fn end(&mut self, now: Instant) -> Option<Gesture> {
+        let press = self.press.take()?;
+        if press.moved {
+            let (dx, dy) = (press.last.0 - press.start.0, press.last.1 - press.start.1);
+            return Some(Gesture::Swipe(Self::swipe_direction(dx, dy)));
+        }
+        if press.held || now.duration_since(press.started_at) >= self.config.long_press {
+            return None;
+        }
+        Some(Gesture::Tap(press.start.0, press.start.1))
+    }
+
+    /// Forgets the tracked finger without reporting a gesture.
+    ///
+    /// Used when the touch lands on an on-screen button instead of the map.
+    pub fn cancel(&mut self) {
+        self.press = None;
+    }
+
+    /// Snaps a slide to the nearest of the eight movement directions.
+    fn swipe_direction(dx: f32, dy: f32) -> Position {
+        // Slides within about 22.5 degrees of an axis count as straight
+        let straight = 2.4142;
+        let step = |along: f32, across: f32| {
+            if along.abs() * straight < across.abs() {
+                0
+            } else {
+                along.signum() as i32
+            }
+        };
+        Position::new(step(dx, dy), step(dy, dx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gestures_are_told_apart_by_distance_and_time() {
+        let config = GestureConfig {
+            swipe_distance: 30.0,
+            long_press: Duration::from_millis(400),
+        };
+        let mut gestures = GestureRecognizer::new(config);
+        let now = Instant::now();
+        let later = |ms| now + Duration::from_millis(ms);
+
+        // A short slide is still a tap
+        gestures.begin((50.0, 50.0), now);
+        gestures.track((60.0, 55.0));
+        assert_eq!(gestures.hold(later(100)), None);
+        assert_eq!(gestures.end(later(150)), Some(Gesture::Tap(50.0, 50.0)));
+
+        // Diagonal slides move diagonally
+        gestures.begin((50.0, 50.0), now);
+        gestures.track((20.0, 15.0));
+        assert_eq!(
+            gestures.end(later(150)),
+            Some(Gesture::Swipe(Position::new(-1, -1)))
+        );
+
+        // A press held still examines once and makes nothing on release
+        gestures.begin((80.0, 40.0), now);
+        assert_eq!(
+            gestures.hold(later(450)),
+            Some(Gesture::LongPress(80.0, 40.0))
+        );
+        assert_eq!(gestures.hold(later(600)), None);
+        assert_eq!(gestures.end(later(700)), None);
+
+        // A press cancelled by a button makes nothing
+        gestures.begin((80.0, 40.0), now);
+        gestures.cancel();
+        assert_eq!(gestures.end(later(100)), None);
+    }
+}
@@ -2,25 +2,41 @@
 //!
 //! Input handling and command parsing for player interactions.
 
+pub mod bindings;
 pub mod commands;
+pub mod gestures;
 
+pub use bindings::*;
 pub use commands::*;
+pub use gestures::*;
 
 use crate::game::{
-    ConcreteAction, Direction, Entity, GameState, MoveAction, Position, StairDirection,
-    UseStairsAction, WaitAction,
+    AbilityAction, ButcherAction, CastSpellAction, ConcreteAction, Direction, DoorAction, Entity,
+    GameState, Interaction, PickUpAction, Position, PrayAction, SearchAction,
+    ShoutAction, StairDirection, TalkAction, UseStairsAction, WaitAction,
 };
 use crate::{ThatchError, ThatchResult};
 use macroquad::prelude::*;
+use std::time::Instant;
 
 /// Input handler for processing player commands.
 ///
 /// Handles keyboard input and converts it to game actions that can be
-/// processed by the game state.
+/// processed by the game state. Number keys type a count for the next
+/// movement or wait, and the repeat key (`` ` ``) takes the last one again.
+/// Shift with a movement key, or capital HJKL, runs. Keys are looked up in
+/// the [`KeyBinding`] table, which the help screen lists.
+/// On touch screens, swipes move, taps travel and long presses examine.
 #[derive(Clone)]
 pub struct InputHandler {
     /// Whether to enable Vi-style movement keys (hjkl)
     pub vi_keys_enabled: bool,
+    /// Keys and the commands they give, earlier bindings first
+    pub bindings: Vec<KeyBinding>,
+    /// Count being typed and the last command that can be repeated
+    count_prefix: CountPrefix,
+    /// Touch gestures in progress and the thresholds used to recognize them
+    pub gestures: GestureRecognizer,
 }
 
 impl Default for InputHandler {
@@ -43,125 +59,243 @@ impl InputHandler {
     pub fn new() -> Self {
         Self {
             vi_keys_enabled: true,
+            bindings: default_bindings(),
+            count_prefix: CountPrefix::new(),
+            gestures: GestureRecognizer::default(),
         }
     }
 
     /// Gets the current input if any key is pressed.
     ///
     /// Returns the corresponding player input, or None if no key is pressed.
-    pub fn get_input(&self) -> Option<PlayerInput> {
+    pub fn get_input(&mut self) -> Option<PlayerInput> {
         self.process_macroquad_input()
     }
 
     /// Gets the current input, checking both keyboard and provided touch input.
     ///
     /// Returns the corresponding player input, or None if no input is detected.
-    pub fn get_input_with_touch(&self, touch_input: Option<PlayerInput>) -> Option<PlayerInput> {
+    pub fn get_input_with_touch(
+        &mut self,
+        touch_input: Option<PlayerInput>,
+    ) -> Option<PlayerInput> {
         // Check touch input first (higher priority for mobile)
         if let Some(input) = touch_input {
-            return Some(input);
+            return Some(self.count_prefix.apply(input));
         }
 
         // Fall back to keyboard input
         self.process_macroquad_input()
     }
 
-    /// Processes macroquad input and returns the corresponding player input.
-    fn process_macroquad_input(&self) -> Option<PlayerInput> {
-        // Check for quit
-        if is_key_pressed(KeyCode::Escape) {
-            return Some(PlayerInput::Quit);
-        }
+    /// Gets the count typed for the next command, if any.
+    pub fn pending_count(&self) -> Option<u32> {
+        self.count_prefix.pending()
+    }
 
-        // Movement keys - Arrow keys
-        if is_key_pressed(KeyCode::Up) {
-            return Some(PlayerInput::Move(Position::new(0, -1)));
-        }
-        if is_key_pressed(KeyCode::Down) {
-            return Some(PlayerInput::Move(Position::new(0, 1)));
-        }
-        if is_key_pressed(KeyCode::Left) {
-            return Some(PlayerInput::Move(Position::new(-1, 0)));
+    /// Processes macroquad input, applying any typed count to it.
+    fn process_macroquad_input(&mut self) -> Option<PlayerInput> {
+        // Escape abandons a count before it opens the menu
+        if is_key_pressed(KeyCode::Escape) && self.count_prefix.pending().is_some() {
+            self.count_prefix.clear();
+            return None;
         }
-        if is_key_pressed(KeyCode::Right) {
-            return Some(PlayerInput::Move(Position::new(1, 0)));
+
+        // Count prefix
+        if !self.is_shift_held() {
+            if let Some(digit) = self.pressed_digit() {
+                self.count_prefix.push_digit(digit);
+                return None;
+            }
         }
 
-        // Movement keys - WASD
-        if is_key_pressed(KeyCode::W) {
-            return Some(PlayerInput::Move(Position::new(0, -1)));
+        // Repeat the last movement or wait
+        if is_key_pressed(KeyCode::GraveAccent) {
+            return self.count_prefix.repeat_last();
         }
-        if is_key_pressed(KeyCode::S) {
-            return Some(PlayerInput::Move(Position::new(0, 1)));
+
+        let input = self.pressed_command()?;
+
+        // A count says how many turns to undo
+        if let PlayerInput::Undo(_) = input {
+            let turns = self.count_prefix.pending().unwrap_or(1);
+            self.count_prefix.clear();
+            return Some(PlayerInput::Undo(turns));
         }
-        if is_key_pressed(KeyCode::A) {
-            return Some(PlayerInput::Move(Position::new(-1, 0)));
+        Some(self.count_prefix.apply(input))
+    }
+
+    /// Returns the digit of any number key pressed this frame.
+    fn pressed_digit(&self) -> Option<u32> {
+        const DIGIT_KEYS: [KeyCode; 10] = [
+            KeyCode::Key0,
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+
+        DIGIT_KEYS
+            .iter()
+            .position(|&key| is_key_pressed(key))
+            .map(|digit| digit as u32)
+    }
+
+    /// Returns the command of the key pressed this frame, if any.
+    fn pressed_command(&self) -> Option<PlayerInput> {
+        // Zoom - the mouse wheel, besides the zoom keys
+        let (_, wheel_y) = mouse_wheel();
+        if wheel_y > 0.0 {
+            return Some(PlayerInput::ZoomIn);
         }
-        if is_key_pressed(KeyCode::D) {
-            return Some(PlayerInput::Move(Position::new(1, 0)));
+        if wheel_y < 0.0 {
+            return Some(PlayerInput::ZoomOut);
         }
 
-        // Movement keys - Vi style (hjkl) if enabled
-        if self.vi_keys_enabled {
-            if is_key_pressed(KeyCode::H) {
-                return Some(PlayerInput::Move(Position::new(-1, 0)));
-            }
-            if is_key_pressed(KeyCode::J) {
-                return Some(PlayerInput::Move(Position::new(0, 1)));
-            }
-            if is_key_pressed(KeyCode::K) {
-                return Some(PlayerInput::Move(Position::new(0, -1)));
-            }
-            if is_key_pressed(KeyCode::L) {
-                return Some(PlayerInput::Move(Position::new(1, 0)));
-            }
+        self.usable_bindings()
+            .find(|binding| binding.keys.iter().any(|&key| is_key_pressed(key)))
+            .map(|binding| binding.input.clone())
+    }
 
-            // No diagonal movement keys - removed for cardinal-only movement
-        }
+    /// Returns true if a key bound to `input` was pressed this frame, for
+    /// screens that take only a few commands.
+    pub fn is_input_pressed(&self, input: &PlayerInput) -> bool {
+        self.usable_bindings().any(|binding| {
+            binding.input == *input && binding.keys.iter().any(|&key| is_key_pressed(key))
+        })
+    }
 
-        // Wait/rest
-        if is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Period) {
-            return Some(PlayerInput::Wait);
-        }
+    /// Gets the bindings that apply with the modifiers held right now.
+    fn usable_bindings(&self) -> impl Iterator<Item = &KeyBinding> {
+        let held = self.held_modifier();
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.modifier.matches(held))
+            .filter(|binding| self.vi_keys_enabled || !binding.vi_keys)
+    }
 
-        // Help
-        if is_key_pressed(KeyCode::F1) {
-            return Some(PlayerInput::Help);
+    /// Returns the modifier held down, Ctrl winning over Shift.
+    fn held_modifier(&self) -> Modifier {
+        if self.is_free_look_held() {
+            Modifier::Ctrl
+        } else if self.is_shift_held() {
+            Modifier::Shift
+        } else {
+            Modifier::None
         }
+    }
+
+    /// Gets mouse input for the tile under the cursor.
+    ///
+    /// Left click travels to `hovered_tile`, right click examines it. Returns
+    /// None if the cursor is not over the map or no button was pressed.
+    pub fn get_mouse_input(&self, hovered_tile: Option<Position>) -> Option<PlayerInput> {
+        let tile = hovered_tile?;
 
-        // Inventory
-        if is_key_pressed(KeyCode::I) {
-            return Some(PlayerInput::ShowInventory);
+        if is_mouse_button_pressed(MouseButton::Left) {
+            return Some(PlayerInput::TravelTo(tile));
+        }
+        if is_mouse_button_pressed(MouseButton::Right) {
+            return Some(PlayerInput::Examine(tile));
         }
 
-        // Pick up item
-        if is_key_pressed(KeyCode::Comma) || is_key_pressed(KeyCode::G) {
-            return Some(PlayerInput::PickUp);
+        None
+    }
+
+    /// Gets input from touch gestures on the map.
+    ///
+    /// Swipes move the player one step, taps travel to the tile under the
+    /// finger and long presses examine it. `tile_at` converts a screen point
+    /// to the world tile under it, or None outside the map.
+    pub fn get_gesture_input(
+        &mut self,
+        tile_at: impl Fn(f32, f32) -> Option<Position>,
+    ) -> Option<PlayerInput> {
+        let now = Instant::now();
+        let mut gesture = None;
+
+        // Only the first finger down is followed
+        if let Some(touch) = touches().first() {
+            let point = (touch.position.x, touch.position.y);
+            match touch.phase {
+                TouchPhase::Started => self.gestures.begin(point, now),
+                TouchPhase::Moved | TouchPhase::Stationary => self.gestures.track(point),
+                TouchPhase::Ended => {
+                    self.gestures.track(point);
+                    gesture = self.gestures.end(now);
+                }
+                TouchPhase::Cancelled => self.gestures.cancel(),
+            }
         }
+        let gesture = gesture.or_else(|| self.gestures.hold(now))?;
 
-        // Enter (confirm action)
-        if is_key_pressed(KeyCode::Enter) {
-            return Some(PlayerInput::Confirm);
+        match gesture {
+            Gesture::Swipe(delta) => Some(PlayerInput::Move(delta)),
+            Gesture::Tap(x, y) => tile_at(x, y).map(PlayerInput::TravelTo),
+            Gesture::LongPress(x, y) => tile_at(x, y).map(PlayerInput::Examine),
         }
+    }
+
+    /// Returns the zero-based menu entry selected with the number keys 1-9, if any.
+    pub fn get_menu_selection(&self) -> Option<usize> {
+        const NUMBER_KEYS: [KeyCode; 9] = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+
+        NUMBER_KEYS.iter().position(|&key| is_key_pressed(key))
+    }
+
+    /// Returns true if there was any input this frame: a key or mouse button,
+    /// the mouse moving or its wheel turning, or a touch.
+    pub fn has_activity(&self) -> bool {
+        let (wheel_x, wheel_y) = mouse_wheel();
+        get_last_key_pressed().is_some()
+            || wheel_x != 0.0
+            || wheel_y != 0.0
+            || mouse_delta_position() != Vec2::ZERO
+            || [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+                .into_iter()
+                .any(|button| is_mouse_button_down(button) || is_mouse_button_released(button))
+            || !touches().is_empty()
+    }
+
+    /// Returns true while the free-look key (Ctrl) is held down.
+    pub fn is_free_look_held(&self) -> bool {
+        is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
+    }
+
+    /// Returns true while Shift is held down, for the shifted commands and running.
+    pub fn is_shift_held(&self) -> bool {
+        is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+    }
 
-        // Stairs - using number keys since < > are hard to press
-        if is_key_pressed(KeyCode::Key1) {
-            return Some(PlayerInput::UseStairs(StairDirection::Up));
+    /// Returns the movement delta of any movement key pressed this frame.
+    pub fn pressed_movement_delta(&self) -> Option<Position> {
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            return Some(Position::new(0, -1));
         }
-        if is_key_pressed(KeyCode::Key2) {
-            return Some(PlayerInput::UseStairs(StairDirection::Down));
+        if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            return Some(Position::new(0, 1));
         }
-
-        // Debug commands
-        if is_key_pressed(KeyCode::F12) {
-            return Some(PlayerInput::ToggleAutoexplore);
+        if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) {
+            return Some(Position::new(-1, 0));
         }
-
-        // Debug damage (X key)
-        if is_key_pressed(KeyCode::X) {
-            return Some(PlayerInput::DebugDamage);
+        if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) {
+            return Some(Position::new(1, 0));
         }
-
         None
     }
 
@@ -178,11 +312,16 @@ impl InputHandler {
             PlayerInput::Move(delta) => {
                 if let Some(player) = game_state.get_player() {
                     if let Some(direction) = Direction::from_delta(delta) {
-                        Ok(Some(ConcreteAction::Move(MoveAction {
-                            actor: player.id(),
+                        // Walking into a closed door may open it
+                        let level = game_state.world.current_level().ok_or_else(|| {
+                            ThatchError::InvalidState("No current level".to_string())
+                        })?;
+                        Ok(Some(game_state.door_opening.step(
+                            level,
+                            player.id(),
+                            player.position(),
                             direction,
-                            metadata: std::collections::HashMap::new(),
-                        })))
+                        )))
                     } else {
                         Err(ThatchError::InvalidAction(
                             "Invalid movement direction".to_string(),
@@ -204,6 +343,102 @@ impl InputHandler {
                 }
             }
 
+            PlayerInput::Shout => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::Shout(ShoutAction::new(player.id()))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::Search => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::Search(SearchAction::new(player.id()))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::UseAbility => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::Ability(AbilityAction::new(
+                        player.id(),
+                        player.class.ability(),
+                    ))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::PickUp => {
+                if let Some(player) = game_state.get_player() {
+                    let top_item = game_state
+                        .world
+                        .current_level()
+                        .and_then(|level| level.ground_items.top_at(player.position()))
+                        .map(|stack| stack.item.id);
+                    Ok(top_item.map(|item_id| {
+                        ConcreteAction::PickUp(PickUpAction::new(player.id(), item_id))
+                    }))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::Butcher => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(ButcherAction::corpse_under_player(game_state).map(|corpse_id| {
+                        ConcreteAction::Butcher(ButcherAction::new(player.id(), corpse_id))
+                    }))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::Talk => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(TalkAction::npc_within_reach(game_state)
+                        .map(|npc_id| ConcreteAction::Talk(TalkAction::new(player.id(), npc_id))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::Pray => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::Pray(PrayAction::new(player.id()))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::OpenDoor(position) => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::Door(DoorAction::open(player.id(), position))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::CloseDoor(position) => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::Door(DoorAction::close(player.id(), position))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
+            PlayerInput::CastSpell(spell) => {
+                if let Some(player) = game_state.get_player() {
+                    Ok(Some(ConcreteAction::CastSpell(CastSpellAction::new(
+                        player.id(),
+                        spell,
+                    ))))
+                } else {
+                    Err(ThatchError::InvalidState("No player found".to_string()))
+                }
+            }
+
             PlayerInput::UseStairs(direction) => {
                 if let Some(player) = game_state.get_player() {
                     Ok(Some(ConcreteAction::UseStairs(UseStairsAction::new(
@@ -226,6 +461,8 @@ impl InputHandler {
 pub enum PlayerInput {
     /// Move in a given direction (relative position)
     Move(Position),
+    /// Run in a given direction until something is worth stopping for
+    Run(Position),
     /// Wait/rest for one turn
     Wait,
     /// Quit the game
@@ -234,12 +471,12 @@ pub enum PlayerInput {
     Help,
     /// Show inventory
     ShowInventory,
+    /// Show the character sheet
+    ShowCharacterSheet,
     /// Pick up item at current position
     PickUp,
     /// Cancel current action
     Cancel,
-    /// Confirm current action
-    Confirm,
     /// Use stairs in the specified direction
     UseStairs(StairDirection),
     /// Start a new game (when game has ended)
@@ -248,4 +485,84 @@ pub enum PlayerInput {
     ToggleAutoexplore,
     /// Debug command to deal damage to player
     DebugDamage,
+    /// Debug command to check game state consistency
+    DebugValidate,
+    /// Zoom the map view in
+    ZoomIn,
+    /// Zoom the map view out
+    ZoomOut,
+    /// Pan the map view in free-look mode (relative direction)
+    Pan(Position),
+    /// Travel to a world position (mouse click)
+    TravelTo(Position),
+    /// Examine a world position (mouse right-click)
+    Examine(Position),
+    /// Open the travel destination menu
+    OpenTravelMenu,
+    /// Enter look mode to examine visible tiles with a cursor
+    Look,
+    /// Search adjacent tiles for secret doors
+    Search,
+    /// Rest until recovered or interrupted
+    Rest,
+    /// Fight the nearest visible hostile until it dies or something interrupts
+    AutoFight,
+    /// Use the player's class ability
+    UseAbility,
+    /// Open the spell selection menu
+    OpenSpellMenu,
+    /// Cast a known spell by name
+    CastSpell(String),
+    /// Throw a weapon from the pack at the nearest visible hostile
+    Throw,
+    /// Shoot the ready launcher at the nearest visible hostile
+    Fire,
+    /// Open the menu of items to use, wear or take off
+    OpenUseMenu,
+    /// Open the chest within reach and list its contents
+    OpenContainer,
+    /// Butcher the corpse under the player into food
+    Butcher,
+    /// Shout, alerting monsters that hear it
+    Shout,
+    /// Talk to the NPC within reach
+    Talk,
+    /// Pray at the altar within reach
+    Pray,
+    /// Open the menu of everything doable at the player's tile
+    Interact,
+    /// Open the closed door at a position next to the player
+    OpenDoor(Position),
+    /// Close the open door at a position next to the player
+    CloseDoor(Position),
+    /// Cycle the command given to allies: follow, stay, attack
+    CommandAllies,
+    /// Open the quest log
+    ShowQuestLog,
+    /// Open the message log
+    ShowMessageLog,
+    /// Toggle the overlay of tiles monsters can attack next turn
+    ToggleDanger,
+    /// Toggle the profiler overlay (dev-tools only)
+    ToggleProfiler,
+    /// Roll the game back this many turns (dev-tools only)
+    Undo(u32),
+    /// Take a movement or wait command this many times
+    Repeat(Box<PlayerInput>, u32),
+}
+
+impl From<Interaction> for PlayerInput {
+    /// Gets the input that carries out an interaction, as its own key would.
+    fn from(interaction: Interaction) -> Self {
+        match interaction {
+            Interaction::PickUp => PlayerInput::PickUp,
+            Interaction::Butcher => PlayerInput::Butcher,
+            Interaction::UseStairs(direction) => PlayerInput::UseStairs(direction),
+            Interaction::Pray => PlayerInput::Pray,
+            Interaction::Talk => PlayerInput::Talk,
+            Interaction::OpenContainer => PlayerInput::OpenContainer,
+            Interaction::OpenDoor(position) => PlayerInput::OpenDoor(position),
+            Interaction::CloseDoor(position) => PlayerInput::CloseDoor(position),
+        }
+    }
 }
@@ -58,6 +58,8 @@ pub use game::{
     // From world
     Level,
     MessageImportance,
+    Monster,
+    MonsterType,
     MoveAction,
     PlayerCharacter,
     Position,
@@ -70,7 +72,8 @@ pub use game::{
 };
 
 pub use generation::{
-    GenerationConfig, Generator, Room, RoomCorridorGenerator, RoomType, WorldGenerator,
+    BspDungeonGenerator, GenerationConfig, Generator, GeneratorKind, Room, RoomCorridorGenerator,
+    RoomType, TemplateLibrary, WorldGenerator,
 };
 
 pub use rendering::{MacroquadDisplay, UI};
@@ -101,6 +104,53 @@ pub enum ThatchError {
     /// LLM integration error
     #[error("LLDM error: {0}")]
     LldmError(String),
+
+    /// A move into a position that cannot be entered
+    #[error("Invalid action: Position {by}")]
+    MoveBlocked {
+        /// Position the move was headed for
+        position: Position,
+        /// What is in the way
+        by: BlockedBy,
+    },
+}
+
+impl ThatchError {
+    /// Gets a stable code for the kind of error, so remote clients and the
+    /// interface can react to it without reading the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::Serde(_) => "serialization",
+            Self::InvalidState(_) => "invalid_state",
+            Self::InvalidAction(_) => "invalid_action",
+            Self::GenerationFailed(_) => "generation_failed",
+            Self::LldmError(_) => "lldm",
+            Self::MoveBlocked { .. } => "move_blocked",
+        }
+    }
+}
+
+/// What keeps a move out of a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockedBy {
+    /// The position is off the level
+    Bounds,
+    /// The tile cannot be walked on
+    Terrain,
+    /// Another entity stands there
+    Entity(EntityId),
+}
+
+impl std::fmt::Display for BlockedBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bounds => write!(f, "out of bounds"),
+            Self::Terrain => write!(f, "is blocked"),
+            Self::Entity(_) => write!(f, "occupied by another entity"),
+        }
+    }
 }
 
 /// Result type used throughout the Thatch codebase.
@@ -115,7 +165,7 @@ pub mod config {
     pub const DEFAULT_DUNGEON_WIDTH: u32 = 80;
 
     /// Default dungeon height in tiles
-    pub const DEFAULT_DUNGEON_HEIGHT: u32 = 40;
+    pub const DEFAULT_DUNGEON_HEIGHT: u32 = 50;
 
     /// Maximum number of entities per level
     pub const MAX_ENTITIES_PER_LEVEL: usize = 1000;
@@ -125,4 +175,71 @@ pub mod config {
 
     /// Frames per second target for the game loop
     pub const TARGET_FPS: u64 = 60;
+
+    /// Directory where morgue files (character dumps) are written
+    pub const MORGUE_DIRECTORY: &str = "morgue";
+
+    /// Directory crash reports are written to
+    pub const CRASH_DIRECTORY: &str = "crashes";
+
+    /// File the game is saved to and continued from
+    pub const SAVE_FILE: &str = "thatch_save.json";
+
+    /// Name autosave files start with, before their slot number
+    pub const AUTOSAVE_FILE_STEM: &str = "thatch_autosave";
+
+    /// Number of autosave files written in turn
+    pub const AUTOSAVE_SLOTS: usize = 2;
+
+    /// Turns between autosaves unless changed in the options
+    pub const DEFAULT_AUTOSAVE_TURNS: u64 = 100;
+
+    /// File player settings are saved to
+    pub const SETTINGS_FILE: &str = "thatch_config.json";
+
+    /// File achievements and other progress kept between runs are saved to
+    pub const PROGRESS_FILE: &str = "thatch_progress.json";
+
+    /// File the best scores of past runs are saved to
+    pub const HIGH_SCORES_FILE: &str = "thatch_scores.json";
+
+    /// Monster and item templates loaded at startup, if present
+    pub const TEMPLATES_FILE: &str = "assets/data/templates.json";
+
+    /// Directory color theme files are loaded from, if present
+    pub const THEMES_DIRECTORY: &str = "assets/themes";
+
+    /// Directory vault files are loaded from, if present
+    pub const VAULTS_DIRECTORY: &str = "assets/vaults";
+
+    /// Tileset used when none is specified; "none" draws glyphs instead of
+    /// loading an atlas from `assets/tilesets/`
+    pub const DEFAULT_TILESET: &str = "none";
+
+    /// Default address for the WebSocket remote-control server
+    pub const DEFAULT_WS_ADDRESS: &str = "127.0.0.1:9002";
+
+    /// Default perception for the player and most monsters
+    pub const DEFAULT_PERCEPTION: u32 = 10;
+
+    /// Turns the player can spend in water before starting to drown
+    pub const BREATH_TURNS: u32 = 5;
+
+    /// Damage taken each turn spent drowning
+    pub const DROWNING_DAMAGE: u32 = 8;
+
+    /// Mana the player recovers each turn
+    pub const MANA_REGEN_PER_TURN: u32 = 1;
+
+    /// Chance each turn in water of swimming without swallowing water
+    pub const SWIM_CHECK_CHANCE: f64 = 0.75;
+
+    /// Number of levels in the dungeon; the Amulet lies on the last one
+    pub const DUNGEON_LEVELS: u32 = 26;
+
+    /// Number of recent messages the game state remembers
+    pub const RECENT_MESSAGE_LIMIT: usize = 20;
+
+    /// Fraction of maximum health below which autoexplore pauses
+    pub const AUTOEXPLORE_HEALTH_THRESHOLD: f32 = 0.3;
 }
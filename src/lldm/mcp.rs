@@ -1,19 +1,353 @@
 //! # MCP Integration
 //!
 //! Model Context Protocol server integration.
+//!
+//! An [`McpServer`] speaks MCP's stdio transport: each line read is one
+//! JSON-RPC 2.0 message, and each request gets one line back. It answers
+//! `initialize`, `ping`, `tools/list`, `tools/call`, `resources/list` and
+//! `resources/read`; notifications get no reply. The tools are the
+//! [`SESSION_TOOLS`] and the [`QUERY_TOOLS`], and a call is passed on as a
+//! request to the server's [`SessionManager`], the one the WebSocket server
+//! uses, so both interfaces behave identically. Tool arguments may name a
+//! `session`; calls without one go to the default session. Each session's snapshot is also a resource
+//! at `thatch://sessions/{id}/snapshot`.
 
-/// Placeholder for MCP server integration.
-pub struct McpServer;
+use crate::{SessionManager, SessionReply, ThatchResult, QUERY_TOOLS};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::io::{BufRead, Write};
 
-impl Default for McpServer {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Version of the Model Context Protocol the server speaks.
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Name and description of each tool passed straight to the sessions.
+pub const SESSION_TOOLS: [(&str, &str); 5] = [
+    (
+        "snapshot",
+        "What the player can see, their condition, and the recent messages",
+    ),
+    (
+        "act",
+        "Takes one serialized action for the player and ends the turn",
+    ),
+    (
+        "create",
+        "Starts a new game with an optional `seed` and `player_name`",
+    ),
+    ("destroy", "Ends the session"),
+    ("sessions", "The ids of every session"),
+];
+
+/// JSON-RPC error code for a message that is not JSON.
+const PARSE_ERROR: i64 = -32700;
+
+/// JSON-RPC error code for a method the server does not have.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC error code for a call with missing or wrong parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// Prefix of every snapshot resource's URI.
+const RESOURCE_PREFIX: &str = "thatch://sessions/";
+
+/// Suffix of every snapshot resource's URI.
+const RESOURCE_SUFFIX: &str = "/snapshot";
+
+/// A JSON-RPC message from the client; only the fields served are read.
+#[derive(Debug, Clone, Deserialize)]
+struct McpMessage {
+    /// Request id, absent for notifications
+    #[serde(default)]
+    id: Option<Value>,
+    /// Name of the method to call
+    method: String,
+    /// Method arguments
+    #[serde(default)]
+    params: Value,
+}
+
+/// Arguments of a `tools/call` request.
+#[derive(Debug, Clone, Deserialize)]
+struct ToolCall {
+    /// Tool to call
+    name: String,
+    /// Tool arguments
+    #[serde(default)]
+    arguments: Map<String, Value>,
+}
+
+/// Arguments of a `resources/read` request.
+#[derive(Debug, Clone, Deserialize)]
+struct ResourceRead {
+    /// Resource to read
+    uri: String,
+}
+
+/// A JSON-RPC error, with its code.
+type RpcError = (i64, String);
+
+/// Serves the games in a [`SessionManager`] to MCP clients.
+///
+/// Requests are answered by the same [`SessionManager`] the WebSocket server
+/// uses, so both interfaces behave identically.
+pub struct McpServer {
+    /// The games being served
+    sessions: SessionManager,
 }
 
 impl McpServer {
-    /// Creates a new MCP server.
-    pub fn new() -> Self {
-        Self
+    /// Creates a new MCP server for a set of sessions.
+    pub fn new(sessions: SessionManager) -> Self {
+        Self { sessions }
+    }
+
+    /// Serves the current state of a session's game as a snapshot resource.
+    pub fn game_snapshot(&self, session: &str) -> ThatchResult<String> {
+        self.sessions.snapshot(session)?.to_json()
+    }
+
+    /// Answers a session request.
+    pub fn handle_request(&self, request: &str) -> SessionReply {
+        self.sessions.handle_request(request)
+    }
+
+    /// Answers messages read one per line from `input`, writing each
+    /// response as a line of `output`, until the input ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or writing fails.
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> ThatchResult<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers one JSON-RPC message, or returns `None` for a notification.
+    pub fn handle_message(&self, message: &str) -> Option<Value> {
+        let message: McpMessage = match serde_json::from_str(message) {
+            Ok(message) => message,
+            Err(e) => return Some(rpc_error(Value::Null, (PARSE_ERROR, e.to_string()))),
+        };
+        let id = message.id?;
+
+        let result = match message.method.as_str() {
+            "initialize" => Ok(json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": { "tools": {}, "resources": {} },
+                "serverInfo": { "name": "thatch", "version": crate::VERSION },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_list() })),
+            "tools/call" => self.call_tool(message.params),
+            "resources/list" => Ok(self.resource_list()),
+            "resources/read" => self.read_resource(message.params),
+            method => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => rpc_error(id, error),
+        })
+    }
+
+    /// Calls a tool through the sessions, reporting a failed call in the
+    /// result as MCP asks.
+    fn call_tool(&self, params: Value) -> Result<Value, RpcError> {
+        let mut call: ToolCall = parse_params(params)?;
+        if !tool_list().iter().any(|tool| tool["name"] == call.name.as_str()) {
+            return Err((INVALID_PARAMS, format!("Unknown tool '{}'", call.name)));
+        }
+
+        let session = call.arguments.remove("session");
+        let request = json!({
+            "id": 0,
+            "session": session,
+            "method": call.name,
+            "params": call.arguments,
+        });
+        let response = self.sessions.handle_request(&request.to_string()).response;
+        let (text, failed) = match response.get("error") {
+            Some(error) => (error.as_str().unwrap_or_default().to_string(), true),
+            None => (response["result"].to_string(), false),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": failed,
+        }))
+    }
+
+    /// Lists the snapshot resource of every session.
+    fn resource_list(&self) -> Value {
+        let resources: Vec<Value> = self
+            .sessions
+            .session_ids()
+            .into_iter()
+            .map(|id| {
+                json!({
+                    "uri": format!("{}{}{}", RESOURCE_PREFIX, id, RESOURCE_SUFFIX),
+                    "name": format!("Snapshot of session {}", id),
+                    "mimeType": "application/json",
+                })
+            })
+            .collect();
+        json!({ "resources": resources })
+    }
+
+    /// Reads a session's snapshot resource.
+    fn read_resource(&self, params: Value) -> Result<Value, RpcError> {
+        let read: ResourceRead = parse_params(params)?;
+        let session = read
+            .uri
+            .strip_prefix(RESOURCE_PREFIX)
+            .and_then(|rest| rest.strip_suffix(RESOURCE_SUFFIX))
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown resource '{}'", read.uri)))?;
+        let text = self
+            .game_snapshot(session)
+            .map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+        Ok(json!({
+            "contents": [{ "uri": read.uri, "mimeType": "application/json", "text": text }],
+        }))
+    }
+}
+
+/// Lists every tool with the schema of its arguments.
+fn tool_list() -> Vec<Value> {
+    SESSION_TOOLS
+        .iter()
+        .chain(QUERY_TOOLS.iter())
+        .map(|&(name, description)| {
+            json!({
+                "name": name,
+                "description": description,
+                "inputSchema": input_schema(name),
+            })
+        })
+        .collect()
+}
+
+/// Gets the JSON schema of a tool's arguments.
+///
+/// Every tool takes an optional `session`; `act` takes the serialized action
+/// itself, so it allows any other argument.
+fn input_schema(tool: &str) -> Value {
+    let mut properties = json!({
+        "session": { "type": "string", "description": "Session to use, or the default one" },
+    });
+    match tool {
+        "create" => {
+            properties["seed"] = json!({ "type": "integer", "minimum": 0 });
+            properties["player_name"] = json!({ "type": "string" });
+        }
+        "get_map" => {
+            properties["level_id"] = json!({ "type": "integer", "minimum": 0 });
+            properties["explored_only"] = json!({ "type": "boolean" });
+        }
+        "get_entities" => {
+            properties["radius"] = json!({
+                "type": "integer",
+                "minimum": 0,
+                "default": crate::DEFAULT_ENTITIES_RADIUS,
+            });
+        }
+        "get_messages" => {
+            properties["since_turn"] = json!({ "type": "integer", "minimum": 0 });
+        }
+        "act" => {
+            return json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": true,
+            })
+        }
+        _ => {}
+    }
+    json!({ "type": "object", "properties": properties, "additionalProperties": false })
+}
+
+/// Reads the parameters of a request, as an empty object if there are none.
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() {
+        Value::Object(Map::new())
+    } else {
+        params
+    };
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+/// Builds a JSON-RPC error response.
+fn rpc_error(id: Value, (code, message): RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, HeadlessSession, Position, DEFAULT_SESSION_ID};
+
+    #[test]
+    fn test_mcp_server_lists_and_calls_tools_over_lines() {
+        let mut game_state = GameState::new(3);
+        game_state
+            .initialize_player("Served".to_string(), Position::new(5, 5))
+            .unwrap();
+        let sessions = SessionManager::new(crate::GenerationConfig::new(3));
+        sessions.insert(DEFAULT_SESSION_ID, HeadlessSession::new(game_state));
+        let server = McpServer::new(sessions);
+
+        let input = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                    "params": { "name": "snapshot", "arguments": {} } }),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/call",
+                    "params": { "name": "snapshot", "arguments": { "session": "nope" } } }),
+            json!({ "jsonrpc": "2.0", "id": 5, "method": "resources/read",
+                    "params": { "uri": "thatch://sessions/default/snapshot" } }),
+            json!({ "jsonrpc": "2.0", "id": 6, "method": "teleport" }),
+        ]
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        let replies: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        // The notification gets no reply
+        assert_eq!(replies.len(), 6);
+        assert_eq!(replies[0]["result"]["protocolVersion"], MCP_PROTOCOL_VERSION);
+        let tools = replies[1]["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|tool| tool["name"] == "act"));
+        let entities = tools.iter().find(|tool| tool["name"] == "get_entities").unwrap();
+        assert_eq!(entities["inputSchema"]["properties"]["radius"]["type"], "integer");
+
+        let snapshot = &replies[2]["result"];
+        assert_eq!(snapshot["isError"], false);
+        let text = snapshot["content"][0]["text"].as_str().unwrap();
+        let snapshot: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(snapshot["player"]["name"], "Served");
+        assert_eq!(replies[3]["result"]["isError"], true);
+
+        let resource = &replies[4]["result"]["contents"][0];
+        assert_eq!(resource["mimeType"], "application/json");
+        assert_eq!(replies[5]["error"]["code"], METHOD_NOT_FOUND);
     }
 }
@@ -0,0 +1,244 @@
+//! # LLDM Memory
+//!
+//! What the dungeon master remembers of the run so far.
+//!
+//! As the run goes on, the game state notes the named rooms the player walks
+//! into, the NPCs they talk to and their first kill of each kind of monster as
+//! [`MemoryFact`]s in the [`LldmMemory`] kept in the LLDM state. Once more than
+//! [`MEMORY_FACT_LIMIT`] facts pile up, the oldest are folded into a summary
+//! line so the memory stays small. Snapshots and dialogue contexts carry the
+//! memory into every LLDM prompt, so generated narration agrees with what has
+//! already happened.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Facts kept word for word before the oldest are summarized.
+pub const MEMORY_FACT_LIMIT: usize = 24;
+
+/// Summary lines kept; the oldest are forgotten beyond this.
+pub const MEMORY_SUMMARY_LIMIT: usize = 8;
+
+/// What a remembered fact is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MemoryKind {
+    /// The player walked into a named room
+    Room,
+    /// The player talked to an NPC
+    Npc,
+    /// The player killed a kind of monster for the first time
+    Kill,
+}
+
+/// One thing that happened during the run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryFact {
+    /// What the fact is about
+    pub kind: MemoryKind,
+    /// Name of the room, NPC or monster the fact concerns
+    pub subject: String,
+    /// Dungeon level it happened on, starting at 1
+    pub depth: u32,
+    /// Turn it happened on
+    pub turn: u64,
+    /// The fact, as told to the model
+    pub text: String,
+}
+
+/// The facts the LLDM remembers, oldest first.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{LldmMemory, MemoryFact, MemoryKind};
+///
+/// let fact = MemoryFact {
+///     kind: MemoryKind::Kill,
+///     subject: "Troll".to_string(),
+///     depth: 4,
+///     turn: 310,
+///     text: "Slew a Troll.".to_string(),
+/// };
+/// let mut memory = LldmMemory::new();
+///
+/// assert!(memory.remember(fact.clone()));
+/// assert!(!memory.remember(fact));
+/// assert_eq!(memory.lines(), ["Level 4, turn 310: Slew a Troll."]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LldmMemory {
+    /// Summaries of facts too old to keep word for word, oldest first
+    pub summary: Vec<String>,
+    /// Recent facts, oldest first
+    pub facts: Vec<MemoryFact>,
+    /// Everything ever remembered, as kind and subject, so nothing is noted twice
+    seen: BTreeSet<String>,
+}
+
+impl LldmMemory {
+    /// Creates an empty memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes a fact, unless one of the same kind about the same subject
+    /// was noted before.
+    ///
+    /// Returns true if the fact was new. Adding it may fold the oldest facts
+    /// into the summary.
+    pub fn remember(&mut self, fact: MemoryFact) -> bool {
+        if !self
+            .seen
+            .insert(format!("{:?}:{}", fact.kind, fact.subject))
+        {
+            return false;
+        }
+        self.facts.push(fact);
+        if self.facts.len() > MEMORY_FACT_LIMIT {
+            self.summarize();
+        }
+        true
+    }
+
+    /// Gets the most recent fact of a kind still kept word for word.
+    pub fn latest(&self, kind: MemoryKind) -> Option<&MemoryFact> {
+        self.facts.iter().rev().find(|fact| fact.kind == kind)
+    }
+
+    /// Returns true if nothing has been remembered.
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_empty() && self.facts.is_empty()
+    }
+
+    /// Gets the memory as lines for a prompt, summaries first.
+    pub fn lines(&self) -> Vec<String> {
+        let facts = self
+            .facts
+            .iter()
+            .map(|fact| format!("Level {}, turn {}: {}", fact.depth, fact.turn, fact.text));
+        self.summary.iter().cloned().chain(facts).collect()
+    }
+
+    /// Folds the older half of the facts into one summary line.
+    fn summarize(&mut self) {
+        let old: Vec<MemoryFact> = self.facts.drain(..MEMORY_FACT_LIMIT / 2).collect();
+        let subjects = |kind| {
+            let names: Vec<&str> = old
+                .iter()
+                .filter(|fact| fact.kind == kind)
+                .map(|fact| fact.subject.as_str())
+                .collect();
+            names.join(", ")
+        };
+
+        let mut parts = Vec::new();
+        for (kind, verb) in [
+            (MemoryKind::Room, "visited"),
+            (MemoryKind::Npc, "spoke with"),
+            (MemoryKind::Kill, "slew"),
+        ] {
+            let names = subjects(kind);
+            if !names.is_empty() {
+                parts.push(format!("{} {}", verb, names));
+            }
+        }
+        let (first, last) = (&old[0], &old[old.len() - 1]);
+        self.summary.push(format!(
+            "Levels {}-{}, turns {}-{}: the player {}.",
+            first.depth,
+            last.depth,
+            first.turn,
+            last.turn,
+            parts.join("; ")
+        ));
+        if self.summary.len() > MEMORY_SUMMARY_LIMIT {
+            self.summary.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Action, GameEvent, GameState, Monster, MonsterType, Npc, NpcRole, Position, Room, RoomType,
+        TalkAction,
+    };
+
+    #[test]
+    fn test_run_events_are_remembered_and_summarized() {
+        let mut game_state = GameState::new(12345);
+        let player_id = game_state
+            .initialize_player("Chronicler".to_string(), Position::new(5, 5))
+            .unwrap();
+        let mut room = Room::new(0, Position::new(4, 4), 4, 4, RoomType::Library);
+        room.name = Some("the Dusty Archive".to_string());
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .rooms
+            .push(room);
+
+        // Walking into a named room is remembered once
+        for to in [Position::new(5, 5), Position::new(6, 5)] {
+            let moved = GameEvent::EntityMoved {
+                entity_id: player_id,
+                from: Position::new(4, 5),
+                to,
+            };
+            game_state.process_event(&moved).unwrap();
+        }
+        let memory = &game_state.lldm_state.memory;
+        assert_eq!(memory.facts.len(), 1);
+        assert_eq!(memory.facts[0].subject, "the Dusty Archive");
+
+        // So are conversations and the first kill of each kind
+        let priest_id = game_state
+            .add_entity(Npc::new(NpcRole::Priest, Position::new(5, 6)).into())
+            .unwrap();
+        TalkAction::new(player_id, priest_id)
+            .execute(&mut game_state)
+            .unwrap();
+        for _ in 0..2 {
+            let goblin = Monster::new(MonsterType::Goblin, Position::new(8, 8));
+            let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+            let died = GameEvent::EntityDied {
+                entity_id: goblin_id,
+                killer: Some(player_id),
+            };
+            game_state.process_event(&died).unwrap();
+        }
+        let memory = &game_state.lldm_state.memory;
+        assert_eq!(memory.facts.len(), 3);
+        assert_eq!(memory.latest(MemoryKind::Kill).unwrap().subject, "Goblin");
+
+        // What the LLDM remembers reaches its prompts
+        let snapshot = game_state.snapshot_for_llm(crate::DEFAULT_SNAPSHOT_TOKEN_BUDGET);
+        assert_eq!(snapshot.memory, memory.lines());
+        let client = crate::LldmClient::new();
+        let npc = game_state.entities[&priest_id].as_npc().unwrap();
+        let context = client.dialogue_context(&game_state, npc);
+        assert_eq!(context["notable_kill"], "Goblin");
+        assert!(client
+            .generate_dialogue(&context)
+            .iter()
+            .any(|line| line.contains("Goblin")));
+
+        // Old facts are folded into a summary once the memory fills up
+        let mut memory = LldmMemory::new();
+        for turn in 0..=MEMORY_FACT_LIMIT as u64 {
+            memory.remember(MemoryFact {
+                kind: MemoryKind::Kill,
+                subject: format!("Beast {}", turn),
+                depth: 1,
+                turn,
+                text: format!("Slew Beast {}.", turn),
+            });
+        }
+        assert_eq!(memory.facts.len(), MEMORY_FACT_LIMIT / 2 + 1);
+        assert_eq!(memory.summary.len(), 1);
+        assert!(memory.summary[0].starts_with("Levels 1-1, turns 0-11: the player slew Beast 0"));
+    }
+}
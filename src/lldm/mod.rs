@@ -3,10 +3,39 @@
 //! LLM Dungeon Master integration for enhanced content generation.
 
 pub mod mcp;
+pub mod memory;
+pub mod prompts;
+pub mod requests;
+pub mod session;
+pub mod sessions;
+pub mod snapshot;
+pub mod spectator;
+pub mod tools;
 pub mod traits;
+#[cfg(feature = "ws-server")]
+pub mod ws;
 
 pub use mcp::*;
+pub use memory::*;
+pub use prompts::*;
+pub use requests::*;
+pub use session::*;
+pub use sessions::*;
+pub use snapshot::*;
+pub use spectator::*;
+pub use tools::*;
 pub use traits::*;
+#[cfg(feature = "ws-server")]
+pub use ws::*;
+
+use crate::{
+    Deity, Entity, GameState, GenerationConfig, Item, Level, Npc, Objective, Quest, QuestReward,
+    Room, RoomType, ThatchResult, UniqueFlavor, DEITY_NAMES, FLOOR_THEME_NAMES, UNIQUE_KEY,
+};
+use std::collections::HashMap;
+
+/// Experience a generated quest awards for each dungeon level of depth.
+pub const QUEST_XP_PER_LEVEL: u32 = 25;
 
 /// Placeholder for LLDM integration.
 pub struct LldmClient;
@@ -22,4 +51,344 @@ impl LldmClient {
     pub fn new() -> Self {
         Self
     }
+
+    /// Builds the world description handed to the narrator model.
+    pub fn narration_context(&self, game_state: &GameState) -> ThatchResult<String> {
+        game_state
+            .snapshot_for_llm(DEFAULT_SNAPSHOT_TOKEN_BUDGET)
+            .to_json()
+    }
+
+    /// Gathers the room and floor metadata a quest is written from.
+    pub fn quest_context(&self, level: &Level, room: Option<&Room>) -> HashMap<String, String> {
+        let mut context = HashMap::from([("level".to_string(), (level.id + 1).to_string())]);
+        if let Some(name) = &level.name {
+            context.insert("level_name".to_string(), name.clone());
+        }
+        if let Some(theme) = &level.theme {
+            context.insert("level_theme".to_string(), theme.name.clone());
+        }
+        if let Some(room) = room {
+            context.insert("room_type".to_string(), room_kind(&room.room_type).to_string());
+            if let Some(name) = &room.name {
+                context.insert("room_name".to_string(), name.clone());
+            }
+            if let Some(description) = &room.description {
+                context.insert("room_description".to_string(), description.clone());
+            }
+        }
+        context
+    }
+
+    /// Writes a quest for the player arriving in `room` on `level`.
+    ///
+    /// The objective is to press on to the next level, or to find the
+    /// [`ARTIFACT_NAME`](crate::ARTIFACT_NAME) on the deepest one, and the flavor
+    /// text comes from the [`quest_context`](Self::quest_context). Until a model
+    /// is connected the text is filled in from fixed phrasing, so the same floor
+    /// always yields the same quest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Level, LldmClient, Objective, Position};
+    ///
+    /// let mut level = Level::new(2, 10, 10);
+    /// level.name = Some("the Sunken Halls".to_string());
+    /// level.stairs_down_position = Some(Position::new(5, 5));
+    /// let quest = LldmClient::new().generate_quest(&level, None);
+    ///
+    /// assert_eq!(quest.objective, Objective::ReachFloor { floor: 3 });
+    /// assert!(quest.description.contains("the Sunken Halls"));
+    /// ```
+    pub fn generate_quest(&self, level: &Level, room: Option<&Room>) -> Quest {
+        let context = self.quest_context(level, room);
+        let depth = &context["level"];
+        let place = match (context.get("room_name"), context.get("room_type")) {
+            (Some(name), _) => name.clone(),
+            (None, Some(kind)) => format!("the {}", kind),
+            (None, None) => "the dark".to_string(),
+        };
+        let floor_name = context
+            .get("level_name")
+            .cloned()
+            .unwrap_or_else(|| format!("dungeon level {}", depth));
+
+        let (title, goal, objective) = if level.stairs_down_position.is_some() {
+            (
+                format!("Deeper than level {}", depth),
+                "the stairs lead somewhere worse. Find out where.".to_string(),
+                Objective::ReachFloor { floor: level.id + 1 },
+            )
+        } else {
+            (
+                format!("The heart of level {}", depth),
+                format!("the {} lies close. Take it.", crate::ARTIFACT_NAME),
+                Objective::Retrieve {
+                    item: crate::ARTIFACT_NAME.to_string(),
+                },
+            )
+        };
+        let mut description = format!("A voice in {} of {} says {}", place, floor_name, goal);
+        if let Some(extra) = context.get("room_description") {
+            description = format!("{} {}", extra, description);
+        }
+
+        Quest::new(
+            title,
+            description,
+            objective,
+            QuestReward::Experience(QUEST_XP_PER_LEVEL * (level.id + 1)),
+        )
+    }
+
+    /// Gathers what an NPC knows when the player talks to it.
+    pub fn dialogue_context(&self, game_state: &GameState, npc: &Npc) -> HashMap<String, String> {
+        let mut context = HashMap::from([
+            ("npc_name".to_string(), npc.name.clone()),
+            ("npc_role".to_string(), npc.role.display_name().to_string()),
+        ]);
+        if let Some(player) = game_state.get_player() {
+            context.insert("player_name".to_string(), player.name().to_string());
+            context.insert("player_class".to_string(), player.class.display_name().to_string());
+        }
+        if let Some(level) = game_state.world.current_level() {
+            let room = level.rooms.iter().find(|room| room.contains(npc.position));
+            context.extend(self.quest_context(level, room));
+        }
+        if let Some(quest) = game_state.quests.active().next() {
+            context.insert("player_quest".to_string(), quest.title.clone());
+        }
+        let memory = &game_state.lldm_state.memory;
+        if !memory.is_empty() {
+            context.insert("memory".to_string(), memory.lines().join("\n"));
+        }
+        if let Some(kill) = memory.latest(MemoryKind::Kill) {
+            context.insert("notable_kill".to_string(), kill.subject.clone());
+        }
+        context
+    }
+
+    /// Writes what an NPC says, from its [`dialogue_context`](Self::dialogue_context).
+    ///
+    /// Until a model is connected the lines are filled in from fixed phrasing,
+    /// so the same context always yields the same lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use thatch::LldmClient;
+    ///
+    /// let context = HashMap::from([
+    ///     ("npc_role".to_string(), "Priest".to_string()),
+    ///     ("player_name".to_string(), "Ada".to_string()),
+    /// ]);
+    /// let lines = LldmClient::new().generate_dialogue(&context);
+    ///
+    /// assert!(lines[0].contains("Ada"));
+    /// ```
+    pub fn generate_dialogue(&self, context: &HashMap<String, String>) -> Vec<String> {
+        let player = context.get("player_name").map_or("stranger", String::as_str);
+        let place = context
+            .get("room_name")
+            .or(context.get("level_name"))
+            .cloned()
+            .unwrap_or_else(|| match context.get("level") {
+                Some(depth) => format!("dungeon level {}", depth),
+                None => "these halls".to_string(),
+            });
+
+        let mut lines = vec![format!("Well met, {}. Few find their way to {}.", player, place)];
+        lines.push(match context.get("npc_role").map(String::as_str) {
+            Some("Shopkeeper") => "Coin or trade, it's all the same to me down here.".to_string(),
+            Some("Priest") => "The light here is faint, but it will shelter you.".to_string(),
+            _ => "Keep your wits about you.".to_string(),
+        });
+        if let Some(class) = context.get("player_class") {
+            lines.push(format!("A {} has a better chance than most.", class.to_lowercase()));
+        }
+        if let Some(kill) = context.get("notable_kill") {
+            lines.push(format!("Word travels fast down here. They say you cut down a {}.", kill));
+        }
+        if let Some(quest) = context.get("player_quest") {
+            lines.push(format!("You seek \"{}\"? Then go, and go quickly.", quest));
+        }
+        lines
+    }
+
+    /// Names the deity the altars of a floor belong to, from the
+    /// [`quest_context`](Self::quest_context) of the floor.
+    ///
+    /// Until a model is connected the deity is picked from [`DEITY_NAMES`] by
+    /// depth and watches over the floor by name, so the same floor always
+    /// yields the same deity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Level, LldmClient};
+    ///
+    /// let mut level = Level::new(1, 10, 10);
+    /// level.name = Some("the Sunken Halls".to_string());
+    /// let client = LldmClient::new();
+    /// let deity = client.generate_deity(&client.quest_context(&level, None));
+    ///
+    /// assert_eq!(deity.title(), "Orun, keeper of the Sunken Halls");
+    ///
+    /// let deep = Level::new(21, 10, 10);
+    /// let deity = client.generate_deity(&client.quest_context(&deep, None));
+    /// assert!(deity.title().ends_with("keeper of the 22nd deep"));
+    /// ```
+    pub fn generate_deity(&self, context: &HashMap<String, String>) -> Deity {
+        let depth: usize = context.get("level").and_then(|level| level.parse().ok()).unwrap_or(1);
+        let name = DEITY_NAMES[(depth.max(1) - 1) % DEITY_NAMES.len()];
+        let epithet = match context.get("level_name") {
+            Some(place) => format!("keeper of {}", place),
+            None => format!("keeper of the {} deep", ordinal(depth)),
+        };
+        Deity::new(name, epithet)
+    }
+
+    /// Gathers what the LLDM is told about a unique item to name it: the
+    /// floor it lies on and the kind of gear it is, but none of its numbers.
+    pub fn unique_context(&self, level: &Level, item: &Item) -> HashMap<String, String> {
+        let room = level.rooms.iter().find(|room| room.contains(item.position));
+        let mut context = self.quest_context(level, room);
+        if let Some(noun) = item.metadata.get(UNIQUE_KEY) {
+            context.insert("item_kind".to_string(), noun.clone());
+        }
+        context
+    }
+
+    /// Names and describes a unique item, from its
+    /// [`unique_context`](Self::unique_context).
+    ///
+    /// Until a model is connected the words are filled in from fixed phrasing,
+    /// so the same context always yields the same name. Whatever comes back
+    /// is checked with [`UniqueFlavor::is_valid`] before it is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use thatch::LldmClient;
+    ///
+    /// let context = HashMap::from([
+    ///     ("item_kind".to_string(), "Blade".to_string()),
+    ///     ("level_name".to_string(), "the Sunken Halls".to_string()),
+    /// ]);
+    /// let flavor = LldmClient::new().generate_unique_flavor(&context);
+    ///
+    /// assert_eq!(flavor.name, "Blade of the Sunken Halls");
+    /// assert!(flavor.is_valid());
+    /// ```
+    pub fn generate_unique_flavor(&self, context: &HashMap<String, String>) -> UniqueFlavor {
+        let noun = context.get("item_kind").map_or("Relic", String::as_str);
+        let place = context
+            .get("level_name")
+            .cloned()
+            .unwrap_or_else(|| match context.get("level").and_then(|level| level.parse().ok()) {
+                Some(depth) => format!("the {} Deep", capitalize(&ordinal(depth))),
+                None => "the Deep".to_string(),
+            });
+        let room = match (context.get("room_name"), context.get("room_type")) {
+            (Some(name), _) => name.clone(),
+            (None, Some(kind)) => format!("the {}", kind),
+            (None, None) => "the dark".to_string(),
+        };
+        let lore = format!("Lost in {} long ago, it has waited for a worthy hand.", room);
+        UniqueFlavor {
+            name: format!("{} of {}", noun, place),
+            lore,
+        }
+    }
+
+    /// Gathers what the LLDM is told about a floor to theme it, before any of
+    /// the floor is generated: how deep it lies, how deep the dungeon goes,
+    /// and the stone it is carved from.
+    pub fn floor_theme_context(
+        &self,
+        floor_id: u32,
+        config: &GenerationConfig,
+    ) -> HashMap<String, String> {
+        let palette = config.depth_table.profile_for(floor_id).palette;
+        HashMap::from([
+            ("level".to_string(), (floor_id + 1).to_string()),
+            ("floors".to_string(), config.shape.floor_count.to_string()),
+            ("palette".to_string(), format!("{:?}", palette).to_lowercase()),
+            ("world_seed".to_string(), config.seed.to_string()),
+        ])
+    }
+
+    /// Names the theme of a floor, such as "the flooded archives", from its
+    /// [`floor_theme_context`](Self::floor_theme_context).
+    ///
+    /// Until a model is connected the theme is picked from
+    /// [`FLOOR_THEME_NAMES`] by depth and world seed, so the same floor always
+    /// yields the same theme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{GenerationConfig, LldmClient, FLOOR_THEME_NAMES};
+    ///
+    /// let client = LldmClient::new();
+    /// let config = GenerationConfig::new(0);
+    /// let theme = client.generate_floor_theme(&client.floor_theme_context(1, &config));
+    ///
+    /// assert_eq!(theme, FLOOR_THEME_NAMES[1]);
+    /// ```
+    pub fn generate_floor_theme(&self, context: &HashMap<String, String>) -> String {
+        let depth: u64 = context.get("level").and_then(|level| level.parse().ok()).unwrap_or(1);
+        let seed: u64 = context.get("world_seed").and_then(|seed| seed.parse().ok()).unwrap_or(0);
+        let index = seed.wrapping_add(depth.max(1) - 1) % FLOOR_THEME_NAMES.len() as u64;
+        FLOOR_THEME_NAMES[index as usize].to_string()
+    }
+}
+
+/// Capitalizes the first letter of a word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Writes a number as an ordinal, spelled out up to ten, such as "third"
+/// or "22nd".
+fn ordinal(number: usize) -> String {
+    const ORDINALS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth",
+    ];
+    if let Some(word) = number.checked_sub(1).and_then(|index| ORDINALS.get(index)) {
+        return word.to_string();
+    }
+    let suffix = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", number, suffix)
+}
+
+/// Names a kind of room for quest flavor text.
+fn room_kind(room_type: &RoomType) -> &str {
+    match room_type {
+        RoomType::Normal => "chamber",
+        RoomType::Treasure => "treasure vault",
+        RoomType::Boss => "lair",
+        RoomType::Shop => "shop",
+        RoomType::Puzzle => "puzzle room",
+        RoomType::Sanctuary => "sanctuary",
+        RoomType::Library => "library",
+        RoomType::Prison => "prison",
+        RoomType::Throne => "throne room",
+        RoomType::Secret => "secret room",
+        RoomType::LldmGenerated { subtype } => subtype,
+    }
 }
@@ -0,0 +1,493 @@
+//! # Prompt Templates
+//!
+//! The prompts the LLDM is sent for each kind of content, and the checks its
+//! replies must pass.
+//!
+//! A [`PromptRegistry`] holds a [`PromptTemplate`] for each
+//! [`LldmRequest::request_type`](crate::LldmRequest::request_type): room
+//! descriptions, monster flavor, quest hooks and epitaphs. A template's text
+//! names its context in braces, such as `{room_kind}`, filled in from a typed
+//! [`PromptContext`] like [`RoomDescriptionContext`] or from the context of
+//! an [`LldmRequest`]; a prompt missing any of it is never sent. Each kind of
+//! content has a serde struct its reply must parse into, such as
+//! [`RoomDescription`], with every text field a single line no longer than
+//! [`PromptTemplate::max_length`]. [`LldmClient::complete`] asks again when a
+//! reply does not, up to [`PROMPT_ATTEMPTS`] times.
+
+use crate::{LldmClient, LldmRequest, LldmState, LldmTransport, ThatchError, ThatchResult};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Completions asked for before a request for content is given up.
+pub const PROMPT_ATTEMPTS: u32 = 3;
+
+/// Request type of room descriptions.
+pub const ROOM_DESCRIPTION: &str = "room_description";
+
+/// Request type of monster flavor text.
+pub const MONSTER_FLAVOR: &str = "monster_flavor";
+
+/// Request type of quest hooks.
+pub const QUEST_HOOK: &str = "quest_hook";
+
+/// Request type of epitaphs.
+pub const EPITAPH: &str = "epitaph";
+
+/// The prompt for one kind of LLDM content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    /// Request type the template answers
+    pub request_type: String,
+    /// Prompt text, naming its context in braces
+    pub text: String,
+    /// Shape of the JSON reply, appended to the prompt as written
+    pub output_format: String,
+    /// Longest any text field of the reply may be
+    pub max_length: usize,
+}
+
+impl PromptTemplate {
+    /// Creates a template.
+    pub fn new(request_type: &str, text: &str, output_format: &str, max_length: usize) -> Self {
+        Self {
+            request_type: request_type.to_string(),
+            text: text.to_string(),
+            output_format: output_format.to_string(),
+            max_length,
+        }
+    }
+
+    /// Fills in the template from `lookup`, then appends the output format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first placeholder `lookup` has no value
+    /// for, or if a brace is never closed.
+    pub fn render(&self, lookup: impl Fn(&str) -> Option<String>) -> ThatchResult<String> {
+        let mut prompt = String::new();
+        let mut rest = self.text.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').ok_or_else(|| {
+                let request_type = &self.request_type;
+                ThatchError::LldmError(format!("Unclosed brace in the {} prompt", request_type))
+            })?;
+            let key = &rest[start + 1..start + end];
+            let value = lookup(key).ok_or_else(|| {
+                ThatchError::LldmError(format!(
+                    "The {} prompt needs \"{}\" in its context",
+                    self.request_type, key
+                ))
+            })?;
+            prompt.push_str(&rest[..start]);
+            prompt.push_str(&value);
+            rest = &rest[start + end + 1..];
+        }
+        prompt.push_str(rest);
+        prompt.push_str("\n\n");
+        prompt.push_str(&self.output_format);
+        Ok(prompt)
+    }
+
+    /// Parses a completion into `O`, checking every text field.
+    ///
+    /// A completion wrapped in a Markdown code fence is unwrapped first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the completion is not a JSON object of the shape
+    /// of `O`, or a text field is empty, more than one line, or longer than
+    /// [`max_length`](Self::max_length).
+    pub fn parse<O: PromptOutput>(&self, completion: &str) -> ThatchResult<O> {
+        let json = completion.trim();
+        let json = json
+            .strip_prefix("```json")
+            .or_else(|| json.strip_prefix("```"))
+            .and_then(|fenced| fenced.strip_suffix("```"))
+            .unwrap_or(json);
+        let output: O = serde_json::from_str(json.trim()).map_err(|error| {
+            ThatchError::LldmError(format!("Malformed {} reply: {}", self.request_type, error))
+        })?;
+        for text in output.texts() {
+            if text.trim().is_empty() || text.contains('\n') {
+                return Err(ThatchError::LldmError(format!(
+                    "The {} reply has an empty or multi-line field",
+                    self.request_type
+                )));
+            }
+            if text.chars().count() > self.max_length {
+                return Err(ThatchError::LldmError(format!(
+                    "The {} reply runs past {} characters",
+                    self.request_type, self.max_length
+                )));
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Context a prompt is written from, and the reply it expects.
+pub trait PromptContext: Serialize {
+    /// The reply the prompt asks for
+    type Output: PromptOutput;
+
+    /// Request type of the template the context fills in.
+    const REQUEST_TYPE: &'static str;
+}
+
+/// A reply from the LLDM, parsed from JSON.
+pub trait PromptOutput: DeserializeOwned {
+    /// Gets every text field, to be checked before the reply is used.
+    fn texts(&self) -> Vec<&str>;
+}
+
+/// What a room description is written from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoomDescriptionContext {
+    /// Dungeon level, starting at 1
+    pub level: u32,
+    /// Kind of room, such as "library"
+    pub room_kind: String,
+    /// Theme of the floor, if it has one
+    pub level_theme: Option<String>,
+}
+
+/// A room's name and description.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoomDescription {
+    /// Short name of the room
+    pub name: String,
+    /// What the player sees on entering
+    pub description: String,
+}
+
+impl PromptContext for RoomDescriptionContext {
+    type Output = RoomDescription;
+    const REQUEST_TYPE: &'static str = ROOM_DESCRIPTION;
+}
+
+impl PromptOutput for RoomDescription {
+    fn texts(&self) -> Vec<&str> {
+        vec![&self.name, &self.description]
+    }
+}
+
+/// What a monster's flavor text is written from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MonsterFlavorContext {
+    /// Dungeon level, starting at 1
+    pub level: u32,
+    /// Name of the monster
+    pub monster_name: String,
+}
+
+/// How a monster looks and sounds.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonsterFlavor {
+    /// What the player sees of the monster
+    pub description: String,
+    /// What the monster shouts on seeing the player
+    pub battle_cry: String,
+}
+
+impl PromptContext for MonsterFlavorContext {
+    type Output = MonsterFlavor;
+    const REQUEST_TYPE: &'static str = MONSTER_FLAVOR;
+}
+
+impl PromptOutput for MonsterFlavor {
+    fn texts(&self) -> Vec<&str> {
+        vec![&self.description, &self.battle_cry]
+    }
+}
+
+/// What a quest hook is written from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuestHookContext {
+    /// Dungeon level, starting at 1
+    pub level: u32,
+    /// What the quest asks of the player
+    pub objective: String,
+}
+
+/// The title and opening line of a quest.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuestHook {
+    /// Title shown in the quest log
+    pub title: String,
+    /// Line that draws the player in
+    pub hook: String,
+}
+
+impl PromptContext for QuestHookContext {
+    type Output = QuestHook;
+    const REQUEST_TYPE: &'static str = QUEST_HOOK;
+}
+
+impl PromptOutput for QuestHook {
+    fn texts(&self) -> Vec<&str> {
+        vec![&self.title, &self.hook]
+    }
+}
+
+/// What a fallen character's epitaph is written from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EpitaphContext {
+    /// Name of the character
+    pub character_name: String,
+    /// Class of the character
+    pub class: String,
+    /// Deepest dungeon level reached, starting at 1
+    pub depth: u32,
+    /// What killed the character
+    pub cause_of_death: String,
+}
+
+/// The words on a fallen character's gravestone.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Epitaph {
+    /// The epitaph
+    pub epitaph: String,
+}
+
+impl PromptContext for EpitaphContext {
+    type Output = Epitaph;
+    const REQUEST_TYPE: &'static str = EPITAPH;
+}
+
+impl PromptOutput for Epitaph {
+    fn texts(&self) -> Vec<&str> {
+        vec![&self.epitaph]
+    }
+}
+
+/// Prompt templates by request type.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{PromptRegistry, RoomDescriptionContext};
+///
+/// let registry = PromptRegistry::builtin();
+/// let prompt = registry
+///     .render(&RoomDescriptionContext {
+///         level: 3,
+///         room_kind: "library".to_string(),
+///         level_theme: Some("the flooded archives".to_string()),
+///     })
+///     .unwrap();
+/// assert!(prompt.contains("library on dungeon level 3"));
+/// assert!(prompt.contains("\"description\""));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptRegistry {
+    /// Templates keyed by request type
+    #[serde(serialize_with = "crate::sorted_map")]
+    pub templates: HashMap<String, PromptTemplate>,
+}
+
+impl PromptRegistry {
+    /// Creates a registry of the built-in templates.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(PromptTemplate::new(
+            ROOM_DESCRIPTION,
+            "You are the dungeon master of a roguelike. Describe a {room_kind} on dungeon \
+             level {level}, on a floor known as {level_theme}, in one or two sentences.",
+            "Reply with only a JSON object: \
+             {\"name\": \"<a short name for the room>\", \"description\": \"<the description>\"}",
+            300,
+        ));
+        registry.register(PromptTemplate::new(
+            MONSTER_FLAVOR,
+            "You are the dungeon master of a roguelike. A {monster_name} on dungeon level \
+             {level} has seen the player. Describe it in one sentence and give its battle cry.",
+            "Reply with only a JSON object: \
+             {\"description\": \"<the description>\", \"battle_cry\": \"<what it shouts>\"}",
+            200,
+        ));
+        registry.register(PromptTemplate::new(
+            QUEST_HOOK,
+            "You are the dungeon master of a roguelike. Write a quest for dungeon level \
+             {level} whose goal is to {objective}.",
+            "Reply with only a JSON object: \
+             {\"title\": \"<a short title>\", \"hook\": \"<one line to draw the player in>\"}",
+            200,
+        ));
+        registry.register(PromptTemplate::new(
+            EPITAPH,
+            "You are the dungeon master of a roguelike. {character_name} the {class} fell on \
+             dungeon level {depth}, killed by {cause_of_death}. Write their epitaph.",
+            "Reply with only a JSON object: {\"epitaph\": \"<the epitaph>\"}",
+            120,
+        ));
+        registry
+    }
+
+    /// Adds a template, replacing any with the same request type.
+    pub fn register(&mut self, template: PromptTemplate) {
+        self.templates
+            .insert(template.request_type.clone(), template);
+    }
+
+    /// Gets the template for a request type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no template is registered for it.
+    pub fn template(&self, request_type: &str) -> ThatchResult<&PromptTemplate> {
+        self.templates.get(request_type).ok_or_else(|| {
+            ThatchError::LldmError(format!("No prompt template for \"{}\"", request_type))
+        })
+    }
+
+    /// Writes the prompt for a typed context.
+    ///
+    /// Missing optional context is written as "unknown".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context's template is missing or needs a
+    /// field the context does not have.
+    pub fn render<C: PromptContext>(&self, context: &C) -> ThatchResult<String> {
+        let Value::Object(fields) = serde_json::to_value(context)? else {
+            return Err(ThatchError::LldmError(format!(
+                "The {} context is not a struct",
+                C::REQUEST_TYPE
+            )));
+        };
+        self.template(C::REQUEST_TYPE)?
+            .render(|key| match fields.get(key)? {
+                Value::String(text) => Some(text.clone()),
+                Value::Null => Some("unknown".to_string()),
+                value => Some(value.to_string()),
+            })
+    }
+
+    /// Writes the prompt for a queued request, from its context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request type has no template or its context
+    /// is missing a field the template needs.
+    pub fn render_request(&self, request: &LldmRequest) -> ThatchResult<String> {
+        self.template(&request.request_type)?
+            .render(|key| request.context.get(key).cloned())
+    }
+}
+
+impl LldmClient {
+    /// Asks the model for the content a typed context calls for.
+    ///
+    /// Each completion is sent through [`request`](Self::request) and
+    /// checked with [`PromptTemplate::parse`]; a malformed one is counted in
+    /// the [`LldmTelemetry`](crate::LldmTelemetry) and asked for again, up to
+    /// [`PROMPT_ATTEMPTS`] times in all.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prompt cannot be written, a request fails, or
+    /// every completion is malformed.
+    pub fn complete<C: PromptContext>(
+        &self,
+        state: &mut LldmState,
+        transport: &mut dyn LldmTransport,
+        registry: &PromptRegistry,
+        context: &C,
+    ) -> ThatchResult<C::Output> {
+        let template = registry.template(C::REQUEST_TYPE)?;
+        let prompt = registry.render(context)?;
+        let mut last_error = None;
+        for _ in 0..PROMPT_ATTEMPTS {
+            let completion = self.request(state, transport, &prompt)?;
+            match template.parse(&completion) {
+                Ok(output) => return Ok(output),
+                Err(error) => {
+                    state.telemetry.malformed += 1;
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ThatchError::LldmError(format!("No {} reply was asked for", C::REQUEST_TYPE))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LldmFailure, LldmPriority};
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    /// An endpoint that answers from a script and remembers the prompts.
+    struct ScriptedModel {
+        completions: VecDeque<String>,
+        prompts: Vec<String>,
+    }
+
+    impl LldmTransport for ScriptedModel {
+        fn send(&mut self, prompt: &str, _timeout: Duration) -> Result<String, LldmFailure> {
+            self.prompts.push(prompt.to_string());
+            self.completions
+                .pop_front()
+                .ok_or_else(|| LldmFailure::Unavailable("script ran out".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_prompts_are_filled_in_and_malformed_replies_asked_again() {
+        let registry = PromptRegistry::builtin();
+        let context = EpitaphContext {
+            character_name: "Wren".to_string(),
+            class: "Rogue".to_string(),
+            depth: 4,
+            cause_of_death: "a Troll".to_string(),
+        };
+        let mut model = ScriptedModel {
+            completions: VecDeque::from([
+                "The dungeon claimed another.".to_string(),
+                "{\"epitaph\": \"Quick hands.\", \"mood\": \"grim\"}".to_string(),
+                "```json\n{\"epitaph\": \"Quick hands, slow feet.\"}\n```".to_string(),
+            ]),
+            prompts: Vec::new(),
+        };
+        let mut state = LldmState::default();
+        let client = LldmClient::new();
+
+        let epitaph = client
+            .complete(&mut state, &mut model, &registry, &context)
+            .unwrap();
+        assert_eq!(epitaph.epitaph, "Quick hands, slow feet.");
+        assert_eq!(state.telemetry.malformed, 2);
+        assert!(model.prompts[0].contains("Wren the Rogue fell on dungeon level 4"));
+
+        // Too long a reply is rejected on every attempt
+        let rambling = format!("{{\"epitaph\": \"{}\"}}", "a".repeat(121));
+        model.completions = VecDeque::from(vec![rambling; PROMPT_ATTEMPTS as usize]);
+        assert!(client
+            .complete(&mut state, &mut model, &registry, &context)
+            .is_err());
+
+        // A queued request must carry every field its template names
+        let mut request = LldmRequest {
+            id: "1".to_string(),
+            request_type: QUEST_HOOK.to_string(),
+            context: HashMap::from([("level".to_string(), "2".to_string())]),
+            priority: LldmPriority::Normal,
+            created_at: 0,
+        };
+        assert!(registry.render_request(&request).is_err());
+        request
+            .context
+            .insert("objective".to_string(), "slay the Orc".to_string());
+        let prompt = registry.render_request(&request).unwrap();
+        assert!(prompt.contains("level 2 whose goal is to slay the Orc"));
+    }
+}
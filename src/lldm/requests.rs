@@ -0,0 +1,655 @@
+//! # LLDM Requests
+//!
+//! Rate limiting, retries, timeouts and a circuit breaker around calls to a
+//! model.
+//!
+//! [`LldmClient::request`] sends a prompt through an [`LldmTransport`], which
+//! stands between the game and whatever endpoint serves the model, such as
+//! the [`HttpTransport`]. Attempts beyond
+//! [`LldmRequestPolicy::requests_per_minute`] are refused, failed attempts
+//! are retried with exponential backoff, and an attempt that runs past
+//! [`LldmRequestPolicy::timeout_ms`] counts as failed. Once
+//! [`LldmRequestPolicy::breaker_threshold`] requests in a row have failed, the
+//! circuit breaker opens and refuses requests without touching the endpoint
+//! until its cool-down has passed. Every refusal and failure is a
+//! [`ThatchError::LldmError`]. The [`LldmTelemetry`] in the [`LldmState`]
+//! counts all of it.
+//!
+//! Requests wait on the endpoint and sleep through their backoff, so the game
+//! never sends them itself: an [`LldmWorker`] takes the queued
+//! [`LldmRequest`]s and sends them on a thread of its own. A request that
+//! fails leaves the game with the deterministic content it writes when no
+//! model is connected.
+
+use crate::{
+    LldmClient, LldmConfig, LldmRequest, LldmState, PromptRegistry, ThatchError, ThatchResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Milliseconds in the window the rate limit counts attempts over.
+pub const RATE_LIMIT_WINDOW_MS: u64 = 60_000;
+
+/// How requests to the LLDM endpoint are limited, retried and timed out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LldmRequestPolicy {
+    /// Retries after the first attempt before a request fails
+    pub max_retries: u32,
+    /// Wait before the first retry in milliseconds, doubled for each retry after it
+    pub initial_backoff_ms: u64,
+    /// Longest wait between retries in milliseconds
+    pub max_backoff_ms: u64,
+    /// Most attempts sent to the endpoint in any minute
+    pub requests_per_minute: u32,
+    /// Longest an attempt may take in milliseconds
+    pub timeout_ms: u64,
+    /// Failed requests in a row that open the circuit breaker
+    pub breaker_threshold: u32,
+    /// How long the open circuit breaker refuses requests, in milliseconds
+    pub breaker_cooldown_ms: u64,
+}
+
+impl Default for LldmRequestPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff_ms: 250,
+            max_backoff_ms: 4_000,
+            requests_per_minute: 30,
+            timeout_ms: 10_000,
+            breaker_threshold: 3,
+            breaker_cooldown_ms: 60_000,
+        }
+    }
+}
+
+impl LldmRequestPolicy {
+    /// Gets the wait before a retry, counting retries from zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use thatch::LldmRequestPolicy;
+    ///
+    /// let policy = LldmRequestPolicy::default();
+    /// assert_eq!(policy.backoff(0), Duration::from_millis(250));
+    /// assert_eq!(policy.backoff(2), Duration::from_millis(1_000));
+    /// assert_eq!(policy.backoff(10), Duration::from_millis(policy.max_backoff_ms));
+    /// ```
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+        let delay = self.initial_backoff_ms.saturating_mul(factor);
+        Duration::from_millis(delay.min(self.max_backoff_ms))
+    }
+}
+
+/// Counts of what has happened to requests sent to the LLDM.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LldmTelemetry {
+    /// Requests made, whether or not they reached the endpoint
+    pub requests: u64,
+    /// Requests answered by the endpoint
+    pub successes: u64,
+    /// Attempts made after a failed one
+    pub retries: u64,
+    /// Attempts that ran past the timeout
+    pub timeouts: u64,
+    /// Requests that failed on every attempt
+    pub failures: u64,
+    /// Requests refused by the rate limit
+    pub rate_limited: u64,
+    /// Requests refused while the circuit breaker was open
+    pub short_circuited: u64,
+    /// Times the circuit breaker has opened
+    pub breaker_trips: u64,
+    /// Requests answered with fallback content instead
+    pub fallbacks: u64,
+    /// Completions rejected for not matching their prompt's output
+    #[serde(default)]
+    pub malformed: u64,
+}
+
+impl LldmTelemetry {
+    /// Adds another set of counts to these.
+    fn add(&mut self, other: &Self) {
+        self.requests += other.requests;
+        self.successes += other.successes;
+        self.retries += other.retries;
+        self.timeouts += other.timeouts;
+        self.failures += other.failures;
+        self.rate_limited += other.rate_limited;
+        self.short_circuited += other.short_circuited;
+        self.breaker_trips += other.breaker_trips;
+        self.fallbacks += other.fallbacks;
+        self.malformed += other.malformed;
+    }
+}
+
+/// The rate limit window and circuit breaker of the LLDM endpoint.
+///
+/// Only lasts as long as the process; a loaded game starts with the breaker
+/// closed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LldmRequestGate {
+    /// When each attempt in the current window was sent, in milliseconds
+    sent_ms: VecDeque<u64>,
+    /// Requests that have failed since the last success
+    consecutive_failures: u32,
+    /// When the open circuit breaker closes again, in milliseconds
+    open_until_ms: Option<u64>,
+}
+
+impl LldmRequestGate {
+    /// Returns true if the circuit breaker is refusing requests at `now_ms`.
+    pub fn is_open(&self, now_ms: u64) -> bool {
+        self.open_until_ms.is_some_and(|until| now_ms < until)
+    }
+
+    /// Records an attempt at `now_ms`, returning false without recording it
+    /// if `per_minute` attempts were already sent in the last minute.
+    fn admit(&mut self, now_ms: u64, per_minute: u32) -> bool {
+        while self
+            .sent_ms
+            .front()
+            .is_some_and(|&sent| now_ms.saturating_sub(sent) >= RATE_LIMIT_WINDOW_MS)
+        {
+            self.sent_ms.pop_front();
+        }
+        if self.sent_ms.len() >= per_minute as usize {
+            return false;
+        }
+        self.sent_ms.push_back(now_ms);
+        true
+    }
+
+    /// Closes the circuit breaker after a request succeeds.
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.open_until_ms = None;
+    }
+
+    /// Counts a failed request, returning true if it opened the breaker.
+    fn record_failure(&mut self, now_ms: u64, policy: &LldmRequestPolicy) -> bool {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures < policy.breaker_threshold {
+            return false;
+        }
+        self.open_until_ms = Some(now_ms + policy.breaker_cooldown_ms);
+        true
+    }
+}
+
+/// Why an attempt to reach the LLDM endpoint failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LldmFailure {
+    /// No reply came before the timeout
+    TimedOut,
+    /// The endpoint could not be reached or refused the request
+    Unavailable(String),
+}
+
+/// Carries prompts to whatever endpoint serves the model.
+pub trait LldmTransport {
+    /// Sends a prompt and waits up to `timeout` for the reply.
+    fn send(&mut self, prompt: &str, timeout: Duration) -> Result<String, LldmFailure>;
+
+    /// Gets the time requests are measured against, in milliseconds.
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64)
+    }
+
+    /// Waits out the backoff before a retry.
+    ///
+    /// Sleeps by default, which only ever holds up an [`LldmWorker`]'s thread.
+    fn wait(&mut self, delay: Duration) {
+        std::thread::sleep(delay);
+    }
+}
+
+impl LldmClient {
+    /// Sends a prompt to the model, within the limits of the
+    /// [`LldmRequestPolicy`] in `state`'s config.
+    ///
+    /// Blocks until the reply comes or every retry has failed, so the game
+    /// leaves this to an [`LldmWorker`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ThatchError::LldmError`] if the circuit breaker is open, the
+    /// rate limit is reached, or every attempt fails or times out.
+    pub fn request(
+        &self,
+        state: &mut LldmState,
+        transport: &mut dyn LldmTransport,
+        prompt: &str,
+    ) -> ThatchResult<String> {
+        let policy = state.config.requests.clone();
+        let telemetry = &mut state.telemetry;
+        let gate = &mut state.gate;
+        telemetry.requests += 1;
+        if gate.is_open(transport.now_ms()) {
+            telemetry.short_circuited += 1;
+            return Err(ThatchError::LldmError(
+                "The LLDM endpoint is down; its circuit breaker is open".to_string(),
+            ));
+        }
+
+        let timeout = Duration::from_millis(policy.timeout_ms);
+        let mut retry = 0;
+        loop {
+            let started = transport.now_ms();
+            if !gate.admit(started, policy.requests_per_minute) {
+                telemetry.rate_limited += 1;
+                return Err(ThatchError::LldmError(format!(
+                    "More than {} LLDM requests a minute",
+                    policy.requests_per_minute
+                )));
+            }
+            let reply = transport.send(prompt, timeout);
+            let elapsed = transport.now_ms().saturating_sub(started);
+            let reason = match reply {
+                Ok(reply) if elapsed <= policy.timeout_ms => {
+                    gate.record_success();
+                    telemetry.successes += 1;
+                    return Ok(reply);
+                }
+                Ok(_) | Err(LldmFailure::TimedOut) => {
+                    telemetry.timeouts += 1;
+                    format!("timed out after {} ms", policy.timeout_ms)
+                }
+                Err(LldmFailure::Unavailable(reason)) => reason,
+            };
+
+            if retry >= policy.max_retries {
+                telemetry.failures += 1;
+                if gate.record_failure(transport.now_ms(), &policy) {
+                    telemetry.breaker_trips += 1;
+                }
+                return Err(ThatchError::LldmError(format!(
+                    "LLDM request failed after {} attempts: {}",
+                    retry + 1,
+                    reason
+                )));
+            }
+            transport.wait(policy.backoff(retry));
+            retry += 1;
+            telemetry.retries += 1;
+        }
+    }
+}
+
+/// Posts prompts to a plain `http://` endpoint, taking the body of its reply
+/// as the completion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpTransport {
+    /// Host and port connected to
+    address: String,
+    /// Path prompts are posted to
+    path: String,
+}
+
+impl HttpTransport {
+    /// Creates a transport for an endpoint such as `http://localhost:8080/complete`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ThatchError::LldmError`] if the endpoint is not an `http://` URL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::HttpTransport;
+    ///
+    /// assert!(HttpTransport::new("http://localhost:8080/complete").is_ok());
+    /// assert!(HttpTransport::new("https://example.com").is_err());
+    /// ```
+    pub fn new(endpoint: &str) -> ThatchResult<Self> {
+        let rest = endpoint.strip_prefix("http://").unwrap_or_default();
+        let (authority, path) = rest.find('/').map_or((rest, "/"), |at| rest.split_at(at));
+        if authority.is_empty() {
+            return Err(ThatchError::LldmError(format!(
+                "The LLDM endpoint \"{}\" is not an http:// URL",
+                endpoint
+            )));
+        }
+        let address = if authority.contains(':') {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+        Ok(Self {
+            address,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Maps an I/O error talking to the endpoint to why the attempt failed.
+fn io_failure(error: std::io::Error) -> LldmFailure {
+    match error.kind() {
+        ErrorKind::TimedOut | ErrorKind::WouldBlock => LldmFailure::TimedOut,
+        _ => LldmFailure::Unavailable(error.to_string()),
+    }
+}
+
+impl LldmTransport for HttpTransport {
+    fn send(&mut self, prompt: &str, timeout: Duration) -> Result<String, LldmFailure> {
+        let address = self
+            .address
+            .to_socket_addrs()
+            .map_err(io_failure)?
+            .next()
+            .ok_or_else(|| LldmFailure::Unavailable(format!("{} has no address", self.address)))?;
+        let mut stream = TcpStream::connect_timeout(&address, timeout).map_err(io_failure)?;
+        stream.set_read_timeout(Some(timeout)).map_err(io_failure)?;
+        stream
+            .set_write_timeout(Some(timeout))
+            .map_err(io_failure)?;
+        write!(
+            stream,
+            "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\
+             Content-Length: {}\r\n\r\n{}",
+            self.path,
+            self.address,
+            prompt.len(),
+            prompt
+        )
+        .map_err(io_failure)?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(io_failure)?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| LldmFailure::Unavailable("The reply has no body".to_string()))?;
+        match head.split_whitespace().nth(1) {
+            Some("200") => Ok(body.to_string()),
+            status => Err(LldmFailure::Unavailable(format!(
+                "The endpoint answered with status {}",
+                status.unwrap_or("nothing")
+            ))),
+        }
+    }
+}
+
+/// What the [`LldmWorker`] made of one request.
+#[derive(Debug)]
+struct LldmReply {
+    /// ID of the request answered
+    id: String,
+    /// The completion, or `None` if the request failed
+    completion: Option<String>,
+    /// What happened to the request
+    telemetry: LldmTelemetry,
+}
+
+/// Sends queued [`LldmRequest`]s to the model on a background thread.
+///
+/// Each request is written with the built-in [`PromptRegistry`] and sent
+/// through [`LldmClient::request`], so waiting on the endpoint and backing
+/// off between retries never holds up a frame. Completions are cached under
+/// their request's ID.
+#[derive(Debug)]
+pub struct LldmWorker {
+    /// Requests for the background thread
+    requests: Sender<LldmRequest>,
+    /// Replies from the background thread, in order
+    replies: Receiver<LldmReply>,
+}
+
+impl LldmWorker {
+    /// Starts sending requests through `transport`, within the limits of
+    /// `config`.
+    pub fn spawn(mut transport: Box<dyn LldmTransport + Send>, config: LldmConfig) -> Self {
+        let (requests, queued) = mpsc::channel::<LldmRequest>();
+        let (sender, replies) = mpsc::channel();
+
+        thread::spawn(move || {
+            let client = LldmClient::new();
+            let registry = PromptRegistry::builtin();
+            let mut state = LldmState {
+                config,
+                ..LldmState::default()
+            };
+            for request in queued {
+                let completion = registry
+                    .render_request(&request)
+                    .and_then(|prompt| client.request(&mut state, transport.as_mut(), &prompt));
+                if completion.is_err() {
+                    state.telemetry.fallbacks += 1;
+                }
+                let reply = LldmReply {
+                    id: request.id,
+                    completion: completion.ok(),
+                    telemetry: std::mem::take(&mut state.telemetry),
+                };
+                // Stop once nobody is listening
+                if sender.send(reply).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self { requests, replies }
+    }
+
+    /// Starts a worker for a game's LLDM, if it is enabled and has an endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the endpoint is not an `http://` URL.
+    pub fn for_game(state: &LldmState) -> ThatchResult<Option<Self>> {
+        let Some(endpoint) = state.config.endpoint.as_deref().filter(|_| state.enabled) else {
+            return Ok(None);
+        };
+        let transport = HttpTransport::new(endpoint)?;
+        Ok(Some(Self::spawn(Box::new(transport), state.config.clone())))
+    }
+
+    /// Hands `state`'s pending requests to the background thread and caches
+    /// the completions that have come back since the last call.
+    ///
+    /// Never blocks. Returns the number of completions cached.
+    pub fn poll(&self, state: &mut LldmState) -> usize {
+        for request in state.pending_requests.drain(..) {
+            // The thread only stops once this worker is dropped
+            let _ = self.requests.send(request);
+        }
+        let mut cached = 0;
+        for reply in self.replies.try_iter() {
+            state.telemetry.add(&reply.telemetry);
+            if let Some(completion) = reply.completion {
+                state.content_cache.insert(reply.id, completion);
+                cached += 1;
+            }
+        }
+        cached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+
+    /// An endpoint that replies from a script on a clock that only moves when
+    /// told to.
+    struct ScriptedTransport {
+        replies: VecDeque<Result<String, LldmFailure>>,
+        latency_ms: u64,
+        clock_ms: u64,
+        waits: Vec<Duration>,
+    }
+
+    impl LldmTransport for ScriptedTransport {
+        fn send(&mut self, _prompt: &str, _timeout: Duration) -> Result<String, LldmFailure> {
+            self.clock_ms += self.latency_ms;
+            let failure = Err(LldmFailure::Unavailable("connection refused".to_string()));
+            self.replies.pop_front().unwrap_or(failure)
+        }
+
+        fn now_ms(&self) -> u64 {
+            self.clock_ms
+        }
+
+        fn wait(&mut self, delay: Duration) {
+            self.clock_ms += delay.as_millis() as u64;
+            self.waits.push(delay);
+        }
+    }
+
+    #[test]
+    fn test_requests_retry_then_stop_once_the_breaker_opens() {
+        let client = LldmClient::new();
+        let mut state = LldmState::default();
+        let policy = state.config.requests.clone();
+        let mut transport = ScriptedTransport {
+            replies: VecDeque::from([
+                Err(LldmFailure::TimedOut),
+                Ok("The walls weep.".to_string()),
+            ]),
+            latency_ms: 10,
+            clock_ms: 0,
+            waits: Vec::new(),
+        };
+
+        // A timed out attempt is retried after backing off
+        let reply = client
+            .request(&mut state, &mut transport, "describe")
+            .unwrap();
+        assert_eq!(reply, "The walls weep.");
+        assert_eq!(transport.waits, [policy.backoff(0)]);
+        assert_eq!((state.telemetry.timeouts, state.telemetry.retries), (1, 1));
+
+        // A reply slower than the timeout counts as timed out
+        transport.latency_ms = policy.timeout_ms + 1;
+        transport.replies = VecDeque::from(vec![Ok("Too late.".to_string()); 3]);
+        let error = client
+            .request(&mut state, &mut transport, "describe")
+            .unwrap_err();
+        assert!(matches!(error, ThatchError::LldmError(_)));
+        assert_eq!(state.telemetry.timeouts, 4);
+
+        // Enough failures in a row open the breaker, which then refuses requests unsent
+        transport.latency_ms = 10;
+        for _ in 1..policy.breaker_threshold {
+            assert!(client
+                .request(&mut state, &mut transport, "describe")
+                .is_err());
+        }
+        assert_eq!(state.telemetry.breaker_trips, 1);
+        let clock_ms = transport.clock_ms;
+        assert!(client
+            .request(&mut state, &mut transport, "describe")
+            .is_err());
+        assert_eq!(transport.clock_ms, clock_ms);
+        assert_eq!(state.telemetry.short_circuited, 1);
+        assert_eq!(state.telemetry.failures, policy.breaker_threshold as u64);
+
+        // After the cool-down one good reply closes it again
+        transport.clock_ms += policy.breaker_cooldown_ms;
+        transport.replies = VecDeque::from([Ok("Light returns.".to_string())]);
+        let reply = client
+            .request(&mut state, &mut transport, "describe")
+            .unwrap();
+        assert_eq!(reply, "Light returns.");
+        assert!(!state.gate.is_open(transport.now_ms()));
+
+        // Attempts beyond the per-minute limit are refused without being sent
+        state.config.requests.requests_per_minute = 1;
+        transport.clock_ms += RATE_LIMIT_WINDOW_MS;
+        transport.replies = VecDeque::from([Ok("One.".to_string()), Ok("Two.".to_string())]);
+        assert!(client.request(&mut state, &mut transport, "first").is_ok());
+        assert!(client
+            .request(&mut state, &mut transport, "second")
+            .is_err());
+        assert_eq!(state.telemetry.rate_limited, 1);
+        assert_eq!(transport.replies.len(), 1);
+    }
+
+    #[test]
+    fn test_worker_caches_completions_from_an_http_endpoint() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/complete", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let mut reader = std::io::BufReader::new(stream);
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                assert!(
+                    reader.read_line(&mut line).unwrap() > 0,
+                    "headers cut short"
+                );
+                if let Some(length) = line.strip_prefix("Content-Length: ") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                head.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.0 200 OK\r\n\r\n{\"epitaph\": \"Here lies Ada.\"}")
+                .unwrap();
+            (head, String::from_utf8(body).unwrap())
+        });
+
+        let mut state = LldmState {
+            enabled: true,
+            ..LldmState::default()
+        };
+        state.config.endpoint = Some(endpoint);
+        let worker = LldmWorker::for_game(&state).unwrap().unwrap();
+        state.pending_requests.push(LldmRequest {
+            id: "epitaph".to_string(),
+            request_type: crate::EPITAPH.to_string(),
+            context: [
+                ("character_name", "Ada"),
+                ("class", "Wizard"),
+                ("depth", "4"),
+                ("cause_of_death", "a Troll"),
+            ]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+            priority: crate::LldmPriority::Normal,
+            created_at: 0,
+        });
+
+        // Polling hands the request over without waiting for the reply
+        worker.poll(&mut state);
+        assert!(state.pending_requests.is_empty());
+        let (head, body) = server.join().unwrap();
+        assert!(head.starts_with("POST /complete HTTP/1.0"));
+        assert!(body.contains("Ada the Wizard fell on dungeon level 4"));
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while state.telemetry.successes + state.telemetry.fallbacks == 0 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "no reply from the worker"
+            );
+            worker.poll(&mut state);
+            thread::yield_now();
+        }
+        assert_eq!(
+            state.content_cache["epitaph"],
+            "{\"epitaph\": \"Here lies Ada.\"}"
+        );
+        assert_eq!(state.telemetry.successes, 1);
+    }
+}
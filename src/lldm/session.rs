@@ -0,0 +1,277 @@
+//! # Headless Session
+//!
+//! Runs a game without a window for remote clients.
+//!
+//! A [`HeadlessSession`] owns a [`GameState`] and answers small JSON-RPC style
+//! requests. Each request names a `method`, optionally carries `params`, and
+//! may include an `id` that is copied into the response:
+//!
+//! ```json
+//! {"id":1,"method":"snapshot"}
+//! {"id":2,"method":"act","params":{"Wait":{"actor":"…","metadata":{}}}}
+//! {"id":3,"method":"subscribe"}
+//! ```
+//!
+//! | Method      | Params               | Result                                        |
+//! |-------------|----------------------|-----------------------------------------------|
+//! | `snapshot`  | none                 | the [`LlmSnapshot`] of the current state      |
+//! | `act`       | a [`ConcreteAction`] | every event the action caused                 |
+//! | `subscribe` | none                 | `true`; events are then pushed as they happen |
+//! | `get_*`     | a query              | the view asked for; see [`QUERY_TOOLS`]       |
+//!
+//! Successful responses carry `result` and failed ones carry `error` with a
+//! message and `code` with the kind of failure, such as `move_blocked`, so
+//! clients need not parse the message. A blocked move also carries `data`
+//! with the position and what is in the way. Subscribers receive
+//! `{"method":"event","params":…}` notifications holding the same
+//! [`EventLogEntry`] objects the event log writes, for actions taken by every
+//! client. The MCP and WebSocket servers are both transports
+//! around a [`SessionManager`](crate::SessionManager) of these sessions.
+
+use crate::{
+    answer_query, ConcreteAction, EventLogEntry, GameEvent, GameState, GenerationConfig,
+    LlmSnapshot, ThatchError, ThatchResult, DEFAULT_SNAPSHOT_TOKEN_BUDGET,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// A request from a remote client.
+#[derive(Debug, Clone, Deserialize)]
+struct SessionRequest {
+    /// Client-chosen id echoed back in the response
+    #[serde(default)]
+    id: Value,
+    /// Name of the method to call
+    method: String,
+    /// Method arguments
+    #[serde(default)]
+    params: Value,
+}
+
+/// The session's answer to one request.
+#[derive(Debug)]
+pub struct SessionReply {
+    /// Response to send back to the client
+    pub response: Value,
+    /// Event notifications for the client, if it just subscribed
+    pub subscription: Option<Receiver<Value>>,
+}
+
+/// A game played entirely through requests.
+#[derive(Debug)]
+pub struct HeadlessSession {
+    /// The game being played
+    pub game_state: GameState,
+    /// Channels of subscribed clients
+    subscribers: Vec<Sender<Value>>,
+}
+
+impl HeadlessSession {
+    /// Creates a session around a game that already has a player.
+    pub fn new(game_state: GameState) -> Self {
+        Self {
+            game_state,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Generates a dungeon from `config` and starts a session with a player
+    /// named `player_name` at its spawn point.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dungeon cannot be generated.
+    pub fn start(config: GenerationConfig, player_name: &str) -> ThatchResult<Self> {
+        let mut game_state = GameState::new_with_generation_config(config)?;
+        let spawn = game_state
+            .world
+            .current_level()
+            .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?
+            .player_spawn;
+        game_state.initialize_player(player_name.to_string(), spawn)?;
+        game_state.update_player_visibility(spawn)?;
+        Ok(Self::new(game_state))
+    }
+
+    /// Gets a snapshot of the current game state.
+    pub fn snapshot(&self) -> LlmSnapshot {
+        self.game_state
+            .snapshot_for_llm(DEFAULT_SNAPSHOT_TOKEN_BUDGET)
+    }
+
+    /// Opens a channel that receives every event notification from now on.
+    pub fn subscribe(&mut self) -> Receiver<Value> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Executes a player action and ends the turn.
+    ///
+    /// Events are processed the same way the interactive game processes them,
+    /// including events raised in response to others.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the game is over, the action is not the player's,
+    /// or the action fails.
+    pub fn submit(&mut self, action: &ConcreteAction) -> ThatchResult<Vec<GameEvent>> {
+        if self.game_state.is_game_ended() {
+            return Err(ThatchError::InvalidAction("The game is over".to_string()));
+        }
+        if Some(action.actor()) != self.game_state.player_id {
+            return Err(ThatchError::InvalidAction(
+                "Remote actions must be taken by the player".to_string(),
+            ));
+        }
+
+        let events = action.execute(&mut self.game_state)?;
+        self.notify(&EventLogEntry::Action {
+            turn: self.game_state.turn_number,
+            action,
+        });
+        let mut processed = self.process_events(events)?;
+        let turn_events = self.game_state.advance_turn()?;
+        self.notify(&EventLogEntry::Turn {
+            turn: self.game_state.turn_number,
+        });
+        processed.extend(self.process_events(turn_events)?);
+        Ok(processed)
+    }
+
+    /// Answers one JSON request.
+    ///
+    /// Malformed requests and failed calls produce an error response rather
+    /// than an `Err`, so a transport can always send the reply.
+    pub fn handle_request(&mut self, request: &str) -> SessionReply {
+        let request: SessionRequest = match serde_json::from_str(request) {
+            Ok(request) => request,
+            Err(e) => {
+                return SessionReply {
+                    response: error_response(Value::Null, &ThatchError::from(e)),
+                    subscription: None,
+                }
+            }
+        };
+
+        let mut subscription = None;
+        let result = match request.method.as_str() {
+            "snapshot" => serde_json::to_value(self.snapshot()).map_err(ThatchError::from),
+            "act" => serde_json::from_value::<ConcreteAction>(request.params)
+                .map_err(ThatchError::from)
+                .and_then(|action| self.submit(&action))
+                .and_then(|events| serde_json::to_value(events).map_err(ThatchError::from)),
+            "subscribe" => {
+                subscription = Some(self.subscribe());
+                Ok(Value::Bool(true))
+            }
+            method => answer_query(&self.game_state, method, request.params).unwrap_or_else(|| {
+                Err(ThatchError::InvalidAction(format!(
+                    "Unknown method '{}'",
+                    method
+                )))
+            }),
+        };
+
+        let response = match result {
+            Ok(result) => json!({ "id": request.id, "result": result }),
+            Err(e) => error_response(request.id, &e),
+        };
+        SessionReply {
+            response,
+            subscription,
+        }
+    }
+
+    /// Processes events and their responses, returning everything that happened.
+    fn process_events(&mut self, events: Vec<GameEvent>) -> ThatchResult<Vec<GameEvent>> {
+        let mut queue: VecDeque<_> = events.into();
+        let mut processed = Vec::new();
+        while let Some(event) = queue.pop_front() {
+            self.notify(&EventLogEntry::Event {
+                turn: self.game_state.turn_number,
+                event: &event,
+            });
+            queue.extend(self.game_state.process_event(&event)?);
+            processed.push(event);
+        }
+        Ok(processed)
+    }
+
+    /// Sends a notification to every subscriber, forgetting disconnected ones.
+    fn notify(&mut self, entry: &EventLogEntry) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+        let Ok(params) = serde_json::to_value(entry) else {
+            return;
+        };
+        let notification = json!({ "method": "event", "params": params });
+        self.subscribers
+            .retain(|subscriber| subscriber.send(notification.clone()).is_ok());
+    }
+}
+
+/// Builds the response to a failed request.
+pub(crate) fn error_response(id: Value, error: &ThatchError) -> Value {
+    let mut response = json!({ "id": id, "error": error.to_string(), "code": error.code() });
+    if let ThatchError::MoveBlocked { position, by } = error {
+        response["data"] = json!({ "position": position, "by": by });
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_entity_id, Direction, MoveAction, PlayerCharacter, Position, WaitAction};
+
+    #[test]
+    fn test_requests_act_for_the_player_and_notify_subscribers() {
+        let mut game_state = GameState::new(7);
+        let player = PlayerCharacter::new("Remote".to_string(), Position::new(5, 5));
+        let player_id = game_state.add_entity(player.into()).unwrap();
+        game_state.set_player_id(player_id);
+        let mut session = HeadlessSession::new(game_state);
+
+        let reply = session.handle_request(r#"{"id":1,"method":"subscribe"}"#);
+        let events = reply.subscription.unwrap();
+        assert_eq!(reply.response["result"], true);
+
+        let wait = ConcreteAction::Wait(WaitAction::new(player_id));
+        let request = json!({ "id": 2, "method": "act", "params": wait }).to_string();
+        let reply = session.handle_request(&request);
+        assert_eq!(reply.response["id"], 2);
+        assert!(reply.response["result"].is_array());
+        assert_eq!(session.game_state.turn_number, 1);
+
+        let kinds: Vec<Value> = events
+            .try_iter()
+            .map(|notification| notification["params"]["kind"].clone())
+            .collect();
+        assert_eq!(kinds.first(), Some(&json!("action")));
+        assert!(kinds.contains(&json!("turn")));
+
+        let snapshot = session.handle_request(r#"{"id":3,"method":"snapshot"}"#);
+        assert!(snapshot.response["result"].is_object());
+
+        // Only the player can be controlled, and nonsense is rejected politely
+        let foreign = ConcreteAction::Wait(WaitAction::new(new_entity_id()));
+        let request = json!({ "method": "act", "params": foreign }).to_string();
+        assert!(session.handle_request(&request).response["error"].is_string());
+        assert_eq!(
+            session.handle_request("not json").response["code"],
+            "serialization"
+        );
+
+        // Blocked moves say what is in the way
+        let blocked = ConcreteAction::Move(MoveAction::new(player_id, Direction::North));
+        let request = json!({ "method": "act", "params": blocked }).to_string();
+        let response = session.handle_request(&request).response;
+        assert_eq!(response["code"], "move_blocked");
+        assert_eq!(response["data"]["by"], "terrain");
+        assert!(session.handle_request(r#"{"method":"fly"}"#).response["error"].is_string());
+    }
+}
@@ -0,0 +1,314 @@
+//! # Session Manager
+//!
+//! Many independent games served from one process.
+//!
+//! A [`SessionManager`] keeps [`HeadlessSession`]s by id, so an evaluation
+//! harness can play games side by side: each session has its own seed and
+//! [`GameState`], and sessions lock separately, so a slow turn in one game
+//! never holds up another. Besides every [`HeadlessSession`] method, requests
+//! to the manager can manage the sessions themselves:
+//!
+//! | Method     | Params                             | Result                            |
+//! |------------|------------------------------------|-----------------------------------|
+//! | `create`   | optional `seed` and `player_name`  | `{"session":…,"seed":…}`          |
+//! | `destroy`  | none                               | `true`                            |
+//! | `sessions` | none                               | the ids of every session, sorted  |
+//!
+//! Every other request is answered by the session named in its `session`
+//! field, or by the [`DEFAULT_SESSION_ID`] session if it has none, so clients
+//! of a single game need not know about sessions at all. Responses from a
+//! session carry its id in a `session` field:
+//!
+//! ```json
+//! {"id":1,"method":"create","params":{"seed":42}}
+//! {"id":2,"session":"…","method":"act","params":{"Wait":{"actor":"…","metadata":{}}}}
+//! {"id":3,"session":"…","method":"destroy"}
+//! ```
+//!
+//! [`GameState`]: crate::GameState
+
+use super::session::error_response;
+use crate::{
+    ConcreteAction, GameEvent, GenerationConfig, HeadlessSession, LlmSnapshot, SessionReply,
+    ThatchError, ThatchResult,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Id of the session that answers requests which do not name one.
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+/// Most sessions a manager keeps at once.
+pub const MAX_SESSIONS: usize = 64;
+
+/// Name given to the player of a created session unless one is asked for.
+const DEFAULT_PLAYER_NAME: &str = "Player";
+
+/// A request to the manager; only the fields it routes on are read.
+#[derive(Debug, Clone, Deserialize)]
+struct ManagerRequest {
+    /// Client-chosen id echoed back in the response
+    #[serde(default)]
+    id: Value,
+    /// Name of the method to call
+    method: String,
+    /// Session the request is for
+    #[serde(default)]
+    session: Option<String>,
+    /// Method arguments
+    #[serde(default)]
+    params: Value,
+}
+
+/// Arguments of a `create` request.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct CreateParams {
+    /// World seed, or a random one if unset
+    #[serde(default)]
+    seed: Option<u64>,
+    /// Name of the new game's player
+    #[serde(default)]
+    player_name: Option<String>,
+}
+
+/// Independent games, each played through its own [`HeadlessSession`].
+#[derive(Debug)]
+pub struct SessionManager {
+    /// Settings new games are generated with, apart from the seed
+    config: GenerationConfig,
+    /// Every live session by id
+    sessions: Mutex<HashMap<String, Arc<Mutex<HeadlessSession>>>>,
+}
+
+impl SessionManager {
+    /// Creates a manager with no sessions whose games are generated from
+    /// `config`, each with its own seed.
+    pub fn new(config: GenerationConfig) -> Self {
+        Self {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds an existing session under `id`, replacing any session with that id.
+    pub fn insert(&self, id: impl Into<String>, session: HeadlessSession) {
+        lock(&self.sessions).insert(id.into(), Arc::new(Mutex::new(session)));
+    }
+
+    /// Generates a new game from `seed` and returns the id of its session.
+    ///
+    /// The dungeon is generated before the manager is locked, so other
+    /// sessions keep playing meanwhile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manager already has [`MAX_SESSIONS`] sessions
+    /// or the dungeon cannot be generated.
+    pub fn create(&self, seed: u64, player_name: &str) -> ThatchResult<String> {
+        self.check_capacity()?;
+        let config = GenerationConfig {
+            seed,
+            ..self.config.clone()
+        };
+        let session = HeadlessSession::start(config, player_name)?;
+
+        self.check_capacity()?;
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        self.insert(id.clone(), session);
+        Ok(id)
+    }
+
+    /// Gets the session with `id`, to lock and use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such session.
+    pub fn session(&self, id: &str) -> ThatchResult<Arc<Mutex<HeadlessSession>>> {
+        lock(&self.sessions)
+            .get(id)
+            .cloned()
+            .ok_or_else(|| ThatchError::InvalidAction(format!("There is no session '{}'", id)))
+    }
+
+    /// Takes a player action in the session with `id` and ends its turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such session or the action fails.
+    pub fn step(&self, id: &str, action: &ConcreteAction) -> ThatchResult<Vec<GameEvent>> {
+        let session = self.session(id)?;
+        let mut session = lock(&session);
+        session.submit(action)
+    }
+
+    /// Gets a snapshot of the game in the session with `id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such session.
+    pub fn snapshot(&self, id: &str) -> ThatchResult<LlmSnapshot> {
+        let session = self.session(id)?;
+        let snapshot = lock(&session).snapshot();
+        Ok(snapshot)
+    }
+
+    /// Ends the session with `id`, returning whether there was one.
+    ///
+    /// A client still holding the session finishes its request first.
+    pub fn destroy(&self, id: &str) -> bool {
+        lock(&self.sessions).remove(id).is_some()
+    }
+
+    /// Gets the ids of every session, sorted.
+    pub fn session_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = lock(&self.sessions).keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Answers one JSON request, managing sessions or passing it to the
+    /// session it names.
+    ///
+    /// Like [`HeadlessSession::handle_request`], failures produce an error
+    /// response rather than an `Err`.
+    pub fn handle_request(&self, request: &str) -> SessionReply {
+        let parsed: ManagerRequest = match serde_json::from_str(request) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return SessionReply {
+                    response: error_response(Value::Null, &ThatchError::from(e)),
+                    subscription: None,
+                }
+            }
+        };
+        let id = parsed.session.as_deref().unwrap_or(DEFAULT_SESSION_ID);
+
+        let result = match parsed.method.as_str() {
+            "create" => self.create_from(parsed.params),
+            "sessions" => Ok(json!(self.session_ids())),
+            "destroy" if self.destroy(id) => Ok(json!(true)),
+            "destroy" => Err(ThatchError::InvalidAction(format!(
+                "There is no session '{}'",
+                id
+            ))),
+            _ => match self.session(id) {
+                Ok(session) => {
+                    let mut reply = lock(&session).handle_request(request);
+                    reply.response["session"] = json!(id);
+                    return reply;
+                }
+                Err(e) => Err(e),
+            },
+        };
+
+        let response = match result {
+            Ok(result) => json!({ "id": parsed.id, "result": result }),
+            Err(e) => error_response(parsed.id, &e),
+        };
+        SessionReply {
+            response,
+            subscription: None,
+        }
+    }
+
+    /// Creates a session from the parameters of a `create` request.
+    fn create_from(&self, params: Value) -> ThatchResult<Value> {
+        let params: CreateParams = if params.is_null() {
+            CreateParams::default()
+        } else {
+            serde_json::from_value(params)?
+        };
+        let seed = params.seed.unwrap_or_else(rand::random);
+        let player_name = params.player_name.as_deref().unwrap_or(DEFAULT_PLAYER_NAME);
+        let id = self.create(seed, player_name)?;
+        Ok(json!({ "session": id, "seed": seed }))
+    }
+
+    /// Checks there is room for another session.
+    fn check_capacity(&self) -> ThatchResult<()> {
+        if lock(&self.sessions).len() >= MAX_SESSIONS {
+            return Err(ThatchError::InvalidAction(format!(
+                "No more than {} sessions can run at once",
+                MAX_SESSIONS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Locks a mutex, carrying on if another client's thread panicked.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WaitAction, WorldShape};
+    use std::thread;
+
+    #[test]
+    fn test_sessions_are_created_played_in_parallel_and_destroyed() {
+        let config = GenerationConfig {
+            shape: WorldShape {
+                floor_count: 2,
+                floor_width: 40,
+                floor_height: 30,
+            },
+            ..GenerationConfig::new(0)
+        };
+        let manager = SessionManager::new(config);
+        let ask = |request: Value| manager.handle_request(&request.to_string()).response;
+
+        let created: Vec<Value> = [11, 12]
+            .iter()
+            .map(|seed| ask(json!({ "method": "create", "params": { "seed": seed } })))
+            .collect();
+        let ids: Vec<String> = created
+            .iter()
+            .map(|reply| reply["result"]["session"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(created[1]["result"]["seed"], 12);
+        assert_eq!(manager.session_ids().len(), 2);
+
+        // Each game advances on its own thread without touching the other
+        thread::scope(|scope| {
+            for (turns, id) in ids.iter().enumerate() {
+                let manager = &manager;
+                scope.spawn(move || {
+                    let session = manager.session(id).unwrap();
+                    let player_id = lock(&session).game_state.player_id.unwrap();
+                    for _ in 0..=turns {
+                        let wait = ConcreteAction::Wait(WaitAction::new(player_id));
+                        manager.step(id, &wait).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(manager.snapshot(&ids[0]).unwrap().turn, 1);
+        assert_eq!(manager.snapshot(&ids[1]).unwrap().turn, 2);
+
+        // Requests reach the session they name, and nothing else
+        let stats = ask(json!({ "id": 5, "session": ids[1], "method": "get_stats" }));
+        assert_eq!(
+            (stats["id"].clone(), stats["result"]["turn"].clone()),
+            (json!(5), json!(2))
+        );
+        assert_eq!(stats["session"], ids[1].as_str());
+        assert!(ask(json!({ "method": "snapshot" }))["error"].is_string());
+
+        assert_eq!(
+            ask(json!({ "session": ids[0], "method": "destroy" }))["result"],
+            true
+        );
+        assert!(ask(json!({ "session": ids[0], "method": "snapshot" }))["error"].is_string());
+        assert_eq!(
+            ask(json!({ "method": "sessions" }))["result"],
+            json!([ids[1]])
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! # LLM Snapshots
+//!
+//! Compact, size-bounded views of the game state for language models.
+//!
+//! Both the MCP server and the LLDM narrator describe the world to a model
+//! through [`GameState::snapshot_for_llm`]. A snapshot holds an ASCII map of
+//! the tiles around the player, the marks left on them by earlier fights, the
+//! entities the player can see, player stats, the rooms the player has seen
+//! and where they lead, recent messages, what the LLDM remembers of the run,
+//! and the current objectives. When the serialized snapshot would exceed the
+//! token budget, older messages and memories, the room list, distant entities,
+//! and the outer rings of the map are dropped until it fits.
+
+use crate::{Entity, GameCompletionState, GameState, Level, Position, ThatchError, ThatchResult};
+use serde::{Deserialize, Serialize};
+
+/// Token budget used when a caller does not pick one.
+pub const DEFAULT_SNAPSHOT_TOKEN_BUDGET: usize = 1500;
+
+/// Rough number of JSON characters per model token.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Tiles shown in each direction from the player before truncation.
+pub const SNAPSHOT_MAP_RADIUS: i32 = 10;
+
+/// Smallest map radius truncation will shrink to.
+const MIN_MAP_RADIUS: i32 = 2;
+
+/// Messages kept before truncation starts dropping entities and map rows.
+const MIN_MESSAGES: usize = 3;
+
+/// A compact description of the game for a language model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmSnapshot {
+    /// Current game turn
+    pub turn: u64,
+    /// Dungeon level the player is on, starting at 1
+    pub depth: u32,
+    /// Theme the LLDM gave the player's level, if any
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// World position of the top-left character of `map`
+    pub map_origin: Position,
+    /// ASCII map rows around the player; unexplored tiles are blank
+    pub map: Vec<String>,
+    /// Blood, scorch marks and rubble on the explored tiles of the map, nearest first
+    #[serde(default)]
+    pub aftermath: Vec<String>,
+    /// The player's condition, if there is a player
+    pub player: Option<PlayerSnapshot>,
+    /// Visible entities other than the player, nearest first
+    pub entities: Vec<EntitySnapshot>,
+    /// Rooms the player has seen on this level and the rooms they lead to
+    #[serde(default)]
+    pub rooms: Vec<String>,
+    /// Recent message log entries, oldest first
+    pub messages: Vec<String>,
+    /// What the LLDM remembers of the run, oldest first
+    #[serde(default)]
+    pub memory: Vec<String>,
+    /// What the player is currently trying to do
+    pub objectives: Vec<String>,
+    /// Whether anything was dropped to fit the token budget
+    pub truncated: bool,
+}
+
+/// The player's condition in an [`LlmSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    /// Player name
+    pub name: String,
+    /// Current position
+    pub position: Position,
+    /// Current health
+    pub health: u32,
+    /// Maximum health
+    pub max_health: u32,
+    /// Current mana
+    pub mana: u32,
+    /// Maximum mana
+    pub max_mana: u32,
+    /// Character level
+    pub level: u32,
+    /// Active status effects
+    pub statuses: Vec<String>,
+}
+
+/// An entity the player can see in an [`LlmSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    /// Entity name
+    pub name: String,
+    /// Character the entity is drawn with on the map
+    pub glyph: char,
+    /// Current position
+    pub position: Position,
+    /// Steps from the player, counted along rows and columns
+    pub distance: u32,
+    /// Whether the entity is hostile to the player
+    pub hostile: bool,
+    /// Current health, if the entity has stats
+    pub health: Option<u32>,
+}
+
+impl LlmSnapshot {
+    /// Captures a snapshot of `game_state` that fits in `token_budget` tokens.
+    ///
+    /// If even the most aggressively truncated snapshot is too large it is
+    /// returned anyway, with `truncated` set.
+    pub fn capture(game_state: &GameState, token_budget: usize) -> Self {
+        let center = game_state
+            .player_id
+            .and_then(|id| game_state.get_entity_position(id))
+            .or_else(|| {
+                game_state
+                    .world
+                    .current_level()
+                    .map(|level| level.player_spawn)
+            })
+            .unwrap_or_else(Position::origin);
+
+        let mut radius = SNAPSHOT_MAP_RADIUS;
+        let (map_origin, map) = Self::map_rows(game_state, center, radius);
+        let mut snapshot = Self {
+            turn: game_state.turn_number,
+            depth: game_state.world.current_level_id + 1,
+            theme: game_state
+                .world
+                .current_level()
+                .and_then(|level| level.theme.as_ref())
+                .map(|theme| theme.name.clone()),
+            map_origin,
+            map,
+            aftermath: Self::aftermath(game_state, center, radius),
+            player: Self::player_snapshot(game_state),
+            entities: Self::visible_entities(game_state, center),
+            rooms: Self::known_rooms(game_state),
+            messages: game_state
+                .recent_messages
+                .iter()
+                .map(|message| message.text.clone())
+                .collect(),
+            memory: game_state.lldm_state.memory.lines(),
+            objectives: Self::objectives(game_state),
+            truncated: false,
+        };
+
+        while snapshot.estimated_tokens() > token_budget {
+            snapshot.truncated = true;
+            if snapshot.messages.len() > MIN_MESSAGES {
+                snapshot.messages.remove(0);
+            } else if !snapshot.memory.is_empty() {
+                snapshot.memory.remove(0);
+            } else if !snapshot.rooms.is_empty() {
+                snapshot.rooms.pop();
+            } else if !snapshot.entities.is_empty() {
+                snapshot.entities.pop();
+            } else if radius > MIN_MAP_RADIUS {
+                radius -= 1;
+                let (map_origin, map) = Self::map_rows(game_state, center, radius);
+                snapshot.map_origin = map_origin;
+                snapshot.map = map;
+                snapshot.aftermath = Self::aftermath(game_state, center, radius);
+            } else if !snapshot.messages.is_empty() {
+                snapshot.messages.remove(0);
+            } else {
+                break;
+            }
+        }
+
+        snapshot
+    }
+
+    /// Serializes the snapshot as compact JSON.
+    pub fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string(self).map_err(ThatchError::from)
+    }
+
+    /// Estimates how many model tokens the serialized snapshot uses.
+    pub fn estimated_tokens(&self) -> usize {
+        let chars = self.to_json().map(|json| json.len()).unwrap_or(0);
+        chars.div_ceil(CHARS_PER_TOKEN)
+    }
+
+    /// Draws the explored tiles and visible entities within `radius` of `center`.
+    fn map_rows(game_state: &GameState, center: Position, radius: i32) -> (Position, Vec<String>) {
+        let Some(level) = game_state.world.current_level() else {
+            return (center, Vec::new());
+        };
+
+        let min_x = (center.x - radius).max(0);
+        let min_y = (center.y - radius).max(0);
+        let max_x = (center.x + radius).min(level.width as i32 - 1);
+        let max_y = (center.y + radius).min(level.height as i32 - 1);
+
+        let rows = (min_y..=max_y)
+            .map(|y| {
+                let row: String = (min_x..=max_x)
+                    .map(|x| Self::map_char(game_state, level, Position::new(x, y)))
+                    .collect();
+                row.trim_end().to_string()
+            })
+            .collect();
+
+        (Position::new(min_x, min_y), rows)
+    }
+
+    /// Describes the overlays on explored tiles within `radius` of `center`.
+    fn aftermath(game_state: &GameState, center: Position, radius: i32) -> Vec<String> {
+        let Some(level) = game_state.world.current_level() else {
+            return Vec::new();
+        };
+
+        let mut marks = Vec::new();
+        for y in center.y - radius..=center.y + radius {
+            for x in center.x - radius..=center.x + radius {
+                let position = Position::new(x, y);
+                let Some(tile) = level.get_tile(position).filter(|tile| tile.is_explored()) else {
+                    continue;
+                };
+                if let Some(overlay) = tile.overlay {
+                    marks.push((center.manhattan_distance(position), position, overlay));
+                }
+            }
+        }
+        marks.sort_by_key(|(distance, ..)| *distance);
+        marks
+            .into_iter()
+            .map(|(_, position, overlay)| {
+                format!(
+                    "({}, {}): {}",
+                    position.x,
+                    position.y,
+                    overlay.description()
+                )
+            })
+            .collect()
+    }
+
+    /// Gets the character shown for one map position.
+    fn map_char(game_state: &GameState, level: &Level, position: Position) -> char {
+        let Some(tile) = level.get_tile(position) else {
+            return ' ';
+        };
+        if tile.is_visible() {
+            if let Some(entity) = game_state
+                .get_entity_at_position(position)
+                .and_then(|id| game_state.entities.get(&id))
+            {
+                return entity.as_entity().display_char();
+            }
+        }
+        if tile.is_visible() || tile.is_explored() {
+            tile.tile_type.clone().to_char()
+        } else {
+            ' '
+        }
+    }
+
+    /// Describes the rooms whose centers the player has explored.
+    fn known_rooms(game_state: &GameState) -> Vec<String> {
+        let Some(level) = game_state.world.current_level() else {
+            return Vec::new();
+        };
+        let seen = |room_id: u32| {
+            level
+                .rooms
+                .iter()
+                .find(|room| room.id == room_id)
+                .and_then(|room| level.get_tile(room.center()))
+                .is_some_and(|tile| tile.is_explored())
+        };
+        level.graph.describe(level, seen)
+    }
+
+    /// Describes the player's condition.
+    pub(crate) fn player_snapshot(game_state: &GameState) -> Option<PlayerSnapshot> {
+        let player = game_state.get_player()?;
+        Some(PlayerSnapshot {
+            name: player.name.clone(),
+            position: player.position(),
+            health: player.stats.health,
+            max_health: player.stats.max_health,
+            mana: player.stats.mana,
+            max_mana: player.stats.max_mana,
+            level: player.stats.level,
+            statuses: player
+                .statuses
+                .effects
+                .iter()
+                .map(|effect| effect.kind.display_name().to_string())
+                .collect(),
+        })
+    }
+
+    /// Lists the non-player entities on visible tiles, nearest first.
+    fn visible_entities(game_state: &GameState, center: Position) -> Vec<EntitySnapshot> {
+        let Some(level) = game_state.world.current_level() else {
+            return Vec::new();
+        };
+
+        let mut entities: Vec<EntitySnapshot> = level
+            .entities
+            .iter()
+            .filter(|&&id| Some(id) != game_state.player_id)
+            .filter_map(|id| game_state.entities.get(id))
+            .map(|entity| entity.as_entity())
+            .filter(|entity| {
+                level
+                    .get_tile(entity.position())
+                    .is_some_and(|tile| tile.is_visible())
+            })
+            .map(|entity| EntitySnapshot {
+                name: entity.name().to_string(),
+                glyph: entity.display_char(),
+                position: entity.position(),
+                distance: center.manhattan_distance(entity.position()),
+                hostile: entity.is_hostile(),
+                health: entity.stats().map(|stats| stats.health),
+            })
+            .collect();
+        entities.sort_by_key(|entity| entity.distance);
+        entities
+    }
+
+    /// Works out what the player should be doing next.
+    fn objectives(game_state: &GameState) -> Vec<String> {
+        match game_state.completion_state {
+            GameCompletionState::Playing => {}
+            GameCompletionState::EscapedEarly => {
+                return vec!["The player escaped the dungeon.".to_string()]
+            }
+            GameCompletionState::EscapedWithArtifact => {
+                return vec!["The player escaped with the Amulet.".to_string()]
+            }
+            GameCompletionState::PlayerDied => return vec!["The player has died.".to_string()],
+        }
+
+        let carrying_artifact = game_state.player_has_artifact();
+        let mut objectives = vec![if carrying_artifact {
+            format!(
+                "Carry the Amulet back up to the surface (level {} of {}).",
+                game_state.world.current_level_id + 1,
+                game_state.world.shape.floor_count
+            )
+        } else {
+            format!(
+                "Retrieve the Amulet from the bottom of the dungeon (level {} of {}).",
+                game_state.world.current_level_id + 1,
+                game_state.world.shape.floor_count
+            )
+        }];
+
+        let hostiles = game_state.visible_hostiles().len();
+        if hostiles > 0 {
+            objectives.push(format!("Deal with {} visible hostile(s).", hostiles));
+        }
+
+        if let Some(player) = game_state.get_player() {
+            if player.stats.health * 3 < player.stats.max_health {
+                objectives.push("Recover health before exploring further.".to_string());
+            }
+        }
+
+        if let Some(level) = game_state.world.current_level() {
+            let (stairs, direction) = if carrying_artifact {
+                (level.stairs_up_position, "up")
+            } else {
+                (level.stairs_down_position, "down")
+            };
+            let known_stairs =
+                stairs.filter(|&pos| level.get_tile(pos).is_some_and(|tile| tile.is_explored()));
+            objectives.push(match known_stairs {
+                Some(pos) => format!("Take the stairs {} at ({}, {}).", direction, pos.x, pos.y),
+                None => format!("Find the stairs {}.", direction),
+            });
+        }
+
+        objectives
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_snapshot_describes_player_and_map() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        game_state
+            .initialize_player("Narrated".to_string(), position)
+            .unwrap();
+        game_state.update_player_visibility(position).unwrap();
+
+        let snapshot = game_state.snapshot_for_llm(DEFAULT_SNAPSHOT_TOKEN_BUDGET);
+
+        assert_eq!(snapshot.player.as_ref().unwrap().name, "Narrated");
+        assert_eq!(snapshot.depth, 1);
+        let row = &snapshot.map[(position.y - snapshot.map_origin.y) as usize];
+        assert_eq!(
+            row.chars()
+                .nth((position.x - snapshot.map_origin.x) as usize),
+            Some('@')
+        );
+        assert!(!snapshot.objectives.is_empty());
+        assert!(!snapshot.truncated);
+    }
+
+    #[test]
+    fn test_snapshot_truncates_to_budget() {
+        let mut game_state = GameState::new(12345);
+        let position = Position::new(5, 5);
+        game_state
+            .initialize_player("Verbose".to_string(), position)
+            .unwrap();
+        game_state.update_player_visibility(position).unwrap();
+        for turn in 0..config::RECENT_MESSAGE_LIMIT {
+            game_state
+                .process_event(&crate::GameEvent::Message {
+                    text: format!("Something long and unremarkable happened on turn {}.", turn),
+                    importance: crate::MessageImportance::Normal,
+                    channel: crate::MessageChannel::System,
+                })
+                .unwrap();
+        }
+
+        let full = game_state.snapshot_for_llm(usize::MAX);
+        let budget = full.estimated_tokens() / 2;
+        let truncated = game_state.snapshot_for_llm(budget);
+
+        assert!(!full.truncated);
+        assert!(truncated.truncated);
+        assert!(truncated.estimated_tokens() <= budget);
+        assert!(truncated.messages.len() < full.messages.len());
+        assert_eq!(truncated.messages.last(), full.messages.last());
+    }
+}
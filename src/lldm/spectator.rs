@@ -0,0 +1,92 @@
+//! # Spectator
+//!
+//! Follows a game that remote clients are playing.
+//!
+//! A [`Spectator`] subscribes to a shared [`HeadlessSession`] and, whenever
+//! its notifications show the game moved on, copies the game state out along
+//! with the events that moved it. The window renders that copy, so it never
+//! holds the session while drawing and never acts in the game itself.
+
+use super::sessions::lock;
+use crate::{GameEvent, GameState, HeadlessSession};
+use serde_json::Value;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+
+/// What changed in a spectated game since it was last polled.
+#[derive(Debug, Clone)]
+pub struct SpectatorUpdate {
+    /// Copy of the game as it is now
+    pub game_state: GameState,
+    /// Events that happened since the last update, in order
+    pub events: Vec<GameEvent>,
+}
+
+/// Watches a session driven by other clients.
+#[derive(Debug)]
+pub struct Spectator {
+    /// The game being watched
+    session: Arc<Mutex<HeadlessSession>>,
+    /// Notifications of everything that happens in it
+    notifications: Receiver<Value>,
+}
+
+impl Spectator {
+    /// Starts watching `session`.
+    pub fn new(session: Arc<Mutex<HeadlessSession>>) -> Self {
+        let notifications = lock(&session).subscribe();
+        Self {
+            session,
+            notifications,
+        }
+    }
+
+    /// Copies the game as it is now.
+    pub fn game_state(&self) -> GameState {
+        lock(&self.session).game_state.clone()
+    }
+
+    /// Returns the game and its new events if anything happened since the
+    /// last poll, without waiting.
+    pub fn poll(&mut self) -> Option<SpectatorUpdate> {
+        let notifications: Vec<Value> = self.notifications.try_iter().collect();
+        if notifications.is_empty() {
+            return None;
+        }
+        let events = notifications
+            .into_iter()
+            .filter(|notification| notification["params"]["kind"] == "event")
+            .filter_map(|mut notification| {
+                serde_json::from_value(notification["params"]["event"].take()).ok()
+            })
+            .collect();
+        Some(SpectatorUpdate {
+            game_state: self.game_state(),
+            events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConcreteAction, Position, WaitAction};
+
+    #[test]
+    fn test_spectator_follows_actions_taken_by_other_clients() {
+        let mut game_state = GameState::new(3);
+        let player_id = game_state
+            .initialize_player("Watched".to_string(), Position::new(5, 5))
+            .unwrap();
+        let session = Arc::new(Mutex::new(HeadlessSession::new(game_state)));
+        let mut spectator = Spectator::new(Arc::clone(&session));
+        assert!(spectator.poll().is_none());
+
+        let wait = ConcreteAction::Wait(WaitAction::new(player_id));
+        let events = lock(&session).submit(&wait).unwrap();
+        let update = spectator.poll().unwrap();
+        assert_eq!(update.game_state.turn_number, 1);
+        assert_eq!(update.events, events);
+        assert!(spectator.poll().is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//! # Query Tools
+//!
+//! Read-only questions an LLM dungeon master can ask about the game.
+//!
+//! Besides acting and taking snapshots, a [`HeadlessSession`] answers the
+//! tools listed in [`QUERY_TOOLS`], so the MCP and WebSocket servers expose
+//! them too. Each tool takes a serde query, such as a [`MapQuery`], and
+//! answers with a serde view, such as a [`MapView`], worked out straight from
+//! the [`GameState`], so a model can reason about the world without reading
+//! the screen. Unlike a snapshot, the tools see past what the player can see
+//! unless asked not to.
+//!
+//! [`HeadlessSession`]: crate::HeadlessSession
+
+use crate::{
+    Entity, EntitySnapshot, GameState, GameStatistics, LlmSnapshot, PlayerSnapshot, Position,
+    RecentMessage, ThatchError, ThatchResult,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Name and description of each query tool.
+pub const QUERY_TOOLS: [(&str, &str); 4] = [
+    (
+        "get_map",
+        "ASCII map of a level (`level_id`, default the current one), \
+         blank where unexplored if `explored_only` is set",
+    ),
+    (
+        "get_entities",
+        "Everything within `radius` steps of the player, nearest first",
+    ),
+    (
+        "get_messages",
+        "Recent messages shown on or after turn `since_turn`",
+    ),
+    (
+        "get_stats",
+        "Turn, depth, the player's condition and the run's statistics",
+    ),
+];
+
+/// Asks for the map of a level.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapQuery {
+    /// Level to draw, or the current one if unset
+    #[serde(default)]
+    pub level_id: Option<u32>,
+    /// Whether to leave unexplored tiles, and entities out of sight, blank
+    #[serde(default)]
+    pub explored_only: bool,
+}
+
+/// The map of a level.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapView {
+    /// Level drawn
+    pub level_id: u32,
+    /// Width of the level in tiles
+    pub width: u32,
+    /// Height of the level in tiles
+    pub height: u32,
+    /// One string per row of tiles, top first
+    pub rows: Vec<String>,
+}
+
+impl MapQuery {
+    /// Draws the level asked for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the level does not exist.
+    pub fn answer(&self, game_state: &GameState) -> ThatchResult<MapView> {
+        let level_id = self.level_id.unwrap_or(game_state.world.current_level_id);
+        let level = game_state
+            .world
+            .get_level(level_id)
+            .ok_or_else(|| ThatchError::InvalidAction(format!("There is no level {}", level_id)))?;
+
+        let glyphs: HashMap<Position, char> = level
+            .entities
+            .iter()
+            .filter_map(|id| game_state.entities.get(id))
+            .map(|entity| (entity.position(), entity.as_entity().display_char()))
+            .collect();
+        let rows = level
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                let row: String = row
+                    .iter()
+                    .enumerate()
+                    .map(|(x, tile)| {
+                        let seen = tile.is_visible() || tile.is_explored();
+                        if self.explored_only && !seen {
+                            return ' ';
+                        }
+                        let position = Position::new(x as i32, y as i32);
+                        match glyphs.get(&position) {
+                            Some(&glyph) if !self.explored_only || tile.is_visible() => glyph,
+                            _ => tile.tile_type.clone().to_char(),
+                        }
+                    })
+                    .collect();
+                row.trim_end().to_string()
+            })
+            .collect();
+
+        Ok(MapView {
+            level_id,
+            width: level.width,
+            height: level.height,
+            rows,
+        })
+    }
+}
+
+/// Radius `get_entities` looks within when none is given.
+pub const DEFAULT_ENTITIES_RADIUS: u32 = 10;
+
+/// Asks for the entities around the player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntitiesQuery {
+    /// Steps from the player, counted along rows and columns, to look within
+    #[serde(default = "default_entities_radius")]
+    pub radius: u32,
+}
+
+fn default_entities_radius() -> u32 {
+    DEFAULT_ENTITIES_RADIUS
+}
+
+impl Default for EntitiesQuery {
+    fn default() -> Self {
+        Self {
+            radius: DEFAULT_ENTITIES_RADIUS,
+        }
+    }
+}
+
+impl EntitiesQuery {
+    /// Lists the other entities on the player's level within the radius,
+    /// seen or not, nearest first.
+    pub fn answer(&self, game_state: &GameState) -> Vec<EntitySnapshot> {
+        let player = game_state.get_player();
+        let (Some(player), Some(level)) = (player, game_state.world.current_level()) else {
+            return Vec::new();
+        };
+        let center = player.position();
+        let mut entities: Vec<EntitySnapshot> = level
+            .entities
+            .iter()
+            .filter(|&&id| Some(id) != game_state.player_id)
+            .filter_map(|id| game_state.entities.get(id))
+            .map(|entity| entity.as_entity())
+            .filter(|entity| center.manhattan_distance(entity.position()) <= self.radius)
+            .map(|entity| EntitySnapshot {
+                name: entity.name().to_string(),
+                glyph: entity.display_char(),
+                position: entity.position(),
+                distance: center.manhattan_distance(entity.position()),
+                hostile: entity.is_hostile(),
+                health: entity.stats().map(|stats| stats.health),
+            })
+            .collect();
+        entities.sort_by_key(|entity| entity.distance);
+        entities
+    }
+}
+
+/// Asks for the messages shown since a turn.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessagesQuery {
+    /// Earliest turn to include
+    #[serde(default)]
+    pub since_turn: u64,
+}
+
+impl MessagesQuery {
+    /// Lists the remembered messages shown on or after the turn, oldest first.
+    pub fn answer(&self, game_state: &GameState) -> Vec<RecentMessage> {
+        game_state
+            .recent_messages
+            .iter()
+            .filter(|message| message.turn >= self.since_turn)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Where the run stands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsView {
+    /// Current game turn
+    pub turn: u64,
+    /// Dungeon level the player is on, starting at 1
+    pub depth: u32,
+    /// The player's condition, if there is a player
+    pub player: Option<PlayerSnapshot>,
+    /// Statistics of the run so far
+    pub statistics: GameStatistics,
+}
+
+impl StatsView {
+    /// Gathers where the run stands.
+    pub fn new(game_state: &GameState) -> Self {
+        Self {
+            turn: game_state.turn_number,
+            depth: game_state.world.current_level_id + 1,
+            player: LlmSnapshot::player_snapshot(game_state),
+            statistics: game_state.statistics.clone(),
+        }
+    }
+}
+
+/// Answers a query tool by name, with its JSON parameters.
+///
+/// Missing parameters are read as an empty object. Returns `None` if no tool
+/// has that name.
+pub fn answer_query(
+    game_state: &GameState,
+    tool: &str,
+    params: Value,
+) -> Option<ThatchResult<Value>> {
+    fn parse<Q: DeserializeOwned>(params: Value) -> ThatchResult<Q> {
+        let params = if params.is_null() {
+            Value::Object(Default::default())
+        } else {
+            params
+        };
+        serde_json::from_value(params).map_err(ThatchError::from)
+    }
+    let answer = match tool {
+        "get_map" => parse::<MapQuery>(params)
+            .and_then(|query| query.answer(game_state))
+            .and_then(|view| serde_json::to_value(view).map_err(ThatchError::from)),
+        "get_entities" => parse::<EntitiesQuery>(params)
+            .map(|query| query.answer(game_state))
+            .and_then(|view| serde_json::to_value(view).map_err(ThatchError::from)),
+        "get_messages" => parse::<MessagesQuery>(params)
+            .map(|query| query.answer(game_state))
+            .and_then(|view| serde_json::to_value(view).map_err(ThatchError::from)),
+        "get_stats" => serde_json::to_value(StatsView::new(game_state)).map_err(ThatchError::from),
+        _ => return None,
+    };
+    Some(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GameEvent, GameState, HeadlessSession, Monster, MonsterType, Position};
+    use serde_json::json;
+
+    #[test]
+    fn test_query_tools_answer_through_the_session() {
+        let mut game_state = GameState::new(7);
+        game_state
+            .initialize_player("Queried".to_string(), Position::new(5, 5))
+            .unwrap();
+        let goblin = Monster::new(MonsterType::Goblin, Position::new(8, 5));
+        let goblin_id = game_state.add_entity(goblin.into()).unwrap();
+        game_state
+            .world
+            .current_level_mut()
+            .unwrap()
+            .add_entity(goblin_id);
+        game_state.turn_number = 4;
+        game_state
+            .process_event(&GameEvent::Message {
+                text: "A goblin snarls.".to_string(),
+                importance: crate::MessageImportance::Normal,
+                channel: crate::MessageChannel::Combat,
+            })
+            .unwrap();
+        let mut session = HeadlessSession::new(game_state);
+        let mut ask = |method: &str, params| {
+            let request = json!({ "id": 1, "method": method, "params": params });
+            session.handle_request(&request.to_string()).response
+        };
+
+        let map = ask("get_map", json!({}));
+        assert_eq!(
+            map["result"]["rows"][5].as_str().unwrap().chars().nth(5),
+            Some('@')
+        );
+        let hidden = ask("get_map", json!({ "explored_only": true, "level_id": 0 }));
+        assert_ne!(hidden["result"]["rows"], map["result"]["rows"]);
+        assert!(ask("get_map", json!({ "level_id": 99 }))["error"].is_string());
+
+        assert_eq!(
+            ask("get_entities", json!({ "radius": 2 }))["result"],
+            json!([])
+        );
+        let near = ask("get_entities", json!({ "radius": 3 }));
+        assert_eq!(near["result"][0]["distance"], 3);
+        // Without a radius the default one is used
+        assert_eq!(ask("get_entities", json!({}))["result"], near["result"]);
+
+        let messages = ask("get_messages", json!({ "since_turn": 4 }));
+        assert_eq!(messages["result"][0]["text"], "A goblin snarls.");
+        assert_eq!(
+            ask("get_messages", json!({ "since_turn": 5 }))["result"],
+            json!([])
+        );
+
+        let stats = ask("get_stats", serde_json::Value::Null);
+        assert_eq!(stats["result"]["turn"], 4);
+        assert_eq!(stats["result"]["player"]["name"], "Queried");
+    }
+}
@@ -0,0 +1,178 @@
+//! # WebSocket Server
+//!
+//! Remote control of the games in a [`SessionManager`] over WebSocket.
+//!
+//! Clients connect with the server's token in the query string, for example
+//! `ws://127.0.0.1:9002/?token=secret`, or in an `Authorization: Bearer`
+//! header. Connections without it are refused during the handshake. Each text
+//! message is one session request and gets one text reply; subscribed clients
+//! also receive event notifications between replies, each carrying the id of
+//! its session in a `session` field.
+
+use crate::{SessionManager, ThatchError, ThatchResult};
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::Message;
+
+/// How long a connection waits for a request before forwarding notifications.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a new connection has to finish the handshake before it is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves shared sessions to any number of WebSocket clients.
+pub struct WsServer {
+    /// The games clients control
+    sessions: Arc<SessionManager>,
+    /// Secret clients must present to connect
+    token: Arc<str>,
+}
+
+impl WsServer {
+    /// Creates a server for `sessions` that only accepts clients with `token`.
+    pub fn new(sessions: SessionManager, token: impl Into<Arc<str>>) -> Self {
+        Self {
+            sessions: Arc::new(sessions),
+            token: token.into(),
+        }
+    }
+
+    /// Listens on `address` and serves clients until the listener fails.
+    ///
+    /// Each client gets its own thread; problems with one connection do not
+    /// stop the server, and dev-tools builds log them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound or accepting fails.
+    pub fn serve(&self, address: &str) -> ThatchResult<()> {
+        let listener = TcpListener::bind(address)?;
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let sessions = Arc::clone(&self.sessions);
+            let token = Arc::clone(&self.token);
+            thread::spawn(move || {
+                let result = serve_client(stream, &sessions, &token);
+                #[cfg(feature = "dev-tools")]
+                if let Err(e) = result {
+                    tracing::warn!("WebSocket client disconnected: {}", e);
+                }
+                #[cfg(not(feature = "dev-tools"))]
+                let _ = result;
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks a handshake request for the token, in the query or a bearer header.
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let in_query = request
+        .uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .any(|pair| pair.strip_prefix("token=") == Some(token));
+    let in_header = request
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        == Some(token);
+    in_query || in_header
+}
+
+/// Runs one connection: authenticates, then answers requests and forwards
+/// notifications until the client leaves.
+// The handshake callback has to return tungstenite's own (large) refusal type
+#[allow(clippy::result_large_err)]
+fn serve_client(stream: TcpStream, sessions: &SessionManager, token: &str) -> ThatchResult<()> {
+    let ws_error = |e: tungstenite::Error| ThatchError::InvalidState(e.to_string());
+
+    let authenticate = |request: &Request, response: Response| {
+        if is_authorized(request, token) {
+            return Ok(response);
+        }
+        let mut refusal = ErrorResponse::new(Some("Missing or wrong token".to_string()));
+        *refusal.status_mut() = StatusCode::UNAUTHORIZED;
+        Err(refusal)
+    };
+    // A client that never finishes the handshake must not hold its thread forever
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut socket = tungstenite::accept_hdr(stream, authenticate)
+        .map_err(|e| ThatchError::InvalidState(format!("Handshake failed: {}", e)))?;
+    socket.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let mut subscriptions = Vec::new();
+    loop {
+        match socket.read() {
+            Ok(Message::Text(request)) => {
+                let reply = sessions.handle_request(&request);
+                if let Some(subscription) = reply.subscription {
+                    subscriptions.push((reply.response["session"].clone(), subscription));
+                }
+                socket
+                    .send(Message::Text(reply.response.to_string()))
+                    .map_err(ws_error)?;
+            }
+            Ok(Message::Close(_))
+            | Err(tungstenite::Error::ConnectionClosed)
+            | Err(tungstenite::Error::AlreadyClosed) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(ws_error(e)),
+        }
+
+        for (session, notifications) in &subscriptions {
+            for mut notification in notifications.try_iter() {
+                notification["session"] = session.clone();
+                socket
+                    .send(Message::Text(notification.to_string()))
+                    .map_err(ws_error)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenerationConfig;
+    use std::io::{Read, Write};
+
+    /// Sends a WebSocket upgrade request for `path` and returns the status line.
+    fn handshake_status(path: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let sessions = SessionManager::new(GenerationConfig::new(0));
+            serve_client(stream, &sessions, "secret")
+        });
+
+        let mut client = TcpStream::connect(address).unwrap();
+        write!(
+            client,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+             Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            path, address
+        )
+        .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(server.join().unwrap().is_err());
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn test_handshake_without_the_token_is_refused() {
+        assert!(handshake_status("/").contains("401"));
+        assert!(handshake_status("/?token=guess").contains("401"));
+    }
+}
@@ -4,15 +4,20 @@
 
 use clap::Parser;
 use macroquad::prelude::*;
-use thatch::{Entity, GameState, PlayerCharacter, SceneManager, ThatchError, ThatchResult};
+use thatch::{
+    config, Entity, EventLog, GameState, GenerationConfig, GeneratorKind, SceneManager,
+    TemplateLibrary, ThatchConfig, ThatchError, ThatchResult, VaultLibrary, WorldShape,
+};
+use std::path::{Path, PathBuf};
 #[cfg(feature = "dev-tools")]
 use tracing::{error, info, Level};
 #[cfg(feature = "dev-tools")]
 use tracing_subscriber;
 
+// Logs go to stderr, leaving stdout to the MCP server
 #[cfg(not(feature = "dev-tools"))]
 macro_rules! info {
-    ($($arg:tt)*) => { println!($($arg)*) };
+    ($($arg:tt)*) => { eprintln!($($arg)*) };
 }
 
 #[cfg(not(feature = "dev-tools"))]
@@ -45,11 +50,77 @@ struct Args {
     /// Log level (error, warn, info, debug, trace)
     #[clap(long, default_value = "info")]
     log_level: String,
+
+    /// Tileset to load from assets/tilesets ("none" for glyph rendering)
+    #[clap(long, default_value = thatch::config::DEFAULT_TILESET)]
+    tileset: String,
+
+    /// Dungeon layout style ("caverns" or "bsp" for classic rooms and corridors)
+    #[clap(long, default_value = "caverns")]
+    generator: GeneratorKind,
+
+    /// Start after generating the first floors and generate the rest in the background
+    #[clap(long)]
+    lazy_floors: bool,
+
+    /// Number of dungeon floors; the Amulet lies on the deepest
+    #[clap(long, default_value_t = config::DUNGEON_LEVELS)]
+    floors: u32,
+
+    /// Width of each dungeon floor in tiles
+    #[clap(long, default_value_t = config::DEFAULT_DUNGEON_WIDTH)]
+    floor_width: u32,
+
+    /// Height of each dungeon floor in tiles
+    #[clap(long, default_value_t = config::DEFAULT_DUNGEON_HEIGHT)]
+    floor_height: u32,
+
+    /// Append every action, event, and turn to this file as newline-delimited JSON
+    #[clap(long)]
+    event_log: Option<PathBuf>,
+
+    /// Run headless and accept remote control over WebSocket
+    #[clap(long)]
+    ws_server: bool,
+
+    /// Address the WebSocket server listens on
+    #[clap(long, default_value = thatch::config::DEFAULT_WS_ADDRESS)]
+    ws_address: String,
+
+    /// Token WebSocket clients must present (a random one is printed if omitted)
+    #[clap(long)]
+    ws_token: Option<String>,
+
+    /// Serve the game over WebSocket and watch remote clients play it in the window
+    #[clap(long)]
+    spectate: bool,
+
+    /// Time world generation, pathfinding, and field of view, then exit
+    #[clap(long)]
+    bench_world: bool,
+
+    /// Print the hash of the world the seed and layout options generate, then exit
+    #[clap(long)]
+    dump_world_hash: bool,
+
+    /// Play this many headless games with a bot and report on game balance, then exit
+    #[clap(long, value_name = "RUNS")]
+    simulate: Option<u32>,
+
+    /// File the simulation report is written to, as CSV if it ends in .csv and JSON otherwise
+    #[clap(long)]
+    simulate_report: Option<PathBuf>,
 }
 
-#[macroquad::main("Thatch Roguelike")]
+/// Opens a resizable, high-DPI window at the logical resolution.
+fn window_conf() -> Conf {
+    thatch::window_conf()
+}
+
+#[macroquad::main(window_conf)]
 async fn main() -> ThatchResult<()> {
     let args = Args::parse();
+    thatch::install_crash_handler();
 
     // Initialize logging
     initialize_logging(&args.log_level)?;
@@ -60,7 +131,7 @@ async fn main() -> ThatchResult<()> {
         #[cfg(feature = "mcp-server")]
         {
             info!("Starting in MCP server mode");
-            return start_mcp_server().await;
+            return start_mcp_server(&args);
         }
         #[cfg(not(feature = "mcp-server"))]
         {
@@ -71,6 +142,48 @@ async fn main() -> ThatchResult<()> {
         }
     }
 
+    if args.spectate {
+        #[cfg(feature = "ws-server")]
+        {
+            info!("Starting in spectator mode");
+            return run_spectator(&args).await;
+        }
+        #[cfg(not(feature = "ws-server"))]
+        {
+            error!("Spectator mode needs the WebSocket server. Rebuild with --features ws-server");
+            return Err(ThatchError::InvalidState(
+                "WebSocket server not available".to_string(),
+            ));
+        }
+    }
+
+    if args.ws_server {
+        #[cfg(feature = "ws-server")]
+        {
+            info!("Starting in WebSocket server mode");
+            return start_ws_server(&args);
+        }
+        #[cfg(not(feature = "ws-server"))]
+        {
+            error!("WebSocket server feature not enabled. Rebuild with --features ws-server");
+            return Err(ThatchError::InvalidState(
+                "WebSocket server not available".to_string(),
+            ));
+        }
+    }
+
+    if args.bench_world {
+        return run_world_benchmark(&args);
+    }
+
+    if args.dump_world_hash {
+        return dump_world_hash(&args);
+    }
+
+    if let Some(runs) = args.simulate {
+        return run_simulation(&args, runs);
+    }
+
     if args.ai_player {
         info!("Starting in AI player mode");
         return run_ai_player_mode(&args).await;
@@ -97,13 +210,14 @@ fn initialize_logging(log_level: &str) -> ThatchResult<()> {
         tracing_subscriber::fmt()
             .with_max_level(level)
             .with_target(false)
+            .with_writer(std::io::stderr)
             .init();
     }
 
     #[cfg(not(feature = "dev-tools"))]
     {
         let _ = log_level; // Suppress unused variable warning
-        println!("Logging initialized (basic mode)");
+        eprintln!("Logging initialized (basic mode)");
     }
 
     Ok(())
@@ -113,11 +227,8 @@ fn initialize_logging(log_level: &str) -> ThatchResult<()> {
 async fn run_game(args: &Args) -> ThatchResult<()> {
     info!("Initializing macroquad display");
 
-    // Configure window for both desktop and mobile
-    // On mobile, this will be overridden by the platform
-    request_new_screen_size(1024.0, 768.0);
-
-    // Enable high DPI support for mobile
+    // The window size and high-DPI support come from window_conf; on mobile
+    // the platform picks the size and the layout scales to fit it
     set_pc_assets_folder("assets");
 
     // Initialize input handler
@@ -128,14 +239,56 @@ async fn run_game(args: &Args) -> ThatchResult<()> {
 
 /// Main game loop implementation.
 async fn run_game_loop(args: &Args, input_handler: &thatch::InputHandler) -> ThatchResult<()> {
-    // Generate a proper dungeon level
-    let seed = args.seed.unwrap_or(12345);
+    let templates = load_templates()?;
+    let vaults = load_vaults(&templates)?;
+    let game_state = new_game_state(args, &templates, &vaults)?;
 
-    info!("Generating complete 3D dungeon with seed: {}", seed);
+    // Initialize scene manager with game state and input handler
+    let mut scene_manager = SceneManager::new(game_state, input_handler.clone()).await?;
+    scene_manager.set_templates(templates);
+    scene_manager.set_vaults(vaults);
+    if let Some(seed) = args.seed {
+        scene_manager.set_seed(seed);
+    }
+    if let Some(path) = &args.event_log {
+        scene_manager.set_event_log(EventLog::open(path)?);
+        info!("Streaming game events to {}", path.display());
+    }
+    if args.tileset != "none" {
+        scene_manager.load_tileset(&args.tileset).await;
+    }
+
+    // Run the main scene loop
+    scene_manager.run().await?;
+
+    info!("Game loop ended");
+    Ok(())
+}
 
-    // Initialize game state with complete 3D dungeon (all 26 floors)
-    info!("Initializing game state with 3D dungeon generation");
-    let mut game_state = GameState::new_with_complete_dungeon(seed)?;
+/// Generates the dungeon and places the player at its entrance.
+fn new_game_state(
+    args: &Args,
+    templates: &TemplateLibrary,
+    vaults: &VaultLibrary,
+) -> ThatchResult<GameState> {
+    let config = generation_config(args, templates, vaults);
+    info!(
+        "Generating complete 3D dungeon with seed: {} ({} layout, {} floors of {}x{})",
+        config.seed,
+        args.generator,
+        config.shape.floor_count,
+        config.shape.floor_width,
+        config.shape.floor_height
+    );
+
+    let mut game_state = if args.lazy_floors {
+        info!("Initializing game state with the first floors; the rest stream in the background");
+        GameState::new_lazy_with_generation_config(config)?
+    } else {
+        // Initialize game state with complete 3D dungeon (every floor)
+        info!("Initializing game state with 3D dungeon generation");
+        GameState::new_with_generation_config(config)?
+    };
 
     // Create and place player at the spawn point
     let player_pos = if let Some(level) = game_state.world.current_level() {
@@ -143,9 +296,7 @@ async fn run_game_loop(args: &Args, input_handler: &thatch::InputHandler) -> Tha
     } else {
         return Err(ThatchError::InvalidState("No current level".to_string()));
     };
-    let player = PlayerCharacter::new("Player".to_string(), player_pos);
-    let player_id = game_state.add_entity(player.into())?;
-    game_state.set_player_id(player_id);
+    game_state.initialize_player("Player".to_string(), player_pos)?;
 
     // Initialize player visibility
     if let Some(player) = game_state.get_player() {
@@ -153,14 +304,157 @@ async fn run_game_loop(args: &Args, input_handler: &thatch::InputHandler) -> Tha
     }
 
     info!("Player created and placed at {:?}", player_pos);
+    Ok(game_state)
+}
 
-    // Initialize scene manager with game state and input handler
-    let mut scene_manager = SceneManager::new(game_state, input_handler.clone()).await?;
+/// Builds the dungeon generation settings the command line asks for, with a
+/// random seed if none was given.
+///
+/// Floors are themed by the LLDM when the saved settings turn it on.
+fn generation_config(
+    args: &Args,
+    templates: &TemplateLibrary,
+    vaults: &VaultLibrary,
+) -> GenerationConfig {
+    let settings = ThatchConfig::load_or_default(Path::new(config::SETTINGS_FILE));
+    GenerationConfig {
+        use_lldm: settings.lldm_enabled,
+        generator: args.generator,
+        templates: templates.clone(),
+        vaults: vaults.clone(),
+        shape: WorldShape {
+            floor_count: args.floors,
+            floor_width: args.floor_width,
+            floor_height: args.floor_height,
+        },
+        ..GenerationConfig::new(args.seed.unwrap_or_else(::rand::random))
+    }
+}
 
-    // Run the main scene loop
-    scene_manager.run().await?;
+/// Loads and validates the monster and item templates.
+///
+/// Uses the template file when it exists, so edits take effect without a
+/// rebuild, and the templates built into the game otherwise.
+fn load_templates() -> ThatchResult<TemplateLibrary> {
+    let path = Path::new(config::TEMPLATES_FILE);
+    if !path.exists() {
+        info!("No {} found, using built-in templates", config::TEMPLATES_FILE);
+        return TemplateLibrary::builtin();
+    }
 
-    info!("Game loop ended");
+    let templates = TemplateLibrary::load(path).map_err(|e| {
+        ThatchError::InvalidState(format!("Invalid {}: {}", config::TEMPLATES_FILE, e))
+    })?;
+    info!(
+        "Loaded {} monster and {} item templates",
+        templates.monsters.len(),
+        templates.items.len()
+    );
+    Ok(templates)
+}
+
+/// Loads and validates the vaults.
+///
+/// Vault files on disk are added to the vaults built into the game, replacing
+/// any of the same name.
+fn load_vaults(templates: &TemplateLibrary) -> ThatchResult<VaultLibrary> {
+    let directory = Path::new(config::VAULTS_DIRECTORY);
+    let vaults = if directory.is_dir() {
+        VaultLibrary::load_directory(directory)?
+    } else {
+        info!("No {} found, using built-in vaults", config::VAULTS_DIRECTORY);
+        VaultLibrary::builtin()?
+    };
+    vaults.validate(templates).map_err(|e| {
+        ThatchError::InvalidState(format!("Invalid vaults in {}: {}", config::VAULTS_DIRECTORY, e))
+    })?;
+    info!("Loaded {} vaults", vaults.vaults.len());
+    Ok(vaults)
+}
+
+/// Times the benchmark workloads and prints a line for each.
+///
+/// Runs across the benchmark seeds, or only `--seed` if one was given.
+fn run_world_benchmark(args: &Args) -> ThatchResult<()> {
+    let seeds = match args.seed {
+        Some(seed) => vec![seed],
+        None => thatch::BENCH_SEEDS.to_vec(),
+    };
+    info!("Benchmarking seeds {:?}", seeds);
+    for sample in thatch::bench_world(&seeds)? {
+        info!("{}", sample);
+    }
+    Ok(())
+}
+
+/// Generates the world for the seed and layout options and prints its hash,
+/// followed by the hash of each floor.
+///
+/// Builds that generate the same world print the same hashes, whatever the platform.
+fn dump_world_hash(args: &Args) -> ThatchResult<()> {
+    let seed = args.seed.unwrap_or(12345);
+    let templates = load_templates()?;
+    let config = GenerationConfig {
+        generator: args.generator,
+        vaults: load_vaults(&templates)?,
+        templates,
+        shape: WorldShape {
+            floor_count: args.floors,
+            floor_width: args.floor_width,
+            floor_height: args.floor_height,
+        },
+        ..GenerationConfig::new(seed)
+    };
+    let world = GameState::new_with_generation_config(config)?.world;
+    println!(
+        "seed {} ({} layout, {} floors of {}x{}): {:016x}",
+        seed,
+        args.generator,
+        args.floors,
+        args.floor_width,
+        args.floor_height,
+        thatch::world_hash(&world)
+    );
+    for (id, hash) in thatch::floor_hashes(&world) {
+        println!("  floor {}: {:016x}", id, hash);
+    }
+    Ok(())
+}
+
+/// Plays `runs` bot games and writes or prints the balance report.
+///
+/// Runs start at `--seed`, or at a random seed if none was given.
+fn run_simulation(args: &Args, runs: u32) -> ThatchResult<()> {
+    let seed = args.seed.unwrap_or_else(::rand::random);
+    let templates = load_templates()?;
+    let config = GenerationConfig {
+        generator: args.generator,
+        vaults: load_vaults(&templates)?,
+        templates,
+        shape: WorldShape {
+            floor_count: args.floors,
+            floor_width: args.floor_width,
+            floor_height: args.floor_height,
+        },
+        ..GenerationConfig::new(seed)
+    };
+    info!("Simulating {} runs from seed {}", runs, seed);
+    let report = thatch::simulate(&config, runs)?;
+    info!(
+        "Average depth {:.1}, {:.1} items found, {:.1} turns per floor",
+        report.average_depth, report.average_items_found, report.average_turns_per_floor
+    );
+    info!("Outcomes: {:?}; deaths: {:?}", report.outcomes, report.death_causes);
+
+    match &args.simulate_report {
+        Some(path) => {
+            let csv = path.extension().is_some_and(|extension| extension == "csv");
+            let contents = if csv { report.to_csv() } else { report.to_json()? };
+            std::fs::write(path, contents)?;
+            info!("Simulation report written to {}", path.display());
+        }
+        None => println!("{}", report.to_json()?),
+    }
     Ok(())
 }
 
@@ -171,10 +465,83 @@ async fn run_ai_player_mode(_args: &Args) -> ThatchResult<()> {
     Ok(())
 }
 
-/// Starts the MCP server for external control.
+/// Serves headless games to WebSocket clients until the server stops.
+///
+/// The game the command line describes is the default session; clients can
+/// create more with the same settings and other seeds.
+#[cfg(feature = "ws-server")]
+fn start_ws_server(args: &Args) -> ThatchResult<()> {
+    let templates = load_templates()?;
+    let vaults = load_vaults(&templates)?;
+    let sessions = new_sessions(args, &templates, &vaults)?;
+    ws_server(args, sessions).serve(&args.ws_address)
+}
+
+/// Serves headless games over WebSocket in the background and shows the
+/// default one in the window, where only the view can be changed.
+#[cfg(feature = "ws-server")]
+async fn run_spectator(args: &Args) -> ThatchResult<()> {
+    let templates = load_templates()?;
+    let vaults = load_vaults(&templates)?;
+    let sessions = new_sessions(args, &templates, &vaults)?;
+    let spectator = thatch::Spectator::new(sessions.session(thatch::DEFAULT_SESSION_ID)?);
+
+    let server = ws_server(args, sessions);
+    let address = args.ws_address.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = server.serve(&address) {
+            error!("WebSocket server stopped: {}", e);
+        }
+    });
+
+    set_pc_assets_folder("assets");
+    let input_handler = thatch::InputHandler::new();
+    let mut scene_manager = SceneManager::new(spectator.game_state(), input_handler).await?;
+    scene_manager.set_templates(templates);
+    if args.tileset != "none" {
+        scene_manager.load_tileset(&args.tileset).await;
+    }
+    scene_manager.spectate(spectator);
+    scene_manager.run().await
+}
+
+/// Creates the session manager for remote play, holding the game the command
+/// line describes as its default session.
+#[cfg(any(feature = "ws-server", feature = "mcp-server"))]
+fn new_sessions(
+    args: &Args,
+    templates: &TemplateLibrary,
+    vaults: &VaultLibrary,
+) -> ThatchResult<thatch::SessionManager> {
+    let game_state = new_game_state(args, templates, vaults)?;
+    let sessions = thatch::SessionManager::new(generation_config(args, templates, vaults));
+    sessions.insert(thatch::DEFAULT_SESSION_ID, thatch::HeadlessSession::new(game_state));
+    Ok(sessions)
+}
+
+/// Creates the WebSocket server for `sessions`, printing the address clients
+/// connect to.
+#[cfg(feature = "ws-server")]
+fn ws_server(args: &Args, sessions: thatch::SessionManager) -> thatch::WsServer {
+    let token = args
+        .ws_token
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+
+    info!("WebSocket server listening on ws://{}/?token={}", args.ws_address, token);
+    thatch::WsServer::new(sessions, token)
+}
+
+/// Serves headless games to an MCP client over standard input and output
+/// until the input closes.
+///
+/// The game the command line describes is the default session; the client
+/// can create more with the same settings and other seeds.
 #[cfg(feature = "mcp-server")]
-async fn start_mcp_server() -> ThatchResult<()> {
-    info!("MCP server mode not yet implemented");
-    // TODO: Implement MCP server
-    Ok(())
+fn start_mcp_server(args: &Args) -> ThatchResult<()> {
+    let templates = load_templates()?;
+    let vaults = load_vaults(&templates)?;
+    let sessions = new_sessions(args, &templates, &vaults)?;
+    let stdin = std::io::stdin();
+    thatch::McpServer::new(sessions).serve(stdin.lock(), std::io::stdout().lock())
 }
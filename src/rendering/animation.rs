@@ -0,0 +1,237 @@
+//! # Animation Layer
+//!
+//! Short, purely visual animations layered on top of the turn-based game.
+//!
+//! Game logic never waits on animations: actions resolve immediately and the
+//! resulting events are handed to the [`AnimationQueue`], which interpolates
+//! movement, flashes damaged entities, and floats damage numbers. Any player
+//! input can skip whatever is still playing.
+
+use crate::{EntityId, Position};
+use std::time::{Duration, Instant};
+
+/// How long an entity takes to slide between two tiles.
+pub const MOVE_ANIMATION_DURATION: Duration = Duration::from_millis(100);
+
+/// How long a damaged entity flashes.
+pub const FLASH_ANIMATION_DURATION: Duration = Duration::from_millis(150);
+
+/// How long a damage number floats above its target.
+pub const DAMAGE_NUMBER_DURATION: Duration = Duration::from_millis(600);
+
+/// Distance in tiles a damage number rises over its lifetime.
+pub const DAMAGE_NUMBER_RISE: f32 = 0.75;
+
+/// Kinds of animation the renderer knows how to draw.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationKind {
+    /// Entity sliding from one tile to another
+    Move {
+        entity_id: EntityId,
+        from: Position,
+        to: Position,
+    },
+    /// Entity flashing after being hit
+    Flash { entity_id: EntityId },
+    /// Number floating up from a tile
+    DamageNumber { position: Position, amount: u32 },
+}
+
+/// A single animation with its timing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// What is being animated
+    pub kind: AnimationKind,
+    /// When the animation started
+    pub started_at: Instant,
+    /// Total length of the animation
+    pub duration: Duration,
+}
+
+impl Animation {
+    /// Returns how far along the animation is at `now`, from 0.0 to 1.0.
+    pub fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.started_at);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Returns true once the animation has played to the end.
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+}
+
+/// Queue of in-flight animations.
+#[derive(Debug, Clone, Default)]
+pub struct AnimationQueue {
+    animations: Vec<Animation>,
+}
+
+impl AnimationQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an animation starting at `now`.
+    pub fn push(&mut self, kind: AnimationKind, duration: Duration, now: Instant) {
+        // A new move for the same entity replaces the old one so it never lags behind
+        if let AnimationKind::Move { entity_id, .. } = &kind {
+            let entity_id = *entity_id;
+            self.animations.retain(|animation| match animation.kind {
+                AnimationKind::Move { entity_id: id, .. } => id != entity_id,
+                _ => true,
+            });
+        }
+
+        self.animations.push(Animation {
+            kind,
+            started_at: now,
+            duration,
+        });
+    }
+
+    /// Queues an entity sliding from `from` to `to`.
+    pub fn push_move(&mut self, entity_id: EntityId, from: Position, to: Position, now: Instant) {
+        self.push(
+            AnimationKind::Move {
+                entity_id,
+                from,
+                to,
+            },
+            MOVE_ANIMATION_DURATION,
+            now,
+        );
+    }
+
+    /// Queues a hit flash and a floating damage number.
+    pub fn push_damage(
+        &mut self,
+        entity_id: EntityId,
+        position: Position,
+        amount: u32,
+        now: Instant,
+    ) {
+        self.push(
+            AnimationKind::Flash { entity_id },
+            FLASH_ANIMATION_DURATION,
+            now,
+        );
+        self.push(
+            AnimationKind::DamageNumber { position, amount },
+            DAMAGE_NUMBER_DURATION,
+            now,
+        );
+    }
+
+    /// Drops animations that have finished by `now`.
+    pub fn update(&mut self, now: Instant) {
+        self.animations
+            .retain(|animation| !animation.is_finished(now));
+    }
+
+    /// Skips every animation still playing.
+    pub fn skip(&mut self) {
+        self.animations.clear();
+    }
+
+    /// Returns true if anything is still animating.
+    pub fn is_animating(&self) -> bool {
+        !self.animations.is_empty()
+    }
+
+    /// Gets all active animations.
+    pub fn animations(&self) -> &[Animation] {
+        &self.animations
+    }
+
+    /// Gets the interpolated position of a moving entity, in fractional tiles.
+    ///
+    /// Returns None if the entity is not currently sliding.
+    pub fn entity_position(&self, entity_id: EntityId, now: Instant) -> Option<(f32, f32)> {
+        self.animations
+            .iter()
+            .find_map(|animation| match animation.kind {
+                AnimationKind::Move {
+                    entity_id: id,
+                    from,
+                    to,
+                } if id == entity_id => {
+                    let t = animation.progress(now);
+                    Some((
+                        from.x as f32 + (to.x - from.x) as f32 * t,
+                        from.y as f32 + (to.y - from.y) as f32 * t,
+                    ))
+                }
+                _ => None,
+            })
+    }
+
+    /// Gets how strongly an entity is flashing, from 0.0 (not at all) to 1.0.
+    pub fn flash_intensity(&self, entity_id: EntityId, now: Instant) -> f32 {
+        self.animations
+            .iter()
+            .filter_map(|animation| match animation.kind {
+                AnimationKind::Flash { entity_id: id } if id == entity_id => {
+                    Some(1.0 - animation.progress(now))
+                }
+                _ => None,
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::new_entity_id;
+
+    #[test]
+    fn test_move_interpolation_and_expiry() {
+        let mut queue = AnimationQueue::new();
+        let entity_id = new_entity_id();
+        let start = Instant::now();
+
+        queue.push_move(entity_id, Position::new(0, 0), Position::new(2, 0), start);
+
+        let halfway = start + MOVE_ANIMATION_DURATION / 2;
+        let (x, y) = queue.entity_position(entity_id, halfway).unwrap();
+        assert!((x - 1.0).abs() < 0.01);
+        assert_eq!(y, 0.0);
+
+        queue.update(start + MOVE_ANIMATION_DURATION);
+        assert!(!queue.is_animating());
+        assert!(queue.entity_position(entity_id, halfway).is_none());
+    }
+
+    #[test]
+    fn test_new_move_replaces_previous() {
+        let mut queue = AnimationQueue::new();
+        let entity_id = new_entity_id();
+        let now = Instant::now();
+
+        queue.push_move(entity_id, Position::new(0, 0), Position::new(1, 0), now);
+        queue.push_move(entity_id, Position::new(1, 0), Position::new(2, 0), now);
+
+        assert_eq!(queue.animations().len(), 1);
+        assert_eq!(queue.entity_position(entity_id, now), Some((1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_damage_flash_and_skip() {
+        let mut queue = AnimationQueue::new();
+        let entity_id = new_entity_id();
+        let now = Instant::now();
+
+        queue.push_damage(entity_id, Position::new(3, 3), 7, now);
+        assert_eq!(queue.flash_intensity(entity_id, now), 1.0);
+        assert_eq!(queue.animations().len(), 2);
+
+        queue.skip();
+        assert!(!queue.is_animating());
+        assert_eq!(queue.flash_intensity(entity_id, now), 0.0);
+    }
+}
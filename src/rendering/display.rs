@@ -2,12 +2,43 @@
 //!
 //! Screen management and 2D graphics rendering functionality using macroquad.
 
-use crate::game::{ConcreteEntity, Entity, GameState, Position, TileType};
-use crate::input::PlayerInput;
-use crate::rendering::UI;
-use crate::{ThatchError, ThatchResult};
+use crate::game::{
+    ConcreteEntity, DangerMap, Entity, EntityType, GameCompletionState, GameEvent, GameState,
+    Level, LightKind, MessageChannel, MessageImportance, Position, RunSummary, Tile, TileEffect,
+    TileOverlay, TilePalette, TileType,
+};
+use crate::input::{InputHandler, PlayerInput};
+use crate::rendering::{
+    AnimationKind, AnimationQueue, ColorRole, Frontend, LoggedMessage, MessageLog, ScreenLayout,
+    Theme, TileBatch, TileBatchBuilder, TileBatchKey, TileQuad, Tileset, UiScale,
+    DAMAGE_NUMBER_RISE, LOGICAL_HEIGHT, LOGICAL_WIDTH, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, UI,
+};
+use crate::{TemplateLibrary, ThatchError, ThatchResult};
 use macroquad::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Smallest allowed zoom multiplier for the map viewport.
+pub const MIN_ZOOM: f32 = 0.5;
+
+/// Largest allowed zoom multiplier for the map viewport.
+pub const MAX_ZOOM: f32 = 3.0;
+
+/// Amount the zoom multiplier changes per zoom step.
+pub const ZOOM_STEP: f32 = 0.25;
+
+/// Number of tiles the viewport moves per pan step in free-look mode.
+pub const PAN_STEP_TILES: i32 = 4;
+
+/// Brightness of remembered tiles, and the floor for dark but visible ones.
+pub const REMEMBERED_BRIGHTNESS: f32 = 0.4;
+
+/// Lowest brightness tiles are drawn at in high-contrast mode.
+pub const HIGH_CONTRAST_BRIGHTNESS: f32 = 0.7;
+
+/// How long each toast stays on screen.
+pub const TOAST_DURATION: Duration = Duration::from_secs(3);
 
 /// Macroquad display manager for the game.
 ///
@@ -30,10 +61,12 @@ pub struct MacroquadDisplay {
     pub map_height: i32,
     /// UI panel width in pixels
     pub ui_panel_width: f32,
-    /// Message history
-    pub messages: Vec<String>,
-    /// Maximum number of messages to keep
-    pub max_messages: usize,
+    /// Scale applied to logical sizes for the current window
+    pub scale: UiScale,
+    /// Message history, with the channels the player has muted
+    pub messages: MessageLog,
+    /// Number of recent messages shown under the map
+    pub visible_messages: usize,
     /// Last player position for tracking movement
     pub last_player_pos: Option<Position>,
     /// Tile textures
@@ -42,6 +75,39 @@ pub struct MacroquadDisplay {
     pub font: Option<Font>,
     /// UI component for touch controls
     pub ui: UI,
+    /// Zoom multiplier applied on top of the responsive tile size
+    pub zoom: f32,
+    /// Whether the viewport is detached from the player (free-look mode)
+    pub free_look: bool,
+    /// Text of the examine popup, if one is open
+    pub examine_text: Option<String>,
+    /// Toasts waiting to be shown, the first one on screen
+    pub toasts: VecDeque<String>,
+    /// When the first toast appeared, once it has
+    toast_shown_at: Option<Instant>,
+    /// Whether the profiler overlay is shown
+    pub show_profiler: bool,
+    /// Sprite atlas used for the map, if one was loaded
+    pub tileset: Option<Tileset>,
+    /// Visual-only animations for movement and combat
+    pub animations: AnimationQueue,
+    /// Whether visible hostile monsters get a name label and health bar
+    pub show_monster_labels: bool,
+    /// Whether tiles visible monsters can attack next turn are tinted
+    pub show_danger: bool,
+    /// Monster templates, used to describe monsters in hover tooltips
+    pub templates: TemplateLibrary,
+    /// Whether the map is drawn in a high-contrast palette
+    pub high_contrast: bool,
+    /// Multiplier for panel, message and label text sizes
+    pub text_scale: f32,
+    /// Colors for the map, labels and messages
+    pub theme: Theme,
+    /// Terrain in the viewport, rebuilt only when it changes
+    pub map_batch: TileBatch,
+    /// Danger overlay with the level and turn it was worked out for, so it
+    /// is only worked out again once something has moved
+    danger_map: Option<((u32, u64), DangerMap)>,
 }
 
 impl MacroquadDisplay {
@@ -65,12 +131,29 @@ impl MacroquadDisplay {
             map_width: 0,
             map_height: 0,
             ui_panel_width: 0.0,
-            messages: Vec::new(),
-            max_messages: 100,
+            scale: UiScale::new(LOGICAL_WIDTH, LOGICAL_HEIGHT),
+            messages: MessageLog::new(),
+            visible_messages: 3,
             last_player_pos: None,
             tile_textures: HashMap::new(),
             font: None,
             ui: UI::new(),
+            zoom: 1.0,
+            free_look: false,
+            examine_text: None,
+            toasts: VecDeque::new(),
+            toast_shown_at: None,
+            show_profiler: false,
+            tileset: None,
+            animations: AnimationQueue::new(),
+            show_monster_labels: true,
+            show_danger: false,
+            templates: TemplateLibrary::default(),
+            high_contrast: false,
+            text_scale: 1.0,
+            theme: Theme::default(),
+            map_batch: TileBatch::new(),
+            danger_map: None,
         };
 
         display.update_layout_dimensions();
@@ -79,21 +162,45 @@ impl MacroquadDisplay {
     }
 
     /// Draws text that wraps within a specified width.
-    fn draw_wrapped_text(&self, text: &str, x: f32, y: f32, font_size: f32, color: Color, _max_width: f32) {
+    fn draw_wrapped_text(
+        &self,
+        text: &str,
+        x: f32,
+        y: f32,
+        font_size: f32,
+        color: Color,
+        _max_width: f32,
+    ) {
         // For now, just draw the text normally (word wrapping is complex)
         // In a real implementation, you'd break text into lines
         draw_text(text, x, y, font_size, color);
     }
 
     /// Updates layout dimensions based on current screen size for responsive design.
+    ///
+    /// Called every frame, so window resizes and device rotations take effect
+    /// on the next frame. Desktop windows shrunk below the minimum size are
+    /// asked to grow back to it.
     pub fn update_layout_dimensions(&mut self) {
         let current_width = screen_width();
         let current_height = screen_height();
 
         // Only update if screen size changed or first time
-        if (current_width - self.screen_width).abs() > 1.0 || (current_height - self.screen_height).abs() > 1.0 {
+        if (current_width - self.screen_width).abs() > 1.0
+            || (current_height - self.screen_height).abs() > 1.0
+        {
             self.screen_width = current_width;
             self.screen_height = current_height;
+            self.scale =
+                UiScale::new(current_width, current_height).with_text_scale(self.text_scale);
+
+            #[cfg(not(any(target_os = "android", target_os = "ios", target_arch = "wasm32")))]
+            if self.scale.is_below_minimum() {
+                request_new_screen_size(
+                    current_width.max(MIN_WINDOW_WIDTH),
+                    current_height.max(MIN_WINDOW_HEIGHT),
+                );
+            }
 
             // Responsive calculations
             self.calculate_responsive_layout();
@@ -102,29 +209,28 @@ impl MacroquadDisplay {
 
     /// Calculates responsive layout dimensions based on screen size.
     fn calculate_responsive_layout(&mut self) {
-        // Responsive tile size based on screen resolution
-        let base_tile_size = 24.0;
-        let scale_factor = (self.screen_width / 1024.0).max(0.5).min(2.0); // Scale between 0.5x and 2x
-        self.tile_size = base_tile_size * scale_factor;
-
-        // Responsive UI panel width (15-25% of screen width)
-        let panel_ratio = if self.screen_width < 800.0 { 0.15 } else if self.screen_width > 1600.0 { 0.20 } else { 0.18 };
-        self.ui_panel_width = (self.screen_width * panel_ratio).max(250.0).min(400.0);
-
-        // Message area height (8-12% of screen height)
-        let message_ratio = if self.screen_height < 600.0 { 0.08 } else { 0.10 };
-        let message_area_height = (self.screen_height * message_ratio).max(60.0).min(120.0);
-
-        // Calculate map dimensions
-        let available_map_width = self.screen_width - self.ui_panel_width;
-        let available_map_height = self.screen_height - message_area_height;
+        let layout = ScreenLayout::new(self.scale, self.zoom, self.visible_messages);
+        self.tile_size = layout.tile_size;
+        self.ui_panel_width = layout.panel_width;
+        self.map_width = layout.map_width;
+        self.map_height = layout.map_height;
+    }
 
-        self.map_width = (available_map_width / self.tile_size) as i32;
-        self.map_height = (available_map_height / self.tile_size) as i32;
+    /// Sets the multiplier for panel, message and label text sizes.
+    ///
+    /// The message area grows or shrinks with the text.
+    pub fn set_text_scale(&mut self, text_scale: f32) {
+        self.text_scale = text_scale;
+        self.scale = self.scale.with_text_scale(text_scale);
+        self.calculate_responsive_layout();
+    }
 
-        // Ensure minimum map size
-        self.map_width = self.map_width.max(20);
-        self.map_height = self.map_height.max(15);
+    /// Sets how many recent messages are shown under the map.
+    ///
+    /// The map shrinks or grows to make room for them.
+    pub fn set_visible_messages(&mut self, count: usize) {
+        self.visible_messages = count;
+        self.calculate_responsive_layout();
     }
 
     /// Initializes graphics resources.
@@ -135,6 +241,24 @@ impl MacroquadDisplay {
         Ok(())
     }
 
+    /// Loads a tileset from the assets folder, replacing the current one.
+    ///
+    /// On failure the display keeps rendering colored glyphs and the error is
+    /// returned so the caller can report it.
+    pub async fn load_tileset(&mut self, name: &str) -> ThatchResult<()> {
+        self.map_batch.invalidate();
+        match Tileset::load(name).await {
+            Ok(tileset) => {
+                self.tileset = Some(tileset);
+                Ok(())
+            }
+            Err(e) => {
+                self.tileset = None;
+                Err(e)
+            }
+        }
+    }
+
     /// Creates tile textures for different tile types.
     async fn create_tile_textures(&mut self) {
         // For now, we'll just use colored rectangles for tiles
@@ -144,15 +268,21 @@ impl MacroquadDisplay {
         let white_texture = Texture2D::from_rgba8(1, 1, &[255, 255, 255, 255]);
 
         // Map characters to the base texture (we'll use colors to differentiate)
-        // Note: In macroquad, textures are reference-counted, so cloning is cheap
-        self.tile_textures.insert('#', white_texture.clone()); // Wall
-        self.tile_textures.insert('.', white_texture.clone()); // Floor
-        self.tile_textures.insert('@', white_texture.clone()); // Player
-        self.tile_textures.insert('+', white_texture.clone()); // Closed door
-        self.tile_textures.insert('\'', white_texture.clone()); // Open door
-        self.tile_textures.insert('<', white_texture.clone()); // Stairs up
-        self.tile_textures.insert('>', white_texture.clone()); // Stairs down
-        self.tile_textures.insert('~', white_texture.clone()); // Water
+        // Note: In macroquad, textures are handles, so copying is cheap
+        self.tile_textures.insert('#', white_texture); // Wall
+        self.tile_textures.insert('.', white_texture); // Floor
+        self.tile_textures.insert('@', white_texture); // Player
+        self.tile_textures.insert('+', white_texture); // Closed door
+        self.tile_textures.insert('\'', white_texture); // Open door
+        self.tile_textures.insert('<', white_texture); // Stairs up
+        self.tile_textures.insert('>', white_texture); // Stairs down
+        self.tile_textures.insert('~', white_texture); // Water
+        self.tile_textures.insert('&', white_texture); // Chest
+        self.tile_textures.insert('{', white_texture); // Fountain
+        self.tile_textures.insert('^', white_texture); // Trap
+        self.tile_textures.insert('_', white_texture); // Plaque
+        self.tile_textures.insert('O', white_texture); // Teleporter
+        self.tile_textures.insert('|', white_texture); // Altar
         self.tile_textures.insert('*', white_texture); // Special
     }
 
@@ -163,9 +293,9 @@ impl MacroquadDisplay {
         // Update layout dimensions for responsive design
         self.update_layout_dimensions();
 
-        // Check if we need to update viewport
+        // Check if we need to update viewport (free-look keeps the viewport where it is)
         let current_player_pos = game_state.get_player().map(|p| p.position());
-        if current_player_pos != self.last_player_pos {
+        if !self.free_look && current_player_pos != self.last_player_pos {
             if let Some(pos) = current_player_pos {
                 self.center_viewport_on_position(pos);
             }
@@ -175,10 +305,29 @@ impl MacroquadDisplay {
         // Clear screen
         clear_background(BLACK);
 
+        // Drop finished animations before drawing
+        let now = Instant::now();
+        self.animations.update(now);
+
         // Render components
         self.render_map(game_state)?;
+        if self.show_danger {
+            self.render_danger_overlay(game_state);
+        }
+        self.render_animations(game_state, now);
+        if self.show_monster_labels {
+            self.render_monster_labels(game_state, now);
+        }
         self.render_ui(game_state)?;
         self.render_messages()?;
+        self.render_hover_tooltip(game_state)?;
+        self.render_examine_popup();
+        self.render_toast(now);
+
+        #[cfg(feature = "dev-tools")]
+        if self.show_profiler {
+            self.render_profiler_overlay();
+        }
 
         // Always render touch controls for all platforms
         self.ui.render_touch_controls();
@@ -192,49 +341,478 @@ impl MacroquadDisplay {
         self.viewport_y = position.y - (self.map_height / 2);
     }
 
+    /// Sets the zoom multiplier, clamped to [`MIN_ZOOM`, `MAX_ZOOM`].
+    ///
+    /// The layout is recalculated immediately and the viewport keeps the same
+    /// tile at its center.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let center = Position::new(
+            self.viewport_x + self.map_width / 2,
+            self.viewport_y + self.map_height / 2,
+        );
+
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self.calculate_responsive_layout();
+        self.center_viewport_on_position(center);
+    }
+
+    /// Zooms in by one step.
+    pub fn zoom_in(&mut self) {
+        self.set_zoom(self.zoom + ZOOM_STEP);
+    }
+
+    /// Zooms out by one step.
+    pub fn zoom_out(&mut self) {
+        self.set_zoom(self.zoom - ZOOM_STEP);
+    }
+
+    /// Moves the viewport in free-look mode.
+    ///
+    /// `delta` is a unit direction; the viewport moves [`PAN_STEP_TILES`] tiles
+    /// in that direction and stays detached from the player until
+    /// [`MacroquadDisplay::end_free_look`] is called.
+    pub fn pan_viewport(&mut self, delta: Position) {
+        self.free_look = true;
+        self.viewport_x += delta.x * PAN_STEP_TILES;
+        self.viewport_y += delta.y * PAN_STEP_TILES;
+    }
+
+    /// Leaves free-look mode and snaps the viewport back to the player on the next render.
+    pub fn end_free_look(&mut self) {
+        if self.free_look {
+            self.free_look = false;
+            self.last_player_pos = None;
+        }
+    }
+
+    /// Converts the current mouse position to a world tile position.
+    ///
+    /// Returns `None` when the cursor is outside the map area.
+    pub fn mouse_tile_position(&self) -> Option<Position> {
+        let (mouse_x, mouse_y) = mouse_position();
+        self.screen_tile_position(mouse_x, mouse_y)
+    }
+
+    /// Converts a screen point in pixels to a world tile position.
+    ///
+    /// Returns `None` when the point is outside the map area.
+    pub fn screen_tile_position(&self, x: f32, y: f32) -> Option<Position> {
+        if x < 0.0 || y < 0.0 || self.tile_size <= 0.0 {
+            return None;
+        }
+
+        let screen_x = (x / self.tile_size) as i32;
+        let screen_y = (y / self.tile_size) as i32;
+        if screen_x >= self.map_width || screen_y >= self.map_height {
+            return None;
+        }
+
+        Some(Position::new(
+            self.viewport_x + screen_x,
+            self.viewport_y + screen_y,
+        ))
+    }
+
+    /// Opens the examine popup with the given description.
+    pub fn show_examine(&mut self, text: String) {
+        self.examine_text = Some(text);
+    }
+
+    /// Closes the examine popup.
+    pub fn clear_examine(&mut self) {
+        self.examine_text = None;
+    }
+
+    /// Queues a toast to pop up at the top of the screen for a few seconds.
+    pub fn show_toast(&mut self, text: String) {
+        self.toasts.push_back(text);
+    }
+
+    /// Toggles the profiler overlay.
+    pub fn toggle_profiler(&mut self) {
+        self.show_profiler = !self.show_profiler;
+    }
+
+    /// Toggles the danger overlay and says whether it is now on.
+    pub fn toggle_danger_overlay(&mut self) {
+        self.show_danger = !self.show_danger;
+        let state = if self.show_danger { "on" } else { "off" };
+        self.add_message(format!("Danger overlay {}.", state));
+    }
+
+    /// Renders frame time and the most expensive profiled scopes.
+    #[cfg(feature = "dev-tools")]
+    fn render_profiler_overlay(&self) {
+        let (frame_time, top_costs) =
+            crate::with_profiler(|profiler| (profiler.frame_time(), profiler.top_costs(6)));
+
+        let x = 10.0;
+        let line_height = 18.0;
+        let height = 50.0 + top_costs.len() as f32 * line_height;
+        draw_rectangle(x, 10.0, 420.0, height, Color::new(0.0, 0.0, 0.0, 0.8));
+
+        draw_text(
+            &format!(
+                "FPS {} | frame {:.2} ms | update+render {:.2} ms",
+                get_fps(),
+                get_frame_time() * 1000.0,
+                frame_time.as_secs_f64() * 1000.0
+            ),
+            x + 8.0,
+            30.0,
+            16.0,
+            YELLOW,
+        );
+
+        for (index, (name, stats)) in top_costs.iter().enumerate() {
+            draw_text(
+                &format!(
+                    "{:<26} last {:>6.2} ms  total {:>8.1} ms  x{}",
+                    name,
+                    stats.last_frame.as_secs_f64() * 1000.0,
+                    stats.total.as_secs_f64() * 1000.0,
+                    stats.calls
+                ),
+                x + 8.0,
+                50.0 + index as f32 * line_height,
+                14.0,
+                WHITE,
+            );
+        }
+    }
+
+    /// Renders a tooltip for the explored tile under the mouse cursor.
+    fn render_hover_tooltip(&self, game_state: &GameState) -> ThatchResult<()> {
+        let Some(world_pos) = self.mouse_tile_position() else {
+            return Ok(());
+        };
+        let Some(level) = game_state.world.current_level() else {
+            return Ok(());
+        };
+
+        if let Some(tile) = level.get_tile(world_pos) {
+            let (mouse_x, mouse_y) = mouse_position();
+            // Visible monsters are described before the tile they stand on
+            let monster = game_state
+                .get_entity_at_position(world_pos)
+                .and_then(|entity_id| game_state.entities.get(&entity_id))
+                .and_then(|entity| entity.as_monster())
+                .filter(|_| tile.is_visible());
+            if let Some(monster) = monster {
+                let text = self.templates.describe_monster(monster);
+                self.ui.render_tooltip(&text, mouse_x, mouse_y);
+            } else if tile.is_explored() {
+                self.ui
+                    .render_tile_tooltip(&tile.tile_type, mouse_x, mouse_y)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tints explored tiles that visible hostiles can attack next turn, more
+    /// strongly where several of them can.
+    fn render_danger_overlay(&mut self, game_state: &GameState) {
+        let Some(level) = game_state.world.current_level() else {
+            return;
+        };
+        let key = (level.id, game_state.turn_number);
+        if self.danger_map.as_ref().is_none_or(|(computed, _)| *computed != key) {
+            self.danger_map = Some((key, DangerMap::compute(game_state)));
+        }
+        let Some((_, danger)) = &self.danger_map else {
+            return;
+        };
+        let color = self.theme.color(ColorRole::Danger);
+
+        for (position, threats) in danger.iter() {
+            let on_map = position.x >= self.viewport_x
+                && position.y >= self.viewport_y
+                && position.x < self.viewport_x + self.map_width
+                && position.y < self.viewport_y + self.map_height;
+            let explored = level
+                .get_tile(position)
+                .is_some_and(|tile| tile.is_explored());
+            if !on_map || !explored {
+                continue;
+            }
+
+            let alpha = (0.15 + 0.1 * threats as f32).min(0.45);
+            let (x, y) = self.world_to_screen(position.x as f32, position.y as f32);
+            draw_rectangle(
+                x,
+                y,
+                self.tile_size,
+                self.tile_size,
+                Color::new(color.r, color.g, color.b, alpha),
+            );
+        }
+    }
+
+    /// Draws a name label and health bar over each visible hostile monster.
+    fn render_monster_labels(&self, game_state: &GameState, now: Instant) {
+        let Some(level) = game_state.world.current_level() else {
+            return;
+        };
+        let font_size = self.scale.font(12.0);
+        let bar_height = (self.tile_size * 0.12).max(3.0);
+
+        for entity_id in &level.entities {
+            let Some(monster) = game_state
+                .entities
+                .get(entity_id)
+                .and_then(|entity| entity.as_monster())
+                .filter(|monster| monster.hostile)
+            else {
+                continue;
+            };
+            let visible = level
+                .get_tile(monster.position)
+                .is_some_and(|tile| tile.is_visible());
+            let on_map = monster.position.x >= self.viewport_x
+                && monster.position.y >= self.viewport_y
+                && monster.position.x < self.viewport_x + self.map_width
+                && monster.position.y < self.viewport_y + self.map_height;
+            if !visible || !on_map {
+                continue;
+            }
+
+            // Labels follow monsters as they slide between tiles
+            let (x, y) = self
+                .animations
+                .entity_position(*entity_id, now)
+                .unwrap_or((monster.position.x as f32, monster.position.y as f32));
+            let (screen_x, screen_y) = self.world_to_screen(x, y);
+
+            let max_health = monster.stats.max_health.max(1) as f32;
+            let ratio = (monster.stats.health as f32 / max_health).clamp(0.0, 1.0);
+            let bar_y = screen_y - bar_height - 1.0;
+            draw_rectangle(screen_x, bar_y, self.tile_size, bar_height, DARKGRAY);
+            let bar_color = self.theme.health_color(ratio);
+            draw_rectangle(
+                screen_x,
+                bar_y,
+                self.tile_size * ratio,
+                bar_height,
+                bar_color,
+            );
+
+            let label_width = measure_text(&monster.name, None, font_size as u16, 1.0).width;
+            let label_x = screen_x + (self.tile_size - label_width) / 2.0;
+            draw_text(&monster.name, label_x, bar_y - 2.0, font_size, WHITE);
+        }
+    }
+
+    /// Renders the examine popup along the bottom of the map area.
+    fn render_examine_popup(&self) {
+        let Some(text) = &self.examine_text else {
+            return;
+        };
+
+        let font_size = 18.0;
+        let box_width = self.map_width as f32 * self.tile_size;
+        let box_y = self.map_height as f32 * self.tile_size - 50.0;
+
+        draw_rectangle(0.0, box_y, box_width, 50.0, Color::new(0.0, 0.0, 0.0, 0.85));
+        draw_rectangle_lines(0.0, box_y, box_width, 50.0, 2.0, LIGHTGRAY);
+        draw_text(text, 10.0, box_y + 22.0, font_size, WHITE);
+        draw_text(
+            "Right-click elsewhere or press any key to close",
+            10.0,
+            box_y + 42.0,
+            14.0,
+            GRAY,
+        );
+    }
+
+    /// Renders the current toast at the top of the screen, moving on to the
+    /// next once it has been shown for [`TOAST_DURATION`].
+    fn render_toast(&mut self, now: Instant) {
+        let shown_at = *self.toast_shown_at.get_or_insert(now);
+        if now.duration_since(shown_at) >= TOAST_DURATION {
+            self.toasts.pop_front();
+            self.toast_shown_at = None;
+        }
+        let Some(text) = self.toasts.front() else {
+            self.toast_shown_at = None;
+            return;
+        };
+
+        let font_size = 20.0;
+        let width = measure_text(text, None, font_size as u16, 1.0).width + 40.0;
+        let x = (screen_width() - width) / 2.0;
+        let y = 20.0;
+        draw_rectangle(x, y, width, 40.0, Color::new(0.1, 0.1, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, 40.0, 2.0, GOLD);
+        draw_text(text, x + 20.0, y + 26.0, font_size, GOLD);
+    }
+
+    /// Highlights the look cursor and shows how to leave look mode, with the
+    /// description of what is under the cursor below.
+    pub fn render_look_cursor(&self, position: Position, description: Option<&str>) {
+        let (x, y) = self.world_to_screen(position.x as f32, position.y as f32);
+        draw_rectangle_lines(x, y, self.tile_size, self.tile_size, 2.0, YELLOW);
+
+        let box_width = self.map_width as f32 * self.tile_size;
+        let box_height = if description.is_some() { 48.0 } else { 26.0 };
+        draw_rectangle(0.0, 0.0, box_width, box_height, Color::new(0.0, 0.0, 0.0, 0.85));
+        draw_text(
+            "LOOK: move the cursor to examine, X or ESC to exit",
+            10.0,
+            18.0,
+            16.0,
+            YELLOW,
+        );
+        if let Some(description) = description {
+            draw_text(description, 10.0, 40.0, 16.0, WHITE);
+        }
+    }
+
+    /// Shows the count typed for the next command along the top of the map.
+    pub fn render_pending_count(&self, count: u32) {
+        let box_width = self.map_width as f32 * self.tile_size;
+        draw_rectangle(0.0, 0.0, box_width, 26.0, Color::new(0.0, 0.0, 0.0, 0.85));
+        draw_text(
+            &format!("COUNT: {count} - the next command repeats, ESC to cancel"),
+            10.0,
+            18.0,
+            16.0,
+            YELLOW,
+        );
+    }
+
     /// Renders the game map using macroquad graphics.
-    fn render_map(&self, game_state: &GameState) -> ThatchResult<()> {
+    fn render_map(&mut self, game_state: &GameState) -> ThatchResult<()> {
+        crate::profile_scope!("render_map");
         let level = game_state
             .world
             .current_level()
             .ok_or_else(|| ThatchError::InvalidState("No current level".to_string()))?;
 
+        let key = TileBatchKey {
+            level_id: level.id,
+            viewport: (
+                self.viewport_x,
+                self.viewport_y,
+                self.map_width,
+                self.map_height,
+            ),
+            tile_size: self.tile_size,
+            turn: game_state.turn_number,
+            field_of_view: game_state.field_of_view.clone(),
+            high_contrast: self.high_contrast,
+        };
+        if !self.map_batch.is_current(&key) {
+            self.map_batch = self.build_map_batch(game_state, level, key);
+        }
+        self.map_batch.draw();
+
+        // Tiles with something on them are drawn every frame, as is anything
+        // that has turned up on the map since the batch was built
+        let occupied = self.map_batch.occupied();
+        for tile in occupied {
+            self.render_world_tile(game_state, level, tile.position, tile.brightness);
+        }
+        let arrivals = level
+            .entities
+            .iter()
+            .filter_map(|&entity_id| game_state.get_entity_position(entity_id))
+            .chain(level.containers.iter().map(|container| container.position))
+            .chain(level.ground_items.iter().map(|stack| stack.item.position))
+            .filter(|&position| occupied.iter().all(|tile| tile.position != position));
+        for position in arrivals {
+            if let Some(brightness) = level.get_tile(position).and_then(Self::tile_brightness) {
+                self.render_world_tile(game_state, level, position, brightness);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the brightness a tile is drawn at, or `None` if it has never
+    /// been seen and is left black.
+    fn tile_brightness(tile: &Tile) -> Option<f32> {
+        if tile.is_visible() {
+            // Dimly lit tiles fade towards the remembered-tile brightness
+            Some(REMEMBERED_BRIGHTNESS + (1.0 - REMEMBERED_BRIGHTNESS) * tile.light.clamp(0.0, 1.0))
+        } else if tile.is_explored() {
+            // Explored but not visible tiles are drawn in darker color
+            Some(REMEMBERED_BRIGHTNESS)
+        } else {
+            None
+        }
+    }
+
+    /// Merges the terrain in the viewport into a batch built for `key`.
+    ///
+    /// Unexplored tiles are skipped before anything else is looked up, and
+    /// tiles with an entity, chest or item on them are left for
+    /// [`Self::render_map`] to draw each frame.
+    fn build_map_batch(
+        &self,
+        game_state: &GameState,
+        level: &Level,
+        key: TileBatchKey,
+    ) -> TileBatch {
+        crate::profile_scope!("build_map_batch");
+        let mut builder = TileBatchBuilder::new();
         for screen_y in 0..self.map_height {
             for screen_x in 0..self.map_width {
                 let world_x = self.viewport_x + screen_x;
                 let world_y = self.viewport_y + screen_y;
                 let world_pos = Position::new(world_x, world_y);
+                let Some(tile) = level.get_tile(world_pos) else {
+                    continue;
+                };
+                let Some(brightness) = Self::tile_brightness(tile) else {
+                    continue;
+                };
+                let occupied = game_state.get_entity_at_position(world_pos).is_some()
+                    || level.container_at(world_pos).is_some()
+                    || level.ground_items.top_at(world_pos).is_some();
+                if occupied {
+                    builder.push_occupied(world_pos, brightness);
+                    continue;
+                }
 
-                let screen_pixel_x = screen_x as f32 * self.tile_size;
-                let screen_pixel_y = screen_y as f32 * self.tile_size;
-
-                if let Some(tile) = level.get_tile(world_pos) {
-                    if tile.is_visible() {
-                        self.render_tile_at_position(
-                            game_state,
-                            world_pos,
-                            &tile.tile_type,
-                            screen_pixel_x,
-                            screen_pixel_y,
-                            false,
-                        );
-                    } else if tile.is_explored() {
-                        // Render explored but not visible tiles in darker color
-                        self.render_tile_at_position(
-                            game_state,
-                            world_pos,
-                            &tile.tile_type,
-                            screen_pixel_x,
-                            screen_pixel_y,
-                            true,
-                        );
-                    }
-                    // Don't render unexplored tiles (leave them black)
+                let (character, base_color) =
+                    self.terrain_glyph(Some(level), world_pos, &tile.tile_type);
+                let (x, y) = (
+                    screen_x as f32 * self.tile_size,
+                    screen_y as f32 * self.tile_size,
+                );
+                let quad = self.glyph_quad(character, base_color, brightness, x, y);
+                if let Some((texture, quad)) = quad {
+                    builder.push(texture, quad);
                 }
             }
         }
+        builder.build(key)
+    }
 
-        Ok(())
+    /// Renders the tile at a world position, if it is inside the viewport.
+    fn render_world_tile(
+        &self,
+        game_state: &GameState,
+        level: &Level,
+        world_pos: Position,
+        brightness: f32,
+    ) {
+        let screen_x = world_pos.x - self.viewport_x;
+        let screen_y = world_pos.y - self.viewport_y;
+        if !(0..self.map_width).contains(&screen_x) || !(0..self.map_height).contains(&screen_y) {
+            return;
+        }
+        if let Some(tile) = level.get_tile(world_pos) {
+            self.render_tile_at_position(
+                game_state,
+                world_pos,
+                &tile.tile_type,
+                screen_x as f32 * self.tile_size,
+                screen_y as f32 * self.tile_size,
+                brightness,
+            );
+        }
     }
 
     /// Renders a tile at the given screen position.
@@ -245,85 +823,342 @@ impl MacroquadDisplay {
         tile_type: &TileType,
         screen_x: f32,
         screen_y: f32,
-        is_explored_only: bool,
+        brightness: f32,
     ) {
-        // Check if there's an entity at this position
+        // Check if there's an entity at this position (sliding entities are drawn by the animation pass)
         if let Some(entity_id) = game_state.get_entity_at_position(world_pos) {
-            if let Some(entity) = game_state.entities.get(&entity_id) {
-                let (character, base_color) = match entity {
-                    ConcreteEntity::Player(_) => ('@', YELLOW),
-                };
+            let is_sliding = self
+                .animations
+                .entity_position(entity_id, Instant::now())
+                .is_some();
+            if let Some(entity) = game_state.entities.get(&entity_id).filter(|_| !is_sliding) {
+                let (character, base_color) = self.entity_glyph(entity);
+
+                self.draw_glyph(character, base_color, brightness, screen_x, screen_y);
+                return;
+            }
+        }
 
-                let color = if is_explored_only {
-                    Color::new(
-                        base_color.r * 0.4,
-                        base_color.g * 0.4,
-                        base_color.b * 0.4,
-                        base_color.a,
-                    )
-                } else {
-                    base_color
-                };
+        // No entity, draw any chest standing here
+        let level = game_state.world.current_level();
+        if let Some(container) = level.and_then(|level| level.container_at(world_pos)) {
+            let color = self.theme.color(ColorRole::Container);
+            self.draw_glyph(
+                container.display_char(),
+                color,
+                brightness,
+                screen_x,
+                screen_y,
+            );
+            return;
+        }
+
+        // Otherwise draw the item on top of any pile lying here
+        if let Some(stack) = level.and_then(|level| level.ground_items.top_at(world_pos)) {
+            let character = stack.item.item_type.display_char();
+            let color = self.theme.color(ColorRole::Item);
+            self.draw_glyph(character, color, brightness, screen_x, screen_y);
+            return;
+        }
+
+        let (character, base_color) = self.terrain_glyph(level, world_pos, tile_type);
+        self.draw_glyph(character, base_color, brightness, screen_x, screen_y);
+    }
+
+    /// Gets the character and color used to draw a tile's terrain.
+    fn terrain_glyph(
+        &self,
+        level: Option<&Level>,
+        world_pos: Position,
+        tile_type: &TileType,
+    ) -> (char, Color) {
+        // Render the tile in the level's palette, tinted by any light source on it
+        let palette = level.map(|level| level.palette).unwrap_or_default();
+        let (mut character, mut base_color) = self.get_tile_display_data(tile_type, palette);
+        if let Some(source) = level.and_then(|level| level.light_source_at(world_pos)) {
+            base_color = Self::light_color(source.kind);
+        }
+        // Overlays tint the tile, and stand in for the glyph of bare floor
+        let overlay = level
+            .and_then(|level| level.get_tile(world_pos))
+            .and_then(|tile| tile.overlay);
+        if let Some(overlay) = overlay {
+            if *tile_type == TileType::Floor {
+                character = overlay.to_char();
+            }
+            if !self.high_contrast {
+                base_color = Self::overlay_tint(base_color, overlay);
+            }
+        }
+        (character, base_color)
+    }
+
+    /// Queues animations for the visual effects of a game event.
+    ///
+    /// Only the presentation is affected; the event has already been applied.
+    pub fn animate_event(&mut self, event: &GameEvent, game_state: &GameState) {
+        let now = Instant::now();
+        match event {
+            GameEvent::EntityMoved {
+                entity_id,
+                from,
+                to,
+            } => {
+                self.animations.push_move(*entity_id, *from, *to, now);
+            }
+            GameEvent::EntityDamaged {
+                entity_id, damage, ..
+            } => {
+                if let Some(position) = game_state.get_entity_position(*entity_id) {
+                    self.animations
+                        .push_damage(*entity_id, position, *damage, now);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Skips any animations still playing.
+    pub fn skip_animations(&mut self) {
+        self.animations.skip();
+    }
 
-                if let Some(texture) = self.tile_textures.get(&character) {
-                    draw_texture_ex(
-                        *texture,
+    /// Converts fractional world tile coordinates to screen pixels.
+    fn world_to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.viewport_x as f32) * self.tile_size,
+            (y - self.viewport_y as f32) * self.tile_size,
+        )
+    }
+
+    /// Draws sliding entities, hit flashes, and floating damage numbers.
+    fn render_animations(&self, game_state: &GameState, now: Instant) {
+        for animation in self.animations.animations() {
+            match &animation.kind {
+                AnimationKind::Move { entity_id, .. } => {
+                    let Some((x, y)) = self.animations.entity_position(*entity_id, now) else {
+                        continue;
+                    };
+                    let Some(entity) = game_state.entities.get(entity_id) else {
+                        continue;
+                    };
+                    let (character, base_color) = self.entity_glyph(entity);
+                    let (screen_x, screen_y) = self.world_to_screen(x, y);
+                    self.draw_glyph(character, base_color, 1.0, screen_x, screen_y);
+                }
+                AnimationKind::Flash { entity_id } => {
+                    let Some(position) = game_state.get_entity_position(*entity_id) else {
+                        continue;
+                    };
+                    let (x, y) = self
+                        .animations
+                        .entity_position(*entity_id, now)
+                        .unwrap_or((position.x as f32, position.y as f32));
+                    let (screen_x, screen_y) = self.world_to_screen(x, y);
+                    let intensity = self.animations.flash_intensity(*entity_id, now);
+                    draw_rectangle(
                         screen_x,
                         screen_y,
-                        color,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(self.tile_size, self.tile_size)),
-                            ..Default::default()
-                        },
+                        self.tile_size,
+                        self.tile_size,
+                        Color::new(1.0, 0.1, 0.1, 0.6 * intensity),
+                    );
+                }
+                AnimationKind::DamageNumber { position, amount } => {
+                    let progress = animation.progress(now);
+                    let (screen_x, screen_y) = self.world_to_screen(
+                        position.x as f32,
+                        position.y as f32 - DAMAGE_NUMBER_RISE * progress,
+                    );
+                    draw_text(
+                        &amount.to_string(),
+                        screen_x + self.tile_size * 0.25,
+                        screen_y,
+                        self.tile_size * 0.8,
+                        Color::new(1.0, 0.3, 0.3, 1.0 - progress),
                     );
                 }
-                return;
             }
         }
+    }
 
-        // No entity, render the tile
-        let (character, base_color) = self.get_tile_display_data(tile_type);
-        let color = if is_explored_only {
+    /// Draws a glyph at a screen position.
+    ///
+    /// Uses the tileset sprite when the glyph is mapped, otherwise a tile tinted
+    /// with `base_color`. The color is scaled by `brightness`, which is lower for
+    /// dark and remembered tiles.
+    fn draw_glyph(
+        &self,
+        character: char,
+        base_color: Color,
+        brightness: f32,
+        screen_x: f32,
+        screen_y: f32,
+    ) {
+        if let Some((texture, quad)) =
+            self.glyph_quad(character, base_color, brightness, screen_x, screen_y)
+        {
+            let params = DrawTextureParams {
+                dest_size: Some(vec2(quad.size, quad.size)),
+                source: quad.source,
+                ..Default::default()
+            };
+            draw_texture_ex(texture, quad.x, quad.y, quad.color, params);
+        }
+    }
+
+    /// Gets the texture and quad [`Self::draw_glyph`] would draw, or `None`
+    /// if the glyph has no texture.
+    fn glyph_quad(
+        &self,
+        character: char,
+        base_color: Color,
+        brightness: f32,
+        screen_x: f32,
+        screen_y: f32,
+    ) -> Option<(Texture2D, TileQuad)> {
+        // High contrast keeps remembered and dimly lit tiles easy to make out
+        let brightness = if self.high_contrast {
+            brightness.max(HIGH_CONTRAST_BRIGHTNESS)
+        } else {
+            brightness
+        };
+        let dim = |color: Color| {
             Color::new(
-                base_color.r * 0.4,
-                base_color.g * 0.4,
-                base_color.b * 0.4,
-                base_color.a,
+                color.r * brightness,
+                color.g * brightness,
+                color.b * brightness,
+                color.a,
             )
-        } else {
-            base_color
+        };
+        let quad = |source, color| TileQuad {
+            x: screen_x,
+            y: screen_y,
+            size: self.tile_size,
+            source,
+            color,
         };
 
-        if let Some(texture) = self.tile_textures.get(&character) {
-            draw_texture_ex(
-                *texture,
-                screen_x,
-                screen_y,
-                color,
-                DrawTextureParams {
-                    dest_size: Some(vec2(self.tile_size, self.tile_size)),
-                    ..Default::default()
-                },
-            );
+        if let Some(tileset) = &self.tileset {
+            if let Some(source) = tileset.source_rect(character) {
+                return Some((tileset.texture, quad(Some(source), dim(WHITE))));
+            }
+        }
+
+        let texture = self.tile_textures.get(&character)?;
+        Some((*texture, quad(None, dim(base_color))))
+    }
+
+    /// Gets the character and color used to draw an entity.
+    fn entity_glyph(&self, entity: &ConcreteEntity) -> (char, Color) {
+        let entity = entity.as_entity();
+        let role = match entity.entity_type() {
+            EntityType::Player => ColorRole::Player,
+            EntityType::Monster(_) => ColorRole::Monster,
+            EntityType::Item(_) => ColorRole::Item,
+            EntityType::Npc => ColorRole::Npc,
+            EntityType::LldmGenerated { .. } => ColorRole::Lldm,
+        };
+        let color = self.theme.color(role);
+        (entity.display_char(), color)
+    }
+
+    /// Gets the color a light source tints the tile it sits on.
+    fn light_color(kind: LightKind) -> Color {
+        match kind {
+            LightKind::Torch => ORANGE,
+            LightKind::Fungus => Color::new(0.4, 0.9, 0.6, 1.0),
+            LightKind::Lantern => YELLOW,
         }
     }
 
+    /// Gets the wall and floor colors for a level palette.
+    fn palette_colors(palette: TilePalette) -> (Color, Color) {
+        match palette {
+            TilePalette::Stone => (WHITE, GRAY),
+            TilePalette::Moss => (
+                Color::new(0.55, 0.75, 0.5, 1.0),
+                Color::new(0.35, 0.5, 0.35, 1.0),
+            ),
+            TilePalette::Crypt => (
+                Color::new(0.85, 0.82, 0.7, 1.0),
+                Color::new(0.55, 0.52, 0.45, 1.0),
+            ),
+            TilePalette::Ember => (
+                Color::new(0.9, 0.5, 0.3, 1.0),
+                Color::new(0.55, 0.35, 0.3, 1.0),
+            ),
+            TilePalette::Abyss => (
+                Color::new(0.6, 0.45, 0.85, 1.0),
+                Color::new(0.35, 0.3, 0.5, 1.0),
+            ),
+        }
+    }
+
+    /// Blends a tile's color halfway towards the color of an overlay on it.
+    fn overlay_tint(color: Color, overlay: TileOverlay) -> Color {
+        let tint = match overlay {
+            TileOverlay::Blood => Color::new(0.6, 0.05, 0.05, 1.0),
+            TileOverlay::Scorch => Color::new(0.15, 0.12, 0.1, 1.0),
+            TileOverlay::Rubble => Color::new(0.6, 0.55, 0.45, 1.0),
+        };
+        Color::new(
+            (color.r + tint.r) / 2.0,
+            (color.g + tint.g) / 2.0,
+            (color.b + tint.b) / 2.0,
+            color.a,
+        )
+    }
+
     /// Gets the display character and color for a tile type.
-    fn get_tile_display_data(&self, tile_type: &TileType) -> (char, Color) {
+    fn get_tile_display_data(&self, tile_type: &TileType, palette: TilePalette) -> (char, Color) {
+        let (wall_color, floor_color) = if self.high_contrast {
+            (WHITE, Color::new(0.45, 0.45, 0.45, 1.0))
+        } else if self.theme.level_palettes {
+            Self::palette_colors(palette)
+        } else {
+            (
+                self.theme.color(ColorRole::Wall),
+                self.theme.color(ColorRole::Floor),
+            )
+        };
+        let color = |role| self.theme.color(role);
         match tile_type {
-            TileType::Wall => ('#', WHITE),
-            TileType::Floor => ('.', GRAY),
+            TileType::Wall | TileType::SecretDoor => ('#', wall_color),
+            TileType::Floor => ('.', floor_color),
             TileType::Door { is_open } => {
                 if *is_open {
-                    ('\'', YELLOW)
+                    ('\'', color(ColorRole::Door))
                 } else {
-                    ('+', YELLOW)
+                    ('+', color(ColorRole::Door))
                 }
             }
-            TileType::StairsUp => ('<', LIGHTGRAY),
-            TileType::StairsDown => ('>', ORANGE),
-            TileType::Water => ('~', BLUE),
-            TileType::Special { .. } => ('*', MAGENTA),
+            TileType::StairsUp => ('<', color(ColorRole::StairsUp)),
+            TileType::StairsDown => ('>', color(ColorRole::StairsDown)),
+            TileType::Water => ('~', color(ColorRole::Water)),
+            TileType::Lava => (';', color(ColorRole::Lava)),
+            TileType::Chasm => ('-', color(ColorRole::Chasm)),
+            TileType::Special { effect, .. } => match effect {
+                // Traps pass for floor until they are found
+                Some(effect) if effect.is_hidden() => ('.', floor_color),
+                Some(effect @ TileEffect::HealFountain { .. }) => {
+                    (effect.to_char(), color(ColorRole::Fountain))
+                }
+                Some(effect @ TileEffect::DryFountain) => {
+                    (effect.to_char(), color(ColorRole::DryFountain))
+                }
+                Some(effect @ TileEffect::Trap { .. }) => {
+                    (effect.to_char(), color(ColorRole::Trap))
+                }
+                Some(effect @ TileEffect::Plaque { .. }) => {
+                    (effect.to_char(), color(ColorRole::Plaque))
+                }
+                Some(effect @ TileEffect::Teleporter { .. }) => {
+                    (effect.to_char(), color(ColorRole::Teleporter))
+                }
+                Some(effect @ TileEffect::Altar) => (effect.to_char(), color(ColorRole::Special)),
+                None => ('*', color(ColorRole::Special)),
+            },
         }
     }
 
@@ -332,12 +1167,11 @@ impl MacroquadDisplay {
         let panel_x = self.map_width as f32 * self.tile_size + 10.0;
         let panel_width = self.ui_panel_width - 20.0; // Leave margins
         let mut line_y = 20.0;
-        
+
         // Responsive font sizes and spacing
-        let scale_factor = (self.screen_width / 1024.0).max(0.7).min(1.3);
-        let title_font_size = 24.0 * scale_factor;
-        let normal_font_size = 16.0 * scale_factor;
-        let line_height = 18.0 * scale_factor;
+        let title_font_size = self.scale.font(24.0);
+        let normal_font_size = self.scale.font(16.0);
+        let line_height = ScreenLayout::line_height(self.scale);
 
         // Render panel background
         draw_rectangle(
@@ -355,7 +1189,7 @@ impl MacroquadDisplay {
         // Render player stats if available
         if let Some(player) = game_state.get_player() {
             self.draw_wrapped_text(
-                &format!("Player: {}", player.name),
+                &format!("Player: {} ({})", player.name, player.class.display_name()),
                 panel_x,
                 line_y,
                 normal_font_size,
@@ -387,6 +1221,61 @@ impl MacroquadDisplay {
             );
             line_y += line_height;
 
+            let ability = player.class.ability();
+            let cooldown = player
+                .ability_ready_turn
+                .saturating_sub(game_state.turn_number);
+            self.draw_wrapped_text(
+                &if cooldown > 0 {
+                    format!("{}: {} turns", ability.display_name(), cooldown)
+                } else {
+                    format!("{}: Ready (Shift+C)", ability.display_name())
+                },
+                panel_x,
+                line_y,
+                normal_font_size,
+                if cooldown > 0 { GRAY } else { GREEN },
+                panel_width,
+            );
+            line_y += line_height;
+
+            if let Some((launcher, ammo)) = game_state.player_launcher() {
+                let count = game_state.shots_ready();
+                self.draw_wrapped_text(
+                    &format!("{}: {} {}", launcher.name, count, ammo.plural_name()),
+                    panel_x,
+                    line_y,
+                    normal_font_size,
+                    if count > 0 { WHITE } else { GRAY },
+                    panel_width,
+                );
+                line_y += line_height;
+            }
+
+            if !player.statuses.is_empty() {
+                let statuses: Vec<String> = player
+                    .statuses
+                    .effects
+                    .iter()
+                    .map(|effect| {
+                        format!(
+                            "{} ({})",
+                            effect.kind.display_name(),
+                            effect.turns_remaining
+                        )
+                    })
+                    .collect();
+                self.draw_wrapped_text(
+                    &statuses.join(", "),
+                    panel_x,
+                    line_y,
+                    normal_font_size,
+                    SKYBLUE,
+                    panel_width,
+                );
+                line_y += line_height;
+            }
+
             self.draw_wrapped_text(
                 &format!("Dungeon Level: {}", game_state.world.current_level_id + 1),
                 panel_x,
@@ -397,6 +1286,16 @@ impl MacroquadDisplay {
             );
             line_y += line_height;
 
+            self.draw_wrapped_text(
+                &game_state.current_exploration().summary(),
+                panel_x,
+                line_y,
+                normal_font_size,
+                LIGHTGRAY,
+                panel_width,
+            );
+            line_y += line_height;
+
             self.draw_wrapped_text(
                 &format!("Character Level: {}", player.stats.level),
                 panel_x,
@@ -430,25 +1329,11 @@ impl MacroquadDisplay {
             // Show tile information
             if let Some(level) = game_state.world.current_level() {
                 if let Some(tile) = level.get_tile(player.position()) {
-                    let tile_name = match &tile.tile_type {
-                        TileType::Floor => "Floor",
-                        TileType::Wall => "Wall",
-                        TileType::Door { is_open } => {
-                            if *is_open {
-                                "Open Door"
-                            } else {
-                                "Closed Door"
-                            }
-                        }
-                        TileType::StairsUp => "Stairs Up",
-                        TileType::StairsDown => "Stairs Down",
-                        TileType::Water => "Water",
-                        TileType::Special { .. } => "Special",
-                    };
+                    let tile_name = tile.tile_type.display_name();
 
                     let tile_color = match &tile.tile_type {
-                        TileType::StairsUp => LIGHTGRAY,
-                        TileType::StairsDown => ORANGE,
+                        TileType::StairsUp => self.theme.color(ColorRole::StairsUp),
+                        TileType::StairsDown => self.theme.color(ColorRole::StairsDown),
                         _ => WHITE,
                     };
 
@@ -467,7 +1352,14 @@ impl MacroquadDisplay {
 
         // Render game info
         let time_info = game_state.get_game_time_info();
-        self.draw_wrapped_text("Game Info:", panel_x, line_y, normal_font_size, SKYBLUE, panel_width);
+        self.draw_wrapped_text(
+            "Game Info:",
+            panel_x,
+            line_y,
+            normal_font_size,
+            SKYBLUE,
+            panel_width,
+        );
         line_y += line_height;
 
         self.draw_wrapped_text(
@@ -491,19 +1383,53 @@ impl MacroquadDisplay {
         line_y += line_height * 2.0;
 
         // Render controls
-        self.draw_wrapped_text("Controls:", panel_x, line_y, normal_font_size, GREEN, panel_width);
+        self.draw_wrapped_text(
+            "Controls:",
+            panel_x,
+            line_y,
+            normal_font_size,
+            GREEN,
+            panel_width,
+        );
         line_y += line_height;
 
         // Always available controls
         let basic_controls = [
             "WASD/Arrow keys: Move",
             "SPACE: Wait",
-            "ESC: Quit",
-            "F1: Help",
+            "+/-/Wheel: Zoom",
+            "Shift+Move, HJKL: Run",
+            "Ctrl+Move: Look around",
+            "Click: Travel, R-Click: Examine",
+            "G: Travel menu",
+            "X: Look",
+            "F: Search, V: Shout",
+            "Tab: Auto-fight",
+            "F2: Danger overlay",
+            "C: Character sheet",
+            "Shift+C: Class ability",
+            "Z: Cast spell",
+            "I: Inventory",
+            "U: Use, wear or remove item",
+            "T: Throw, Shift+F: Fire",
+            ",: Pick up, Enter: Interact",
+            "O: Open chest, B: Butcher",
+            "E: Talk, P: Pray, Q: Quest log",
+            "Y: Command allies",
+            "M: Message log",
+            "ESC: Menu",
+            "F1, ?: Help",
         ];
 
         for control in &basic_controls {
-            self.draw_wrapped_text(control, panel_x, line_y, normal_font_size, WHITE, panel_width);
+            self.draw_wrapped_text(
+                control,
+                panel_x,
+                line_y,
+                normal_font_size,
+                WHITE,
+                panel_width,
+            );
             line_y += line_height;
         }
 
@@ -513,19 +1439,44 @@ impl MacroquadDisplay {
                 if let Some(tile) = level.get_tile(player.position()) {
                     match tile.tile_type {
                         TileType::StairsUp => {
-                            self.draw_wrapped_text("1: Go up stairs (<)", panel_x, line_y, normal_font_size, WHITE, panel_width);
-                            line_y += line_height;
+                            self.draw_wrapped_text(
+                                "1: Go up stairs (<)",
+                                panel_x,
+                                line_y,
+                                normal_font_size,
+                                WHITE,
+                                panel_width,
+                            );
                         }
                         TileType::StairsDown => {
-                            self.draw_wrapped_text("2: Go down stairs (>)", panel_x, line_y, normal_font_size, WHITE, panel_width);
-                            line_y += line_height;
+                            self.draw_wrapped_text(
+                                "2: Go down stairs (>)",
+                                panel_x,
+                                line_y,
+                                normal_font_size,
+                                WHITE,
+                                panel_width,
+                            );
                         }
                         _ => {
                             // Show greyed out stair options when not on stairs
-                            self.draw_wrapped_text("1: Go up stairs (<)", panel_x, line_y, normal_font_size, GRAY, panel_width);
-                            line_y += line_height;
-                            self.draw_wrapped_text("2: Go down stairs (>)", panel_x, line_y, normal_font_size, GRAY, panel_width);
+                            self.draw_wrapped_text(
+                                "1: Go up stairs (<)",
+                                panel_x,
+                                line_y,
+                                normal_font_size,
+                                GRAY,
+                                panel_width,
+                            );
                             line_y += line_height;
+                            self.draw_wrapped_text(
+                                "2: Go down stairs (>)",
+                                panel_x,
+                                line_y,
+                                normal_font_size,
+                                GRAY,
+                                panel_width,
+                            );
                         }
                     }
                 }
@@ -538,13 +1489,13 @@ impl MacroquadDisplay {
     /// Renders the message area.
     fn render_messages(&self) -> ThatchResult<()> {
         // Responsive font sizes and spacing
-        let scale_factor = (self.screen_width / 1024.0).max(0.7).min(1.3);
-        let normal_font_size = 16.0 * scale_factor;
-        let line_height = 18.0 * scale_factor;
-        
-        let message_area_height = 80.0 * scale_factor;
+        let normal_font_size = self.scale.font(16.0);
+        let line_height = ScreenLayout::line_height(self.scale);
+
+        let message_area_height =
+            ScreenLayout::message_area_height(self.scale, self.visible_messages);
         let message_area_y = self.screen_height - message_area_height;
-        let message_count = 3; // Show last 3 messages
+        let message_count = self.visible_messages;
 
         // Draw background for message area
         draw_rectangle(
@@ -555,16 +1506,14 @@ impl MacroquadDisplay {
             Color::new(0.0, 0.0, 0.0, 0.8),
         );
 
-        // Render messages
-        let start_index = if self.messages.len() > message_count {
-            self.messages.len() - message_count
-        } else {
-            0
-        };
+        // Render the most recent messages that get past the channel filter
+        let recent: Vec<&LoggedMessage> =
+            self.messages.visible().rev().take(message_count).collect();
 
-        for (i, message) in self.messages.iter().skip(start_index).enumerate() {
+        for (i, message) in recent.iter().rev().enumerate() {
             let y = message_area_y + i as f32 * line_height;
-            draw_text(message, 10.0, y, normal_font_size, WHITE);
+            let color = UI::message_color(message, &self.theme);
+            draw_text(&message.text, 10.0, y, normal_font_size, color);
         }
 
         Ok(())
@@ -577,13 +1526,64 @@ impl MacroquadDisplay {
         self.ui.render_touch_controls()
     }
 
-    /// Adds a message to the message history.
+    /// Adds a system message to the message history.
     pub fn add_message(&mut self, message: String) {
-        self.messages.push(message);
+        self.add_game_message(message, MessageImportance::Normal, MessageChannel::System);
+    }
+
+    /// Adds a message to the message history on its channel.
+    pub fn add_game_message(
+        &mut self,
+        message: String,
+        importance: MessageImportance,
+        channel: MessageChannel,
+    ) {
+        self.messages
+            .push(LoggedMessage::new(message, importance, channel));
+    }
+}
+
+impl Frontend for MacroquadDisplay {
+    async fn render_game(&mut self, game_state: &GameState) -> ThatchResult<()> {
+        MacroquadDisplay::render_game(self, game_state).await
+    }
+
+    fn poll_input(&mut self, input_handler: &mut InputHandler) -> Option<PlayerInput> {
+        let gesture_input = input_handler.get_gesture_input(|x, y| self.screen_tile_position(x, y));
+        let touch_input = self.get_touch_input();
+        if touch_input.is_some() {
+            // A touch on an on-screen button is not a gesture on the map
+            input_handler.gestures.cancel();
+        }
 
-        // Keep only the most recent messages
-        if self.messages.len() > self.max_messages {
-            self.messages.remove(0);
+        // Touches also arrive as mouse clicks, which gestures already handle
+        let mouse_input = if touches().is_empty() {
+            input_handler.get_mouse_input(self.mouse_tile_position())
+        } else {
+            None
+        };
+
+        input_handler
+            .get_input_with_touch(touch_input.or(gesture_input))
+            .or(mouse_input)
+    }
+
+    async fn show_ending(
+        &mut self,
+        completion_state: &GameCompletionState,
+        summary: &RunSummary,
+        morgue_path: Option<&Path>,
+    ) -> ThatchResult<()> {
+        self.ui
+            .render_ending_screen(completion_state, summary)
+            .await?;
+        if let Some(path) = morgue_path {
+            self.ui.render_morgue_reference(path);
         }
+        Ok(())
+    }
+
+    fn push_message(&mut self, message: String) {
+        self.add_message(message);
     }
 }
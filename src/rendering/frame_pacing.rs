@@ -0,0 +1,116 @@
+//! # Frame Pacing
+//!
+//! Slowing the main loop down while nothing on screen is changing.
+//!
+//! Menus and buttons are drawn and clicked in the same pass, so every loop
+//! still redraws the scene, but once there has been no input, animation,
+//! window resize or automatic movement for [`IDLE_GRACE`], the loop waits out
+//! the rest of [`IDLE_FRAME_TIME`] before the next frame. The first sign of
+//! activity brings it straight back to full speed, which keeps a game left
+//! open on a laptop or phone from draining the battery.
+
+use std::time::{Duration, Instant};
+
+/// How long the screen keeps redrawing at full speed after the last activity.
+pub const IDLE_GRACE: Duration = Duration::from_millis(500);
+
+/// Shortest time between frames while idle, about ten frames per second.
+pub const IDLE_FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// Decides how long each pass of the main loop waits before the next frame.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use thatch::{FramePacer, IDLE_FRAME_TIME, IDLE_GRACE};
+///
+/// let start = Instant::now();
+/// let mut pacer = FramePacer::new(start);
+/// assert_eq!(pacer.idle_wait(start, start), None);
+///
+/// let later = start + IDLE_GRACE;
+/// assert_eq!(pacer.idle_wait(later, later), Some(IDLE_FRAME_TIME));
+///
+/// pacer.mark_dirty(later);
+/// assert_eq!(pacer.idle_wait(later, later), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    /// When something last changed on screen
+    last_activity: Instant,
+    /// Window size the last frame was drawn at
+    screen_size: (f32, f32),
+}
+
+impl FramePacer {
+    /// Creates a pacer that starts out at full speed.
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_activity: now,
+            screen_size: (0.0, 0.0),
+        }
+    }
+
+    /// Notes that something changed and the screen should redraw at full speed.
+    pub fn mark_dirty(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    /// Notes the window size, marking the screen dirty if it changed.
+    pub fn note_screen_size(&mut self, width: f32, height: f32, now: Instant) {
+        if self.screen_size != (width, height) {
+            self.screen_size = (width, height);
+            self.mark_dirty(now);
+        }
+    }
+
+    /// Returns true once nothing has changed for [`IDLE_GRACE`].
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_activity) >= IDLE_GRACE
+    }
+
+    /// Gets how long to wait before the next frame of a pass that started at
+    /// `frame_started`, or `None` to go straight on.
+    pub fn idle_wait(&self, frame_started: Instant, now: Instant) -> Option<Duration> {
+        if !self.is_idle(now) {
+            return None;
+        }
+        IDLE_FRAME_TIME
+            .checked_sub(now.saturating_duration_since(frame_started))
+            .filter(|wait| !wait.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacer_slows_down_when_idle_and_wakes_on_activity() {
+        let start = Instant::now();
+        let mut pacer = FramePacer::new(start);
+        pacer.note_screen_size(800.0, 600.0, start);
+
+        // Full speed until the grace period runs out
+        let soon = start + IDLE_GRACE / 2;
+        assert!(!pacer.is_idle(soon));
+        assert_eq!(pacer.idle_wait(soon, soon), None);
+
+        // Then each frame is padded out to the idle frame time
+        let idle = start + IDLE_GRACE;
+        let frame_started = idle - Duration::from_millis(30);
+        assert_eq!(
+            pacer.idle_wait(frame_started, idle),
+            Some(Duration::from_millis(70))
+        );
+        assert_eq!(pacer.idle_wait(idle - IDLE_FRAME_TIME, idle), None);
+
+        // Resizing the window wakes the loop, but the same size again does not
+        pacer.note_screen_size(800.0, 600.0, idle);
+        assert!(pacer.is_idle(idle));
+        pacer.note_screen_size(1024.0, 768.0, idle);
+        assert!(!pacer.is_idle(idle));
+        assert!(pacer.is_idle(idle + IDLE_GRACE));
+    }
+}
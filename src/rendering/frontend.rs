@@ -0,0 +1,53 @@
+//! # Frontend Abstraction
+//!
+//! The boundary between game logic and a presentation backend.
+//!
+//! [`SceneManager`](crate::SceneManager) drives the game through the
+//! [`Frontend`] trait for the operations every backend must support: drawing
+//! the game, collecting player input, and showing the ending screen. Backend
+//! specific features (free-look, zoom, menus) stay on the concrete type.
+//!
+//! Macroquad is currently the only backend, selected by the default
+//! `macroquad-frontend` feature. [`ActiveFrontend`] names the backend the
+//! enabled feature selects, so alternatives can be added behind features of
+//! their own without touching the scene code.
+
+use crate::{GameCompletionState, GameState, InputHandler, PlayerInput, RunSummary, ThatchResult};
+use std::path::Path;
+
+/// Operations a presentation backend must provide to run the game.
+///
+/// Methods are async because macroquad drives rendering from an async main
+/// loop; backends without an async runtime can simply return ready futures.
+#[allow(async_fn_in_trait)] // Frontends run on the single render thread, so `Send` is not needed
+pub trait Frontend {
+    /// Draws one frame of the playing scene.
+    async fn render_game(&mut self, game_state: &GameState) -> ThatchResult<()>;
+
+    /// Collects this frame's player input, if any.
+    ///
+    /// Touch controls take priority over keyboard input, which takes priority
+    /// over mouse clicks on the map. Any count typed on the handler is applied
+    /// to the input.
+    fn poll_input(&mut self, input_handler: &mut InputHandler) -> Option<PlayerInput>;
+
+    /// Draws the ending screen for a finished run.
+    ///
+    /// `morgue_path` points at the character dump written for the run, if any.
+    async fn show_ending(
+        &mut self,
+        completion_state: &GameCompletionState,
+        summary: &RunSummary,
+        morgue_path: Option<&Path>,
+    ) -> ThatchResult<()>;
+
+    /// Appends a line to the message log.
+    fn push_message(&mut self, message: String);
+}
+
+/// The backend compiled into this build.
+#[cfg(feature = "macroquad-frontend")]
+pub type ActiveFrontend = crate::MacroquadDisplay;
+
+#[cfg(not(feature = "macroquad-frontend"))]
+compile_error!("thatch needs a frontend feature; enable `macroquad-frontend`");
@@ -0,0 +1,159 @@
+//! # Message Log
+//!
+//! The history of messages shown to the player, filtered by channel.
+//!
+//! Every message carries a [`MessageChannel`] and a [`MessageImportance`].
+//! The player can mute channels from the message log overlay to cut down on
+//! noise such as combat chatter; muted messages are still kept, so unmuting a
+//! channel brings them back. Critical messages are always shown, whatever
+//! their channel.
+
+use crate::{MessageChannel, MessageImportance};
+use std::collections::{HashSet, VecDeque};
+
+/// Messages kept before the oldest are forgotten.
+pub const MESSAGE_LOG_CAPACITY: usize = 100;
+
+/// One message in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedMessage {
+    /// The message text
+    pub text: String,
+    /// How much the player needs to see it
+    pub importance: MessageImportance,
+    /// What part of the game it is about
+    pub channel: MessageChannel,
+}
+
+impl LoggedMessage {
+    /// Creates a log entry.
+    pub fn new(text: String, importance: MessageImportance, channel: MessageChannel) -> Self {
+        Self {
+            text,
+            importance,
+            channel,
+        }
+    }
+}
+
+/// The message history, with the channels the player has muted.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{LoggedMessage, MessageChannel, MessageImportance, MessageLog};
+///
+/// let mut log = MessageLog::new();
+/// let hit = "The Goblin takes 4 damage.".to_string();
+/// let death = "You have died!".to_string();
+/// let combat = MessageChannel::Combat;
+/// log.push(LoggedMessage::new(hit, MessageImportance::Normal, combat));
+/// log.push(LoggedMessage::new(death, MessageImportance::Critical, combat));
+///
+/// log.toggle_channel(combat);
+/// let shown: Vec<&str> = log.visible().map(|message| message.text.as_str()).collect();
+/// assert_eq!(shown, ["You have died!"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageLog {
+    /// Messages, oldest first
+    entries: VecDeque<LoggedMessage>,
+    /// Largest number of messages kept
+    capacity: usize,
+    /// Channels hidden from view
+    muted: HashSet<MessageChannel>,
+}
+
+impl Default for MessageLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageLog {
+    /// Creates an empty log keeping [`MESSAGE_LOG_CAPACITY`] messages, with
+    /// every channel shown.
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: MESSAGE_LOG_CAPACITY,
+            muted: HashSet::new(),
+        }
+    }
+
+    /// Adds a message, forgetting the oldest if the log is full.
+    pub fn push(&mut self, message: LoggedMessage) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(message);
+    }
+
+    /// Mutes a shown channel, or shows a muted one.
+    pub fn toggle_channel(&mut self, channel: MessageChannel) {
+        if !self.muted.remove(&channel) {
+            self.muted.insert(channel);
+        }
+    }
+
+    /// Checks if a channel is muted.
+    pub fn is_muted(&self, channel: MessageChannel) -> bool {
+        self.muted.contains(&channel)
+    }
+
+    /// Checks if a message gets past the filter.
+    pub fn shows(&self, message: &LoggedMessage) -> bool {
+        message.importance == MessageImportance::Critical || !self.is_muted(message.channel)
+    }
+
+    /// Iterates over the messages that get past the filter, oldest first.
+    pub fn visible(&self) -> impl DoubleEndedIterator<Item = &LoggedMessage> {
+        self.entries.iter().filter(|message| self.shows(message))
+    }
+
+    /// Gets the text of every message, muted or not, oldest first.
+    pub fn texts(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|message| message.text.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_muted_channels_hide_all_but_critical_messages() {
+        let mut log = MessageLog::new();
+        for index in 0..=MESSAGE_LOG_CAPACITY {
+            let channel = MessageChannel::ALL[index % MessageChannel::ALL.len()];
+            log.push(LoggedMessage::new(
+                format!("Message {}", index),
+                MessageImportance::Normal,
+                channel,
+            ));
+        }
+        assert_eq!(log.texts().len(), MESSAGE_LOG_CAPACITY);
+        assert_eq!(log.texts()[0], "Message 1");
+
+        log.push(LoggedMessage::new(
+            "You are drowning!".to_string(),
+            MessageImportance::Critical,
+            MessageChannel::Exploration,
+        ));
+        log.toggle_channel(MessageChannel::Exploration);
+        assert!(log.is_muted(MessageChannel::Exploration));
+        let exploration: Vec<&LoggedMessage> = log
+            .visible()
+            .filter(|message| message.channel == MessageChannel::Exploration)
+            .collect();
+        assert_eq!(exploration.len(), 1);
+        assert_eq!(exploration[0].text, "You are drowning!");
+
+        // Muted messages come back when the channel is shown again
+        log.toggle_channel(MessageChannel::Exploration);
+        assert_eq!(log.visible().count(), MESSAGE_LOG_CAPACITY);
+    }
+}
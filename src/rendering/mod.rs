@@ -2,10 +2,26 @@
 //!
 //! 2D graphics rendering system using macroquad for display management.
 
+pub mod animation;
 pub mod display;
+pub mod frame_pacing;
+pub mod frontend;
+pub mod message_log;
+pub mod scaling;
+pub mod theme;
+pub mod tile_batch;
+pub mod tileset;
 pub mod ui;
 
+pub use animation::*;
 pub use display::*;
+pub use frame_pacing::*;
+pub use frontend::*;
+pub use message_log::*;
+pub use scaling::*;
+pub use theme::*;
+pub use tile_batch::*;
+pub use tileset::*;
 pub use ui::*;
 
 /// Placeholder rendering system for macroquad graphics output.
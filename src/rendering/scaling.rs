@@ -0,0 +1,234 @@
+//! # Scaling
+//!
+//! Resolution-independent sizes for the screen layout, text and buttons.
+//!
+//! The interface is designed for a [`LOGICAL_WIDTH`] by [`LOGICAL_HEIGHT`]
+//! screen. [`UiScale`] turns sizes in those logical pixels into sizes for the
+//! actual window, and [`ScreenLayout`] splits the window between the map, the
+//! side panel and the message area so the panel and messages always fit, down
+//! to [`MIN_WINDOW_WIDTH`] by [`MIN_WINDOW_HEIGHT`]. The window is opened with
+//! high-DPI support, so sizes are in points and text is rasterized at the
+//! display's full resolution.
+
+use macroquad::window::{screen_height, screen_width, Conf};
+
+/// Width of the screen the interface is designed for, in logical pixels.
+pub const LOGICAL_WIDTH: f32 = 1024.0;
+
+/// Height of the screen the interface is designed for, in logical pixels.
+pub const LOGICAL_HEIGHT: f32 = 768.0;
+
+/// Narrowest window the layout is guaranteed to fit.
+pub const MIN_WINDOW_WIDTH: f32 = 640.0;
+
+/// Shortest window the layout is guaranteed to fit.
+pub const MIN_WINDOW_HEIGHT: f32 = 480.0;
+
+/// Smallest scale applied to logical sizes.
+pub const MIN_UI_SCALE: f32 = 0.6;
+
+/// Largest scale applied to logical sizes.
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+/// Smallest font size drawn, so text stays readable in small windows.
+pub const MIN_FONT_SIZE: f32 = 12.0;
+
+/// Smallest side of a touch button, so it stays easy to hit.
+pub const MIN_TOUCH_TARGET: f32 = 44.0;
+
+/// Side of a map tile at a scale and zoom of one.
+const BASE_TILE_SIZE: f32 = 24.0;
+
+/// Width of the side panel at a scale of one.
+const BASE_PANEL_WIDTH: f32 = 280.0;
+
+/// Largest share of the window width the side panel may take.
+const MAX_PANEL_SHARE: f32 = 0.4;
+
+/// Returns the window settings the game is opened with.
+///
+/// The window starts at the logical resolution, can be resized, and renders
+/// at the display's full resolution on high-DPI screens.
+pub fn window_conf() -> Conf {
+    Conf {
+        window_title: "Thatch Roguelike".to_string(),
+        window_width: LOGICAL_WIDTH as i32,
+        window_height: LOGICAL_HEIGHT as i32,
+        high_dpi: true,
+        window_resizable: true,
+        ..Conf::default()
+    }
+}
+
+/// How much logical sizes are scaled for the current window.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{UiScale, MIN_FONT_SIZE};
+///
+/// let scale = UiScale::new(2048.0, 1536.0);
+/// assert_eq!(scale.factor, 2.0);
+/// assert_eq!(scale.px(70.0), 140.0);
+///
+/// let small = UiScale::new(640.0, 480.0);
+/// assert_eq!(small.font(14.0), MIN_FONT_SIZE);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UiScale {
+    /// Window width in points
+    pub width: f32,
+    /// Window height in points
+    pub height: f32,
+    /// Multiplier from logical pixels to points
+    pub factor: f32,
+    /// Extra multiplier for font sizes, chosen by the player
+    pub text_scale: f32,
+}
+
+impl UiScale {
+    /// Computes the scale for a window, fitting the logical screen inside it.
+    pub fn new(width: f32, height: f32) -> Self {
+        let factor = (width / LOGICAL_WIDTH)
+            .min(height / LOGICAL_HEIGHT)
+            .clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        Self {
+            width,
+            height,
+            factor,
+            text_scale: 1.0,
+        }
+    }
+
+    /// Returns this scale with font sizes multiplied by `text_scale`.
+    pub fn with_text_scale(self, text_scale: f32) -> Self {
+        Self { text_scale, ..self }
+    }
+
+    /// Computes the scale for the current window.
+    pub fn current() -> Self {
+        Self::new(screen_width(), screen_height())
+    }
+
+    /// Scales a length in logical pixels.
+    pub fn px(self, logical: f32) -> f32 {
+        logical * self.factor
+    }
+
+    /// Scales a font size in logical pixels, never going below [`MIN_FONT_SIZE`].
+    ///
+    /// The player's text scale applies on top of the window scale.
+    pub fn font(self, logical: f32) -> f32 {
+        (self.px(logical) * self.text_scale)
+            .max(MIN_FONT_SIZE)
+            .round()
+    }
+
+    /// Scales the side of a touch button, never going below [`MIN_TOUCH_TARGET`].
+    pub fn touch_target(self, logical: f32) -> f32 {
+        self.px(logical).max(MIN_TOUCH_TARGET)
+    }
+
+    /// Returns true if the window is smaller than the layout is designed to fit.
+    pub fn is_below_minimum(self) -> bool {
+        self.width < MIN_WINDOW_WIDTH || self.height < MIN_WINDOW_HEIGHT
+    }
+}
+
+/// Where the map, side panel and message area go in the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenLayout {
+    /// Side of a map tile in points
+    pub tile_size: f32,
+    /// Map viewport width in tiles
+    pub map_width: i32,
+    /// Map viewport height in tiles
+    pub map_height: i32,
+    /// Side panel width in points
+    pub panel_width: f32,
+    /// Message area height in points
+    pub message_area_height: f32,
+}
+
+impl ScreenLayout {
+    /// Lays out a window at a scale, map zoom and number of message lines.
+    ///
+    /// The side panel and message area are sized first and the map gets the
+    /// rest, so the map never runs under them.
+    pub fn new(scale: UiScale, zoom: f32, message_lines: usize) -> Self {
+        let tile_size = scale.px(BASE_TILE_SIZE) * zoom;
+        let panel_width = scale
+            .px(BASE_PANEL_WIDTH)
+            .min(scale.width * MAX_PANEL_SHARE);
+        let message_area_height = Self::message_area_height(scale, message_lines);
+
+        let map_width = ((scale.width - panel_width) / tile_size) as i32;
+        let map_height = ((scale.height - message_area_height) / tile_size) as i32;
+        Self {
+            tile_size,
+            map_width: map_width.max(1),
+            map_height: map_height.max(1),
+            panel_width,
+            message_area_height,
+        }
+    }
+
+    /// Height of the message area showing `lines` messages.
+    pub fn message_area_height(scale: UiScale, lines: usize) -> f32 {
+        let line_height = Self::line_height(scale);
+        (scale.px(26.0) + lines as f32 * line_height).max(scale.px(80.0))
+    }
+
+    /// Height of one line of panel or message text.
+    pub fn line_height(scale: UiScale) -> f32 {
+        scale.font(16.0) + scale.px(2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_fits_the_panel_and_messages_in_any_window() {
+        let windows = [
+            (MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT),
+            (LOGICAL_WIDTH, LOGICAL_HEIGHT),
+            (412.0, 915.0),
+            (915.0, 412.0),
+            (2560.0, 1440.0),
+        ];
+        for (width, height) in windows {
+            let scale = UiScale::new(width, height);
+            for zoom in [crate::MIN_ZOOM, 1.0, crate::MAX_ZOOM] {
+                for lines in [crate::MIN_MESSAGE_COUNT, crate::MAX_MESSAGE_COUNT] {
+                    let layout = ScreenLayout::new(scale, zoom, lines);
+                    let map_right = layout.map_width as f32 * layout.tile_size;
+                    let map_bottom = layout.map_height as f32 * layout.tile_size;
+                    assert!(
+                        map_right + layout.panel_width <= width,
+                        "{}x{}",
+                        width,
+                        height
+                    );
+                    assert!(map_bottom + layout.message_area_height <= height);
+                    assert!(layout.map_width >= 1 && layout.map_height >= 1);
+                }
+            }
+        }
+
+        // Sizes follow the window, within limits
+        assert_eq!(UiScale::new(LOGICAL_WIDTH, LOGICAL_HEIGHT).factor, 1.0);
+        assert_eq!(UiScale::new(100.0, 100.0).factor, MIN_UI_SCALE);
+        assert!(UiScale::new(100.0, 100.0).is_below_minimum());
+        assert_eq!(
+            UiScale::new(320.0, 240.0).touch_target(70.0),
+            MIN_TOUCH_TARGET
+        );
+        let large_text = UiScale::new(LOGICAL_WIDTH, LOGICAL_HEIGHT).with_text_scale(1.5);
+        assert_eq!(large_text.font(16.0), 24.0);
+        let layout = ScreenLayout::new(large_text, 1.0, crate::MAX_MESSAGE_COUNT);
+        let map_bottom = layout.map_height as f32 * layout.tile_size;
+        assert!(map_bottom + layout.message_area_height <= LOGICAL_HEIGHT);
+    }
+}
@@ -0,0 +1,374 @@
+//! # Color Themes
+//!
+//! Colors for the map and messages, chosen by what they mean.
+//!
+//! Renderers ask a [`Theme`] for the color of a [`ColorRole`] instead of using
+//! fixed colors. A theme is a JSON file in [`config::THEMES_DIRECTORY`]
+//! mapping roles to hex colors:
+//!
+//! ```json
+//! {
+//!   "name": "Default",
+//!   "level_palettes": true,
+//!   "colors": { "wall": "#FFFFFF", "floor": "#828282", "monster": "#E62937" }
+//! }
+//! ```
+//!
+//! Roles a theme leaves out fall back to the default theme. With
+//! `level_palettes` set, walls and floors keep the tint of each level's
+//! [`TilePalette`](crate::TilePalette) instead of the theme's colors. The
+//! [`ThemePreset`]s are built into the game, so they work without an assets
+//! folder; a file of the same name on disk replaces the built-in one.
+//!
+//! [`config::THEMES_DIRECTORY`]: crate::config::THEMES_DIRECTORY
+
+use crate::{ThatchError, ThatchResult};
+use macroquad::color::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The theme presets, compiled into the game.
+const BUILTIN_THEMES: [(ThemePreset, &str); 4] = [
+    (
+        ThemePreset::Default,
+        include_str!("../../assets/themes/default.json"),
+    ),
+    (
+        ThemePreset::Dark,
+        include_str!("../../assets/themes/dark.json"),
+    ),
+    (
+        ThemePreset::Deuteranopia,
+        include_str!("../../assets/themes/deuteranopia.json"),
+    ),
+    (
+        ThemePreset::Protanopia,
+        include_str!("../../assets/themes/protanopia.json"),
+    ),
+];
+
+/// What a color is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorRole {
+    /// Walls
+    Wall,
+    /// Floors
+    Floor,
+    /// Doors, open or closed
+    Door,
+    /// Stairs leading up
+    StairsUp,
+    /// Stairs leading down
+    StairsDown,
+    /// Water
+    Water,
+    /// Lava
+    Lava,
+    /// Chasms
+    Chasm,
+    /// Fountains
+    Fountain,
+    /// Fountains that have been drunk dry
+    DryFountain,
+    /// Traps
+    Trap,
+    /// Plaques
+    Plaque,
+    /// Teleporters
+    Teleporter,
+    /// Altars and other special tiles
+    Special,
+    /// The player
+    Player,
+    /// Hostile monsters
+    Monster,
+    /// Items on the ground
+    Item,
+    /// Friendly characters
+    Npc,
+    /// Entities the dungeon master created
+    Lldm,
+    /// Chests and other containers
+    Container,
+    /// Health bar of an unhurt monster
+    HealthFull,
+    /// Health bar of a nearly dead monster
+    HealthEmpty,
+    /// Tint of tiles visible monsters can attack next turn
+    Danger,
+    /// Combat messages
+    MessageCombat,
+    /// Exploration messages
+    MessageExploration,
+    /// System messages
+    MessageSystem,
+    /// Story messages
+    MessageNarrative,
+    /// Important messages, whatever their channel
+    MessageImportant,
+    /// Critical messages, whatever their channel
+    MessageCritical,
+}
+
+impl ColorRole {
+    /// Every role
+    pub const ALL: [ColorRole; 29] = [
+        ColorRole::Wall,
+        ColorRole::Floor,
+        ColorRole::Door,
+        ColorRole::StairsUp,
+        ColorRole::StairsDown,
+        ColorRole::Water,
+        ColorRole::Lava,
+        ColorRole::Chasm,
+        ColorRole::Fountain,
+        ColorRole::DryFountain,
+        ColorRole::Trap,
+        ColorRole::Plaque,
+        ColorRole::Teleporter,
+        ColorRole::Special,
+        ColorRole::Player,
+        ColorRole::Monster,
+        ColorRole::Item,
+        ColorRole::Npc,
+        ColorRole::Lldm,
+        ColorRole::Container,
+        ColorRole::HealthFull,
+        ColorRole::HealthEmpty,
+        ColorRole::Danger,
+        ColorRole::MessageCombat,
+        ColorRole::MessageExploration,
+        ColorRole::MessageSystem,
+        ColorRole::MessageNarrative,
+        ColorRole::MessageImportant,
+        ColorRole::MessageCritical,
+    ];
+}
+
+/// The themes that can be picked in the options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemePreset {
+    /// The standard colors, with each level's own wall and floor tint
+    #[default]
+    Default,
+    /// Dimmer colors for dark rooms
+    Dark,
+    /// Blue, orange and yellow in place of red and green
+    Deuteranopia,
+    /// Like the deuteranopia theme, with bright oranges in place of reds
+    Protanopia,
+}
+
+impl ThemePreset {
+    /// Every preset, in the order the options step through them
+    pub const ALL: [ThemePreset; 4] = [
+        ThemePreset::Default,
+        ThemePreset::Dark,
+        ThemePreset::Deuteranopia,
+        ThemePreset::Protanopia,
+    ];
+
+    /// Returns a short human-readable name for the preset.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ThemePreset::Default => "Default",
+            ThemePreset::Dark => "Dark",
+            ThemePreset::Deuteranopia => "Deuteranopia-safe",
+            ThemePreset::Protanopia => "Protanopia-safe",
+        }
+    }
+
+    /// Returns the name of the preset's theme file, without its extension.
+    pub fn file_stem(self) -> &'static str {
+        match self {
+            ThemePreset::Default => "default",
+            ThemePreset::Dark => "dark",
+            ThemePreset::Deuteranopia => "deuteranopia",
+            ThemePreset::Protanopia => "protanopia",
+        }
+    }
+}
+
+/// A mapping from color roles to colors.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{ColorRole, Theme, ThemePreset};
+///
+/// let theme = Theme::builtin(ThemePreset::Deuteranopia).unwrap();
+/// let monster = theme.color(ColorRole::Monster);
+/// let default = Theme::builtin(ThemePreset::Default).unwrap();
+/// assert_ne!(monster, default.color(ColorRole::Monster));
+///
+/// let json = r##"{ "name": "Mono", "colors": { "wall": "#808080" } }"##;
+/// let custom = Theme::from_json(json).unwrap();
+/// assert_eq!(custom.color(ColorRole::Wall).r, 128.0 / 255.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    /// Name of the theme
+    pub name: String,
+    /// Whether walls and floors use each level's palette instead of the theme
+    #[serde(default)]
+    pub level_palettes: bool,
+    /// Hex colors, as `#RRGGBB`, for each role the theme sets
+    pub colors: BTreeMap<ColorRole, String>,
+    /// Parsed colors, including those taken from the default theme
+    #[serde(skip)]
+    resolved: BTreeMap<ColorRole, Color>,
+}
+
+impl Default for Theme {
+    /// Gets the built-in default theme, or an all-white one if it is
+    /// invalid, which the tests rule out.
+    fn default() -> Self {
+        Self::builtin(ThemePreset::Default).unwrap_or_else(|_| Self {
+            name: ThemePreset::Default.display_name().to_string(),
+            level_palettes: false,
+            colors: BTreeMap::new(),
+            resolved: BTreeMap::new(),
+        })
+    }
+}
+
+impl Theme {
+    /// Gets a theme built into the game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preset has no built-in theme or it is invalid.
+    pub fn builtin(preset: ThemePreset) -> ThatchResult<Self> {
+        let (_, json) = BUILTIN_THEMES
+            .iter()
+            .find(|(builtin, _)| *builtin == preset)
+            .ok_or_else(|| {
+                ThatchError::InvalidState(format!("No built-in theme for {:?}", preset))
+            })?;
+        Self::from_json(json)
+    }
+
+    /// Parses a theme from JSON, filling in missing roles from the default theme.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed or a color is not `#RRGGBB`.
+    pub fn from_json(json: &str) -> ThatchResult<Self> {
+        let mut theme: Self = serde_json::from_str(json)?;
+        for (role, hex) in &theme.colors {
+            theme.resolved.insert(*role, parse_hex_color(hex)?);
+        }
+
+        let missing: Vec<ColorRole> = ColorRole::ALL
+            .into_iter()
+            .filter(|role| !theme.resolved.contains_key(role))
+            .collect();
+        if !missing.is_empty() {
+            let default = Self::builtin(ThemePreset::Default)?;
+            for role in missing {
+                theme.resolved.insert(role, default.color(role));
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Loads a theme from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the theme is invalid.
+    pub fn load(path: &Path) -> ThatchResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Loads a preset's theme file from the themes directory, falling back to
+    /// the built-in theme if the file is missing or invalid.
+    pub fn load_preset(preset: ThemePreset) -> Self {
+        let path =
+            Path::new(crate::config::THEMES_DIRECTORY).join(format!("{}.json", preset.file_stem()));
+        Self::load(&path)
+            .or_else(|_| Self::builtin(preset))
+            .unwrap_or_default()
+    }
+
+    /// Gets the color for a role.
+    pub fn color(&self, role: ColorRole) -> Color {
+        self.resolved
+            .get(&role)
+            .copied()
+            .unwrap_or(macroquad::color::WHITE)
+    }
+
+    /// Blends between the empty and full health bar colors.
+    pub fn health_color(&self, ratio: f32) -> Color {
+        let (empty, full) = (
+            self.color(ColorRole::HealthEmpty),
+            self.color(ColorRole::HealthFull),
+        );
+        let blend = |from: f32, to: f32| from + (to - from) * ratio.clamp(0.0, 1.0);
+        Color::new(
+            blend(empty.r, full.r),
+            blend(empty.g, full.g),
+            blend(empty.b, full.b),
+            1.0,
+        )
+    }
+}
+
+/// Parses a `#RRGGBB` color.
+fn parse_hex_color(hex: &str) -> ThatchResult<Color> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let channel = |index: usize| {
+        digits
+            .get(index..index + 2)
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+    };
+    match (digits.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(Color::from_rgba(r, g, b, 255)),
+        _ => Err(ThatchError::InvalidState(format!(
+            "Theme color '{}' is not #RRGGBB",
+            hex
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes_set_every_role() {
+        for preset in ThemePreset::ALL {
+            let theme = Theme::builtin(preset).unwrap();
+            for role in ColorRole::ALL {
+                assert!(
+                    theme.colors.contains_key(&role),
+                    "{:?} lacks {:?}",
+                    preset,
+                    role
+                );
+            }
+        }
+
+        // The colorblind-safe themes keep monsters apart from NPCs and items
+        for preset in [ThemePreset::Deuteranopia, ThemePreset::Protanopia] {
+            let theme = Theme::builtin(preset).unwrap();
+            assert_ne!(
+                theme.colors[&ColorRole::Monster],
+                theme.colors[&ColorRole::Npc]
+            );
+            assert_ne!(
+                theme.colors[&ColorRole::Monster],
+                theme.colors[&ColorRole::Item]
+            );
+        }
+
+        let short = r##"{ "name": "Bad", "colors": { "wall": "#12345" } }"##;
+        assert!(Theme::from_json(short).is_err());
+        let theme = Theme::builtin(ThemePreset::Default).unwrap();
+        assert_eq!(theme.health_color(1.0), theme.color(ColorRole::HealthFull));
+        assert!(theme.level_palettes);
+    }
+}
@@ -0,0 +1,273 @@
+//! # Tile Batching
+//!
+//! Map terrain drawn as a handful of meshes instead of one texture per tile.
+//!
+//! The terrain in the viewport only changes when the viewport moves, the
+//! player's view changes, or a turn passes, so the renderer builds a
+//! [`TileBatch`] of textured quads once and draws it every frame until its
+//! [`TileBatchKey`] no longer matches. Quads sharing a texture are merged into
+//! meshes of at most [`MAX_QUADS_PER_MESH`], and quads that would come out
+//! fully black are dropped while building. Tiles with something standing on
+//! them are left out of the batch and drawn each frame, so moving entities
+//! and animations stay live.
+
+use crate::{FieldOfView, Position};
+use macroquad::models::{draw_mesh, Mesh, Vertex};
+use macroquad::prelude::{vec2, vec3, Color, Rect, Texture2D};
+
+/// Most quads merged into one mesh, keeping each mesh within a single draw call.
+pub const MAX_QUADS_PER_MESH: usize = 800;
+
+/// Everything the terrain in the viewport depends on; the batch is rebuilt
+/// when any of it changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileBatchKey {
+    /// Floor being drawn
+    pub level_id: u32,
+    /// Viewport offset and size in tiles
+    pub viewport: (i32, i32, i32, i32),
+    /// Tile size in pixels
+    pub tile_size: f32,
+    /// Turn the batch was built on
+    pub turn: u64,
+    /// The player's field of view when the batch was built
+    pub field_of_view: Option<FieldOfView>,
+    /// Whether the map was drawn in high contrast
+    pub high_contrast: bool,
+}
+
+/// One tile's sprite, tinted and placed on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileQuad {
+    /// Left edge in screen pixels
+    pub x: f32,
+    /// Top edge in screen pixels
+    pub y: f32,
+    /// Width and height in screen pixels
+    pub size: f32,
+    /// Part of the texture to draw in texture pixels, or all of it
+    pub source: Option<Rect>,
+    /// Tint, already scaled by the tile's brightness
+    pub color: Color,
+}
+
+impl TileQuad {
+    /// Returns true if the quad would be drawn pure black or not at all.
+    pub fn is_black(&self) -> bool {
+        self.color.a <= 0.0 || (self.color.r <= 0.0 && self.color.g <= 0.0 && self.color.b <= 0.0)
+    }
+}
+
+/// A tile left out of the batch because something stands on it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OccupiedTile {
+    /// Tile in the world
+    pub position: Position,
+    /// Brightness the tile is drawn at
+    pub brightness: f32,
+}
+
+/// Collects the quads for a [`TileBatch`].
+///
+/// # Examples
+///
+/// ```
+/// use macroquad::prelude::{Texture2D, BLACK, WHITE};
+/// use thatch::{TileBatchBuilder, TileQuad};
+///
+/// let mut builder = TileBatchBuilder::new();
+/// let quad = TileQuad { x: 0.0, y: 0.0, size: 16.0, source: None, color: WHITE };
+/// builder.push(Texture2D::empty(), quad);
+/// builder.push(Texture2D::empty(), TileQuad { color: BLACK, ..quad });
+/// assert_eq!(builder.quad_count(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct TileBatchBuilder {
+    groups: Vec<(Texture2D, Vec<TileQuad>)>,
+    occupied: Vec<OccupiedTile>,
+}
+
+impl TileBatchBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a quad drawn with `texture`, unless it would come out black.
+    pub fn push(&mut self, texture: Texture2D, quad: TileQuad) {
+        if quad.is_black() {
+            return;
+        }
+        match self.groups.iter_mut().find(|(group, _)| *group == texture) {
+            Some((_, quads)) => quads.push(quad),
+            None => self.groups.push((texture, vec![quad])),
+        }
+    }
+
+    /// Marks a tile as drawn each frame rather than batched.
+    pub fn push_occupied(&mut self, position: Position, brightness: f32) {
+        self.occupied.push(OccupiedTile {
+            position,
+            brightness,
+        });
+    }
+
+    /// Gets the number of quads collected so far.
+    pub fn quad_count(&self) -> usize {
+        self.groups.iter().map(|(_, quads)| quads.len()).sum()
+    }
+
+    /// Merges the collected quads into meshes for a batch built for `key`.
+    pub fn build(self, key: TileBatchKey) -> TileBatch {
+        let meshes = self
+            .groups
+            .into_iter()
+            .flat_map(|(texture, quads)| {
+                quads
+                    .chunks(MAX_QUADS_PER_MESH)
+                    .map(|chunk| quad_mesh(texture, chunk))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        TileBatch {
+            key: Some(key),
+            meshes,
+            occupied: self.occupied,
+        }
+    }
+}
+
+/// Builds one mesh of two triangles per quad.
+fn quad_mesh(texture: Texture2D, quads: &[TileQuad]) -> Mesh {
+    let mut vertices = Vec::with_capacity(quads.len() * 4);
+    let mut indices = Vec::with_capacity(quads.len() * 6);
+    for quad in quads {
+        let (u0, v0, u1, v1) = match quad.source {
+            Some(source) => (
+                source.x / texture.width(),
+                source.y / texture.height(),
+                (source.x + source.w) / texture.width(),
+                (source.y + source.h) / texture.height(),
+            ),
+            None => (0.0, 0.0, 1.0, 1.0),
+        };
+        let (x0, y0) = (quad.x, quad.y);
+        let (x1, y1) = (quad.x + quad.size, quad.y + quad.size);
+        let corner = |x, y, u, v| Vertex {
+            position: vec3(x, y, 0.0),
+            uv: vec2(u, v),
+            color: quad.color,
+        };
+        let first = vertices.len() as u16;
+        vertices.extend([
+            corner(x0, y0, u0, v0),
+            corner(x1, y0, u1, v0),
+            corner(x1, y1, u1, v1),
+            corner(x0, y1, u0, v1),
+        ]);
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+    Mesh {
+        vertices,
+        indices,
+        texture: Some(texture),
+    }
+}
+
+/// The terrain in the viewport, merged into meshes.
+#[derive(Default)]
+pub struct TileBatch {
+    key: Option<TileBatchKey>,
+    meshes: Vec<Mesh>,
+    occupied: Vec<OccupiedTile>,
+}
+
+impl TileBatch {
+    /// Creates an empty batch that needs building before use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the batch was built for `key` and can be drawn as is.
+    pub fn is_current(&self, key: &TileBatchKey) -> bool {
+        self.key.as_ref() == Some(key)
+    }
+
+    /// Forces the batch to be rebuilt before it is next drawn, for changes
+    /// the key does not cover such as a new theme or tileset.
+    pub fn invalidate(&mut self) {
+        self.key = None;
+    }
+
+    /// Gets the meshes the terrain was merged into.
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+
+    /// Gets the tiles left out of the batch because something stood on them.
+    pub fn occupied(&self) -> &[OccupiedTile] {
+        &self.occupied
+    }
+
+    /// Draws every mesh in the batch.
+    pub fn draw(&self) {
+        for mesh in &self.meshes {
+            draw_mesh(mesh);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::prelude::{BLACK, WHITE};
+
+    #[test]
+    fn test_builder_merges_quads_into_meshes_and_drops_black_ones() {
+        let key = TileBatchKey {
+            level_id: 0,
+            viewport: (0, 0, 80, 50),
+            tile_size: 16.0,
+            turn: 1,
+            field_of_view: None,
+            high_contrast: false,
+        };
+        let mut builder = TileBatchBuilder::new();
+        for index in 0..2000 {
+            let quad = TileQuad {
+                x: (index % 80) as f32 * 16.0,
+                y: (index / 80) as f32 * 16.0,
+                size: 16.0,
+                source: None,
+                color: if index % 10 == 0 { BLACK } else { WHITE },
+            };
+            builder.push(Texture2D::empty(), quad);
+        }
+        builder.push_occupied(Position::new(3, 4), 0.4);
+        assert_eq!(builder.quad_count(), 1800);
+
+        let mut batch = builder.build(key.clone());
+        let quads: Vec<usize> = batch
+            .meshes()
+            .iter()
+            .map(|mesh| mesh.vertices.len() / 4)
+            .collect();
+        assert_eq!(quads, vec![800, 800, 200]);
+        let meshes = batch.meshes();
+        assert!(meshes
+            .iter()
+            .all(|mesh| mesh.indices.len() == mesh.vertices.len() / 4 * 6));
+        let last = meshes[2].indices.iter().max().copied();
+        assert_eq!(last, Some(199 * 4 + 3));
+        assert_eq!(batch.occupied()[0].position, Position::new(3, 4));
+
+        // Any change to what the terrain depends on calls for a rebuild
+        assert!(batch.is_current(&key));
+        assert!(!batch.is_current(&TileBatchKey {
+            turn: 2,
+            ..key.clone()
+        }));
+        batch.invalidate();
+        assert!(!batch.is_current(&key));
+    }
+}
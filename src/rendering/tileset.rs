@@ -0,0 +1,183 @@
+//! # Tilesets
+//!
+//! Sprite atlas support for the map renderer.
+//!
+//! A tileset lives in `assets/tilesets/<name>/` and consists of an atlas image
+//! plus a `tileset.json` mapping that assigns display glyphs to atlas cells:
+//!
+//! ```json
+//! {
+//!   "image": "atlas.png",
+//!   "tile_width": 16,
+//!   "tile_height": 16,
+//!   "glyphs": {
+//!     "#": { "column": 0, "row": 0 },
+//!     ".": { "column": 1, "row": 0 },
+//!     "@": { "column": 0, "row": 4 }
+//!   }
+//! }
+//! ```
+//!
+//! Glyphs are the same characters used by [`TileType::to_char`](crate::TileType::to_char)
+//! and entity rendering, so any glyph without a mapping (or a missing tileset)
+//! falls back to colored glyph rendering.
+
+use crate::{ThatchError, ThatchResult};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name of the mapping file inside a tileset directory.
+pub const TILESET_MAPPING_FILE: &str = "tileset.json";
+
+/// A cell in the sprite atlas, in tile units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtlasCell {
+    /// Column of the cell, counting from the left
+    pub column: u32,
+    /// Row of the cell, counting from the top
+    pub row: u32,
+}
+
+/// Data-driven mapping from glyphs to atlas cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TilesetMapping {
+    /// Atlas image file, relative to the tileset directory
+    pub image: String,
+    /// Width of one atlas cell in pixels
+    pub tile_width: u32,
+    /// Height of one atlas cell in pixels
+    pub tile_height: u32,
+    /// Glyph to atlas cell assignments; keys must be single characters
+    pub glyphs: HashMap<String, AtlasCell>,
+}
+
+impl TilesetMapping {
+    /// Parses and validates a mapping from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed, a cell size is zero, or a
+    /// glyph key is not exactly one character.
+    pub fn from_json(json: &str) -> ThatchResult<Self> {
+        let mapping: Self = serde_json::from_str(json)?;
+
+        if mapping.tile_width == 0 || mapping.tile_height == 0 {
+            return Err(ThatchError::InvalidState(
+                "Tileset cell size must be non-zero".to_string(),
+            ));
+        }
+        if let Some(bad_key) = mapping.glyphs.keys().find(|key| key.chars().count() != 1) {
+            return Err(ThatchError::InvalidState(format!(
+                "Tileset glyph '{}' must be a single character",
+                bad_key
+            )));
+        }
+
+        Ok(mapping)
+    }
+
+    /// Builds the glyph lookup table of source rectangles in atlas pixels.
+    pub fn source_rects(&self) -> HashMap<char, Rect> {
+        self.glyphs
+            .iter()
+            .filter_map(|(key, cell)| {
+                let glyph = key.chars().next()?;
+                let rect = Rect::new(
+                    (cell.column * self.tile_width) as f32,
+                    (cell.row * self.tile_height) as f32,
+                    self.tile_width as f32,
+                    self.tile_height as f32,
+                );
+                Some((glyph, rect))
+            })
+            .collect()
+    }
+}
+
+/// A loaded tileset: atlas texture plus glyph lookup.
+pub struct Tileset {
+    /// Name of the tileset directory
+    pub name: String,
+    /// Atlas texture
+    pub texture: Texture2D,
+    /// Source rectangle for each mapped glyph
+    source_rects: HashMap<char, Rect>,
+}
+
+impl Tileset {
+    /// Loads `tilesets/<name>` from the assets folder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mapping or atlas image is missing or invalid.
+    pub async fn load(name: &str) -> ThatchResult<Self> {
+        let directory = format!("tilesets/{}", name);
+
+        let json = load_string(&format!("{}/{}", directory, TILESET_MAPPING_FILE))
+            .await
+            .map_err(|e| {
+                ThatchError::InvalidState(format!("Failed to load tileset '{}': {}", name, e))
+            })?;
+        let mapping = TilesetMapping::from_json(&json)?;
+
+        let texture = load_texture(&format!("{}/{}", directory, mapping.image))
+            .await
+            .map_err(|e| {
+                ThatchError::InvalidState(format!(
+                    "Failed to load tileset image '{}': {}",
+                    mapping.image, e
+                ))
+            })?;
+        // Keep pixel art crisp when scaled
+        texture.set_filter(FilterMode::Nearest);
+
+        Ok(Self {
+            name: name.to_string(),
+            texture,
+            source_rects: mapping.source_rects(),
+        })
+    }
+
+    /// Gets the atlas rectangle for a glyph, if the tileset maps it.
+    pub fn source_rect(&self, glyph: char) -> Option<Rect> {
+        self.source_rects.get(&glyph).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r##"{
+        "image": "atlas.png",
+        "tile_width": 16,
+        "tile_height": 8,
+        "glyphs": {
+            "#": { "column": 2, "row": 3 },
+            "@": { "column": 0, "row": 1 }
+        }
+    }"##;
+
+    #[test]
+    fn test_mapping_source_rects() {
+        let mapping = TilesetMapping::from_json(SAMPLE).unwrap();
+        let rects = mapping.source_rects();
+
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[&'#'], Rect::new(32.0, 24.0, 16.0, 8.0));
+        assert_eq!(rects[&'@'], Rect::new(0.0, 8.0, 16.0, 8.0));
+    }
+
+    #[test]
+    fn test_mapping_rejects_multi_character_glyphs() {
+        let json = SAMPLE.replace("\"#\"", "\"##\"");
+        assert!(TilesetMapping::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_mapping_rejects_zero_cell_size() {
+        let json = SAMPLE.replace("\"tile_width\": 16", "\"tile_width\": 0");
+        assert!(TilesetMapping::from_json(&json).is_err());
+    }
+}
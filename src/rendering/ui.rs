@@ -2,10 +2,26 @@
 //!
 //! UI components for health bars, inventory, messages, and other interface elements using macroquad.
 
-use crate::game::{GameCompletionState, Position, StairDirection, TileType};
-use crate::input::PlayerInput;
-use crate::ThatchResult;
+use crate::game::{
+    CharacterSheet, Conversation, GameCompletionState, InventoryCommand, InventoryEntry, Position,
+    Quest, RunSummary, Spell, StairDirection, TileType, TravelMenu,
+};
+use crate::input::{HelpSection, PlayerInput};
+use crate::rendering::{ColorRole, LoggedMessage, MessageLog, Theme, UiScale, MIN_FONT_SIZE};
+use crate::{MessageChannel, MessageImportance, ThatchResult};
 use macroquad::prelude::*;
+use std::path::Path;
+
+/// What was clicked or tapped on the inventory screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryClick {
+    /// The item at this index in the list
+    Item(usize),
+    /// A command button for the selected item
+    Command(InventoryCommand),
+    /// The close button
+    Close,
+}
 
 /// UI component for rendering game screens.
 pub struct UI;
@@ -69,22 +85,6 @@ impl UI {
             SKYBLUE,
         );
 
-        // Controls
-        draw_text(
-            "Press 'N' for New Game",
-            center_x - 120.0,
-            center_y + 70.0,
-            20.0,
-            GREEN,
-        );
-        draw_text(
-            "Press 'ESC' to Quit",
-            center_x - 120.0,
-            center_y + 90.0,
-            20.0,
-            GREEN,
-        );
-
         Ok(())
     }
 
@@ -106,14 +106,14 @@ impl UI {
 
         // Story text
         draw_text(
-            "You have conquered the deepest depths of the ancient",
+            "You climb into the daylight with the Amulet of Ages",
             center_x - 300.0,
             center_y - 70.0,
             20.0,
             WHITE,
         );
         draw_text(
-            "dungeon! The treasures of 26 levels are yours, and",
+            "clutched in your hand. Its power is yours at last, and",
             center_x - 300.0,
             center_y - 50.0,
             20.0,
@@ -135,22 +135,6 @@ impl UI {
             YELLOW,
         );
 
-        // Controls
-        draw_text(
-            "Press 'N' for New Game",
-            center_x - 120.0,
-            center_y + 70.0,
-            20.0,
-            GREEN,
-        );
-        draw_text(
-            "Press 'ESC' to Quit",
-            center_x - 120.0,
-            center_y + 90.0,
-            20.0,
-            GREEN,
-        );
-
         Ok(())
     }
 
@@ -201,22 +185,6 @@ impl UI {
             DARKGRAY,
         );
 
-        // Controls
-        draw_text(
-            "Press 'N' for New Game",
-            center_x - 120.0,
-            center_y + 70.0,
-            20.0,
-            GREEN,
-        );
-        draw_text(
-            "Press 'ESC' to Quit",
-            center_x - 120.0,
-            center_y + 90.0,
-            20.0,
-            GREEN,
-        );
-
         Ok(())
     }
 
@@ -224,22 +192,30 @@ impl UI {
     pub fn render_tile_tooltip(&self, tile_type: &TileType, x: f32, y: f32) -> ThatchResult<()> {
         let tooltip_text = match tile_type {
             TileType::StairsUp => {
-                "Stairs Up - Press '1' to ascend (Warning: Exiting at level 1 ends the game!)"
+                "Stairs Up - Press '<' to ascend (Warning: Exiting at level 1 ends the game!)"
             }
-            TileType::StairsDown => "Stairs Down - Press '2' to descend to the next level",
+            TileType::StairsDown => "Stairs Down - Press '>' to descend to the next level",
             TileType::Door { is_open } => {
                 if *is_open {
-                    "Open Door - Press 'C' to close"
+                    "Open Door - Press Enter to close"
                 } else {
-                    "Closed Door - Press 'O' to open"
+                    "Closed Door - Press Enter to open"
                 }
             }
-            TileType::Special { description } => description,
+            TileType::Special { description, .. } => description,
             _ => return Ok(()), // No tooltip for regular tiles
         };
 
-        // Render tooltip box with background
-        let text_width = tooltip_text.len() as f32 * 8.0;
+        self.render_tooltip(tooltip_text, x, y);
+        Ok(())
+    }
+
+    /// Renders a line of text in a tooltip box just above a screen point.
+    ///
+    /// The box is moved left if it would run off the right of the screen.
+    pub fn render_tooltip(&self, text: &str, x: f32, y: f32) {
+        let text_width = measure_text(text, None, 16, 1.0).width;
+        let x = x.min(screen_width() - text_width - 10.0).max(0.0);
         draw_rectangle(
             x,
             y - 20.0,
@@ -247,25 +223,960 @@ impl UI {
             25.0,
             Color::new(0.0, 0.0, 0.5, 0.8),
         );
-        draw_text(tooltip_text, x + 5.0, y - 5.0, 16.0, WHITE);
-
-        Ok(())
+        draw_text(text, x + 5.0, y - 5.0, 16.0, WHITE);
     }
 
     /// Renders the game ending screen based on completion state.
+    ///
+    /// The story text for the ending is followed by the run summary and the
+    /// new game / quit controls.
     pub async fn render_ending_screen(
         &self,
         completion_state: &GameCompletionState,
+        summary: &RunSummary,
     ) -> ThatchResult<()> {
         match completion_state {
-            GameCompletionState::EscapedEarly => self.render_escape_screen().await,
-            GameCompletionState::CompletedDungeon => self.render_victory_screen().await,
-            GameCompletionState::PlayerDied => self.render_death_screen().await,
+            GameCompletionState::EscapedEarly => self.render_escape_screen().await?,
+            GameCompletionState::EscapedWithArtifact => self.render_victory_screen().await?,
+            GameCompletionState::PlayerDied => self.render_death_screen().await?,
             GameCompletionState::Playing => {
                 // Should not render ending screen if still playing
-                Ok(())
+                return Ok(());
+            }
+        }
+
+        self.render_run_summary(summary);
+        self.render_ending_controls();
+        Ok(())
+    }
+
+    /// Renders the run statistics below the ending story text.
+    pub fn render_run_summary(&self, summary: &RunSummary) {
+        let center_x = screen_width() / 2.0;
+        let center_y = screen_height() / 2.0;
+        let left_x = center_x - 250.0;
+        let line_height = 20.0;
+        let stats = &summary.statistics;
+
+        let lines = [
+            format!(
+                "Score: {} ({})",
+                summary.score.total(),
+                summary.score.breakdown()
+            ),
+            format!("Turns taken: {}", summary.turns),
+            format!("Deepest level: {}", stats.max_depth_reached + 1),
+            match stats.artifact_found_turn {
+                Some(turn) => format!("Amulet found on turn {}", turn),
+                None => "Amulet: never found".to_string(),
+            },
+            format!("Enemies defeated: {}", stats.enemies_defeated),
+            format!("Items collected: {}", stats.items_collected),
+            format!("Play time: {}", summary.formatted_play_time()),
+        ];
+
+        draw_text("Run Summary", left_x, center_y + 45.0, 22.0, ORANGE);
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(
+                line,
+                left_x,
+                center_y + 70.0 + index as f32 * line_height,
+                18.0,
+                LIGHTGRAY,
+            );
+        }
+
+        let seed_y = center_y + 70.0 + lines.len() as f32 * line_height + 10.0;
+        draw_text(
+            &format!("Seed: {}", summary.seed),
+            left_x,
+            seed_y,
+            20.0,
+            YELLOW,
+        );
+        draw_text(
+            &format!("Share this dungeon: thatch --seed {}", summary.seed),
+            left_x,
+            seed_y + 20.0,
+            16.0,
+            GRAY,
+        );
+        if let Some(epitaph) = &summary.epitaph {
+            draw_text(
+                &format!("\"{}\"", epitaph),
+                left_x,
+                seed_y + 50.0,
+                18.0,
+                WHITE,
+            );
+        }
+    }
+
+    /// Renders the new game and quit prompts at the bottom of the ending screen.
+    fn render_ending_controls(&self) {
+        let center_x = screen_width() / 2.0;
+        let center_y = screen_height() / 2.0;
+
+        draw_text(
+            "Press 'N' for New Game",
+            center_x - 120.0,
+            center_y + 230.0,
+            20.0,
+            GREEN,
+        );
+        draw_text(
+            "Press 'ESC' to Quit",
+            center_x - 120.0,
+            center_y + 250.0,
+            20.0,
+            GREEN,
+        );
+    }
+
+    /// Renders a note on the ending screen pointing at the run's morgue file.
+    pub fn render_morgue_reference(&self, path: &Path) {
+        let center_x = screen_width() / 2.0;
+        let center_y = screen_height() / 2.0;
+
+        draw_text(
+            &format!("Character dump saved to {}", path.display()),
+            center_x - 250.0,
+            center_y + 280.0,
+            18.0,
+            LIGHTGRAY,
+        );
+    }
+
+    /// Renders the travel destination menu as a centered overlay.
+    ///
+    /// Entries on the page being shown are numbered from 1.
+    pub fn render_travel_menu(&self, menu: &TravelMenu) {
+        let entries = menu.page_entries();
+        let line_height = 24.0;
+        let width = 360.0;
+        let height = 70.0 + entries.len() as f32 * line_height;
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        let title = if menu.page_count() > 1 {
+            format!(
+                "Travel to... (page {}/{})",
+                menu.page + 1,
+                menu.page_count()
+            )
+        } else {
+            "Travel to...".to_string()
+        };
+        draw_text(&title, x + 15.0, y + 28.0, 22.0, YELLOW);
+
+        for (index, destination) in entries.iter().enumerate() {
+            draw_text(
+                &format!("{}. {}", index + 1, destination.label()),
+                x + 15.0,
+                y + 55.0 + index as f32 * line_height,
+                18.0,
+                WHITE,
+            );
+        }
+
+        let footer = if menu.page_count() > 1 {
+            "1-9: Select   PgUp/PgDn: Page   ESC: Cancel"
+        } else {
+            "1-9: Select   ESC: Cancel"
+        };
+        draw_text(footer, x + 15.0, y + height - 10.0, 14.0, GRAY);
+    }
+
+    /// Renders the spell selection menu as a centered overlay.
+    ///
+    /// Entries are numbered from 1; spells costing more than `mana` are greyed out.
+    pub fn render_spell_menu(&self, spells: &[&Spell], mana: u32) {
+        let line_height = 40.0;
+        let width = 420.0;
+        let height = 70.0 + spells.len().min(9) as f32 * line_height;
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text(
+            &format!("Cast a spell ({} mana)", mana),
+            x + 15.0,
+            y + 28.0,
+            22.0,
+            YELLOW,
+        );
+
+        for (index, spell) in spells.iter().take(9).enumerate() {
+            let entry_y = y + 55.0 + index as f32 * line_height;
+            let color = if spell.mana_cost <= mana {
+                WHITE
+            } else {
+                DARKGRAY
+            };
+            draw_text(
+                &format!("{}. {} ({} mana)", index + 1, spell.name, spell.mana_cost),
+                x + 15.0,
+                entry_y,
+                18.0,
+                color,
+            );
+            draw_text(spell.description, x + 35.0, entry_y + 16.0, 14.0, GRAY);
+        }
+
+        draw_text(
+            "1-9: Cast   ESC: Cancel",
+            x + 15.0,
+            y + height - 10.0,
+            14.0,
+            GRAY,
+        );
+    }
+
+    /// Renders a menu of items as a centered overlay.
+    ///
+    /// Items are listed by the names the player knows them by, numbered from 1;
+    /// at most nine are shown. `verb` describes what choosing an item does.
+    pub fn render_item_menu(&self, title: &str, verb: &str, labels: &[String]) {
+        let line_height = 24.0;
+        let width = 360.0;
+        let height = 70.0 + labels.len().min(9) as f32 * line_height;
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text(title, x + 15.0, y + 28.0, 22.0, YELLOW);
+
+        for (index, label) in labels.iter().take(9).enumerate() {
+            draw_text(
+                &format!("{}. {}", index + 1, label),
+                x + 15.0,
+                y + 55.0 + index as f32 * line_height,
+                18.0,
+                WHITE,
+            );
+        }
+
+        draw_text(
+            &format!("1-9: {}   ESC: Cancel", verb),
+            x + 15.0,
+            y + height - 10.0,
+            14.0,
+            GRAY,
+        );
+    }
+
+    /// Renders the menu of what the player can do where they stand.
+    ///
+    /// Each entry is a button, so the menu works on touch screens as well as
+    /// with the number keys. Returns the index of the entry clicked this frame.
+    pub fn render_interact_menu(&self, labels: &[String]) -> Option<usize> {
+        let width = 360.0;
+        let button_height = 40.0;
+        let spacing = 8.0;
+        let shown = labels.len().min(9);
+        let height = 70.0 + shown as f32 * (button_height + spacing);
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text("Do what?", x + 15.0, y + 28.0, 22.0, YELLOW);
+
+        let mut clicked = None;
+        for (index, label) in labels.iter().take(9).enumerate() {
+            let button_y = y + 40.0 + index as f32 * (button_height + spacing);
+            let text = format!("{}. {}", index + 1, label);
+            let color = Color::new(0.1, 0.2, 0.35, 1.0);
+            if self.render_button(
+                &text,
+                x + 15.0,
+                button_y,
+                width - 30.0,
+                button_height,
+                color,
+            ) {
+                clicked = Some(index);
+            }
+        }
+
+        draw_text(
+            "1-9: Choose   ESC: Cancel",
+            x + 15.0,
+            y + height - 10.0,
+            14.0,
+            GRAY,
+        );
+        clicked
+    }
+
+    /// Renders the full-screen inventory.
+    ///
+    /// Items are laid out in a grid of cells showing their glyph and name,
+    /// worn ones outlined in yellow. The selected item's commands are shown as
+    /// buttons along the bottom, with `detail`, its examine text, above them.
+    /// Returns what was clicked or tapped this frame.
+    pub fn render_inventory(
+        &self,
+        entries: &[InventoryEntry],
+        selected: usize,
+        capacity: usize,
+        detail: Option<&str>,
+    ) -> Option<InventoryClick> {
+        clear_background(BLACK);
+
+        let margin = 20.0;
+        let cell_width = 230.0;
+        let cell_height = 52.0;
+        let columns = ((screen_width() - margin) / (cell_width + margin)).max(1.0) as usize;
+        let pack = entries.iter().filter(|entry| !entry.worn).count();
+        draw_text(
+            &format!("Inventory ({}/{} in pack)", pack, capacity),
+            margin,
+            40.0,
+            28.0,
+            YELLOW,
+        );
+
+        let mut clicked = None;
+        let (mouse_x, mouse_y) = mouse_position();
+        for (index, entry) in entries.iter().enumerate() {
+            let x = margin + (index % columns) as f32 * (cell_width + margin);
+            let y = 64.0 + (index / columns) as f32 * (cell_height + margin / 2.0);
+            let fill = if index == selected {
+                Color::new(0.2, 0.4, 0.7, 1.0)
+            } else {
+                Color::new(0.1, 0.1, 0.15, 1.0)
+            };
+            draw_rectangle(x, y, cell_width, cell_height, fill);
+            let outline = if entry.worn { YELLOW } else { GRAY };
+            draw_rectangle_lines(x, y, cell_width, cell_height, 2.0, outline);
+
+            draw_text(&entry.glyph.to_string(), x + 10.0, y + 36.0, 32.0, WHITE);
+            draw_text(&entry.label, x + 40.0, y + 24.0, 16.0, WHITE);
+            if entry.worn {
+                draw_text("worn", x + 40.0, y + 42.0, 14.0, YELLOW);
+            }
+
+            let hovered = mouse_x >= x
+                && mouse_x <= x + cell_width
+                && mouse_y >= y
+                && mouse_y <= y + cell_height;
+            if hovered && is_mouse_button_pressed(MouseButton::Left) {
+                clicked = Some(InventoryClick::Item(index));
+            }
+        }
+
+        let button_height = 48.0;
+        let button_width = 120.0;
+        let bottom = screen_height() - button_height - margin;
+        if let Some(detail) = detail {
+            draw_text(detail, margin, bottom - 20.0, 18.0, LIGHTGRAY);
+        }
+
+        let color = Color::new(0.1, 0.2, 0.35, 1.0);
+        let commands = entries
+            .get(selected)
+            .map_or(&[][..], |entry| &entry.commands);
+        for (index, &command) in commands.iter().enumerate() {
+            let x = margin + index as f32 * (button_width + margin / 2.0);
+            let label = format!("{} ({})", command.label(), command.key());
+            if self.render_button(&label, x, bottom, button_width, button_height, color) {
+                clicked = Some(InventoryClick::Command(command));
+            }
+        }
+        let close_x = screen_width() - button_width - margin;
+        if self.render_button("Close", close_x, bottom, button_width, button_height, color) {
+            clicked = Some(InventoryClick::Close);
+        }
+
+        draw_text(
+            "Arrows: Select   Enter: Default   I/ESC: Close",
+            margin,
+            screen_height() - 6.0,
+            14.0,
+            GRAY,
+        );
+
+        clicked
+    }
+
+    /// Renders the character sheet over the whole window.
+    ///
+    /// Sections fill two columns, top to bottom, moving to the second column
+    /// once the first runs out of room.
+    pub fn render_character_sheet(&self, sheet: &CharacterSheet) {
+        draw_rectangle(
+            0.0,
+            0.0,
+            screen_width(),
+            screen_height(),
+            Color::new(0.0, 0.0, 0.0, 0.95),
+        );
+
+        let margin = 20.0;
+        let line_height = 20.0;
+        let column_width = (screen_width() - margin * 3.0) / 2.0;
+        let top = 80.0;
+        let bottom = screen_height() - 40.0;
+        draw_text(&sheet.title, margin, 44.0, 28.0, YELLOW);
+
+        let (mut x, mut y) = (margin, top);
+        for section in &sheet.sections {
+            let height = (section.lines.len() + 1) as f32 * line_height + 10.0;
+            if y + height > bottom && y > top && x == margin {
+                x += column_width + margin;
+                y = top;
+            }
+
+            draw_text(section.title, x, y, 20.0, SKYBLUE);
+            y += line_height;
+            for line in &section.lines {
+                draw_text(line, x + 10.0, y, 16.0, WHITE);
+                y += line_height;
+            }
+            y += 10.0;
+        }
+
+        draw_text("C/ESC: Close", margin, screen_height() - 14.0, 14.0, GRAY);
+    }
+
+    /// Renders the quest log as a centered overlay.
+    ///
+    /// Active quests are listed first with their progress; completed quests
+    /// follow in grey. At most nine are shown.
+    pub fn render_quest_log<'a>(&self, quests: impl Iterator<Item = &'a Quest>) {
+        let mut quests: Vec<&Quest> = quests.collect();
+        quests.sort_by_key(|quest| quest.is_complete());
+
+        let line_height = 40.0;
+        let width = 480.0;
+        let height = 70.0 + quests.len().clamp(1, 9) as f32 * line_height;
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text("Quests", x + 15.0, y + 28.0, 22.0, YELLOW);
+
+        if quests.is_empty() {
+            draw_text("You have no quests.", x + 15.0, y + 55.0, 18.0, GRAY);
+        }
+        for (index, quest) in quests.iter().take(9).enumerate() {
+            let entry_y = y + 55.0 + index as f32 * line_height;
+            let (color, status) = if quest.is_complete() {
+                (DARKGRAY, "done".to_string())
+            } else {
+                let required = quest.objective.required();
+                (WHITE, format!("{}/{}", quest.progress, required))
+            };
+            draw_text(
+                &format!("{} ({}) - {}", quest.title, status, quest.reward.describe()),
+                x + 15.0,
+                entry_y,
+                18.0,
+                color,
+            );
+            draw_text(
+                &quest.objective.describe(),
+                x + 35.0,
+                entry_y + 16.0,
+                14.0,
+                GRAY,
+            );
+        }
+
+        draw_text("Q/ESC: Close", x + 15.0, y + height - 10.0, 14.0, GRAY);
+    }
+
+    /// Picks the color a message is drawn in.
+    ///
+    /// Critical and important messages have their own colors whatever their
+    /// channel; other messages are tinted by channel and dimmed when they are
+    /// only informational.
+    pub fn message_color(message: &LoggedMessage, theme: &Theme) -> Color {
+        let role = match message.channel {
+            MessageChannel::Combat => ColorRole::MessageCombat,
+            MessageChannel::Exploration => ColorRole::MessageExploration,
+            MessageChannel::System => ColorRole::MessageSystem,
+            MessageChannel::Narrative => ColorRole::MessageNarrative,
+        };
+        let color = theme.color(role);
+        match message.importance {
+            MessageImportance::Critical => theme.color(ColorRole::MessageCritical),
+            MessageImportance::Important => theme.color(ColorRole::MessageImportant),
+            MessageImportance::Info => Color::new(color.r * 0.7, color.g * 0.7, color.b * 0.7, 1.0),
+            _ => color,
+        }
+    }
+
+    /// Renders the message log as a centered overlay.
+    ///
+    /// The top line shows which channels are shown; the most recent messages
+    /// that get past the filter are listed below it, newest last.
+    pub fn render_message_log(&self, log: &MessageLog, theme: &Theme) {
+        let line_height = 18.0;
+        let shown_lines = 20;
+        let width = (screen_width() - 40.0).min(720.0);
+        let height = 100.0 + shown_lines as f32 * line_height;
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text("Message Log", x + 15.0, y + 28.0, 22.0, YELLOW);
+
+        let mut filter_x = x + 15.0;
+        for (index, channel) in MessageChannel::ALL.iter().enumerate() {
+            let muted = log.is_muted(*channel);
+            let label = format!(
+                "{}. {} [{}]",
+                index + 1,
+                channel.display_name(),
+                if muted { "off" } else { "on" }
+            );
+            let color = if muted { DARKGRAY } else { WHITE };
+            draw_text(&label, filter_x, y + 52.0, 16.0, color);
+            filter_x += measure_text(&label, None, 16, 1.0).width + 20.0;
+        }
+
+        let recent: Vec<&LoggedMessage> = log.visible().rev().take(shown_lines).collect();
+        if recent.is_empty() {
+            draw_text("No messages.", x + 15.0, y + 80.0, 16.0, GRAY);
+        }
+        for (index, message) in recent.iter().rev().enumerate() {
+            let entry_y = y + 80.0 + index as f32 * line_height;
+            let color = Self::message_color(message, theme);
+            draw_text(&message.text, x + 15.0, entry_y, 16.0, color);
+        }
+
+        draw_text(
+            "1-4: Toggle channel   M/ESC: Close",
+            x + 15.0,
+            y + height - 10.0,
+            14.0,
+            GRAY,
+        );
+    }
+
+    /// Renders the line an NPC is saying in a box at the bottom of the screen.
+    pub fn render_dialogue(&self, conversation: &Conversation) {
+        let width = (screen_width() - 40.0).min(720.0);
+        let height = 110.0;
+        let x = (screen_width() - width) / 2.0;
+        let y = screen_height() - height - 20.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, GREEN);
+        draw_text(&conversation.speaker, x + 15.0, y + 28.0, 22.0, GREEN);
+        if let Some(line) = conversation.current_line() {
+            draw_text(line, x + 15.0, y + 58.0, 18.0, WHITE);
+        }
+
+        let page = format!("{}/{}", conversation.line + 1, conversation.lines.len());
+        draw_text(&page, x + width - 50.0, y + 28.0, 16.0, GRAY);
+        draw_text(
+            "ENTER/SPACE: Next, ESC: Leave",
+            x + 15.0,
+            y + height - 12.0,
+            14.0,
+            GRAY,
+        );
+    }
+
+    /// Renders the title screen and main menu.
+    ///
+    /// `entries` pairs each label with whether it can be chosen. Returns the
+    /// index of an entry clicked or tapped this frame.
+    pub fn render_main_menu(
+        &self,
+        entries: &[(&str, bool)],
+        selected: usize,
+        status: Option<&str>,
+    ) -> Option<usize> {
+        clear_background(BLACK);
+
+        let center_x = screen_width() / 2.0;
+        let width = 280.0;
+        let height = 48.0;
+        let spacing = 14.0;
+        let top = screen_height() / 2.0 - 120.0;
+
+        draw_text("THATCH", center_x - 110.0, top - 70.0, 64.0, YELLOW);
+        draw_text(
+            &format!("v{}", crate::VERSION),
+            center_x - 20.0,
+            top - 40.0,
+            18.0,
+            GRAY,
+        );
+
+        let mut clicked = None;
+        for (index, (label, enabled)) in entries.iter().enumerate() {
+            let color = if !enabled {
+                Color::new(0.2, 0.2, 0.2, 1.0)
+            } else if index == selected {
+                Color::new(0.2, 0.4, 0.7, 1.0)
+            } else {
+                Color::new(0.1, 0.2, 0.35, 1.0)
+            };
+            let y = top + index as f32 * (height + spacing);
+            if self.render_button(label, center_x - width / 2.0, y, width, height, color) {
+                clicked = Some(index);
+            }
+        }
+
+        if let Some(status) = status {
+            let y = top + entries.len() as f32 * (height + spacing) + 20.0;
+            draw_text(status, center_x - width / 2.0, y, 18.0, LIGHTGRAY);
+        }
+
+        draw_text(
+            "Up/Down: Select   Enter: Choose   1-9: Quick select",
+            center_x - 190.0,
+            screen_height() - 30.0,
+            16.0,
+            GRAY,
+        );
+
+        clicked
+    }
+
+    /// Renders the seed prompt shown before a new game starts.
+    ///
+    /// Returns `Some(true)` if the start button was pressed this frame and
+    /// `Some(false)` if the back button was. `status` is shown under the
+    /// buttons, such as why the typed seed was not accepted.
+    pub fn render_seed_entry(&self, seed_text: &str, status: Option<&str>) -> Option<bool> {
+        clear_background(BLACK);
+
+        let center_x = screen_width() / 2.0;
+        let center_y = screen_height() / 2.0;
+
+        draw_text("New Game", center_x - 70.0, center_y - 100.0, 32.0, YELLOW);
+        draw_text(
+            "Type a seed, or leave it blank for a random dungeon:",
+            center_x - 220.0,
+            center_y - 50.0,
+            18.0,
+            WHITE,
+        );
+
+        draw_rectangle(center_x - 150.0, center_y - 30.0, 300.0, 40.0, DARKGRAY);
+        draw_rectangle_lines(
+            center_x - 150.0,
+            center_y - 30.0,
+            300.0,
+            40.0,
+            2.0,
+            LIGHTGRAY,
+        );
+        let shown = if seed_text.is_empty() {
+            "(random)"
+        } else {
+            seed_text
+        };
+        draw_text(shown, center_x - 140.0, center_y - 2.0, 24.0, WHITE);
+
+        if let Some(status) = status {
+            draw_text(status, center_x - 150.0, center_y + 104.0, 18.0, LIGHTGRAY);
+        }
+
+        let color = Color::new(0.1, 0.2, 0.35, 1.0);
+        let y = center_y + 30.0;
+        if self.render_button("Start", center_x - 150.0, y, 140.0, 48.0, color) {
+            return Some(true);
+        }
+        if self.render_button("Back", center_x + 10.0, y, 140.0, 48.0, color) {
+            return Some(false);
+        }
+        None
+    }
+
+    /// Renders the class selection shown before a new game starts.
+    ///
+    /// `entries` pairs each class name with its description. Returns the
+    /// index of the class clicked this frame, where an index equal to
+    /// `entries.len()` is the back button.
+    pub fn render_class_selection(
+        &self,
+        entries: &[(&str, &str)],
+        selected: usize,
+    ) -> Option<usize> {
+        clear_background(BLACK);
+
+        let x = 40.0;
+        let width = 220.0;
+        let height = 48.0;
+        let spacing = 14.0;
+        draw_text("Choose a Class", x, 60.0, 32.0, YELLOW);
+
+        let mut clicked = None;
+        for (index, (name, description)) in entries.iter().enumerate() {
+            let color = if index == selected {
+                Color::new(0.2, 0.4, 0.7, 1.0)
+            } else {
+                Color::new(0.1, 0.2, 0.35, 1.0)
+            };
+            let y = 90.0 + index as f32 * (height + spacing);
+            if self.render_button(name, x, y, width, height, color) {
+                clicked = Some(index);
+            }
+            draw_text(
+                description,
+                x + width + 20.0,
+                y + height / 2.0 + 6.0,
+                18.0,
+                LIGHTGRAY,
+            );
+        }
+
+        draw_text(
+            "Up/Down: Select   Enter: Start   1-9: Quick select   ESC: Back",
+            x,
+            screen_height() - 30.0,
+            16.0,
+            GRAY,
+        );
+        if self.render_button(
+            "Back",
+            screen_width() - 180.0,
+            screen_height() - 80.0,
+            140.0,
+            48.0,
+            Color::new(0.1, 0.2, 0.35, 1.0),
+        ) {
+            clicked = Some(entries.len());
+        }
+        clicked
+    }
+
+    /// Renders the list of past runs.
+    ///
+    /// Returns true if the back button was pressed this frame.
+    pub fn render_records(&self, lines: &[String]) -> bool {
+        clear_background(BLACK);
+
+        let x = 40.0;
+        draw_text("Records", x, 60.0, 32.0, YELLOW);
+
+        if lines.is_empty() {
+            draw_text("No finished runs yet.", x, 110.0, 20.0, LIGHTGRAY);
+        }
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(line, x, 110.0 + index as f32 * 26.0, 20.0, WHITE);
+        }
+
+        draw_text("ESC/Enter: Back", x, screen_height() - 30.0, 16.0, GRAY);
+        self.render_button(
+            "Back",
+            screen_width() - 180.0,
+            screen_height() - 80.0,
+            140.0,
+            48.0,
+            Color::new(0.1, 0.2, 0.35, 1.0),
+        )
+    }
+
+    /// Renders the help screen: the commands in `sections`, starting
+    /// `scroll` rows down, under the search typed so far.
+    ///
+    /// Returns true if the back button was pressed this frame.
+    pub fn render_help(&self, sections: &[HelpSection], query: &str, scroll: usize) -> bool {
+        clear_background(BLACK);
+
+        let x = 40.0;
+        draw_text("Help", x, 60.0, 32.0, YELLOW);
+        draw_text(
+            &format!("Search: {}_", query),
+            x + 120.0,
+            60.0,
+            20.0,
+            LIGHTGRAY,
+        );
+
+        let mut rows: Vec<(&str, &str, bool)> = Vec::new();
+        for section in sections {
+            rows.push((&section.title, "", true));
+            for entry in &section.entries {
+                rows.push((&entry.keys, &entry.description, false));
+            }
+        }
+        if rows.is_empty() {
+            draw_text("No commands match.", x, 110.0, 20.0, LIGHTGRAY);
+        }
+
+        let row_height = 24.0;
+        let visible = ((screen_height() - 190.0) / row_height).max(1.0) as usize;
+        for (index, &(keys, description, heading)) in
+            rows.iter().skip(scroll).take(visible).enumerate()
+        {
+            let y = 110.0 + index as f32 * row_height;
+            if heading {
+                draw_text(keys, x, y, 22.0, GREEN);
+            } else {
+                draw_text(keys, x + 20.0, y, 18.0, WHITE);
+                draw_text(description, x + 320.0, y, 18.0, LIGHTGRAY);
+            }
+        }
+
+        draw_text(
+            "Type to search   Up/Down/PgUp/PgDn: Scroll   ESC: Clear search, then back",
+            x,
+            screen_height() - 30.0,
+            16.0,
+            GRAY,
+        );
+        self.render_button(
+            "Back",
+            screen_width() - 180.0,
+            screen_height() - 80.0,
+            140.0,
+            48.0,
+            Color::new(0.1, 0.2, 0.35, 1.0),
+        )
+    }
+
+    /// Renders the options list with each setting's current value.
+    ///
+    /// Returns the index of the row clicked this frame, where an index equal
+    /// to `rows.len()` is the back button.
+    pub fn render_options(&self, rows: &[(&str, String)], selected: usize) -> Option<usize> {
+        clear_background(BLACK);
+
+        let x = 40.0;
+        let width = 460.0;
+        // Rows shrink to fit above the help line when there are many settings
+        let row_space = (screen_height() - 140.0) / rows.len().max(1) as f32;
+        let height = (row_space * 0.8).min(44.0);
+        let spacing = (row_space - height).min(10.0);
+        draw_text("Options", x, 60.0, 32.0, YELLOW);
+
+        let mut clicked = None;
+        for (index, (label, value)) in rows.iter().enumerate() {
+            let color = if index == selected {
+                Color::new(0.2, 0.4, 0.7, 1.0)
+            } else {
+                Color::new(0.1, 0.2, 0.35, 1.0)
+            };
+            let y = 90.0 + index as f32 * (height + spacing);
+            if self.render_button(label, x, y, width, height, color) {
+                clicked = Some(index);
+            }
+            draw_text(value, x + width + 20.0, y + height / 2.0 + 6.0, 20.0, WHITE);
+        }
+
+        draw_text(
+            "Up/Down: Select   Left/Right/Enter: Change   ESC: Save and go back",
+            x,
+            screen_height() - 30.0,
+            16.0,
+            GRAY,
+        );
+        let back_color = if selected == rows.len() {
+            Color::new(0.2, 0.4, 0.7, 1.0)
+        } else {
+            Color::new(0.1, 0.2, 0.35, 1.0)
+        };
+        if self.render_button(
+            "Back",
+            screen_width() - 180.0,
+            screen_height() - 80.0,
+            140.0,
+            48.0,
+            back_color,
+        ) {
+            clicked = Some(rows.len());
+        }
+        clicked
+    }
+
+    /// Renders the pause menu over the map.
+    ///
+    /// Returns the index of the entry clicked this frame, if any.
+    pub fn render_pause_menu(
+        &self,
+        labels: &[&str],
+        selected: usize,
+        status: Option<&str>,
+    ) -> Option<usize> {
+        let width = 300.0;
+        let button_height = 44.0;
+        let spacing = 10.0;
+        let height = 80.0 + labels.len() as f32 * (button_height + spacing);
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text("Paused", x + 20.0, y + 34.0, 28.0, YELLOW);
+
+        let mut clicked = None;
+        for (index, label) in labels.iter().enumerate() {
+            let color = if index == selected {
+                Color::new(0.2, 0.4, 0.7, 1.0)
+            } else {
+                Color::new(0.1, 0.2, 0.35, 1.0)
+            };
+            let button_y = y + 50.0 + index as f32 * (button_height + spacing);
+            if self.render_button(
+                label,
+                x + 20.0,
+                button_y,
+                width - 40.0,
+                button_height,
+                color,
+            ) {
+                clicked = Some(index);
             }
         }
+
+        if let Some(status) = status {
+            draw_text(status, x, y + height + 24.0, 18.0, LIGHTGRAY);
+        }
+        draw_text(
+            "Up/Down: Select   Enter: Choose   ESC: Resume",
+            x,
+            y + height - 8.0,
+            14.0,
+            GRAY,
+        );
+
+        clicked
+    }
+
+    /// Renders a yes/no confirmation box over the map.
+    pub fn render_confirmation(&self, title: &str, lines: &[String]) {
+        let line_height = 24.0;
+        let width = 520.0;
+        let height = 70.0 + lines.len() as f32 * line_height;
+        let x = (screen_width() - width) / 2.0;
+        let y = (screen_height() - height) / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(x, y, width, height, 2.0, LIGHTGRAY);
+        draw_text(title, x + 15.0, y + 28.0, 22.0, YELLOW);
+
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(
+                line,
+                x + 15.0,
+                y + 55.0 + index as f32 * line_height,
+                18.0,
+                WHITE,
+            );
+        }
+
+        draw_text(
+            "Y: Confirm   N/ESC: Cancel",
+            x + 15.0,
+            y + height - 10.0,
+            14.0,
+            GRAY,
+        );
     }
 
     /// Renders touch-friendly control buttons and handles touch input.
@@ -275,9 +1186,10 @@ impl UI {
         let screen_w = screen_width();
         let screen_h = screen_height();
 
-        // Button dimensions - increased for better touch targets
-        let button_size = 70.0;
-        let button_margin = 12.0;
+        // Button dimensions scale with the window but stay large enough to hit
+        let scale = UiScale::current();
+        let button_size = scale.touch_target(70.0);
+        let button_margin = scale.px(12.0);
 
         // Movement pad (left side)
         let pad_x = button_margin;
@@ -290,7 +1202,7 @@ impl UI {
 
         // Action buttons (right side)
         let action_x = screen_w - (button_size * 2.0 + button_margin * 3.0);
-        let action_y = screen_h - (button_size * 3.0 + button_margin * 4.0);
+        let action_y = screen_h - (button_size * 4.0 + button_margin * 5.0);
 
         if let Some(input) =
             self.render_action_buttons(action_x, action_y, button_size, button_margin)
@@ -358,7 +1270,8 @@ impl UI {
         input
     }
 
-    /// Renders action buttons for stairs and autoexplore.
+    /// Renders action buttons for stairs, autoexplore, help, auto-fight, the
+    /// inventory and the interaction menu.
     fn render_action_buttons(&self, x: f32, y: f32, size: f32, margin: f32) -> Option<PlayerInput> {
         let mut input = None;
 
@@ -403,6 +1316,42 @@ impl UI {
             input = Some(PlayerInput::Help);
         }
 
+        // Auto-fight button - bright red for better visibility
+        if self.render_button(
+            "FIGHT",
+            x,
+            y + (size + margin) * 2.0,
+            size,
+            size,
+            Color::new(0.9, 0.1, 0.1, 1.0),
+        ) {
+            input = Some(PlayerInput::AutoFight);
+        }
+
+        // Inventory button - teal for better visibility
+        if self.render_button(
+            "INV",
+            x + size + margin,
+            y + (size + margin) * 2.0,
+            size,
+            size,
+            Color::new(0.0, 0.6, 0.6, 1.0),
+        ) {
+            input = Some(PlayerInput::ShowInventory);
+        }
+
+        // Interaction button spans both columns - gold for better visibility
+        if self.render_button(
+            "ACT",
+            x,
+            y + (size + margin) * 3.0,
+            size * 2.0 + margin,
+            size,
+            Color::new(0.8, 0.6, 0.0, 1.0),
+        ) {
+            input = Some(PlayerInput::Interact);
+        }
+
         input
     }
 
@@ -440,9 +1389,13 @@ impl UI {
         // Add inner shadow for better visibility
         draw_rectangle_lines(x + 1.0, y + 1.0, width - 2.0, height - 2.0, 1.0, LIGHTGRAY);
 
-        // Draw button text with better contrast
-        let text_size = 28.0; // Larger text for better visibility
-        let text_width = text.len() as f32 * text_size * 0.6;
+        // Draw button text with better contrast, shrunk to fit the button
+        let mut text_size = UiScale::current().font(28.0).min(height * 0.6);
+        let mut text_width = measure_text(text, None, text_size as u16, 1.0).width;
+        if text_width > width - 8.0 {
+            text_size = (text_size * (width - 8.0) / text_width).max(MIN_FONT_SIZE);
+            text_width = measure_text(text, None, text_size as u16, 1.0).width;
+        }
         let text_x = x + (width - text_width) / 2.0;
         let text_y = y + height / 2.0 + text_size / 2.0;
 
@@ -3,113 +3,1544 @@
 //! A centralized system for managing different game scenes (playing, ending screens, etc.)
 //! This eliminates the need for complex state management in the main loop.
 
-use crate::{Entity, GameCompletionState, GameState, InputHandler, MacroquadDisplay, PlayerInput, ThatchError, ThatchResult};
+use crate::rendering::ui::InventoryClick;
+use crate::{
+    help_sections, Action, ActiveFrontend, BlockedBy, ButcherAction, CharacterClass,
+    CharacterSheet, ConcreteAction, Conversation, Direction, Entity, EntityId, EquipItemAction,
+    EventLog, EventLogEntry, FireAction, FloorStreamer, FramePacer, Frontend, GameCompletionState,
+    GameState, HighScoreTable, InputHandler, InteractionEntry, InventoryEntry, Item, ItemType,
+    LldmWorker, LookAction, MetaProgress, MorgueReport, OpenContainerAction, PlayerInput, Position,
+    SaveManager, SaveMode, SettingsEntry, Spectator, Spell, StairDirection,
+    TakeFromContainerAction, TalkAction, TemplateLibrary, ThatchConfig, ThatchError, ThatchResult,
+    Theme, ThrowAction, TravelMenu, UnequipItemAction, UseItemAction, VaultLibrary,
+};
 use macroquad::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Most lines of past runs and high scores listed on the records screen.
+const MAX_RECORDS_SHOWN: usize = 20;
+
+/// Most high scores listed on the records screen.
+const MAX_HIGH_SCORES_SHOWN: usize = 5;
+
+/// Rows the help screen scrolls by for Page Up and Page Down.
+const HELP_PAGE_ROWS: usize = 10;
 
 /// Represents the current scene in the game
 #[derive(Debug, Clone, PartialEq)]
 pub enum SceneType {
+    /// Title screen and main menu
+    MainMenu,
+    /// Choosing a class for a new game started with the given seed
+    ChooseClass(Option<u64>),
+    /// List of past runs read from the morgue directory
+    Records,
+    /// Adjusting and saving player settings
+    Options,
+    /// Searchable list of every command and its keys
+    Help,
     /// Normal gameplay
     Playing,
+    /// Asking the player to confirm stairs that would end the run
+    ConfirmStairs(StairDirection),
+    /// Pause menu shown over the map
+    Paused,
+    /// Asking the player to confirm quitting without saving
+    ConfirmQuit,
     /// Game over screen (death, victory, or escape)
     GameOver(GameCompletionState),
+    /// Watching a game played by remote clients
+    Spectating,
+}
+
+/// Entries on the main menu, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainMenuEntry {
+    /// Start a run, asking for a seed first
+    NewGame,
+    /// Start a run on the tutorial floor
+    Tutorial,
+    /// Load the saved game
+    Continue,
+    /// Open the options scene
+    Options,
+    /// List past runs and high scores
+    Records,
+    /// Open the list of commands
+    Help,
+    /// Leave the game
+    Quit,
+}
+
+impl MainMenuEntry {
+    /// Every entry, in display order
+    pub const ALL: [MainMenuEntry; 7] = [
+        MainMenuEntry::NewGame,
+        MainMenuEntry::Tutorial,
+        MainMenuEntry::Continue,
+        MainMenuEntry::Options,
+        MainMenuEntry::Records,
+        MainMenuEntry::Help,
+        MainMenuEntry::Quit,
+    ];
+
+    /// Returns the label shown on the menu
+    pub fn label(self) -> &'static str {
+        match self {
+            MainMenuEntry::NewGame => "New Game",
+            MainMenuEntry::Tutorial => "Tutorial",
+            MainMenuEntry::Continue => "Continue",
+            MainMenuEntry::Options => "Options",
+            MainMenuEntry::Records => "Records",
+            MainMenuEntry::Help => "Help",
+            MainMenuEntry::Quit => "Quit",
+        }
+    }
+}
+
+/// Entries on the pause menu, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuEntry {
+    /// Close the menu and carry on playing
+    Resume,
+    /// Save the game, also quitting with permadeath
+    Save,
+    /// Open the options scene
+    Options,
+    /// Quit without saving, asking first
+    Quit,
+}
+
+impl PauseMenuEntry {
+    /// Every entry, in display order
+    pub const ALL: [PauseMenuEntry; 4] = [
+        PauseMenuEntry::Resume,
+        PauseMenuEntry::Save,
+        PauseMenuEntry::Options,
+        PauseMenuEntry::Quit,
+    ];
+
+    /// Returns the label shown on the menu; with permadeath, saving also quits
+    pub fn label(self, save_mode: SaveMode) -> &'static str {
+        match self {
+            PauseMenuEntry::Resume => "Resume",
+            PauseMenuEntry::Save if save_mode.is_permadeath() => "Save and quit",
+            PauseMenuEntry::Save => "Save",
+            PauseMenuEntry::Options => "Options",
+            PauseMenuEntry::Quit => "Quit",
+        }
+    }
 }
 
 /// The main scene manager that coordinates all game scenes
 pub struct SceneManager {
     current_scene: SceneType,
     game_state: GameState,
-    display: MacroquadDisplay,
+    display: ActiveFrontend,
     input_handler: InputHandler,
+    /// Path of the morgue file written for the last finished run
+    morgue_path: Option<PathBuf>,
+    /// Destinations listed in the open travel menu, if it is open
+    travel_menu: Option<TravelMenu>,
+    /// Spells listed in the open spell menu, if it is open
+    spell_menu: Option<Vec<&'static Spell>>,
+    /// Pack items listed in the open use menu, if it is open
+    use_menu: Option<Vec<EntityId>>,
+    /// Interactions listed in the open interaction menu, if it is open
+    interact_menu: Option<Vec<InteractionEntry>>,
+    /// Highlighted item on the inventory screen, if it is open
+    inventory_selection: Option<usize>,
+    /// Examine text shown on the inventory screen
+    inventory_detail: Option<String>,
+    /// Container whose contents are listed in the open container menu, if it is open
+    container_menu: Option<EntityId>,
+    /// Whether the quest log is open
+    quest_log_open: bool,
+    /// Whether the character sheet is open
+    character_sheet_open: bool,
+    /// Whether the message log is open
+    message_log_open: bool,
+    /// Turn the surroundings were last described on, when narration is on
+    narrated_turn: Option<u64>,
+    /// Conversation shown in the dialogue box, if the player is talking to an NPC
+    dialogue: Option<Conversation>,
+    /// Position of the look cursor, if look mode is active
+    look_cursor: Option<Position>,
+    /// Description of what is under the look cursor
+    look_description: Option<String>,
+    /// Highlighted main menu entry
+    menu_selection: usize,
+    /// Highlighted pause menu entry
+    pause_selection: usize,
+    /// Highlighted class on the class selection screen
+    class_selection: usize,
+    /// Seed typed so far, if the new game seed prompt is open
+    seed_input: Option<String>,
+    /// Seed given on the command line, filled into the new game seed prompt
+    seed: Option<u64>,
+    /// Whether the next new game starts on the tutorial floor
+    play_tutorial: bool,
+    /// Autosave offered for recovery at startup, until the player answers
+    recovery: Option<PathBuf>,
+    /// Status line shown under the main menu, seed prompt or pause menu
+    menu_status: Option<String>,
+    /// Summaries of past runs shown on the records screen
+    records: Vec<String>,
+    /// Player settings, saved whenever the options scene is closed
+    settings: ThatchConfig,
+    /// Theme preset the display was last given, reloaded only when it changes
+    theme_preset: Option<crate::ThemePreset>,
+    /// Achievements earned across runs, saved whenever one is added
+    progress: MetaProgress,
+    /// Where the game is saved and when it is autosaved
+    saves: SaveManager,
+    /// Highlighted row in the options scene
+    options_selection: usize,
+    /// Scene to return to when the options scene is closed
+    options_return: SceneType,
+    /// Search typed on the help screen
+    help_query: String,
+    /// Rows the help screen is scrolled down by
+    help_scroll: usize,
+    /// Scene to return to when the help screen is closed
+    help_return: SceneType,
+    /// Monster and item templates used when generating new dungeons
+    templates: TemplateLibrary,
+    /// Prefab vaults stamped into new dungeons
+    vaults: VaultLibrary,
+    /// Watches the template file for edits to reload
+    #[cfg(feature = "dev-tools")]
+    template_watcher: crate::TemplateWatcher,
+    /// Stream of actions, events, and turns for external observers, if enabled
+    event_log: Option<EventLog>,
+    /// Background generation of the floors a lazy world is still missing
+    floor_streamer: Option<FloorStreamer>,
+    /// Background requests to the LLDM endpoint, when one is configured
+    lldm_worker: Option<LldmWorker>,
+    /// Slows the loop down while nothing on screen is changing
+    frame_pacer: FramePacer,
+    /// The remotely played game being watched, while spectating
+    spectator: Option<Spectator>,
+    /// Checkpoints and actions for undoing turns while debugging
+    #[cfg(feature = "dev-tools")]
+    rewind: crate::RewindHistory,
 }
 
 impl SceneManager {
     /// Creates a new scene manager with the given game state and display
     pub async fn new(game_state: GameState, input_handler: InputHandler) -> ThatchResult<Self> {
-        let mut display = MacroquadDisplay::new().await?;
+        let mut display = ActiveFrontend::new().await?;
         display.add_message("Welcome to Thatch Roguelike!".to_string());
         display.add_message("Use WASD/arrows or touch controls to move".to_string());
+        display.add_message("Press ? for help".to_string());
 
-        Ok(Self {
-            current_scene: SceneType::Playing,
+        let floor_streamer = FloorStreamer::for_game(&game_state);
+        let mut saves = SaveManager::default();
+        saves.track(&game_state);
+        let mut scene_manager = Self {
+            current_scene: SceneType::MainMenu,
             game_state,
             display,
             input_handler,
-        })
+            morgue_path: None,
+            travel_menu: None,
+            spell_menu: None,
+            use_menu: None,
+            interact_menu: None,
+            inventory_selection: None,
+            inventory_detail: None,
+            container_menu: None,
+            quest_log_open: false,
+            character_sheet_open: false,
+            message_log_open: false,
+            narrated_turn: None,
+            dialogue: None,
+            look_cursor: None,
+            look_description: None,
+            menu_selection: 0,
+            pause_selection: 0,
+            class_selection: 0,
+            seed_input: None,
+            seed: None,
+            play_tutorial: false,
+            recovery: saves.recoverable_autosave(),
+            menu_status: None,
+            records: Vec::new(),
+            settings: ThatchConfig::load_or_default(Path::new(crate::config::SETTINGS_FILE)),
+            theme_preset: None,
+            progress: MetaProgress::load_or_default(Path::new(crate::config::PROGRESS_FILE)),
+            saves,
+            options_selection: 0,
+            options_return: SceneType::MainMenu,
+            help_query: String::new(),
+            help_scroll: 0,
+            help_return: SceneType::MainMenu,
+            templates: TemplateLibrary::builtin()?,
+            vaults: VaultLibrary::builtin()?,
+            #[cfg(feature = "dev-tools")]
+            template_watcher: crate::TemplateWatcher::new(crate::config::TEMPLATES_FILE),
+            event_log: None,
+            floor_streamer,
+            lldm_worker: None,
+            frame_pacer: FramePacer::new(Instant::now()),
+            spectator: None,
+            #[cfg(feature = "dev-tools")]
+            rewind: crate::RewindHistory::default(),
+        };
+        scene_manager.apply_settings();
+        Ok(scene_manager)
+    }
+
+    /// Applies the current settings to input, display, and the game state
+    fn apply_settings(&mut self) {
+        self.input_handler.vi_keys_enabled = self.settings.vi_keys;
+        self.input_handler.gestures.config = self.settings.gesture_config();
+        self.display.show_monster_labels = self.settings.monster_labels;
+        self.display.high_contrast = self.settings.high_contrast;
+        self.display.set_text_scale(self.settings.font_scale);
+        if self.theme_preset != Some(self.settings.theme) {
+            self.display.theme = Theme::load_preset(self.settings.theme);
+            self.theme_preset = Some(self.settings.theme);
+        }
+        self.display.map_batch.invalidate();
+        self.display.set_zoom(self.settings.zoom);
+        self.display
+            .set_visible_messages(self.settings.message_count);
+        self.game_state.autoexplore_state.action_delay_ms = self.settings.autoexplore_delay_ms;
+        self.game_state.door_opening = self.settings.door_opening;
+        self.saves.interval = self.settings.autosave_turns;
+        self.saves.mode = self.settings.save_mode;
+        self.game_state.lldm_state.enabled = self.settings.lldm_enabled;
+        self.game_state.lldm_state.config.endpoint = self.settings.lldm_endpoint.clone();
+        self.lldm_worker = LldmWorker::for_game(&self.game_state.lldm_state).unwrap_or_else(|e| {
+            #[cfg(feature = "dev-tools")]
+            tracing::error!("LLDM requests disabled: {}", e);
+            #[cfg(not(feature = "dev-tools"))]
+            eprintln!("LLDM requests disabled: {}", e);
+            None
+        });
+    }
+
+    /// Loads a map tileset by name, falling back to glyph rendering if it is unavailable
+    pub async fn load_tileset(&mut self, name: &str) {
+        match self.display.load_tileset(name).await {
+            Ok(()) => {
+                #[cfg(feature = "dev-tools")]
+                tracing::info!("Loaded tileset '{}'", name);
+                #[cfg(not(feature = "dev-tools"))]
+                println!("Loaded tileset '{}'", name);
+            }
+            Err(e) => {
+                #[cfg(feature = "dev-tools")]
+                tracing::info!("Using glyph rendering: {}", e);
+                #[cfg(not(feature = "dev-tools"))]
+                println!("Using glyph rendering: {}", e);
+            }
+        }
+    }
+
+    /// Streams every action, event, and turn of play to `event_log`
+    pub fn set_event_log(&mut self, event_log: EventLog) {
+        self.event_log = Some(event_log);
+    }
+
+    /// Watches the game `spectator` follows instead of playing one
+    ///
+    /// Only the view can be changed while spectating; quitting closes the
+    /// window and leaves the game to its remote players.
+    pub fn spectate(&mut self, spectator: Spectator) {
+        self.game_state = spectator.game_state();
+        self.spectator = Some(spectator);
+        self.display
+            .add_message("Spectating; the game is played remotely".to_string());
+        self.current_scene = SceneType::Spectating;
+    }
+
+    /// Adds the floors finished in the background to a lazily generated world
+    ///
+    /// A failure stops streaming; the missing floors are then generated when
+    /// the player reaches them.
+    fn stream_floors(&mut self) {
+        let Some(streamer) = &self.floor_streamer else {
+            return;
+        };
+        if let Err(e) = streamer.poll(&mut self.game_state.world) {
+            self.floor_streamer = None;
+            #[cfg(feature = "dev-tools")]
+            tracing::error!("Background floor generation stopped: {}", e);
+            #[cfg(not(feature = "dev-tools"))]
+            eprintln!("Background floor generation stopped: {}", e);
+        }
+    }
+
+    /// Sets the monster and item templates used when generating new dungeons,
+    /// spawning wanderers and describing monsters
+    pub fn set_templates(&mut self, templates: TemplateLibrary) {
+        self.display.templates = templates.clone();
+        self.game_state.templates = templates.clone();
+        self.templates = templates;
+    }
+
+    /// Sets the seed the new game prompt starts filled in with
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Sets the vaults stamped into new dungeons
+    pub fn set_vaults(&mut self, vaults: VaultLibrary) {
+        self.vaults = vaults;
+    }
+
+    /// Reloads the template file if it was edited, keeping the old templates if
+    /// the new ones are invalid
+    #[cfg(feature = "dev-tools")]
+    fn reload_changed_templates(&mut self) {
+        match self.template_watcher.poll(Instant::now()) {
+            Some(Ok(templates)) => {
+                tracing::info!("Reloaded templates from {}", crate::config::TEMPLATES_FILE);
+                self.display.add_message(
+                    "Templates reloaded; they apply to the next new game.".to_string(),
+                );
+                self.set_templates(templates);
+            }
+            Some(Err(e)) => {
+                tracing::error!("Keeping previous templates: {}", e);
+                self.display
+                    .add_message(format!("Template reload failed: {}", e));
+            }
+            None => {}
+        }
     }
 
     /// Runs the main scene loop until the game exits
     pub async fn run(&mut self) -> ThatchResult<()> {
         loop {
+            let frame_started = Instant::now();
+            #[cfg(feature = "dev-tools")]
+            crate::with_profiler(|profiler| profiler.begin_frame());
+
+            #[cfg(feature = "dev-tools")]
+            self.reload_changed_templates();
+            self.stream_floors();
+            if let Some(worker) = &self.lldm_worker {
+                worker.poll(&mut self.game_state.lldm_state);
+            }
+
             match self.current_scene {
+                SceneType::MainMenu => {
+                    if self.update_main_menu_scene().await? {
+                        break; // Exit requested
+                    }
+                }
+                SceneType::ChooseClass(seed) => self.update_choose_class_scene(seed).await?,
+                SceneType::Records => self.update_records_scene(),
+                SceneType::Options => self.update_options_scene(),
+                SceneType::Help => self.update_help_scene(),
                 SceneType::Playing => {
                     if self.update_playing_scene().await? {
                         break; // Exit requested
                     }
                 }
+                SceneType::ConfirmStairs(ref direction) => {
+                    self.update_confirm_stairs_scene(direction.clone()).await?;
+                }
+                SceneType::Paused => self.update_pause_scene().await?,
+                SceneType::ConfirmQuit => {
+                    if self.update_confirm_quit_scene().await? {
+                        break; // Exit requested
+                    }
+                }
                 SceneType::GameOver(ref completion_state) => {
-                    if self.update_game_over_scene(completion_state.clone()).await? {
+                    if self
+                        .update_game_over_scene(completion_state.clone())
+                        .await?
+                    {
+                        break; // Exit requested
+                    }
+                }
+                SceneType::Spectating => {
+                    if self.update_spectating_scene().await? {
                         break; // Exit requested
                     }
                 }
             }
+
+            #[cfg(feature = "dev-tools")]
+            crate::with_profiler(|profiler| profiler.end_frame());
+
+            self.pace_frame(frame_started);
             next_frame().await;
         }
         Ok(())
     }
 
-    /// Updates the playing scene, returns true if exit is requested
-    async fn update_playing_scene(&mut self) -> ThatchResult<bool> {
-        // Handle input
-        let touch_input = self.display.get_touch_input();
-        
-        if let Some(input) = self.input_handler.get_input_with_touch(touch_input) {
-            match input {
-                PlayerInput::Quit => return Ok(true),
-                
-                PlayerInput::Help => {
-                    self.display.add_message(
-                        "Help: WASD/arrows=move, ESC=quit, SPACE=wait, F12=autoexplore, X=debug damage".to_string(),
-                    );
-                }
+    /// Waits out the rest of the frame if nothing on screen has changed for a while
+    fn pace_frame(&mut self, frame_started: Instant) {
+        let now = Instant::now();
+        self.frame_pacer
+            .note_screen_size(screen_width(), screen_height(), now);
+        let busy = self.input_handler.has_activity()
+            || self.display.animations.is_animating()
+            || self.game_state.is_travelling()
+            || self.game_state.is_resting()
+            || self.game_state.is_repeating()
+            || self.game_state.is_fighting()
+            || self.game_state.is_autoexplore_enabled();
+        if busy {
+            self.frame_pacer.mark_dirty(now);
+        }
+
+        // Browsers pace frames themselves, and threads cannot sleep there
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(wait) = self.frame_pacer.idle_wait(frame_started, now) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Updates the playing scene, returns true if exit is requested
+    async fn update_playing_scene(&mut self) -> ThatchResult<bool> {
+        // The inventory screen takes over the whole window while it is open
+        if self.inventory_selection.is_some() {
+            self.update_inventory_screen().await?;
+            return Ok(false);
+        }
+
+        // The travel menu captures all input while it is open
+        if self.travel_menu.is_some() {
+            self.update_travel_menu();
+            self.display.render_game(&self.game_state).await?;
+            if let Some(menu) = &self.travel_menu {
+                self.display.ui.render_travel_menu(menu);
+            }
+            return Ok(false);
+        }
+
+        // The spell menu captures all input while it is open
+        if self.spell_menu.is_some() {
+            self.update_spell_menu().await?;
+            self.display.render_game(&self.game_state).await?;
+            if let Some(spells) = &self.spell_menu {
+                let mana = self
+                    .game_state
+                    .get_player()
+                    .map_or(0, |player| player.stats.mana);
+                self.display.ui.render_spell_menu(spells, mana);
+            }
+            return Ok(false);
+        }
+
+        // The use menu captures all input while it is open
+        if self.use_menu.is_some() {
+            self.update_use_menu().await?;
+            self.display.render_game(&self.game_state).await?;
+            if let Some(items) = &self.use_menu {
+                let worn = self.game_state.get_player().map(|player| &player.equipment);
+                let labels: Vec<String> = items
+                    .iter()
+                    .filter_map(|id| self.game_state.carried_items.get(id))
+                    .map(|item| {
+                        let name = self.game_state.item_display_name(item);
+                        if worn.is_some_and(|worn| worn.values().any(|id| *id == item.id)) {
+                            format!("{} (worn)", name)
+                        } else {
+                            name
+                        }
+                    })
+                    .collect();
+                self.display
+                    .ui
+                    .render_item_menu("Use which item?", "Use", &labels);
+            }
+            return Ok(false);
+        }
+
+        // The interaction menu captures all input while it is open; the chosen
+        // interaction is then handled below as if its own key had been pressed
+        let mut chosen = None;
+        if let Some(entries) = &self.interact_menu {
+            self.display.render_game(&self.game_state).await?;
+            let labels: Vec<String> = entries.iter().map(|entry| entry.label.clone()).collect();
+            let clicked = self.display.ui.render_interact_menu(&labels);
+            chosen = self.update_interact_menu(clicked);
+            if chosen.is_none() {
+                return Ok(false);
+            }
+        }
+
+        // The container menu captures all input while it is open
+        if self.container_menu.is_some() {
+            self.update_container_menu().await?;
+            self.display.render_game(&self.game_state).await?;
+            if let Some(contents) = self.container_menu_contents() {
+                let labels: Vec<String> = contents
+                    .iter()
+                    .map(|item| self.game_state.item_display_name(item))
+                    .collect();
+                self.display
+                    .ui
+                    .render_item_menu("Take which item?", "Take", &labels);
+            }
+            return Ok(false);
+        }
+
+        // The quest log captures all input while it is open
+        if self.quest_log_open {
+            if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::Q) {
+                self.quest_log_open = false;
+            }
+            self.display.render_game(&self.game_state).await?;
+            self.display
+                .ui
+                .render_quest_log(self.game_state.quests.iter());
+            return Ok(false);
+        }
+
+        // The character sheet captures all input while it is open
+        if self.character_sheet_open {
+            if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::C) {
+                self.character_sheet_open = false;
+            }
+            self.display.render_game(&self.game_state).await?;
+            if let Some(sheet) = CharacterSheet::new(&self.game_state) {
+                self.display.ui.render_character_sheet(&sheet);
+            }
+            return Ok(false);
+        }
+
+        // The message log captures all input while it is open
+        if self.message_log_open {
+            if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::M) {
+                self.message_log_open = false;
+            }
+            let channel_keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+            for (key, channel) in channel_keys.iter().zip(crate::MessageChannel::ALL) {
+                if is_key_pressed(*key) {
+                    self.display.messages.toggle_channel(channel);
+                }
+            }
+            self.display.render_game(&self.game_state).await?;
+            self.display
+                .ui
+                .render_message_log(&self.display.messages, &self.display.theme);
+            return Ok(false);
+        }
+
+        // The dialogue box captures all input while it is open
+        if self.dialogue.is_some() {
+            self.update_dialogue();
+            self.display.render_game(&self.game_state).await?;
+            if let Some(conversation) = &self.dialogue {
+                self.display.ui.render_dialogue(conversation);
+            }
+            return Ok(false);
+        }
+
+        // Look mode captures all input while it is active
+        if let Some(cursor) = self.look_cursor {
+            self.update_look_mode(cursor)?;
+            self.display.render_game(&self.game_state).await?;
+            if let Some(cursor) = self.look_cursor {
+                self.display
+                    .render_look_cursor(cursor, self.look_description.as_deref());
+            }
+            return Ok(false);
+        }
+
+        // Releasing the free-look key snaps the view back to the player
+        if !self.input_handler.is_free_look_held() {
+            self.display.end_free_look();
+        }
+
+        #[cfg(feature = "dev-tools")]
+        self.rewind.observe(&self.game_state);
+
+        // Handle input
+        if let Some(input) = chosen.or_else(|| self.display.poll_input(&mut self.input_handler)) {
+            // Any input finishes animations still in flight; game logic never waits for them
+            self.display.skip_animations();
+
+            // Any manual input other than view changes interrupts travel, rest, and
+            // repeats and closes the examine popup
+            if !matches!(
+                input,
+                PlayerInput::ZoomIn
+                    | PlayerInput::ZoomOut
+                    | PlayerInput::Pan(_)
+                    | PlayerInput::ToggleDanger
+            ) {
+                self.game_state.cancel_travel();
+                self.game_state.cancel_rest();
+                self.game_state.cancel_repeat();
+                self.game_state.cancel_fight();
+                self.display.clear_examine();
+            }
+
+            match input {
+                PlayerInput::Quit => {
+                    self.pause_selection = 0;
+                    self.menu_status = None;
+                    self.current_scene = SceneType::Paused;
+                }
+
+                PlayerInput::TravelTo(destination) => {
+                    if let Err(e) = self.game_state.start_travel(destination) {
+                        self.display.add_message(e.to_string());
+                    }
+                }
+
+                PlayerInput::UseStairs(ref direction)
+                    if self.game_state.stairs_ending(direction).is_some() =>
+                {
+                    self.current_scene = SceneType::ConfirmStairs(direction.clone());
+                }
+
+                PlayerInput::Repeat(input, count) => {
+                    if let Some(action) = self
+                        .input_handler
+                        .input_to_action(*input, &self.game_state)?
+                    {
+                        self.game_state.start_repeat(action, count);
+                    }
+                }
+
+                PlayerInput::Run(delta) => {
+                    if let Some(direction) = Direction::from_delta(delta) {
+                        self.game_state.start_run(direction)?;
+                    }
+                }
+
+                PlayerInput::Rest => {
+                    if let Err(e) = self.game_state.start_rest() {
+                        self.display.add_message(e.to_string());
+                    }
+                }
+
+                PlayerInput::AutoFight => {
+                    if let Err(e) = self.game_state.start_fight() {
+                        self.display.add_message(e.to_string());
+                    }
+                }
+
+                PlayerInput::OpenTravelMenu => {
+                    let destinations = self.game_state.travel_destinations();
+                    if destinations.is_empty() {
+                        self.display
+                            .add_message("You don't know anywhere to travel to.".to_string());
+                    } else {
+                        self.travel_menu = Some(TravelMenu::new(destinations));
+                    }
+                }
+
+                PlayerInput::OpenSpellMenu => {
+                    let spells: Vec<&'static Spell> = self
+                        .game_state
+                        .get_player()
+                        .map(|player| {
+                            player
+                                .known_spells
+                                .iter()
+                                .filter_map(|name| Spell::by_name(name))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    if spells.is_empty() {
+                        self.display
+                            .add_message("You don't know any spells.".to_string());
+                    } else {
+                        self.spell_menu = Some(spells);
+                    }
+                }
+
+                PlayerInput::PickUp => {
+                    let has_items = self.game_state.get_player().is_some_and(|player| {
+                        self.game_state
+                            .world
+                            .current_level()
+                            .and_then(|level| level.ground_items.top_at(player.position()))
+                            .is_some()
+                    });
+                    if has_items {
+                        self.handle_game_action(PlayerInput::PickUp).await?;
+                    } else {
+                        self.display
+                            .add_message("There is nothing here to pick up.".to_string());
+                    }
+                }
+
+                PlayerInput::CommandAllies => {
+                    let command = self.game_state.next_ally_command();
+                    let events = self.game_state.command_allies(command);
+                    if events.is_empty() {
+                        self.display
+                            .add_message("You have no allies here.".to_string());
+                    }
+                    self.process_game_events(events).await?;
+                }
+
+                PlayerInput::ShowInventory => {
+                    if InventoryEntry::list(&self.game_state).is_empty() {
+                        self.display
+                            .add_message("You are carrying nothing.".to_string());
+                    } else {
+                        // The key that opened the screen is not a command
+                        while get_char_pressed().is_some() {}
+                        self.inventory_selection = Some(0);
+                        self.inventory_detail = None;
+                    }
+                }
+
+                PlayerInput::Interact => {
+                    let entries = InteractionEntry::list(&self.game_state);
+                    if entries.is_empty() {
+                        self.display
+                            .add_message("There is nothing to do here.".to_string());
+                    } else {
+                        self.interact_menu = Some(entries);
+                    }
+                }
+
+                PlayerInput::ShowQuestLog => self.quest_log_open = true,
+                PlayerInput::ShowCharacterSheet => self.character_sheet_open = true,
+                PlayerInput::ShowMessageLog => self.message_log_open = true,
+
+                PlayerInput::Talk => {
+                    let npc_id = TalkAction::npc_within_reach(&self.game_state);
+                    match (npc_id, self.game_state.player_id) {
+                        (Some(npc_id), Some(player_id)) => {
+                            let action = TalkAction::new(player_id, npc_id);
+                            self.perform_action(ConcreteAction::Talk(action)).await?;
+                            self.start_dialogue(npc_id);
+                        }
+                        _ => self
+                            .display
+                            .add_message("There is nobody here to talk to.".to_string()),
+                    }
+                }
+
+                PlayerInput::Butcher => {
+                    if ButcherAction::corpse_under_player(&self.game_state).is_some() {
+                        self.handle_game_action(PlayerInput::Butcher).await?;
+                    } else {
+                        self.display
+                            .add_message("There is nothing here to butcher.".to_string());
+                    }
+                }
+
+                PlayerInput::OpenUseMenu => {
+                    let items: Vec<EntityId> = self
+                        .game_state
+                        .get_player()
+                        .map(|player| {
+                            // Worn items follow the pack, in slot order so the menu keeps still
+                            let mut worn: Vec<(&String, &EntityId)> =
+                                player.equipment.iter().collect();
+                            worn.sort();
+                            player
+                                .inventory
+                                .iter()
+                                .copied()
+                                .filter(|id| {
+                                    self.game_state.carried_items.get(id).is_some_and(|item| {
+                                        matches!(item.item_type, ItemType::Consumable(_))
+                                            || item.item_type.equip_slot().is_some()
+                                    })
+                                })
+                                .chain(worn.into_iter().map(|(_, &id)| id))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    if items.is_empty() {
+                        self.display
+                            .add_message("You have nothing to use.".to_string());
+                    } else {
+                        self.use_menu = Some(items);
+                    }
+                }
+
+                PlayerInput::OpenContainer => {
+                    let container = self.game_state.get_player().and_then(|player| {
+                        self.game_state
+                            .container_within_reach(player.position())
+                            .map(|container| (container.id, container.opened))
+                    });
+                    match (container, self.game_state.player_id) {
+                        (Some((container_id, opened)), Some(player_id)) => {
+                            // Looking into a chest that is already open takes no turn
+                            if !opened {
+                                let action = OpenContainerAction::new(player_id, container_id);
+                                self.perform_action(ConcreteAction::OpenContainer(action))
+                                    .await?;
+                            }
+                            self.container_menu = Some(container_id);
+                            if self
+                                .container_menu_contents()
+                                .is_none_or(|items| items.is_empty())
+                            {
+                                self.container_menu = None;
+                                if opened {
+                                    self.display.add_message("The chest is empty.".to_string());
+                                }
+                            }
+                        }
+                        _ => self
+                            .display
+                            .add_message("There is no chest within reach.".to_string()),
+                    }
+                }
+
+                PlayerInput::Throw => match ThrowAction::at_nearest_hostile(&self.game_state) {
+                    Ok(action) => self.perform_action(ConcreteAction::Throw(action)).await?,
+                    Err(e) => self.display.add_message(e.to_string()),
+                },
+
+                PlayerInput::Fire => match FireAction::at_nearest_hostile(&self.game_state) {
+                    Ok(action) => self.perform_action(ConcreteAction::Fire(action)).await?,
+                    Err(e) => self.display.add_message(e.to_string()),
+                },
+
+                PlayerInput::Examine(position) => {
+                    let description = self.game_state.describe_position(position);
+                    self.display.show_examine(description);
+                }
+
+                PlayerInput::Look => {
+                    if let Some(position) = self.game_state.get_player().map(|p| p.position()) {
+                        self.display.add_message(
+                            "Look mode: move to examine, X or ESC to exit".to_string(),
+                        );
+                        self.look_cursor = Some(position);
+                        self.look_at(position)?;
+                    }
+                }
+
+                PlayerInput::ZoomIn => self.display.zoom_in(),
+
+                PlayerInput::ZoomOut => self.display.zoom_out(),
+
+                PlayerInput::ToggleDanger => self.display.toggle_danger_overlay(),
+
+                PlayerInput::ToggleProfiler => self.display.toggle_profiler(),
+
+                #[cfg(feature = "dev-tools")]
+                PlayerInput::Undo(turns) => self.undo_turns(turns),
+
+                PlayerInput::Pan(delta) => self.display.pan_viewport(delta),
+
+                PlayerInput::Help => self.open_help(),
+
+                PlayerInput::DebugDamage => {
+                    self.handle_debug_damage()?;
+                }
+
+                PlayerInput::DebugValidate => {
+                    let issues = self.game_state.validate_consistency();
+                    if issues.is_empty() {
+                        self.display
+                            .add_message("State check: consistent".to_string());
+                    }
+                    for issue in issues {
+                        self.display.add_message(format!("State check: {}", issue));
+                    }
+                }
+
+                PlayerInput::ToggleAutoexplore => {
+                    let enabled = self.game_state.toggle_autoexplore();
+                    if enabled {
+                        self.display
+                            .add_message("Autoexplore enabled (F12 to toggle off)".to_string());
+                    } else {
+                        self.display.add_message("Autoexplore disabled".to_string());
+                    }
+                }
+
+                _ => {
+                    self.handle_game_action(input).await?;
+                }
+            }
+        } else if self.game_state.is_travelling() {
+            // Continue click-to-travel if no manual input
+            self.handle_travel().await?;
+        } else if self.game_state.is_resting() {
+            // Continue resting if no manual input
+            self.handle_rest().await?;
+        } else if self.game_state.is_repeating() {
+            // Continue a counted command if no manual input
+            self.handle_repeat().await?;
+        } else if self.game_state.is_fighting() {
+            // Keep fighting if no manual input
+            self.handle_fight().await?;
+        } else {
+            // Handle autoexplore if no manual input
+            self.handle_autoexplore().await?;
+        }
+        self.narrate_surroundings();
+        self.autosave();
+
+        // Check for scene transition
+        if self.game_state.is_game_ended() {
+            self.write_morgue_file();
+            self.record_high_score();
+            if let Err(e) = self.saves.end_run() {
+                self.display
+                    .add_message(format!("Could not delete autosaves: {}", e));
+            }
+            self.current_scene =
+                SceneType::GameOver(self.game_state.get_completion_state().clone());
+        }
+
+        // Render the current scene, with any count typed so far
+        self.display.render_game(&self.game_state).await?;
+        if let Some(count) = self.input_handler.pending_count() {
+            self.display.render_pending_count(count);
+        }
+
+        Ok(false)
+    }
+
+    /// Updates the spectating scene, returns true if exit is requested
+    ///
+    /// The game shown is replaced by the spectated one whenever it moves on,
+    /// and its events are animated and logged as if played here.
+    async fn update_spectating_scene(&mut self) -> ThatchResult<bool> {
+        if let Some(update) = self.spectator.as_mut().and_then(Spectator::poll) {
+            let was_ended = self.game_state.is_game_ended();
+            self.game_state = update.game_state;
+            for event in update.events {
+                self.display.animate_event(&event, &self.game_state);
+                if let crate::GameEvent::Message {
+                    text,
+                    importance,
+                    channel,
+                } = event
+                {
+                    self.display.add_game_message(text, importance, channel);
+                }
+            }
+            if self.game_state.is_game_ended() && !was_ended {
+                self.display
+                    .add_message("The spectated game is over".to_string());
+            }
+            self.frame_pacer.mark_dirty(Instant::now());
+        }
+
+        if !self.input_handler.is_free_look_held() {
+            self.display.end_free_look();
+        }
+        // Only view changes and quitting are accepted; the game is not ours to play
+        match self.display.poll_input(&mut self.input_handler) {
+            Some(PlayerInput::Quit) => return Ok(true),
+            Some(PlayerInput::ZoomIn) => self.display.zoom_in(),
+            Some(PlayerInput::ZoomOut) => self.display.zoom_out(),
+            Some(PlayerInput::Pan(delta)) => self.display.pan_viewport(delta),
+            Some(PlayerInput::ToggleDanger) => self.display.toggle_danger_overlay(),
+            Some(PlayerInput::ToggleProfiler) => self.display.toggle_profiler(),
+            _ => {}
+        }
+
+        self.display.render_game(&self.game_state).await?;
+        Ok(false)
+    }
+
+    /// Updates the main menu scene, returns true if exit is requested
+    async fn update_main_menu_scene(&mut self) -> ThatchResult<bool> {
+        if self.seed_input.is_some() {
+            self.update_seed_entry().await?;
+            return Ok(false);
+        }
+        if self.recovery.is_some() {
+            self.update_recovery_prompt()?;
+            return Ok(false);
+        }
+
+        let can_continue = self.saves.save_path.exists();
+        let entries: Vec<(&str, bool)> = MainMenuEntry::ALL
+            .iter()
+            .map(|&entry| {
+                (
+                    entry.label(),
+                    entry != MainMenuEntry::Continue || can_continue,
+                )
+            })
+            .collect();
+
+        let clicked = self.display.ui.render_main_menu(
+            &entries,
+            self.menu_selection,
+            self.menu_status.as_deref(),
+        );
+
+        let count = MainMenuEntry::ALL.len();
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            self.menu_selection = (self.menu_selection + count - 1) % count;
+        } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            self.menu_selection = (self.menu_selection + 1) % count;
+        }
+
+        if self.input_handler.is_input_pressed(&PlayerInput::Help) {
+            self.open_help();
+            return Ok(false);
+        }
+
+        let chosen = clicked
+            .or_else(|| self.input_handler.get_menu_selection())
+            .or_else(|| is_key_pressed(KeyCode::Enter).then_some(self.menu_selection));
+        let Some(entry) = chosen.and_then(|index| MainMenuEntry::ALL.get(index).copied()) else {
+            return Ok(false);
+        };
+
+        self.menu_status = None;
+        match entry {
+            MainMenuEntry::NewGame => {
+                // First-time players get the tutorial without asking for it
+                self.play_tutorial = !self.progress.is_returning_player();
+                self.seed_input = Some(self.seed.map(|seed| seed.to_string()).unwrap_or_default());
+            }
+            MainMenuEntry::Tutorial => {
+                self.play_tutorial = true;
+                self.current_scene = SceneType::ChooseClass(None);
+            }
+            MainMenuEntry::Continue => self.continue_saved_game()?,
+            MainMenuEntry::Options => self.open_options(),
+            MainMenuEntry::Records => {
+                let high_scores =
+                    HighScoreTable::load_or_default(Path::new(crate::config::HIGH_SCORES_FILE));
+                self.records.clear();
+                if !high_scores.scores.is_empty() {
+                    self.records.push("High Scores".to_string());
+                    self.records.extend(
+                        high_scores
+                            .scores
+                            .iter()
+                            .take(MAX_HIGH_SCORES_SHOWN)
+                            .enumerate()
+                            .map(|(index, run)| format!("{}. {}", index + 1, run.summary_line())),
+                    );
+                    self.records.push(String::new());
+                    self.records.push("Recent Runs".to_string());
+                }
+                // Past runs fill what room the high scores leave
+                let morgue_directory = Path::new(crate::config::MORGUE_DIRECTORY);
+                let room = MAX_RECORDS_SHOWN.saturating_sub(self.records.len());
+                self.records.extend(
+                    MorgueReport::load_directory(morgue_directory)
+                        .iter()
+                        .take(room)
+                        .map(MorgueReport::summary_line),
+                );
+                if !self.progress.achievements.is_empty() {
+                    let earned: Vec<&str> = self
+                        .progress
+                        .achievements
+                        .iter()
+                        .map(|achievement| achievement.display_name())
+                        .collect();
+                    self.records.push(String::new());
+                    self.records.push(format!(
+                        "Achievements ({}/{}): {}",
+                        earned.len(),
+                        crate::Achievement::ALL.len(),
+                        earned.join(", ")
+                    ));
+                }
+                self.current_scene = SceneType::Records;
+            }
+            MainMenuEntry::Help => self.open_help(),
+            MainMenuEntry::Quit => return Ok(true),
+        }
+        Ok(false)
+    }
+
+    /// Handles typing a seed for a new game
+    async fn update_seed_entry(&mut self) -> ThatchResult<()> {
+        let Some(seed_text) = self.seed_input.as_mut() else {
+            return Ok(());
+        };
+
+        while let Some(c) = get_char_pressed() {
+            if c.is_ascii_digit() && seed_text.len() < 20 {
+                seed_text.push(c);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            seed_text.pop();
+        }
+
+        let button = self
+            .display
+            .ui
+            .render_seed_entry(seed_text, self.menu_status.as_deref());
+        if button == Some(false) || is_key_pressed(KeyCode::Escape) {
+            self.seed_input = None;
+            self.menu_status = None;
+        } else if button == Some(true) || is_key_pressed(KeyCode::Enter) {
+            // A blank seed picks a random one, but too large a seed is a mistake
+            let seed = if seed_text.is_empty() {
+                None
+            } else if let Ok(seed) = seed_text.parse() {
+                Some(seed)
+            } else {
+                self.menu_status = Some(format!("Seeds go up to {}", u64::MAX));
+                return Ok(());
+            };
+            self.seed_input = None;
+            self.menu_status = None;
+            self.current_scene = SceneType::ChooseClass(seed);
+        }
+        Ok(())
+    }
+
+    /// Updates the class selection shown before a new game starts
+    async fn update_choose_class_scene(&mut self, seed: Option<u64>) -> ThatchResult<()> {
+        let entries: Vec<(&str, &str)> = CharacterClass::ALL
+            .iter()
+            .map(|class| (class.display_name(), class.description()))
+            .collect();
+        let clicked = self
+            .display
+            .ui
+            .render_class_selection(&entries, self.class_selection);
+
+        let count = CharacterClass::ALL.len();
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            self.class_selection = (self.class_selection + count - 1) % count;
+        } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            self.class_selection = (self.class_selection + 1) % count;
+        }
+
+        // The back button sits after the last class
+        if clicked == Some(count) || is_key_pressed(KeyCode::Escape) {
+            self.current_scene = SceneType::MainMenu;
+            return Ok(());
+        }
+
+        let chosen = clicked
+            .or_else(|| self.input_handler.get_menu_selection())
+            .or_else(|| is_key_pressed(KeyCode::Enter).then_some(self.class_selection));
+        if let Some(&class) = chosen.and_then(|index| CharacterClass::ALL.get(index)) {
+            self.start_new_game_with_seed(seed, class).await?;
+        }
+        Ok(())
+    }
+
+    /// Asks whether to pick up from an autosave newer than the manual save
+    fn update_recovery_prompt(&mut self) -> ThatchResult<()> {
+        let lines = vec![
+            "An autosave is newer than your last save, so the".to_string(),
+            "last session may have ended without saving.".to_string(),
+            "Continue from the autosave?".to_string(),
+        ];
+        self.display
+            .ui
+            .render_confirmation("Recover autosave?", &lines);
+
+        if is_key_pressed(KeyCode::Y) {
+            if let Some(path) = self.recovery.take() {
+                self.load_game(&path)?;
+            }
+        } else if is_key_pressed(KeyCode::N) || is_key_pressed(KeyCode::Escape) {
+            self.recovery = None;
+        }
+        Ok(())
+    }
+
+    /// Loads the saved game and resumes play
+    fn continue_saved_game(&mut self) -> ThatchResult<()> {
+        let path = self.saves.save_path.clone();
+        if !path.exists() {
+            self.menu_status = Some("There is no saved game to continue.".to_string());
+            return Ok(());
+        }
+        self.load_game(&path)
+    }
+
+    /// Loads a game from a save or autosave file and resumes play
+    fn load_game(&mut self, path: &Path) -> ThatchResult<()> {
+        match self.saves.load(path) {
+            Ok(game_state) => {
+                self.game_state = game_state;
+                self.game_state.templates = self.templates.clone();
+                self.apply_settings();
+                self.game_state.restore_lazy_generation(self.vaults.clone());
+                self.floor_streamer = FloorStreamer::for_game(&self.game_state);
+                self.saves.track(&self.game_state);
+                if let Some(position) = self.game_state.get_player().map(|p| p.position()) {
+                    self.game_state.update_player_visibility(position)?;
+                }
+                self.current_scene = SceneType::Playing;
+                self.morgue_path = None;
+                self.display.add_message("Welcome back!".to_string());
+            }
+            Err(e) => {
+                self.menu_status = Some(format!("Could not load the saved game: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the records scene listing past runs
+    fn update_records_scene(&mut self) {
+        let back = self.display.ui.render_records(&self.records);
+        if back || is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::Enter) {
+            self.current_scene = SceneType::MainMenu;
+        }
+    }
+
+    /// Opens the help screen, returning to the current scene when it closes
+    fn open_help(&mut self) {
+        // The key that opened the help is not the start of a search
+        while get_char_pressed().is_some() {}
+        self.help_query.clear();
+        self.help_scroll = 0;
+        self.help_return = self.current_scene.clone();
+        self.current_scene = SceneType::Help;
+    }
+
+    /// Updates the help screen, listing the commands from the key bindings
+    fn update_help_scene(&mut self) {
+        while let Some(c) = get_char_pressed() {
+            if !c.is_control() {
+                self.help_query.push(c);
+                self.help_scroll = 0;
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) && self.help_query.pop().is_some() {
+            self.help_scroll = 0;
+        }
+
+        let sections = help_sections(
+            &self.input_handler.bindings,
+            self.input_handler.vi_keys_enabled,
+            &self.help_query,
+        );
+        let rows: usize = sections
+            .iter()
+            .map(|section| section.entries.len() + 1)
+            .sum();
+        let (_, wheel_y) = mouse_wheel();
+        if is_key_pressed(KeyCode::Up) || wheel_y > 0.0 {
+            self.help_scroll = self.help_scroll.saturating_sub(1);
+        } else if is_key_pressed(KeyCode::Down) || wheel_y < 0.0 {
+            self.help_scroll += 1;
+        } else if is_key_pressed(KeyCode::PageUp) {
+            self.help_scroll = self.help_scroll.saturating_sub(HELP_PAGE_ROWS);
+        } else if is_key_pressed(KeyCode::PageDown) {
+            self.help_scroll += HELP_PAGE_ROWS;
+        }
+        self.help_scroll = self.help_scroll.min(rows.saturating_sub(1));
+
+        let back = self
+            .display
+            .ui
+            .render_help(&sections, &self.help_query, self.help_scroll);
+        // Escape clears a search before it leaves
+        if is_key_pressed(KeyCode::Escape) && !self.help_query.is_empty() {
+            self.help_query.clear();
+            self.help_scroll = 0;
+        } else if back || is_key_pressed(KeyCode::Escape) {
+            self.current_scene = self.help_return.clone();
+        }
+    }
+
+    /// Opens the options scene, returning to the current scene when it closes
+    fn open_options(&mut self) {
+        // Zoom can also be changed with the zoom keys while playing
+        self.settings.zoom = self.display.zoom;
+        self.options_selection = 0;
+        self.options_return = self.current_scene.clone();
+        self.current_scene = SceneType::Options;
+    }
+
+    /// Updates the options scene, saving the settings when it is closed
+    fn update_options_scene(&mut self) {
+        let rows: Vec<(&str, String)> = SettingsEntry::ALL
+            .iter()
+            .map(|&entry| (entry.label(), self.settings.value_text(entry)))
+            .collect();
+        let clicked = self
+            .display
+            .ui
+            .render_options(&rows, self.options_selection);
+
+        // The back button sits after the last setting
+        let count = SettingsEntry::ALL.len() + 1;
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            self.options_selection = (self.options_selection + count - 1) % count;
+        } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            self.options_selection = (self.options_selection + 1) % count;
+        }
+
+        let selected = SettingsEntry::ALL.get(self.options_selection).copied();
+        let back = clicked == Some(SettingsEntry::ALL.len())
+            || is_key_pressed(KeyCode::Escape)
+            || (selected.is_none() && is_key_pressed(KeyCode::Enter));
+
+        let adjusted =
+            if let Some(index) = clicked.filter(|&index| index < SettingsEntry::ALL.len()) {
+                self.options_selection = index;
+                Some((SettingsEntry::ALL[index], true))
+            } else if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::Enter) {
+                selected.map(|entry| (entry, true))
+            } else if is_key_pressed(KeyCode::Left) {
+                selected.map(|entry| (entry, false))
+            } else {
+                None
+            };
+        // Changes take effect as they are made, so they can be seen behind the menu
+        if let Some((entry, forward)) = adjusted {
+            self.settings.adjust(entry, forward);
+            self.apply_settings();
+        }
+
+        if back {
+            self.menu_status = self
+                .settings
+                .save(Path::new(crate::config::SETTINGS_FILE))
+                .err()
+                .map(|e| format!("Could not save settings: {}", e));
+            self.current_scene = self.options_return.clone();
+        }
+    }
+
+    /// Updates the stair confirmation scene shown before stairs that end the run
+    async fn update_confirm_stairs_scene(&mut self, direction: StairDirection) -> ThatchResult<()> {
+        let warning = match self.game_state.stairs_ending(&direction) {
+            Some(GameCompletionState::EscapedEarly) => {
+                "Climbing these stairs leaves the dungeon and ends your run."
+            }
+            Some(GameCompletionState::EscapedWithArtifact) => {
+                "Climbing these stairs carries the Amulet out of the dungeon and wins the game."
+            }
+            _ => {
+                // Nothing to confirm any more
+                self.current_scene = SceneType::Playing;
+                return Ok(());
+            }
+        };
+        let exploration = self.game_state.current_exploration();
+        let lines = vec![
+            warning.to_string(),
+            format!(
+                "You have explored {}% of this floor and found {} of its {} rooms.",
+                exploration.tile_percentage(),
+                exploration.rooms_discovered,
+                exploration.total_rooms
+            ),
+        ];
+
+        self.display.render_game(&self.game_state).await?;
+        self.display.ui.render_confirmation("Are you sure?", &lines);
+
+        if is_key_pressed(KeyCode::Y) {
+            self.current_scene = SceneType::Playing;
+            self.handle_game_action(PlayerInput::UseStairs(direction))
+                .await?;
+        } else if is_key_pressed(KeyCode::N) || is_key_pressed(KeyCode::Escape) {
+            self.current_scene = SceneType::Playing;
+            self.display
+                .add_message("You decide to stay in the dungeon.".to_string());
+        }
+        Ok(())
+    }
+
+    /// Updates the pause menu shown over the map
+    async fn update_pause_scene(&mut self) -> ThatchResult<()> {
+        self.display.render_game(&self.game_state).await?;
+        let save_mode = self.settings.save_mode;
+        let labels: Vec<&str> = PauseMenuEntry::ALL
+            .iter()
+            .map(|entry| entry.label(save_mode))
+            .collect();
+        let clicked = self.display.ui.render_pause_menu(
+            &labels,
+            self.pause_selection,
+            self.menu_status.as_deref(),
+        );
 
-                PlayerInput::DebugDamage => {
-                    self.handle_debug_damage()?;
-                }
+        if is_key_pressed(KeyCode::Escape) {
+            self.current_scene = SceneType::Playing;
+            return Ok(());
+        }
 
-                PlayerInput::ToggleAutoexplore => {
-                    let enabled = self.game_state.toggle_autoexplore();
-                    if enabled {
-                        self.display.add_message("Autoexplore enabled (F12 to toggle off)".to_string());
-                    } else {
-                        self.display.add_message("Autoexplore disabled".to_string());
-                    }
-                }
+        let count = PauseMenuEntry::ALL.len();
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            self.pause_selection = (self.pause_selection + count - 1) % count;
+        } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            self.pause_selection = (self.pause_selection + 1) % count;
+        }
 
-                _ => {
-                    self.handle_game_action(input).await?;
+        let chosen = clicked
+            .or_else(|| self.input_handler.get_menu_selection())
+            .or_else(|| is_key_pressed(KeyCode::Enter).then_some(self.pause_selection));
+        let Some(entry) = chosen.and_then(|index| PauseMenuEntry::ALL.get(index).copied()) else {
+            return Ok(());
+        };
+
+        self.menu_status = None;
+        match entry {
+            PauseMenuEntry::Resume => self.current_scene = SceneType::Playing,
+            PauseMenuEntry::Save => {
+                let saved = self.saves.save(&self.game_state);
+                if saved.is_ok() && save_mode.is_permadeath() {
+                    self.current_scene = SceneType::MainMenu;
                 }
+                self.menu_status = Some(match saved {
+                    Ok(()) => "Game saved.".to_string(),
+                    Err(e) => format!("Could not save the game: {}", e),
+                });
             }
-        } else {
-            // Handle autoexplore if no manual input
-            self.handle_autoexplore().await?;
+            PauseMenuEntry::Options => self.open_options(),
+            PauseMenuEntry::Quit => self.current_scene = SceneType::ConfirmQuit,
         }
+        Ok(())
+    }
 
-        // Check for scene transition
-        if self.game_state.is_game_ended() {
-            self.current_scene = SceneType::GameOver(self.game_state.get_completion_state().clone());
-        }
+    /// Updates the quit confirmation, returns true if exit is requested
+    async fn update_confirm_quit_scene(&mut self) -> ThatchResult<bool> {
+        let permadeath = self.settings.save_mode.is_permadeath();
+        let lines = if permadeath {
+            vec![
+                "Your character will be lost for good.".to_string(),
+                "Choose Save and quit on the pause menu to keep it.".to_string(),
+            ]
+        } else {
+            vec![
+                "Progress since your last save will be lost.".to_string(),
+                "Choose Save on the pause menu to keep it.".to_string(),
+            ]
+        };
 
-        // Render the current scene
         self.display.render_game(&self.game_state).await?;
-        
+        self.display
+            .ui
+            .render_confirmation("Quit without saving?", &lines);
+
+        if is_key_pressed(KeyCode::Y) {
+            if permadeath {
+                // Otherwise the next startup would offer the run back as a recovery
+                if let Err(e) = self.saves.discard_autosaves() {
+                    self.display
+                        .add_message(format!("Could not delete autosaves: {}", e));
+                }
+            }
+            return Ok(true);
+        }
+        if is_key_pressed(KeyCode::N) || is_key_pressed(KeyCode::Escape) {
+            self.current_scene = SceneType::Paused;
+        }
         Ok(false)
     }
 
     /// Updates the game over scene, returns true if exit is requested
-    async fn update_game_over_scene(&mut self, completion_state: GameCompletionState) -> ThatchResult<bool> {
+    async fn update_game_over_scene(
+        &mut self,
+        completion_state: GameCompletionState,
+    ) -> ThatchResult<bool> {
         // Render the ending screen
-        self.display.ui.render_ending_screen(&completion_state).await?;
+        let summary = self.game_state.run_summary();
+        self.display
+            .show_ending(&completion_state, &summary, self.morgue_path.as_deref())
+            .await?;
 
         // Handle input
         if is_key_pressed(KeyCode::N) {
@@ -124,17 +1555,31 @@ impl SceneManager {
 
     /// Handles a game action (movement, etc.)
     async fn handle_game_action(&mut self, input: PlayerInput) -> ThatchResult<()> {
-        if let Some(action) = self.input_handler.input_to_action(input, &self.game_state)? {
-            match action.execute(&mut self.game_state) {
-                Ok(events) => {
-                    self.process_game_events(events).await?;
-                    self.game_state.advance_turn()?;
+        if let Some(action) = self
+            .input_handler
+            .input_to_action(input, &self.game_state)?
+        {
+            let action = match self.game_state.stumble(&action) {
+                Some(stumble) => {
+                    self.display
+                        .add_message("You stumble about in confusion.".to_string());
+                    stumble
                 }
-                Err(e) => {
-                    // Suppress wall collision messages to reduce noise
-                    if !e.to_string().contains("Position is blocked") {
-                        self.display.add_message(format!("Invalid action: {}", e));
-                    }
+                None => action,
+            };
+            self.perform_action(action).await?;
+        }
+        Ok(())
+    }
+
+    /// Executes a player action and, if it succeeds, advances the turn
+    async fn perform_action(&mut self, action: ConcreteAction) -> ThatchResult<()> {
+        match action.execute(&mut self.game_state) {
+            Ok(events) => self.finish_turn(&action, events).await?,
+            Err(e) => {
+                // Suppress wall collision messages to reduce noise
+                if !is_wall_bump(&e) {
+                    self.display.add_message(format!("Invalid action: {}", e));
                 }
             }
         }
@@ -143,37 +1588,679 @@ impl SceneManager {
 
     /// Handles autoexplore actions
     async fn handle_autoexplore(&mut self) -> ThatchResult<()> {
+        if let Some(reason) = self.game_state.check_autoexplore_interruption() {
+            self.display.add_message(reason);
+            return Ok(());
+        }
+
         if let Some(autoexplore_action) = self.game_state.get_autoexplore_action()? {
             match autoexplore_action.execute(&mut self.game_state) {
-                Ok(events) => {
-                    self.process_game_events(events).await?;
-                    self.game_state.advance_turn()?;
+                Ok(events) => self.finish_turn(&autoexplore_action, events).await?,
+                Err(ThatchError::MoveBlocked {
+                    by: BlockedBy::Entity(blocker),
+                    ..
+                }) => {
+                    // Something wandered into the path; let the player deal with it
+                    self.game_state.toggle_autoexplore();
+                    let name = self.game_state.entities.get(&blocker).map_or_else(
+                        || "Something".to_string(),
+                        |entity| entity.as_entity().name().to_string(),
+                    );
+                    self.display
+                        .add_message(format!("Autoexplore stopped: {} is in the way", name));
                 }
                 Err(e) => {
                     // Autoexplore failed, disable it
                     self.game_state.toggle_autoexplore();
-                    self.display.add_message(format!("Autoexplore disabled due to error: {}", e));
+                    self.display
+                        .add_message(format!("Autoexplore disabled due to error: {}", e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles input for the open travel menu
+    fn update_travel_menu(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.travel_menu = None;
+            return;
+        }
+
+        let Some(menu) = self.travel_menu.as_mut() else {
+            return;
+        };
+        if is_key_pressed(KeyCode::PageDown) || is_key_pressed(KeyCode::Right) {
+            menu.next_page();
+            return;
+        } else if is_key_pressed(KeyCode::PageUp) || is_key_pressed(KeyCode::Left) {
+            menu.previous_page();
+            return;
+        }
+
+        let Some(index) = self.input_handler.get_menu_selection() else {
+            return;
+        };
+        let Some(destination) = menu.select(index).cloned() else {
+            return;
+        };
+
+        self.travel_menu = None;
+        match self.game_state.start_travel_to(&destination) {
+            Ok(()) => self
+                .display
+                .add_message(format!("Travelling to: {}", destination.label())),
+            Err(e) => self.display.add_message(e.to_string()),
+        }
+    }
+
+    /// Handles input for the open spell menu, casting the chosen spell
+    async fn update_spell_menu(&mut self) -> ThatchResult<()> {
+        if is_key_pressed(KeyCode::Escape) {
+            self.spell_menu = None;
+            return Ok(());
+        }
+
+        let Some(index) = self.input_handler.get_menu_selection() else {
+            return Ok(());
+        };
+        let Some(spell) = self
+            .spell_menu
+            .as_ref()
+            .and_then(|spells| spells.get(index).copied())
+        else {
+            return Ok(());
+        };
+
+        self.spell_menu = None;
+        self.handle_game_action(PlayerInput::CastSpell(spell.name.to_string()))
+            .await
+    }
+
+    /// Gets the contents of the container in the open container menu, once it
+    /// has been opened.
+    fn container_menu_contents(&self) -> Option<&[Item]> {
+        let container_id = self.container_menu?;
+        self.game_state
+            .world
+            .current_level()?
+            .containers
+            .iter()
+            .find(|container| container.id == container_id && container.opened)
+            .map(|container| container.contents.as_slice())
+    }
+
+    /// Handles input for the open container menu, taking the chosen item
+    async fn update_container_menu(&mut self) -> ThatchResult<()> {
+        if is_key_pressed(KeyCode::Escape) {
+            self.container_menu = None;
+            return Ok(());
+        }
+
+        let Some(index) = self.input_handler.get_menu_selection() else {
+            return Ok(());
+        };
+        let (Some(container_id), Some(player_id)) =
+            (self.container_menu, self.game_state.player_id)
+        else {
+            return Ok(());
+        };
+        let Some(item_id) = self
+            .container_menu_contents()
+            .and_then(|items| items.get(index))
+            .map(|item| item.id)
+        else {
+            return Ok(());
+        };
+
+        let action = TakeFromContainerAction::new(player_id, container_id, item_id);
+        self.perform_action(ConcreteAction::TakeFromContainer(action))
+            .await?;
+        if self
+            .container_menu_contents()
+            .is_none_or(|items| items.is_empty())
+        {
+            self.container_menu = None;
+        }
+        Ok(())
+    }
+
+    /// Opens the dialogue box on everything an NPC has to say
+    fn start_dialogue(&mut self, npc_id: EntityId) {
+        let speaker = match self.game_state.entities.get(&npc_id) {
+            Some(npc) => npc.name().to_string(),
+            None => return,
+        };
+        match self.game_state.npc_dialogue(npc_id) {
+            Ok(lines) if !lines.is_empty() => {
+                self.dialogue = Some(Conversation::new(npc_id, speaker, lines));
+            }
+            Ok(_) => {}
+            Err(e) => self.display.add_message(e.to_string()),
+        }
+    }
+
+    /// Handles input for the dialogue box, moving through the NPC's lines
+    fn update_dialogue(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.dialogue = None;
+            return;
+        }
+        if !(is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space)) {
+            return;
+        }
+        if let Some(conversation) = &mut self.dialogue {
+            if !conversation.advance() {
+                self.dialogue = None;
+            }
+        }
+    }
+
+    /// Handles input for the open interaction menu, closing it and returning
+    /// the input for the interaction chosen by key or by `clicked`
+    fn update_interact_menu(&mut self, clicked: Option<usize>) -> Option<PlayerInput> {
+        if is_key_pressed(KeyCode::Escape) {
+            self.interact_menu = None;
+            return None;
+        }
+
+        let index = clicked.or_else(|| self.input_handler.get_menu_selection())?;
+        let interaction = self
+            .interact_menu
+            .as_ref()
+            .and_then(|entries| entries.get(index))
+            .map(|entry| entry.interaction.clone())?;
+        self.interact_menu = None;
+        Some(interaction.into())
+    }
+
+    /// Handles input for the open use menu, using, wearing or taking off the
+    /// chosen item
+    async fn update_use_menu(&mut self) -> ThatchResult<()> {
+        if is_key_pressed(KeyCode::Escape) {
+            self.use_menu = None;
+            return Ok(());
+        }
+
+        let Some(index) = self.input_handler.get_menu_selection() else {
+            return Ok(());
+        };
+        let Some(item_id) = self
+            .use_menu
+            .as_ref()
+            .and_then(|items| items.get(index).copied())
+        else {
+            return Ok(());
+        };
+
+        self.use_menu = None;
+        let Some(player) = self.game_state.get_player() else {
+            return Ok(());
+        };
+        let player_id = player.id();
+        let worn_slot = player
+            .equipment
+            .iter()
+            .find(|(_, id)| **id == item_id)
+            .map(|(slot, _)| slot.clone());
+        let equip_slot = self
+            .game_state
+            .carried_items
+            .get(&item_id)
+            .and_then(|item| item.item_type.equip_slot());
+
+        let action = match (worn_slot, equip_slot) {
+            (Some(slot), _) => ConcreteAction::Unequip(UnequipItemAction::new(player_id, slot)),
+            (None, Some(slot)) => {
+                ConcreteAction::Equip(EquipItemAction::new(player_id, item_id, slot.to_string()))
+            }
+            (None, None) => ConcreteAction::UseItem(UseItemAction::new(player_id, item_id)),
+        };
+        self.perform_action(action).await
+    }
+
+    /// Shows the inventory screen and handles its input, carrying out the
+    /// command chosen for the selected item
+    ///
+    /// Commands that take a turn close the screen so their outcome can be seen
+    /// on the map; examining keeps it open with the item's description.
+    async fn update_inventory_screen(&mut self) -> ThatchResult<()> {
+        let entries = InventoryEntry::list(&self.game_state);
+        let (Some(selected), Some(last)) = (self.inventory_selection, entries.len().checked_sub(1))
+        else {
+            self.inventory_selection = None;
+            return Ok(());
+        };
+        let selected = selected.min(last);
+        let capacity = self
+            .game_state
+            .get_player()
+            .map_or(0, |player| player.inventory_capacity);
+        let clicked = self.display.ui.render_inventory(
+            &entries,
+            selected,
+            capacity,
+            self.inventory_detail.as_deref(),
+        );
+
+        if clicked == Some(InventoryClick::Close)
+            || is_key_pressed(KeyCode::Escape)
+            || is_key_pressed(KeyCode::I)
+        {
+            self.inventory_selection = None;
+            return Ok(());
+        }
+
+        let count = entries.len();
+        let mut selection = selected;
+        if let Some(InventoryClick::Item(index)) = clicked {
+            selection = index;
+        } else if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::Left) {
+            selection = (selected + count - 1) % count;
+        } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::Right) {
+            selection = (selected + 1) % count;
+        }
+        if selection != selected {
+            self.inventory_detail = None;
+        }
+        self.inventory_selection = Some(selection);
+
+        let mut typed = None;
+        while let Some(c) = get_char_pressed() {
+            typed = Some(c.to_ascii_uppercase());
+        }
+        let entry = &entries[selection];
+        let command = match clicked {
+            Some(InventoryClick::Command(command)) => Some(command),
+            _ if is_key_pressed(KeyCode::Enter) => entry.commands.first().copied(),
+            _ => typed.and_then(|c| {
+                entry
+                    .commands
+                    .iter()
+                    .copied()
+                    .find(|command| command.key() == c)
+            }),
+        };
+        let Some(command) = command else {
+            return Ok(());
+        };
+
+        match command.action(&self.game_state, entry.item_id) {
+            Ok(Some(action)) => {
+                self.inventory_selection = None;
+                self.perform_action(action).await?;
+            }
+            Ok(None) => {
+                self.inventory_detail = self
+                    .game_state
+                    .carried_items
+                    .get(&entry.item_id)
+                    .map(|item| self.game_state.describe_item(item));
+            }
+            Err(e) => self.inventory_detail = Some(e.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Handles input while look mode is active, moving the cursor over visible tiles
+    fn update_look_mode(&mut self, cursor: Position) -> ThatchResult<()> {
+        if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::X) {
+            self.look_cursor = None;
+            self.look_description = None;
+            return Ok(());
+        }
+
+        let Some(delta) = self.input_handler.pressed_movement_delta() else {
+            return Ok(());
+        };
+        let target = cursor + delta;
+        let visible = self
+            .game_state
+            .world
+            .current_level()
+            .and_then(|level| level.get_tile(target))
+            .is_some_and(|tile| tile.is_visible());
+        if !visible {
+            return Ok(());
+        }
+
+        self.look_cursor = Some(target);
+        self.look_at(target)
+    }
+
+    /// Examines a position with a look action, without spending a turn, and
+    /// shows what is there in the look bar rather than the message log
+    fn look_at(&mut self, position: Position) -> ThatchResult<()> {
+        let player_id = self
+            .game_state
+            .player_id
+            .ok_or_else(|| ThatchError::InvalidState("No player found".to_string()))?;
+
+        for event in LookAction::new(player_id, position).execute(&mut self.game_state)? {
+            if let crate::GameEvent::Message { text, .. } = event {
+                self.look_description = Some(text);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles one step of travel
+    async fn handle_travel(&mut self) -> ThatchResult<()> {
+        if let Some(reason) = self.game_state.check_travel_interruption() {
+            self.display.add_message(reason);
+            return Ok(());
+        }
+
+        let travel_action = match self.game_state.get_travel_action() {
+            Ok(action) => action,
+            Err(e) => {
+                self.display
+                    .add_message(format!("Travel interrupted: {}", e));
+                return Ok(());
+            }
+        };
+
+        if let Some(action) = travel_action {
+            match action.execute(&mut self.game_state) {
+                Ok(events) => self.finish_turn(&action, events).await?,
+                Err(e) => {
+                    // Something moved into the way, stop travelling
+                    self.game_state.cancel_travel();
+                    self.display
+                        .add_message(format!("Travel interrupted: {}", e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles one repeat of a counted command
+    async fn handle_repeat(&mut self) -> ThatchResult<()> {
+        if let Some(reason) = self.game_state.check_repeat_interruption() {
+            self.display.add_message(reason);
+            return Ok(());
+        }
+
+        if let Some(action) = self.game_state.get_repeat_action() {
+            match action.execute(&mut self.game_state) {
+                Ok(events) => self.finish_turn(&action, events).await?,
+                Err(e) => {
+                    // Walking into a wall just ends the repeat
+                    self.game_state.cancel_repeat();
+                    if !is_wall_bump(&e) {
+                        self.display.add_message(format!("Invalid action: {}", e));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles one blow or step of an auto-fight
+    async fn handle_fight(&mut self) -> ThatchResult<()> {
+        if let Some(reason) = self.game_state.check_fight_interruption() {
+            self.display.add_message(reason);
+            return Ok(());
+        }
+
+        let fight_action = match self.game_state.get_fight_action() {
+            Ok(action) => action,
+            Err(e) => {
+                self.display
+                    .add_message(format!("You stop fighting: {}", e));
+                return Ok(());
+            }
+        };
+
+        if let Some(action) = fight_action {
+            match action.execute(&mut self.game_state) {
+                Ok(events) => self.finish_turn(&action, events).await?,
+                Err(e) => {
+                    self.game_state.cancel_fight();
+                    self.display
+                        .add_message(format!("You stop fighting: {}", e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles one turn of rest
+    async fn handle_rest(&mut self) -> ThatchResult<()> {
+        if let Some(report) = self.game_state.check_rest_completion() {
+            self.display.add_message(report);
+            return Ok(());
+        }
+
+        if let Some(action) = self.game_state.get_rest_action() {
+            match action.execute(&mut self.game_state) {
+                Ok(events) => self.finish_turn(&action, events).await?,
+                Err(e) => {
+                    self.game_state.cancel_rest();
+                    self.display.add_message(format!("Rest interrupted: {}", e));
                 }
             }
         }
         Ok(())
     }
 
+    /// Finishes the turn of an action that executed, processing its events and
+    /// then those of the turn advancing
+    async fn finish_turn(
+        &mut self,
+        action: &ConcreteAction,
+        events: Vec<crate::GameEvent>,
+    ) -> ThatchResult<()> {
+        self.log_event(&EventLogEntry::Action {
+            turn: self.game_state.turn_number,
+            action,
+        });
+        #[cfg(feature = "dev-tools")]
+        self.rewind.record(self.game_state.turn_number, action);
+        crate::crash_recorder().record_action(self.game_state.turn_number, action);
+        self.process_game_events(events).await?;
+        let turn_events = self.game_state.advance_turn()?;
+        self.log_event(&EventLogEntry::Turn {
+            turn: self.game_state.turn_number,
+        });
+        self.process_game_events(turn_events).await?;
+        crate::crash_recorder().record_state(&self.game_state);
+        Ok(())
+    }
+
+    /// Rolls the game back `turns` turns by replaying from the last checkpoint
+    /// before them
+    #[cfg(feature = "dev-tools")]
+    fn undo_turns(&mut self, turns: u32) {
+        match self.rewind.rewind(&self.game_state, u64::from(turns)) {
+            Ok(game_state) => {
+                self.game_state = game_state;
+                self.display.skip_animations();
+                self.display.map_batch.invalidate();
+                let turn = self.game_state.turn_number;
+                self.display
+                    .add_message(format!("Rewound to turn {}", turn));
+            }
+            Err(e) => self.display.add_message(format!("Cannot undo: {}", e)),
+        }
+    }
+
+    /// Appends an entry to the event log, if one is open
+    ///
+    /// A failed write closes the log rather than interrupting the game.
+    fn log_event(&mut self, entry: &EventLogEntry) {
+        let Some(log) = self.event_log.as_mut() else {
+            return;
+        };
+        if let Err(e) = log.record(entry) {
+            self.event_log = None;
+            self.display.add_message(format!("Event log closed: {}", e));
+        }
+    }
+
     /// Processes game events and displays messages
+    ///
+    /// Response events are processed in turn, so damage that kills the player
+    /// goes on to end the game.
     async fn process_game_events(&mut self, events: Vec<crate::GameEvent>) -> ThatchResult<()> {
-        for event in &events {
-            let response_events = self.game_state.process_event(event)?;
-            
-            // Display any messages from events
-            for response_event in response_events {
-                if let crate::GameEvent::Message { text, .. } = response_event {
-                    self.display.add_message(text);
+        let mut queue: std::collections::VecDeque<_> = events.into();
+        while let Some(event) = queue.pop_front() {
+            self.log_event(&EventLogEntry::Event {
+                turn: self.game_state.turn_number,
+                event: &event,
+            });
+            let response_events = self.game_state.process_event(&event)?;
+            self.display.animate_event(&event, &self.game_state);
+
+            // Display any messages, whether from the action or a response
+            match event {
+                crate::GameEvent::Message {
+                    text,
+                    importance,
+                    channel,
+                } => self.display.add_game_message(text, importance, channel),
+                crate::GameEvent::AchievementUnlocked { achievement } => {
+                    self.record_achievement(achievement)
                 }
+                crate::GameEvent::PlayerChangedLevel { .. } => self.record_tutorial_progress(),
+                _ => {}
             }
+
+            queue.extend(response_events);
         }
         Ok(())
     }
 
+    /// Describes the player's surroundings once per turn when narration is on.
+    fn narrate_surroundings(&mut self) {
+        let narration = self.settings.narration;
+        let turn = self.game_state.turn_number;
+        if !narration.is_enabled() || self.narrated_turn == Some(turn) {
+            return;
+        }
+        self.narrated_turn = Some(turn);
+
+        if let Some(text) = crate::describe_surroundings(&self.game_state) {
+            if narration.to_stdout() {
+                println!("{}", text);
+            }
+            self.display.add_game_message(
+                text,
+                crate::MessageImportance::Normal,
+                crate::MessageChannel::Exploration,
+            );
+        }
+    }
+
+    /// Autosaves the game when the player changes level or the autosave interval passes.
+    ///
+    /// Failures are reported in the message log but never interrupt the game.
+    fn autosave(&mut self) {
+        match self.saves.update(&self.game_state) {
+            Ok(Some(_)) => self.display.add_game_message(
+                "Game autosaved.".to_string(),
+                crate::MessageImportance::Info,
+                crate::MessageChannel::System,
+            ),
+            Ok(None) => {}
+            Err(e) => self
+                .display
+                .add_message(format!("Could not autosave: {}", e)),
+        }
+    }
+
+    /// Toasts an achievement and saves it to the progress file if it is new.
+    ///
+    /// Failures are reported in the message log but never interrupt the game.
+    fn record_achievement(&mut self, achievement: crate::Achievement) {
+        self.display.show_toast(format!(
+            "Achievement unlocked: {}",
+            achievement.display_name()
+        ));
+        if !self.progress.unlock(achievement) {
+            return;
+        }
+        if let Err(e) = self.progress.save(Path::new(crate::config::PROGRESS_FILE)) {
+            self.display
+                .add_message(format!("Could not save achievements: {}", e));
+        }
+    }
+
+    /// Notes in the progress file once the tutorial is finished, so later
+    /// new games skip it.
+    fn record_tutorial_progress(&mut self) {
+        let finished = self
+            .game_state
+            .tutorial
+            .as_ref()
+            .is_some_and(|t| t.is_finished());
+        if !finished || self.progress.tutorial_completed {
+            return;
+        }
+        self.progress.tutorial_completed = true;
+        if let Err(e) = self.progress.save(Path::new(crate::config::PROGRESS_FILE)) {
+            self.display
+                .add_message(format!("Could not save progress: {}", e));
+        }
+    }
+
+    /// Writes the morgue file for the finished run and remembers its path.
+    ///
+    /// Failures are reported in the message log but never interrupt the ending.
+    fn write_morgue_file(&mut self) {
+        let report = MorgueReport::from_game_state(
+            &self.game_state,
+            &self.display.messages.texts(),
+            crate::DEFAULT_MORGUE_MESSAGE_COUNT,
+        );
+
+        match report.write_to_default_directory() {
+            Ok(path) => {
+                #[cfg(feature = "dev-tools")]
+                tracing::info!("Morgue file written to {}", path.display());
+                #[cfg(not(feature = "dev-tools"))]
+                println!("Morgue file written to {}", path.display());
+                self.morgue_path = Some(path);
+            }
+            Err(e) => {
+                self.morgue_path = None;
+                self.display
+                    .add_message(format!("Failed to write morgue file: {}", e));
+            }
+        }
+    }
+
+    /// Adds the finished run to the high-score table and saves it if the run
+    /// made the table.
+    ///
+    /// Failures are reported in the message log but never interrupt the ending.
+    fn record_high_score(&mut self) {
+        let Some(player) = self.game_state.get_player() else {
+            return;
+        };
+        let run = crate::HighScore {
+            score: self.game_state.score().total(),
+            seed: self.game_state.rng_seed,
+            class: player.class,
+            character_name: player.name().to_string(),
+            ending: self.game_state.get_completion_state().clone(),
+            depth: self.game_state.statistics.max_depth_reached + 1,
+            turns: self.game_state.turn_number,
+        };
+
+        let path = Path::new(crate::config::HIGH_SCORES_FILE);
+        let mut table = HighScoreTable::load_or_default(path);
+        let Some(place) = table.record(run) else {
+            return;
+        };
+        self.display
+            .show_toast(format!("New high score: #{}", place));
+        if let Err(e) = table.save(path) {
+            self.display
+                .add_message(format!("Could not save high scores: {}", e));
+        }
+    }
+
     /// Handles debug damage command
     fn handle_debug_damage(&mut self) -> ThatchResult<()> {
         if let Some(player_id) = self.game_state.player_id {
@@ -181,47 +2268,64 @@ impl SceneManager {
             tracing::info!("Debug damage command executed - dealing 150 damage");
             #[cfg(not(feature = "dev-tools"))]
             println!("Debug damage command executed - dealing 150 damage");
-            
+
             if let Some(player) = self.game_state.get_player() {
                 #[cfg(feature = "dev-tools")]
-                tracing::info!("Player current health: {}/{}", player.stats.health, player.stats.max_health);
+                tracing::info!(
+                    "Player current health: {}/{}",
+                    player.stats.health,
+                    player.stats.max_health
+                );
                 #[cfg(not(feature = "dev-tools"))]
-                println!("Player current health: {}/{}", player.stats.health, player.stats.max_health);
+                println!(
+                    "Player current health: {}/{}",
+                    player.stats.health, player.stats.max_health
+                );
             }
-            
+
             let damage_event = crate::GameEvent::EntityDamaged {
                 entity_id: player_id,
                 damage: 150, // Enough to kill player with 100 HP
                 source: None,
             };
-            
+
             // Process damage through the player's handle_event first
-            if let Some(crate::ConcreteEntity::Player(ref mut player)) = self.game_state.entities.get_mut(&player_id) {
+            if let Some(player) = self
+                .game_state
+                .entities
+                .get_mut(&player_id)
+                .and_then(crate::ConcreteEntity::as_player_mut)
+            {
                 #[cfg(feature = "dev-tools")]
                 tracing::info!("Calling player.handle_event() directly");
                 #[cfg(not(feature = "dev-tools"))]
                 println!("Calling player.handle_event() directly");
-                
+
                 match player.handle_event(&damage_event) {
                     Ok(events) => {
                         #[cfg(feature = "dev-tools")]
                         tracing::info!("Player.handle_event() returned {} events", events.len());
                         #[cfg(not(feature = "dev-tools"))]
                         println!("Player.handle_event() returned {} events", events.len());
-                        
+
                         for event in &events {
                             #[cfg(feature = "dev-tools")]
                             tracing::info!("Event from player: {:?}", event);
                             #[cfg(not(feature = "dev-tools"))]
                             println!("Event from player: {:?}", event);
                         }
-                        
+
                         // Now process these events through game state
                         for event in events {
                             let response_events = self.game_state.process_event(&event)?;
                             for response_event in response_events {
-                                if let crate::GameEvent::Message { text, .. } = response_event {
-                                    self.display.add_message(text);
+                                if let crate::GameEvent::Message {
+                                    text,
+                                    importance,
+                                    channel,
+                                } = response_event
+                                {
+                                    self.display.add_game_message(text, importance, channel);
                                 }
                             }
                         }
@@ -240,18 +2344,53 @@ impl SceneManager {
 
     /// Starts a new game with a fresh dungeon
     async fn start_new_game(&mut self) -> ThatchResult<()> {
-        let new_seed = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let class = self
+            .game_state
+            .get_player()
+            .map(|player| player.class)
+            .unwrap_or_default();
+        self.play_tutorial = !self.progress.is_returning_player();
+        self.start_new_game_with_seed(None, class).await
+    }
+
+    /// Starts a new game as `class` with the given seed, or a time-based one if none is given
+    async fn start_new_game_with_seed(
+        &mut self,
+        seed: Option<u64>,
+        class: CharacterClass,
+    ) -> ThatchResult<()> {
+        let new_seed = seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        });
 
         #[cfg(feature = "dev-tools")]
         tracing::info!("Starting new game with seed: {}", new_seed);
         #[cfg(not(feature = "dev-tools"))]
         println!("Starting new game with seed: {}", new_seed);
 
-        // Create new game state
-        self.game_state = GameState::new_with_complete_dungeon(new_seed)?;
+        // Create new game state, keeping the dungeon style, shape, and streaming of the
+        // previous run, with floors themed by the LLDM if it is enabled
+        let config = crate::GenerationConfig {
+            generator: self.game_state.generator,
+            templates: self.templates.clone(),
+            vaults: self.vaults.clone(),
+            shape: self.game_state.world.shape,
+            use_lldm: self.settings.lldm_enabled,
+            ..crate::GenerationConfig::new(new_seed)
+        };
+        self.game_state = if self.game_state.lazy_floors {
+            GameState::new_lazy_with_generation_config(config)?
+        } else {
+            GameState::new_with_generation_config(config)?
+        };
+        if self.play_tutorial {
+            self.game_state.start_tutorial(&self.templates)?;
+        }
+        self.floor_streamer = FloorStreamer::for_game(&self.game_state);
+        self.apply_settings();
 
         // Create and place new player
         let player_pos = if let Some(level) = self.game_state.world.current_level() {
@@ -259,20 +2398,57 @@ impl SceneManager {
         } else {
             return Err(ThatchError::InvalidState("No current level".to_string()));
         };
-        
-        let player = crate::PlayerCharacter::new("Player".to_string(), player_pos);
-        let player_id = self.game_state.add_entity(player.into())?;
-        self.game_state.set_player_id(player_id);
+
+        self.game_state
+            .initialize_player_with_class("Player".to_string(), player_pos, class)?;
 
         // Initialize player visibility
         if let Some(player) = self.game_state.get_player() {
-            self.game_state.update_player_visibility(player.position())?;
+            self.game_state
+                .update_player_visibility(player.position())?;
         }
+        self.saves.track(&self.game_state);
 
         // Reset scene to playing
         self.current_scene = SceneType::Playing;
-        self.display.add_message("New game started!".to_string());
+        self.morgue_path = None;
+        self.travel_menu = None;
+        self.spell_menu = None;
+        self.use_menu = None;
+        self.interact_menu = None;
+        self.container_menu = None;
+        self.quest_log_open = false;
+        self.message_log_open = false;
+        self.narrated_turn = None;
+        self.dialogue = None;
+        self.display.add_message(format!(
+            "New game started as a {}! Press Shift+C to use {}.",
+            class.display_name(),
+            class.ability().display_name()
+        ));
+        // Show what the game announced while placing the player, like the first quest
+        for message in &self.game_state.recent_messages {
+            self.display.add_message(message.text.clone());
+        }
+        if self.game_state.tutorial.is_some() {
+            self.display.add_game_message(
+                crate::TutorialStep::Walk.instructions().to_string(),
+                crate::MessageImportance::Important,
+                crate::MessageChannel::System,
+            );
+        }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Returns true for the error of walking into a wall, which needs no message
+fn is_wall_bump(error: &ThatchError) -> bool {
+    matches!(
+        error,
+        ThatchError::MoveBlocked {
+            by: BlockedBy::Terrain,
+            ..
+        }
+    )
+}
@@ -0,0 +1,306 @@
+//! # Benchmark Workloads
+//!
+//! The expensive code paths whose speed is tracked between changes.
+//!
+//! The same workloads back the Criterion benchmarks in `benches/` and the
+//! `--bench-world` command line flag, which times them in whatever build it
+//! runs in so release performance can be checked without a bench toolchain:
+//!
+//! - full world generation, every floor, across [`BENCH_SEEDS`]
+//! - a single floor, which for the default generator is dominated by
+//!   progressive wall placement
+//! - A* and Dijkstra pathfinding on floor [`BENCH_FLOOR`]
+//! - a field of view update at that floor's spawn point
+//! - a crowd of monsters stepping towards that spawn point, through a shared
+//!   [`DijkstraMap`] or a path search each, for every size in [`CROWD_SIZES`]
+//! - a whole turn with [`CROWD_TURN_SIZE`] monsters chasing the player
+
+use crate::{generate_lazy_floor, DijkstraMap, GameState, GenerationConfig, Level, Monster};
+use crate::{MonsterType, PathfindingUtils, Position, ThatchError, ThatchResult};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Seeds the world generation workload is measured across.
+pub const BENCH_SEEDS: [u64; 3] = [7, 42, 12345];
+
+/// Floor the pathfinding and field of view workloads run on.
+pub const BENCH_FLOOR: u32 = 5;
+
+/// Field of view updates timed per seed, since each one is quick.
+pub const FOV_RUNS: u32 = 100;
+
+/// Numbers of monsters the crowd movement workload is measured with.
+pub const CROWD_SIZES: [usize; 4] = [25, 50, 100, 200];
+
+/// Number of monsters chasing the player in the crowd turn workload.
+pub const CROWD_TURN_SIZE: usize = 250;
+
+/// The timing of one workload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchSample {
+    /// Name of the workload
+    pub name: &'static str,
+    /// Number of times it ran
+    pub runs: u32,
+    /// Time taken by every run together
+    pub total: Duration,
+}
+
+impl BenchSample {
+    /// Gets the average time of one run.
+    pub fn mean(&self) -> Duration {
+        self.total / self.runs.max(1)
+    }
+}
+
+impl fmt::Display for BenchSample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<24} {:>10.3} ms/run over {} runs",
+            self.name,
+            self.mean().as_secs_f64() * 1000.0,
+            self.runs
+        )
+    }
+}
+
+/// Generates a complete world for `seed`.
+///
+/// # Errors
+///
+/// Returns an error if the world cannot be generated.
+pub fn bench_generate_world(seed: u64) -> ThatchResult<GameState> {
+    GameState::new_with_complete_dungeon(seed)
+}
+
+/// Generates floor [`BENCH_FLOOR`] on its own for `seed`.
+///
+/// # Errors
+///
+/// Returns an error if the floor cannot be generated.
+pub fn bench_generate_floor(seed: u64) -> ThatchResult<Level> {
+    generate_lazy_floor(&GenerationConfig::new(seed), BENCH_FLOOR, None)
+}
+
+/// Finds a path from a floor's spawn point to its stairs down, or to the
+/// farthest reachable position on the deepest floor.
+pub fn bench_astar(level: &Level) -> Option<Vec<Position>> {
+    let pathfinding = PathfindingUtils::new();
+    let goal = level.stairs_down_position.or_else(|| {
+        let distances = pathfinding.distance_map(level, level.player_spawn);
+        distances
+            .into_iter()
+            .max_by_key(|&(position, distance)| (distance, position.y, position.x))
+            .map(|(position, _)| position)
+    })?;
+    pathfinding.find_path(level, level.player_spawn, goal)
+}
+
+/// Maps the walking distance from a floor's spawn point to everywhere on it.
+pub fn bench_dijkstra(level: &Level) -> HashMap<Position, u32> {
+    PathfindingUtils::new().distance_map(level, level.player_spawn)
+}
+
+/// Picks up to `count` passable positions spread evenly over a floor, away
+/// from its spawn point, for a crowd of monsters to stand on.
+pub fn bench_crowd(level: &Level, count: usize) -> Vec<Position> {
+    let open: Vec<Position> = (0..level.height as i32)
+        .flat_map(|y| (0..level.width as i32).map(move |x| Position::new(x, y)))
+        .filter(|&position| level.is_passable(position) && position != level.player_spawn)
+        .collect();
+    let spacing = (open.len() / count.max(1)).max(1);
+    open.into_iter().step_by(spacing).take(count).collect()
+}
+
+/// Steps every monster in a crowd towards a floor's spawn point by walking
+/// downhill on one shared [`DijkstraMap`].
+pub fn bench_crowd_dijkstra(level: &Level, crowd: &[Position]) -> Vec<Option<Position>> {
+    let map = DijkstraMap::new(level, level.player_spawn);
+    crowd
+        .iter()
+        .map(|&position| map.downhill(position, |next| crowd.contains(&next)))
+        .collect()
+}
+
+/// Steps every monster in a crowd towards a floor's spawn point with a path
+/// search of its own, as monsters did before sharing a [`DijkstraMap`].
+pub fn bench_crowd_astar(level: &Level, crowd: &[Position]) -> Vec<Option<Position>> {
+    let pathfinding = PathfindingUtils::new();
+    crowd
+        .iter()
+        .map(|&position| {
+            pathfinding
+                .find_path(level, position, level.player_spawn)?
+                .first()
+                .copied()
+        })
+        .collect()
+}
+
+/// Creates a game on floor [`BENCH_FLOOR`] with `count` goblins spread over
+/// the floor, all chasing the player at its spawn point.
+///
+/// # Errors
+///
+/// Returns an error if the world cannot be generated.
+pub fn bench_crowd_game(seed: u64, count: usize) -> ThatchResult<GameState> {
+    let mut game_state = bench_fov_game(seed)?;
+    let level = game_state
+        .world
+        .current_level()
+        .ok_or_else(|| ThatchError::InvalidState("Benchmark floor is missing".to_string()))?;
+    let spawn = level.player_spawn;
+    for position in bench_crowd(level, count) {
+        if game_state.get_entity_at_position(position).is_some() {
+            continue;
+        }
+        let mut goblin = Monster::new(MonsterType::Goblin, position);
+        goblin.investigating = Some(spawn);
+        let goblin_id = game_state.add_entity(goblin.into())?;
+        if let Some(level) = game_state.world.current_level_mut() {
+            level.add_entity(goblin_id);
+        }
+    }
+    Ok(game_state)
+}
+
+/// Creates a game with the player standing at the spawn point of floor
+/// [`BENCH_FLOOR`], ready for field of view updates.
+///
+/// # Errors
+///
+/// Returns an error if the world cannot be generated.
+pub fn bench_fov_game(seed: u64) -> ThatchResult<GameState> {
+    let mut game_state = bench_generate_world(seed)?;
+    game_state.world.current_level_id = BENCH_FLOOR;
+    let spawn = game_state
+        .world
+        .current_level()
+        .ok_or_else(|| ThatchError::InvalidState("Benchmark floor is missing".to_string()))?
+        .player_spawn;
+    game_state.initialize_player("Bencher".to_string(), spawn)?;
+    Ok(game_state)
+}
+
+/// Finds a tile next to a floor's spawn point for the player to step back
+/// and forth to, so field of view updates have something to redo.
+pub fn bench_fov_step(level: &Level) -> Position {
+    let spawn = level.player_spawn;
+    spawn
+        .cardinal_adjacent_positions()
+        .into_iter()
+        .find(|&next| level.is_passable(next))
+        .unwrap_or(spawn)
+}
+
+/// Times every workload once per seed, and the field of view [`FOV_RUNS`]
+/// times per seed. Crowd movement is timed with the largest of the
+/// [`CROWD_SIZES`].
+///
+/// # Errors
+///
+/// Returns an error if a world or floor cannot be generated.
+pub fn bench_world(seeds: &[u64]) -> ThatchResult<Vec<BenchSample>> {
+    let mut samples = [
+        "world generation",
+        "floor generation",
+        "A* spawn to stairs",
+        "Dijkstra distance map",
+        "field of view",
+        "crowd step, Dijkstra map",
+        "crowd step, A* each",
+        "crowd turn",
+    ]
+    .map(|name| BenchSample {
+        name,
+        runs: 0,
+        total: Duration::ZERO,
+    });
+    let mut time = |index: usize, runs: u32, started: Instant| {
+        samples[index].runs += runs;
+        samples[index].total += started.elapsed();
+    };
+
+    for &seed in seeds {
+        let started = Instant::now();
+        bench_generate_world(seed)?;
+        time(0, 1, started);
+
+        let started = Instant::now();
+        let level = bench_generate_floor(seed)?;
+        time(1, 1, started);
+
+        let started = Instant::now();
+        bench_astar(&level);
+        time(2, 1, started);
+
+        let started = Instant::now();
+        bench_dijkstra(&level);
+        time(3, 1, started);
+
+        let mut game_state = bench_fov_game(seed)?;
+        let floor = game_state.world.current_level();
+        let spawn = floor.map_or_else(Position::origin, |floor| floor.player_spawn);
+        let step = floor.map_or(spawn, bench_fov_step);
+        let started = Instant::now();
+        for run in 0..FOV_RUNS {
+            game_state.update_player_visibility(if run % 2 == 0 { step } else { spawn })?;
+        }
+        time(4, FOV_RUNS, started);
+
+        let crowd = bench_crowd(&level, CROWD_SIZES[CROWD_SIZES.len() - 1]);
+        let started = Instant::now();
+        bench_crowd_dijkstra(&level, &crowd);
+        time(5, 1, started);
+
+        let started = Instant::now();
+        bench_crowd_astar(&level, &crowd);
+        time(6, 1, started);
+
+        let mut game_state = bench_crowd_game(seed, CROWD_TURN_SIZE)?;
+        let started = Instant::now();
+        game_state.advance_turn()?;
+        time(7, 1, started);
+    }
+    Ok(samples.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_workloads_run_on_a_real_floor() {
+        let level = bench_generate_floor(BENCH_SEEDS[0]).unwrap();
+        assert_eq!(level.id, BENCH_FLOOR);
+        let path = bench_astar(&level).unwrap();
+        assert_eq!(path.last().copied(), level.stairs_down_position);
+
+        // Every step along the path is one further from the spawn point
+        let distances = bench_dijkstra(&level);
+        for (step, position) in path.iter().enumerate() {
+            assert_eq!(distances[position], step as u32 + 1);
+        }
+
+        // Every monster in a crowd steps one closer, never onto another
+        let crowd = bench_crowd(&level, CROWD_SIZES[0]);
+        assert_eq!(crowd.len(), CROWD_SIZES[0]);
+        let map = DijkstraMap::new(&level, level.player_spawn);
+        let steps = bench_crowd_dijkstra(&level, &crowd);
+        assert!(steps.iter().any(Option::is_some));
+        for (from, step) in crowd.iter().zip(&steps) {
+            if let Some(step) = step {
+                assert_eq!(map.distance(*step), map.distance(*from).map(|d| d - 1));
+                assert!(!crowd.contains(step));
+            }
+        }
+        assert_eq!(bench_crowd_astar(&level, &crowd).len(), crowd.len());
+
+        let samples = bench_world(&[BENCH_SEEDS[0]]).unwrap();
+        assert_eq!(samples.len(), 8);
+        assert!(samples.iter().all(|sample| sample.runs > 0));
+        assert_eq!(samples[4].runs, FOV_RUNS);
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! Utility functions for mathematics, pathfinding, and general game operations.
 
+pub mod bench;
 pub mod math;
 pub mod pathfinding;
+pub mod profiler;
+pub mod settings;
+pub mod simulate;
 
+pub use bench::*;
 pub use math::*;
 pub use pathfinding::*;
+pub use profiler::*;
+pub use settings::*;
+pub use simulate::*;
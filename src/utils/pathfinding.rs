@@ -1,10 +1,87 @@
 //! # Pathfinding Algorithms
 //!
 //! Pathfinding utilities for AI movement and navigation.
+//!
+//! Besides paths within a single level, routes can be planned between floors.
+//! Stairs down on one floor sit at the same position as stairs up on the floor
+//! below, so a route is a chain of per-floor paths, each ending on the stairs
+//! that lead towards the destination floor.
+//!
+//! Teleporters count as edges within a floor: stepping onto one leaves the
+//! walker on its partner, so paths can cut across a level through them.
+//!
+//! Many walkers heading for the same goal share a [`DijkstraMap`] instead:
+//! one sweep out from the goal gives every tile its distance, and each walker
+//! just steps downhill. This is how a crowd of monsters chases the player
+//! without a path search per monster.
+
+use crate::{Level, Position, StairDirection, World};
+use ::pathfinding::prelude::{astar, dijkstra_all, dijkstra_reach};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A position on a specific floor of the dungeon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FloorPosition {
+    /// Level the position is on
+    pub level_id: u32,
+    /// Position within the level
+    pub position: Position,
+}
+
+impl FloorPosition {
+    /// Creates a position on a floor.
+    pub fn new(level_id: u32, position: Position) -> Self {
+        Self { level_id, position }
+    }
+}
+
+/// The part of a route walked on one floor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteLeg {
+    /// Level the leg is walked on
+    pub level_id: u32,
+    /// Positions to step onto in order, not including where the leg starts
+    pub steps: Vec<Position>,
+    /// Stairs taken at the end of the leg, or `None` on the destination floor
+    pub stairs: Option<StairDirection>,
+}
 
-/// Placeholder for pathfinding utilities.
+/// Finds paths over level terrain.
+///
+/// Paths use cardinal moves only, like the player, and treat anything
+/// passable as walkable; entities are ignored since they move between turns.
+/// Each step costs the [`Level::path_cost`] of the tile stepped onto, so
+/// paths prefer clean floor to water and rubble, and only cross lava or
+/// chasms when there is no way around.
+/// A path that steps onto a teleporter continues from the teleporter's
+/// partner, so the step after it is adjacent to the partner rather than to
+/// the teleporter itself.
 pub struct PathfindingUtils;
 
+/// Gets the positions one step on from `position` along a path begun at `start`,
+/// with what stepping onto each costs.
+///
+/// Only positions `walkable` allows are stepped onto. The walker stands on the
+/// partner of any teleporter it stepped onto, but never teleports from the
+/// tile it started on.
+fn successors<'a>(
+    level: &'a Level,
+    start: Position,
+    position: Position,
+    walkable: &'a impl Fn(Position) -> bool,
+) -> impl Iterator<Item = (Position, u32)> + 'a {
+    let standing = match level.portal_exit(position) {
+        Some(exit) if position != start => exit,
+        _ => position,
+    };
+    standing
+        .adjacent_positions()
+        .into_iter()
+        .filter(move |&next| walkable(next))
+        .map(|next| (next, level.path_cost(next)))
+}
+
 impl Default for PathfindingUtils {
     fn default() -> Self {
         Self::new()
@@ -16,4 +93,431 @@ impl PathfindingUtils {
     pub fn new() -> Self {
         Self
     }
+
+    /// Finds a shortest path between two positions on a level.
+    ///
+    /// Returns the positions to step onto, not including `start`, or `None`
+    /// if `goal` cannot be reached. A path may end on a teleporter leading to
+    /// `goal`.
+    pub fn find_path(
+        &self,
+        level: &Level,
+        start: Position,
+        goal: Position,
+    ) -> Option<Vec<Position>> {
+        self.search(level, start, goal, |position| level.is_passable(position))
+    }
+
+    /// Finds a shortest path the player can take between two positions.
+    ///
+    /// Unlike [`Self::find_path`], the path may go through closed doors, to be
+    /// opened on the way, and it goes around every position `blocked` reports,
+    /// such as one an entity stands on, apart from `goal` itself.
+    pub fn find_route(
+        &self,
+        level: &Level,
+        start: Position,
+        goal: Position,
+        blocked: impl Fn(Position) -> bool,
+    ) -> Option<Vec<Position>> {
+        self.search(level, start, goal, |position| {
+            level
+                .get_tile(position)
+                .is_some_and(|tile| tile.tile_type.is_routable())
+                && (position == goal || !blocked(position))
+        })
+    }
+
+    /// Runs A* from `start` to `goal` over the positions `walkable` allows.
+    fn search(
+        &self,
+        level: &Level,
+        start: Position,
+        goal: Position,
+        walkable: impl Fn(Position) -> bool,
+    ) -> Option<Vec<Position>> {
+        if !walkable(goal) {
+            return None;
+        }
+
+        // Any route through teleporters walks to one entrance and from one
+        // exit, which keeps the estimate from overshooting
+        let portals = level.portals();
+        let via_portal = portals
+            .iter()
+            .map(|&(_, exit)| exit.manhattan_distance(goal))
+            .min();
+        let estimate = |position: Position| {
+            let direct = position.manhattan_distance(goal);
+            let entrance = portals
+                .iter()
+                .map(|&(entrance, _)| position.manhattan_distance(entrance))
+                .min();
+            match (entrance, via_portal) {
+                (Some(entrance), Some(exit)) => direct.min(entrance + exit),
+                _ => direct,
+            }
+        };
+
+        let (mut path, _) = astar(
+            &start,
+            |&position| successors(level, start, position, &walkable),
+            |&position| estimate(position),
+            |&position| {
+                position == goal
+                    || (position != start && level.portal_exit(position) == Some(goal))
+            },
+        )?;
+        path.remove(0);
+        Some(path)
+    }
+
+    /// Finds the walking distance from `origin` to every reachable position.
+    ///
+    /// Uses Dijkstra's algorithm over the same moves and costs as
+    /// [`Self::find_path`], so a teleporter's distance is that of stepping
+    /// onto it. `origin` itself is left out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Level, PathfindingUtils, Position, Tile};
+    ///
+    /// let mut level = Level::new(0, 5, 3);
+    /// for x in 0..4 {
+    ///     level.set_tile(Position::new(x, 1), Tile::floor()).unwrap();
+    /// }
+    /// let distances = PathfindingUtils::new().distance_map(&level, Position::new(0, 1));
+    /// assert_eq!(distances[&Position::new(3, 1)], 3);
+    /// assert_eq!(distances.len(), 3);
+    /// ```
+    pub fn distance_map(&self, level: &Level, origin: Position) -> HashMap<Position, u32> {
+        let passable = |position| level.is_passable(position);
+        dijkstra_all(&origin, |&position| successors(level, origin, position, &passable))
+        .into_iter()
+        .map(|(position, (_, distance))| (position, distance))
+        .collect()
+    }
+
+    /// Finds every position within `max_steps` moves of `origin`, with the
+    /// number of moves it takes to get there.
+    ///
+    /// Uses the same moves as [`Self::find_path`], but counts every step as
+    /// one move whatever the terrain, since path costs steer routes without
+    /// slowing anyone down. Stops searching once the budget runs out, so it
+    /// stays cheap on large open levels. `origin` itself is included at
+    /// distance 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{Level, PathfindingUtils, Position, Tile};
+    ///
+    /// let mut level = Level::new(0, 6, 3);
+    /// for x in 0..6 {
+    ///     level.set_tile(Position::new(x, 1), Tile::floor()).unwrap();
+    /// }
+    /// let reach = PathfindingUtils::new().reachable_within(&level, Position::new(0, 1), 2);
+    /// assert_eq!(reach.len(), 3);
+    /// assert_eq!(reach[&Position::new(2, 1)], 2);
+    /// ```
+    pub fn reachable_within(
+        &self,
+        level: &Level,
+        origin: Position,
+        max_steps: u32,
+    ) -> HashMap<Position, u32> {
+        let passable = |position| level.is_passable(position);
+        dijkstra_reach(&origin, |&position| {
+            successors(level, origin, position, &passable).map(|(next, _)| (next, 1))
+        })
+            .take_while(|item| item.total_cost <= max_steps)
+            .map(|item| (item.node, item.total_cost))
+            .collect()
+    }
+
+    /// Plans a route across any number of floors.
+    ///
+    /// Each leg but the last ends on the stairs towards the destination floor,
+    /// and the next leg starts on the matching stairs of the floor reached.
+    /// Returns `None` if a floor is missing, lacks the stairs needed, or any
+    /// leg has no path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use thatch::{FloorPosition, GameState, PathfindingUtils};
+    ///
+    /// let game_state = GameState::new_with_complete_dungeon(7).unwrap();
+    /// let level = game_state.world.get_level(0).unwrap();
+    /// let start = FloorPosition::new(0, level.player_spawn);
+    /// let goal = game_state.world.get_level(2).unwrap().player_spawn;
+    ///
+    /// let legs = PathfindingUtils::new()
+    ///     .plan_route(&game_state.world, start, FloorPosition::new(2, goal))
+    ///     .unwrap();
+    /// assert_eq!(legs.len(), 3);
+    /// assert_eq!(legs[2].level_id, 2);
+    /// ```
+    pub fn plan_route(
+        &self,
+        world: &World,
+        start: FloorPosition,
+        goal: FloorPosition,
+    ) -> Option<Vec<RouteLeg>> {
+        let mut legs = Vec::new();
+        let mut current = start;
+
+        while current.level_id != goal.level_id {
+            let level = world.get_level(current.level_id)?;
+            let (stairs, exit, next_level_id) = if goal.level_id > current.level_id {
+                (StairDirection::Down, level.stairs_down_position?, current.level_id + 1)
+            } else {
+                (StairDirection::Up, level.stairs_up_position?, current.level_id - 1)
+            };
+
+            legs.push(RouteLeg {
+                level_id: current.level_id,
+                steps: self.find_path(level, current.position, exit)?,
+                stairs: Some(stairs),
+            });
+            current = FloorPosition::new(next_level_id, exit);
+        }
+
+        let level = world.get_level(goal.level_id)?;
+        legs.push(RouteLeg {
+            level_id: goal.level_id,
+            steps: self.find_path(level, current.position, goal.position)?,
+            stairs: None,
+        });
+        Some(legs)
+    }
+}
+
+/// Walking distances to one goal from everywhere on a level.
+///
+/// Built with a single Dijkstra sweep out from the goal over passable tiles
+/// and cardinal moves, so it costs the same however many walkers use it.
+/// A tile's distance includes its own [`Level::path_cost`], so stepping
+/// downhill never walks into a hazard that a longer way avoids. Teleporters
+/// are treated as plain floor, since the sweep runs backwards from the goal.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{DijkstraMap, Level, Position, Tile};
+///
+/// let mut level = Level::new(0, 5, 3);
+/// for x in 0..5 {
+///     level.set_tile(Position::new(x, 1), Tile::floor()).unwrap();
+/// }
+/// let map = DijkstraMap::new(&level, Position::new(0, 1));
+/// assert_eq!(map.distance(Position::new(4, 1)), Some(4));
+/// assert_eq!(map.downhill(Position::new(4, 1), |_| false), Some(Position::new(3, 1)));
+/// assert_eq!(map.downhill(Position::new(4, 1), |p| p.x == 3), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DijkstraMap {
+    /// Where every walker is heading
+    pub goal: Position,
+    width: u32,
+    height: u32,
+    distances: Vec<Option<u32>>,
+}
+
+impl DijkstraMap {
+    /// Maps the walking distance to `goal` from every position on `level`.
+    pub fn new(level: &Level, goal: Position) -> Self {
+        let mut map = Self {
+            goal,
+            width: level.width,
+            height: level.height,
+            distances: vec![None; (level.width * level.height) as usize],
+        };
+        let Some(index) = map.index(goal) else {
+            return map;
+        };
+        map.distances[index] = Some(0);
+
+        let mut queue = BinaryHeap::from([Reverse((0, goal.x, goal.y))]);
+        while let Some(Reverse((distance, x, y))) = queue.pop() {
+            let position = Position::new(x, y);
+            if map.distance(position).is_some_and(|best| best < distance) {
+                continue;
+            }
+            for next in position.adjacent_positions() {
+                let Some(index) = map.index(next).filter(|_| level.is_passable(next)) else {
+                    continue;
+                };
+                let through = distance + level.path_cost(next);
+                if map.distances[index].is_none_or(|best| through < best) {
+                    map.distances[index] = Some(through);
+                    queue.push(Reverse((through, next.x, next.y)));
+                }
+            }
+        }
+        map
+    }
+
+    /// Gets the walking distance from `position` to the goal, or `None` if
+    /// the goal cannot be reached from there.
+    pub fn distance(&self, position: Position) -> Option<u32> {
+        self.distances[self.index(position)?]
+    }
+
+    /// Gets the step from `position` that brings a walker closest to the
+    /// goal, skipping positions for which `blocked` returns true.
+    ///
+    /// Only steps that actually get closer are taken, so a walker whose way
+    /// is blocked tries another route of the same length or waits, rather
+    /// than wandering off.
+    pub fn downhill(
+        &self,
+        position: Position,
+        blocked: impl Fn(Position) -> bool,
+    ) -> Option<Position> {
+        let here = self.distance(position)?;
+        position
+            .adjacent_positions()
+            .into_iter()
+            .filter_map(|next| Some((next, self.distance(next)?)))
+            .filter(|&(next, distance)| distance < here && !blocked(next))
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(next, _)| next)
+    }
+
+    /// Gets the index of an in-bounds position in the distance grid.
+    fn index(&self, position: Position) -> Option<usize> {
+        let in_bounds = position.x >= 0
+            && position.y >= 0
+            && (position.x as u32) < self.width
+            && (position.y as u32) < self.height;
+        in_bounds.then(|| position.y as usize * self.width as usize + position.x as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tile, TileType};
+
+    /// Builds an open floor with aligned stairs in its corners.
+    fn open_level(id: u32, up: Option<Position>, down: Option<Position>) -> Level {
+        let mut level = Level::new(id, 10, 10);
+        for y in 0..10 {
+            for x in 0..10 {
+                level.set_tile(Position::new(x, y), Tile::floor()).unwrap();
+            }
+        }
+        if let Some(up) = up {
+            level.set_tile(up, Tile::new(TileType::StairsUp)).unwrap();
+            level.stairs_up_position = Some(up);
+        }
+        if let Some(down) = down {
+            level.set_tile(down, Tile::new(TileType::StairsDown)).unwrap();
+            level.stairs_down_position = Some(down);
+        }
+        level
+    }
+
+    #[test]
+    fn test_route_chains_floors_through_aligned_stairs() {
+        let first_stairs = Position::new(8, 1);
+        let second_stairs = Position::new(1, 8);
+        let mut world = World::new(1);
+        world.add_level(open_level(0, None, Some(first_stairs)));
+        world.add_level(open_level(1, Some(first_stairs), Some(second_stairs)));
+        world.add_level(open_level(2, Some(second_stairs), None));
+        let pathfinding = PathfindingUtils::new();
+
+        let start = FloorPosition::new(0, Position::new(1, 1));
+        let goal = FloorPosition::new(2, Position::new(8, 8));
+        let legs = pathfinding.plan_route(&world, start, goal).unwrap();
+
+        let floors: Vec<u32> = legs.iter().map(|leg| leg.level_id).collect();
+        assert_eq!(floors, [0, 1, 2]);
+        assert_eq!(legs[0].steps.last(), Some(&first_stairs));
+        assert_eq!(legs[0].steps.len(), 7);
+        assert_eq!(legs[1].steps.last(), Some(&second_stairs));
+        assert_eq!(legs[1].stairs, Some(StairDirection::Down));
+        assert_eq!(legs[2].steps.last(), Some(&goal.position));
+        assert_eq!(legs[2].stairs, None);
+
+        // Climbing back retraces the same stairs
+        let back = pathfinding.plan_route(&world, goal, start).unwrap();
+        assert_eq!(back[0].steps.last(), Some(&second_stairs));
+        assert_eq!(back[0].stairs, Some(StairDirection::Up));
+
+        // Walling off the destination floor's stairs leaves no route
+        let level = world.get_level_mut(2).unwrap();
+        for (x, y) in [(1, 7), (0, 8), (2, 8), (1, 9)] {
+            level.set_tile(Position::new(x, y), Tile::wall()).unwrap();
+        }
+        assert!(pathfinding.plan_route(&world, start, goal).is_none());
+    }
+
+    #[test]
+    fn test_paths_cut_through_teleporters() {
+        let mut level = Level::new(0, 30, 3);
+        for x in 0..30 {
+            level.set_tile(Position::new(x, 1), Tile::floor()).unwrap();
+        }
+        let (near, far) = (Position::new(1, 1), Position::new(28, 1));
+        let near_tile = crate::TileEffect::Teleporter { destination: far }.into_tile();
+        level.set_tile(near, near_tile).unwrap();
+        let far_tile = crate::TileEffect::Teleporter { destination: near }.into_tile();
+        level.set_tile(far, far_tile).unwrap();
+        let pathfinding = PathfindingUtils::new();
+
+        // Stepping onto the near teleporter lands beside the goal
+        let start = Position::new(0, 1);
+        let goal = Position::new(29, 1);
+        assert_eq!(pathfinding.find_path(&level, start, goal).unwrap(), [near, goal]);
+        assert_eq!(pathfinding.distance_map(&level, start)[&goal], 2);
+
+        // Standing on a teleporter does not send the walker anywhere
+        let path = pathfinding.find_path(&level, far, goal).unwrap();
+        assert_eq!(path, [goal]);
+
+        // The autoexplore pathfinder takes the same shortcut
+        let game_state = crate::GameState::new_with_level(level, 1).unwrap();
+        let path = crate::AutoexploreState::new()
+            .find_path(&game_state, start, goal)
+            .unwrap();
+        assert_eq!(path.unwrap(), [near, goal]);
+
+        // The teleporters are found again after a save is loaded
+        let json = game_state.save_to_json().unwrap();
+        let loaded = crate::GameState::load_from_json(&json).unwrap();
+        let portals = loaded.world.current_level().unwrap().portals();
+        assert_eq!(portals, [(near, far), (far, near)]);
+    }
+
+    #[test]
+    fn test_paths_prefer_clean_floor_to_slow_terrain() {
+        let mut level = open_level(0, None, None);
+        let (shallows, deep) = (Position::new(4, 1), Position::new(5, 1));
+        for water in [shallows, deep] {
+            level.set_tile(water, Tile::new(TileType::Water)).unwrap();
+        }
+        level.add_overlay(Position::new(4, 5), crate::TileOverlay::Rubble);
+        assert_eq!(level.path_cost(Position::new(4, 5)), crate::RUBBLE_PATH_COST);
+        let pathfinding = PathfindingUtils::new();
+
+        // Walking around the water takes two more steps but costs less
+        let (start, goal) = (Position::new(3, 1), Position::new(6, 1));
+        let path = pathfinding.find_path(&level, start, goal).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(!path.contains(&shallows) && !path.contains(&deep));
+        let map = DijkstraMap::new(&level, goal);
+        assert_ne!(map.downhill(start, |_| false), Some(shallows));
+        let game_state = crate::GameState::new_with_level(level.clone(), 1).unwrap();
+        let path = crate::AutoexploreState::new()
+            .find_path(&game_state, start, goal)
+            .unwrap();
+        assert_eq!(path.unwrap().len(), 5);
+
+        // Costs steer routes but every step is still one move
+        assert_eq!(pathfinding.reachable_within(&level, start, 1)[&shallows], 1);
+    }
 }
@@ -0,0 +1,194 @@
+//! # Profiler
+//!
+//! Lightweight timed scopes for finding expensive code paths.
+//!
+//! Scopes are recorded with the [`profile_scope!`](crate::profile_scope) macro,
+//! which compiles to nothing unless the `dev-tools` feature is enabled. Timings
+//! are collected in a thread-local [`Profiler`] and shown in the F3 overlay.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Records the time spent in the rest of the enclosing block.
+///
+/// Only active with the `dev-tools` feature; otherwise it expands to nothing.
+///
+/// # Examples
+///
+/// ```
+/// fn expensive() {
+///     thatch::profile_scope!("expensive");
+///     // ... work ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "dev-tools")]
+        let _profile_guard = $crate::ScopeTimer::new($name);
+    };
+}
+
+/// Accumulated timings for a single named scope.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScopeStats {
+    /// Number of times the scope was entered
+    pub calls: u64,
+    /// Total time spent in the scope
+    pub total: Duration,
+    /// Longest single call
+    pub max: Duration,
+    /// Time spent in the scope during the last completed frame
+    pub last_frame: Duration,
+    /// Time spent in the scope during the current frame so far
+    current_frame: Duration,
+}
+
+/// Collects scope timings and frame times.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    scopes: HashMap<&'static str, ScopeStats>,
+    frame_start: Option<Instant>,
+    last_frame_time: Duration,
+}
+
+impl Profiler {
+    /// Creates an empty profiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one call of `name` that took `elapsed`.
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+        let stats = self.scopes.entry(name).or_default();
+        stats.calls += 1;
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+        stats.current_frame += elapsed;
+    }
+
+    /// Marks the start of a frame.
+    pub fn begin_frame(&mut self) {
+        self.frame_start = Some(Instant::now());
+    }
+
+    /// Marks the end of a frame, rolling per-frame scope times over.
+    pub fn end_frame(&mut self) {
+        if let Some(start) = self.frame_start.take() {
+            self.last_frame_time = start.elapsed();
+        }
+        for stats in self.scopes.values_mut() {
+            stats.last_frame = std::mem::take(&mut stats.current_frame);
+        }
+    }
+
+    /// Duration of the last completed frame.
+    pub fn frame_time(&self) -> Duration {
+        self.last_frame_time
+    }
+
+    /// Gets the stats for a scope, if it has been recorded.
+    pub fn scope(&self, name: &str) -> Option<&ScopeStats> {
+        self.scopes.get(name)
+    }
+
+    /// Returns up to `count` scopes ordered by total time spent, most expensive first.
+    pub fn top_costs(&self, count: usize) -> Vec<(&'static str, ScopeStats)> {
+        let mut scopes: Vec<_> = self
+            .scopes
+            .iter()
+            .map(|(name, stats)| (*name, stats.clone()))
+            .collect();
+        scopes.sort_by(|a, b| b.1.total.cmp(&a.1.total).then(a.0.cmp(b.0)));
+        scopes.truncate(count);
+        scopes
+    }
+
+    /// Clears all recorded timings.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+thread_local! {
+    static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::new());
+}
+
+/// Runs `f` with the thread-local profiler.
+pub fn with_profiler<R>(f: impl FnOnce(&mut Profiler) -> R) -> R {
+    PROFILER.with(|profiler| f(&mut profiler.borrow_mut()))
+}
+
+/// Guard that records its lifetime into the thread-local profiler when dropped.
+pub struct ScopeTimer {
+    name: &'static str,
+    start: Instant,
+}
+
+impl ScopeTimer {
+    /// Starts timing a scope.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ScopeTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        with_profiler(|profiler| profiler.record(self.name, elapsed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_top_costs() {
+        let mut profiler = Profiler::new();
+        profiler.record("cheap", Duration::from_millis(1));
+        profiler.record("expensive", Duration::from_millis(10));
+        profiler.record("expensive", Duration::from_millis(5));
+
+        let top = profiler.top_costs(1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].0, "expensive");
+        assert_eq!(top[0].1.calls, 2);
+        assert_eq!(top[0].1.total, Duration::from_millis(15));
+        assert_eq!(top[0].1.max, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_frame_rollover() {
+        let mut profiler = Profiler::new();
+        profiler.begin_frame();
+        profiler.record("render", Duration::from_millis(3));
+        profiler.end_frame();
+
+        assert_eq!(
+            profiler.scope("render").unwrap().last_frame,
+            Duration::from_millis(3)
+        );
+
+        // A frame without calls reports zero for the scope
+        profiler.begin_frame();
+        profiler.end_frame();
+        assert_eq!(profiler.scope("render").unwrap().last_frame, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_scope_timer_records_on_drop() {
+        with_profiler(Profiler::reset);
+        {
+            let _timer = ScopeTimer::new("scoped");
+        }
+        assert_eq!(
+            with_profiler(|p| p.scope("scoped").map(|s| s.calls)),
+            Some(1)
+        );
+    }
+}
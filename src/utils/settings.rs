@@ -0,0 +1,420 @@
+//! # Settings
+//!
+//! Player preferences that persist between runs.
+//!
+//! [`ThatchConfig`] is stored as JSON in [`config::SETTINGS_FILE`] and edited
+//! from the options scene. Missing fields fall back to their defaults, so
+//! settings files written by older versions keep loading.
+//!
+//! [`config::SETTINGS_FILE`]: crate::config::SETTINGS_FILE
+
+use crate::{
+    config, DoorOpening, GestureConfig, NarrationMode, SaveMode, ThatchError, ThatchResult,
+    ThemePreset, DEFAULT_LONG_PRESS_MS, DEFAULT_SWIPE_DISTANCE, MAX_ZOOM, MIN_ZOOM, ZOOM_STEP,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Delays between autoexplore steps offered in the options, slowest first.
+pub const AUTOEXPLORE_DELAYS_MS: [u64; 5] = [200, 100, 50, 25, 10];
+
+/// Fewest message log lines shown under the map.
+pub const MIN_MESSAGE_COUNT: usize = 1;
+
+/// Most message log lines shown under the map.
+pub const MAX_MESSAGE_COUNT: usize = 8;
+
+/// Swipe distances in pixels offered in the options, most sensitive first.
+pub const SWIPE_DISTANCES: [f32; 4] = [20.0, 40.0, 60.0, 90.0];
+
+/// Long-press delays in milliseconds offered in the options, quickest first.
+pub const LONG_PRESS_DELAYS_MS: [u64; 4] = [300, 500, 750, 1000];
+
+/// Turns between autosaves offered in the options; zero autosaves only on level changes.
+pub const AUTOSAVE_INTERVALS: [u64; 5] = [0, 50, 100, 250, 500];
+
+/// Text scales offered in the options, smallest first.
+pub const FONT_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// Player preferences saved to the settings file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThatchConfig {
+    /// Milliseconds between autoexplore steps
+    pub autoexplore_delay_ms: u64,
+    /// Whether hjkl/yubn move the player
+    pub vi_keys: bool,
+    /// Map zoom multiplier
+    pub zoom: f32,
+    /// Message log lines shown under the map
+    pub message_count: usize,
+    /// Whether the LLM dungeon master is enabled
+    pub lldm_enabled: bool,
+    /// `http://` URL prompts are posted to when the LLDM is enabled
+    pub lldm_endpoint: Option<String>,
+    /// Distance in pixels a finger has to slide for a swipe
+    pub swipe_distance: f32,
+    /// Milliseconds a finger has to be held still for a long press
+    pub long_press_ms: u64,
+    /// Whether visible hostile monsters get a name label and health bar
+    pub monster_labels: bool,
+    /// Where turn-by-turn descriptions of the surroundings are sent
+    pub narration: NarrationMode,
+    /// Whether the map is drawn in a high-contrast palette
+    pub high_contrast: bool,
+    /// Multiplier for panel, message and label text sizes
+    pub font_scale: f32,
+    /// Color theme for the map and messages
+    pub theme: ThemePreset,
+    /// Turns between autosaves, or zero to autosave only on level changes
+    pub autosave_turns: u64,
+    /// Whether loading a save uses it up, making death final
+    pub save_mode: SaveMode,
+    /// What walking into a closed door does
+    pub door_opening: DoorOpening,
+}
+
+impl Default for ThatchConfig {
+    fn default() -> Self {
+        Self {
+            autoexplore_delay_ms: 50,
+            vi_keys: true,
+            zoom: 1.0,
+            message_count: 3,
+            lldm_enabled: false,
+            lldm_endpoint: None,
+            swipe_distance: DEFAULT_SWIPE_DISTANCE,
+            long_press_ms: DEFAULT_LONG_PRESS_MS,
+            monster_labels: true,
+            narration: NarrationMode::Off,
+            high_contrast: false,
+            font_scale: 1.0,
+            theme: ThemePreset::Default,
+            autosave_turns: config::DEFAULT_AUTOSAVE_TURNS,
+            save_mode: SaveMode::Permadeath,
+            door_opening: DoorOpening::default(),
+        }
+    }
+}
+
+impl ThatchConfig {
+    /// Loads settings from a JSON file.
+    pub fn load(path: &Path) -> ThatchResult<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(ThatchError::from)
+    }
+
+    /// Loads settings from a JSON file, using the defaults if it is missing or unreadable.
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    /// Writes settings to a JSON file.
+    pub fn save(&self, path: &Path) -> ThatchResult<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Gets the touch gesture thresholds.
+    pub fn gesture_config(&self) -> GestureConfig {
+        GestureConfig {
+            swipe_distance: self.swipe_distance,
+            long_press: Duration::from_millis(self.long_press_ms),
+        }
+    }
+
+    /// Steps a setting to its next (or previous) value, wrapping at either end.
+    pub fn adjust(&mut self, entry: SettingsEntry, forward: bool) {
+        match entry {
+            SettingsEntry::AutoexploreSpeed => {
+                let count = AUTOEXPLORE_DELAYS_MS.len();
+                let current = AUTOEXPLORE_DELAYS_MS
+                    .iter()
+                    .position(|&delay| delay <= self.autoexplore_delay_ms)
+                    .unwrap_or(count - 1);
+                let next = if forward {
+                    (current + 1) % count
+                } else {
+                    (current + count - 1) % count
+                };
+                self.autoexplore_delay_ms = AUTOEXPLORE_DELAYS_MS[next];
+            }
+            SettingsEntry::ViKeys => self.vi_keys = !self.vi_keys,
+            SettingsEntry::Zoom => {
+                let step = if forward { ZOOM_STEP } else { -ZOOM_STEP };
+                let zoom = self.zoom + step;
+                self.zoom = if zoom > MAX_ZOOM + f32::EPSILON {
+                    MIN_ZOOM
+                } else if zoom < MIN_ZOOM - f32::EPSILON {
+                    MAX_ZOOM
+                } else {
+                    zoom
+                };
+            }
+            SettingsEntry::MessageCount => {
+                self.message_count = if forward {
+                    if self.message_count >= MAX_MESSAGE_COUNT {
+                        MIN_MESSAGE_COUNT
+                    } else {
+                        self.message_count + 1
+                    }
+                } else if self.message_count <= MIN_MESSAGE_COUNT {
+                    MAX_MESSAGE_COUNT
+                } else {
+                    self.message_count - 1
+                };
+            }
+            SettingsEntry::Lldm => self.lldm_enabled = !self.lldm_enabled,
+            SettingsEntry::SwipeDistance => {
+                let current = SWIPE_DISTANCES
+                    .iter()
+                    .position(|&distance| distance >= self.swipe_distance)
+                    .unwrap_or(SWIPE_DISTANCES.len() - 1);
+                let next = Self::step(current, SWIPE_DISTANCES.len(), forward);
+                self.swipe_distance = SWIPE_DISTANCES[next];
+            }
+            SettingsEntry::LongPressDelay => {
+                let current = LONG_PRESS_DELAYS_MS
+                    .iter()
+                    .position(|&delay| delay >= self.long_press_ms)
+                    .unwrap_or(LONG_PRESS_DELAYS_MS.len() - 1);
+                let next = Self::step(current, LONG_PRESS_DELAYS_MS.len(), forward);
+                self.long_press_ms = LONG_PRESS_DELAYS_MS[next];
+            }
+            SettingsEntry::MonsterLabels => self.monster_labels = !self.monster_labels,
+            SettingsEntry::Narration => {
+                let modes = NarrationMode::ALL;
+                let current = modes
+                    .iter()
+                    .position(|&mode| mode == self.narration)
+                    .unwrap_or(0);
+                self.narration = modes[Self::step(current, modes.len(), forward)];
+            }
+            SettingsEntry::HighContrast => self.high_contrast = !self.high_contrast,
+            SettingsEntry::Theme => {
+                let presets = ThemePreset::ALL;
+                let current = presets
+                    .iter()
+                    .position(|&theme| theme == self.theme)
+                    .unwrap_or(0);
+                self.theme = presets[Self::step(current, presets.len(), forward)];
+            }
+            SettingsEntry::Autosave => {
+                let current = AUTOSAVE_INTERVALS
+                    .iter()
+                    .position(|&turns| turns >= self.autosave_turns)
+                    .unwrap_or(AUTOSAVE_INTERVALS.len() - 1);
+                let next = Self::step(current, AUTOSAVE_INTERVALS.len(), forward);
+                self.autosave_turns = AUTOSAVE_INTERVALS[next];
+            }
+            SettingsEntry::SaveMode => {
+                let modes = SaveMode::ALL;
+                let current = modes
+                    .iter()
+                    .position(|&mode| mode == self.save_mode)
+                    .unwrap_or(0);
+                self.save_mode = modes[Self::step(current, modes.len(), forward)];
+            }
+            SettingsEntry::FontScale => {
+                let current = FONT_SCALES
+                    .iter()
+                    .position(|&scale| scale >= self.font_scale)
+                    .unwrap_or(FONT_SCALES.len() - 1);
+                self.font_scale = FONT_SCALES[Self::step(current, FONT_SCALES.len(), forward)];
+            }
+            SettingsEntry::DoorOpening => {
+                let modes = DoorOpening::ALL;
+                let current = modes
+                    .iter()
+                    .position(|&mode| mode == self.door_opening)
+                    .unwrap_or(0);
+                self.door_opening = modes[Self::step(current, modes.len(), forward)];
+            }
+        }
+    }
+
+    /// Returns the current value of a setting as shown in the options scene.
+    pub fn value_text(&self, entry: SettingsEntry) -> String {
+        let on_off = |enabled: bool| if enabled { "On" } else { "Off" }.to_string();
+        match entry {
+            SettingsEntry::AutoexploreSpeed => format!("{} ms per step", self.autoexplore_delay_ms),
+            SettingsEntry::ViKeys => on_off(self.vi_keys),
+            SettingsEntry::Zoom => format!("{:.2}x", self.zoom),
+            SettingsEntry::MessageCount => format!("{} lines", self.message_count),
+            SettingsEntry::Lldm => on_off(self.lldm_enabled),
+            SettingsEntry::SwipeDistance => format!("{} px", self.swipe_distance),
+            SettingsEntry::LongPressDelay => format!("{} ms", self.long_press_ms),
+            SettingsEntry::MonsterLabels => on_off(self.monster_labels),
+            SettingsEntry::Narration => self.narration.display_name().to_string(),
+            SettingsEntry::HighContrast => on_off(self.high_contrast),
+            SettingsEntry::Theme => self.theme.display_name().to_string(),
+            SettingsEntry::FontScale => format!("{:.2}x", self.font_scale),
+            SettingsEntry::Autosave if self.autosave_turns == 0 => "Level changes only".to_string(),
+            SettingsEntry::Autosave => format!("Every {} turns", self.autosave_turns),
+            SettingsEntry::SaveMode => self.save_mode.display_name().to_string(),
+            SettingsEntry::DoorOpening => self.door_opening.display_name().to_string(),
+        }
+    }
+
+    /// Steps an index into a list of `count` choices, wrapping at either end.
+    fn step(index: usize, count: usize, forward: bool) -> usize {
+        if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        }
+    }
+}
+
+/// Settings listed in the options scene, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsEntry {
+    /// Delay between autoexplore steps
+    AutoexploreSpeed,
+    /// Whether hjkl/yubn move the player
+    ViKeys,
+    /// Map zoom multiplier
+    Zoom,
+    /// Message log lines shown under the map
+    MessageCount,
+    /// Whether the LLM dungeon master is enabled
+    Lldm,
+    /// Distance a finger has to slide for a swipe
+    SwipeDistance,
+    /// Time a finger has to be held still for a long press
+    LongPressDelay,
+    /// Whether visible hostile monsters are labelled
+    MonsterLabels,
+    /// Where descriptions of the surroundings are sent
+    Narration,
+    /// Whether the map uses the high-contrast palette
+    HighContrast,
+    /// Multiplier for text sizes
+    FontScale,
+    /// Color theme for the map and messages
+    Theme,
+    /// Turns between autosaves
+    Autosave,
+    /// Whether loading a save uses it up
+    SaveMode,
+    /// What walking into a closed door does
+    DoorOpening,
+}
+
+impl SettingsEntry {
+    /// Every entry, in display order
+    pub const ALL: [SettingsEntry; 15] = [
+        SettingsEntry::AutoexploreSpeed,
+        SettingsEntry::ViKeys,
+        SettingsEntry::Zoom,
+        SettingsEntry::MessageCount,
+        SettingsEntry::Lldm,
+        SettingsEntry::SwipeDistance,
+        SettingsEntry::LongPressDelay,
+        SettingsEntry::MonsterLabels,
+        SettingsEntry::Narration,
+        SettingsEntry::HighContrast,
+        SettingsEntry::FontScale,
+        SettingsEntry::Theme,
+        SettingsEntry::Autosave,
+        SettingsEntry::SaveMode,
+        SettingsEntry::DoorOpening,
+    ];
+
+    /// Returns the label shown in the options scene
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingsEntry::AutoexploreSpeed => "Autoexplore speed",
+            SettingsEntry::ViKeys => "Vi-keys",
+            SettingsEntry::Zoom => "Zoom",
+            SettingsEntry::MessageCount => "Messages shown",
+            SettingsEntry::Lldm => "LLM dungeon master",
+            SettingsEntry::SwipeDistance => "Swipe distance",
+            SettingsEntry::LongPressDelay => "Long-press delay",
+            SettingsEntry::MonsterLabels => "Monster labels",
+            SettingsEntry::Narration => "Describe surroundings",
+            SettingsEntry::HighContrast => "High contrast",
+            SettingsEntry::FontScale => "Text size",
+            SettingsEntry::Theme => "Color theme",
+            SettingsEntry::Autosave => "Autosave",
+            SettingsEntry::SaveMode => "Saves",
+            SettingsEntry::DoorOpening => "Walk into doors",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_wraps_at_the_ends() {
+        let mut settings = ThatchConfig::default();
+
+        settings.adjust(SettingsEntry::AutoexploreSpeed, true);
+        assert_eq!(settings.autoexplore_delay_ms, 25);
+        settings.adjust(SettingsEntry::AutoexploreSpeed, true);
+        settings.adjust(SettingsEntry::AutoexploreSpeed, true);
+        assert_eq!(settings.autoexplore_delay_ms, AUTOEXPLORE_DELAYS_MS[0]);
+
+        settings.message_count = MAX_MESSAGE_COUNT;
+        settings.adjust(SettingsEntry::MessageCount, true);
+        assert_eq!(settings.message_count, MIN_MESSAGE_COUNT);
+        settings.adjust(SettingsEntry::MessageCount, false);
+        assert_eq!(settings.message_count, MAX_MESSAGE_COUNT);
+
+        settings.zoom = MAX_ZOOM;
+        settings.adjust(SettingsEntry::Zoom, true);
+        assert_eq!(settings.zoom, MIN_ZOOM);
+
+        settings.adjust(SettingsEntry::ViKeys, true);
+        assert!(!settings.vi_keys);
+
+        settings.adjust(SettingsEntry::SwipeDistance, false);
+        assert_eq!(settings.swipe_distance, SWIPE_DISTANCES[0]);
+        settings.adjust(SettingsEntry::SwipeDistance, false);
+        assert_eq!(settings.swipe_distance, SWIPE_DISTANCES[3]);
+        settings.adjust(SettingsEntry::LongPressDelay, true);
+        assert_eq!(
+            settings.gesture_config().long_press,
+            Duration::from_millis(750)
+        );
+
+        settings.adjust(SettingsEntry::Narration, false);
+        assert_eq!(settings.narration, NarrationMode::MessageLogAndStdout);
+        settings.adjust(SettingsEntry::FontScale, false);
+        assert_eq!(settings.font_scale, FONT_SCALES[0]);
+        settings.adjust(SettingsEntry::Theme, false);
+        assert_eq!(settings.theme, ThemePreset::Protanopia);
+        settings.adjust(SettingsEntry::Autosave, false);
+        settings.adjust(SettingsEntry::Autosave, false);
+        assert_eq!(
+            settings.value_text(SettingsEntry::Autosave),
+            "Level changes only"
+        );
+        settings.adjust(SettingsEntry::SaveMode, true);
+        assert_eq!(settings.save_mode, SaveMode::Casual);
+        settings.adjust(SettingsEntry::DoorOpening, true);
+        assert_eq!(settings.door_opening, DoorOpening::OneTurn);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("settings.json");
+
+        let settings = ThatchConfig {
+            lldm_enabled: true,
+            message_count: 5,
+            ..ThatchConfig::default()
+        };
+        settings.save(&path).unwrap();
+
+        assert_eq!(ThatchConfig::load(&path).unwrap(), settings);
+        assert_eq!(
+            ThatchConfig::load_or_default(&temp_dir.path().join("missing.json")),
+            ThatchConfig::default()
+        );
+    }
+}
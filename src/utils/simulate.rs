@@ -0,0 +1,386 @@
+//! # Balance Simulation
+//!
+//! Headless games played by a simple bot, for judging game balance.
+//!
+//! `thatch --simulate N` plays N games on consecutive seeds and gathers how
+//! each one went into a [`SimulationReport`]: how deep the bot got, what
+//! killed it, what it picked up, and how long each floor took. The bot plays
+//! through a [`HeadlessSession`], so its turns resolve exactly as the player's
+//! do. It is not clever, which is the point: it shows what a careless player
+//! runs into.
+//!
+//! Each turn the bot
+//!
+//! - attacks an adjacent hostile
+//! - drinks a healing potion once below [`SIM_HEAL_BELOW_PERCENT`] health
+//! - picks up whatever it stands on, and detours to visible items within
+//!   [`SIM_ITEM_DETOUR`] tiles
+//! - otherwise heads for the stairs down, the Amulet on the deepest floor, and
+//!   the stairs up once it carries the Amulet
+
+use crate::{
+    AttackAction, ConcreteAction, ConsumableType, Direction, EntityId, GameCompletionState,
+    GameEvent, GameState, GenerationConfig, HeadlessSession, ItemType, MoveAction,
+    PathfindingUtils, PickUpAction, StairDirection, StatusKind, ThatchError, ThatchResult,
+    TileEffect, TileType, UseItemAction, UseStairsAction, WaitAction, ARTIFACT_NAME,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Turns a simulated run may last before it is called off.
+pub const SIM_MAX_TURNS: u64 = 5000;
+
+/// Health, as a percentage of the maximum, below which the bot drinks a potion.
+pub const SIM_HEAL_BELOW_PERCENT: u32 = 40;
+
+/// Farthest, in tiles walked, the bot leaves its route to pick up an item.
+pub const SIM_ITEM_DETOUR: u32 = 8;
+
+/// Death cause recorded when nothing the bot can see did the damage.
+const UNKNOWN_CAUSE: &str = "unknown";
+
+/// How one simulated game went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedRun {
+    /// Seed the dungeon was generated from
+    pub seed: u64,
+    /// How the run ended; still playing means it ran out of turns
+    pub outcome: GameCompletionState,
+    /// Deepest dungeon level reached, counting from 1
+    pub depth: u32,
+    /// What dealt the killing blow, if the bot died
+    pub death_cause: Option<String>,
+    /// Items picked up
+    pub items_found: u32,
+    /// Turns played
+    pub turns: u64,
+    /// Turns played for each floor reached
+    pub turns_per_floor: f64,
+}
+
+impl SimulatedRun {
+    /// Gets a short name for the run's outcome.
+    pub fn outcome_name(&self) -> &'static str {
+        match self.outcome {
+            GameCompletionState::Playing => "timeout",
+            GameCompletionState::EscapedEarly => "escaped",
+            GameCompletionState::EscapedWithArtifact => "victory",
+            GameCompletionState::PlayerDied => "died",
+        }
+    }
+}
+
+/// Every simulated run and the averages across them.
+///
+/// # Examples
+///
+/// ```
+/// use thatch::{GameCompletionState, SimulatedRun, SimulationReport};
+///
+/// let run = |depth, death_cause: Option<&str>| SimulatedRun {
+///     seed: 1,
+///     outcome: GameCompletionState::PlayerDied,
+///     depth,
+///     death_cause: death_cause.map(str::to_string),
+///     items_found: 2,
+///     turns: 300,
+///     turns_per_floor: 100.0,
+/// };
+/// let report = SimulationReport::new(vec![run(3, Some("Goblin")), run(5, Some("Goblin"))]);
+///
+/// assert_eq!(report.average_depth, 4.0);
+/// assert_eq!(report.death_causes["Goblin"], 2);
+/// assert_eq!(report.to_csv().lines().count(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    /// Number of runs played
+    pub run_count: usize,
+    /// Mean deepest dungeon level reached
+    pub average_depth: f64,
+    /// Mean items picked up per run
+    pub average_items_found: f64,
+    /// Mean turns spent on each floor
+    pub average_turns_per_floor: f64,
+    /// Number of runs ending each way, by outcome name
+    pub outcomes: BTreeMap<String, u32>,
+    /// Number of deaths by cause
+    pub death_causes: BTreeMap<String, u32>,
+    /// Every run, in the order played
+    pub runs: Vec<SimulatedRun>,
+}
+
+impl SimulationReport {
+    /// Aggregates the statistics of finished runs.
+    pub fn new(runs: Vec<SimulatedRun>) -> Self {
+        let mean = |value: fn(&SimulatedRun) -> f64| {
+            runs.iter().map(value).sum::<f64>() / runs.len().max(1) as f64
+        };
+        let mut outcomes = BTreeMap::new();
+        let mut death_causes = BTreeMap::new();
+        for run in &runs {
+            *outcomes.entry(run.outcome_name().to_string()).or_insert(0) += 1;
+            if let Some(cause) = &run.death_cause {
+                *death_causes.entry(cause.clone()).or_insert(0) += 1;
+            }
+        }
+
+        Self {
+            run_count: runs.len(),
+            average_depth: mean(|run| run.depth as f64),
+            average_items_found: mean(|run| run.items_found as f64),
+            average_turns_per_floor: mean(|run| run.turns_per_floor),
+            outcomes,
+            death_causes,
+            runs,
+        }
+    }
+
+    /// Formats the runs as CSV, one row per run under a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "seed,outcome,depth,death_cause,items_found,turns,turns_per_floor\n".to_string();
+        for run in &self.runs {
+            // Causes are entity names, which never hold commas or quotes
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{:.1}",
+                run.seed,
+                run.outcome_name(),
+                run.depth,
+                run.death_cause.as_deref().unwrap_or(""),
+                run.items_found,
+                run.turns,
+                run.turns_per_floor
+            );
+        }
+        csv
+    }
+
+    /// Formats the whole report as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the report cannot be serialized.
+    pub fn to_json(&self) -> ThatchResult<String> {
+        serde_json::to_string_pretty(self).map_err(ThatchError::from)
+    }
+}
+
+/// Plays `runs` games on the seeds following `config.seed` and reports on them.
+///
+/// # Errors
+///
+/// Returns an error if a dungeon cannot be generated or a turn fails.
+pub fn simulate(config: &GenerationConfig, runs: u32) -> ThatchResult<SimulationReport> {
+    let runs = (0..runs)
+        .map(|run| {
+            simulate_run(GenerationConfig {
+                seed: config.seed.wrapping_add(run as u64),
+                ..config.clone()
+            })
+        })
+        .collect::<ThatchResult<Vec<_>>>()?;
+    Ok(SimulationReport::new(runs))
+}
+
+/// Generates a dungeon from `config` and lets the bot play it to the end or
+/// for [`SIM_MAX_TURNS`] turns.
+///
+/// # Errors
+///
+/// Returns an error if the dungeon cannot be generated or a turn fails.
+pub fn simulate_run(config: GenerationConfig) -> ThatchResult<SimulatedRun> {
+    let seed = config.seed;
+    let mut session = HeadlessSession::start(config, "Bot")?;
+    let player_id = session
+        .game_state
+        .player_id
+        .ok_or_else(|| ThatchError::InvalidState("No player".to_string()))?;
+    let mut last_hurt_by = None;
+    while !session.game_state.is_game_ended() && session.game_state.turn_number < SIM_MAX_TURNS {
+        let action = bot_action(&session.game_state, player_id);
+        let events = match session.submit(&action) {
+            Ok(events) => events,
+            // A refused action costs the bot its turn rather than the run
+            Err(_) => session.submit(&ConcreteAction::Wait(WaitAction::new(player_id)))?,
+        };
+        for event in &events {
+            if let GameEvent::EntityDamaged {
+                entity_id, source, ..
+            } = event
+            {
+                if *entity_id == player_id {
+                    last_hurt_by = Some(damage_cause(&session.game_state, player_id, *source));
+                }
+            }
+        }
+    }
+
+    let game_state = &session.game_state;
+    let died = game_state.completion_state == GameCompletionState::PlayerDied;
+    let depth = game_state.statistics.max_depth_reached + 1;
+    Ok(SimulatedRun {
+        seed,
+        outcome: game_state.completion_state.clone(),
+        depth,
+        death_cause: died.then(|| last_hurt_by.unwrap_or_else(|| UNKNOWN_CAUSE.to_string())),
+        items_found: game_state.statistics.items_collected,
+        turns: game_state.turn_number,
+        turns_per_floor: game_state.turn_number as f64 / depth as f64,
+    })
+}
+
+/// Names what just hurt the player.
+fn damage_cause(game_state: &GameState, player_id: EntityId, source: Option<EntityId>) -> String {
+    if let Some(entity) = source.and_then(|id| game_state.entities.get(&id)) {
+        return entity.as_entity().name().to_string();
+    }
+    let Some(player) = game_state.get_player() else {
+        return UNKNOWN_CAUSE.to_string();
+    };
+    if player.turns_in_water > crate::config::BREATH_TURNS {
+        return "drowning".to_string();
+    }
+    if player.statuses.has(StatusKind::Burning) {
+        return "burning".to_string();
+    }
+    let on_trap = game_state
+        .get_entity_position(player_id)
+        .and_then(|pos| game_state.world.current_level()?.get_tile(pos))
+        .is_some_and(|tile| matches!(tile.tile_type.effect(), Some(TileEffect::Trap { .. })));
+    if on_trap {
+        return "trap".to_string();
+    }
+    UNKNOWN_CAUSE.to_string()
+}
+
+/// Chooses the bot's next action.
+fn bot_action(game_state: &GameState, player_id: EntityId) -> ConcreteAction {
+    let wait = ConcreteAction::Wait(WaitAction::new(player_id));
+    let (Some(player), Some(level)) = (game_state.get_player(), game_state.world.current_level())
+    else {
+        return wait;
+    };
+    let position = player.position;
+
+    let adjacent = position.adjacent_positions();
+    let target = level.entities.iter().copied().find(|&id| {
+        game_state
+            .entities
+            .get(&id)
+            .is_some_and(|entity| entity.as_entity().is_hostile())
+            && game_state.is_entity_alive(id)
+            && game_state
+                .get_entity_position(id)
+                .is_some_and(|pos| adjacent.contains(&pos))
+    });
+    if let Some(target) = target {
+        return ConcreteAction::Attack(AttackAction::new(player_id, target));
+    }
+
+    let stats = &player.stats;
+    if stats.health * 100 < stats.max_health * SIM_HEAL_BELOW_PERCENT {
+        let potion = player.inventory.iter().copied().find(|id| {
+            game_state.carried_items.get(id).is_some_and(|item| {
+                item.item_type == ItemType::Consumable(ConsumableType::HealthPotion)
+            })
+        });
+        if let Some(potion) = potion {
+            return ConcreteAction::UseItem(UseItemAction::new(player_id, potion));
+        }
+    }
+
+    let pack_has_room = player.inventory.len() < player.inventory_capacity;
+    if let Some(stack) = level
+        .ground_items
+        .top_at(position)
+        .filter(|_| pack_has_room)
+    {
+        return ConcreteAction::PickUp(PickUpAction::new(player_id, stack.item.id));
+    }
+
+    let tile = level.get_tile(position).map(|tile| &tile.tile_type);
+    let has_artifact = game_state.player_has_artifact();
+    let route_goal = if has_artifact {
+        if tile == Some(&TileType::StairsUp) {
+            return ConcreteAction::UseStairs(UseStairsAction::new(player_id, StairDirection::Up));
+        }
+        level.stairs_up_position
+    } else if let Some(stairs_down) = level.stairs_down_position {
+        if tile == Some(&TileType::StairsDown) {
+            return ConcreteAction::UseStairs(UseStairsAction::new(
+                player_id,
+                StairDirection::Down,
+            ));
+        }
+        Some(stairs_down)
+    } else {
+        level
+            .ground_items
+            .iter()
+            .find(|stack| stack.item.name == ARTIFACT_NAME)
+            .map(|stack| stack.item.position)
+    };
+
+    let detour = level
+        .ground_items
+        .iter()
+        .map(|stack| stack.item.position)
+        .filter(|&pos| pack_has_room && pos.manhattan_distance(position) <= SIM_ITEM_DETOUR)
+        .filter(|&pos| level.get_tile(pos).is_some_and(|tile| tile.is_visible()))
+        .min_by_key(|&pos| (pos.manhattan_distance(position), pos.y, pos.x));
+
+    let pathfinding = PathfindingUtils::new();
+    let step = [detour, route_goal].into_iter().flatten().find_map(|goal| {
+        pathfinding
+            .find_path(level, position, goal)?
+            .first()
+            .copied()
+    });
+    match step.and_then(|next| Direction::from_delta(next - position)) {
+        Some(direction) => ConcreteAction::Move(MoveAction::new(player_id, direction)),
+        None => wait,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeneratorKind, WorldShape};
+
+    #[test]
+    fn test_simulated_runs_are_reported_per_seed() {
+        let config = GenerationConfig {
+            shape: WorldShape {
+                floor_count: 3,
+                floor_width: 40,
+                floor_height: 30,
+            },
+            generator: GeneratorKind::Bsp,
+            ..GenerationConfig::for_testing(21)
+        };
+        let report = simulate(&config, 2).unwrap();
+        assert_eq!(report.run_count, 2);
+        assert_eq!(report.runs[1].seed, 22);
+        for run in &report.runs {
+            assert!(run.depth >= 1 && run.turns <= SIM_MAX_TURNS);
+            assert_eq!(
+                run.death_cause.is_some(),
+                run.outcome == GameCompletionState::PlayerDied
+            );
+        }
+        let total: u32 = report.outcomes.values().sum();
+        assert_eq!(total, 2);
+
+        // The same seed plays out the same way
+        assert_eq!(simulate_run(config.clone()).unwrap(), report.runs[0]);
+        let csv = report.to_csv();
+        assert!(csv.starts_with("seed,outcome,depth"));
+        assert_eq!(csv.lines().count(), 3);
+        let json: SimulationReport = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json.outcomes, report.outcomes);
+        assert_eq!(json.runs[1].turns, report.runs[1].turns);
+    }
+}
@@ -1,9 +1,9 @@
 //! Integration tests for stair navigation and 3D dungeon functionality.
 
-use rand::{rngs::StdRng, SeedableRng};
 use thatch::{
-    Action, ConcreteAction, ConcreteEntity, Entity, GameState, GenerationConfig, PlayerCharacter,
-    Position, RoomCorridorGenerator, StairDirection, TileType, UseStairsAction, WorldGenerator,
+    Action, ConcreteEntity, GameState, GenerationConfig, Item, ItemType, PlayerCharacter, Position,
+    RoomCorridorGenerator, StairDirection, TileType, UseStairsAction, WorldGenerator,
+    ARTIFACT_NAME,
 };
 
 /// Test stair navigation between floors using the 3D generation system.
@@ -15,16 +15,17 @@ fn test_stair_navigation_3d_dungeon() {
         GameState::new_with_complete_dungeon(seed).expect("Failed to create 3D dungeon");
 
     // Create and place player on level 0
-    let player_entity = ConcreteEntity::Player(PlayerCharacter::new("TestHero".to_string()));
+    let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+        "TestHero".to_string(),
+        Position::new(0, 0),
+    ));
     let player_id = player_entity.id();
 
     // Add player to game state
     game_state
         .add_entity(player_entity)
         .expect("Failed to add player");
-    game_state
-        .set_player(player_id)
-        .expect("Failed to set player");
+    game_state.set_player_id(player_id);
 
     // Get initial spawn position (should be on level 0)
     let initial_pos = game_state
@@ -131,11 +132,12 @@ fn test_complete_stair_alignment() {
             );
         }
 
-        // Verify level 0 has no up stairs
+        // Level 0's up stairs, at the player spawn, are the way out of the dungeon
         if level_id == 0 {
-            assert!(
-                current_level.stairs_up_position.is_none(),
-                "Level 0 should not have up stairs"
+            assert_eq!(
+                current_level.stairs_up_position,
+                Some(current_level.player_spawn),
+                "Level 0 should have its exit at the player spawn"
             );
         } else {
             assert!(
@@ -169,15 +171,16 @@ fn test_stair_boundary_conditions() {
         GameState::new_with_complete_dungeon(seed).expect("Failed to create 3D dungeon");
 
     // Create player
-    let player_entity = ConcreteEntity::Player(PlayerCharacter::new("TestHero".to_string()));
+    let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+        "TestHero".to_string(),
+        Position::new(0, 0),
+    ));
     let player_id = player_entity.id();
 
     game_state
         .add_entity(player_entity)
         .expect("Failed to add player");
-    game_state
-        .set_player(player_id)
-        .expect("Failed to set player");
+    game_state.set_player_id(player_id);
 
     // Test going up from level 0 (should trigger escape ending)
     let initial_pos = game_state
@@ -215,19 +218,20 @@ fn test_stair_boundary_conditions() {
         thatch::GameCompletionState::EscapedEarly
     );
 
-    // Test going down from level 25 (should trigger win ending)
+    // Going down from level 25 is refused: there is nothing deeper
     let mut game_state_25 =
         GameState::new_with_complete_dungeon(seed + 1).expect("Failed to create 3D dungeon");
 
-    let player_entity_25 = ConcreteEntity::Player(PlayerCharacter::new("TestHero25".to_string()));
+    let player_entity_25 = ConcreteEntity::Player(PlayerCharacter::new(
+        "TestHero25".to_string(),
+        Position::new(0, 0),
+    ));
     let player_id_25 = player_entity_25.id();
 
     game_state_25
         .add_entity(player_entity_25)
         .expect("Failed to add player");
-    game_state_25
-        .set_player(player_id_25)
-        .expect("Failed to set player");
+    game_state_25.set_player_id(player_id_25);
 
     // Move to level 25
     game_state_25
@@ -235,15 +239,6 @@ fn test_stair_boundary_conditions() {
         .change_level(25)
         .expect("Failed to change to level 25");
 
-    let level_25_pos = game_state_25
-        .world
-        .current_level()
-        .expect("No level 25")
-        .player_spawn;
-    game_state_25
-        .set_entity_position(player_id_25, level_25_pos)
-        .expect("Failed to set player position on level 25");
-
     // Create fake stairs down on level 25 for testing
     let test_stairs_down_pos = Position::new(15, 15);
     game_state_25
@@ -262,14 +257,61 @@ fn test_stair_boundary_conditions() {
     let use_stairs_action_25 = UseStairsAction::new(player_id_25, StairDirection::Down);
     let result_25 = use_stairs_action_25.execute(&mut game_state_25);
 
-    // Should succeed but trigger win ending
     assert!(
-        result_25.is_ok(),
-        "Using stairs down from level 25 should succeed"
+        result_25.is_err(),
+        "Using stairs down from level 25 should be refused"
     );
     assert_eq!(
         game_state_25.completion_state,
-        thatch::GameCompletionState::CompletedDungeon
+        thatch::GameCompletionState::Playing
+    );
+    assert_eq!(game_state_25.world.current_level_id, 25);
+}
+
+/// Test that climbing out of level 0 with the Amulet wins the game.
+#[test]
+fn test_escape_with_artifact() {
+    let seed = 22222;
+    let mut game_state =
+        GameState::new_with_complete_dungeon(seed).expect("Failed to create 3D dungeon");
+
+    let player_entity = ConcreteEntity::Player(PlayerCharacter::new(
+        "Bearer".to_string(),
+        Position::new(0, 0),
+    ));
+    let player_id = player_entity.id();
+    game_state
+        .add_entity(player_entity)
+        .expect("Failed to add player");
+    game_state.set_player_id(player_id);
+
+    let stairs_up_pos = Position::new(10, 10);
+    game_state
+        .set_entity_position(player_id, stairs_up_pos)
+        .expect("Failed to move player");
+    if let Some(level) = game_state.world.current_level_mut() {
+        level
+            .set_tile(stairs_up_pos, thatch::Tile::new(TileType::StairsUp))
+            .expect("Failed to set stairs up tile");
+    }
+
+    let amulet = Item::new(
+        ARTIFACT_NAME.to_string(),
+        ItemType::QuestItem,
+        stairs_up_pos,
+    );
+    game_state
+        .give_item_to_player(amulet, false)
+        .expect("Failed to give the Amulet");
+
+    let use_stairs_action = UseStairsAction::new(player_id, StairDirection::Up);
+    use_stairs_action
+        .execute(&mut game_state)
+        .expect("Climbing out with the Amulet should succeed");
+
+    assert_eq!(
+        game_state.completion_state,
+        thatch::GameCompletionState::EscapedWithArtifact
     );
 }
 
@@ -333,12 +375,11 @@ fn test_3d_generation_validity() {
 fn test_world_generator_trait() {
     let seed = 33333;
     let config = GenerationConfig::new(seed);
-    let mut rng = StdRng::seed_from_u64(seed);
     let generator = RoomCorridorGenerator::new();
 
     // Generate world using trait
     let world = generator
-        .generate_world(&config, &mut rng)
+        .generate_world(&config)
         .expect("World generation should succeed");
 
     // Validate world using trait
@@ -194,8 +194,9 @@ fn test_player_movement_action() -> ThatchResult<()> {
         // Execute the movement action
         let events = action.execute(&mut game_state)?;
 
-        // Verify that a move event was generated
-        assert_eq!(events.len(), 1);
+        // Verify that a move event was generated, followed by the footstep noise
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], thatch::GameEvent::NoiseMade { .. }));
         if let thatch::GameEvent::EntityMoved {
             entity_id,
             from,